@@ -1,6 +1,32 @@
 pub mod aegis256;
+mod aesgcm;
+mod aesocb;
+pub mod archive;
+pub mod backup;
 mod chachapoly1305;
+mod ciphertext;
+mod convergent;
+pub mod envelope;
+pub mod etm;
+mod incremental;
+mod key;
+pub mod ndarray;
+pub mod object;
+mod oneshot;
+pub mod page;
+pub mod rotating;
+mod roundtrip;
+pub mod stream;
+pub mod threshold;
 mod xchachapoly1305;
 
-pub use chachapoly1305::ChaCha20Poly1305;
+pub use aesgcm::AesGcm;
+pub use aesocb::AesOcb;
+pub use chachapoly1305::{ChaCha20Poly1305, ChaCha20Poly1305Ietf, ChaCha20Poly1305Original};
+pub use ciphertext::Ciphertext;
+pub use convergent::ConvergentCipher;
+pub use incremental::{AeadDecryptor, AeadEncryptor};
+pub use key::SymmetricKey;
+pub use oneshot::{open, parse_header, seal, seal_with, AeadAlgorithm, SealError, SealHeader};
+pub use roundtrip::{verify_roundtrip, RoundtripFailure};
 pub use xchachapoly1305::XChaCha20Poly1305;
@@ -1,2 +1,5 @@
 pub(crate) mod backends;
-pub use backends::{decrypt, encrypt};
+pub use backends::{backend, decrypt, decrypt_with_backend, encrypt, encrypt_with_backend};
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 32;
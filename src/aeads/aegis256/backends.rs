@@ -2,16 +2,67 @@
 pub mod aesni;
 #[cfg(target_arch = "aarch64")]
 pub mod armcrypto;
+pub mod soft;
+
+use crate::backend::{self, Backend};
+use crate::errors::InvalidMac;
+use crate::utils::const_time_eq;
+
+use core::ops::{BitAnd, BitXor, Index, IndexMut};
+use zeroize::Zeroize;
+
+pub trait Block: Copy + BitAnd<Output = Self> + BitXor<Output = Self> {
+    fn load(items: &[u8]) -> Self;
+    fn store(&self) -> [u8; 16];
+    /// One AES round of `self`, with `other` as the round key.
+    fn enc(&self, other: Self) -> Self;
+}
+
+macro_rules! impl_block {
+    ($block:ty) => {
+        impl Block for $block {
+            #[inline(always)]
+            fn load(items: &[u8]) -> Self {
+                <$block>::load(items)
+            }
+
+            #[inline(always)]
+            fn store(&self) -> [u8; 16] {
+                <$block>::store(self)
+            }
+
+            #[inline(always)]
+            fn enc(&self, other: Self) -> Self {
+                <$block>::enc(self, other)
+            }
+        }
+    };
+}
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use crate::aeads::aegis256::backends::aesni::Block;
+impl_block!(aesni::Block);
 #[cfg(target_arch = "aarch64")]
-use crate::aeads::aegis256::backends::armcrypto::Block;
+impl_block!(armcrypto::Block);
+impl_block!(soft::Block);
 
-use crate::errors::InvalidMac;
-use crate::utils::const_time_eq;
+/// Whether the AES instructions are used for `backend`: they are unless it is
+/// `Backend::Scalar`, provided the CPU has them, as for AES itself.
+fn hardware(backend: Backend) -> bool {
+    backend != Backend::Scalar && crate::ciphers::aes::hardware_supported()
+}
+
+/// The implementation `encrypt` and `decrypt` use.
+pub fn backend() -> &'static str {
+    name(backend::hardware_aes())
+}
 
-use core::ops::{Index, IndexMut};
+fn name(hardware: bool) -> &'static str {
+    match hardware {
+        true if cfg!(target_arch = "aarch64") => "armcrypto",
+        true => "aesni",
+        false => "bitsliced",
+    }
+}
 
 const C0: [u8; 16] = [
     0x00, 0x01, 0x01, 0x02, 0x03, 0x05, 0x08, 0x0d, 0x15, 0x22, 0x37, 0x59, 0x90, 0xe9, 0x79, 0x62,
@@ -21,18 +72,18 @@ const C1: [u8; 16] = [
     0xdb, 0x3d, 0x18, 0x55, 0x6d, 0xc2, 0x2f, 0xf1, 0x20, 0x11, 0x31, 0x42, 0x73, 0xb5, 0x28, 0xdd,
 ];
 
-pub struct State([Block; 6]);
+pub struct State<B: Block>([B; 6]);
 
-impl State {
-    pub fn new(key: &[u8], nonce: &[u8]) -> State {
-        let c0 = Block::load(&C0);
-        let c1 = Block::load(&C1);
+impl<B: Block> State<B> {
+    pub fn new(key: &[u8], nonce: &[u8]) -> State<B> {
+        let c0 = B::load(&C0);
+        let c1 = B::load(&C1);
 
-        let k0 = Block::load(&key[..16]);
-        let k1 = Block::load(&key[16..32]);
+        let k0 = B::load(&key[..16]);
+        let k1 = B::load(&key[16..32]);
 
-        let n0 = Block::load(&nonce[..16]);
-        let n1 = Block::load(&nonce[16..32]);
+        let n0 = B::load(&nonce[..16]);
+        let n1 = B::load(&nonce[16..32]);
 
         let k0_n0 = k0 ^ n0;
         let k1_n1 = k1 ^ n1;
@@ -49,7 +100,7 @@ impl State {
         output
     }
 
-    fn update(&mut self, d: Block) {
+    fn update(&mut self, d: B) {
         let temp = self[5];
 
         for i in (1..6).rev() {
@@ -65,7 +116,7 @@ impl State {
         adlen: usize,
         mlen: usize,
     ) -> [u8; MAC_LENGTH] {
-        let temp = Block::load(
+        let temp = B::load(
             &[
                 ((adlen as u64) << 3).to_le_bytes(),
                 ((mlen as u64) << 3).to_le_bytes(),
@@ -80,24 +131,24 @@ impl State {
         let mut mac = [0u8; MAC_LENGTH];
         if MAC_LENGTH == 16 {
             mac.copy_from_slice(
-                &(&self[5] ^ &self[4] ^ &self[3] ^ &self[2] ^ &self[1] ^ &self[0]).store(),
+                &(self[5] ^ self[4] ^ self[3] ^ self[2] ^ self[1] ^ self[0]).store(),
             );
         } else {
-            mac[0..16].copy_from_slice(&(&self[2] ^ &self[1] ^ &self[0]).store());
+            mac[0..16].copy_from_slice(&(self[2] ^ self[1] ^ self[0]).store());
 
-            mac[16..32].copy_from_slice(&(&self[5] ^ &self[4] ^ &self[3]).store());
+            mac[16..32].copy_from_slice(&(self[5] ^ self[4] ^ self[3]).store());
         };
 
         mac
     }
 
     pub fn absorb(&mut self, src: &[u8]) {
-        self.update(Block::load(src));
+        self.update(B::load(src));
     }
 
     pub fn enc(&mut self, src: &[u8]) -> [u8; 16] {
-        let msg = Block::load(src);
-        let dst = (&msg ^ &self[5] ^ &self[4] ^ &self[1] ^ &self[2] & &self[3]).store();
+        let msg = B::load(src);
+        let dst = (msg ^ self[5] ^ self[4] ^ self[1] ^ self[2] & self[3]).store();
 
         self.update(msg);
 
@@ -105,7 +156,7 @@ impl State {
     }
 
     pub fn dec(&mut self, src: &[u8]) -> [u8; 16] {
-        let msg = &Block::load(src) ^ &self[5] ^ &self[4] ^ &self[1] ^ &self[2] & &self[3];
+        let msg = B::load(src) ^ self[5] ^ self[4] ^ self[1] ^ self[2] & self[3];
 
         self.update(msg);
 
@@ -119,28 +170,28 @@ impl State {
         let mut src_padded = [0u8; 16];
         src_padded[..len].copy_from_slice(src);
 
-        let z = &self[5] ^ &self[4] ^ &self[1] ^ &self[2] & &self[3];
-        let msg_padded = Block::load(&src_padded) ^ z;
+        let z = self[5] ^ self[4] ^ self[1] ^ self[2] & self[3];
+        let msg_padded = B::load(&src_padded) ^ z;
 
         dst.copy_from_slice(&msg_padded.store());
         dst[len..].fill(0);
 
-        let msg = Block::load(&dst);
+        let msg = B::load(&dst);
         self.update(msg);
 
         dst
     }
 }
 
-impl Index<usize> for State {
-    type Output = Block;
+impl<B: Block> Index<usize> for State<B> {
+    type Output = B;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.0[index]
     }
 }
 
-impl IndexMut<usize> for State {
+impl<B: Block> IndexMut<usize> for State<B> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.0[index]
     }
@@ -152,7 +203,44 @@ pub fn encrypt<const MAC_LENGTH: usize>(
     nonce: &[u8],
     ad: &[u8],
 ) -> Vec<u8> {
-    let mut state = State::new(key, nonce);
+    seal::<MAC_LENGTH>(backend::hardware_aes(), key, msg, nonce, ad)
+}
+
+/// `encrypt`, with `Backend::Scalar` selecting the bitsliced implementation and any other
+/// backend the AES instructions when the CPU has them.
+pub fn encrypt_with_backend<const MAC_LENGTH: usize>(
+    backend: Backend,
+    key: &[u8],
+    msg: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+) -> Vec<u8> {
+    seal::<MAC_LENGTH>(hardware(backend), key, msg, nonce, ad)
+}
+
+fn seal<const MAC_LENGTH: usize>(
+    hardware: bool,
+    key: &[u8],
+    msg: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+) -> Vec<u8> {
+    match hardware {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        true => seal_with::<aesni::Block, MAC_LENGTH>(key, msg, nonce, ad),
+        #[cfg(target_arch = "aarch64")]
+        true => seal_with::<armcrypto::Block, MAC_LENGTH>(key, msg, nonce, ad),
+        _ => seal_with::<soft::Block, MAC_LENGTH>(key, msg, nonce, ad),
+    }
+}
+
+fn seal_with<B: Block, const MAC_LENGTH: usize>(
+    key: &[u8],
+    msg: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+) -> Vec<u8> {
+    let mut state = State::<B>::new(key, nonce);
 
     let mut ciphertext = Vec::new();
     let adlen = ad.len();
@@ -195,7 +283,49 @@ pub fn decrypt<const MAC_LENGTH: usize>(
     nonce: &[u8],
     ad: &[u8],
 ) -> Result<Vec<u8>, InvalidMac> {
-    let mut state = State::new(key, nonce);
+    open::<MAC_LENGTH>(backend::hardware_aes(), key, msg, nonce, ad)
+}
+
+/// `decrypt`, choosing the implementation as `encrypt_with_backend` does.
+pub fn decrypt_with_backend<const MAC_LENGTH: usize>(
+    backend: Backend,
+    key: &[u8],
+    msg: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+) -> Result<Vec<u8>, InvalidMac> {
+    open::<MAC_LENGTH>(hardware(backend), key, msg, nonce, ad)
+}
+
+fn open<const MAC_LENGTH: usize>(
+    hardware: bool,
+    key: &[u8],
+    msg: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+) -> Result<Vec<u8>, InvalidMac> {
+    match hardware {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        true => open_with::<aesni::Block, MAC_LENGTH>(key, msg, nonce, ad),
+        #[cfg(target_arch = "aarch64")]
+        true => open_with::<armcrypto::Block, MAC_LENGTH>(key, msg, nonce, ad),
+        _ => open_with::<soft::Block, MAC_LENGTH>(key, msg, nonce, ad),
+    }
+}
+
+fn open_with<B: Block, const MAC_LENGTH: usize>(
+    key: &[u8],
+    msg: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+) -> Result<Vec<u8>, InvalidMac> {
+    // a ciphertext of an empty message is just the tag
+    if msg.len() < MAC_LENGTH {
+        return Err(InvalidMac);
+    }
+
+    let (ct, expected_tag) = msg.split_at(msg.len() - MAC_LENGTH);
+    let mut state = State::<B>::new(key, nonce);
 
     for block in ad.chunks(16) {
         if block.len() < 16 {
@@ -210,20 +340,19 @@ pub fn decrypt<const MAC_LENGTH: usize>(
 
     let mut plaintext = Vec::new();
 
-    for block in msg[..msg.len() - MAC_LENGTH].chunks(16) {
+    for block in ct.chunks(16) {
         if block.len() < 16 {
-            let mut pad: [u8; 16] = [0u8; 16];
-            pad[..block.len()].copy_from_slice(block);
-
-            plaintext.extend_from_slice(&state.declast(&pad)[..block.len()]);
+            // declast pads the block itself, and must know its length to truncate
+            plaintext.extend_from_slice(&state.declast(block)[..block.len()]);
         } else {
             plaintext.extend_from_slice(&state.dec(&block));
         }
     }
 
-    let tag = state.finalize::<MAC_LENGTH>(ad.len(), msg.len());
+    let tag = state.finalize::<MAC_LENGTH>(ad.len(), ct.len());
 
-    if const_time_eq(&msg[msg.len() - MAC_LENGTH..], &tag) {
+    if !const_time_eq(expected_tag, &tag) {
+        plaintext.zeroize();
         return Err(InvalidMac);
     }
 
@@ -0,0 +1,41 @@
+use crate::ciphers::aes::backends::soft;
+use core::ops::{BitAnd, BitXor};
+
+// the bitsliced AES round, for CPUs without AES instructions
+#[derive(Clone, Copy)]
+pub struct Block([u8; 16]);
+
+impl Block {
+    #[inline(always)]
+    pub fn load(items: &[u8]) -> Block {
+        Block(items[..16].try_into().unwrap())
+    }
+
+    #[inline(always)]
+    pub fn store(&self) -> [u8; 16] {
+        self.0
+    }
+
+    #[inline(always)]
+    pub fn enc(&self, other: Block) -> Block {
+        Block(soft::round(&self.0, &other.0))
+    }
+}
+
+impl BitAnd for Block {
+    type Output = Block;
+
+    #[inline(always)]
+    fn bitand(self, other: Self) -> Self::Output {
+        Block(core::array::from_fn(|i| self.0[i] & other.0[i]))
+    }
+}
+
+impl BitXor for Block {
+    type Output = Block;
+
+    #[inline(always)]
+    fn bitxor(self, other: Self) -> Self::Output {
+        Block(core::array::from_fn(|i| self.0[i] ^ other.0[i]))
+    }
+}
@@ -0,0 +1,191 @@
+use crate::aeads::{Ciphertext, SymmetricKey};
+use crate::audit::{self, KeyId, Operation};
+use crate::backend::Backend;
+use crate::ciphers::aes::Aes;
+use crate::errors::Error;
+use crate::errors::InvalidMac;
+use crate::macs::ghash::GHash;
+use crate::utils::{const_time_eq, random_bytes, redacted_debug};
+use zeroize::Zeroize;
+
+// counter blocks encrypted per call into the block cipher
+const BATCH: usize = 8;
+
+pub struct AesGcm {
+    aes: Aes,
+    ghash: GHash,
+    key_id: Option<KeyId>,
+}
+
+redacted_debug!(AesGcm, "<redacted>");
+
+impl AesGcm {
+    /// The AES-256 key size; `new` also accepts 16 and 24 byte keys.
+    pub const KEY_SIZE: usize = 32;
+    pub const NONCE_SIZE: usize = 12;
+    pub const TAG_SIZE: usize = 16;
+    /// The shortest tag `encrypt_with_tag_size` allows, 96 bits.
+    pub const MIN_TAG_SIZE: usize = 12;
+
+    /// Accepts 16, 24 or 32 byte keys.
+    pub fn new(key: &[u8]) -> Result<AesGcm, Error> {
+        AesGcm::from_aes(Aes::new(key)?, audit::key_id(key))
+    }
+
+    pub fn with_backend(key: &[u8], backend: Backend) -> Result<AesGcm, Error> {
+        AesGcm::from_aes(Aes::with_backend(key, backend)?, audit::key_id(key))
+    }
+
+    /// An AES-256 key.
+    pub fn generate_key() -> SymmetricKey {
+        SymmetricKey::generate()
+    }
+
+    /// A random nonce. 96-bit nonces can safely be chosen at random for up to about
+    /// 2^32 messages under one key.
+    pub fn generate_nonce() -> [u8; Self::NONCE_SIZE] {
+        random_bytes()
+    }
+
+    fn from_aes(aes: Aes, key_id: Option<KeyId>) -> Result<AesGcm, Error> {
+        let mut h = [0u8; 16];
+        aes.encrypt_block(&mut h);
+
+        let ghash = GHash::with_hardware(&h, aes.is_hardware());
+        h.zeroize();
+
+        Ok(AesGcm { aes, ghash, key_id })
+    }
+
+    pub fn backend(&self) -> &'static str {
+        self.aes.backend()
+    }
+
+    fn ctr(&self, nonce: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut output = data.to_vec();
+        let mut blocks = [[0u8; 16]; BATCH];
+
+        for (index, chunk) in output.chunks_mut(16 * BATCH).enumerate() {
+            let blocks = &mut blocks[..chunk.len().div_ceil(16)];
+
+            for (offset, block) in blocks.iter_mut().enumerate() {
+                let counter = ((index * BATCH + offset) as u32).wrapping_add(2);
+
+                block[..12].copy_from_slice(nonce);
+                block[12..].copy_from_slice(&counter.to_be_bytes());
+            }
+
+            self.aes.encrypt_blocks(blocks);
+
+            for (byte, key) in chunk.iter_mut().zip(blocks.as_flattened()) {
+                *byte ^= key;
+            }
+        }
+
+        blocks.zeroize();
+
+        output
+    }
+
+    fn tag(&self, nonce: &[u8], ad: &[u8], ct: &[u8]) -> [u8; 16] {
+        let mut ghash = self.ghash.clone();
+
+        ghash.update(ad);
+        ghash.update(ct);
+        ghash.update(
+            &[
+                (ad.len() as u64 * 8).to_be_bytes(),
+                (ct.len() as u64 * 8).to_be_bytes(),
+            ]
+            .concat(),
+        );
+
+        let mut mask = [0u8; 16];
+        mask[..12].copy_from_slice(nonce);
+        mask[15] = 1;
+        self.aes.encrypt_block(&mut mask);
+
+        let mut tag = ghash.finish();
+
+        for (byte, mask) in tag.iter_mut().zip(mask) {
+            *byte ^= mask;
+        }
+
+        tag
+    }
+
+    /// Encrypts with a 12-byte nonce, appending the 16-byte tag.
+    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Ciphertext {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+
+        let ct = self.ctr(nonce, msg);
+        let tag = self.tag(nonce, ad, &ct);
+        audit::record(Operation::Encrypt, || self.key_id, true);
+
+        Ciphertext::new(ct, tag)
+    }
+
+    pub fn decrypt(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        let msg = self.open(ct, nonce, ad, Self::TAG_SIZE);
+        audit::record(Operation::Decrypt, || self.key_id, msg.is_ok());
+
+        msg
+    }
+
+    /// Encrypts with the tag truncated to `tag_size` bytes, from 12 to 16, for protocols that
+    /// mandate short tags. Every byte cut from the tag makes forgeries 256 times more likely.
+    pub fn encrypt_with_tag_size(
+        &self,
+        msg: &[u8],
+        nonce: &[u8],
+        ad: &[u8],
+        tag_size: usize,
+    ) -> Vec<u8> {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+        assert!((Self::MIN_TAG_SIZE..=Self::TAG_SIZE).contains(&tag_size));
+
+        let mut ct = self.ctr(nonce, msg);
+        let tag = self.tag(nonce, ad, &ct);
+        ct.extend_from_slice(&tag[..tag_size]);
+        audit::record(Operation::Encrypt, || self.key_id, true);
+
+        ct
+    }
+
+    pub fn decrypt_with_tag_size(
+        &self,
+        ct: &[u8],
+        nonce: &[u8],
+        ad: &[u8],
+        tag_size: usize,
+    ) -> Result<Vec<u8>, InvalidMac> {
+        assert!((Self::MIN_TAG_SIZE..=Self::TAG_SIZE).contains(&tag_size));
+
+        let msg = self.open(ct, nonce, ad, tag_size);
+        audit::record(Operation::Decrypt, || self.key_id, msg.is_ok());
+
+        msg
+    }
+
+    fn open(
+        &self,
+        ct: &[u8],
+        nonce: &[u8],
+        ad: &[u8],
+        tag_size: usize,
+    ) -> Result<Vec<u8>, InvalidMac> {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+
+        if ct.len() < tag_size {
+            return Err(InvalidMac);
+        }
+
+        let (ciphertext, tag) = ct.split_at(ct.len() - tag_size);
+
+        if !const_time_eq(tag, &self.tag(nonce, ad, ciphertext)[..tag_size]) {
+            return Err(InvalidMac);
+        }
+
+        Ok(self.ctr(nonce, ciphertext))
+    }
+}
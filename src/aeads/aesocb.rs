@@ -0,0 +1,242 @@
+// AES-OCB (RFC 7253) with 128-bit tags. Decryption runs the byte-wise inverse cipher, so it is
+// much slower than encryption; it is here for formats that mandate OCB, such as OpenPGP.
+
+use crate::aeads::Ciphertext;
+use crate::audit::{self, KeyId, Operation};
+use crate::ciphers::aes::inverse::InverseAes;
+use crate::ciphers::aes::Aes;
+use crate::errors::Error;
+use crate::errors::InvalidMac;
+use crate::utils::{const_time_eq, random_bytes, redacted_debug};
+use zeroize::Zeroize;
+
+fn double(block: &[u8; 16]) -> [u8; 16] {
+    let carry = (block[0] >> 7).wrapping_neg() & 0x87;
+    let mut output: [u8; 16] =
+        core::array::from_fn(|i| (block[i] << 1) | block.get(i + 1).map_or(0, |next| next >> 7));
+    output[15] ^= carry;
+
+    output
+}
+
+fn xor(block: &mut [u8; 16], other: &[u8; 16]) {
+    for (byte, other) in block.iter_mut().zip(other) {
+        *byte ^= other;
+    }
+}
+
+pub struct AesOcb {
+    aes: Aes,
+    inverse: InverseAes,
+    l_star: [u8; 16],
+    l_dollar: [u8; 16],
+    // L_i for every i that can be the number of trailing zeros of a block index
+    l: [[u8; 16]; 64],
+    key_id: Option<KeyId>,
+}
+
+redacted_debug!(AesOcb, "<redacted>");
+
+impl Drop for AesOcb {
+    fn drop(&mut self) {
+        self.l_star.zeroize();
+        self.l_dollar.zeroize();
+        self.l.zeroize();
+    }
+}
+
+impl AesOcb {
+    /// The AES-256 key size; `new` also accepts 16 and 24 byte keys.
+    pub const KEY_SIZE: usize = 32;
+    pub const NONCE_SIZE: usize = 12;
+    pub const MAX_NONCE_SIZE: usize = 15;
+    pub const TAG_SIZE: usize = 16;
+
+    /// Accepts 16, 24 or 32 byte keys.
+    pub fn new(key: &[u8]) -> Result<AesOcb, Error> {
+        let aes = Aes::new(key)?;
+        let inverse = InverseAes::new(key)?;
+
+        let mut l_star = [0u8; 16];
+        aes.encrypt_block(&mut l_star);
+        let l_dollar = double(&l_star);
+
+        let mut previous = l_dollar;
+        let l = core::array::from_fn(|_| {
+            previous = double(&previous);
+            previous
+        });
+        previous.zeroize();
+
+        Ok(AesOcb {
+            aes,
+            inverse,
+            l_star,
+            l_dollar,
+            l,
+            key_id: audit::key_id(key),
+        })
+    }
+
+    pub fn generate_nonce() -> [u8; Self::NONCE_SIZE] {
+        random_bytes()
+    }
+
+    fn initial_offset(&self, nonce: &[u8]) -> [u8; 16] {
+        let mut ktop = [0u8; 16];
+        ktop[16 - nonce.len()..].copy_from_slice(nonce);
+        ktop[15 - nonce.len()] |= 1;
+
+        let bottom = (ktop[15] & 0x3f) as usize;
+        ktop[15] &= 0xc0;
+        self.aes.encrypt_block(&mut ktop);
+
+        let mut stretch = [0u8; 25];
+        stretch[..16].copy_from_slice(&ktop);
+
+        for i in 0..8 {
+            stretch[16 + i] = ktop[i] ^ ktop[i + 1];
+        }
+
+        let (bytes, bits) = (bottom / 8, bottom % 8);
+        let offset = core::array::from_fn(|i| {
+            (stretch[i + bytes] << bits) | (u16::from(stretch[i + bytes + 1]) >> (8 - bits)) as u8
+        });
+
+        ktop.zeroize();
+        stretch.zeroize();
+
+        offset
+    }
+
+    fn hash(&self, ad: &[u8]) -> [u8; 16] {
+        let mut sum = [0u8; 16];
+        let mut offset = [0u8; 16];
+        let mut block = [0u8; 16];
+        let mut chunks = ad.chunks_exact(16);
+
+        for (i, chunk) in chunks.by_ref().enumerate() {
+            xor(&mut offset, &self.l[(i + 1).trailing_zeros() as usize]);
+            block.copy_from_slice(chunk);
+            xor(&mut block, &offset);
+            self.aes.encrypt_block(&mut block);
+            xor(&mut sum, &block);
+        }
+
+        let rest = chunks.remainder();
+
+        if !rest.is_empty() {
+            xor(&mut offset, &self.l_star);
+            block = [0u8; 16];
+            block[..rest.len()].copy_from_slice(rest);
+            block[rest.len()] = 0x80;
+            xor(&mut block, &offset);
+            self.aes.encrypt_block(&mut block);
+            xor(&mut sum, &block);
+        }
+
+        offset.zeroize();
+        block.zeroize();
+
+        sum
+    }
+
+    // encrypts or decrypts `data` in place and returns the tag
+    fn process(&self, nonce: &[u8], ad: &[u8], data: &mut [u8], encrypt: bool) -> [u8; 16] {
+        let mut offset = self.initial_offset(nonce);
+        let mut checksum = [0u8; 16];
+        let mut block = [0u8; 16];
+
+        let (blocks, rest) = data.split_at_mut(data.len() / 16 * 16);
+
+        for (i, chunk) in blocks.chunks_exact_mut(16).enumerate() {
+            xor(&mut offset, &self.l[(i + 1).trailing_zeros() as usize]);
+            block.copy_from_slice(chunk);
+
+            if encrypt {
+                xor(&mut checksum, &block);
+                xor(&mut block, &offset);
+                self.aes.encrypt_block(&mut block);
+                xor(&mut block, &offset);
+            } else {
+                xor(&mut block, &offset);
+                self.inverse.decrypt_block(&mut block);
+                xor(&mut block, &offset);
+                xor(&mut checksum, &block);
+            }
+
+            chunk.copy_from_slice(&block);
+        }
+
+        if !rest.is_empty() {
+            xor(&mut offset, &self.l_star);
+            let mut pad = offset;
+            self.aes.encrypt_block(&mut pad);
+
+            block = [0u8; 16];
+
+            if encrypt {
+                block[..rest.len()].copy_from_slice(rest);
+            }
+
+            for (byte, pad) in rest.iter_mut().zip(pad) {
+                *byte ^= pad;
+            }
+
+            if !encrypt {
+                block[..rest.len()].copy_from_slice(rest);
+            }
+
+            block[rest.len()] = 0x80;
+            xor(&mut checksum, &block);
+            pad.zeroize();
+        }
+
+        xor(&mut checksum, &offset);
+        xor(&mut checksum, &self.l_dollar);
+        self.aes.encrypt_block(&mut checksum);
+        xor(&mut checksum, &self.hash(ad));
+
+        offset.zeroize();
+        block.zeroize();
+
+        checksum
+    }
+
+    /// Encrypts with a nonce of 1 to 15 bytes, appending the 16-byte tag.
+    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Ciphertext {
+        assert!((1..=Self::MAX_NONCE_SIZE).contains(&nonce.len()));
+
+        let mut ct = msg.to_vec();
+        let tag = self.process(nonce, ad, &mut ct, true);
+        audit::record(Operation::Encrypt, || self.key_id, true);
+
+        Ciphertext::new(ct, tag)
+    }
+
+    pub fn decrypt(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        let msg = self.open(ct, nonce, ad);
+        audit::record(Operation::Decrypt, || self.key_id, msg.is_ok());
+
+        msg
+    }
+
+    fn open(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        assert!((1..=Self::MAX_NONCE_SIZE).contains(&nonce.len()));
+
+        if ct.len() < Self::TAG_SIZE {
+            return Err(InvalidMac);
+        }
+
+        let (ciphertext, tag) = ct.split_at(ct.len() - Self::TAG_SIZE);
+
+        let mut msg = ciphertext.to_vec();
+
+        if !const_time_eq(tag, &self.process(nonce, ad, &mut msg, false)) {
+            msg.zeroize();
+            return Err(InvalidMac);
+        }
+
+        Ok(msg)
+    }
+}
@@ -0,0 +1,402 @@
+// An encrypted container of named entries, like an encrypted tar. Entries are written in 64 KiB
+// ChaCha20-Poly1305 chunks as they are added, and an encrypted index of names, metadata and
+// offsets goes at the end, so entries can be extracted by name without reading the others.
+//
+// Layout: magic || version || salt (32) || entry chunks... || sealed index || index length (u64).
+// Each archive derives its own key from the caller's key and the salt. A chunk's nonce is its
+// entry number and chunk number, and its associated data marks whether it is the entry's last.
+
+use crate::aeads::ChaCha20Poly1305;
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{random_bytes, redacted_debug};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use zeroize::Zeroize;
+
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+const MAGIC: [u8; 4] = *b"RCAR";
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 4 + 1 + 32;
+const TAG_LENGTH: usize = ChaCha20Poly1305::TAG_SIZE;
+
+// the entry number whose nonces are reserved for the index
+const INDEX: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveError {
+    Io(std::io::ErrorKind),
+    Malformed,
+    InvalidMac,
+    NotFound,
+    DuplicateName,
+    TooManyEntries,
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::Io(kind) => write!(f, "Could not access the archive: {}.", kind),
+            ArchiveError::Malformed => write!(f, "The archive is malformed."),
+            ArchiveError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This archive may be tampered with."
+            ),
+            ArchiveError::NotFound => write!(f, "The archive has no entry with that name."),
+            ArchiveError::DuplicateName => {
+                write!(f, "The archive already has an entry with that name.")
+            }
+            ArchiveError::TooManyEntries => write!(f, "The archive cannot hold more entries."),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<InvalidEncoding> for ArchiveError {
+    fn from(_: InvalidEncoding) -> ArchiveError {
+        ArchiveError::Malformed
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(error: std::io::Error) -> ArchiveError {
+        // running out of data part way through means the archive was cut short
+        if error.kind() == std::io::ErrorKind::UnexpectedEof {
+            return ArchiveError::Malformed;
+        }
+
+        ArchiveError::Io(error.kind())
+    }
+}
+
+fn archive_key(key: &[u8], salt: &[u8]) -> ChaCha20Poly1305 {
+    assert_eq!(key.len(), ChaCha20Poly1305::KEY_SIZE);
+
+    let mut derived = Hkdf::<Sha256>::extract(salt, key)
+        .expand(b"raycrypt archive", ChaCha20Poly1305::KEY_SIZE)
+        .unwrap();
+    let aead = ChaCha20Poly1305::new(&derived);
+    derived.zeroize();
+
+    aead
+}
+
+fn nonce(entry: u32, chunk: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..4].copy_from_slice(&entry.to_be_bytes());
+    nonce[4..].copy_from_slice(&chunk.to_be_bytes());
+
+    nonce
+}
+
+// an empty entry still has one, empty, chunk
+fn chunk_count(size: u64) -> u64 {
+    size.div_ceil(CHUNK_SIZE as u64).max(1)
+}
+
+fn encrypted_size(size: u64) -> u64 {
+    size + chunk_count(size) * TAG_LENGTH as u64
+}
+
+/// An entry's name, size and metadata. The metadata is opaque to the archive, for things like
+/// permissions and timestamps, and is encrypted and authenticated along with the name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    name: String,
+    metadata: Vec<u8>,
+    size: u64,
+    offset: u64,
+    number: u32,
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn metadata(&self) -> &[u8] {
+        &self.metadata
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+fn parse_index(index: &[u8], index_start: u64) -> Result<Vec<Entry>, ArchiveError> {
+    let mut reader = Reader::new(index);
+    let count = reader.u32()?;
+    let mut entries = Vec::new();
+    let mut offset = HEADER_LENGTH as u64;
+
+    for number in 0..count {
+        let length = reader.u16()?;
+        let name = String::from_utf8(reader.bytes(length.into())?.to_vec())
+            .map_err(|_| ArchiveError::Malformed)?;
+        let length = reader.u32()?;
+        let metadata = reader.bytes(length as usize)?.to_vec();
+        let size = reader.u64()?;
+
+        // entries are stored back to back, so their offsets follow from their sizes
+        entries.push(Entry {
+            name,
+            metadata,
+            size,
+            offset,
+            number,
+        });
+        offset = offset
+            .checked_add(encrypted_size(size))
+            .ok_or(ArchiveError::Malformed)?;
+    }
+
+    reader.finish()?;
+
+    if offset != index_start {
+        return Err(ArchiveError::Malformed);
+    }
+
+    Ok(entries)
+}
+
+pub struct EncryptedArchive<R> {
+    reader: R,
+    aead: ChaCha20Poly1305,
+    entries: Vec<Entry>,
+    names: HashMap<String, usize>,
+}
+
+redacted_debug!(EncryptedArchive<R: Read>, "<redacted>");
+
+impl<R: Read + Seek> EncryptedArchive<R> {
+    /// Reads and checks the index of an archive that starts at the beginning of `reader`.
+    pub fn open(key: &[u8], mut reader: R) -> Result<EncryptedArchive<R>, ArchiveError> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut header = [0u8; HEADER_LENGTH];
+        reader.read_exact(&mut header)?;
+
+        if header[..4] != MAGIC || header[4] != VERSION {
+            return Err(ArchiveError::Malformed);
+        }
+
+        let aead = archive_key(key, &header[5..]);
+
+        let length = reader.seek(SeekFrom::End(0))?;
+        let available = length
+            .checked_sub((HEADER_LENGTH + 8) as u64)
+            .ok_or(ArchiveError::Malformed)?;
+
+        let mut index_length = [0u8; 8];
+        reader.seek(SeekFrom::End(-8))?;
+        reader.read_exact(&mut index_length)?;
+        let index_length = u64::from_be_bytes(index_length);
+
+        if index_length > available {
+            return Err(ArchiveError::Malformed);
+        }
+
+        let index_start = length - 8 - index_length;
+        let mut sealed = vec![0u8; index_length as usize];
+        reader.seek(SeekFrom::Start(index_start))?;
+        reader.read_exact(&mut sealed)?;
+
+        let index = aead
+            .decrypt(&sealed, &nonce(INDEX, 0), &header)
+            .map_err(|_| ArchiveError::InvalidMac)?;
+        let entries = parse_index(&index, index_start)?;
+
+        let names = entries
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| (entry.name.clone(), position))
+            .collect();
+
+        Ok(EncryptedArchive {
+            reader,
+            aead,
+            entries,
+            names,
+        })
+    }
+
+    /// The entries in the order they were added.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&Entry> {
+        self.names
+            .get(name)
+            .map(|&position| &self.entries[position])
+    }
+
+    /// Decrypts the named entry into `writer` a chunk at a time, returning its size. If a chunk
+    /// fails to authenticate, the chunks before it have already been written.
+    pub fn extract<W: Write>(&mut self, name: &str, mut writer: W) -> Result<u64, ArchiveError> {
+        let entry = self.entry(name).ok_or(ArchiveError::NotFound)?;
+        let (number, offset, size) = (entry.number, entry.offset, entry.size);
+
+        self.reader.seek(SeekFrom::Start(offset))?;
+
+        let chunks = chunk_count(size);
+        let mut buffer = vec![0u8; CHUNK_SIZE + TAG_LENGTH];
+
+        for chunk in 0..chunks {
+            let last = chunk + 1 == chunks;
+            let length = if last {
+                (size - chunk * CHUNK_SIZE as u64) as usize
+            } else {
+                CHUNK_SIZE
+            };
+
+            let ct = &mut buffer[..length + TAG_LENGTH];
+            self.reader.read_exact(ct)?;
+
+            let mut msg = self
+                .aead
+                .decrypt(ct, &nonce(number, chunk), &[last as u8])
+                .map_err(|_| ArchiveError::InvalidMac)?;
+            let written = writer.write_all(&msg);
+            msg.zeroize();
+            written?;
+        }
+
+        Ok(size)
+    }
+
+    /// Decrypts the named entry into memory.
+    pub fn read(&mut self, name: &str) -> Result<Vec<u8>, ArchiveError> {
+        let mut data = Vec::new();
+        self.extract(name, &mut data)?;
+
+        Ok(data)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Adds entries to a new archive. Nothing is readable until `finish` writes the index, and an
+/// error part way through an entry leaves the archive unusable.
+pub struct ArchiveWriter<W> {
+    writer: W,
+    aead: ChaCha20Poly1305,
+    header: [u8; HEADER_LENGTH],
+    index: Vec<u8>,
+    names: HashSet<String>,
+}
+
+redacted_debug!(ArchiveWriter<W: Write>, "<redacted>");
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Starts a new archive in `writer` under a 32-byte key.
+    pub fn new(key: &[u8], mut writer: W) -> Result<ArchiveWriter<W>, ArchiveError> {
+        let mut header = [0u8; HEADER_LENGTH];
+        header[..4].copy_from_slice(&MAGIC);
+        header[4] = VERSION;
+        header[5..].copy_from_slice(&random_bytes::<32>());
+
+        let aead = archive_key(key, &header[5..]);
+        writer.write_all(&header)?;
+
+        Ok(ArchiveWriter {
+            writer,
+            aead,
+            header,
+            index: Vec::new(),
+            names: HashSet::new(),
+        })
+    }
+
+    /// Streams everything `data` yields into a new entry, returning its size. Names are at most
+    /// 65535 bytes and must be unique within the archive.
+    pub fn add<D: Read>(
+        &mut self,
+        name: &str,
+        metadata: &[u8],
+        mut data: D,
+    ) -> Result<u64, ArchiveError> {
+        assert!(name.len() <= u16::MAX as usize);
+        assert!(metadata.len() <= u32::MAX as usize);
+
+        if self.names.contains(name) {
+            return Err(ArchiveError::DuplicateName);
+        }
+
+        let number = self.names.len() as u32;
+
+        if number == INDEX {
+            return Err(ArchiveError::TooManyEntries);
+        }
+
+        let mut size = 0u64;
+        let mut current = read_chunk(&mut data)?;
+
+        for chunk in 0.. {
+            // a full chunk might be the last one, which only reading on can tell
+            let next = if current.len() == CHUNK_SIZE {
+                read_chunk(&mut data)?
+            } else {
+                Vec::new()
+            };
+            let last = next.is_empty();
+
+            let ct = self
+                .aead
+                .encrypt(&current, &nonce(number, chunk), &[last as u8]);
+            self.writer.write_all(&ct)?;
+
+            size += current.len() as u64;
+            current.zeroize();
+
+            if last {
+                break;
+            }
+
+            current = next;
+        }
+
+        self.names.insert(name.to_string());
+        self.index
+            .extend_from_slice(&(name.len() as u16).to_be_bytes());
+        self.index.extend_from_slice(name.as_bytes());
+        self.index
+            .extend_from_slice(&(metadata.len() as u32).to_be_bytes());
+        self.index.extend_from_slice(metadata);
+        self.index.extend_from_slice(&size.to_be_bytes());
+
+        Ok(size)
+    }
+
+    /// Writes the index and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, ArchiveError> {
+        let mut index = (self.names.len() as u32).to_be_bytes().to_vec();
+        index.extend_from_slice(&self.index);
+
+        let sealed = self.aead.encrypt(&index, &nonce(INDEX, 0), &self.header);
+        index.zeroize();
+        self.index.zeroize();
+
+        self.writer.write_all(&sealed)?;
+        self.writer
+            .write_all(&(sealed.len() as u64).to_be_bytes())?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+fn read_chunk<D: Read>(data: &mut D) -> std::io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    data.by_ref()
+        .take(CHUNK_SIZE as u64)
+        .read_to_end(&mut chunk)?;
+
+    Ok(chunk)
+}
@@ -0,0 +1,568 @@
+// An encrypted, deduplicating backup container that grows across sessions. Each snapshot is split
+// with a keyed `chunking::Chunker`, chunks the container does not hold yet are appended as
+// XChaCha20-Poly1305 records, and `commit` appends an encrypted index of the session's new chunks
+// and snapshots. Nothing is rewritten in place.
+//
+// Layout: magic || version || salt (32) || key check (32), then records of
+// type || length (u32) || nonce (24) || ciphertext, with the container header, record type and
+// record offset as associated data, so records cannot be moved. Nonces are random, so a record
+// rewritten after an interruption never reuses one.
+//
+// A session cut short leaves chunk records without an index. `open` keeps every such record that
+// decrypts, up to the first torn or damaged one, and the next `commit` indexes them, so resuming
+// a backup only writes what was not written before. Dropping the latest commits entirely goes
+// unnoticed, as with any append-only file; compare `commits` with a count kept elsewhere.
+
+use crate::aeads::XChaCha20Poly1305;
+use crate::chunking::{ChunkError, Chunker, Manifest};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{const_time_eq, random_bytes, redacted_debug};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use zeroize::Zeroize;
+
+const MAGIC: [u8; 4] = *b"RCBK";
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 4 + 1 + 32 + 32;
+const RECORD_HEADER_LENGTH: usize = 1 + 4 + XChaCha20Poly1305::NONCE_SIZE;
+const TAG_LENGTH: usize = XChaCha20Poly1305::TAG_SIZE;
+
+const CHUNK: u8 = 1;
+const INDEX: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackupError {
+    Io(std::io::ErrorKind),
+    Malformed,
+    /// The key is not the one the container was created with.
+    WrongKey,
+    /// A committed record does not authenticate: the container was tampered with.
+    InvalidMac,
+    NotFound,
+    DuplicateName,
+}
+
+impl std::fmt::Display for BackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackupError::Io(kind) => write!(f, "Could not access the backup: {}.", kind),
+            BackupError::Malformed => write!(f, "The backup is malformed."),
+            BackupError::WrongKey => write!(f, "The key does not open this backup."),
+            BackupError::InvalidMac => {
+                write!(f, "Invalid MAC detected. This backup may be tampered with.")
+            }
+            BackupError::NotFound => write!(f, "The backup has no snapshot with that name."),
+            BackupError::DuplicateName => {
+                write!(f, "The backup already has a snapshot with that name.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+impl From<InvalidEncoding> for BackupError {
+    fn from(_: InvalidEncoding) -> BackupError {
+        BackupError::Malformed
+    }
+}
+
+impl From<std::io::Error> for BackupError {
+    fn from(error: std::io::Error) -> BackupError {
+        if error.kind() == std::io::ErrorKind::UnexpectedEof {
+            return BackupError::Malformed;
+        }
+
+        BackupError::Io(error.kind())
+    }
+}
+
+impl From<ChunkError> for BackupError {
+    fn from(error: ChunkError) -> BackupError {
+        match error {
+            ChunkError::Io(kind) => BackupError::Io(kind),
+            _ => BackupError::Malformed,
+        }
+    }
+}
+
+struct Keys {
+    aead: XChaCha20Poly1305,
+    chunker: Chunker,
+    check: [u8; 32],
+}
+
+fn derive_keys(key: &[u8], salt: &[u8]) -> Keys {
+    assert_eq!(key.len(), XChaCha20Poly1305::KEY_SIZE);
+
+    let mut derived = Hkdf::<Sha256>::extract(salt, key)
+        .expand(b"raycrypt backup", 96)
+        .unwrap();
+
+    let aead = XChaCha20Poly1305::new(&derived[..32]);
+    let chunker = Chunker::keyed(
+        derived[32..64].try_into().unwrap(),
+        Chunker::DEFAULT_MIN_SIZE,
+        Chunker::DEFAULT_AVG_SIZE,
+        Chunker::DEFAULT_MAX_SIZE,
+    )
+    .unwrap();
+    let check = derived[64..].try_into().unwrap();
+    derived.zeroize();
+
+    Keys {
+        aead,
+        chunker,
+        check,
+    }
+}
+
+/// A named snapshot: the manifest of the data it was made from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    name: String,
+    manifest: Manifest,
+}
+
+impl Snapshot {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    pub fn size(&self) -> u64 {
+        self.manifest.total_size()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Location {
+    offset: u64,
+    length: u32,
+}
+
+// a record's place in the file, from its clear header
+struct RecordHeader {
+    kind: u8,
+    offset: u64,
+    length: u32,
+    nonce: [u8; XChaCha20Poly1305::NONCE_SIZE],
+}
+
+impl RecordHeader {
+    fn end(&self) -> u64 {
+        self.offset + (RECORD_HEADER_LENGTH as u64) + self.length as u64
+    }
+}
+
+pub struct Backup<F> {
+    file: F,
+    aead: XChaCha20Poly1305,
+    chunker: Chunker,
+    header: [u8; HEADER_LENGTH],
+    end: u64,
+    commits: u64,
+    chunks: HashMap<[u8; 32], Location>,
+    snapshots: Vec<Snapshot>,
+    // written since the last commit, or recovered from an interrupted session
+    pending_chunks: Vec<[u8; 32]>,
+    pending_snapshots: Vec<Snapshot>,
+}
+
+redacted_debug!(Backup<F: Read>, "<redacted>");
+
+impl<F: Read + Write + Seek> Backup<F> {
+    /// Starts a new, empty container in `file` under a 32-byte key.
+    pub fn create(key: &[u8], mut file: F) -> Result<Backup<F>, BackupError> {
+        let mut header = [0u8; HEADER_LENGTH];
+        header[..4].copy_from_slice(&MAGIC);
+        header[4] = VERSION;
+        header[5..37].copy_from_slice(&random_bytes::<32>());
+
+        let keys = derive_keys(key, &header[5..37]);
+        header[37..].copy_from_slice(&keys.check);
+
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header)?;
+        file.flush()?;
+
+        Ok(Backup {
+            file,
+            aead: keys.aead,
+            chunker: keys.chunker,
+            header,
+            end: HEADER_LENGTH as u64,
+            commits: 0,
+            chunks: HashMap::new(),
+            snapshots: Vec::new(),
+            pending_chunks: Vec::new(),
+            pending_snapshots: Vec::new(),
+        })
+    }
+
+    /// Opens a container, reading every commit and recovering the chunks of an interrupted
+    /// session, which the next `commit` indexes.
+    pub fn open(key: &[u8], mut file: F) -> Result<Backup<F>, BackupError> {
+        file.seek(SeekFrom::Start(0))?;
+
+        let mut header = [0u8; HEADER_LENGTH];
+        file.read_exact(&mut header)?;
+
+        if header[..4] != MAGIC || header[4] != VERSION {
+            return Err(BackupError::Malformed);
+        }
+
+        let keys = derive_keys(key, &header[5..37]);
+
+        if !const_time_eq(&keys.check, &header[37..]) {
+            return Err(BackupError::WrongKey);
+        }
+
+        let length = file.seek(SeekFrom::End(0))?;
+        let mut backup = Backup {
+            file,
+            aead: keys.aead,
+            chunker: keys.chunker,
+            header,
+            end: HEADER_LENGTH as u64,
+            commits: 0,
+            chunks: HashMap::new(),
+            snapshots: Vec::new(),
+            pending_chunks: Vec::new(),
+            pending_snapshots: Vec::new(),
+        };
+
+        let records = backup.scan(length)?;
+        let mut committed = 0;
+
+        for (position, record) in records.iter().enumerate() {
+            if record.kind != INDEX {
+                continue;
+            }
+
+            match backup.read_record(record) {
+                Ok(index) => backup.apply_index(&index)?,
+                // a damaged final index is the commit that was interrupted
+                Err(BackupError::InvalidMac) if position + 1 == records.len() => break,
+                Err(error) => return Err(error),
+            }
+
+            backup.end = record.end();
+            committed = position + 1;
+        }
+
+        backup.recover(&records[committed..])
+    }
+
+    // the clear headers of every complete record, stopping at the first one cut short
+    fn scan(&mut self, length: u64) -> Result<Vec<RecordHeader>, BackupError> {
+        let mut records = Vec::new();
+        let mut offset = HEADER_LENGTH as u64;
+
+        while offset + RECORD_HEADER_LENGTH as u64 <= length {
+            let mut bytes = [0u8; RECORD_HEADER_LENGTH];
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.file.read_exact(&mut bytes)?;
+
+            let record = RecordHeader {
+                kind: bytes[0],
+                offset,
+                length: u32::from_be_bytes(bytes[1..5].try_into().unwrap()),
+                nonce: bytes[5..].try_into().unwrap(),
+            };
+
+            if !matches!(record.kind, CHUNK | INDEX)
+                || (record.length as usize) < TAG_LENGTH
+                || record.end() > length
+            {
+                break;
+            }
+
+            offset = record.end();
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    // adopts the chunk records after the last commit, up to the first that does not decrypt
+    fn recover(mut self, records: &[RecordHeader]) -> Result<Backup<F>, BackupError> {
+        for record in records {
+            if record.kind != CHUNK {
+                break;
+            }
+
+            let mut chunk = match self.read_record(record) {
+                Ok(chunk) => chunk,
+                Err(BackupError::InvalidMac) => break,
+                Err(error) => return Err(error),
+            };
+
+            let address = self.chunker.address(&chunk);
+            let location = Location {
+                offset: record.offset,
+                length: chunk.len() as u32,
+            };
+            chunk.zeroize();
+
+            if let Entry::Vacant(entry) = self.chunks.entry(address) {
+                entry.insert(location);
+                self.pending_chunks.push(address);
+            }
+
+            self.end = record.end();
+        }
+
+        Ok(self)
+    }
+
+    fn associated_data(&self, kind: u8, offset: u64) -> Vec<u8> {
+        [&self.header[..], &[kind], &offset.to_be_bytes()].concat()
+    }
+
+    fn read_record(&mut self, record: &RecordHeader) -> Result<Vec<u8>, BackupError> {
+        let mut ct = vec![0u8; record.length as usize];
+        self.file
+            .seek(SeekFrom::Start(record.offset + RECORD_HEADER_LENGTH as u64))?;
+        self.file.read_exact(&mut ct)?;
+
+        self.aead
+            .decrypt(
+                &ct,
+                &record.nonce,
+                &self.associated_data(record.kind, record.offset),
+            )
+            .map_err(|_| BackupError::InvalidMac)
+    }
+
+    fn write_record(&mut self, kind: u8, data: &[u8]) -> Result<u64, BackupError> {
+        let offset = self.end;
+        let nonce = XChaCha20Poly1305::generate_nonce();
+        let ct = self
+            .aead
+            .encrypt(data, &nonce, &self.associated_data(kind, offset));
+
+        let mut record = Vec::with_capacity(RECORD_HEADER_LENGTH + ct.len());
+        record.push(kind);
+        record.extend_from_slice(&(ct.len() as u32).to_be_bytes());
+        record.extend_from_slice(&nonce);
+        record.extend_from_slice(&ct);
+
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&record)?;
+        self.end = offset + record.len() as u64;
+
+        Ok(offset)
+    }
+
+    fn apply_index(&mut self, index: &[u8]) -> Result<(), BackupError> {
+        let mut reader = Reader::new(index);
+
+        // commits are numbered, so one cannot be dropped from between two others
+        if reader.u64()? != self.commits {
+            return Err(BackupError::Malformed);
+        }
+
+        for _ in 0..reader.u32()? {
+            let address = reader.array()?;
+            let offset = reader.u64()?;
+            let length = reader.u32()?;
+
+            self.chunks.insert(address, Location { offset, length });
+        }
+
+        for _ in 0..reader.u32()? {
+            let length = reader.u16()?;
+            let name = String::from_utf8(reader.bytes(length.into())?.to_vec())
+                .map_err(|_| BackupError::Malformed)?;
+            let length = reader.u32()?;
+            let manifest = Manifest::from_bytes(reader.bytes(length as usize)?)?;
+
+            if self.snapshot(&name).is_some() {
+                return Err(BackupError::Malformed);
+            }
+
+            for chunk in manifest.chunks() {
+                if !self.chunks.contains_key(chunk.address()) {
+                    return Err(BackupError::Malformed);
+                }
+            }
+
+            self.snapshots.push(Snapshot { name, manifest });
+        }
+
+        reader.finish()?;
+        self.commits += 1;
+
+        Ok(())
+    }
+
+    /// Chunks everything `reader` yields into a new snapshot, writing only the chunks the
+    /// container does not already hold. The snapshot is listed once it is committed. Names are
+    /// at most 65535 bytes and must be unique within the container.
+    pub fn add_snapshot<R: Read>(&mut self, name: &str, reader: R) -> Result<u64, BackupError> {
+        assert!(name.len() <= u16::MAX as usize);
+
+        let taken = self.snapshot(name).is_some()
+            || self
+                .pending_snapshots
+                .iter()
+                .any(|snapshot| snapshot.name == name);
+
+        if taken {
+            return Err(BackupError::DuplicateName);
+        }
+
+        let chunker = self.chunker.clone();
+        let mut failure = None;
+
+        let manifest = chunker.chunk_reader(reader, |address, chunk| {
+            if self.chunks.contains_key(address) {
+                return Ok(());
+            }
+
+            match self.write_record(CHUNK, chunk) {
+                Ok(offset) => {
+                    let length = chunk.len() as u32;
+                    self.chunks.insert(*address, Location { offset, length });
+                    self.pending_chunks.push(*address);
+
+                    Ok(())
+                }
+                Err(error) => {
+                    failure = Some(error);
+                    Err(std::io::Error::other("backup write failed"))
+                }
+            }
+        });
+
+        let manifest = match (manifest, failure) {
+            (_, Some(error)) => return Err(error),
+            (manifest, None) => manifest?,
+        };
+
+        let size = manifest.total_size();
+        self.pending_snapshots.push(Snapshot {
+            name: name.to_string(),
+            manifest,
+        });
+
+        Ok(size)
+    }
+
+    /// Writes the index of everything added since the last commit and flushes the file.
+    pub fn commit(&mut self) -> Result<(), BackupError> {
+        let mut index = self.commits.to_be_bytes().to_vec();
+
+        index.extend_from_slice(&(self.pending_chunks.len() as u32).to_be_bytes());
+        for address in &self.pending_chunks {
+            let location = self.chunks[address];
+            index.extend_from_slice(address);
+            index.extend_from_slice(&location.offset.to_be_bytes());
+            index.extend_from_slice(&location.length.to_be_bytes());
+        }
+
+        index.extend_from_slice(&(self.pending_snapshots.len() as u32).to_be_bytes());
+        for snapshot in &self.pending_snapshots {
+            let manifest = snapshot.manifest.to_bytes();
+            index.extend_from_slice(&(snapshot.name.len() as u16).to_be_bytes());
+            index.extend_from_slice(snapshot.name.as_bytes());
+            index.extend_from_slice(&(manifest.len() as u32).to_be_bytes());
+            index.extend_from_slice(&manifest);
+        }
+
+        self.write_record(INDEX, &index)?;
+        self.file.flush()?;
+
+        self.commits += 1;
+        self.pending_chunks.clear();
+        self.snapshots.append(&mut self.pending_snapshots);
+
+        Ok(())
+    }
+
+    /// The committed snapshots, oldest first.
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    pub fn snapshot(&self, name: &str) -> Option<&Snapshot> {
+        self.snapshots.iter().find(|snapshot| snapshot.name == name)
+    }
+
+    pub fn commits(&self) -> u64 {
+        self.commits
+    }
+
+    /// How many distinct chunks the container holds, committed or not.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Chunks written, or recovered, since the last commit.
+    pub fn pending_chunk_count(&self) -> usize {
+        self.pending_chunks.len()
+    }
+
+    /// Decrypts the named snapshot into `writer`, returning its size. Every chunk is checked
+    /// against its address before it is written.
+    pub fn restore<W: Write>(&mut self, name: &str, writer: W) -> Result<u64, BackupError> {
+        let manifest = self
+            .snapshot(name)
+            .ok_or(BackupError::NotFound)?
+            .manifest
+            .clone();
+        let chunker = self.chunker.clone();
+        let mut failure = None;
+
+        let restored = chunker.restore(&manifest, writer, |address| {
+            match self.read_chunk(address) {
+                Ok(chunk) => Some(chunk),
+                Err(error) => {
+                    failure = Some(error);
+                    None
+                }
+            }
+        });
+
+        match (restored, failure) {
+            (_, Some(error)) => Err(error),
+            (Err(ChunkError::CorruptChunk), None) => Err(BackupError::InvalidMac),
+            (restored, None) => Ok(restored?),
+        }
+    }
+
+    fn read_chunk(&mut self, address: &[u8; 32]) -> Result<Vec<u8>, BackupError> {
+        let location = *self.chunks.get(address).ok_or(BackupError::Malformed)?;
+
+        let mut bytes = [0u8; RECORD_HEADER_LENGTH];
+        self.file.seek(SeekFrom::Start(location.offset))?;
+        self.file.read_exact(&mut bytes)?;
+
+        let record = RecordHeader {
+            kind: bytes[0],
+            offset: location.offset,
+            length: u32::from_be_bytes(bytes[1..5].try_into().unwrap()),
+            nonce: bytes[5..].try_into().unwrap(),
+        };
+
+        if record.kind != CHUNK
+            || record.length as u64 != location.length as u64 + TAG_LENGTH as u64
+        {
+            return Err(BackupError::Malformed);
+        }
+
+        self.read_record(&record)
+    }
+
+    pub fn into_inner(self) -> F {
+        self.file
+    }
+}
@@ -1,22 +1,49 @@
+use crate::aeads::{Ciphertext, SymmetricKey};
+use crate::audit::{self, KeyId, Operation};
 pub use crate::ciphers::chacha::ChaCha20;
 pub use crate::errors::InvalidMac;
+use crate::macs::poly1305::one_time_key;
 pub use crate::macs::poly1305::Poly1305;
-use crate::utils::const_time_eq;
+use crate::utils::{const_time_eq, random_bytes, redacted_debug};
+use zeroize::Zeroize;
 
 pub struct ChaCha20Poly1305 {
     chacha: ChaCha20,
+    key_id: Option<KeyId>,
 }
 
+redacted_debug!(ChaCha20Poly1305, "<32 bytes, redacted>");
+
 impl ChaCha20Poly1305 {
+    pub const KEY_SIZE: usize = 32;
+    pub const NONCE_SIZE: usize = 12;
+    pub const TAG_SIZE: usize = 16;
+
     pub fn new(key: &[u8]) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::with_key_id(key, audit::key_id(key))
+    }
+
+    pub(crate) fn with_key_id(key: &[u8], key_id: Option<KeyId>) -> ChaCha20Poly1305 {
         ChaCha20Poly1305 {
             chacha: ChaCha20::new(key),
+            key_id,
         }
     }
 
+    pub fn generate_key() -> SymmetricKey {
+        SymmetricKey::generate()
+    }
+
+    /// A random nonce. 96-bit nonces can safely be chosen at random for up to about
+    /// 2^32 messages under one key.
+    pub fn generate_nonce() -> [u8; Self::NONCE_SIZE] {
+        random_bytes()
+    }
+
     fn mac(&self, nonce: &[u8], ad: &[u8], ct: &[u8]) -> [u8; 16] {
-        let poly1305_key: [u8; 32] = self.chacha.keystream(nonce, 0)[..32].try_into().unwrap();
+        let mut poly1305_key = one_time_key(&self.chacha, nonce);
         let mut poly1305 = Poly1305::new(poly1305_key);
+        poly1305_key.zeroize();
 
         poly1305.update(ad);
         poly1305.update(ct);
@@ -27,22 +54,130 @@ impl ChaCha20Poly1305 {
         poly1305.tag()
     }
 
-    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Vec<u8> {
-        let ct = self.chacha.encrypt(msg, nonce);
-        let tag = self.mac(&nonce, &ad, &ct);
+    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Ciphertext {
+        let ct = self.seal(msg, nonce, ad);
+        audit::record(Operation::Encrypt, || self.key_id, true);
 
-        [ct, tag.to_vec()].concat()
+        ct
     }
 
     pub fn decrypt(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
-        let (ciphertext, tag) = (&ct[0..ct.len() - 16], &ct[ct.len() - 16..]);
-        let msg = self.chacha.encrypt(ciphertext, nonce);
-        let mac = self.mac(&nonce, &ad, &ct);
+        let msg = self.open(ct, nonce, ad);
+        audit::record(Operation::Decrypt, || self.key_id, msg.is_ok());
+
+        msg
+    }
+
+    // encrypt and decrypt without an audit event, for callers that record their own
+    pub(crate) fn seal(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Ciphertext {
+        let ct = self.chacha.encrypt(msg, nonce);
+        let tag = self.mac(nonce, ad, &ct);
+
+        Ciphertext::new(ct, tag)
+    }
+
+    pub(crate) fn open(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        if ct.len() < Self::TAG_SIZE {
+            return Err(InvalidMac);
+        }
+
+        let (ciphertext, tag) = ct.split_at(ct.len() - Self::TAG_SIZE);
+        let mac = self.mac(nonce, ad, ciphertext);
 
         if !const_time_eq(tag, &mac) {
             return Err(InvalidMac);
         }
 
-        Ok(msg)
+        Ok(self.chacha.encrypt(ciphertext, nonce))
+    }
+}
+
+/// The RFC 8439 construction, with a 96-bit nonce and a 32-bit block counter.
+pub type ChaCha20Poly1305Ietf = ChaCha20Poly1305;
+
+/// The original ChaCha20-Poly1305 construction, with a 64-bit nonce and a 64-bit block counter,
+/// as libsodium's `crypto_aead_chacha20poly1305`. Only for data already made with it: its nonce
+/// is too short to choose at random. The tag covers ad || len(ad) || ct || len(ct), without the
+/// padding of the IETF construction.
+pub struct ChaCha20Poly1305Original {
+    chacha: ChaCha20,
+    key_id: Option<KeyId>,
+}
+
+redacted_debug!(ChaCha20Poly1305Original, "<32 bytes, redacted>");
+
+impl ChaCha20Poly1305Original {
+    pub const KEY_SIZE: usize = 32;
+    pub const NONCE_SIZE: usize = 8;
+    pub const TAG_SIZE: usize = 16;
+
+    pub fn new(key: &[u8]) -> ChaCha20Poly1305Original {
+        ChaCha20Poly1305Original {
+            chacha: ChaCha20::new(key),
+            key_id: audit::key_id(key),
+        }
+    }
+
+    // the 64-bit counter's upper half goes where the IETF layout has the first nonce word, and
+    // stays zero for messages under 256 GiB
+    fn block_nonce(nonce: &[u8]) -> [u8; 12] {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+
+        let mut block_nonce = [0u8; 12];
+        block_nonce[4..].copy_from_slice(nonce);
+
+        block_nonce
+    }
+
+    fn xor(&self, msg: &[u8], block_nonce: &[u8; 12]) -> Vec<u8> {
+        assert!((msg.len() as u64).div_ceil(64) < u32::MAX as u64);
+
+        self.chacha.encrypt(msg, block_nonce)
+    }
+
+    fn mac(&self, block_nonce: &[u8; 12], ad: &[u8], ct: &[u8]) -> [u8; 16] {
+        let mut poly1305_key = one_time_key(&self.chacha, block_nonce);
+        let mut poly1305 = Poly1305::new(poly1305_key);
+        poly1305_key.zeroize();
+
+        poly1305.update_unpadded(
+            &[
+                ad,
+                &(ad.len() as u64).to_le_bytes(),
+                ct,
+                &(ct.len() as u64).to_le_bytes(),
+            ]
+            .concat(),
+        );
+
+        poly1305.tag()
+    }
+
+    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Ciphertext {
+        let block_nonce = Self::block_nonce(nonce);
+        let ct = self.xor(msg, &block_nonce);
+        let tag = self.mac(&block_nonce, ad, &ct);
+        audit::record(Operation::Encrypt, || self.key_id, true);
+
+        Ciphertext::new(ct, tag)
+    }
+
+    pub fn decrypt(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        let block_nonce = Self::block_nonce(nonce);
+
+        let msg = if ct.len() < Self::TAG_SIZE {
+            Err(InvalidMac)
+        } else {
+            let (ciphertext, tag) = ct.split_at(ct.len() - Self::TAG_SIZE);
+
+            if const_time_eq(tag, &self.mac(&block_nonce, ad, ciphertext)) {
+                Ok(self.xor(ciphertext, &block_nonce))
+            } else {
+                Err(InvalidMac)
+            }
+        };
+        audit::record(Operation::Decrypt, || self.key_id, msg.is_ok());
+
+        msg
     }
 }
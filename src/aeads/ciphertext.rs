@@ -0,0 +1,48 @@
+/// An AEAD ciphertext with its 16-byte tag appended. It dereferences to the combined bytes, so it
+/// can be passed straight back to `decrypt`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext(Vec<u8>);
+
+impl Ciphertext {
+    pub const TAG_SIZE: usize = 16;
+
+    pub(crate) fn new(mut ciphertext: Vec<u8>, tag: [u8; 16]) -> Ciphertext {
+        ciphertext.extend_from_slice(&tag);
+
+        Ciphertext(ciphertext)
+    }
+
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.0[..self.0.len() - Ciphertext::TAG_SIZE]
+    }
+
+    pub fn tag(&self) -> &[u8; 16] {
+        self.0[self.0.len() - Ciphertext::TAG_SIZE..]
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl std::ops::Deref for Ciphertext {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Ciphertext {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Ciphertext> for Vec<u8> {
+    fn from(ciphertext: Ciphertext) -> Vec<u8> {
+        ciphertext.0
+    }
+}
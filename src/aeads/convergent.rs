@@ -0,0 +1,81 @@
+use crate::aeads::{SymmetricKey, XChaCha20Poly1305};
+use crate::errors::InvalidMac;
+use crate::hashes::blake3::{self, Blake3};
+use crate::utils::{const_time_eq, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const NONCE_CONTEXT: &str = "raycrypt 2026-10-16 convergent encryption nonce";
+const KEY_CONTEXT: &str = "raycrypt 2026-10-16 convergent encryption key";
+
+/// Convergent encryption: equal plaintexts under the same secret encrypt to equal ciphertexts,
+/// so encrypted storage can deduplicate them. This leaks which messages are equal, and anyone
+/// holding the secret can confirm a guess of a plaintext, so only use it where that is wanted.
+#[derive(ZeroizeOnDrop)]
+pub struct ConvergentCipher {
+    nonce_key: [u8; 32],
+    #[zeroize(skip)]
+    aead: XChaCha20Poly1305,
+}
+
+redacted_debug!(ConvergentCipher, "<32 bytes, redacted>");
+
+impl ConvergentCipher {
+    pub const KEY_SIZE: usize = 32;
+    pub const NONCE_SIZE: usize = 24;
+    pub const TAG_SIZE: usize = 16;
+
+    pub fn new(secret: &[u8]) -> ConvergentCipher {
+        assert_eq!(secret.len(), Self::KEY_SIZE);
+
+        let mut key = blake3::derive_key(KEY_CONTEXT, secret);
+        let aead = XChaCha20Poly1305::new(&key);
+        key.zeroize();
+
+        ConvergentCipher {
+            nonce_key: blake3::derive_key(NONCE_CONTEXT, secret),
+            aead,
+        }
+    }
+
+    pub fn generate_key() -> SymmetricKey {
+        SymmetricKey::generate()
+    }
+
+    // the nonce is a keyed hash of the associated data and plaintext, as in SIV
+    fn nonce(&self, msg: &[u8], ad: &[u8]) -> [u8; Self::NONCE_SIZE] {
+        let mut hasher = Blake3::new_keyed(&self.nonce_key);
+        hasher.update(&(ad.len() as u64).to_le_bytes());
+        hasher.update(ad);
+        hasher.update(msg);
+
+        hasher.digest()[..Self::NONCE_SIZE].try_into().unwrap()
+    }
+
+    /// Returns the nonce followed by the ciphertext and tag. The output is a function of the
+    /// secret, `msg` and `ad` alone, which is the point and also the weakness.
+    pub fn encrypt_revealing_equality(&self, msg: &[u8], ad: &[u8]) -> Vec<u8> {
+        let nonce = self.nonce(msg, ad);
+
+        let mut output = nonce.to_vec();
+        output.extend_from_slice(&self.aead.encrypt(msg, &nonce, ad));
+
+        output
+    }
+
+    pub fn decrypt(&self, ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        if ct.len() < Self::NONCE_SIZE + Self::TAG_SIZE {
+            return Err(InvalidMac);
+        }
+
+        let (nonce, ct) = ct.split_at(Self::NONCE_SIZE);
+        let mut msg = self.aead.decrypt(ct, nonce, ad)?;
+
+        // a valid ciphertext always carries the nonce its plaintext derives
+        if !const_time_eq(&self.nonce(&msg, ad), nonce) {
+            msg.zeroize();
+            return Err(InvalidMac);
+        }
+
+        Ok(msg)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::aeads::{SymmetricKey, XChaCha20Poly1305};
+use crate::ecc::ed25519::{SigningKey, VerifyingKey};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::errors::InvalidMac;
+use crate::utils::redacted_debug;
+use zeroize::Zeroize;
+
+const LENGTH_SIZE: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvelopeError<E> {
+    Malformed,
+    InvalidMac,
+    Provider(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for EnvelopeError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::Malformed => write!(f, "The envelope is malformed."),
+            EnvelopeError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This message may be tampered with."
+            ),
+            EnvelopeError::Provider(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for EnvelopeError<E> {}
+
+impl<E> From<InvalidEncoding> for EnvelopeError<E> {
+    fn from(_: InvalidEncoding) -> EnvelopeError<E> {
+        EnvelopeError::Malformed
+    }
+}
+
+/// The key-encryption-key (KEK) operations of envelope encryption, for applications that keep
+/// their KEKs in a KMS or HSM. The provider only ever sees data keys and their associated data,
+/// never the messages themselves.
+pub trait KeyProvider {
+    type Error;
+
+    /// Encrypts a data key under the KEK, binding it to `ad`.
+    fn wrap_key(&self, key: &SymmetricKey, ad: &[u8]) -> Result<Vec<u8>, Self::Error>;
+
+    fn unwrap_key(&self, wrapped: &[u8], ad: &[u8]) -> Result<SymmetricKey, Self::Error>;
+
+    /// Signs with a key held by the provider, for authenticating manifests or key metadata.
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// A `KeyProvider` that keeps its keys in process memory. Wrapped keys are
+/// `nonce || XChaCha20-Poly1305 ciphertext` and signatures are Ed25519.
+pub struct LocalKeyProvider {
+    kek: XChaCha20Poly1305,
+    signing_key: SigningKey,
+}
+
+redacted_debug!(LocalKeyProvider, "<redacted>");
+
+impl LocalKeyProvider {
+    pub fn new(kek: &SymmetricKey, signing_key: SigningKey) -> LocalKeyProvider {
+        LocalKeyProvider {
+            kek: XChaCha20Poly1305::new(kek.as_bytes()),
+            signing_key,
+        }
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+}
+
+impl KeyProvider for LocalKeyProvider {
+    type Error = InvalidMac;
+
+    fn wrap_key(&self, key: &SymmetricKey, ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        let nonce = XChaCha20Poly1305::generate_nonce();
+
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&self.kek.encrypt(key.as_bytes(), &nonce, ad));
+
+        Ok(wrapped)
+    }
+
+    fn unwrap_key(&self, wrapped: &[u8], ad: &[u8]) -> Result<SymmetricKey, InvalidMac> {
+        if wrapped.len() < XChaCha20Poly1305::NONCE_SIZE {
+            return Err(InvalidMac);
+        }
+
+        let (nonce, ct) = wrapped.split_at(XChaCha20Poly1305::NONCE_SIZE);
+        let mut key = self.kek.decrypt(ct, nonce, ad)?;
+        let unwrapped = SymmetricKey::new(&key).map_err(|_| InvalidMac);
+        key.zeroize();
+
+        unwrapped
+    }
+
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        Ok(self.signing_key.sign(msg).to_vec())
+    }
+}
+
+/// Encrypts `msg` under a fresh data key and wraps that key with `provider`. The envelope is
+/// `wrapped key length (2 bytes, big-endian) || wrapped key || nonce || ciphertext`, and `ad` is
+/// bound to both the wrapped key and the ciphertext.
+pub fn seal<P: KeyProvider>(
+    provider: &P,
+    msg: &[u8],
+    ad: &[u8],
+) -> Result<Vec<u8>, EnvelopeError<P::Error>> {
+    let key = SymmetricKey::generate();
+    let wrapped = provider
+        .wrap_key(&key, ad)
+        .map_err(EnvelopeError::Provider)?;
+
+    let length = u16::try_from(wrapped.len()).map_err(|_| EnvelopeError::Malformed)?;
+
+    let nonce = XChaCha20Poly1305::generate_nonce();
+    let ct = XChaCha20Poly1305::new(key.as_bytes()).encrypt(msg, &nonce, ad);
+
+    let mut envelope = Vec::with_capacity(LENGTH_SIZE + wrapped.len() + nonce.len() + ct.len());
+    envelope.extend_from_slice(&length.to_be_bytes());
+    envelope.extend_from_slice(&wrapped);
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ct);
+
+    Ok(envelope)
+}
+
+pub fn open<P: KeyProvider>(
+    provider: &P,
+    envelope: &[u8],
+    ad: &[u8],
+) -> Result<Vec<u8>, EnvelopeError<P::Error>> {
+    let mut reader = Reader::new(envelope);
+    let length = reader.u16()?;
+    let wrapped = reader.bytes(length.into())?;
+    let nonce = reader.bytes(XChaCha20Poly1305::NONCE_SIZE)?;
+
+    if reader.len() < XChaCha20Poly1305::TAG_SIZE {
+        return Err(EnvelopeError::Malformed);
+    }
+
+    let ct = reader.rest();
+
+    let key = provider
+        .unwrap_key(wrapped, ad)
+        .map_err(EnvelopeError::Provider)?;
+
+    XChaCha20Poly1305::new(key.as_bytes())
+        .decrypt(ct, nonce, ad)
+        .map_err(|_| EnvelopeError::InvalidMac)
+}
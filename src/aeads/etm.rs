@@ -0,0 +1,208 @@
+// Generic encrypt-then-MAC, for reproducing formats that pair a stream cipher with a MAC under
+// separate keys, such as AES-CTR with HMAC-SHA256. The encoding follows the AES-CBC-HMAC-SHA2
+// construction of RFC 7518: the tag is the MAC of ad || nonce || ciphertext || the length of ad
+// in bits (u64, big-endian), truncated to the tag size, and the output is
+// nonce || ciphertext || tag.
+//
+// Prefer an AEAD for anything new; this exists for interoperability.
+
+use crate::ciphers::aes::ctr::AesCtr;
+use crate::ciphers::chacha::ChaCha20;
+use crate::ciphers::salsa::{Salsa20, XSalsa20};
+use crate::ciphers::xchacha::XChaCha20;
+use crate::errors::Error;
+use crate::errors::InvalidMac;
+use crate::hashes::Hash;
+use crate::macs::hmac::Hmac;
+use crate::utils::{const_time_eq, redacted_debug};
+
+/// An unauthenticated stream cipher, keyed once and used with a fresh nonce per message.
+pub trait StreamCipher: Sized {
+    const NONCE_SIZE: usize;
+
+    fn new(key: &[u8]) -> Result<Self, Error>;
+
+    fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8>;
+}
+
+/// A MAC that can safely authenticate many messages under one key, so not Poly1305.
+pub trait Mac: Clone {
+    const TAG_SIZE: usize;
+
+    fn new(key: &[u8]) -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn tag(self) -> Vec<u8>;
+}
+
+impl StreamCipher for AesCtr {
+    const NONCE_SIZE: usize = AesCtr::IV_SIZE;
+
+    fn new(key: &[u8]) -> Result<AesCtr, Error> {
+        AesCtr::new(key)
+    }
+
+    fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor(msg, nonce)
+    }
+}
+
+/// Starts at block 0, as there is no Poly1305 key to reserve block 0 for.
+impl StreamCipher for ChaCha20 {
+    const NONCE_SIZE: usize = 12;
+
+    fn new(key: &[u8]) -> Result<ChaCha20, Error> {
+        if key.len() != 32 {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(ChaCha20::new(key))
+    }
+
+    fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.encrypt_with_counter(msg, nonce, 0)
+    }
+}
+
+impl StreamCipher for XChaCha20 {
+    const NONCE_SIZE: usize = 24;
+
+    fn new(key: &[u8]) -> Result<XChaCha20, Error> {
+        if key.len() != 32 {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(XChaCha20::new(key))
+    }
+
+    fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor(msg, nonce)
+    }
+}
+
+impl StreamCipher for Salsa20 {
+    const NONCE_SIZE: usize = 8;
+
+    fn new(key: &[u8]) -> Result<Salsa20, Error> {
+        if key.len() != 32 {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(Salsa20::new(key))
+    }
+
+    fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor(msg, nonce)
+    }
+}
+
+impl StreamCipher for XSalsa20 {
+    const NONCE_SIZE: usize = 24;
+
+    fn new(key: &[u8]) -> Result<XSalsa20, Error> {
+        if key.len() != 32 {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(XSalsa20::new(key))
+    }
+
+    fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor(msg, nonce)
+    }
+}
+
+impl<H: Hash> Mac for Hmac<H> {
+    const TAG_SIZE: usize = H::OUTPUT_SIZE;
+
+    fn new(key: &[u8]) -> Hmac<H> {
+        Hmac::new(key)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn tag(self) -> Vec<u8> {
+        self.tag()
+    }
+}
+
+pub struct EtM<C: StreamCipher, M: Mac> {
+    cipher: C,
+    mac: M,
+    tag_size: usize,
+}
+
+redacted_debug!(EtM<C: StreamCipher, M: Mac>, "<redacted>");
+
+impl<C: StreamCipher, M: Mac> EtM<C, M> {
+    /// Uses the full tag of the MAC.
+    pub fn new(encryption_key: &[u8], mac_key: &[u8]) -> Result<EtM<C, M>, Error> {
+        EtM::with_tag_size(encryption_key, mac_key, M::TAG_SIZE)
+    }
+
+    /// Truncates tags to `tag_size` bytes, between 16 and the MAC's tag size.
+    pub fn with_tag_size(
+        encryption_key: &[u8],
+        mac_key: &[u8],
+        tag_size: usize,
+    ) -> Result<EtM<C, M>, Error> {
+        assert!((16..=M::TAG_SIZE).contains(&tag_size));
+
+        Ok(EtM {
+            cipher: C::new(encryption_key)?,
+            mac: M::new(mac_key),
+            tag_size,
+        })
+    }
+
+    pub fn tag_size(&self) -> usize {
+        self.tag_size
+    }
+
+    fn tag(&self, nonce: &[u8], ct: &[u8], ad: &[u8]) -> Vec<u8> {
+        let mut mac = self.mac.clone();
+        mac.update(ad);
+        mac.update(nonce);
+        mac.update(ct);
+        mac.update(&(ad.len() as u64 * 8).to_be_bytes());
+
+        let mut tag = mac.tag();
+        tag.truncate(self.tag_size);
+
+        tag
+    }
+
+    /// `nonce` must never repeat under the same encryption key.
+    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Vec<u8> {
+        assert_eq!(nonce.len(), C::NONCE_SIZE);
+
+        let ct = self.cipher.xor(msg, nonce);
+        let tag = self.tag(nonce, &ct, ad);
+
+        let mut output = Vec::with_capacity(nonce.len() + ct.len() + tag.len());
+        output.extend_from_slice(nonce);
+        output.extend_from_slice(&ct);
+        output.extend_from_slice(&tag);
+
+        output
+    }
+
+    /// Checks the tag before decrypting anything.
+    pub fn decrypt(&self, sealed: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        if sealed.len() < C::NONCE_SIZE + self.tag_size {
+            return Err(InvalidMac);
+        }
+
+        let (nonce, rest) = sealed.split_at(C::NONCE_SIZE);
+        let (ct, tag) = rest.split_at(rest.len() - self.tag_size);
+
+        if !const_time_eq(&self.tag(nonce, ct, ad), tag) {
+            return Err(InvalidMac);
+        }
+
+        Ok(self.cipher.xor(ct, nonce))
+    }
+}
@@ -0,0 +1,173 @@
+use crate::ciphers::chacha::ChaCha20;
+use crate::errors::InvalidMac;
+use crate::macs::poly1305::{one_time_key, Poly1305};
+use crate::utils::{const_time_eq, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// ChaCha20-Poly1305 (RFC 8439) split into calls: the cipher keeps its place in a partly used
+// keystream block, and the MAC input is buffered into whole 16-byte blocks so that only the
+// final block of the ciphertext gets padded
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct Incremental {
+    chacha: ChaCha20,
+    nonce: [u8; 12],
+    counter: u32,
+    keystream: [u8; 64],
+    used: usize,
+    poly1305: Poly1305,
+    block: [u8; 16],
+    buffered: usize,
+    ad_length: u64,
+    ct_length: u64,
+}
+
+impl Incremental {
+    fn new(key: &[u8], nonce: &[u8], ad: &[u8]) -> Incremental {
+        assert_eq!(nonce.len(), 12);
+
+        let chacha = ChaCha20::new(key);
+
+        let mut poly1305_key = one_time_key(&chacha, nonce);
+        let mut poly1305 = Poly1305::new(poly1305_key);
+        poly1305_key.zeroize();
+
+        poly1305.update(ad);
+
+        Incremental {
+            chacha,
+            nonce: nonce.try_into().unwrap(),
+            counter: 1,
+            keystream: [0u8; 64],
+            used: 64,
+            poly1305,
+            block: [0u8; 16],
+            buffered: 0,
+            ad_length: ad.len() as u64,
+            ct_length: 0,
+        }
+    }
+
+    fn apply(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(data.len());
+
+        let leftover = data.len().min(64 - self.used);
+        let (head, rest) = data.split_at(leftover);
+
+        output.extend(
+            head.iter()
+                .zip(&self.keystream[self.used..])
+                .map(|(m, k)| m ^ k),
+        );
+        self.used += leftover;
+
+        let (blocks, tail) = rest.split_at(rest.len() - rest.len() % 64);
+
+        output.extend(
+            self.chacha
+                .encrypt_with_counter(blocks, &self.nonce, self.counter),
+        );
+        self.counter = self.counter.wrapping_add((blocks.len() / 64) as u32);
+
+        if !tail.is_empty() {
+            self.keystream = self.chacha.keystream(&self.nonce, self.counter);
+            self.counter = self.counter.wrapping_add(1);
+
+            output.extend(tail.iter().zip(&self.keystream).map(|(m, k)| m ^ k));
+            self.used = tail.len();
+        }
+
+        output
+    }
+
+    fn authenticate(&mut self, ct: &[u8]) {
+        self.ct_length += ct.len() as u64;
+
+        let fill = ct.len().min(16 - self.buffered);
+        let (head, rest) = ct.split_at(fill);
+
+        self.block[self.buffered..self.buffered + fill].copy_from_slice(head);
+        self.buffered += fill;
+
+        if self.buffered < 16 {
+            return;
+        }
+
+        self.poly1305.update(&self.block);
+
+        let (blocks, tail) = rest.split_at(rest.len() - rest.len() % 16);
+        self.poly1305.update(blocks);
+
+        self.block[..tail.len()].copy_from_slice(tail);
+        self.buffered = tail.len();
+    }
+
+    fn tag(&mut self) -> [u8; 16] {
+        let block = self.block;
+        self.poly1305.update(&block[..self.buffered]);
+
+        self.poly1305
+            .update(&[self.ad_length.to_le_bytes(), self.ct_length.to_le_bytes()].concat());
+
+        self.poly1305.tag()
+    }
+}
+
+/// ChaCha20-Poly1305 over a message that arrives in pieces. The concatenated `update` outputs
+/// and the tag from `finalize` are exactly what `ChaCha20Poly1305::encrypt` produces for the
+/// whole message; there is no framing, so the caller decides how to carry them.
+pub struct AeadEncryptor {
+    state: Incremental,
+}
+
+redacted_debug!(AeadEncryptor, "<redacted>");
+
+impl AeadEncryptor {
+    /// `nonce` must be 12 bytes.
+    pub fn new(key: &[u8], nonce: &[u8], ad: &[u8]) -> AeadEncryptor {
+        AeadEncryptor {
+            state: Incremental::new(key, nonce, ad),
+        }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let ct = self.state.apply(chunk);
+        self.state.authenticate(&ct);
+
+        ct
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        self.state.tag()
+    }
+}
+
+/// The decrypting side of `AeadEncryptor`. `update` returns plaintext before the tag has been
+/// checked, so nothing it returns may be trusted or acted on until `finalize` succeeds.
+pub struct AeadDecryptor {
+    state: Incremental,
+}
+
+redacted_debug!(AeadDecryptor, "<redacted>");
+
+impl AeadDecryptor {
+    /// `nonce` must be 12 bytes.
+    pub fn new(key: &[u8], nonce: &[u8], ad: &[u8]) -> AeadDecryptor {
+        AeadDecryptor {
+            state: Incremental::new(key, nonce, ad),
+        }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) -> Vec<u8> {
+        self.state.authenticate(chunk);
+
+        self.state.apply(chunk)
+    }
+
+    pub fn finalize(mut self, tag: &[u8]) -> Result<(), InvalidMac> {
+        if !const_time_eq(&self.state.tag(), tag) {
+            return Err(InvalidMac);
+        }
+
+        Ok(())
+    }
+}
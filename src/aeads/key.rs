@@ -0,0 +1,50 @@
+use crate::encoding::{base64, hex};
+use crate::errors::Error;
+use crate::secret::SecretString;
+use crate::utils::{random_bytes, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SymmetricKey([u8; 32]);
+
+redacted_debug!(SymmetricKey, "<32 bytes, redacted>");
+
+impl SymmetricKey {
+    pub const KEY_SIZE: usize = 32;
+
+    pub fn new(key: &[u8]) -> Result<SymmetricKey, Error> {
+        Ok(SymmetricKey(key.try_into().map_err(|_| Error::InvalidKey)?))
+    }
+
+    pub fn generate() -> SymmetricKey {
+        SymmetricKey(random_bytes())
+    }
+
+    pub fn from_hex(key: &str) -> Result<SymmetricKey, Error> {
+        let mut bytes = hex::decode(key)?;
+        let key = SymmetricKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn from_base64(key: &str) -> Result<SymmetricKey, Error> {
+        let mut bytes = base64::decode(key)?;
+        let key = SymmetricKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn to_hex(&self) -> SecretString {
+        SecretString::encode_hex(&self.0)
+    }
+
+    pub fn to_base64(&self) -> SecretString {
+        SecretString::encode_base64(&self.0)
+    }
+}
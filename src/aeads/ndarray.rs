@@ -0,0 +1,211 @@
+// Encryption of n-dimensional numeric arrays, such as tensors and embeddings, that keeps their
+// element type and shape. Both go into a plaintext header that is the associated data, so an
+// array cannot be read back as another type or reshaped without failing to decrypt.
+//
+// Layout: version || dtype length (u8) || dtype || dimensions (u8) || each dimension (u64) ||
+// nonce (24) || ciphertext || tag. Elements are stored little-endian and dtypes are spelled as
+// in NumPy, so "<f4" is a float32. Elements are encrypted in batches as they are converted to
+// bytes, without a full plaintext copy of the array.
+
+use crate::aeads::{AeadDecryptor, AeadEncryptor, XChaCha20Poly1305};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use zeroize::Zeroize;
+
+const VERSION: u8 = 1;
+const MAX_DIMENSIONS: usize = 32;
+const BATCH_SIZE: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NdArrayError {
+    Malformed,
+    ShapeMismatch,
+    WrongType,
+    InvalidMac,
+}
+
+impl std::fmt::Display for NdArrayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NdArrayError::Malformed => write!(f, "The encrypted array is malformed."),
+            NdArrayError::ShapeMismatch => {
+                write!(f, "The shape does not match the number of elements.")
+            }
+            NdArrayError::WrongType => write!(f, "The array holds another element type."),
+            NdArrayError::InvalidMac => {
+                write!(f, "Invalid MAC detected. This array may be tampered with.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NdArrayError {}
+
+impl From<InvalidEncoding> for NdArrayError {
+    fn from(_: InvalidEncoding) -> NdArrayError {
+        NdArrayError::Malformed
+    }
+}
+
+/// A fixed-size numeric type that arrays can hold.
+pub trait Element: Copy + Zeroize {
+    /// The NumPy array-protocol type string.
+    const DTYPE: &'static str;
+    const SIZE: usize;
+
+    fn write_le(self, output: &mut Vec<u8>);
+
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! element {
+    ($type:ty, $dtype:literal) => {
+        impl Element for $type {
+            const DTYPE: &'static str = $dtype;
+            const SIZE: usize = std::mem::size_of::<$type>();
+
+            fn write_le(self, output: &mut Vec<u8>) {
+                output.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_le(bytes: &[u8]) -> $type {
+                <$type>::from_le_bytes(bytes.try_into().unwrap())
+            }
+        }
+    };
+}
+
+element!(u8, "|u1");
+element!(i8, "|i1");
+element!(u16, "<u2");
+element!(i16, "<i2");
+element!(u32, "<u4");
+element!(i32, "<i4");
+element!(u64, "<u8");
+element!(i64, "<i8");
+element!(f32, "<f4");
+element!(f64, "<f8");
+
+fn element_count(shape: &[usize]) -> Option<usize> {
+    shape
+        .iter()
+        .try_fold(1usize, |count, &n| count.checked_mul(n))
+}
+
+/// Encrypts `data`, an array of `shape` in row-major order.
+pub fn encrypt_ndarray<T: Element>(
+    cipher: &XChaCha20Poly1305,
+    data: &[T],
+    shape: &[usize],
+) -> Result<Vec<u8>, NdArrayError> {
+    if shape.len() > MAX_DIMENSIONS || element_count(shape) != Some(data.len()) {
+        return Err(NdArrayError::ShapeMismatch);
+    }
+
+    let mut output = vec![VERSION, T::DTYPE.len() as u8];
+    output.extend_from_slice(T::DTYPE.as_bytes());
+    output.push(shape.len() as u8);
+
+    for &n in shape {
+        output.extend_from_slice(&(n as u64).to_be_bytes());
+    }
+
+    let nonce = XChaCha20Poly1305::generate_nonce();
+    let (subkey, encryption_nonce) = cipher.derive_subkey(&nonce);
+    let mut encryptor = AeadEncryptor::new(subkey.as_bytes(), &encryption_nonce, &output);
+
+    output.reserve(nonce.len() + data.len() * T::SIZE + XChaCha20Poly1305::TAG_SIZE);
+    output.extend_from_slice(&nonce);
+
+    let mut batch = Vec::with_capacity(BATCH_SIZE * T::SIZE);
+
+    for elements in data.chunks(BATCH_SIZE) {
+        batch.clear();
+
+        for &element in elements {
+            element.write_le(&mut batch);
+        }
+
+        output.extend_from_slice(&encryptor.update(&batch));
+    }
+
+    batch.zeroize();
+    output.extend_from_slice(&encryptor.finalize());
+
+    Ok(output)
+}
+
+/// The element type and shape of an encrypted array, which are not secret.
+pub fn describe_ndarray(sealed: &[u8]) -> Result<(&str, Vec<usize>), NdArrayError> {
+    parse_header(sealed).map(|(dtype, shape, _)| (dtype, shape))
+}
+
+// the dtype, the shape and the length of the header
+fn parse_header(sealed: &[u8]) -> Result<(&str, Vec<usize>, usize), NdArrayError> {
+    let mut reader = Reader::new(sealed);
+
+    if reader.byte()? != VERSION {
+        return Err(NdArrayError::Malformed);
+    }
+
+    let dtype_length = reader.byte()? as usize;
+    let dtype =
+        std::str::from_utf8(reader.bytes(dtype_length)?).map_err(|_| NdArrayError::Malformed)?;
+
+    let dimensions = reader.byte()? as usize;
+    if dimensions > MAX_DIMENSIONS {
+        return Err(NdArrayError::Malformed);
+    }
+
+    let mut shape = Vec::with_capacity(dimensions);
+
+    for _ in 0..dimensions {
+        let n = reader.u64()?;
+        shape.push(usize::try_from(n).map_err(|_| NdArrayError::Malformed)?);
+    }
+
+    Ok((dtype, shape, sealed.len() - reader.len()))
+}
+
+/// Returns the elements in row-major order and the shape.
+pub fn decrypt_ndarray<T: Element>(
+    cipher: &XChaCha20Poly1305,
+    sealed: &[u8],
+) -> Result<(Vec<T>, Vec<usize>), NdArrayError> {
+    let (dtype, shape, header_length) = parse_header(sealed)?;
+
+    if dtype != T::DTYPE {
+        return Err(NdArrayError::WrongType);
+    }
+
+    let (header, rest) = sealed.split_at(header_length);
+    let count = element_count(&shape).ok_or(NdArrayError::Malformed)?;
+    let expected = count
+        .checked_mul(T::SIZE)
+        .and_then(|length| length.checked_add(XChaCha20Poly1305::NONCE_SIZE))
+        .and_then(|length| length.checked_add(XChaCha20Poly1305::TAG_SIZE));
+
+    if expected != Some(rest.len()) {
+        return Err(NdArrayError::Malformed);
+    }
+
+    let (nonce, rest) = rest.split_at(XChaCha20Poly1305::NONCE_SIZE);
+    let (ct, tag) = rest.split_at(rest.len() - XChaCha20Poly1305::TAG_SIZE);
+
+    let (subkey, decryption_nonce) = cipher.derive_subkey(nonce);
+    let mut decryptor = AeadDecryptor::new(subkey.as_bytes(), &decryption_nonce, header);
+    let mut data = Vec::with_capacity(count);
+
+    for batch in ct.chunks(BATCH_SIZE * T::SIZE) {
+        let mut bytes = decryptor.update(batch);
+        data.extend(bytes.chunks(T::SIZE).map(T::read_le));
+        bytes.zeroize();
+    }
+
+    if decryptor.finalize(tag).is_err() {
+        data.zeroize();
+        return Err(NdArrayError::InvalidMac);
+    }
+
+    Ok((data, shape))
+}
@@ -0,0 +1,175 @@
+// Encrypted serialization of structured values through a pluggable codec. The codec's name and a
+// caller-chosen type tag are the associated data, so a sealed value cannot be opened as another
+// type or with another codec. Codecs whose decoders are unsafe to run on untrusted input, like
+// pickle, have to be marked as such and are only used through `open_trusted_object`.
+//
+// Layout: version || codec name length (u8) || codec name || tag length (u8) || tag ||
+// nonce (24) || ciphertext || tag.
+
+use crate::aeads::XChaCha20Poly1305;
+use crate::cbor::{self, CborError};
+use zeroize::Zeroize;
+
+const VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectError<E> {
+    Malformed,
+    WrongType,
+    InvalidMac,
+    UntrustedCodec,
+    Codec(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ObjectError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectError::Malformed => write!(f, "The sealed object is malformed."),
+            ObjectError::WrongType => {
+                write!(f, "The sealed object has another type or codec.")
+            }
+            ObjectError::InvalidMac => {
+                write!(f, "Invalid MAC detected. This object may be tampered with.")
+            }
+            ObjectError::UntrustedCodec => {
+                write!(f, "This codec may only decode trusted objects.")
+            }
+            ObjectError::Codec(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for ObjectError<E> {}
+
+pub trait Codec<T> {
+    type Error;
+
+    /// Identifies the serialization format in sealed objects; at most 255 bytes.
+    fn name(&self) -> &str;
+
+    /// Whether decoding can run arbitrary code or otherwise misbehave on hostile input.
+    fn trusted_only(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Self::Error>;
+
+    fn decode(&self, bytes: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// The deterministic CBOR encoding of `cbor::Value`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CborCodec;
+
+impl Codec<cbor::Value> for CborCodec {
+    type Error = CborError;
+
+    fn name(&self) -> &str {
+        "cbor"
+    }
+
+    fn encode(&self, value: &cbor::Value) -> Result<Vec<u8>, CborError> {
+        cbor::encode(value)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<cbor::Value, CborError> {
+        cbor::decode(bytes)
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+#[cfg(feature = "json")]
+impl Codec<serde_json::Value> for JsonCodec {
+    type Error = serde_json::Error;
+
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(value)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+fn header(codec_name: &str, type_tag: &str) -> Vec<u8> {
+    assert!(codec_name.len() <= u8::MAX as usize && type_tag.len() <= u8::MAX as usize);
+
+    let mut header = vec![VERSION, codec_name.len() as u8];
+    header.extend_from_slice(codec_name.as_bytes());
+    header.push(type_tag.len() as u8);
+    header.extend_from_slice(type_tag.as_bytes());
+
+    header
+}
+
+/// Serializes and encrypts `value`. `type_tag` names what it is, such as "user-profile/v2",
+/// and has to be given again to open it.
+pub fn seal_object<T, C: Codec<T>>(
+    cipher: &XChaCha20Poly1305,
+    codec: &C,
+    value: &T,
+    type_tag: &str,
+) -> Result<Vec<u8>, ObjectError<C::Error>> {
+    let mut output = header(codec.name(), type_tag);
+
+    let mut msg = codec.encode(value).map_err(ObjectError::Codec)?;
+    let nonce = XChaCha20Poly1305::generate_nonce();
+    let ct = cipher.encrypt(&msg, &nonce, &output);
+    msg.zeroize();
+
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ct);
+
+    Ok(output)
+}
+
+/// Decrypts and deserializes an object sealed with the same codec and type tag. Codecs that
+/// are only safe on trusted input are refused.
+pub fn open_object<T, C: Codec<T>>(
+    cipher: &XChaCha20Poly1305,
+    codec: &C,
+    sealed: &[u8],
+    type_tag: &str,
+) -> Result<T, ObjectError<C::Error>> {
+    if codec.trusted_only() {
+        return Err(ObjectError::UntrustedCodec);
+    }
+
+    open_trusted_object(cipher, codec, sealed, type_tag)
+}
+
+/// `open_object` for any codec, for objects that only trusted parties could have sealed. The
+/// MAC is checked before decoding, but anyone holding the key can still seal hostile input.
+pub fn open_trusted_object<T, C: Codec<T>>(
+    cipher: &XChaCha20Poly1305,
+    codec: &C,
+    sealed: &[u8],
+    type_tag: &str,
+) -> Result<T, ObjectError<C::Error>> {
+    let header = header(codec.name(), type_tag);
+
+    if sealed.len() < header.len() + XChaCha20Poly1305::NONCE_SIZE + XChaCha20Poly1305::TAG_SIZE {
+        return Err(ObjectError::Malformed);
+    }
+
+    if sealed[..header.len()] != header[..] {
+        return Err(ObjectError::WrongType);
+    }
+
+    let (nonce, ct) = sealed[header.len()..].split_at(XChaCha20Poly1305::NONCE_SIZE);
+    let mut msg = cipher
+        .decrypt(ct, nonce, &header)
+        .map_err(|_| ObjectError::InvalidMac)?;
+
+    let value = codec.decode(&msg).map_err(ObjectError::Codec);
+    msg.zeroize();
+
+    value
+}
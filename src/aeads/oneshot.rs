@@ -0,0 +1,226 @@
+// One-line encryption of a secret under a symmetric key. The output names its algorithm, so
+// `open` needs only the key:
+//
+//     version (2) || algorithm || key id (8) || created (u64) || nonce || ciphertext || tag
+//
+// where created is in seconds since the Unix epoch. Version 1 had only the version and algorithm
+// bytes and can still be opened. The whole header is authenticated along with the caller's
+// associated data, and can be read with `parse_header` without the key, for triaging stored
+// blobs.
+
+use crate::aeads::{AesGcm, ChaCha20Poly1305, SymmetricKey, XChaCha20Poly1305};
+use crate::audit::KeyId;
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::utils::random_bytes;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const VERSION: u8 = 2;
+const HEADER_SIZE: usize = 2 + 8 + 8;
+const TAG_SIZE: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SealError {
+    Malformed,
+    UnknownAlgorithm,
+    WrongKey,
+    InvalidMac,
+}
+
+impl std::fmt::Display for SealError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SealError::Malformed => write!(f, "The sealed message is malformed."),
+            SealError::UnknownAlgorithm => write!(f, "The message uses an unknown algorithm."),
+            SealError::WrongKey => write!(f, "The message was sealed under another key."),
+            SealError::InvalidMac => {
+                write!(
+                    f,
+                    "Invalid MAC detected. This message may be tampered with."
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SealError {}
+
+impl From<InvalidEncoding> for SealError {
+    fn from(_: InvalidEncoding) -> SealError {
+        SealError::Malformed
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AeadAlgorithm {
+    #[default]
+    XChaCha20Poly1305,
+    ChaCha20Poly1305,
+    /// AES-256-GCM.
+    AesGcm,
+}
+
+impl AeadAlgorithm {
+    fn id(&self) -> u8 {
+        match self {
+            AeadAlgorithm::XChaCha20Poly1305 => 1,
+            AeadAlgorithm::ChaCha20Poly1305 => 2,
+            AeadAlgorithm::AesGcm => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<AeadAlgorithm> {
+        match id {
+            1 => Some(AeadAlgorithm::XChaCha20Poly1305),
+            2 => Some(AeadAlgorithm::ChaCha20Poly1305),
+            3 => Some(AeadAlgorithm::AesGcm),
+            _ => None,
+        }
+    }
+
+    pub fn nonce_size(&self) -> usize {
+        match self {
+            AeadAlgorithm::XChaCha20Poly1305 => XChaCha20Poly1305::NONCE_SIZE,
+            AeadAlgorithm::ChaCha20Poly1305 => ChaCha20Poly1305::NONCE_SIZE,
+            AeadAlgorithm::AesGcm => AesGcm::NONCE_SIZE,
+        }
+    }
+}
+
+/// Encrypts `msg` with XChaCha20-Poly1305 and a random nonce.
+pub fn seal(key: &SymmetricKey, msg: &[u8], ad: &[u8]) -> Vec<u8> {
+    seal_with(AeadAlgorithm::default(), key, msg, ad)
+}
+
+/// `seal` with another algorithm. Random 96-bit nonces are only safe for about 2^32 messages
+/// per key, so prefer the default for long-lived keys.
+pub fn seal_with(algorithm: AeadAlgorithm, key: &SymmetricKey, msg: &[u8], ad: &[u8]) -> Vec<u8> {
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+
+    let mut header = Vec::with_capacity(HEADER_SIZE);
+    header.extend_from_slice(&[VERSION, algorithm.id()]);
+    header.extend_from_slice(KeyId::new(key.as_bytes()).as_bytes());
+    header.extend_from_slice(&created.to_be_bytes());
+
+    let ad = [&header[..], ad].concat();
+    let key = key.as_bytes();
+
+    let nonce: [u8; 24] = random_bytes();
+    let nonce = &nonce[..algorithm.nonce_size()];
+
+    let ct = match algorithm {
+        AeadAlgorithm::XChaCha20Poly1305 => XChaCha20Poly1305::new(key).encrypt(msg, nonce, &ad),
+        AeadAlgorithm::ChaCha20Poly1305 => ChaCha20Poly1305::new(key).encrypt(msg, nonce, &ad),
+        AeadAlgorithm::AesGcm => AesGcm::new(key).unwrap().encrypt(msg, nonce, &ad),
+    };
+
+    let mut output = header;
+    output.reserve(nonce.len() + ct.len());
+    output.extend_from_slice(nonce);
+    output.extend_from_slice(&ct);
+
+    output
+}
+
+/// What can be learned about a sealed message without the key. Version 1 messages have no key
+/// id or creation time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealHeader {
+    pub version: u8,
+    pub algorithm: AeadAlgorithm,
+    pub key_id: Option<KeyId>,
+    pub created: Option<SystemTime>,
+    pub nonce: Vec<u8>,
+    pub message_size: usize,
+}
+
+struct Parts<'a> {
+    version: u8,
+    algorithm: AeadAlgorithm,
+    key_id: Option<KeyId>,
+    created: Option<SystemTime>,
+    header: &'a [u8],
+    nonce: &'a [u8],
+    // with the tag
+    ct: &'a [u8],
+}
+
+fn split(sealed: &[u8]) -> Result<Parts<'_>, SealError> {
+    let mut reader = Reader::new(sealed);
+    let version = reader.byte()?;
+
+    if version != 1 && version != VERSION {
+        return Err(SealError::Malformed);
+    }
+
+    let algorithm = AeadAlgorithm::from_id(reader.byte()?).ok_or(SealError::UnknownAlgorithm)?;
+    let (key_id, created) = if version == VERSION {
+        let key_id = KeyId::from_bytes(reader.array()?);
+        let created = UNIX_EPOCH
+            .checked_add(Duration::from_secs(reader.u64()?))
+            .ok_or(SealError::Malformed)?;
+
+        (Some(key_id), Some(created))
+    } else {
+        (None, None)
+    };
+
+    let header = &sealed[..sealed.len() - reader.len()];
+    let nonce = reader.bytes(algorithm.nonce_size())?;
+
+    if reader.len() < TAG_SIZE {
+        return Err(SealError::Malformed);
+    }
+
+    Ok(Parts {
+        version,
+        algorithm,
+        key_id,
+        created,
+        header,
+        nonce,
+        ct: reader.rest(),
+    })
+}
+
+/// Reads the header of a sealed message, for inspecting it without decrypting.
+pub fn parse_header(sealed: &[u8]) -> Result<SealHeader, SealError> {
+    let parts = split(sealed)?;
+
+    Ok(SealHeader {
+        version: parts.version,
+        algorithm: parts.algorithm,
+        key_id: parts.key_id,
+        created: parts.created,
+        nonce: parts.nonce.to_vec(),
+        message_size: parts.ct.len() - TAG_SIZE,
+    })
+}
+
+/// Decrypts the output of `seal` or `seal_with`, whichever algorithm it names.
+pub fn open(key: &SymmetricKey, sealed: &[u8], ad: &[u8]) -> Result<Vec<u8>, SealError> {
+    let Parts {
+        algorithm,
+        key_id,
+        header,
+        nonce,
+        ct,
+        ..
+    } = split(sealed)?;
+
+    if key_id.is_some_and(|key_id| key_id != KeyId::new(key.as_bytes())) {
+        return Err(SealError::WrongKey);
+    }
+
+    let ad = [header, ad].concat();
+    let key = key.as_bytes();
+
+    match algorithm {
+        AeadAlgorithm::XChaCha20Poly1305 => XChaCha20Poly1305::new(key).decrypt(ct, nonce, &ad),
+        AeadAlgorithm::ChaCha20Poly1305 => ChaCha20Poly1305::new(key).decrypt(ct, nonce, &ad),
+        AeadAlgorithm::AesGcm => AesGcm::new(key).unwrap().decrypt(ct, nonce, &ad),
+    }
+    .map_err(|_| SealError::InvalidMac)
+}
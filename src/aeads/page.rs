@@ -0,0 +1,122 @@
+// Page-level encryption for database storage engines, in the style of SQLite codecs. Every page
+// keeps its size on disk: the last RESERVED_SIZE bytes of each page hold the generation it was
+// written in and the tag, so the engine hands over PAGE_SIZE - RESERVED_SIZE bytes of data.
+//
+// A page's nonce is its page number and a generation counter that goes up with every write, so
+// rewriting a page never reuses a nonce, and the tag binds the page to its number. A new codec
+// starts the counter at a random 63-bit value, so reopening a database and writing before
+// reading anything back does not repeat the generations of an earlier session.
+
+use crate::aeads::ChaCha20Poly1305;
+use crate::errors::InvalidMac;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{random_bytes, redacted_debug};
+use zeroize::Zeroize;
+
+pub const RESERVED_SIZE: usize = 8 + ChaCha20Poly1305::TAG_SIZE;
+
+pub struct PageCodec {
+    aead: ChaCha20Poly1305,
+    page_size: usize,
+    generation: u64,
+}
+
+redacted_debug!(PageCodec, "<32 bytes, redacted>");
+
+impl PageCodec {
+    pub const KEY_SIZE: usize = 32;
+
+    /// The generation counter starts at a random value. Use `resume` to continue from a saved
+    /// one instead.
+    pub fn new(key: &[u8], page_size: usize) -> PageCodec {
+        assert_eq!(key.len(), Self::KEY_SIZE);
+        assert!(page_size > RESERVED_SIZE);
+
+        // the page size is part of the key, so pages cannot be read back at another size
+        let mut derived = Hkdf::<Sha256>::extract(&(page_size as u32).to_be_bytes(), key)
+            .expand(b"raycrypt page codec", ChaCha20Poly1305::KEY_SIZE)
+            .unwrap();
+        let aead = ChaCha20Poly1305::new(&derived);
+        derived.zeroize();
+
+        PageCodec {
+            aead,
+            page_size,
+            // the top bit stays clear, leaving at least 2^63 writes before the counter runs out
+            generation: u64::from_be_bytes(random_bytes()) >> 1,
+        }
+    }
+
+    /// Continues from a generation counter saved earlier with `generation`.
+    pub fn resume(key: &[u8], page_size: usize, generation: u64) -> PageCodec {
+        let mut codec = PageCodec::new(key, page_size);
+        codec.generation = generation;
+
+        codec
+    }
+
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// The number of bytes of data a page holds.
+    pub fn data_size(&self) -> usize {
+        self.page_size - RESERVED_SIZE
+    }
+
+    /// The generation the next page will be written in.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn nonce(page_no: u32, generation: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&page_no.to_be_bytes());
+        nonce[4..].copy_from_slice(&generation.to_be_bytes());
+
+        nonce
+    }
+
+    pub fn encrypt_page(&mut self, page_no: u32, data: &[u8]) -> Vec<u8> {
+        assert_eq!(data.len(), self.data_size());
+
+        let generation = self.generation;
+        self.generation = generation
+            .checked_add(1)
+            .expect("page generations exhausted");
+
+        let ct = self
+            .aead
+            .encrypt(data, &Self::nonce(page_no, generation), &[]);
+
+        let mut page = Vec::with_capacity(self.page_size);
+        page.extend_from_slice(ct.ciphertext());
+        page.extend_from_slice(&generation.to_be_bytes());
+        page.extend_from_slice(ct.tag());
+
+        page
+    }
+
+    /// Also moves the generation counter past the page's, so pages written after reading
+    /// existing ones never reuse their nonces.
+    pub fn decrypt_page(&mut self, page_no: u32, page: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        if page.len() != self.page_size {
+            return Err(InvalidMac);
+        }
+
+        let (ct, reserved) = page.split_at(self.data_size());
+        let (generation, tag) = reserved.split_at(8);
+        let generation = u64::from_be_bytes(generation.try_into().unwrap());
+
+        let mut sealed = ct.to_vec();
+        sealed.extend_from_slice(tag);
+        let data = self
+            .aead
+            .decrypt(&sealed, &Self::nonce(page_no, generation), &[])?;
+
+        self.generation = self.generation.max(generation.saturating_add(1));
+
+        Ok(data)
+    }
+}
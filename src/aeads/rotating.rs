@@ -0,0 +1,158 @@
+// Keys that rotate on a fixed schedule without any coordination: time is cut into epochs of
+// `period` counted from the Unix epoch, and each epoch's key is derived from a master key with
+// HKDF-SHA256, so every producer and consumer holding the master key agrees on the current key
+// from its clock alone.
+//
+// Layout: epoch (u64, big-endian) || nonce (24) || ciphertext || tag. The epoch is part of the
+// associated data. Decryption only accepts epochs within the clock skew tolerance of the current
+// time, so ciphertexts from retired epochs are refused even though their keys can be derived.
+
+use crate::aeads::{SymmetricKey, XChaCha20Poly1305};
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::redacted_debug;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zeroize::Zeroize;
+
+const SALT: &[u8] = b"raycrypt rotating key";
+const EPOCH_SIZE: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationError {
+    Malformed,
+    EpochOutOfRange,
+    InvalidMac,
+}
+
+impl std::fmt::Display for RotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RotationError::Malformed => write!(f, "The ciphertext is malformed."),
+            RotationError::EpochOutOfRange => {
+                write!(
+                    f,
+                    "The ciphertext is from an epoch outside the skew tolerance."
+                )
+            }
+            RotationError::InvalidMac => {
+                write!(
+                    f,
+                    "Invalid MAC detected. This message may be tampered with."
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RotationError {}
+
+pub struct RotatingKey {
+    hkdf: Hkdf<Sha256>,
+    period: u64,
+    skew: Duration,
+}
+
+redacted_debug!(RotatingKey, "<redacted>");
+
+impl RotatingKey {
+    /// `period` is a whole number of seconds, such as an hour. Messages are accepted if their
+    /// epoch was current at some point within `skew` of the decrypting clock.
+    pub fn new(master: &SymmetricKey, period: Duration, skew: Duration) -> RotatingKey {
+        assert!(period.as_secs() > 0 && period.subsec_nanos() == 0);
+
+        // the period is in the salt, so keys of different schedules are unrelated
+        let salt = [SALT, &period.as_secs().to_be_bytes()].concat();
+
+        RotatingKey {
+            hkdf: Hkdf::extract(&salt, master.as_bytes()),
+            period: period.as_secs(),
+            skew,
+        }
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.period)
+    }
+
+    pub fn skew(&self) -> Duration {
+        self.skew
+    }
+
+    /// The epoch `time` falls in; times before the Unix epoch are in epoch 0.
+    pub fn epoch(&self, time: SystemTime) -> u64 {
+        time.duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() / self.period)
+    }
+
+    /// When `epoch` starts.
+    pub fn epoch_start(&self, epoch: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(epoch.saturating_mul(self.period))
+    }
+
+    pub fn epoch_key(&self, epoch: u64) -> SymmetricKey {
+        let mut key = self.hkdf.expand(&epoch.to_be_bytes(), 32).unwrap();
+        let epoch_key = SymmetricKey::new(&key).unwrap();
+        key.zeroize();
+
+        epoch_key
+    }
+
+    /// The range of epochs accepted at `now`.
+    pub fn accepted_epochs(&self, now: SystemTime) -> (u64, u64) {
+        let earliest = now.checked_sub(self.skew).unwrap_or(UNIX_EPOCH);
+        let latest = now.checked_add(self.skew).unwrap_or(now);
+
+        (self.epoch(earliest), self.epoch(latest))
+    }
+
+    fn cipher(&self, epoch: u64) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.epoch_key(epoch).as_bytes())
+    }
+
+    /// Encrypts under the key of the epoch `now` falls in.
+    pub fn encrypt(&self, msg: &[u8], ad: &[u8], now: SystemTime) -> Vec<u8> {
+        let epoch = self.epoch(now);
+        let header = epoch.to_be_bytes();
+        let nonce = XChaCha20Poly1305::generate_nonce();
+        let ct = self
+            .cipher(epoch)
+            .encrypt(msg, &nonce, &[&header, ad].concat());
+
+        let mut output = Vec::with_capacity(EPOCH_SIZE + nonce.len() + ct.len());
+        output.extend_from_slice(&header);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ct);
+
+        output
+    }
+
+    /// The epoch a ciphertext claims to be from, before it is authenticated.
+    pub fn ciphertext_epoch(sealed: &[u8]) -> Result<u64, RotationError> {
+        if sealed.len() < EPOCH_SIZE + XChaCha20Poly1305::NONCE_SIZE + XChaCha20Poly1305::TAG_SIZE {
+            return Err(RotationError::Malformed);
+        }
+
+        Ok(u64::from_be_bytes(sealed[..EPOCH_SIZE].try_into().unwrap()))
+    }
+
+    pub fn decrypt(
+        &self,
+        sealed: &[u8],
+        ad: &[u8],
+        now: SystemTime,
+    ) -> Result<Vec<u8>, RotationError> {
+        let epoch = Self::ciphertext_epoch(sealed)?;
+        let (earliest, latest) = self.accepted_epochs(now);
+
+        if epoch < earliest || epoch > latest {
+            return Err(RotationError::EpochOutOfRange);
+        }
+
+        let (header, rest) = sealed.split_at(EPOCH_SIZE);
+        let (nonce, ct) = rest.split_at(XChaCha20Poly1305::NONCE_SIZE);
+
+        self.cipher(epoch)
+            .decrypt(ct, nonce, &[header, ad].concat())
+            .map_err(|_| RotationError::InvalidMac)
+    }
+}
@@ -0,0 +1,288 @@
+use crate::aeads::XChaCha20Poly1305;
+use crate::ciphers::chacha::HChaCha20;
+use crate::utils::redacted_debug;
+use zeroize::Zeroize;
+
+pub const PREFIX_LENGTH: usize = 19;
+pub const TAG_LENGTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamError {
+    InvalidMac,
+    Truncated,
+    CounterOverflow,
+    /// The chunk size passed to `encrypt` or `decrypt` is zero, or too large to add a tag to.
+    InvalidChunkSize,
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This message may be tampered with."
+            ),
+            StreamError::Truncated => write!(f, "The stream ended before its final chunk."),
+            StreamError::CounterOverflow => write!(f, "The stream has too many chunks."),
+            StreamError::InvalidChunkSize => write!(f, "The chunk size is zero or too large."),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+struct Stream {
+    key: [u8; 32],
+    aead: XChaCha20Poly1305,
+    prefix: [u8; PREFIX_LENGTH],
+    counter: u32,
+    overflowed: bool,
+    messages: u64,
+    bytes: u64,
+    rekey_messages: u64,
+    rekey_bytes: u64,
+}
+
+impl Stream {
+    fn new(key: &[u8], prefix: &[u8]) -> Stream {
+        Stream {
+            key: key.try_into().unwrap(),
+            aead: XChaCha20Poly1305::new(key),
+            prefix: prefix.try_into().unwrap(),
+            counter: 0,
+            overflowed: false,
+            messages: 0,
+            bytes: 0,
+            rekey_messages: u64::MAX,
+            rekey_bytes: u64::MAX,
+        }
+    }
+
+    fn nonce(&self, last: bool) -> [u8; 24] {
+        let mut nonce = [0u8; 24];
+        nonce[..PREFIX_LENGTH].copy_from_slice(&self.prefix);
+        nonce[PREFIX_LENGTH..23].copy_from_slice(&self.counter.to_be_bytes());
+        nonce[23] = last as u8;
+
+        nonce
+    }
+
+    fn check(&self) -> Result<(), StreamError> {
+        if self.overflowed {
+            return Err(StreamError::CounterOverflow);
+        }
+
+        Ok(())
+    }
+
+    fn advance(&mut self, length: usize) {
+        (self.counter, self.overflowed) = self.counter.overflowing_add(1);
+
+        self.messages += 1;
+        self.bytes = self.bytes.saturating_add(length as u64);
+
+        if self.messages >= self.rekey_messages || self.bytes >= self.rekey_bytes {
+            self.rekey();
+        }
+    }
+
+    fn rekey(&mut self) {
+        let mut input = [0u8; 16];
+        input[..12].copy_from_slice(&self.prefix[..12]);
+        input[12..].copy_from_slice(&self.counter.to_be_bytes());
+
+        let mut key = HChaCha20::new(&self.key).keystream(&input);
+        self.key.zeroize();
+        self.key = key;
+        self.aead = XChaCha20Poly1305::new(&self.key);
+        key.zeroize();
+
+        self.counter = 0;
+        self.overflowed = false;
+        self.messages = 0;
+        self.bytes = 0;
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+pub struct StreamEncryptor {
+    stream: Stream,
+}
+
+redacted_debug!(StreamEncryptor, "<32 bytes, redacted>");
+
+impl StreamEncryptor {
+    pub fn new(key: &[u8], prefix: &[u8]) -> StreamEncryptor {
+        StreamEncryptor {
+            stream: Stream::new(key, prefix),
+        }
+    }
+
+    pub fn rekey_after_messages(mut self, messages: u64) -> StreamEncryptor {
+        self.stream.rekey_messages = messages;
+        self
+    }
+
+    pub fn rekey_after_bytes(mut self, bytes: u64) -> StreamEncryptor {
+        self.stream.rekey_bytes = bytes;
+        self
+    }
+
+    pub fn rekey(&mut self) {
+        self.stream.rekey();
+    }
+
+    pub fn encrypt_next(&mut self, chunk: &[u8], ad: &[u8]) -> Result<Vec<u8>, StreamError> {
+        self.stream.check()?;
+
+        let ct = self
+            .stream
+            .aead
+            .encrypt(chunk, &self.stream.nonce(false), ad)
+            .into_vec();
+        self.stream.advance(chunk.len());
+
+        Ok(ct)
+    }
+
+    pub fn encrypt_last(self, chunk: &[u8], ad: &[u8]) -> Result<Vec<u8>, StreamError> {
+        self.stream.check()?;
+
+        Ok(self
+            .stream
+            .aead
+            .encrypt(chunk, &self.stream.nonce(true), ad)
+            .into_vec())
+    }
+}
+
+pub struct StreamDecryptor {
+    stream: Stream,
+}
+
+redacted_debug!(StreamDecryptor, "<32 bytes, redacted>");
+
+impl StreamDecryptor {
+    pub fn new(key: &[u8], prefix: &[u8]) -> StreamDecryptor {
+        StreamDecryptor {
+            stream: Stream::new(key, prefix),
+        }
+    }
+
+    pub fn rekey_after_messages(mut self, messages: u64) -> StreamDecryptor {
+        self.stream.rekey_messages = messages;
+        self
+    }
+
+    pub fn rekey_after_bytes(mut self, bytes: u64) -> StreamDecryptor {
+        self.stream.rekey_bytes = bytes;
+        self
+    }
+
+    pub fn rekey(&mut self) {
+        self.stream.rekey();
+    }
+
+    pub fn decrypt_next(&mut self, chunk: &[u8], ad: &[u8]) -> Result<Vec<u8>, StreamError> {
+        self.stream.check()?;
+
+        let msg = self
+            .stream
+            .aead
+            .decrypt(chunk, &self.stream.nonce(false), ad)
+            .map_err(|_| StreamError::InvalidMac)?;
+        self.stream.advance(msg.len());
+
+        Ok(msg)
+    }
+
+    pub fn decrypt_last(self, chunk: &[u8], ad: &[u8]) -> Result<Vec<u8>, StreamError> {
+        self.stream.check()?;
+
+        if let Ok(msg) = self
+            .stream
+            .aead
+            .decrypt(chunk, &self.stream.nonce(true), ad)
+        {
+            return Ok(msg);
+        }
+
+        // a chunk that authenticates as a middle chunk means the stream was cut short
+        match self
+            .stream
+            .aead
+            .decrypt(chunk, &self.stream.nonce(false), ad)
+        {
+            Ok(_) => Err(StreamError::Truncated),
+            Err(_) => Err(StreamError::InvalidMac),
+        }
+    }
+}
+
+pub fn encrypt(
+    key: &[u8],
+    prefix: &[u8],
+    msg: &[u8],
+    ad: &[u8],
+    chunk_size: usize,
+) -> Result<Vec<u8>, StreamError> {
+    if chunk_size == 0 {
+        return Err(StreamError::InvalidChunkSize);
+    }
+
+    let mut encryptor = StreamEncryptor::new(key, prefix);
+    let mut output = Vec::with_capacity(msg.len() + (msg.len() / chunk_size + 1) * TAG_LENGTH);
+
+    let mut chunks = msg.chunks(chunk_size).peekable();
+
+    while let Some(chunk) = chunks.next() {
+        if chunks.peek().is_none() {
+            output.extend(encryptor.encrypt_last(chunk, ad)?);
+            return Ok(output);
+        }
+
+        output.extend(encryptor.encrypt_next(chunk, ad)?);
+    }
+
+    output.extend(encryptor.encrypt_last(&[], ad)?);
+
+    Ok(output)
+}
+
+pub fn decrypt(
+    key: &[u8],
+    prefix: &[u8],
+    ct: &[u8],
+    ad: &[u8],
+    chunk_size: usize,
+) -> Result<Vec<u8>, StreamError> {
+    let sealed_size = match chunk_size.checked_add(TAG_LENGTH) {
+        Some(sealed_size) if chunk_size > 0 => sealed_size,
+        _ => return Err(StreamError::InvalidChunkSize),
+    };
+
+    if ct.is_empty() {
+        return Err(StreamError::Truncated);
+    }
+
+    let mut decryptor = StreamDecryptor::new(key, prefix);
+    let mut output = Vec::with_capacity(ct.len());
+
+    let mut chunks = ct.chunks(sealed_size).peekable();
+
+    while let Some(chunk) = chunks.next() {
+        if chunks.peek().is_none() {
+            output.extend(decryptor.decrypt_last(chunk, ad)?);
+            break;
+        }
+
+        output.extend(decryptor.decrypt_next(chunk, ad)?);
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,328 @@
+// t-of-n envelopes: the data key comes from a secret that is Shamir-shared over the
+// edwards25519 scalar field, with Feldman commitments (a_j G for each polynomial coefficient) so
+// that every share can be checked before it is used. Each share is sealed to one recipient's
+// X25519 key, so any `threshold` recipients can open the envelope together and fewer learn nothing.
+
+use crate::aeads::{ChaCha20Poly1305, XChaCha20Poly1305};
+use crate::ecc::edwards::EdwardsPoint;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::x25519::{self, PrivateKey, PublicKey};
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{random_bytes, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// recipient public key, ephemeral public key, encrypted share
+const SLOT_SIZE: usize = 32 + 32 + 32 + ChaCha20Poly1305::TAG_SIZE;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThresholdError {
+    InvalidThreshold,
+    Malformed,
+    NotARecipient,
+    InvalidShare,
+    DuplicateShare,
+    NotEnoughShares,
+    InvalidMac,
+}
+
+impl std::fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThresholdError::InvalidThreshold => write!(
+                f,
+                "The threshold must be between 1 and the number of recipients."
+            ),
+            ThresholdError::Malformed => write!(f, "The envelope is malformed."),
+            ThresholdError::NotARecipient => write!(f, "The key is not one of the recipients."),
+            ThresholdError::InvalidShare => {
+                write!(f, "The share does not match the envelope's commitments.")
+            }
+            ThresholdError::DuplicateShare => write!(f, "The same share was given twice."),
+            ThresholdError::NotEnoughShares => write!(f, "More shares are needed to decrypt."),
+            ThresholdError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This message may be tampered with."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ThresholdError {}
+
+/// A recipient's decrypted share, to be passed to whoever combines them with `open`.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct DecryptionShare {
+    index: u8,
+    value: Scalar,
+}
+
+redacted_debug!(DecryptionShare, "<redacted>");
+
+impl DecryptionShare {
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn to_bytes(&self) -> [u8; 33] {
+        let mut bytes = [0u8; 33];
+        bytes[0] = self.index;
+        bytes[1..].copy_from_slice(&self.value.to_bytes());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<DecryptionShare, ThresholdError> {
+        if bytes.len() != 33 || bytes[0] == 0 {
+            return Err(ThresholdError::Malformed);
+        }
+
+        let value = Scalar::from_canonical_bytes(bytes[1..].try_into().unwrap())
+            .ok_or(ThresholdError::Malformed)?;
+
+        Ok(DecryptionShare {
+            index: bytes[0],
+            value,
+        })
+    }
+}
+
+fn random_scalar() -> Scalar {
+    let mut bytes: [u8; 64] = random_bytes();
+    let scalar = Scalar::from_bytes_mod_order_wide(&bytes);
+    bytes.zeroize();
+
+    scalar
+}
+
+// the key sealing one share, from the X25519 secret between the sender's ephemeral key and the
+// recipient
+fn share_key(shared: &[u8; 32], ephemeral: &PublicKey, recipient: &PublicKey) -> ChaCha20Poly1305 {
+    let hkdf = Hkdf::<Sha256>::extract(&[&ephemeral[..], recipient].concat(), shared);
+
+    let mut key = hkdf.expand(b"raycrypt threshold share", 32).unwrap();
+    let aead = ChaCha20Poly1305::new(&key);
+    key.zeroize();
+
+    aead
+}
+
+fn data_key(secret: &Scalar, header: &[u8]) -> XChaCha20Poly1305 {
+    let mut secret = secret.to_bytes();
+    let hkdf = Hkdf::<Sha256>::extract(b"raycrypt threshold", &secret);
+    secret.zeroize();
+
+    let mut key = hkdf.expand(header, 32).unwrap();
+    let aead = XChaCha20Poly1305::new(&key);
+    key.zeroize();
+
+    aead
+}
+
+struct Envelope<'a> {
+    // threshold, recipient count and commitments, authenticated everywhere
+    header: &'a [u8],
+    commitments: Vec<EdwardsPoint>,
+    slots: &'a [u8],
+    nonce: &'a [u8],
+    ct: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(envelope: &'a [u8]) -> Result<Envelope<'a>, ThresholdError> {
+        if envelope.len() < 2 {
+            return Err(ThresholdError::Malformed);
+        }
+
+        let (threshold, recipients) = (envelope[0] as usize, envelope[1] as usize);
+        let header_size = 2 + 32 * threshold;
+        let body_size = header_size + SLOT_SIZE * recipients + XChaCha20Poly1305::NONCE_SIZE;
+
+        if threshold == 0
+            || threshold > recipients
+            || envelope.len() < body_size + XChaCha20Poly1305::TAG_SIZE
+        {
+            return Err(ThresholdError::Malformed);
+        }
+
+        let (header, rest) = envelope.split_at(header_size);
+        let (slots, rest) = rest.split_at(SLOT_SIZE * recipients);
+        let (nonce, ct) = rest.split_at(XChaCha20Poly1305::NONCE_SIZE);
+
+        let commitments = header[2..]
+            .chunks_exact(32)
+            .map(|point| EdwardsPoint::decompress(point.try_into().unwrap()))
+            .collect::<Option<Vec<EdwardsPoint>>>()
+            .ok_or(ThresholdError::Malformed)?;
+
+        Ok(Envelope {
+            header,
+            commitments,
+            slots,
+            nonce,
+            ct,
+        })
+    }
+
+    fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+
+    // Feldman's check: f(i) G must equal the sum of C_j i^j
+    fn verify(&self, share: &DecryptionShare) -> Result<(), ThresholdError> {
+        if share.index as usize > self.slots.len() / SLOT_SIZE {
+            return Err(ThresholdError::InvalidShare);
+        }
+
+        let x = Scalar::from_u64(share.index as u64);
+        let expected = self
+            .commitments
+            .iter()
+            .rev()
+            .fold(EdwardsPoint::identity(), |sum, commitment| {
+                sum.mul(&x).add(commitment)
+            });
+
+        if EdwardsPoint::mul_base(&share.value) != expected {
+            return Err(ThresholdError::InvalidShare);
+        }
+
+        Ok(())
+    }
+}
+
+/// Encrypts `msg` so that any `threshold` of `recipients` can decrypt it together. The envelope
+/// lists the recipients' public keys in the clear.
+pub fn seal(
+    recipients: &[PublicKey],
+    threshold: u8,
+    msg: &[u8],
+    ad: &[u8],
+) -> Result<Vec<u8>, ThresholdError> {
+    if threshold == 0 || threshold as usize > recipients.len() || recipients.len() > 255 {
+        return Err(ThresholdError::InvalidThreshold);
+    }
+
+    let mut coefficients: Vec<Scalar> = (0..threshold).map(|_| random_scalar()).collect();
+
+    let mut envelope = vec![threshold, recipients.len() as u8];
+
+    for coefficient in &coefficients {
+        envelope.extend_from_slice(&EdwardsPoint::mul_base(coefficient).compress());
+    }
+
+    let header = envelope.clone();
+
+    for (i, recipient) in recipients.iter().enumerate() {
+        let x = Scalar::from_u64(i as u64 + 1);
+        let mut share = coefficients
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |y, coefficient| y * x + *coefficient)
+            .to_bytes();
+
+        let ephemeral = x25519::keygen(None);
+        let ephemeral_public = ephemeral.public_key();
+        let mut shared = ephemeral.exchange(*recipient);
+
+        let aead = share_key(&shared, &ephemeral_public, recipient);
+        shared.zeroize();
+
+        envelope.extend_from_slice(recipient);
+        envelope.extend_from_slice(&ephemeral_public);
+        envelope.extend_from_slice(&aead.encrypt(&share, &[0u8; 12], &header));
+        share.zeroize();
+    }
+
+    let aead = data_key(&coefficients[0], &header);
+    coefficients.zeroize();
+
+    let nonce = XChaCha20Poly1305::generate_nonce();
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&aead.encrypt(msg, &nonce, &[&header[..], ad].concat()));
+
+    Ok(envelope)
+}
+
+/// Decrypts `recipient`'s share of the envelope and checks it against the commitments.
+pub fn decrypt_share(
+    envelope: &[u8],
+    recipient: &PrivateKey,
+) -> Result<DecryptionShare, ThresholdError> {
+    let envelope = Envelope::parse(envelope)?;
+    let public = recipient.public_key();
+
+    let (index, slot) = envelope
+        .slots
+        .chunks_exact(SLOT_SIZE)
+        .enumerate()
+        .find(|(_, slot)| slot[..32] == public)
+        .ok_or(ThresholdError::NotARecipient)?;
+
+    let ephemeral: PublicKey = slot[32..64].try_into().unwrap();
+    let mut shared = recipient.exchange(ephemeral);
+    let aead = share_key(&shared, &ephemeral, &public);
+    shared.zeroize();
+
+    let mut value = aead
+        .decrypt(&slot[64..], &[0u8; 12], envelope.header)
+        .map_err(|_| ThresholdError::InvalidMac)?;
+
+    let scalar = Scalar::from_canonical_bytes(value[..].try_into().unwrap());
+    value.zeroize();
+
+    let share = DecryptionShare {
+        index: index as u8 + 1,
+        value: scalar.ok_or(ThresholdError::InvalidShare)?,
+    };
+
+    envelope.verify(&share)?;
+
+    Ok(share)
+}
+
+/// Checks every share against the envelope's commitments, so a bad share is caught before it
+/// can corrupt the key, and decrypts once there are enough of them.
+pub fn open(
+    envelope: &[u8],
+    shares: &[DecryptionShare],
+    ad: &[u8],
+) -> Result<Vec<u8>, ThresholdError> {
+    let envelope = Envelope::parse(envelope)?;
+
+    for (i, share) in shares.iter().enumerate() {
+        envelope.verify(share)?;
+
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(ThresholdError::DuplicateShare);
+        }
+    }
+
+    if shares.len() < envelope.threshold() {
+        return Err(ThresholdError::NotEnoughShares);
+    }
+
+    // Lagrange interpolation at 0 over the first `threshold` shares
+    let shares = &shares[..envelope.threshold()];
+    let mut secret = Scalar::ZERO;
+
+    for share in shares {
+        let mut numerator = Scalar::ONE;
+        let mut denominator = Scalar::ONE;
+
+        for other in shares.iter().filter(|other| other.index != share.index) {
+            let x = Scalar::from_u64(other.index as u64);
+
+            numerator = numerator * x;
+            denominator = denominator * (x - Scalar::from_u64(share.index as u64));
+        }
+
+        secret = secret + share.value * numerator * denominator.invert();
+    }
+
+    let aead = data_key(&secret, envelope.header);
+    secret.zeroize();
+
+    aead.decrypt(envelope.ct, envelope.nonce, &[envelope.header, ad].concat())
+        .map_err(|_| ThresholdError::InvalidMac)
+}
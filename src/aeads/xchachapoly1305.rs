@@ -1,37 +1,72 @@
 use crate::aeads::chachapoly1305::ChaCha20Poly1305;
+use crate::aeads::{Ciphertext, SymmetricKey};
+use crate::audit::{self, KeyId, Operation};
 use crate::ciphers::chacha::HChaCha20;
 use crate::errors::InvalidMac;
+use crate::utils::{random_bytes, redacted_debug};
+use zeroize::Zeroize;
 
 pub struct XChaCha20Poly1305 {
     hchacha: HChaCha20,
+    key_id: Option<KeyId>,
 }
 
+redacted_debug!(XChaCha20Poly1305, "<32 bytes, redacted>");
+
 impl XChaCha20Poly1305 {
+    pub const KEY_SIZE: usize = 32;
+    pub const NONCE_SIZE: usize = 24;
+    pub const TAG_SIZE: usize = 16;
+
     pub fn new(key: &[u8]) -> XChaCha20Poly1305 {
         XChaCha20Poly1305 {
             hchacha: HChaCha20::new(key),
+            key_id: audit::key_id(key),
         }
     }
 
-    fn subkey(&self, nonce: &[u8]) -> ([u8; 32], [u8; 12]) {
-        let subkey = self.hchacha.keystream(nonce);
+    pub fn generate_key() -> SymmetricKey {
+        SymmetricKey::generate()
+    }
+
+    /// A random nonce; 192 bits is large enough that collisions are not a concern.
+    pub fn generate_nonce() -> [u8; Self::NONCE_SIZE] {
+        random_bytes()
+    }
+
+    /// The ChaCha20-Poly1305 key and nonce that a 24-byte nonce maps to. The key depends only on
+    /// the first 16 bytes, so it can be cached and reused with `ChaCha20Poly1305` for every nonce
+    /// sharing that prefix.
+    pub fn derive_subkey(&self, nonce: &[u8]) -> (SymmetricKey, [u8; 12]) {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+
+        let mut subkey = self.hchacha.keystream(&nonce[..16]);
+        let key = SymmetricKey::new(&subkey).unwrap();
+        subkey.zeroize();
+
+        let mut short_nonce = [0u8; 12];
+        short_nonce[4..].copy_from_slice(&nonce[16..]);
 
-        (subkey, [&[0u8; 4], &nonce[16..24]].concat().try_into().unwrap())
+        (key, short_nonce)
     }
 
-    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Vec<u8> {
-        let (subkey, encryption_nonce) = self.subkey(nonce);
+    pub fn encrypt(&self, msg: &[u8], nonce: &[u8], ad: &[u8]) -> Ciphertext {
+        let (subkey, encryption_nonce) = self.derive_subkey(nonce);
+        let chacha = ChaCha20Poly1305::with_key_id(subkey.as_bytes(), None);
 
-        let chacha = ChaCha20Poly1305::new(&subkey);
+        let ct = chacha.seal(msg, &encryption_nonce, ad);
+        audit::record(Operation::Encrypt, || self.key_id, true);
 
-        chacha.encrypt(msg, &encryption_nonce, ad)
+        ct
     }
 
     pub fn decrypt(&self, ct: &[u8], nonce: &[u8], ad: &[u8]) -> Result<Vec<u8>, InvalidMac> {
-        let (subkey, encryption_nonce) = self.subkey(nonce);
+        let (subkey, encryption_nonce) = self.derive_subkey(nonce);
+        let chacha = ChaCha20Poly1305::with_key_id(subkey.as_bytes(), None);
 
-        let chacha = ChaCha20Poly1305::new(&subkey);
+        let msg = chacha.open(ct, &encryption_nonce, ad);
+        audit::record(Operation::Decrypt, || self.key_id, msg.is_ok());
 
-        chacha.decrypt(ct, &encryption_nonce, ad)
+        msg
     }
 }
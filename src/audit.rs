@@ -0,0 +1,104 @@
+// A process-wide hook for key usage. Without a sink installed, each key use costs one atomic
+// load and key ids are never computed.
+
+use crate::encoding::hex;
+use crate::hashes::sha256::Sha256;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+static SINK: OnceLock<Box<dyn AuditSink>> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Encrypt,
+    Decrypt,
+    Sign,
+    Verify,
+    KeyExchange,
+}
+
+/// A short, non-secret identifier for a key: the first 8 bytes of a domain-separated SHA-256 of
+/// the key. Asymmetric keys are identified by their public key, so a signing key and its
+/// verifying key share an id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyId([u8; 8]);
+
+impl KeyId {
+    pub fn new(key: &[u8]) -> KeyId {
+        let mut hasher = Sha256::new();
+        hasher.update(b"raycrypt key id");
+        hasher.update(key);
+
+        KeyId(hasher.digest()[..8].try_into().unwrap())
+    }
+
+    /// A key id read back from storage.
+    pub fn from_bytes(bytes: [u8; 8]) -> KeyId {
+        KeyId(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        &self.0
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub operation: Operation,
+    /// `None` for symmetric keys set up before the sink was installed.
+    pub key_id: Option<KeyId>,
+    pub timestamp: SystemTime,
+    pub success: bool,
+}
+
+/// Receives an event for every key use. It is called on the thread doing the operation, so it
+/// should hand events off rather than block.
+pub trait AuditSink: Send + Sync {
+    fn record(&self, event: &AuditEvent);
+}
+
+impl<F: Fn(&AuditEvent) + Send + Sync> AuditSink for F {
+    fn record(&self, event: &AuditEvent) {
+        self(event)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SinkAlreadySet;
+
+impl std::fmt::Display for SinkAlreadySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "An audit sink has already been installed.")
+    }
+}
+
+impl std::error::Error for SinkAlreadySet {}
+
+/// Installs the process-wide sink. It can only be set once, so that audit logging cannot be
+/// quietly switched off later.
+pub fn set_sink(sink: impl AuditSink + 'static) -> Result<(), SinkAlreadySet> {
+    SINK.set(Box::new(sink)).map_err(|_| SinkAlreadySet)
+}
+
+pub fn is_enabled() -> bool {
+    SINK.get().is_some()
+}
+
+pub(crate) fn key_id(key: &[u8]) -> Option<KeyId> {
+    SINK.get().map(|_| KeyId::new(key))
+}
+
+pub(crate) fn record(operation: Operation, key_id: impl FnOnce() -> Option<KeyId>, success: bool) {
+    if let Some(sink) = SINK.get() {
+        sink.record(&AuditEvent {
+            operation,
+            key_id: key_id(),
+            timestamp: SystemTime::now(),
+            success,
+        });
+    }
+}
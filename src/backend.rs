@@ -0,0 +1,320 @@
+use std::sync::OnceLock;
+
+/// Environment variable that forces a backend, read once on first use.
+pub const BACKEND_ENV: &str = "RAYCRYPT_BACKEND";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Backend {
+    Scalar,
+    Sse2,
+    Avx2,
+    Avx512,
+    Neon,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 5] = [
+        Backend::Scalar,
+        Backend::Sse2,
+        Backend::Avx2,
+        Backend::Avx512,
+        Backend::Neon,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Scalar => "scalar",
+            Backend::Sse2 => "sse2",
+            Backend::Avx2 => "avx2",
+            Backend::Avx512 => "avx512",
+            Backend::Neon => "neon",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Backend> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "scalar" | "fallback" => Some(Backend::Scalar),
+            "sse" | "sse2" => Some(Backend::Sse2),
+            "avx2" => Some(Backend::Avx2),
+            "avx512" => Some(Backend::Avx512),
+            "neon" => Some(Backend::Neon),
+            _ => None,
+        }
+    }
+
+    /// Whether the running CPU has the instructions this backend needs.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx512 => is_x86_feature_detected!("avx512f"),
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendInfo {
+    /// Backends the running CPU supports.
+    pub supported: Vec<Backend>,
+    /// Backend requested through `RAYCRYPT_BACKEND`, if it named a known one.
+    pub requested: Option<Backend>,
+    pub chacha20: Backend,
+    pub aes: &'static str,
+    pub sha256: &'static str,
+    pub aegis256: &'static str,
+}
+
+struct Selection {
+    requested: Option<Backend>,
+    chacha20: Backend,
+    hardware_aes: bool,
+    hardware_sha256: bool,
+}
+
+// chosen once per process and shared by every caller, whatever the environment says later
+fn select() -> &'static Selection {
+    static SELECTION: OnceLock<Selection> = OnceLock::new();
+
+    SELECTION.get_or_init(|| {
+        let requested = std::env::var(BACKEND_ENV)
+            .ok()
+            .and_then(|name| Backend::from_name(&name));
+
+        // an unsupported request falls back to the best supported backend
+        let preferred = requested
+            .filter(|backend| backend.is_supported())
+            .or_else(|| {
+                Backend::ALL
+                    .into_iter()
+                    .rev()
+                    .find(|backend| backend.is_supported())
+            })
+            .unwrap_or(Backend::Scalar);
+
+        Selection {
+            requested,
+            chacha20: crate::ciphers::chacha::detect::kernel(preferred),
+            hardware_aes: preferred != Backend::Scalar && crate::ciphers::aes::hardware_supported(),
+            hardware_sha256: preferred != Backend::Scalar
+                && crate::hashes::sha256::hardware_supported(),
+        }
+    })
+}
+
+pub(crate) fn chacha20() -> Backend {
+    select().chacha20
+}
+
+pub(crate) fn hardware_aes() -> bool {
+    select().hardware_aes
+}
+
+pub(crate) fn hardware_sha256() -> bool {
+    select().hardware_sha256
+}
+
+pub fn backend_info() -> BackendInfo {
+    let selection = select();
+
+    BackendInfo {
+        supported: Backend::ALL
+            .into_iter()
+            .filter(|backend| backend.is_supported())
+            .collect(),
+        requested: selection.requested,
+        chacha20: selection.chacha20,
+        aes: match selection.hardware_aes {
+            true if cfg!(target_arch = "aarch64") => "armcrypto",
+            true => "aesni",
+            false => "bitsliced",
+        },
+        sha256: match selection.hardware_sha256 {
+            true if cfg!(target_arch = "aarch64") => "armsha2",
+            true => "shani",
+            false => "scalar",
+        },
+        aegis256: crate::aeads::aegis256::backend(),
+    }
+}
+
+const ALGORITHMS: &[&str] = &[
+    "aegis256",
+    "aes-gcm",
+    "aes-ocb",
+    "aes-ctr",
+    "chacha20",
+    "chacha20-poly1305",
+    "xchacha20-poly1305",
+    "salsa20",
+    "xsalsa20",
+    "poly1305",
+    "ghash",
+    "polyval",
+    "hmac",
+    "sha256",
+    "sha512",
+    "blake2b",
+    "blake2s",
+    "blake3",
+    "hkdf",
+    "pbkdf2",
+    "argon2",
+    "x25519",
+    "ed25519",
+];
+
+/// What this build of raycrypt contains and what the running CPU offers, for branching on and
+/// for bug reports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Features {
+    pub version: &'static str,
+    pub target_arch: &'static str,
+    /// Algorithms compiled in, including those behind optional features.
+    pub algorithms: Vec<&'static str>,
+    /// Optional Cargo features compiled in.
+    pub optional: Vec<&'static str>,
+    /// CPU extensions detected at runtime, whether or not `RAYCRYPT_BACKEND` lets them be used.
+    pub cpu: Vec<&'static str>,
+    pub backend: BackendInfo,
+}
+
+impl Features {
+    /// Whether `name` is a compiled-in algorithm, an optional feature or a detected CPU extension.
+    pub fn has(&self, name: &str) -> bool {
+        self.algorithms
+            .iter()
+            .chain(&self.optional)
+            .chain(&self.cpu)
+            .any(|feature| *feature == name)
+    }
+
+    /// Whether AES and GHASH run on AES-NI or the ARMv8 crypto extensions.
+    pub fn hardware_aes(&self) -> bool {
+        self.backend.aes != "bitsliced"
+    }
+
+    pub fn hardware_sha256(&self) -> bool {
+        self.backend.sha256 != "scalar"
+    }
+}
+
+impl std::fmt::Display for Features {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let backend = &self.backend;
+        let names = |list: &[&str]| {
+            if list.is_empty() {
+                "none".to_string()
+            } else {
+                list.join(" ")
+            }
+        };
+
+        writeln!(f, "raycrypt {} ({})", self.version, self.target_arch)?;
+        writeln!(f, "optional: {}", names(&self.optional))?;
+        writeln!(f, "cpu: {}", names(&self.cpu))?;
+        writeln!(
+            f,
+            "backends: {}",
+            backend
+                .supported
+                .iter()
+                .map(|backend| backend.name())
+                .collect::<Vec<_>>()
+                .join(" ")
+        )?;
+        if let Some(requested) = backend.requested {
+            writeln!(f, "requested: {}", requested)?;
+        }
+        writeln!(
+            f,
+            "chacha20: {}, aes: {}, sha256: {}, aegis256: {}",
+            backend.chacha20, backend.aes, backend.sha256, backend.aegis256
+        )?;
+        write!(f, "algorithms: {}", names(&self.algorithms))
+    }
+}
+
+fn optional_features() -> Vec<&'static str> {
+    let mut optional = Vec::new();
+
+    if cfg!(feature = "rayon") {
+        optional.push("rayon");
+    }
+    if cfg!(feature = "json") {
+        optional.push("json");
+    }
+    if cfg!(all(feature = "pkcs11", unix)) {
+        optional.push("pkcs11");
+    }
+
+    optional
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpu_features() -> Vec<&'static str> {
+    let detected = [
+        ("sse2", is_x86_feature_detected!("sse2")),
+        ("ssse3", is_x86_feature_detected!("ssse3")),
+        ("sse4.1", is_x86_feature_detected!("sse4.1")),
+        ("avx2", is_x86_feature_detected!("avx2")),
+        ("avx512f", is_x86_feature_detected!("avx512f")),
+        ("aes", is_x86_feature_detected!("aes")),
+        ("pclmulqdq", is_x86_feature_detected!("pclmulqdq")),
+        ("sha", is_x86_feature_detected!("sha")),
+    ];
+
+    detected
+        .into_iter()
+        .filter_map(|(name, present)| present.then_some(name))
+        .collect()
+}
+
+#[cfg(target_arch = "aarch64")]
+fn cpu_features() -> Vec<&'static str> {
+    let detected = [
+        ("neon", std::arch::is_aarch64_feature_detected!("neon")),
+        ("aes", std::arch::is_aarch64_feature_detected!("aes")),
+        ("pmull", std::arch::is_aarch64_feature_detected!("pmull")),
+        ("sha2", std::arch::is_aarch64_feature_detected!("sha2")),
+    ];
+
+    detected
+        .into_iter()
+        .filter_map(|(name, present)| present.then_some(name))
+        .collect()
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn cpu_features() -> Vec<&'static str> {
+    Vec::new()
+}
+
+pub fn features() -> Features {
+    let mut algorithms = ALGORITHMS.to_vec();
+
+    if cfg!(feature = "json") {
+        algorithms.push("jwe");
+    }
+
+    Features {
+        version: env!("CARGO_PKG_VERSION"),
+        target_arch: std::env::consts::ARCH,
+        algorithms,
+        optional: optional_features(),
+        cpu: cpu_features(),
+        backend: backend_info(),
+    }
+}
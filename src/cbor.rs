@@ -0,0 +1,413 @@
+// CBOR with the core deterministic encoding of RFC 8949, section 4.2.1: the shortest form of
+// every integer, length and float, definite lengths only, and map entries sorted by the bytes of
+// their encoded keys. Decoding is just as strict, so every value has exactly one encoding and a
+// signature over it cannot be replayed over a differently encoded copy.
+
+use crate::ecc::ed25519::{Signature, SigningKey, VerifyingKey};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+
+const MAX_DEPTH: usize = 64;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CborError {
+    Malformed,
+    NotDeterministic,
+    Unsupported,
+    DuplicateKey,
+    InvalidSignature,
+}
+
+impl std::fmt::Display for CborError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CborError::Malformed => write!(f, "The CBOR data is malformed."),
+            CborError::NotDeterministic => {
+                write!(f, "The CBOR data does not use the deterministic encoding.")
+            }
+            CborError::Unsupported => write!(f, "The CBOR data uses an unsupported feature."),
+            CborError::DuplicateKey => write!(f, "The CBOR map has a duplicate key."),
+            CborError::InvalidSignature => write!(f, "Invalid signature."),
+        }
+    }
+}
+
+impl std::error::Error for CborError {}
+
+impl From<InvalidEncoding> for CborError {
+    fn from(_: InvalidEncoding) -> CborError {
+        CborError::Malformed
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Unsigned(u64),
+    /// -1 - n, so the whole range of CBOR negative integers fits.
+    Negative(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    /// Entries may be in any order; they are sorted when encoded.
+    Map(Vec<(Value, Value)>),
+    Tag(u64, Box<Value>),
+    Bool(bool),
+    Null,
+    Undefined,
+    Float(f64),
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Value {
+        Value::Unsigned(n)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Value {
+        if n < 0 {
+            Value::Negative(!n as u64)
+        } else {
+            Value::Unsigned(n as u64)
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Value {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(bytes: &[u8]) -> Value {
+        Value::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Value {
+        Value::Bool(b)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(f: f64) -> Value {
+        Value::Float(f)
+    }
+}
+
+pub fn encode(value: &Value) -> Result<Vec<u8>, CborError> {
+    let mut output = Vec::new();
+    write_value(&mut output, value)?;
+
+    Ok(output)
+}
+
+/// Decodes `data`, which must hold exactly one value in the deterministic encoding.
+pub fn decode(data: &[u8]) -> Result<Value, CborError> {
+    let mut decoder = Decoder(Reader::new(data));
+    let value = decoder.read_value(0)?;
+    decoder.0.finish()?;
+
+    Ok(value)
+}
+
+/// An Ed25519 signature over the deterministic encoding of `value`.
+pub fn sign_cbor(key: &SigningKey, value: &Value) -> Result<Signature, CborError> {
+    Ok(key.sign(&encode(value)?))
+}
+
+pub fn verify_cbor(key: &VerifyingKey, value: &Value, signature: &[u8]) -> Result<(), CborError> {
+    key.verify(&encode(value)?, signature)
+        .map_err(|_| CborError::InvalidSignature)
+}
+
+fn write_head(output: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+
+    if argument < 24 {
+        output.push(major | argument as u8);
+    } else if argument <= u8::MAX as u64 {
+        output.extend_from_slice(&[major | 24, argument as u8]);
+    } else if argument <= u16::MAX as u64 {
+        output.push(major | 25);
+        output.extend_from_slice(&(argument as u16).to_be_bytes());
+    } else if argument <= u32::MAX as u64 {
+        output.push(major | 26);
+        output.extend_from_slice(&(argument as u32).to_be_bytes());
+    } else {
+        output.push(major | 27);
+        output.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+fn write_value(output: &mut Vec<u8>, value: &Value) -> Result<(), CborError> {
+    match value {
+        Value::Unsigned(n) => write_head(output, UNSIGNED, *n),
+        Value::Negative(n) => write_head(output, NEGATIVE, *n),
+        Value::Bytes(bytes) => {
+            write_head(output, BYTES, bytes.len() as u64);
+            output.extend_from_slice(bytes);
+        }
+        Value::Text(text) => {
+            write_head(output, TEXT, text.len() as u64);
+            output.extend_from_slice(text.as_bytes());
+        }
+        Value::Array(values) => {
+            write_head(output, ARRAY, values.len() as u64);
+
+            for value in values {
+                write_value(output, value)?;
+            }
+        }
+        Value::Map(entries) => {
+            let mut encoded = entries
+                .iter()
+                .map(|(key, value)| Ok((encode(key)?, value)))
+                .collect::<Result<Vec<_>, CborError>>()?;
+            encoded.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            if encoded.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(CborError::DuplicateKey);
+            }
+
+            write_head(output, MAP, encoded.len() as u64);
+
+            for (key, value) in encoded {
+                output.extend_from_slice(&key);
+                write_value(output, value)?;
+            }
+        }
+        Value::Tag(tag, value) => {
+            write_head(output, TAG, *tag);
+            write_value(output, value)?;
+        }
+        Value::Bool(false) => output.push(0xf4),
+        Value::Bool(true) => output.push(0xf5),
+        Value::Null => output.push(0xf6),
+        Value::Undefined => output.push(0xf7),
+        Value::Float(f) => write_float(output, *f),
+    }
+
+    Ok(())
+}
+
+// the shortest of half, single and double precision that holds the value exactly
+fn write_float(output: &mut Vec<u8>, f: f64) {
+    if f.is_nan() {
+        output.extend_from_slice(&[0xf9, 0x7e, 0x00]);
+    } else if let Some(half) = to_half(f) {
+        output.push(0xf9);
+        output.extend_from_slice(&half.to_be_bytes());
+    } else if f as f32 as f64 == f {
+        output.push(0xfa);
+        output.extend_from_slice(&(f as f32).to_be_bytes());
+    } else {
+        output.push(0xfb);
+        output.extend_from_slice(&f.to_be_bytes());
+    }
+}
+
+fn to_half(f: f64) -> Option<u16> {
+    let single = f as f32;
+
+    if single as f64 != f {
+        return None;
+    }
+
+    let bits = single.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = bits & 0x7fffff;
+
+    if single == 0.0 {
+        Some(sign)
+    } else if single.is_infinite() {
+        Some(sign | 0x7c00)
+    } else if (-14..=15).contains(&exponent) {
+        if mantissa & 0x1fff != 0 {
+            return None;
+        }
+
+        Some(sign | ((exponent + 15) as u16) << 10 | (mantissa >> 13) as u16)
+    } else if (-24..-14).contains(&exponent) {
+        // subnormal, in units of 2^-24
+        let shift = 13 + (-14 - exponent);
+        let significand = 0x800000 | mantissa;
+
+        if significand & ((1 << shift) - 1) != 0 {
+            return None;
+        }
+
+        Some(sign | (significand >> shift) as u16)
+    } else {
+        None
+    }
+}
+
+fn from_half(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = (half & 0x3ff) as f64;
+
+    let magnitude = if exponent == 0 {
+        mantissa * 2f64.powi(-24)
+    } else if exponent == 0x1f {
+        if mantissa == 0.0 {
+            f64::INFINITY
+        } else {
+            f64::NAN
+        }
+    } else {
+        (1.0 + mantissa / 1024.0) * 2f64.powi(exponent as i32 - 15)
+    };
+
+    sign * magnitude
+}
+
+struct Decoder<'a>(Reader<'a>);
+
+impl<'a> Decoder<'a> {
+    // the major type, the additional information and the argument it gives
+    fn read_head(&mut self) -> Result<(u8, u8, u64), CborError> {
+        let initial = self.0.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+
+        let argument = match info {
+            0..=23 => info as u64,
+            24..=27 => {
+                let bytes = self.0.bytes(1 << (info - 24))?;
+                bytes.iter().fold(0u64, |acc, &b| acc << 8 | b as u64)
+            }
+            31 => return Err(CborError::NotDeterministic),
+            _ => return Err(CborError::Malformed),
+        };
+
+        // floats are checked on their own, everything else must use the shortest argument
+        if major != SIMPLE {
+            let shortest = match argument {
+                0..=23 => info == argument as u8,
+                24..=0xff => info == 24,
+                0x100..=0xffff => info == 25,
+                0x10000..=0xffffffff => info == 26,
+                _ => info == 27,
+            };
+
+            if !shortest {
+                return Err(CborError::NotDeterministic);
+            }
+        }
+
+        Ok((major, info, argument))
+    }
+
+    fn read_length(&mut self, argument: u64) -> Result<&'a [u8], CborError> {
+        let length = usize::try_from(argument).map_err(|_| CborError::Malformed)?;
+
+        Ok(self.0.bytes(length)?)
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Value, CborError> {
+        if depth > MAX_DEPTH {
+            return Err(CborError::Unsupported);
+        }
+
+        let (major, info, argument) = self.read_head()?;
+
+        let value = match major {
+            UNSIGNED => Value::Unsigned(argument),
+            NEGATIVE => Value::Negative(argument),
+            BYTES => Value::Bytes(self.read_length(argument)?.to_vec()),
+            TEXT => {
+                let text = self.read_length(argument)?;
+                let text = std::str::from_utf8(text).map_err(|_| CborError::Malformed)?;
+
+                Value::Text(text.to_string())
+            }
+            ARRAY => {
+                // every element takes at least one byte
+                if argument > self.0.len() as u64 {
+                    return Err(CborError::Malformed);
+                }
+
+                let mut values = Vec::with_capacity(argument as usize);
+
+                for _ in 0..argument {
+                    values.push(self.read_value(depth + 1)?);
+                }
+
+                Value::Array(values)
+            }
+            MAP => {
+                if argument > self.0.len() as u64 {
+                    return Err(CborError::Malformed);
+                }
+
+                let mut entries = Vec::with_capacity(argument as usize);
+                let mut previous: Option<&[u8]> = None;
+
+                for _ in 0..argument {
+                    let mut start = self.0.clone();
+                    let key = self.read_value(depth + 1)?;
+                    let encoded = start.bytes(start.len() - self.0.len())?;
+
+                    if let Some(previous) = previous {
+                        if encoded == previous {
+                            return Err(CborError::DuplicateKey);
+                        }
+                        if encoded < previous {
+                            return Err(CborError::NotDeterministic);
+                        }
+                    }
+
+                    previous = Some(encoded);
+                    entries.push((key, self.read_value(depth + 1)?));
+                }
+
+                Value::Map(entries)
+            }
+            TAG => Value::Tag(argument, Box::new(self.read_value(depth + 1)?)),
+            _ => self.read_simple(info, argument)?,
+        };
+
+        Ok(value)
+    }
+
+    fn read_simple(&mut self, info: u8, argument: u64) -> Result<Value, CborError> {
+        let value = match info {
+            20 => Value::Bool(false),
+            21 => Value::Bool(true),
+            22 => Value::Null,
+            23 => Value::Undefined,
+            25 => Value::Float(from_half(argument as u16)),
+            26 => Value::Float(f32::from_bits(argument as u32) as f64),
+            27 => Value::Float(f64::from_bits(argument)),
+            _ => return Err(CborError::Unsupported),
+        };
+
+        // a float must be in the shortest form that holds it, with one encoding of NaN
+        if let Value::Float(f) = value {
+            let mut preferred = Vec::new();
+            write_float(&mut preferred, f);
+
+            let mut encoded = vec![SIMPLE << 5 | info];
+            encoded.extend_from_slice(&argument.to_be_bytes()[8 - (1 << (info - 24))..]);
+
+            if encoded != preferred {
+                return Err(CborError::NotDeterministic);
+            }
+        }
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,379 @@
+// Content-defined chunking with FastCDC (Xia et al., 2016), for deduplicating backups: chunk
+// boundaries follow the content rather than offsets, so an insertion only changes the chunks
+// around it. Each chunk is addressed by its BLAKE3 hash, and a manifest lists the addresses and
+// lengths that make up a file.
+//
+// The gear table driving the rolling hash is derived with BLAKE3, so boundaries only match other
+// users of this module. For encrypted backups, a keyed chunker derives both the table and the
+// addresses from a secret key, so neither the chunk sizes nor the addresses can be matched
+// against known files by someone without the key.
+//
+// Manifest layout: magic || version || chunk count (u64) || (address (32) || length (u32))...
+
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::hashes::blake3::{self, Blake3};
+use crate::utils::redacted_debug;
+use std::io::{Read, Write};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const MAGIC: [u8; 4] = *b"RCCM";
+const VERSION: u8 = 1;
+const GEAR_CONTEXT: &str = "raycrypt chunking gear table";
+const ADDRESS_CONTEXT: &str = "raycrypt chunking address";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkError {
+    /// The sizes are out of order, the average is not a power of two, or a size is out of range.
+    InvalidParameters,
+    Malformed,
+    /// A chunk the manifest lists could not be fetched.
+    MissingChunk,
+    /// A fetched chunk does not match its address or length.
+    CorruptChunk,
+    Io(std::io::ErrorKind),
+}
+
+impl std::fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkError::InvalidParameters => write!(f, "The chunk sizes are invalid."),
+            ChunkError::Malformed => write!(f, "The manifest is malformed."),
+            ChunkError::MissingChunk => write!(f, "A chunk of the manifest is missing."),
+            ChunkError::CorruptChunk => write!(f, "A chunk does not match its address."),
+            ChunkError::Io(kind) => write!(f, "Could not read or write the data: {}.", kind),
+        }
+    }
+}
+
+impl std::error::Error for ChunkError {}
+
+impl From<InvalidEncoding> for ChunkError {
+    fn from(_: InvalidEncoding) -> ChunkError {
+        ChunkError::Malformed
+    }
+}
+
+impl From<std::io::Error> for ChunkError {
+    fn from(error: std::io::Error) -> ChunkError {
+        ChunkError::Io(error.kind())
+    }
+}
+
+/// Splits data into chunks of between `min_size` and `max_size` bytes, `avg_size` on average.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Chunker {
+    gear: Vec<u64>,
+    address_key: Option<[u8; 32]>,
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    // normalized chunking: a stricter mask below the average size and a looser one above it
+    mask_small: u64,
+    mask_large: u64,
+}
+
+redacted_debug!(Chunker, "<redacted>");
+
+impl Chunker {
+    pub const DEFAULT_MIN_SIZE: usize = 16 * 1024;
+    pub const DEFAULT_AVG_SIZE: usize = 64 * 1024;
+    pub const DEFAULT_MAX_SIZE: usize = 256 * 1024;
+
+    /// `avg_size` must be a power of two, with 64 <= `min_size` < `avg_size` < `max_size` and
+    /// `max_size` at most 2^31.
+    pub fn new(min_size: usize, avg_size: usize, max_size: usize) -> Result<Chunker, ChunkError> {
+        let gear = Blake3::new_derive_key(GEAR_CONTEXT).digest_xof(256 * 8);
+
+        Chunker::with_gear(&gear, None, min_size, avg_size, max_size)
+    }
+
+    /// A chunker whose boundaries and addresses depend on a 32-byte secret key.
+    pub fn keyed(
+        key: &[u8; 32],
+        min_size: usize,
+        avg_size: usize,
+        max_size: usize,
+    ) -> Result<Chunker, ChunkError> {
+        let mut gear_key = blake3::derive_key(GEAR_CONTEXT, key);
+        let gear = Blake3::new_keyed(&gear_key).digest_xof(256 * 8);
+        gear_key.zeroize();
+
+        let address_key = blake3::derive_key(ADDRESS_CONTEXT, key);
+
+        Chunker::with_gear(&gear, Some(address_key), min_size, avg_size, max_size)
+    }
+
+    fn with_gear(
+        gear_bytes: &[u8],
+        address_key: Option<[u8; 32]>,
+        min_size: usize,
+        avg_size: usize,
+        max_size: usize,
+    ) -> Result<Chunker, ChunkError> {
+        if min_size < 64
+            || !avg_size.is_power_of_two()
+            || min_size >= avg_size
+            || avg_size >= max_size
+            || max_size > 1 << 31
+        {
+            return Err(ChunkError::InvalidParameters);
+        }
+
+        let gear = gear_bytes
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+
+        // the top bits of the gear hash depend on the most bytes, so the masks select those
+        let bits = avg_size.trailing_zeros();
+
+        Ok(Chunker {
+            gear,
+            address_key,
+            min_size,
+            avg_size,
+            max_size,
+            mask_small: !0 << (64 - (bits + 2)),
+            mask_large: !0 << (64 - (bits - 2)),
+        })
+    }
+
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    pub fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// The length of the chunk that starts `data`, taking `data` to be everything left if it is
+    /// shorter than `max_size`.
+    pub fn cut(&self, data: &[u8]) -> usize {
+        if data.len() <= self.min_size {
+            return data.len();
+        }
+
+        let end = data.len().min(self.max_size);
+        let normal = end.min(self.avg_size);
+        let mut hash = 0u64;
+
+        for (i, byte) in data.iter().enumerate().take(end).skip(self.min_size) {
+            hash = (hash << 1).wrapping_add(self.gear[*byte as usize]);
+
+            let mask = if i < normal {
+                self.mask_small
+            } else {
+                self.mask_large
+            };
+
+            if hash & mask == 0 {
+                return i + 1;
+            }
+        }
+
+        end
+    }
+
+    pub fn chunks<'a>(&'a self, data: &'a [u8]) -> Chunks<'a> {
+        Chunks {
+            chunker: self,
+            data,
+        }
+    }
+
+    /// The chunk's address: its BLAKE3 hash, keyed if the chunker is.
+    pub fn address(&self, chunk: &[u8]) -> [u8; 32] {
+        match &self.address_key {
+            Some(key) => blake3::keyed_hash(key, chunk),
+            None => blake3::hash(chunk),
+        }
+    }
+
+    pub fn manifest(&self, data: &[u8]) -> Manifest {
+        Manifest {
+            chunks: self
+                .chunks(data)
+                .map(|chunk| ChunkRef {
+                    address: self.address(chunk),
+                    length: chunk.len() as u32,
+                })
+                .collect(),
+        }
+    }
+
+    /// Chunks everything `reader` yields, handing each chunk and its address to `store` as it
+    /// goes, and returns the manifest. At most `max_size` bytes are buffered.
+    pub fn chunk_reader<R: Read, F: FnMut(&[u8; 32], &[u8]) -> std::io::Result<()>>(
+        &self,
+        mut reader: R,
+        mut store: F,
+    ) -> Result<Manifest, ChunkError> {
+        let mut buffer = Vec::with_capacity(self.max_size);
+        let mut chunks = Vec::new();
+
+        loop {
+            let wanted = self.max_size - buffer.len();
+            reader
+                .by_ref()
+                .take(wanted as u64)
+                .read_to_end(&mut buffer)?;
+
+            if buffer.is_empty() {
+                break;
+            }
+
+            let length = self.cut(&buffer);
+            let address = self.address(&buffer[..length]);
+            store(&address, &buffer[..length])?;
+
+            chunks.push(ChunkRef {
+                address,
+                length: length as u32,
+            });
+            buffer.drain(..length);
+        }
+
+        buffer.zeroize();
+
+        Ok(Manifest { chunks })
+    }
+
+    /// Writes the data a manifest describes to `writer`, fetching each chunk by address and
+    /// checking it before it is written. Returns the number of bytes written.
+    pub fn restore<W: Write, F: FnMut(&[u8; 32]) -> Option<Vec<u8>>>(
+        &self,
+        manifest: &Manifest,
+        mut writer: W,
+        mut fetch: F,
+    ) -> Result<u64, ChunkError> {
+        for chunk in &manifest.chunks {
+            let data = fetch(&chunk.address).ok_or(ChunkError::MissingChunk)?;
+
+            if data.len() != chunk.length as usize || self.address(&data) != chunk.address {
+                return Err(ChunkError::CorruptChunk);
+            }
+
+            writer.write_all(&data)?;
+        }
+
+        writer.flush()?;
+
+        Ok(manifest.total_size())
+    }
+}
+
+impl Default for Chunker {
+    fn default() -> Chunker {
+        Chunker::new(
+            Chunker::DEFAULT_MIN_SIZE,
+            Chunker::DEFAULT_AVG_SIZE,
+            Chunker::DEFAULT_MAX_SIZE,
+        )
+        .unwrap()
+    }
+}
+
+/// The chunks of a buffer, in order.
+#[derive(Debug)]
+pub struct Chunks<'a> {
+    chunker: &'a Chunker,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let (chunk, rest) = self.data.split_at(self.chunker.cut(self.data));
+        self.data = rest;
+
+        Some(chunk)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkRef {
+    address: [u8; 32],
+    length: u32,
+}
+
+impl ChunkRef {
+    pub fn address(&self) -> &[u8; 32] {
+        &self.address
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+/// The chunks that make up some data, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    chunks: Vec<ChunkRef>,
+}
+
+impl Manifest {
+    pub fn chunks(&self) -> &[ChunkRef] {
+        &self.chunks
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.chunks.iter().map(|chunk| chunk.length as u64).sum()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Manifest, ChunkError> {
+        let mut reader = Reader::new(bytes);
+
+        if reader.array::<4>()? != MAGIC || reader.byte()? != VERSION {
+            return Err(ChunkError::Malformed);
+        }
+
+        let count = reader.u64()?;
+
+        // checked against what is left before allocating for it
+        if count > (reader.len() / 36) as u64 {
+            return Err(ChunkError::Malformed);
+        }
+
+        let mut chunks = Vec::with_capacity(count as usize);
+
+        for _ in 0..count {
+            let address = reader.array()?;
+            let length = reader.u32()?;
+
+            if length == 0 {
+                return Err(ChunkError::Malformed);
+            }
+
+            chunks.push(ChunkRef { address, length });
+        }
+
+        reader.finish()?;
+
+        Ok(Manifest { chunks })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(4 + 1 + 8 + 36 * self.chunks.len());
+        output.extend_from_slice(&MAGIC);
+        output.push(VERSION);
+        output.extend_from_slice(&(self.chunks.len() as u64).to_be_bytes());
+
+        for chunk in &self.chunks {
+            output.extend_from_slice(&chunk.address);
+            output.extend_from_slice(&chunk.length.to_be_bytes());
+        }
+
+        output
+    }
+}
@@ -1 +1,4 @@
+pub mod aes;
 pub mod chacha;
+pub mod salsa;
+pub mod xchacha;
@@ -0,0 +1,147 @@
+pub(crate) mod backends;
+pub mod ctr;
+pub(crate) mod inverse;
+pub mod keywrap;
+
+use crate::backend::{self, Backend};
+use crate::errors::Error;
+use backends::soft;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const BLOCK_SIZE: usize = 16;
+
+pub(crate) type RoundKeys = [[u8; 16]; 15];
+
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let mut planes = soft::pack(&word);
+    soft::sub_bytes(&mut planes);
+
+    let mut output = [0u8; 4];
+    soft::unpack(&planes, &mut output);
+    planes.zeroize();
+
+    output
+}
+
+fn expand_key(key: &[u8]) -> (RoundKeys, usize) {
+    let length = key.len() / 4;
+    let rounds = length + 6;
+
+    let mut words = [[0u8; 4]; 60];
+    let mut rcon = 1u8;
+
+    for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        word.copy_from_slice(chunk);
+    }
+
+    for i in length..4 * (rounds + 1) {
+        let mut temp = words[i - 1];
+
+        if i % length == 0 {
+            temp.rotate_left(1);
+            temp = sub_word(temp);
+            temp[0] ^= rcon;
+            rcon = (rcon << 1) ^ if rcon & 0x80 != 0 { 0x1b } else { 0 };
+        } else if length > 6 && i % length == 4 {
+            temp = sub_word(temp);
+        }
+
+        words[i] = core::array::from_fn(|j| words[i - length][j] ^ temp[j]);
+        temp.zeroize();
+    }
+
+    let mut round_keys = [[0u8; 16]; 15];
+
+    for (round_key, chunk) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+        round_key.copy_from_slice(chunk.as_flattened());
+    }
+
+    words.zeroize();
+
+    (round_keys, rounds)
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn hardware_supported() -> bool {
+    is_x86_feature_detected!("aes") && is_x86_feature_detected!("pclmulqdq")
+}
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn hardware_supported() -> bool {
+    std::arch::is_aarch64_feature_detected!("aes")
+        && std::arch::is_aarch64_feature_detected!("pmull")
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub(crate) fn hardware_supported() -> bool {
+    false
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub enum Aes {
+    Bitsliced(soft::Aes),
+    #[cfg(target_arch = "x86_64")]
+    AesNi(backends::aesni::Aes),
+    #[cfg(target_arch = "aarch64")]
+    ArmCrypto(backends::armcrypto::Aes),
+}
+
+impl Aes {
+    pub fn new(key: &[u8]) -> Result<Aes, Error> {
+        Aes::with_hardware(key, backend::hardware_aes())
+    }
+
+    /// `Backend::Scalar` selects the bitsliced implementation; any other backend uses the
+    /// AES instructions when the CPU has them.
+    pub fn with_backend(key: &[u8], backend: Backend) -> Result<Aes, Error> {
+        Aes::with_hardware(key, backend != Backend::Scalar && hardware_supported())
+    }
+
+    pub(crate) fn with_hardware(key: &[u8], hardware: bool) -> Result<Aes, Error> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(Error::InvalidKey);
+        }
+
+        let (mut round_keys, rounds) = expand_key(key);
+
+        let aes = match hardware {
+            #[cfg(target_arch = "x86_64")]
+            true => Aes::AesNi(backends::aesni::Aes::new(&round_keys, rounds)),
+            #[cfg(target_arch = "aarch64")]
+            true => Aes::ArmCrypto(backends::armcrypto::Aes::new(&round_keys, rounds)),
+            _ => Aes::Bitsliced(soft::Aes::new(&round_keys, rounds)),
+        };
+
+        round_keys.zeroize();
+
+        Ok(aes)
+    }
+
+    pub fn backend(&self) -> &'static str {
+        match self {
+            Aes::Bitsliced(_) => "bitsliced",
+            #[cfg(target_arch = "x86_64")]
+            Aes::AesNi(_) => "aesni",
+            #[cfg(target_arch = "aarch64")]
+            Aes::ArmCrypto(_) => "armcrypto",
+        }
+    }
+
+    pub(crate) fn is_hardware(&self) -> bool {
+        !matches!(self, Aes::Bitsliced(_))
+    }
+
+    pub fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        match self {
+            Aes::Bitsliced(aes) => aes.encrypt_blocks(blocks),
+            #[cfg(target_arch = "x86_64")]
+            Aes::AesNi(aes) => aes.encrypt_blocks(blocks),
+            #[cfg(target_arch = "aarch64")]
+            Aes::ArmCrypto(aes) => aes.encrypt_blocks(blocks),
+        }
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        self.encrypt_blocks(core::slice::from_mut(block));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod soft;
+
+#[cfg(target_arch = "x86_64")]
+pub mod aesni;
+#[cfg(target_arch = "aarch64")]
+pub mod armcrypto;
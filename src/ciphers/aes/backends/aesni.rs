@@ -0,0 +1,56 @@
+use crate::ciphers::aes::RoundKeys;
+use core::arch::x86_64::*;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const PARALLEL: usize = 8;
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Aes {
+    round_keys: [__m128i; 15],
+    rounds: usize,
+}
+
+impl Aes {
+    pub fn new(round_keys: &RoundKeys, rounds: usize) -> Aes {
+        let mut keys = [unsafe { _mm_setzero_si128() }; 15];
+
+        for (key, bytes) in keys.iter_mut().zip(round_keys) {
+            *key = unsafe { _mm_loadu_si128(bytes.as_ptr() as *const __m128i) };
+        }
+
+        Aes {
+            round_keys: keys,
+            rounds,
+        }
+    }
+
+    #[target_feature(enable = "aes")]
+    unsafe fn encrypt_parallel(&self, blocks: &mut [[u8; 16]]) {
+        let mut state = [_mm_setzero_si128(); PARALLEL];
+        let state = &mut state[..blocks.len()];
+
+        for (state, block) in state.iter_mut().zip(blocks.iter()) {
+            *state = _mm_xor_si128(
+                _mm_loadu_si128(block.as_ptr() as *const __m128i),
+                self.round_keys[0],
+            );
+        }
+
+        for key in &self.round_keys[1..self.rounds] {
+            for state in state.iter_mut() {
+                *state = _mm_aesenc_si128(*state, *key);
+            }
+        }
+
+        for (state, block) in state.iter_mut().zip(blocks.iter_mut()) {
+            *state = _mm_aesenclast_si128(*state, self.round_keys[self.rounds]);
+            _mm_storeu_si128(block.as_mut_ptr() as *mut __m128i, *state);
+        }
+    }
+
+    pub fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for chunk in blocks.chunks_mut(PARALLEL) {
+            unsafe { self.encrypt_parallel(chunk) };
+        }
+    }
+}
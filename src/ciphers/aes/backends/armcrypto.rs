@@ -0,0 +1,53 @@
+use crate::ciphers::aes::RoundKeys;
+use core::arch::aarch64::*;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const PARALLEL: usize = 8;
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Aes {
+    round_keys: RoundKeys,
+    rounds: usize,
+}
+
+impl Aes {
+    pub fn new(round_keys: &RoundKeys, rounds: usize) -> Aes {
+        Aes {
+            round_keys: *round_keys,
+            rounds,
+        }
+    }
+
+    #[target_feature(enable = "aes")]
+    unsafe fn encrypt_parallel(&self, blocks: &mut [[u8; 16]]) {
+        let mut state = [vdupq_n_u8(0); PARALLEL];
+        let state = &mut state[..blocks.len()];
+
+        for (state, block) in state.iter_mut().zip(blocks.iter()) {
+            *state = vld1q_u8(block.as_ptr());
+        }
+
+        // AESE adds the round key before substituting, so the last key is added separately
+        for key in &self.round_keys[..self.rounds - 1] {
+            let key = vld1q_u8(key.as_ptr());
+
+            for state in state.iter_mut() {
+                *state = vaesmcq_u8(vaeseq_u8(*state, key));
+            }
+        }
+
+        let key = vld1q_u8(self.round_keys[self.rounds - 1].as_ptr());
+        let last = vld1q_u8(self.round_keys[self.rounds].as_ptr());
+
+        for (state, block) in state.iter_mut().zip(blocks.iter_mut()) {
+            *state = veorq_u8(vaeseq_u8(*state, key), last);
+            vst1q_u8(block.as_mut_ptr(), *state);
+        }
+    }
+
+    pub fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for chunk in blocks.chunks_mut(PARALLEL) {
+            unsafe { self.encrypt_parallel(chunk) };
+        }
+    }
+}
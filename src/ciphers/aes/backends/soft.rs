@@ -0,0 +1,290 @@
+use crate::ciphers::aes::RoundKeys;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// blocks encrypted by one bitsliced pass
+const PARALLEL: usize = 4;
+
+// plane k holds bit k of every byte; byte j of block b sits at bit 16 * b + j
+pub(crate) type Planes = [u64; 8];
+
+pub(crate) fn pack(bytes: &[u8]) -> Planes {
+    let mut planes = [0u64; 8];
+
+    for (index, byte) in bytes.iter().enumerate() {
+        for (bit, plane) in planes.iter_mut().enumerate() {
+            *plane |= (((byte >> bit) & 1) as u64) << index;
+        }
+    }
+
+    planes
+}
+
+pub(crate) fn unpack(planes: &Planes, bytes: &mut [u8]) {
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = 0;
+
+        for (bit, plane) in planes.iter().enumerate() {
+            *byte |= (((plane >> index) & 1) as u8) << bit;
+        }
+    }
+}
+
+// Boyar and Peralta's 113 gate circuit for the S-box
+pub(crate) fn sub_bytes(q: &mut Planes) {
+    let [x7, x6, x5, x4, x3, x2, x1, x0] = *q;
+
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    *q = [s7, s6, s5, s4, s3, s2, s1, s0];
+}
+
+const fn row_mask(row: usize, from: usize, to: usize) -> u64 {
+    let mut mask = 0u64;
+    let mut column = from;
+
+    while column < to {
+        mask |= 1 << (4 * column + row);
+        column += 1;
+    }
+
+    mask * 0x0001_0001_0001_0001
+}
+
+fn shift_rows(q: &mut Planes) {
+    for plane in q.iter_mut() {
+        let x = *plane;
+        let mut output = x & row_mask(0, 0, 4);
+
+        output |= ((x & row_mask(1, 1, 4)) >> 4) | ((x & row_mask(1, 0, 1)) << 12);
+        output |= ((x & row_mask(2, 2, 4)) >> 8) | ((x & row_mask(2, 0, 2)) << 8);
+        output |= ((x & row_mask(3, 3, 4)) >> 12) | ((x & row_mask(3, 0, 3)) << 4);
+
+        *plane = output;
+    }
+}
+
+// rotate the rows of every column up by one, two and three
+fn rotate1(x: u64) -> u64 {
+    ((x >> 1) & 0x7777_7777_7777_7777) | ((x << 3) & 0x8888_8888_8888_8888)
+}
+
+fn rotate2(x: u64) -> u64 {
+    ((x >> 2) & 0x3333_3333_3333_3333) | ((x << 2) & 0xcccc_cccc_cccc_cccc)
+}
+
+fn rotate3(x: u64) -> u64 {
+    ((x >> 3) & 0x1111_1111_1111_1111) | ((x << 1) & 0xeeee_eeee_eeee_eeee)
+}
+
+fn mix_columns(q: &mut Planes) {
+    // 2 * (a ^ rotate1(a)) ^ rotate1(a) ^ rotate2(a) ^ rotate3(a)
+    let t: Planes = core::array::from_fn(|k| q[k] ^ rotate1(q[k]));
+    let doubled = [
+        t[7],
+        t[0] ^ t[7],
+        t[1],
+        t[2] ^ t[7],
+        t[3] ^ t[7],
+        t[4],
+        t[5],
+        t[6],
+    ];
+
+    for (k, plane) in q.iter_mut().enumerate() {
+        *plane = doubled[k] ^ rotate1(*plane) ^ rotate2(*plane) ^ rotate3(*plane);
+    }
+}
+
+fn add_round_key(q: &mut Planes, key: &[u16; 8]) {
+    for (plane, key) in q.iter_mut().zip(key) {
+        *plane ^= *key as u64 * 0x0001_0001_0001_0001;
+    }
+}
+
+/// One full encryption round on a single block, as AESENC computes it: SubBytes, ShiftRows and
+/// MixColumns, then the round key.
+pub(crate) fn round(block: &[u8; 16], round_key: &[u8; 16]) -> [u8; 16] {
+    let mut q = pack(block);
+
+    sub_bytes(&mut q);
+    shift_rows(&mut q);
+    mix_columns(&mut q);
+
+    let mut output = [0u8; 16];
+    unpack(&q, &mut output);
+    q.zeroize();
+
+    for (byte, key) in output.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+
+    output
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Aes {
+    // single-block planes, widened to every block when applied
+    round_keys: [[u16; 8]; 15],
+    rounds: usize,
+}
+
+impl Aes {
+    pub fn new(round_keys: &RoundKeys, rounds: usize) -> Aes {
+        let mut planes = [[0u16; 8]; 15];
+
+        for (planes, key) in planes.iter_mut().zip(round_keys) {
+            let mut packed = pack(key);
+            *planes = packed.map(|plane| plane as u16);
+            packed.zeroize();
+        }
+
+        Aes {
+            round_keys: planes,
+            rounds,
+        }
+    }
+
+    fn encrypt_parallel(&self, blocks: &mut [u8]) {
+        let mut q = pack(blocks);
+
+        add_round_key(&mut q, &self.round_keys[0]);
+
+        for key in &self.round_keys[1..self.rounds] {
+            sub_bytes(&mut q);
+            shift_rows(&mut q);
+            mix_columns(&mut q);
+            add_round_key(&mut q, key);
+        }
+
+        sub_bytes(&mut q);
+        shift_rows(&mut q);
+        add_round_key(&mut q, &self.round_keys[self.rounds]);
+
+        unpack(&q, blocks);
+        q.zeroize();
+    }
+
+    pub fn encrypt_blocks(&self, blocks: &mut [[u8; 16]]) {
+        for chunk in blocks.chunks_mut(PARALLEL) {
+            self.encrypt_parallel(chunk.as_flattened_mut());
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// AES in counter mode (NIST SP 800-38A), with the whole 16-byte IV as a big-endian counter that
+// wraps around at 2^128, as in OpenSSL's aes-*-ctr. It has no integrity protection of its own.
+
+use super::Aes;
+use crate::errors::Error;
+use crate::utils::redacted_debug;
+use zeroize::Zeroize;
+
+// counter blocks encrypted per call into the block cipher
+const BATCH: usize = 8;
+
+pub struct AesCtr {
+    aes: Aes,
+}
+
+redacted_debug!(AesCtr, "<redacted>");
+
+impl AesCtr {
+    pub const IV_SIZE: usize = 16;
+
+    /// Accepts 16, 24 or 32 byte keys.
+    pub fn new(key: &[u8]) -> Result<AesCtr, Error> {
+        Ok(AesCtr {
+            aes: Aes::new(key)?,
+        })
+    }
+
+    /// Encrypts or decrypts `msg` with the keystream starting at counter block `iv`.
+    pub fn xor(&self, msg: &[u8], iv: &[u8]) -> Vec<u8> {
+        assert_eq!(iv.len(), Self::IV_SIZE);
+
+        let mut counter = u128::from_be_bytes(iv.try_into().unwrap());
+        let mut output = msg.to_vec();
+        let mut blocks = [[0u8; 16]; BATCH];
+
+        for chunk in output.chunks_mut(16 * BATCH) {
+            let blocks = &mut blocks[..chunk.len().div_ceil(16)];
+
+            for block in blocks.iter_mut() {
+                *block = counter.to_be_bytes();
+                counter = counter.wrapping_add(1);
+            }
+
+            self.aes.encrypt_blocks(blocks);
+
+            for (byte, key) in chunk.iter_mut().zip(blocks.as_flattened()) {
+                *byte ^= key;
+            }
+        }
+
+        blocks.zeroize();
+
+        output
+    }
+}
@@ -0,0 +1,73 @@
+// The AES inverse cipher, which none of the backends provide. It works a byte at a time with the
+// inverse S-box computed arithmetically, so it has no secret-dependent table lookups or branches.
+// It is slow, and only meant for the modes that cannot avoid it: key unwrapping and OCB
+// decryption.
+
+use super::{expand_key, RoundKeys};
+use crate::errors::Error;
+use crate::gf256::{inverse, mul};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// the inverse of the S-box: undo the affine map, then invert in GF(2^8) as a^254
+fn inv_sub_byte(byte: u8) -> u8 {
+    let a = byte.rotate_left(1) ^ byte.rotate_left(3) ^ byte.rotate_left(6) ^ 0x05;
+
+    inverse(a)
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key) in state.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+// InvShiftRows and InvSubBytes together; the state is column-major, so row r of column c is
+// byte r + 4c
+fn inv_shift_sub(state: &mut [u8; 16]) {
+    let shifted: [u8; 16] =
+        core::array::from_fn(|i| inv_sub_byte(state[i % 4 + 4 * ((i / 4 + 4 - i % 4) % 4)]));
+
+    *state = shifted;
+}
+
+fn inv_mix_columns(state: &mut [u8; 16]) {
+    for column in state.chunks_exact_mut(4) {
+        let [a, b, c, d] = [column[0], column[1], column[2], column[3]];
+
+        column[0] = mul(a, 14) ^ mul(b, 11) ^ mul(c, 13) ^ mul(d, 9);
+        column[1] = mul(a, 9) ^ mul(b, 14) ^ mul(c, 11) ^ mul(d, 13);
+        column[2] = mul(a, 13) ^ mul(b, 9) ^ mul(c, 14) ^ mul(d, 11);
+        column[3] = mul(a, 11) ^ mul(b, 13) ^ mul(c, 9) ^ mul(d, 14);
+    }
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub(crate) struct InverseAes {
+    round_keys: RoundKeys,
+    rounds: usize,
+}
+
+impl InverseAes {
+    pub(crate) fn new(key: &[u8]) -> Result<InverseAes, Error> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(Error::InvalidKey);
+        }
+
+        let (round_keys, rounds) = expand_key(key);
+
+        Ok(InverseAes { round_keys, rounds })
+    }
+
+    pub(crate) fn decrypt_block(&self, block: &mut [u8; 16]) {
+        add_round_key(block, &self.round_keys[self.rounds]);
+
+        for round in (1..self.rounds).rev() {
+            inv_shift_sub(block);
+            add_round_key(block, &self.round_keys[round]);
+            inv_mix_columns(block);
+        }
+
+        inv_shift_sub(block);
+        add_round_key(block, &self.round_keys[0]);
+    }
+}
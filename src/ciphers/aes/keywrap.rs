@@ -0,0 +1,84 @@
+// AES Key Wrap (RFC 3394). Unwrapping runs the slow inverse cipher, but only 6 times per 8 bytes
+// of key.
+
+use super::inverse::InverseAes;
+use super::Aes;
+use crate::errors::Error;
+use crate::errors::InvalidMac;
+use crate::utils::const_time_eq;
+use zeroize::Zeroize;
+
+const IV: [u8; 8] = [0xa6; 8];
+
+/// Wraps `key`, a multiple of 8 bytes and at least 16, under a 16, 24 or 32 byte `kek`. The
+/// output is 8 bytes longer than the key.
+pub fn wrap(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, Error> {
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        return Err(Error::InvalidKey);
+    }
+
+    let aes = Aes::new(kek)?;
+    let n = key.len() / 8;
+
+    let mut a = IV;
+    let mut r = key.to_vec();
+    let mut block = [0u8; 16];
+
+    for j in 0..6 {
+        for (i, chunk) in r.chunks_exact_mut(8).enumerate() {
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(chunk);
+            aes.encrypt_block(&mut block);
+
+            let t = (n * j + i + 1) as u64;
+            a = core::array::from_fn(|k| block[k] ^ t.to_be_bytes()[k]);
+            chunk.copy_from_slice(&block[8..]);
+        }
+    }
+
+    block.zeroize();
+
+    let mut output = a.to_vec();
+    output.extend_from_slice(&r);
+    r.zeroize();
+
+    Ok(output)
+}
+
+/// Unwraps a key made by `wrap`, checking its integrity.
+pub fn unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(InvalidMac);
+    }
+
+    let aes = InverseAes::new(kek).map_err(|_| InvalidMac)?;
+    let n = wrapped.len() / 8 - 1;
+
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r = wrapped[8..].to_vec();
+    let mut block = [0u8; 16];
+
+    for j in (0..6).rev() {
+        for (i, chunk) in r.chunks_exact_mut(8).enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+
+            block[..8].copy_from_slice(&core::array::from_fn::<u8, 8, _>(|k| {
+                a[k] ^ t.to_be_bytes()[k]
+            }));
+            block[8..].copy_from_slice(chunk);
+            aes.decrypt_block(&mut block);
+
+            a.copy_from_slice(&block[..8]);
+            chunk.copy_from_slice(&block[8..]);
+        }
+    }
+
+    block.zeroize();
+
+    if !const_time_eq(&a, &IV) {
+        r.zeroize();
+        return Err(InvalidMac);
+    }
+
+    Ok(r)
+}
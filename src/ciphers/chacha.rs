@@ -1,3 +1,51 @@
 pub(crate) mod backends;
 pub(crate) mod detect;
 pub use detect::*;
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+pub const BLOCK_SIZE: usize = 64;
+
+/// Number of blocks produced by a single `keystream_blocks` call.
+pub const KEYSTREAM_BLOCKS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChaChaError {
+    InvalidKeyLength,
+    InvalidNonceLength,
+}
+
+impl std::fmt::Display for ChaChaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChaChaError::InvalidKeyLength => write!(f, "ChaCha20 keys must be 32 bytes."),
+            ChaChaError::InvalidNonceLength => write!(f, "The nonce has the wrong length."),
+        }
+    }
+}
+
+impl std::error::Error for ChaChaError {}
+
+pub fn hchacha20(key: &[u8], nonce: &[u8]) -> Result<[u8; 32], ChaChaError> {
+    if key.len() != KEY_SIZE {
+        return Err(ChaChaError::InvalidKeyLength);
+    }
+
+    if nonce.len() != 16 {
+        return Err(ChaChaError::InvalidNonceLength);
+    }
+
+    Ok(HChaCha20::new(key).keystream(nonce))
+}
+
+pub fn chacha20_block(key: &[u8], nonce: &[u8], counter: u32) -> Result<[u8; 64], ChaChaError> {
+    if key.len() != KEY_SIZE {
+        return Err(ChaChaError::InvalidKeyLength);
+    }
+
+    if nonce.len() != NONCE_SIZE {
+        return Err(ChaChaError::InvalidNonceLength);
+    }
+
+    Ok(ChaCha20::new(key).keystream(nonce, counter))
+}
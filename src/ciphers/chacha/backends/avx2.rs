@@ -3,7 +3,9 @@ use core::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::*;
 
+use crate::ciphers::chacha::KEYSTREAM_BLOCKS;
 use crate::utils::from_le_bytes;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
 
@@ -136,6 +138,7 @@ unsafe fn encrypt_block(
     }
 }
 
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct ChaCha20 {
     state: [__m256i; 3],
 }
@@ -144,6 +147,8 @@ pub struct ChaCha20 {
 impl ChaCha20 {
     #[inline(always)]
     pub fn new(key: &[u8]) -> ChaCha20 {
+        assert_eq!(key.len(), 32);
+
         unsafe {
             let s0 = _mm256_broadcastsi128_si256(_mm_loadu_si128(SIGMA.as_ptr() as *const __m128i));
 
@@ -167,9 +172,9 @@ impl ChaCha20 {
 
     #[inline]
     #[target_feature(enable = "avx2")]
-    pub unsafe fn _encrypt(&self, plaintext: &[u8], nonce: &[u8]) -> Vec<u8> {
+    pub unsafe fn _encrypt(&self, plaintext: &[u8], nonce: &[u8], counter: u32) -> Vec<u8> {
         let nonce_block = [
-            1,
+            counter,
             from_le_bytes(&nonce[0..4]),
             from_le_bytes(&nonce[4..8]),
             from_le_bytes(&nonce[8..12]),
@@ -196,8 +201,18 @@ impl ChaCha20 {
 }
 
 impl ChaCha20 {
-    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8]) -> Vec<u8> {
-        unsafe { self._encrypt(plaintext, nonce) }
+    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8], counter: u32) -> Vec<u8> {
+        unsafe { self._encrypt(plaintext, nonce, counter) }
+    }
+
+    pub fn keystream_blocks(&self, nonce: &[u8], counter: u32) -> [u8; 64 * KEYSTREAM_BLOCKS] {
+        let mut output = [0u8; 64 * KEYSTREAM_BLOCKS];
+
+        for (index, block) in output.chunks_exact_mut(64).enumerate() {
+            block.copy_from_slice(&self.keystream(nonce, counter.wrapping_add(index as u32)));
+        }
+
+        output
     }
 
     pub fn keystream(&self, nonce: &[u8], counter: u32) -> [u8; 64] {
@@ -1,4 +1,6 @@
+use crate::ciphers::chacha::KEYSTREAM_BLOCKS;
 use crate::utils::from_le_bytes;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 fn quarter_round(a: usize, b: usize, c: usize, d: usize, block: &mut [u32; 16]) {
     block[a] = block[a].wrapping_add(block[b]);
@@ -32,91 +34,165 @@ fn double_round(mut block: [u32; 16]) -> [u32; 16] {
     block
 }
 
+// the same rounds over KEYSTREAM_BLOCKS independent states at once, one lane per block
+type Lanes = [u32; KEYSTREAM_BLOCKS];
+
+#[inline(always)]
+fn add(x: Lanes, y: Lanes) -> Lanes {
+    core::array::from_fn(|lane| x[lane].wrapping_add(y[lane]))
+}
+
+#[inline(always)]
+fn xor_rotate(x: Lanes, y: Lanes, rotation: u32) -> Lanes {
+    core::array::from_fn(|lane| (x[lane] ^ y[lane]).rotate_left(rotation))
+}
+
+#[inline(always)]
+fn quarter_round_wide(a: usize, b: usize, c: usize, d: usize, block: &mut [Lanes; 16]) {
+    block[a] = add(block[a], block[b]);
+    block[d] = xor_rotate(block[d], block[a], 16);
+
+    block[c] = add(block[c], block[d]);
+    block[b] = xor_rotate(block[b], block[c], 12);
+
+    block[a] = add(block[a], block[b]);
+    block[d] = xor_rotate(block[d], block[a], 8);
+
+    block[c] = add(block[c], block[d]);
+    block[b] = xor_rotate(block[b], block[c], 7);
+}
+
+fn double_round_wide(block: &mut [Lanes; 16]) {
+    quarter_round_wide(0, 4, 8, 12, block);
+    quarter_round_wide(1, 5, 9, 13, block);
+    quarter_round_wide(2, 6, 10, 14, block);
+    quarter_round_wide(3, 7, 11, 15, block);
+
+    quarter_round_wide(0, 5, 10, 15, block);
+    quarter_round_wide(1, 6, 11, 12, block);
+    quarter_round_wide(2, 7, 8, 13, block);
+    quarter_round_wide(3, 4, 9, 14, block);
+}
+
+fn key_state(key: &[u8]) -> [u32; 12] {
+    assert_eq!(key.len(), 32);
+
+    [
+        0x61707865,
+        0x3320646e,
+        0x79622d32,
+        0x6b206574,
+        from_le_bytes(&key[0..4]),
+        from_le_bytes(&key[4..8]),
+        from_le_bytes(&key[8..12]),
+        from_le_bytes(&key[12..16]),
+        from_le_bytes(&key[16..20]),
+        from_le_bytes(&key[20..24]),
+        from_le_bytes(&key[24..28]),
+        from_le_bytes(&key[28..32]),
+    ]
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct ChaCha20 {
-    key: Vec<u8>,
+    state: [u32; 12],
 }
 
 impl ChaCha20 {
     pub fn new(key: &[u8]) -> ChaCha20 {
-        ChaCha20 { key: key.to_vec() }
+        ChaCha20 {
+            state: key_state(key),
+        }
     }
 
     pub fn keystream(&self, nonce: &[u8], counter: u32) -> [u8; 64] {
-        let mut state = [
-            0x61707865,
-            0x3320646e,
-            0x79622d32,
-            0x6b206574,
-            from_le_bytes(&self.key[0..4]),
-            from_le_bytes(&self.key[4..8]),
-            from_le_bytes(&self.key[8..12]),
-            from_le_bytes(&self.key[12..16]),
-            from_le_bytes(&self.key[16..20]),
-            from_le_bytes(&self.key[20..24]),
-            from_le_bytes(&self.key[24..28]),
-            from_le_bytes(&self.key[28..]),
+        let mut state = [0u32; 16];
+        state[..12].copy_from_slice(&self.state);
+
+        state[12..16].copy_from_slice(&[
             counter,
+            from_le_bytes(&nonce[0..4]),
             from_le_bytes(&nonce[4..8]),
             from_le_bytes(&nonce[8..12]),
-            from_le_bytes(&nonce[12..]),
-        ];
+        ]);
 
-        let mut original_state = state.clone();
+        let original_state = state;
 
         for _ in 0..10 {
             state = double_round(state);
         }
 
         for (i, j) in state.iter_mut().zip(original_state.iter()) {
-            *i += *j;
+            *i = i.wrapping_add(*j);
         }
 
         let mut result = [0u8; 64];
 
         for (index, chunk) in state.iter().enumerate() {
-            result[index * 4..].copy_from_slice(&chunk.to_le_bytes());
+            result[index * 4..index * 4 + 4].copy_from_slice(&chunk.to_le_bytes());
         }
 
         result
     }
 
-    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8]) -> Vec<u8> {
-        let mut ciphertext: Vec<u8> = Vec::new();
+    pub fn keystream_blocks(&self, nonce: &[u8], counter: u32) -> [u8; 64 * KEYSTREAM_BLOCKS] {
+        let mut state = [[0u32; KEYSTREAM_BLOCKS]; 16];
+
+        for (word, value) in state.iter_mut().zip(self.state) {
+            *word = [value; KEYSTREAM_BLOCKS];
+        }
+
+        for (lane, word) in state[12].iter_mut().enumerate() {
+            *word = counter.wrapping_add(lane as u32);
+        }
+
+        state[13] = [from_le_bytes(&nonce[0..4]); KEYSTREAM_BLOCKS];
+        state[14] = [from_le_bytes(&nonce[4..8]); KEYSTREAM_BLOCKS];
+        state[15] = [from_le_bytes(&nonce[8..12]); KEYSTREAM_BLOCKS];
+
+        let original_state = state;
+
+        for _ in 0..10 {
+            double_round_wide(&mut state);
+        }
 
-        for block in plaintext.chunks(64) {
-            let keystream = self.keystream(nonce, 1);
+        let mut result = [0u8; 64 * KEYSTREAM_BLOCKS];
 
-            for (key, chunk) in block.iter().zip(keystream) {
-                ciphertext.push(chunk ^ key);
+        for (index, (words, original)) in state.iter().zip(original_state.iter()).enumerate() {
+            for lane in 0..KEYSTREAM_BLOCKS {
+                let word = words[lane].wrapping_add(original[lane]);
+                result[lane * 64 + index * 4..lane * 64 + index * 4 + 4]
+                    .copy_from_slice(&word.to_le_bytes());
             }
         }
 
+        result
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8], counter: u32) -> Vec<u8> {
+        let mut ciphertext: Vec<u8> = Vec::with_capacity(plaintext.len());
+
+        for (index, block) in plaintext.chunks(64 * KEYSTREAM_BLOCKS).enumerate() {
+            let counter = counter.wrapping_add((index * KEYSTREAM_BLOCKS) as u32);
+            let keystream = self.keystream_blocks(nonce, counter);
+
+            ciphertext.extend(block.iter().zip(keystream).map(|(m, k)| m ^ k));
+        }
+
         ciphertext
     }
 }
 
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct HChaCha20 {
     state: [u32; 12],
 }
 
 impl HChaCha20 {
     pub fn new(key: &[u8]) -> HChaCha20 {
-        let state = [
-            0x61707865,
-            0x3320646e,
-            0x79622d32,
-            0x6b206574,
-            from_le_bytes(&key[0..4]),
-            from_le_bytes(&key[4..8]),
-            from_le_bytes(&key[8..12]),
-            from_le_bytes(&key[12..16]),
-            from_le_bytes(&key[16..20]),
-            from_le_bytes(&key[20..24]),
-            from_le_bytes(&key[24..28]),
-            from_le_bytes(&key[28..32]),
-        ];
-
-        HChaCha20 { state }
+        HChaCha20 {
+            state: key_state(key),
+        }
     }
 
     pub fn keystream(&self, nonce: &[u8]) -> [u8; 32] {
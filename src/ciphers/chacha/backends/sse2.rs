@@ -3,7 +3,9 @@ use core::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::*;
 
+use crate::ciphers::chacha::KEYSTREAM_BLOCKS;
 use crate::utils::from_le_bytes;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
 
@@ -85,6 +87,7 @@ pub unsafe fn rounds(data: [__m128i; 4], hchacha: bool) -> [__m128i; 4] {
     stuff
 }
 
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct ChaCha20 {
     state: [__m128i; 3],
 }
@@ -129,6 +132,8 @@ unsafe fn encrypt_block(
 
 impl ChaCha20 {
     pub fn new(key: &[u8]) -> Self {
+        assert_eq!(key.len(), 32);
+
         unsafe {
             ChaCha20 {
                 state: [
@@ -144,9 +149,9 @@ impl ChaCha20 {
         rounds([self.state[0], self.state[1], self.state[2], *nonce], false)
     }
 
-    unsafe fn _encrypt(&self, plaintext: &[u8], nonce: &[u8]) -> Vec<u8> {
+    unsafe fn _encrypt(&self, plaintext: &[u8], nonce: &[u8], counter: u32) -> Vec<u8> {
         let nonce_block = [
-            1,
+            counter,
             from_le_bytes(&nonce[0..4]),
             from_le_bytes(&nonce[4..8]),
             from_le_bytes(&nonce[8..12]),
@@ -195,11 +200,22 @@ impl ChaCha20 {
         }
     }
 
-    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8]) -> Vec<u8> {
-        unsafe { self._encrypt(plaintext, nonce) }
+    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8], counter: u32) -> Vec<u8> {
+        unsafe { self._encrypt(plaintext, nonce, counter) }
+    }
+
+    pub fn keystream_blocks(&self, nonce: &[u8], counter: u32) -> [u8; 64 * KEYSTREAM_BLOCKS] {
+        let mut output = [0u8; 64 * KEYSTREAM_BLOCKS];
+
+        for (index, block) in output.chunks_exact_mut(64).enumerate() {
+            block.copy_from_slice(&self.keystream(nonce, counter.wrapping_add(index as u32)));
+        }
+
+        output
     }
 }
 
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct HChaCha20 {
     state: [__m128i; 3],
 }
@@ -207,6 +223,8 @@ pub struct HChaCha20 {
 impl HChaCha20 {
     #[inline(always)]
     pub fn new(key: &[u8]) -> HChaCha20 {
+        assert_eq!(key.len(), 32);
+
         unsafe {
             HChaCha20 {
                 state: [
@@ -1,15 +1,125 @@
-use cfg_if::cfg_if;
+use crate::backend::{self, Backend};
+use crate::ciphers::chacha::backends::fallback;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::ciphers::chacha::backends::{avx2, sse2};
+use crate::ciphers::chacha::KEYSTREAM_BLOCKS;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-cfg_if! {
-    if #[cfg(all(any(target_arch="x86", target_arch="x86_64"), target_feature="avx2"))] {
-        pub use crate::ciphers::chacha::backends::avx2::ChaCha20;
-        pub use crate::ciphers::chacha::backends::sse2::HChaCha20;
+// the backends ChaCha20 has kernels for, best first
+pub(crate) fn kernel(requested: Backend) -> Backend {
+    match requested {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx512 | Backend::Avx2 => Backend::Avx2,
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Sse2 => Backend::Sse2,
+        _ => Backend::Scalar,
     }
-    else if #[cfg(all(any(target_arch="x86", target_arch="x86_64"), target_feature="sse2"))] {
-        pub use crate::ciphers::chacha::backends::sse2::ChaCha20;
-        pub use crate::ciphers::chacha::backends::sse2::HChaCha20;
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub enum ChaCha20 {
+    Scalar(fallback::ChaCha20),
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Sse2(sse2::ChaCha20),
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Avx2(avx2::ChaCha20),
+}
+
+impl ChaCha20 {
+    pub fn new(key: &[u8]) -> ChaCha20 {
+        ChaCha20::with_backend(key, backend::chacha20()).unwrap()
+    }
+
+    /// Uses the kernel for `backend`, or `None` if the CPU does not support it.
+    pub fn with_backend(key: &[u8], backend: Backend) -> Option<ChaCha20> {
+        if !backend.is_supported() {
+            return None;
+        }
+
+        Some(match kernel(backend) {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 => ChaCha20::Avx2(avx2::ChaCha20::new(key)),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Sse2 => ChaCha20::Sse2(sse2::ChaCha20::new(key)),
+            _ => ChaCha20::Scalar(fallback::ChaCha20::new(key)),
+        })
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            ChaCha20::Scalar(_) => Backend::Scalar,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Sse2(_) => Backend::Sse2,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Avx2(_) => Backend::Avx2,
+        }
+    }
+
+    pub fn keystream(&self, nonce: &[u8], counter: u32) -> [u8; 64] {
+        match self {
+            ChaCha20::Scalar(chacha) => chacha.keystream(nonce, counter),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Sse2(chacha) => chacha.keystream(nonce, counter),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Avx2(chacha) => chacha.keystream(nonce, counter),
+        }
+    }
+
+    pub fn keystream_blocks(&self, nonce: &[u8], counter: u32) -> [u8; 64 * KEYSTREAM_BLOCKS] {
+        match self {
+            ChaCha20::Scalar(chacha) => chacha.keystream_blocks(nonce, counter),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Sse2(chacha) => chacha.keystream_blocks(nonce, counter),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Avx2(chacha) => chacha.keystream_blocks(nonce, counter),
+        }
     }
-    else {
-        pub use crate::ciphers::chacha::backends::fallback::*;
+
+    /// Encrypts starting at block 1, the RFC 8439 default; block 0 is left for the Poly1305 key.
+    pub fn encrypt(&self, plaintext: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.encrypt_with_counter(plaintext, nonce, 1)
+    }
+
+    pub fn encrypt_with_counter(&self, plaintext: &[u8], nonce: &[u8], counter: u32) -> Vec<u8> {
+        match self {
+            ChaCha20::Scalar(chacha) => chacha.encrypt(plaintext, nonce, counter),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Sse2(chacha) => chacha.encrypt(plaintext, nonce, counter),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ChaCha20::Avx2(chacha) => chacha.encrypt(plaintext, nonce, counter),
+        }
+    }
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub enum HChaCha20 {
+    Scalar(fallback::HChaCha20),
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Sse2(sse2::HChaCha20),
+}
+
+impl HChaCha20 {
+    pub fn new(key: &[u8]) -> HChaCha20 {
+        HChaCha20::with_backend(key, backend::chacha20()).unwrap()
+    }
+
+    pub fn with_backend(key: &[u8], backend: Backend) -> Option<HChaCha20> {
+        if !backend.is_supported() {
+            return None;
+        }
+
+        Some(match kernel(backend) {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 | Backend::Sse2 => HChaCha20::Sse2(sse2::HChaCha20::new(key)),
+            _ => HChaCha20::Scalar(fallback::HChaCha20::new(key)),
+        })
+    }
+
+    pub fn keystream(&self, nonce: &[u8]) -> [u8; 32] {
+        match self {
+            HChaCha20::Scalar(hchacha) => hchacha.keystream(nonce),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            HChaCha20::Sse2(hchacha) => hchacha.keystream(nonce),
+        }
     }
 }
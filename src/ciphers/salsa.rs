@@ -0,0 +1,186 @@
+use crate::utils::from_le_bytes;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The constant NaCl uses for 32-byte keys.
+pub const SIGMA: [u8; 16] = *b"expand 32-byte k";
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 8;
+pub const XNONCE_SIZE: usize = 24;
+pub const BLOCK_SIZE: usize = 64;
+
+fn quarter_round(a: usize, b: usize, c: usize, d: usize, block: &mut [u32; 16]) {
+    block[b] ^= block[a].wrapping_add(block[d]).rotate_left(7);
+    block[c] ^= block[b].wrapping_add(block[a]).rotate_left(9);
+    block[d] ^= block[c].wrapping_add(block[b]).rotate_left(13);
+    block[a] ^= block[d].wrapping_add(block[c]).rotate_left(18);
+}
+
+fn double_round(mut block: [u32; 16]) -> [u32; 16] {
+    quarter_round(0, 4, 8, 12, &mut block);
+    quarter_round(5, 9, 13, 1, &mut block);
+    quarter_round(10, 14, 2, 6, &mut block);
+    quarter_round(15, 3, 7, 11, &mut block);
+
+    quarter_round(0, 1, 2, 3, &mut block);
+    quarter_round(5, 6, 7, 4, &mut block);
+    quarter_round(10, 11, 8, 9, &mut block);
+    quarter_round(15, 12, 13, 14, &mut block);
+
+    block
+}
+
+fn state(input: &[u8; 16], key: &[u8; 32], constant: &[u8; 16]) -> [u32; 16] {
+    let word = |bytes: &[u8], index: usize| from_le_bytes(&bytes[4 * index..4 * index + 4]);
+
+    [
+        word(constant, 0),
+        word(key, 0),
+        word(key, 1),
+        word(key, 2),
+        word(key, 3),
+        word(constant, 1),
+        word(input, 0),
+        word(input, 1),
+        word(input, 2),
+        word(input, 3),
+        word(constant, 2),
+        word(key, 4),
+        word(key, 5),
+        word(key, 6),
+        word(key, 7),
+        word(constant, 3),
+    ]
+}
+
+fn rounds(mut state: [u32; 16]) -> [u32; 16] {
+    for _ in 0..10 {
+        state = double_round(state);
+    }
+
+    state
+}
+
+/// libsodium's `crypto_core_salsa20`: one 64-byte Salsa20 block.
+pub fn core_salsa20(input: &[u8; 16], key: &[u8; 32], constant: &[u8; 16]) -> [u8; 64] {
+    let original_state = state(input, key, constant);
+    let mut state = rounds(original_state);
+
+    let mut result = [0u8; 64];
+
+    for (index, (word, original)) in state.iter().zip(original_state.iter()).enumerate() {
+        result[index * 4..index * 4 + 4]
+            .copy_from_slice(&word.wrapping_add(*original).to_le_bytes());
+    }
+
+    state.zeroize();
+
+    result
+}
+
+/// libsodium's `crypto_core_hsalsa20`, which derives XSalsa20 and crypto_box keys.
+pub fn core_hsalsa20(input: &[u8; 16], key: &[u8; 32], constant: &[u8; 16]) -> [u8; 32] {
+    let mut state = rounds(state(input, key, constant));
+
+    let mut result = [0u8; 32];
+
+    for (result_chunk, index) in result.chunks_exact_mut(4).zip([0, 5, 10, 15, 6, 7, 8, 9]) {
+        result_chunk.copy_from_slice(&state[index].to_le_bytes());
+    }
+
+    state.zeroize();
+
+    result
+}
+
+pub fn hsalsa20(key: &[u8; 32], nonce: &[u8; 16]) -> [u8; 32] {
+    core_hsalsa20(nonce, key, &SIGMA)
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Salsa20 {
+    key: [u8; 32],
+}
+
+impl Salsa20 {
+    pub fn new(key: &[u8]) -> Salsa20 {
+        Salsa20 {
+            key: key.try_into().unwrap(),
+        }
+    }
+
+    pub fn keystream(&self, nonce: &[u8], counter: u64) -> [u8; 64] {
+        let mut input = [0u8; 16];
+        input[..8].copy_from_slice(&nonce[..8]);
+        input[8..].copy_from_slice(&counter.to_le_bytes());
+
+        core_salsa20(&input, &self.key, &SIGMA)
+    }
+
+    pub fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor_ic(msg, nonce, 0)
+    }
+
+    pub fn xor_ic(&self, msg: &[u8], nonce: &[u8], ic: u64) -> Vec<u8> {
+        let mut output = Vec::with_capacity(msg.len());
+
+        for (index, chunk) in msg.chunks(BLOCK_SIZE).enumerate() {
+            let keystream = self.keystream(nonce, ic.wrapping_add(index as u64));
+            output.extend(chunk.iter().zip(keystream).map(|(m, k)| m ^ k));
+        }
+
+        output
+    }
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct XSalsa20 {
+    key: [u8; 32],
+}
+
+impl XSalsa20 {
+    pub fn new(key: &[u8]) -> XSalsa20 {
+        XSalsa20 {
+            key: key.try_into().unwrap(),
+        }
+    }
+
+    fn subkey(&self, nonce: &[u8]) -> Salsa20 {
+        let mut subkey = hsalsa20(&self.key, nonce[..16].try_into().unwrap());
+        let salsa = Salsa20::new(&subkey);
+
+        subkey.zeroize();
+
+        salsa
+    }
+
+    pub fn keystream(&self, nonce: &[u8], counter: u64) -> [u8; 64] {
+        self.subkey(nonce).keystream(&nonce[16..24], counter)
+    }
+
+    pub fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor_ic(msg, nonce, 0)
+    }
+
+    pub fn xor_ic(&self, msg: &[u8], nonce: &[u8], ic: u64) -> Vec<u8> {
+        self.subkey(nonce).xor_ic(msg, &nonce[16..24], ic)
+    }
+}
+
+// argument order follows libsodium's crypto_stream_*_xor functions: message, nonce, counter, key
+
+pub fn salsa20_xor(msg: &[u8], nonce: &[u8], key: &[u8]) -> Vec<u8> {
+    Salsa20::new(key).xor(msg, nonce)
+}
+
+pub fn salsa20_xor_ic(msg: &[u8], nonce: &[u8], ic: u64, key: &[u8]) -> Vec<u8> {
+    Salsa20::new(key).xor_ic(msg, nonce, ic)
+}
+
+pub fn xsalsa20_xor(msg: &[u8], nonce: &[u8], key: &[u8]) -> Vec<u8> {
+    XSalsa20::new(key).xor(msg, nonce)
+}
+
+pub fn xsalsa20_xor_ic(msg: &[u8], nonce: &[u8], ic: u64, key: &[u8]) -> Vec<u8> {
+    XSalsa20::new(key).xor_ic(msg, nonce, ic)
+}
@@ -0,0 +1,92 @@
+use crate::ciphers::chacha::{ChaCha20, HChaCha20, KEYSTREAM_BLOCKS};
+use zeroize::Zeroize;
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 24;
+
+pub struct XChaCha20 {
+    hchacha: HChaCha20,
+}
+
+impl XChaCha20 {
+    pub fn new(key: &[u8]) -> XChaCha20 {
+        XChaCha20 {
+            hchacha: HChaCha20::new(key),
+        }
+    }
+
+    fn subkey(&self, nonce: &[u8]) -> ChaCha20 {
+        let mut subkey = self.hchacha.keystream(&nonce[..16]);
+        let chacha = ChaCha20::new(&subkey);
+
+        subkey.zeroize();
+
+        chacha
+    }
+
+    // the original ChaCha20 layout, with a 64-bit block counter and a 64-bit nonce
+    fn block_nonce(nonce: &[u8], counter: u64) -> [u8; 12] {
+        let mut block_nonce = [0u8; 12];
+        block_nonce[..4].copy_from_slice(&((counter >> 32) as u32).to_le_bytes());
+        block_nonce[4..].copy_from_slice(&nonce[16..24]);
+
+        block_nonce
+    }
+
+    fn block(chacha: &ChaCha20, nonce: &[u8], counter: u64) -> [u8; 64] {
+        chacha.keystream(&Self::block_nonce(nonce, counter), counter as u32)
+    }
+
+    fn blocks(chacha: &ChaCha20, nonce: &[u8], counter: u64) -> [u8; 64 * KEYSTREAM_BLOCKS] {
+        // the wide path cannot carry into the upper counter word
+        if (counter as u32)
+            .checked_add(KEYSTREAM_BLOCKS as u32 - 1)
+            .is_some()
+        {
+            return chacha.keystream_blocks(&Self::block_nonce(nonce, counter), counter as u32);
+        }
+
+        let mut output = [0u8; 64 * KEYSTREAM_BLOCKS];
+
+        for (index, block) in output.chunks_exact_mut(64).enumerate() {
+            block.copy_from_slice(&Self::block(
+                chacha,
+                nonce,
+                counter.wrapping_add(index as u64),
+            ));
+        }
+
+        output
+    }
+
+    pub fn keystream(&self, nonce: &[u8], counter: u64) -> [u8; 64] {
+        Self::block(&self.subkey(nonce), nonce, counter)
+    }
+
+    /// Encrypts from block 0, as libsodium's `crypto_stream_xchacha20_xor`.
+    pub fn xor(&self, msg: &[u8], nonce: &[u8]) -> Vec<u8> {
+        self.xor_ic(msg, nonce, 0)
+    }
+
+    pub fn xor_ic(&self, msg: &[u8], nonce: &[u8], ic: u64) -> Vec<u8> {
+        let chacha = self.subkey(nonce);
+        let mut output = Vec::with_capacity(msg.len());
+
+        for (index, chunk) in msg.chunks(64 * KEYSTREAM_BLOCKS).enumerate() {
+            let counter = ic.wrapping_add((index * KEYSTREAM_BLOCKS) as u64);
+            let keystream = Self::blocks(&chacha, nonce, counter);
+
+            output.extend(chunk.iter().zip(keystream).map(|(m, k)| m ^ k));
+        }
+
+        output
+    }
+}
+
+pub fn xchacha20_xor(msg: &[u8], nonce: &[u8], key: &[u8]) -> Vec<u8> {
+    XChaCha20::new(key).xor(msg, nonce)
+}
+
+pub fn xchacha20_xor_ic(msg: &[u8], nonce: &[u8], ic: u64, key: &[u8]) -> Vec<u8> {
+    XChaCha20::new(key).xor_ic(msg, nonce, ic)
+}
@@ -1,13 +1,14 @@
+pub mod blind;
+pub mod ed25519;
+pub(crate) mod edwards;
+pub mod elligator2;
 pub(crate) mod field;
+pub mod fingerprint;
+pub mod keyfile;
+pub mod pedersen;
+pub mod points;
+pub mod rangeproof;
+pub mod ring;
+pub mod ristretto;
+pub mod scalar;
 pub mod x25519;
-
-#[derive(Debug)]
-pub struct InvalidKey;
-
-impl std::fmt::Display for InvalidKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "This key is an invalid size!")
-    }
-}
-
-impl std::error::Error for InvalidKey {}
@@ -0,0 +1,168 @@
+// Blind Schnorr signatures that come out as ordinary Ed25519 signatures, for issuing tokens that
+// cannot be linked to their issuance. The signer commits to a nonce R = rG, the user blinds it to
+// R' = R + aG + bP and sends back c = H(R', P, m) + b, the signer answers s = r + cx, and the user
+// unblinds to (R', s + a), which `ed25519::VerifyingKey::verify` accepts for m. The signer sees
+// only R, c and s, which are independent of R', m and the final S.
+//
+// Two caveats. Whoever gets blind signatures can get one on any message, so a blind signing key
+// must never sign anything else. And with many sessions open at once, a user can forge one more
+// signature than were issued (the ROS attack), so a `BlindSigner` has one session at a time.
+
+use crate::ecc::ed25519::{self, Signature, SigningKey, VerifyingKey};
+use crate::ecc::edwards::EdwardsPoint;
+use crate::ecc::scalar::Scalar;
+use crate::errors::InvalidSignature;
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlindError {
+    /// The signer's nonce commitment is not a valid point of the prime-order subgroup.
+    InvalidCommitment,
+    /// `sign` was called without a `commit` first, or twice for one commitment.
+    NoSession,
+    /// The blinded challenge is not a canonical scalar.
+    Malformed,
+    /// The signer's response does not verify, so unblinding it would not give a signature.
+    InvalidResponse,
+}
+
+impl std::fmt::Display for BlindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlindError::InvalidCommitment => write!(f, "The nonce commitment is invalid."),
+            BlindError::NoSession => write!(f, "There is no signing session to respond in."),
+            BlindError::Malformed => write!(f, "The blinded challenge is malformed."),
+            BlindError::InvalidResponse => write!(f, "The signer's response does not verify."),
+        }
+    }
+}
+
+impl std::error::Error for BlindError {}
+
+/// The signer's side, holding a key dedicated to blind signing.
+pub struct BlindSigner {
+    key: SigningKey,
+    nonce: Option<Scalar>,
+}
+
+redacted_debug!(BlindSigner, "<redacted>");
+
+impl BlindSigner {
+    pub fn new(key: SigningKey) -> BlindSigner {
+        BlindSigner { key, nonce: None }
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+
+    /// Starts a session, abandoning any open one, and returns the nonce commitment R.
+    pub fn commit(&mut self) -> [u8; 32] {
+        let nonce = Scalar::random();
+        let commitment = EdwardsPoint::mul_base(&nonce).compress();
+
+        if let Some(mut previous) = self.nonce.replace(nonce) {
+            previous.zeroize();
+        }
+
+        commitment
+    }
+
+    /// Answers the blinded challenge of the open session, closing it.
+    pub fn sign(&mut self, blinded_challenge: &[u8; 32]) -> Result<[u8; 32], BlindError> {
+        let mut nonce = self.nonce.take().ok_or(BlindError::NoSession)?;
+
+        let response = match Scalar::from_canonical_bytes(blinded_challenge) {
+            Some(challenge) => Ok((nonce + challenge * *self.key.scalar()).to_bytes()),
+            None => Err(BlindError::Malformed),
+        };
+        nonce.zeroize();
+
+        response
+    }
+}
+
+impl Drop for BlindSigner {
+    fn drop(&mut self) {
+        if let Some(mut nonce) = self.nonce.take() {
+            nonce.zeroize();
+        }
+    }
+}
+
+/// The user's blinding factors for one message, kept between `blind` and `unblind`.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Blinding {
+    alpha: Scalar,
+    // the blinded challenge, as the signer saw it
+    challenge: Scalar,
+    blinded_nonce: [u8; 32],
+    #[zeroize(skip)]
+    commitment: EdwardsPoint,
+    #[zeroize(skip)]
+    public: VerifyingKey,
+}
+
+redacted_debug!(Blinding, "<redacted>");
+
+/// Blinds `msg` against the signer's commitment, returning the blinding factors and the blinded
+/// challenge to send to the signer.
+pub fn blind(
+    public: &VerifyingKey,
+    commitment: &[u8; 32],
+    msg: &[u8],
+) -> Result<(Blinding, [u8; 32]), BlindError> {
+    let point = EdwardsPoint::decompress(commitment).ok_or(BlindError::InvalidCommitment)?;
+
+    if point.is_small_order() || !point.is_torsion_free() {
+        return Err(BlindError::InvalidCommitment);
+    }
+
+    let alpha = Scalar::random();
+    let mut beta = Scalar::random();
+
+    let blinded_nonce = point
+        .add(&EdwardsPoint::mul_base(&alpha))
+        .add(&public.point().mul(&beta))
+        .compress();
+    let challenge = ed25519::challenge(None, &blinded_nonce, public.as_bytes(), msg) + beta;
+    beta.zeroize();
+
+    let blinding = Blinding {
+        alpha,
+        challenge,
+        blinded_nonce,
+        commitment: point,
+        public: *public,
+    };
+
+    Ok((blinding, challenge.to_bytes()))
+}
+
+impl Blinding {
+    /// Checks the signer's response and unblinds it into an Ed25519 signature on the message.
+    pub fn unblind(self, response: &[u8; 32]) -> Result<Signature, BlindError> {
+        let s = Scalar::from_canonical_bytes(response).ok_or(BlindError::InvalidResponse)?;
+
+        // sG = R + cP, for the challenge c the signer was sent
+        let expected = self
+            .commitment
+            .add(&self.public.point().mul(&self.challenge));
+        if EdwardsPoint::mul_base(&s) != expected {
+            return Err(BlindError::InvalidResponse);
+        }
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&self.blinded_nonce);
+        signature[32..].copy_from_slice(&(s + self.alpha).to_bytes());
+
+        Ok(signature)
+    }
+}
+
+/// Verifies an unblinded signature. It is a plain Ed25519 signature, so this is the same as
+/// `public.verify(msg, signature)`.
+pub fn verify(public: &VerifyingKey, msg: &[u8], signature: &[u8]) -> Result<(), InvalidSignature> {
+    public.verify(msg, signature)
+}
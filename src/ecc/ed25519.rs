@@ -0,0 +1,432 @@
+use crate::audit::{self, KeyId, Operation};
+use crate::ecc::edwards::EdwardsPoint;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::x25519;
+use crate::encoding::{base64, hex, pkcs8};
+use crate::errors::Error;
+use crate::errors::InvalidSignature;
+use crate::hashes::blake2b::{self, Blake2b};
+use crate::hashes::sha512::{self, Sha512};
+use crate::secret::SecretString;
+use crate::utils::{const_time_eq, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub type Signature = [u8; 64];
+
+/// The longest context Ed25519ph takes, as its length is encoded in one byte.
+pub const MAX_CONTEXT_LENGTH: usize = 255;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidContext;
+
+impl std::fmt::Display for InvalidContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ed25519ph contexts are at most 255 bytes.")
+    }
+}
+
+impl std::error::Error for InvalidContext {}
+
+// callers check the context length first
+fn dom2(hasher: &mut Sha512, context: Option<&[u8]>) {
+    if let Some(context) = context {
+        hasher.update(b"SigEd25519 no Ed25519 collisions");
+        hasher.update(&[1, context.len() as u8]);
+        hasher.update(context);
+    }
+}
+
+pub(crate) fn challenge(context: Option<&[u8]>, r: &[u8], public: &[u8], msg: &[u8]) -> Scalar {
+    let mut hasher = Sha512::new();
+
+    dom2(&mut hasher, context);
+    hasher.update(r);
+    hasher.update(public);
+    hasher.update(msg);
+
+    Scalar::from_bytes_mod_order_wide(&hasher.digest())
+}
+
+/// Which signatures `verify_with_mode` accepts. Implementations disagree at the edges, so
+/// systems that must agree on validity, such as consensus protocols, should pin one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VerificationMode {
+    /// What `verify` does: canonical encodings of A, R and S, and the cofactorless equation
+    /// [S]B = R + [k]A, as RFC 8032 permits.
+    #[default]
+    Rfc8032,
+    /// `Rfc8032`, also rejecting small-order A and R, so no signature verifies for every
+    /// message or under every key.
+    Strict,
+    /// ZIP-215: S must be canonical, but A and R may use non-canonical encodings and have small
+    /// order, and the cofactored equation [8][S]B = [8]R + [8][k]A is checked. Every ZIP-215
+    /// implementation agrees on every signature, including under batch verification.
+    Zip215,
+}
+
+#[derive(Clone, Copy)]
+pub struct VerifyingKey {
+    bytes: [u8; 32],
+    point: EdwardsPoint,
+}
+
+impl VerifyingKey {
+    pub fn new(key: &[u8]) -> Result<VerifyingKey, Error> {
+        let bytes: [u8; 32] = key.try_into().map_err(|_| Error::InvalidKey)?;
+        let point = EdwardsPoint::decompress(&bytes).ok_or(Error::InvalidKey)?;
+
+        Ok(VerifyingKey { bytes, point })
+    }
+
+    /// Accepts the non-canonical encodings ZIP-215 allows. Signatures under a key that is not
+    /// canonically encoded only verify with `VerificationMode::Zip215`.
+    pub fn new_zip215(key: &[u8]) -> Result<VerifyingKey, Error> {
+        let bytes: [u8; 32] = key.try_into().map_err(|_| Error::InvalidKey)?;
+        let point = EdwardsPoint::decompress_noncanonical(&bytes).ok_or(Error::InvalidKey)?;
+
+        Ok(VerifyingKey { bytes, point })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.bytes
+    }
+
+    pub fn to_x25519(&self) -> x25519::PublicKey {
+        self.point.to_montgomery()
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> Result<(), InvalidSignature> {
+        self.verify_with_context(None, msg, signature, VerificationMode::Rfc8032)
+    }
+
+    pub fn verify_strict(&self, msg: &[u8], signature: &[u8]) -> Result<(), InvalidSignature> {
+        self.verify_with_context(None, msg, signature, VerificationMode::Strict)
+    }
+
+    pub fn verify_with_mode(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        mode: VerificationMode,
+    ) -> Result<(), InvalidSignature> {
+        self.verify_with_context(None, msg, signature, mode)
+    }
+
+    pub(crate) fn point(&self) -> &EdwardsPoint {
+        &self.point
+    }
+
+    fn key_id(&self) -> Option<KeyId> {
+        Some(KeyId::new(&self.bytes))
+    }
+
+    pub fn verify_prehashed(
+        &self,
+        prehash: Sha512,
+        context: &[u8],
+        signature: &[u8],
+    ) -> Result<(), InvalidSignature> {
+        self.verify_with_context(
+            Some(context),
+            &prehash.digest(),
+            signature,
+            VerificationMode::Rfc8032,
+        )
+    }
+
+    fn verify_with_context(
+        &self,
+        context: Option<&[u8]>,
+        msg: &[u8],
+        signature: &[u8],
+        mode: VerificationMode,
+    ) -> Result<(), InvalidSignature> {
+        let result = self.check(context, msg, signature, mode);
+        audit::record(Operation::Verify, || self.key_id(), result.is_ok());
+
+        result
+    }
+
+    fn check(
+        &self,
+        context: Option<&[u8]>,
+        msg: &[u8],
+        signature: &[u8],
+        mode: VerificationMode,
+    ) -> Result<(), InvalidSignature> {
+        // no signature can have been made with a context that is too long
+        if signature.len() != 64 || context.is_some_and(|c| c.len() > MAX_CONTEXT_LENGTH) {
+            return Err(InvalidSignature);
+        }
+
+        let r: &[u8; 32] = signature[..32].try_into().unwrap();
+        let s = Scalar::from_canonical_bytes(signature[32..].try_into().unwrap())
+            .ok_or(InvalidSignature)?;
+        let k = challenge(context, r, &self.bytes, msg);
+
+        if mode == VerificationMode::Zip215 {
+            let r = EdwardsPoint::decompress_noncanonical(r).ok_or(InvalidSignature)?;
+            let check = EdwardsPoint::mul_base(&s).sub(&r).sub(&self.point.mul(&k));
+
+            if !check.is_small_order() {
+                return Err(InvalidSignature);
+            }
+
+            return Ok(());
+        }
+
+        // only keys made with `new_zip215` can be non-canonical
+        if self.point.compress() != self.bytes {
+            return Err(InvalidSignature);
+        }
+
+        if mode == VerificationMode::Strict {
+            let r = EdwardsPoint::decompress(r).ok_or(InvalidSignature)?;
+
+            if self.point.is_small_order() || r.is_small_order() {
+                return Err(InvalidSignature);
+            }
+        }
+
+        let check = EdwardsPoint::mul_base(&s).sub(&self.point.mul(&k));
+
+        if check.compress() != *r {
+            return Err(InvalidSignature);
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VerifyingKey({})", hex::encode(&self.bytes))
+    }
+}
+
+impl PartialEq for VerifyingKey {
+    fn eq(&self, other: &VerifyingKey) -> bool {
+        const_time_eq(&self.bytes, &other.bytes)
+    }
+}
+
+impl Eq for VerifyingKey {}
+
+impl std::hash::Hash for VerifyingKey {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        state.write(&blake2b::hash(&self.bytes, 16));
+    }
+}
+
+impl PartialOrd for VerifyingKey {
+    fn partial_cmp(&self, other: &VerifyingKey) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VerifyingKey {
+    fn cmp(&self, other: &VerifyingKey) -> std::cmp::Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SigningKey {
+    seed: [u8; 32],
+    scalar: Scalar,
+    prefix: [u8; 32],
+    #[zeroize(skip)]
+    public: VerifyingKey,
+}
+
+redacted_debug!(SigningKey, "<32 bytes, redacted>");
+
+impl SigningKey {
+    pub fn new(seed: &[u8]) -> Result<SigningKey, Error> {
+        let seed: [u8; 32] = seed.try_into().map_err(|_| Error::InvalidKey)?;
+        let mut h = sha512::hash(&seed);
+
+        h[0] &= 248;
+        h[31] &= 127;
+        h[31] |= 64;
+
+        let scalar = Scalar::from_bytes_mod_order(h[..32].try_into().unwrap());
+        let prefix = h[32..].try_into().unwrap();
+        let point = EdwardsPoint::mul_base(&scalar);
+
+        h.zeroize();
+
+        Ok(SigningKey {
+            seed,
+            scalar,
+            prefix,
+            public: VerifyingKey {
+                bytes: point.compress(),
+                point,
+            },
+        })
+    }
+
+    pub fn from_seed(seed: &[u8]) -> SigningKey {
+        let mut hasher = Blake2b::new_keyed(b"raycrypt ed25519 seed", 32);
+        hasher.update(seed);
+
+        let mut key = hasher.digest();
+        let signing = SigningKey::new(&key).unwrap();
+        key.zeroize();
+
+        signing
+    }
+
+    pub fn from_hex(key: &str) -> Result<SigningKey, Error> {
+        let mut bytes = hex::decode(key)?;
+        let key = SigningKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn from_base64(key: &str) -> Result<SigningKey, Error> {
+        let mut bytes = base64::decode(key)?;
+        let key = SigningKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn from_pem(key: &str) -> Result<SigningKey, Error> {
+        let mut bytes = pkcs8::decode_pem(key, pkcs8::ED25519)?;
+        let key = SigningKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.seed
+    }
+
+    pub fn to_hex(&self) -> SecretString {
+        SecretString::encode_hex(&self.seed)
+    }
+
+    pub fn to_base64(&self) -> SecretString {
+        SecretString::encode_base64(&self.seed)
+    }
+
+    pub fn to_pem(&self) -> SecretString {
+        SecretString::new(pkcs8::encode_pem(&self.seed, pkcs8::ED25519))
+    }
+
+    pub fn to_x25519(&self) -> x25519::PrivateKey {
+        let mut h = sha512::hash(&self.seed);
+        let private = x25519::PrivateKey::new(&h[..32]).unwrap();
+        h.zeroize();
+
+        private
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.public
+    }
+
+    pub(crate) fn scalar(&self) -> &Scalar {
+        &self.scalar
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.sign_with_context(None, msg)
+    }
+
+    /// Signs with Ed25519ph, failing if `context` is longer than `MAX_CONTEXT_LENGTH`.
+    pub fn sign_prehashed(
+        &self,
+        prehash: Sha512,
+        context: &[u8],
+    ) -> Result<Signature, InvalidContext> {
+        if context.len() > MAX_CONTEXT_LENGTH {
+            return Err(InvalidContext);
+        }
+
+        Ok(self.sign_with_context(Some(context), &prehash.digest()))
+    }
+
+    fn sign_with_context(&self, context: Option<&[u8]>, msg: &[u8]) -> Signature {
+        let mut hasher = Sha512::new();
+        dom2(&mut hasher, context);
+        hasher.update(&self.prefix);
+        hasher.update(msg);
+
+        let r = Scalar::from_bytes_mod_order_wide(&hasher.digest());
+        let big_r = EdwardsPoint::mul_base(&r).compress();
+
+        let k = challenge(context, &big_r, &self.public.bytes, msg);
+        let s = r + k * self.scalar;
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&big_r);
+        signature[32..].copy_from_slice(&s.to_bytes());
+
+        audit::record(Operation::Sign, || self.public.key_id(), true);
+
+        signature
+    }
+}
+
+pub fn keygen(seed: Option<&[u8]>) -> SigningKey {
+    match seed {
+        Some(seed) => SigningKey::from_seed(seed),
+        None => {
+            let mut key = [0u8; 32];
+            crate::rng::fill(&mut key);
+
+            let signing = SigningKey::new(&key).unwrap();
+            key.zeroize();
+
+            signing
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Keypair {
+    signing: SigningKey,
+}
+
+impl Keypair {
+    pub fn new(signing: SigningKey) -> Keypair {
+        Keypair { signing }
+    }
+
+    pub fn generate() -> Keypair {
+        Keypair::new(keygen(None))
+    }
+
+    pub fn from_pem(key: &str) -> Result<Keypair, Error> {
+        SigningKey::from_pem(key).map(Keypair::new)
+    }
+
+    pub fn public(&self) -> VerifyingKey {
+        self.signing.verifying_key()
+    }
+
+    pub fn private(&self) -> &SigningKey {
+        &self.signing
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.signing.sign(msg)
+    }
+
+    pub fn to_pem(&self) -> SecretString {
+        self.signing.to_pem()
+    }
+
+    pub fn to_x25519(&self) -> x25519::Keypair {
+        x25519::Keypair::new(self.signing.to_x25519())
+    }
+}
@@ -0,0 +1,263 @@
+use crate::ecc::field::FieldElement;
+use crate::ecc::scalar::Scalar;
+
+pub(crate) const D: [u8; 32] = [
+    163, 120, 89, 19, 202, 77, 235, 117, 171, 216, 65, 65, 77, 10, 112, 0, 152, 232, 121, 119, 121,
+    64, 199, 140, 115, 254, 111, 43, 238, 108, 3, 82,
+];
+
+const D2: [u8; 32] = [
+    89, 241, 178, 38, 148, 155, 214, 235, 86, 177, 131, 130, 154, 20, 224, 0, 48, 209, 243, 238,
+    242, 128, 142, 25, 231, 252, 223, 86, 220, 217, 6, 36,
+];
+
+pub(crate) const SQRT_M1: [u8; 32] = [
+    176, 160, 14, 74, 39, 27, 238, 196, 120, 228, 47, 173, 6, 24, 67, 47, 167, 215, 251, 61, 153,
+    0, 77, 43, 11, 223, 193, 79, 128, 36, 131, 43,
+];
+
+pub const BASEPOINT: [u8; 32] = [
+    88, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102,
+    102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102, 102,
+];
+
+// extended coordinates: x = X/Z, y = Y/Z, xy = T/Z
+#[derive(Clone, Copy)]
+pub struct EdwardsPoint {
+    pub(crate) x: FieldElement,
+    pub(crate) y: FieldElement,
+    pub(crate) z: FieldElement,
+    pub(crate) t: FieldElement,
+}
+
+struct Cached {
+    y_plus_x: FieldElement,
+    y_minus_x: FieldElement,
+    z: FieldElement,
+    t2d: FieldElement,
+}
+
+struct Completed {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl Completed {
+    fn to_extended(&self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: self.x * self.t,
+            y: self.y * self.z,
+            z: self.z * self.t,
+            t: self.x * self.y,
+        }
+    }
+}
+
+impl EdwardsPoint {
+    fn cached(&self) -> Cached {
+        Cached {
+            y_plus_x: self.y + self.x,
+            y_minus_x: self.y - self.x,
+            z: self.z,
+            t2d: self.t * FieldElement::from_bytes(&D2),
+        }
+    }
+
+    fn add_cached(&self, q: &Cached) -> Completed {
+        let a = self.y + self.x;
+        let b = self.y - self.x;
+        let z = a * q.y_plus_x;
+        let y = b * q.y_minus_x;
+        let t = q.t2d * self.t;
+        let x = self.z * q.z;
+        let t0 = x + x;
+
+        Completed {
+            x: z - y,
+            y: z + y,
+            z: t0 + t,
+            t: t0 - t,
+        }
+    }
+
+    fn sub_cached(&self, q: &Cached) -> Completed {
+        let a = self.y + self.x;
+        let b = self.y - self.x;
+        let z = a * q.y_minus_x;
+        let y = b * q.y_plus_x;
+        let t = q.t2d * self.t;
+        let x = self.z * q.z;
+        let t0 = x + x;
+
+        Completed {
+            x: z - y,
+            y: z + y,
+            z: t0 - t,
+            t: t0 + t,
+        }
+    }
+}
+
+impl EdwardsPoint {
+    pub fn identity() -> EdwardsPoint {
+        EdwardsPoint {
+            x: FieldElement::zero(),
+            y: FieldElement::one(),
+            z: FieldElement::one(),
+            t: FieldElement::zero(),
+        }
+    }
+
+    pub fn basepoint() -> EdwardsPoint {
+        EdwardsPoint::decompress(&BASEPOINT).unwrap()
+    }
+
+    pub fn decompress(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+        let y = FieldElement::from_bytes(bytes);
+
+        let mut canonical = y.to_bytes();
+        canonical[31] |= bytes[31] & 0x80;
+
+        if canonical != *bytes {
+            return None;
+        }
+
+        let point = EdwardsPoint::decompress_noncanonical(bytes)?;
+
+        // the sign bit of x = 0 must be clear
+        if !point.x.is_nonzero() && bytes[31] >> 7 == 1 {
+            return None;
+        }
+
+        Some(point)
+    }
+
+    /// `decompress` that also accepts y >= p and a set sign bit on x = 0, as ZIP-215 requires.
+    pub fn decompress_noncanonical(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+        let y = FieldElement::from_bytes(bytes);
+        let z = FieldElement::one();
+        let yy = y.square();
+        let u = yy - z;
+        let v = yy * FieldElement::from_bytes(&D) + z;
+
+        let v3 = v.square() * v;
+        let mut x = v3.square() * v * u;
+        x = x.pow25523();
+        x = x * v3 * u;
+
+        let vxx = x.square() * v;
+
+        if (vxx - u).is_nonzero() {
+            if (vxx + u).is_nonzero() {
+                return None;
+            }
+
+            x = x * FieldElement::from_bytes(&SQRT_M1);
+        }
+
+        let sign = bytes[31] >> 7;
+
+        if x.is_negative() as u8 != sign {
+            x = x.neg();
+        }
+
+        Some(EdwardsPoint { t: x * y, x, y, z })
+    }
+
+    pub fn compress(&self) -> [u8; 32] {
+        let recip = self.z.invert();
+        let x = self.x * recip;
+        let y = self.y * recip;
+
+        let mut output = y.to_bytes();
+        output[31] ^= (x.is_negative() as u8) << 7;
+
+        output
+    }
+
+    pub fn to_montgomery(self) -> [u8; 32] {
+        let u = (self.z + self.y) * (self.z - self.y).invert();
+
+        u.to_bytes()
+    }
+
+    pub fn double(&self) -> EdwardsPoint {
+        let xx = self.x.square();
+        let yy = self.y.square();
+        let zz2 = self.z.square_and_double();
+        let x_plus_y = self.x + self.y;
+        let t0 = x_plus_y.square();
+
+        let y = yy + xx;
+        let z = yy - xx;
+
+        Completed {
+            x: t0 - y,
+            y,
+            t: zz2 - z,
+            z,
+        }
+        .to_extended()
+    }
+
+    pub fn conditional_assign(&mut self, other: &EdwardsPoint, choice: u8) {
+        let choice = choice as i32;
+
+        self.x.maybe_set(&other.x, choice);
+        self.y.maybe_set(&other.y, choice);
+        self.z.maybe_set(&other.z, choice);
+        self.t.maybe_set(&other.t, choice);
+    }
+
+    pub fn mul(&self, scalar: &Scalar) -> EdwardsPoint {
+        let mut output = EdwardsPoint::identity();
+
+        for i in (0..256).rev() {
+            output = output.double();
+            let sum = output.add(self);
+            output.conditional_assign(&sum, scalar.bit(i));
+        }
+
+        output
+    }
+
+    pub fn mul_base(scalar: &Scalar) -> EdwardsPoint {
+        EdwardsPoint::basepoint().mul(scalar)
+    }
+
+    pub fn add(&self, other: &EdwardsPoint) -> EdwardsPoint {
+        self.add_cached(&other.cached()).to_extended()
+    }
+
+    pub fn sub(&self, other: &EdwardsPoint) -> EdwardsPoint {
+        self.sub_cached(&other.cached()).to_extended()
+    }
+
+    pub fn mul_by_cofactor(&self) -> EdwardsPoint {
+        self.double().double().double()
+    }
+
+    pub fn is_small_order(&self) -> bool {
+        self.mul_by_cofactor() == EdwardsPoint::identity()
+    }
+
+    // multiplying by the group order l, as (l - 1)P + P, leaves only the small-order component
+    pub fn is_torsion_free(&self) -> bool {
+        self.mul(&-Scalar::from_u64(1)).add(self) == EdwardsPoint::identity()
+    }
+}
+
+impl PartialEq for EdwardsPoint {
+    fn eq(&self, other: &EdwardsPoint) -> bool {
+        let x1 = self.x * other.z;
+        let x2 = other.x * self.z;
+        let y1 = self.y * other.z;
+        let y2 = other.y * self.z;
+
+        !(x1 - x2).is_nonzero() && !(y1 - y2).is_nonzero()
+    }
+}
+
+impl Eq for EdwardsPoint {}
@@ -0,0 +1,116 @@
+use crate::ecc::edwards::{EdwardsPoint, SQRT_M1};
+use crate::ecc::field::FieldElement;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::x25519::{PrivateKey, PublicKey};
+use zeroize::Zeroize;
+
+// a point of order 8 on the Edwards curve, used to make public keys cover the whole curve
+const EIGHT_TORSION: [u8; 32] = [
+    0x26, 0xe8, 0x95, 0x8f, 0xc2, 0xb2, 0x27, 0xb0, 0x45, 0xc3, 0xf4, 0x89, 0xf2, 0xef, 0x98, 0xf0,
+    0xd5, 0xdf, 0xac, 0x05, 0xd3, 0xc6, 0x33, 0x39, 0xb1, 0x38, 0x02, 0x88, 0x6d, 0x53, 0xfc, 0x05,
+];
+
+fn small(n: u32) -> FieldElement {
+    let mut bytes = [0u8; 32];
+    bytes[..4].copy_from_slice(&n.to_le_bytes());
+
+    FieldElement::from_bytes(&bytes)
+}
+
+fn sqrt(x: &FieldElement) -> (bool, FieldElement) {
+    let mut root = *x * x.pow25523();
+    let check = root.square();
+
+    let correct = !(check - *x).is_nonzero();
+    let flipped = !(check + *x).is_nonzero();
+
+    root.maybe_set(&(root * FieldElement::from_bytes(&SQRT_M1)), flipped as i32);
+
+    (correct | flipped, root)
+}
+
+pub fn map(representative: &[u8; 32]) -> PublicKey {
+    let a = small(486662);
+
+    let mut bytes = *representative;
+    bytes[31] &= 0x3f;
+
+    let r = FieldElement::from_bytes(&bytes);
+    let w = a.neg() * (FieldElement::one() + r.square().mul32(2)).invert();
+    let (is_square, _) = sqrt(&(w * (w.square() + a * w + FieldElement::one())));
+
+    let mut u = w.neg() - a;
+    u.maybe_set(&w, is_square as i32);
+
+    u.to_bytes()
+}
+
+pub fn unmap(public: &PublicKey, tweak: u8) -> Option<[u8; 32]> {
+    let a = small(486662);
+    let u = FieldElement::from_bytes(public);
+
+    if u.to_bytes() != *public {
+        return None;
+    }
+
+    let u_plus_a = u + a;
+
+    if !u_plus_a.is_nonzero() {
+        return None;
+    }
+
+    let (is_square, _) = sqrt(&(u * u_plus_a).mul32(2).neg());
+
+    if !is_square {
+        return None;
+    }
+
+    let mut r2 = u.neg() * u_plus_a.mul32(2).invert();
+    r2.maybe_set(&(u_plus_a.neg() * u.mul32(2).invert()), (tweak & 1) as i32);
+
+    let (_, mut r) = sqrt(&r2);
+    r.maybe_set(&r.neg(), (r + r).is_negative() as i32);
+
+    let mut representative = r.to_bytes();
+    representative[31] |= tweak & 0xc0;
+
+    Some(representative)
+}
+
+pub fn representative(private: &PrivateKey, tweak: u8) -> Option<[u8; 32]> {
+    let mut clamped = private.key;
+    clamped[0] &= 248;
+    clamped[31] &= 127;
+    clamped[31] |= 64;
+
+    let mut scalar = Scalar::from_bytes_mod_order(&clamped);
+    let torsion = EdwardsPoint::decompress(&EIGHT_TORSION)
+        .unwrap()
+        .mul(&Scalar::from_u64((tweak >> 1 & 7) as u64));
+
+    let public = EdwardsPoint::mul_base(&scalar)
+        .add(&torsion)
+        .to_montgomery();
+
+    clamped.zeroize();
+    scalar.zeroize();
+
+    unmap(&public, tweak)
+}
+
+pub fn generate() -> (PrivateKey, [u8; 32]) {
+    loop {
+        let mut seed = [0u8; 32];
+        let mut tweak = [0u8; 1];
+
+        crate::rng::fill(&mut seed);
+        crate::rng::fill(&mut tweak);
+
+        let private = PrivateKey::new(&seed).unwrap();
+        seed.zeroize();
+
+        if let Some(representative) = representative(&private, tweak[0]) {
+            return (private, representative);
+        }
+    }
+}
@@ -191,22 +191,12 @@ impl FieldElement {
     }
 
     pub fn maybe_set(&mut self, other: &FieldElement, swap: i32) {
-        let mut x = [0i32; 10];
-
         let b = -swap;
 
         for i in 0..10 {
-            x[i] = (&self)[i] ^ (&other)[i];
-            x[i] &= b;
-        }
-
-        let mut output = FieldElement::zero();
-
-        for i in 0..10 {
-            output[i] = (&self)[i] ^ (&other)[i];
+            let x = (self[i] ^ other[i]) & b;
+            self[i] ^= x;
         }
-
-        *self = output
     }
 
     pub fn square_and_double(&self) -> FieldElement {
@@ -0,0 +1,131 @@
+use crate::ecc::ed25519::VerifyingKey;
+use crate::ecc::x25519::PublicKey;
+use crate::encoding::{base64, hex};
+use crate::hashes::{blake2b, sha256};
+use crate::utils::const_time_eq;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FingerprintAlgorithm {
+    Blake2b,
+    Sha256,
+}
+
+impl FingerprintAlgorithm {
+    /// The longest fingerprint the algorithm gives, in bytes.
+    pub fn max_length(self) -> usize {
+        match self {
+            FingerprintAlgorithm::Blake2b => 64,
+            FingerprintAlgorithm::Sha256 => 32,
+        }
+    }
+}
+
+/// The fingerprint length is zero or longer than the algorithm's output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidFingerprintLength;
+
+impl std::fmt::Display for InvalidFingerprintLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Fingerprints are 1 to 64 bytes with BLAKE2b and 1 to 32 with SHA-256."
+        )
+    }
+}
+
+impl std::error::Error for InvalidFingerprintLength {}
+
+#[derive(Clone, Debug)]
+pub struct Fingerprint {
+    algorithm: FingerprintAlgorithm,
+    digest: Vec<u8>,
+}
+
+impl PartialEq for Fingerprint {
+    fn eq(&self, other: &Fingerprint) -> bool {
+        self.algorithm == other.algorithm && const_time_eq(&self.digest, &other.digest)
+    }
+}
+
+impl Eq for Fingerprint {}
+
+impl std::hash::Hash for Fingerprint {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        self.algorithm.hash(state);
+        self.digest.hash(state);
+    }
+}
+
+impl Fingerprint {
+    pub fn new(
+        key: &[u8],
+        algorithm: FingerprintAlgorithm,
+        length: usize,
+    ) -> Result<Fingerprint, InvalidFingerprintLength> {
+        if !(1..=algorithm.max_length()).contains(&length) {
+            return Err(InvalidFingerprintLength);
+        }
+
+        let digest = match algorithm {
+            FingerprintAlgorithm::Blake2b => blake2b::hash(key, length),
+            FingerprintAlgorithm::Sha256 => sha256::hash(key)[..length].to_vec(),
+        };
+
+        Ok(Fingerprint { algorithm, digest })
+    }
+
+    pub fn algorithm(&self) -> FingerprintAlgorithm {
+        self.algorithm
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.digest
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.digest)
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64::encode(&self.digest)
+    }
+
+    pub fn to_colon_hex(&self) -> String {
+        self.digest
+            .iter()
+            .map(|byte| hex::encode(&[*byte]))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
+
+pub trait PublicKeyFingerprint {
+    fn fingerprint_bytes(&self) -> &[u8];
+
+    fn fingerprint(
+        &self,
+        algorithm: FingerprintAlgorithm,
+        length: usize,
+    ) -> Result<Fingerprint, InvalidFingerprintLength> {
+        Fingerprint::new(self.fingerprint_bytes(), algorithm, length)
+    }
+
+    fn verify_fingerprint(&self, expected: &Fingerprint) -> bool {
+        match self.fingerprint(expected.algorithm, expected.digest.len()) {
+            Ok(actual) => const_time_eq(&actual.digest, &expected.digest),
+            Err(_) => false,
+        }
+    }
+}
+
+impl PublicKeyFingerprint for VerifyingKey {
+    fn fingerprint_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl PublicKeyFingerprint for PublicKey {
+    fn fingerprint_bytes(&self) -> &[u8] {
+        self
+    }
+}
@@ -0,0 +1,195 @@
+// Password-protected Ed25519 identity files, for command-line tools that keep a signing key on
+// disk. The public key is stored in the clear so it can be shown without the password, and the
+// seed is sealed with XChaCha20-Poly1305 under a key stretched from the password with Argon2id.
+//
+// Layout: magic || version || memory cost || time cost || parallelism (u32 each) || salt (16) ||
+// nonce (24) || public key (32) || sealed seed (48). Everything before the sealed seed is its
+// associated data, so the parameters and public key cannot be swapped out.
+
+use crate::aeads::XChaCha20Poly1305;
+use crate::ecc::ed25519::{SigningKey, VerifyingKey};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::kdfs::argon2::{Argon2, Variant};
+use crate::utils::random_bytes;
+use std::io::Write;
+use std::path::Path;
+use zeroize::Zeroize;
+
+const MAGIC: [u8; 4] = *b"RCEK";
+const VERSION: u8 = 1;
+const SALT_SIZE: usize = 16;
+const HEADER_SIZE: usize = 4 + 1 + 12 + SALT_SIZE + XChaCha20Poly1305::NONCE_SIZE + 32;
+
+pub const FILE_SIZE: usize = HEADER_SIZE + 32 + XChaCha20Poly1305::TAG_SIZE;
+
+/// The most memory a file may ask Argon2 for, in KiB: that of `Argon2::SENSITIVE`. Anything more
+/// is rejected before deriving, so a crafted file cannot exhaust memory.
+pub const MAX_MEMORY_COST: u32 = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFileError {
+    Io(std::io::ErrorKind),
+    Malformed,
+    /// The password is wrong, or the file was tampered with.
+    WrongPassword,
+}
+
+impl std::fmt::Display for KeyFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyFileError::Io(kind) => write!(f, "Could not access the key file: {}.", kind),
+            KeyFileError::Malformed => write!(f, "The key file is malformed."),
+            KeyFileError::WrongPassword => write!(f, "The password for the key file is wrong."),
+        }
+    }
+}
+
+impl std::error::Error for KeyFileError {}
+
+impl From<InvalidEncoding> for KeyFileError {
+    fn from(_: InvalidEncoding) -> KeyFileError {
+        KeyFileError::Malformed
+    }
+}
+
+impl From<std::io::Error> for KeyFileError {
+    fn from(error: std::io::Error) -> KeyFileError {
+        KeyFileError::Io(error.kind())
+    }
+}
+
+fn sealing_key(kdf: &Argon2, password: &[u8], salt: &[u8]) -> XChaCha20Poly1305 {
+    // the parameters are checked and the salt and length are fixed, so this cannot fail
+    let mut key = kdf
+        .hash(password, salt, XChaCha20Poly1305::KEY_SIZE)
+        .unwrap();
+    let aead = XChaCha20Poly1305::new(&key);
+    key.zeroize();
+
+    aead
+}
+
+/// Seals `key` under `password`, stretched with the memory, time and lanes of `kdf`, which must
+/// be an Argon2id instance such as `Argon2::MODERATE`.
+pub fn seal(key: &SigningKey, password: &[u8], kdf: &Argon2) -> Vec<u8> {
+    assert_eq!(kdf.variant(), Variant::Argon2id);
+    assert!(kdf.memory_cost() <= MAX_MEMORY_COST);
+
+    let salt = random_bytes::<SALT_SIZE>();
+    let nonce = XChaCha20Poly1305::generate_nonce();
+
+    let mut output = Vec::with_capacity(FILE_SIZE);
+    output.extend_from_slice(&MAGIC);
+    output.push(VERSION);
+    output.extend_from_slice(&kdf.memory_cost().to_be_bytes());
+    output.extend_from_slice(&kdf.time_cost().to_be_bytes());
+    output.extend_from_slice(&kdf.parallelism().to_be_bytes());
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(key.verifying_key().as_bytes());
+
+    let mut seed = key.to_bytes();
+    let sealed = sealing_key(kdf, password, &salt).encrypt(&seed, &nonce, &output);
+    seed.zeroize();
+
+    output.extend_from_slice(&sealed);
+
+    output
+}
+
+struct Parsed<'a> {
+    kdf: Argon2,
+    salt: &'a [u8],
+    nonce: &'a [u8],
+    public: VerifyingKey,
+    header: &'a [u8],
+    sealed: &'a [u8],
+}
+
+fn parse(bytes: &[u8]) -> Result<Parsed<'_>, KeyFileError> {
+    let mut reader = Reader::new(bytes);
+
+    if reader.array::<4>()? != MAGIC || reader.byte()? != VERSION {
+        return Err(KeyFileError::Malformed);
+    }
+
+    let memory_cost = reader.u32()?;
+    let time_cost = reader.u32()?;
+    let parallelism = reader.u32()?;
+
+    if memory_cost > MAX_MEMORY_COST {
+        return Err(KeyFileError::Malformed);
+    }
+
+    let kdf = Argon2::new(Variant::Argon2id, memory_cost, time_cost, parallelism)
+        .map_err(|_| KeyFileError::Malformed)?;
+    let salt = reader.bytes(SALT_SIZE)?;
+    let nonce = reader.bytes(XChaCha20Poly1305::NONCE_SIZE)?;
+    let public = VerifyingKey::new(reader.bytes(32)?).map_err(|_| KeyFileError::Malformed)?;
+    let sealed = reader.bytes(32 + XChaCha20Poly1305::TAG_SIZE)?;
+    reader.finish()?;
+
+    Ok(Parsed {
+        kdf,
+        salt,
+        nonce,
+        public,
+        header: &bytes[..HEADER_SIZE],
+        sealed,
+    })
+}
+
+/// Recovers the signing key, which takes one Argon2 run with the file's parameters.
+pub fn open(bytes: &[u8], password: &[u8]) -> Result<SigningKey, KeyFileError> {
+    let parsed = parse(bytes)?;
+
+    let mut seed = sealing_key(&parsed.kdf, password, parsed.salt)
+        .decrypt(parsed.sealed, parsed.nonce, parsed.header)
+        .map_err(|_| KeyFileError::WrongPassword)?;
+    let key = SigningKey::new(&seed).unwrap();
+    seed.zeroize();
+
+    // only a file written by something else could get this far with a mismatched key
+    if key.verifying_key() != parsed.public {
+        return Err(KeyFileError::Malformed);
+    }
+
+    Ok(key)
+}
+
+/// Reads the public key, which needs no password.
+pub fn public_key(bytes: &[u8]) -> Result<VerifyingKey, KeyFileError> {
+    Ok(parse(bytes)?.public)
+}
+
+/// Writes `seal`'s output to `path`, replacing any file there. On Unix the file is created
+/// readable by its owner only.
+pub fn save<P: AsRef<Path>>(
+    path: P,
+    key: &SigningKey,
+    password: &[u8],
+    kdf: &Argon2,
+) -> Result<(), KeyFileError> {
+    let sealed = seal(key, password, kdf);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(&sealed)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+pub fn load<P: AsRef<Path>>(path: P, password: &[u8]) -> Result<SigningKey, KeyFileError> {
+    open(&std::fs::read(path)?, password)
+}
+
+pub fn load_public_key<P: AsRef<Path>>(path: P) -> Result<VerifyingKey, KeyFileError> {
+    public_key(&std::fs::read(path)?)
+}
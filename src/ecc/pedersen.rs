@@ -0,0 +1,98 @@
+// Pedersen commitments over ristretto255. A commitment to v with blinding r is vG + rH, where H
+// is hashed to the group so that nobody knows its discrete logarithm relative to G: the
+// commitment reveals nothing about v, and opening it to another value means solving that
+// logarithm. Commitments add up, committing to the sum of the values under the sum of the
+// blindings. A vector commitment uses one value generator per entry, the first of which is G.
+
+use crate::ecc::points::PointError;
+use crate::ecc::ristretto::RistrettoPoint;
+use crate::ecc::scalar::Scalar;
+
+const BLINDING_LABEL: &[u8] = b"raycrypt pedersen blinding generator";
+const VALUE_LABEL: &[u8] = b"raycrypt pedersen value generator";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidOpening;
+
+impl std::fmt::Display for InvalidOpening {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The commitment does not open to these values.")
+    }
+}
+
+impl std::error::Error for InvalidOpening {}
+
+/// H, the generator the blinding factor multiplies.
+pub fn blinding_generator() -> RistrettoPoint {
+    RistrettoPoint::hash_from_bytes(BLINDING_LABEL)
+}
+
+/// The generator the value at `index` of a vector multiplies: the basepoint for index 0, and a
+/// point hashed from the index otherwise.
+pub fn value_generator(index: u64) -> RistrettoPoint {
+    if index == 0 {
+        return RistrettoPoint::basepoint();
+    }
+
+    RistrettoPoint::hash_from_bytes(&[VALUE_LABEL, &index.to_le_bytes()].concat())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment(RistrettoPoint);
+
+impl Commitment {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Commitment, PointError> {
+        Ok(Commitment(RistrettoPoint::from_bytes(bytes)?))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    pub fn as_point(&self) -> &RistrettoPoint {
+        &self.0
+    }
+
+    pub fn add(&self, other: &Commitment) -> Commitment {
+        Commitment(self.0.add(&other.0))
+    }
+
+    pub fn sub(&self, other: &Commitment) -> Commitment {
+        Commitment(self.0.sub(&other.0))
+    }
+}
+
+pub fn commit(value: &Scalar, blinding: &Scalar) -> Commitment {
+    commit_vector(core::slice::from_ref(value), blinding)
+}
+
+pub fn commit_vector(values: &[Scalar], blinding: &Scalar) -> Commitment {
+    let point = values
+        .iter()
+        .zip(0..)
+        .fold(blinding_generator().mul(blinding), |sum, (value, index)| {
+            sum.add(&value_generator(index).mul(value))
+        });
+
+    Commitment(point)
+}
+
+pub fn verify(
+    commitment: &Commitment,
+    value: &Scalar,
+    blinding: &Scalar,
+) -> Result<(), InvalidOpening> {
+    verify_vector(commitment, core::slice::from_ref(value), blinding)
+}
+
+pub fn verify_vector(
+    commitment: &Commitment,
+    values: &[Scalar],
+    blinding: &Scalar,
+) -> Result<(), InvalidOpening> {
+    if commit_vector(values, blinding) != *commitment {
+        return Err(InvalidOpening);
+    }
+
+    Ok(())
+}
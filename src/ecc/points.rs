@@ -0,0 +1,216 @@
+// Curve25519 points for protocols that do their own group operations. Decoding is canonical
+// unless asked otherwise, and the predicates cover the checks such protocols tend to need before
+// trusting a point: small order, prime order, and whether a Montgomery u-coordinate is on the
+// curve at all rather than on its twist.
+
+use crate::ecc::edwards;
+use crate::ecc::field::FieldElement;
+use crate::ecc::scalar::Scalar;
+
+// every u-coordinate of small order on the curve or its twist, canonically encoded
+const SMALL_ORDER_U: [[u8; 32]; 5] = [
+    [0; 32],
+    [
+        1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0,
+    ],
+    [
+        224, 235, 122, 124, 59, 65, 184, 174, 22, 86, 227, 250, 241, 159, 196, 106, 218, 9, 141,
+        235, 156, 50, 177, 253, 134, 98, 5, 22, 95, 73, 184, 0,
+    ],
+    [
+        95, 156, 149, 188, 163, 80, 140, 36, 177, 208, 177, 85, 156, 131, 239, 91, 4, 68, 92, 196,
+        88, 28, 142, 134, 216, 34, 78, 221, 208, 159, 17, 87,
+    ],
+    [
+        236, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
+        255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 127,
+    ],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointError {
+    /// The encoding is not the unique one for its point.
+    NonCanonical,
+    NotOnCurve,
+}
+
+impl std::fmt::Display for PointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointError::NonCanonical => write!(f, "The point encoding is not canonical."),
+            PointError::NotOnCurve => write!(f, "The point is not on the curve."),
+        }
+    }
+}
+
+impl std::error::Error for PointError {}
+
+fn is_canonical_field_element(bytes: &[u8; 32]) -> bool {
+    FieldElement::from_bytes(bytes).to_bytes() == *bytes
+}
+
+/// A point on the twisted Edwards form of Curve25519, as used by Ed25519.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct EdwardsPoint(edwards::EdwardsPoint);
+
+impl std::fmt::Debug for EdwardsPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EdwardsPoint({})",
+            crate::encoding::hex::encode(&self.to_bytes())
+        )
+    }
+}
+
+impl EdwardsPoint {
+    pub fn identity() -> EdwardsPoint {
+        EdwardsPoint(edwards::EdwardsPoint::identity())
+    }
+
+    pub fn basepoint() -> EdwardsPoint {
+        EdwardsPoint(edwards::EdwardsPoint::basepoint())
+    }
+
+    /// Decodes a point, rejecting y >= p and a sign bit set on x = 0.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<EdwardsPoint, PointError> {
+        let point = EdwardsPoint::from_bytes_noncanonical(bytes)?;
+
+        if point.to_bytes() != *bytes {
+            return Err(PointError::NonCanonical);
+        }
+
+        Ok(point)
+    }
+
+    /// Decodes a point the way ZIP-215 does, accepting every encoding of it.
+    pub fn from_bytes_noncanonical(bytes: &[u8; 32]) -> Result<EdwardsPoint, PointError> {
+        edwards::EdwardsPoint::decompress_noncanonical(bytes)
+            .map(EdwardsPoint)
+            .ok_or(PointError::NotOnCurve)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.compress()
+    }
+
+    pub fn add(&self, other: &EdwardsPoint) -> EdwardsPoint {
+        EdwardsPoint(self.0.add(&other.0))
+    }
+
+    pub fn sub(&self, other: &EdwardsPoint) -> EdwardsPoint {
+        EdwardsPoint(self.0.sub(&other.0))
+    }
+
+    pub fn double(&self) -> EdwardsPoint {
+        EdwardsPoint(self.0.double())
+    }
+
+    pub fn mul(&self, scalar: &Scalar) -> EdwardsPoint {
+        EdwardsPoint(self.0.mul(scalar))
+    }
+
+    pub fn mul_base(scalar: &Scalar) -> EdwardsPoint {
+        EdwardsPoint(edwards::EdwardsPoint::mul_base(scalar))
+    }
+
+    pub fn mul_by_cofactor(&self) -> EdwardsPoint {
+        EdwardsPoint(self.0.mul_by_cofactor())
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.0 == edwards::EdwardsPoint::identity()
+    }
+
+    /// Whether the point is one of the eight whose order divides the cofactor.
+    pub fn is_small_order(&self) -> bool {
+        self.0.is_small_order()
+    }
+
+    /// Whether the point is in the prime-order subgroup, with no small-order component.
+    pub fn is_torsion_free(&self) -> bool {
+        self.0.is_torsion_free()
+    }
+
+    pub fn to_montgomery(&self) -> MontgomeryPoint {
+        MontgomeryPoint(self.0.to_montgomery())
+    }
+}
+
+/// A u-coordinate on the Montgomery form of Curve25519, as used by X25519. It may be on the
+/// twist instead of the curve, which X25519 allows but group operations do not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MontgomeryPoint([u8; 32]);
+
+impl MontgomeryPoint {
+    /// Decodes a u-coordinate that is reduced, has the top bit clear and is on the curve.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<MontgomeryPoint, PointError> {
+        if bytes[31] >> 7 == 1 || !is_canonical_field_element(bytes) {
+            return Err(PointError::NonCanonical);
+        }
+
+        let point = MontgomeryPoint(*bytes);
+
+        if !point.is_on_curve() {
+            return Err(PointError::NotOnCurve);
+        }
+
+        Ok(point)
+    }
+
+    /// Decodes a u-coordinate as RFC 7748 does: the top bit is ignored, the rest reduced, and
+    /// twist points are kept.
+    pub fn from_bytes_noncanonical(bytes: &[u8; 32]) -> MontgomeryPoint {
+        MontgomeryPoint(FieldElement::from_bytes(bytes).to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let u = FieldElement::from_bytes(&self.0);
+        let one = FieldElement::one();
+
+        // u = -1 is on the twist, and has no image on the Edwards curve
+        if !(u + one).is_nonzero() {
+            return false;
+        }
+
+        // v^2 = u^3 + Au^2 + u has a solution exactly when the birationally equivalent Edwards
+        // y = (u - 1) / (u + 1) does
+        let y = (u - one) * (u + one).invert();
+
+        edwards::EdwardsPoint::decompress_noncanonical(&y.to_bytes()).is_some()
+    }
+
+    /// Whether the u-coordinate has small order on the curve or its twist. X25519 with such a
+    /// point gives an all-zero shared secret.
+    pub fn is_small_order(&self) -> bool {
+        SMALL_ORDER_U.contains(&self.0)
+    }
+
+    pub fn is_torsion_free(&self) -> bool {
+        match self.to_edwards(false) {
+            Some(point) => point.is_torsion_free(),
+            None => false,
+        }
+    }
+
+    /// The Edwards point with this u-coordinate and the sign of x given by `sign`, or `None` for a
+    /// point on the twist. The sign is ignored when x = 0.
+    pub fn to_edwards(&self, sign: bool) -> Option<EdwardsPoint> {
+        let u = FieldElement::from_bytes(&self.0);
+        let one = FieldElement::one();
+
+        if !(u + one).is_nonzero() {
+            return None;
+        }
+
+        let mut bytes = ((u - one) * (u + one).invert()).to_bytes();
+        bytes[31] |= (sign as u8) << 7;
+
+        EdwardsPoint::from_bytes_noncanonical(&bytes).ok()
+    }
+}
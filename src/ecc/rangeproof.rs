@@ -0,0 +1,426 @@
+// Bulletproofs range proofs (Bünz et al., 2018) for one 64-bit value under a Pedersen commitment
+// from `ecc::pedersen`: the proof shows 0 <= v < 2^64 without revealing v or the blinding. It is
+// 672 bytes, logarithmic in the bit size thanks to the inner-product argument, and needs no
+// trusted setup. The challenges come from the shared SHA-512 transcript, and the vector
+// generators are hashed to the group.
+
+use crate::ecc::pedersen::{self, Commitment};
+use crate::ecc::ristretto::RistrettoPoint;
+use crate::ecc::scalar::Scalar;
+use crate::hashes::sha512::Sha512;
+use crate::protocols::transcript::Transcript;
+use std::sync::OnceLock;
+use zeroize::Zeroize;
+
+pub const BITS: usize = 64;
+pub const PROOF_SIZE: usize = 32 * (7 + 2 * ROUNDS + 2);
+
+const ROUNDS: usize = BITS.trailing_zeros() as usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeProofError {
+    Malformed,
+    /// The proof does not show that the committed value is in range.
+    Invalid,
+}
+
+impl std::fmt::Display for RangeProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeProofError::Malformed => write!(f, "The range proof is malformed."),
+            RangeProofError::Invalid => write!(f, "The range proof does not verify."),
+        }
+    }
+}
+
+impl std::error::Error for RangeProofError {}
+
+struct Generators {
+    g: Vec<RistrettoPoint>,
+    h: Vec<RistrettoPoint>,
+    q: RistrettoPoint,
+}
+
+fn generators() -> &'static Generators {
+    static GENERATORS: OnceLock<Generators> = OnceLock::new();
+
+    GENERATORS.get_or_init(|| {
+        let hashed = |label: &[u8], i: usize| {
+            RistrettoPoint::hash_from_bytes(&[label, &(i as u64).to_le_bytes()].concat())
+        };
+
+        Generators {
+            g: (0..BITS)
+                .map(|i| hashed(b"raycrypt bulletproofs G", i))
+                .collect(),
+            h: (0..BITS)
+                .map(|i| hashed(b"raycrypt bulletproofs H", i))
+                .collect(),
+            q: RistrettoPoint::hash_from_bytes(b"raycrypt bulletproofs Q"),
+        }
+    })
+}
+
+fn transcript(commitment: &Commitment) -> Transcript<Sha512> {
+    let mut transcript = Transcript::new(b"raycrypt bulletproofs range proof");
+    transcript.append(b"bits", &(BITS as u64).to_le_bytes());
+    transcript.append(b"V", &commitment.to_bytes());
+
+    transcript
+}
+
+// each challenge is bound to its label and absorbed back, so later challenges depend on it
+fn challenge(transcript: &mut Transcript<Sha512>, label: &[u8]) -> Scalar {
+    transcript.append(b"challenge", label);
+    let digest: [u8; 64] = transcript.hash().try_into().unwrap();
+    let challenge = Scalar::from_bytes_mod_order_wide(&digest);
+    transcript.append(label, &challenge.to_bytes());
+
+    challenge
+}
+
+fn inner_product(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter()
+        .zip(b)
+        .fold(Scalar::ZERO, |sum, (x, y)| sum + *x * *y)
+}
+
+fn multiscalar_mul(scalars: &[Scalar], points: &[RistrettoPoint]) -> RistrettoPoint {
+    scalars
+        .iter()
+        .zip(points)
+        .fold(RistrettoPoint::identity(), |sum, (scalar, point)| {
+            sum.add(&point.mul(scalar))
+        })
+}
+
+fn powers(x: &Scalar, n: usize) -> Vec<Scalar> {
+    std::iter::successors(Some(Scalar::ONE), |power| Some(*power * *x))
+        .take(n)
+        .collect()
+}
+
+fn random_vector(n: usize) -> Vec<Scalar> {
+    (0..n).map(|_| Scalar::random()).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeProof {
+    a: RistrettoPoint,
+    s: RistrettoPoint,
+    t1: RistrettoPoint,
+    t2: RistrettoPoint,
+    t_x: Scalar,
+    t_x_blinding: Scalar,
+    e_blinding: Scalar,
+    l_vec: Vec<RistrettoPoint>,
+    r_vec: Vec<RistrettoPoint>,
+    ipp_a: Scalar,
+    ipp_b: Scalar,
+}
+
+impl RangeProof {
+    pub fn from_bytes(bytes: &[u8]) -> Result<RangeProof, RangeProofError> {
+        if bytes.len() != PROOF_SIZE {
+            return Err(RangeProofError::Malformed);
+        }
+
+        let point = |offset: usize| {
+            RistrettoPoint::from_bytes(bytes[offset..offset + 32].try_into().unwrap())
+                .map_err(|_| RangeProofError::Malformed)
+        };
+        let scalar = |offset: usize| {
+            Scalar::from_canonical_bytes(bytes[offset..offset + 32].try_into().unwrap())
+                .ok_or(RangeProofError::Malformed)
+        };
+
+        let mut l_vec = Vec::with_capacity(ROUNDS);
+        let mut r_vec = Vec::with_capacity(ROUNDS);
+
+        for round in 0..ROUNDS {
+            l_vec.push(point(224 + 64 * round)?);
+            r_vec.push(point(256 + 64 * round)?);
+        }
+
+        Ok(RangeProof {
+            a: point(0)?,
+            s: point(32)?,
+            t1: point(64)?,
+            t2: point(96)?,
+            t_x: scalar(128)?,
+            t_x_blinding: scalar(160)?,
+            e_blinding: scalar(192)?,
+            l_vec,
+            r_vec,
+            ipp_a: scalar(PROOF_SIZE - 64)?,
+            ipp_b: scalar(PROOF_SIZE - 32)?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(PROOF_SIZE);
+
+        for point in [&self.a, &self.s, &self.t1, &self.t2] {
+            output.extend_from_slice(&point.to_bytes());
+        }
+
+        for scalar in [&self.t_x, &self.t_x_blinding, &self.e_blinding] {
+            output.extend_from_slice(&scalar.to_bytes());
+        }
+
+        for (l, r) in self.l_vec.iter().zip(&self.r_vec) {
+            output.extend_from_slice(&l.to_bytes());
+            output.extend_from_slice(&r.to_bytes());
+        }
+
+        output.extend_from_slice(&self.ipp_a.to_bytes());
+        output.extend_from_slice(&self.ipp_b.to_bytes());
+
+        output
+    }
+}
+
+/// Commits to `value` under `blinding`, as `pedersen::commit` does, and proves it is below 2^64.
+pub fn prove(value: u64, blinding: &Scalar) -> (Commitment, RangeProof) {
+    let generators = generators();
+    let b = RistrettoPoint::basepoint();
+    let h = pedersen::blinding_generator();
+
+    let commitment = pedersen::commit(&Scalar::from_u64(value), blinding);
+    let mut transcript = transcript(&commitment);
+
+    // a_L holds the bits of the value, and a_R = a_L - 1
+    let mut a_l: Vec<Scalar> = (0..BITS)
+        .map(|i| Scalar::from_u64((value >> i) & 1))
+        .collect();
+    let mut a_r: Vec<Scalar> = a_l.iter().map(|bit| *bit - Scalar::ONE).collect();
+
+    let mut alpha = Scalar::random();
+    let a = h
+        .mul(&alpha)
+        .add(&multiscalar_mul(&a_l, &generators.g))
+        .add(&multiscalar_mul(&a_r, &generators.h));
+
+    let mut s_l = random_vector(BITS);
+    let mut s_r = random_vector(BITS);
+    let mut rho = Scalar::random();
+    let s = h
+        .mul(&rho)
+        .add(&multiscalar_mul(&s_l, &generators.g))
+        .add(&multiscalar_mul(&s_r, &generators.h));
+
+    transcript.append(b"A", &a.to_bytes());
+    transcript.append(b"S", &s.to_bytes());
+    let y = challenge(&mut transcript, b"y");
+    let z = challenge(&mut transcript, b"z");
+    let z2 = z * z;
+
+    // l(X) = l0 + l1 X and r(X) = r0 + r1 X, with t(X) = <l(X), r(X)> = t0 + t1 X + t2 X^2
+    let y_n = powers(&y, BITS);
+    let two_n = powers(&Scalar::from_u64(2), BITS);
+    let mut l0: Vec<Scalar> = a_l.iter().map(|a| *a - z).collect();
+    let mut r0: Vec<Scalar> = (0..BITS)
+        .map(|i| y_n[i] * (a_r[i] + z) + z2 * two_n[i])
+        .collect();
+    let mut r1: Vec<Scalar> = (0..BITS).map(|i| y_n[i] * s_r[i]).collect();
+
+    let t1 = inner_product(&l0, &r1) + inner_product(&s_l, &r0);
+    let t2 = inner_product(&s_l, &r1);
+
+    let mut tau1 = Scalar::random();
+    let mut tau2 = Scalar::random();
+    let big_t1 = b.mul(&t1).add(&h.mul(&tau1));
+    let big_t2 = b.mul(&t2).add(&h.mul(&tau2));
+
+    transcript.append(b"T1", &big_t1.to_bytes());
+    transcript.append(b"T2", &big_t2.to_bytes());
+    let x = challenge(&mut transcript, b"x");
+
+    let l: Vec<Scalar> = (0..BITS).map(|i| l0[i] + s_l[i] * x).collect();
+    let r: Vec<Scalar> = (0..BITS).map(|i| r0[i] + r1[i] * x).collect();
+    let t_x = inner_product(&l, &r);
+    let t_x_blinding = tau2 * x * x + tau1 * x + z2 * *blinding;
+    let e_blinding = alpha + rho * x;
+
+    transcript.append(b"t_x", &t_x.to_bytes());
+    transcript.append(b"t_x_blinding", &t_x_blinding.to_bytes());
+    transcript.append(b"e_blinding", &e_blinding.to_bytes());
+    let q = generators.q.mul(&challenge(&mut transcript, b"w"));
+
+    // the inner-product argument runs over H'_i = y^-i H_i
+    let y_inv_n = powers(&y.invert(), BITS);
+    let h_prime: Vec<RistrettoPoint> = (0..BITS)
+        .map(|i| generators.h[i].mul(&y_inv_n[i]))
+        .collect();
+    let (l_vec, r_vec, ipp_a, ipp_b) =
+        inner_product_proof(&mut transcript, &q, generators.g.clone(), h_prime, l, r);
+
+    for secret in [
+        &mut a_l, &mut a_r, &mut s_l, &mut s_r, &mut l0, &mut r0, &mut r1,
+    ] {
+        secret.zeroize();
+    }
+    for secret in [&mut alpha, &mut rho, &mut tau1, &mut tau2] {
+        secret.zeroize();
+    }
+
+    let proof = RangeProof {
+        a,
+        s,
+        t1: big_t1,
+        t2: big_t2,
+        t_x,
+        t_x_blinding,
+        e_blinding,
+        l_vec,
+        r_vec,
+        ipp_a,
+        ipp_b,
+    };
+
+    (commitment, proof)
+}
+
+type InnerProductProof = (Vec<RistrettoPoint>, Vec<RistrettoPoint>, Scalar, Scalar);
+
+// halves the vectors each round, committing to the cross terms in L and R
+fn inner_product_proof(
+    transcript: &mut Transcript<Sha512>,
+    q: &RistrettoPoint,
+    mut g: Vec<RistrettoPoint>,
+    mut h: Vec<RistrettoPoint>,
+    mut a: Vec<Scalar>,
+    mut b: Vec<Scalar>,
+) -> InnerProductProof {
+    let mut l_vec = Vec::with_capacity(ROUNDS);
+    let mut r_vec = Vec::with_capacity(ROUNDS);
+
+    while a.len() > 1 {
+        let n = a.len() / 2;
+        let (a_lo, a_hi) = a.split_at(n);
+        let (b_lo, b_hi) = b.split_at(n);
+        let (g_lo, g_hi) = g.split_at(n);
+        let (h_lo, h_hi) = h.split_at(n);
+
+        let l = multiscalar_mul(a_lo, g_hi)
+            .add(&multiscalar_mul(b_hi, h_lo))
+            .add(&q.mul(&inner_product(a_lo, b_hi)));
+        let r = multiscalar_mul(a_hi, g_lo)
+            .add(&multiscalar_mul(b_lo, h_hi))
+            .add(&q.mul(&inner_product(a_hi, b_lo)));
+
+        transcript.append(b"L", &l.to_bytes());
+        transcript.append(b"R", &r.to_bytes());
+        let u = challenge(transcript, b"u");
+        let u_inv = u.invert();
+
+        let folded_a = (0..n).map(|i| a_lo[i] * u + a_hi[i] * u_inv).collect();
+        let folded_b = (0..n).map(|i| b_lo[i] * u_inv + b_hi[i] * u).collect();
+        let folded_g = (0..n)
+            .map(|i| g_lo[i].mul(&u_inv).add(&g_hi[i].mul(&u)))
+            .collect();
+        let folded_h = (0..n)
+            .map(|i| h_lo[i].mul(&u).add(&h_hi[i].mul(&u_inv)))
+            .collect();
+
+        a.zeroize();
+        b.zeroize();
+        (a, b, g, h) = (folded_a, folded_b, folded_g, folded_h);
+
+        l_vec.push(l);
+        r_vec.push(r);
+    }
+
+    (l_vec, r_vec, a[0], b[0])
+}
+
+pub fn verify(commitment: &Commitment, proof: &RangeProof) -> Result<(), RangeProofError> {
+    if proof.l_vec.len() != ROUNDS || proof.r_vec.len() != ROUNDS {
+        return Err(RangeProofError::Malformed);
+    }
+
+    let generators = generators();
+    let b = RistrettoPoint::basepoint();
+    let h = pedersen::blinding_generator();
+    let mut transcript = transcript(commitment);
+
+    transcript.append(b"A", &proof.a.to_bytes());
+    transcript.append(b"S", &proof.s.to_bytes());
+    let y = challenge(&mut transcript, b"y");
+    let z = challenge(&mut transcript, b"z");
+    let z2 = z * z;
+
+    transcript.append(b"T1", &proof.t1.to_bytes());
+    transcript.append(b"T2", &proof.t2.to_bytes());
+    let x = challenge(&mut transcript, b"x");
+
+    transcript.append(b"t_x", &proof.t_x.to_bytes());
+    transcript.append(b"t_x_blinding", &proof.t_x_blinding.to_bytes());
+    transcript.append(b"e_blinding", &proof.e_blinding.to_bytes());
+    let q = generators.q.mul(&challenge(&mut transcript, b"w"));
+
+    // t(x) is committed correctly: t_x B + tau_x H = z^2 V + delta(y, z) B + x T1 + x^2 T2
+    let y_n = powers(&y, BITS);
+    let two_n = powers(&Scalar::from_u64(2), BITS);
+    let sum_y = y_n.iter().fold(Scalar::ZERO, |sum, power| sum + *power);
+    let sum_two = two_n.iter().fold(Scalar::ZERO, |sum, power| sum + *power);
+    let delta = (z - z2) * sum_y - z2 * z * sum_two;
+
+    let lhs = b.mul(&proof.t_x).add(&h.mul(&proof.t_x_blinding));
+    let rhs = commitment
+        .as_point()
+        .mul(&z2)
+        .add(&b.mul(&delta))
+        .add(&proof.t1.mul(&x))
+        .add(&proof.t2.mul(&(x * x)));
+
+    if lhs != rhs {
+        return Err(RangeProofError::Invalid);
+    }
+
+    // P = A + xS - z<1, G> + <z + z^2 2^i y^-i, H> - mu H_blinding + t_x Q, then the
+    // inner-product argument: P + sum(u_j^2 L_j + u_j^-2 R_j) = a<s, G> + b<s^-1, H'> + ab Q
+    let mut challenges = Vec::with_capacity(ROUNDS);
+    for (l, r) in proof.l_vec.iter().zip(&proof.r_vec) {
+        transcript.append(b"L", &l.to_bytes());
+        transcript.append(b"R", &r.to_bytes());
+        challenges.push(challenge(&mut transcript, b"u"));
+    }
+
+    let inverses: Vec<Scalar> = challenges.iter().map(Scalar::invert).collect();
+    let y_inv_n = powers(&y.invert(), BITS);
+
+    // s_i is the product of u_j for each set bit of i, from the top, and u_j^-1 for each clear one
+    let s: Vec<Scalar> = (0..BITS)
+        .map(|i| {
+            (0..ROUNDS).fold(Scalar::ONE, |product, j| {
+                if (i >> (ROUNDS - 1 - j)) & 1 == 1 {
+                    product * challenges[j]
+                } else {
+                    product * inverses[j]
+                }
+            })
+        })
+        .collect();
+
+    let g_scalars: Vec<Scalar> = s.iter().map(|s_i| proof.ipp_a * *s_i + z).collect();
+    let h_scalars: Vec<Scalar> = (0..BITS)
+        .map(|i| proof.ipp_b * s[BITS - 1 - i] * y_inv_n[i] - z - z2 * two_n[i] * y_inv_n[i])
+        .collect();
+
+    let mut expected = multiscalar_mul(&g_scalars, &generators.g)
+        .add(&multiscalar_mul(&h_scalars, &generators.h))
+        .add(&q.mul(&(proof.ipp_a * proof.ipp_b - proof.t_x)))
+        .add(&h.mul(&proof.e_blinding));
+
+    for ((l, r), u) in proof.l_vec.iter().zip(&proof.r_vec).zip(&challenges) {
+        let u2 = *u * *u;
+        expected = expected.sub(&l.mul(&u2)).sub(&r.mul(&u2.invert()));
+    }
+
+    if expected != proof.a.add(&proof.s.mul(&x)) {
+        return Err(RangeProofError::Invalid);
+    }
+
+    Ok(())
+}
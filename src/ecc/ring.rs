@@ -0,0 +1,350 @@
+// Ring signatures over ristretto255: a signature shows that the holder of one of the ring's keys
+// signed, without showing which. SAG is the plain scheme (Abe, Ohkubo, Suzuki, 2002). LSAG (Liu,
+// Wei, Wong, 2004) adds a key image I = xH(P), the same for every signature by one key, so two
+// signatures by the same member can be linked while the member stays anonymous.
+
+use crate::ecc::points::PointError;
+use crate::ecc::ristretto::RistrettoPoint;
+use crate::ecc::scalar::Scalar;
+use crate::errors::InvalidSignature;
+use crate::hashes::sha512::Sha512;
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const SAG_LABEL: &[u8] = b"raycrypt sag";
+const LSAG_LABEL: &[u8] = b"raycrypt lsag";
+const KEY_IMAGE_LABEL: &[u8] = b"raycrypt lsag key image";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RingError {
+    /// The signing key's public key is not in the ring.
+    KeyNotInRing,
+    Malformed,
+}
+
+impl std::fmt::Display for RingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RingError::KeyNotInRing => write!(f, "The signing key is not a member of the ring."),
+            RingError::Malformed => write!(f, "The ring signature is malformed."),
+        }
+    }
+}
+
+impl std::error::Error for RingError {}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey(Scalar);
+
+redacted_debug!(SecretKey, "<32 bytes, redacted>");
+
+impl SecretKey {
+    pub fn generate() -> SecretKey {
+        SecretKey(Scalar::random())
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<SecretKey> {
+        let scalar = Scalar::from_canonical_bytes(bytes)?;
+
+        if scalar.is_zero() {
+            return None;
+        }
+
+        Some(SecretKey(scalar))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(RistrettoPoint::mul_base(&self.0))
+    }
+
+    /// The linkability tag every LSAG signature by this key carries.
+    pub fn key_image(&self) -> KeyImage {
+        KeyImage(image_base(&self.public_key()).mul(&self.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(RistrettoPoint);
+
+impl PublicKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<PublicKey, PointError> {
+        Ok(PublicKey(RistrettoPoint::from_bytes(bytes)?))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyImage(RistrettoPoint);
+
+impl KeyImage {
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+/// A SAG signature: the first challenge, then one response per ring member.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    challenge: Scalar,
+    responses: Vec<Scalar>,
+}
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, RingError> {
+        let (challenge, responses) = parse_scalars(bytes)?;
+
+        Ok(Signature {
+            challenge,
+            responses,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_scalars(&self.challenge, &self.responses)
+    }
+}
+
+/// An LSAG signature: the key image, the first challenge, then one response per ring member.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkableSignature {
+    key_image: KeyImage,
+    challenge: Scalar,
+    responses: Vec<Scalar>,
+}
+
+impl LinkableSignature {
+    pub fn from_bytes(bytes: &[u8]) -> Result<LinkableSignature, RingError> {
+        if bytes.len() < 32 {
+            return Err(RingError::Malformed);
+        }
+
+        let image = RistrettoPoint::from_bytes(bytes[..32].try_into().unwrap())
+            .map_err(|_| RingError::Malformed)?;
+        let (challenge, responses) = parse_scalars(&bytes[32..])?;
+
+        Ok(LinkableSignature {
+            key_image: KeyImage(image),
+            challenge,
+            responses,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            &self.key_image.to_bytes()[..],
+            &serialize_scalars(&self.challenge, &self.responses),
+        ]
+        .concat()
+    }
+
+    pub fn key_image(&self) -> KeyImage {
+        self.key_image
+    }
+
+    /// Whether both signatures were made with the same key, whatever the rings and messages.
+    pub fn is_linked(&self, other: &LinkableSignature) -> bool {
+        self.key_image == other.key_image
+    }
+}
+
+fn parse_scalars(bytes: &[u8]) -> Result<(Scalar, Vec<Scalar>), RingError> {
+    if bytes.len() < 64 || !bytes.len().is_multiple_of(32) {
+        return Err(RingError::Malformed);
+    }
+
+    let mut scalars = bytes
+        .chunks_exact(32)
+        .map(|chunk| Scalar::from_canonical_bytes(chunk.try_into().unwrap()))
+        .collect::<Option<Vec<Scalar>>>()
+        .ok_or(RingError::Malformed)?;
+    let challenge = scalars.remove(0);
+
+    Ok((challenge, scalars))
+}
+
+fn serialize_scalars(challenge: &Scalar, responses: &[Scalar]) -> Vec<u8> {
+    let mut output = challenge.to_bytes().to_vec();
+
+    for response in responses {
+        output.extend_from_slice(&response.to_bytes());
+    }
+
+    output
+}
+
+// H(P), the base of a member's key image
+fn image_base(public: &PublicKey) -> RistrettoPoint {
+    RistrettoPoint::hash_from_bytes(&[KEY_IMAGE_LABEL, &public.to_bytes()].concat())
+}
+
+// the ring and everything but the commitments, hashed once per signature
+fn transcript(ring: &[PublicKey], key_image: Option<&KeyImage>, msg: &[u8]) -> Sha512 {
+    let mut hasher = Sha512::new();
+
+    match key_image {
+        Some(image) => {
+            hasher.update(LSAG_LABEL);
+            hasher.update(&image.to_bytes());
+        }
+        None => hasher.update(SAG_LABEL),
+    }
+
+    hasher.update(&(ring.len() as u64).to_le_bytes());
+    for member in ring {
+        hasher.update(&member.to_bytes());
+    }
+
+    hasher.update(&(msg.len() as u64).to_le_bytes());
+    hasher.update(msg);
+
+    hasher
+}
+
+fn challenge(transcript: &Sha512, l: &RistrettoPoint, r: Option<&RistrettoPoint>) -> Scalar {
+    let mut hasher = transcript.clone();
+    hasher.update(&l.to_bytes());
+
+    if let Some(r) = r {
+        hasher.update(&r.to_bytes());
+    }
+
+    Scalar::from_bytes_mod_order_wide(&hasher.digest())
+}
+
+// the challenge after member i, from its challenge c and response s:
+// H(sG + cP_i, sH(P_i) + cI)
+fn next_challenge(
+    transcript: &Sha512,
+    member: &PublicKey,
+    key_image: Option<&KeyImage>,
+    c: &Scalar,
+    s: &Scalar,
+) -> Scalar {
+    let l = RistrettoPoint::mul_base(s).add(&member.0.mul(c));
+    let r = key_image.map(|image| image_base(member).mul(s).add(&image.0.mul(c)));
+
+    challenge(transcript, &l, r.as_ref())
+}
+
+fn sign_ring(
+    key: &SecretKey,
+    ring: &[PublicKey],
+    msg: &[u8],
+    key_image: Option<&KeyImage>,
+) -> Result<(Scalar, Vec<Scalar>), RingError> {
+    let public = key.public_key();
+    let index = ring
+        .iter()
+        .position(|member| *member == public)
+        .ok_or(RingError::KeyNotInRing)?;
+    let transcript = transcript(ring, key_image, msg);
+
+    let mut alpha = Scalar::random();
+    let l = RistrettoPoint::mul_base(&alpha);
+    let r = key_image.map(|_| image_base(&public).mul(&alpha));
+
+    let mut responses = vec![Scalar::ZERO; ring.len()];
+    let mut c = challenge(&transcript, &l, r.as_ref());
+    let mut first = c;
+
+    for offset in 1..ring.len() {
+        let i = (index + offset) % ring.len();
+
+        if i == 0 {
+            first = c;
+        }
+
+        responses[i] = Scalar::random();
+        c = next_challenge(&transcript, &ring[i], key_image, &c, &responses[i]);
+    }
+
+    if index == 0 {
+        first = c;
+    }
+
+    responses[index] = alpha - c * key.0;
+    alpha.zeroize();
+
+    Ok((first, responses))
+}
+
+fn verify_ring(
+    ring: &[PublicKey],
+    msg: &[u8],
+    key_image: Option<&KeyImage>,
+    challenge: &Scalar,
+    responses: &[Scalar],
+) -> Result<(), InvalidSignature> {
+    if ring.is_empty() || responses.len() != ring.len() {
+        return Err(InvalidSignature);
+    }
+
+    let transcript = transcript(ring, key_image, msg);
+    let c = ring
+        .iter()
+        .zip(responses)
+        .fold(*challenge, |c, (member, s)| {
+            next_challenge(&transcript, member, key_image, &c, s)
+        });
+
+    if c != *challenge {
+        return Err(InvalidSignature);
+    }
+
+    Ok(())
+}
+
+/// Signs `msg` as an anonymous member of `ring`, which must contain `key`'s public key.
+pub fn sign(key: &SecretKey, ring: &[PublicKey], msg: &[u8]) -> Result<Signature, RingError> {
+    let (challenge, responses) = sign_ring(key, ring, msg, None)?;
+
+    Ok(Signature {
+        challenge,
+        responses,
+    })
+}
+
+pub fn verify(
+    ring: &[PublicKey],
+    msg: &[u8],
+    signature: &Signature,
+) -> Result<(), InvalidSignature> {
+    verify_ring(ring, msg, None, &signature.challenge, &signature.responses)
+}
+
+/// Signs `msg` as an anonymous member of `ring`, tagged with `key`'s key image.
+pub fn sign_linkable(
+    key: &SecretKey,
+    ring: &[PublicKey],
+    msg: &[u8],
+) -> Result<LinkableSignature, RingError> {
+    let key_image = key.key_image();
+    let (challenge, responses) = sign_ring(key, ring, msg, Some(&key_image))?;
+
+    Ok(LinkableSignature {
+        key_image,
+        challenge,
+        responses,
+    })
+}
+
+pub fn verify_linkable(
+    ring: &[PublicKey],
+    msg: &[u8],
+    signature: &LinkableSignature,
+) -> Result<(), InvalidSignature> {
+    verify_ring(
+        ring,
+        msg,
+        Some(&signature.key_image),
+        &signature.challenge,
+        &signature.responses,
+    )
+}
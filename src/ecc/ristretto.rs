@@ -0,0 +1,214 @@
+// ristretto255 (RFC 9496): a prime-order group built on Curve25519. Every encoding names exactly
+// one group element and there is no cofactor to clear, so protocols written for prime-order
+// groups can use it directly.
+
+use crate::ecc::edwards::{EdwardsPoint, D, SQRT_M1};
+use crate::ecc::field::FieldElement;
+use crate::ecc::points::PointError;
+use crate::ecc::scalar::Scalar;
+use crate::hashes::sha512;
+
+const SQRT_AD_MINUS_ONE: [u8; 32] = [
+    27, 46, 123, 73, 160, 246, 151, 126, 189, 84, 120, 27, 12, 142, 157, 175, 253, 209, 245, 49,
+    201, 252, 60, 15, 172, 72, 131, 43, 191, 49, 105, 55,
+];
+
+const INVSQRT_A_MINUS_D: [u8; 32] = [
+    234, 64, 93, 128, 170, 253, 200, 153, 190, 114, 65, 90, 23, 22, 47, 157, 64, 216, 1, 254, 145,
+    123, 194, 22, 162, 252, 175, 207, 5, 137, 108, 120,
+];
+
+const ONE_MINUS_D_SQ: [u8; 32] = [
+    118, 193, 95, 148, 193, 9, 124, 226, 15, 53, 94, 205, 56, 161, 129, 44, 228, 223, 112, 190,
+    221, 171, 148, 153, 215, 224, 179, 178, 168, 114, 144, 2,
+];
+
+const D_MINUS_ONE_SQ: [u8; 32] = [
+    32, 77, 237, 68, 170, 90, 173, 49, 153, 25, 30, 176, 44, 74, 158, 210, 235, 78, 155, 82, 47,
+    211, 220, 76, 65, 34, 108, 246, 122, 179, 104, 89,
+];
+
+fn equal(a: &FieldElement, b: &FieldElement) -> bool {
+    !(a - b).is_nonzero()
+}
+
+fn select(a: &FieldElement, b: &FieldElement, choice: bool) -> FieldElement {
+    let mut output = *a;
+    output.maybe_set(b, choice as i32);
+
+    output
+}
+
+fn abs(x: &FieldElement) -> FieldElement {
+    select(x, &x.neg(), x.is_negative())
+}
+
+// (was_square, sqrt(u / v)) if u / v is square, and (false, sqrt(i * u / v)) otherwise
+fn sqrt_ratio_m1(u: &FieldElement, v: &FieldElement) -> (bool, FieldElement) {
+    let sqrt_m1 = FieldElement::from_bytes(&SQRT_M1);
+    let v3 = v.square() * *v;
+    let v7 = v3.square() * *v;
+    let r = (*u * v3) * (*u * v7).pow25523();
+    let check = *v * r.square();
+
+    let correct_sign = equal(&check, u);
+    let flipped_sign = equal(&check, &u.neg());
+    let flipped_sign_i = equal(&check, &(u.neg() * sqrt_m1));
+
+    let r = select(&r, &(r * sqrt_m1), flipped_sign | flipped_sign_i);
+
+    (correct_sign | flipped_sign, abs(&r))
+}
+
+// the Elligator map from a field element to a point
+fn map(t: &FieldElement) -> EdwardsPoint {
+    let one = FieldElement::one();
+    let d = FieldElement::from_bytes(&D);
+
+    let r = FieldElement::from_bytes(&SQRT_M1) * t.square();
+    let u = (r + one) * FieldElement::from_bytes(&ONE_MINUS_D_SQ);
+    let v = (one.neg() - r * d) * (r + d);
+
+    let (was_square, s) = sqrt_ratio_m1(&u, &v);
+    let s = select(&abs(&(s * *t)).neg(), &s, was_square);
+    let c = select(&r, &one.neg(), was_square);
+
+    let n = c * (r - one) * FieldElement::from_bytes(&D_MINUS_ONE_SQ) - v;
+    let w0 = (s + s) * v;
+    let w1 = n * FieldElement::from_bytes(&SQRT_AD_MINUS_ONE);
+    let w2 = one - s.square();
+    let w3 = one + s.square();
+
+    EdwardsPoint {
+        x: w0 * w3,
+        y: w2 * w1,
+        z: w1 * w3,
+        t: w0 * w2,
+    }
+}
+
+/// An element of the ristretto255 group.
+#[derive(Clone, Copy)]
+pub struct RistrettoPoint(EdwardsPoint);
+
+impl RistrettoPoint {
+    pub fn identity() -> RistrettoPoint {
+        RistrettoPoint(EdwardsPoint::identity())
+    }
+
+    pub fn basepoint() -> RistrettoPoint {
+        RistrettoPoint(EdwardsPoint::basepoint())
+    }
+
+    /// Decodes an element, rejecting every encoding but the canonical one.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<RistrettoPoint, PointError> {
+        let s = FieldElement::from_bytes(bytes);
+
+        if s.to_bytes() != *bytes || s.is_negative() {
+            return Err(PointError::NonCanonical);
+        }
+
+        let one = FieldElement::one();
+        let ss = s.square();
+        let u1 = one - ss;
+        let u2 = one + ss;
+        let u2_sqr = u2.square();
+        let v = (FieldElement::from_bytes(&D) * u1.square()).neg() - u2_sqr;
+
+        let (was_square, invsqrt) = sqrt_ratio_m1(&one, &(v * u2_sqr));
+        let den_x = invsqrt * u2;
+        let den_y = invsqrt * den_x * v;
+
+        let x = abs(&((s + s) * den_x));
+        let y = u1 * den_y;
+        let t = x * y;
+
+        if !was_square || t.is_negative() || !y.is_nonzero() {
+            return Err(PointError::NotOnCurve);
+        }
+
+        Ok(RistrettoPoint(EdwardsPoint { x, y, z: one, t }))
+    }
+
+    /// Maps 64 uniformly random bytes to an element, with a distribution indistinguishable from
+    /// uniform and no known discrete logarithm.
+    pub fn from_uniform_bytes(bytes: &[u8; 64]) -> RistrettoPoint {
+        let p1 = map(&FieldElement::from_bytes(&bytes[..32]));
+        let p2 = map(&FieldElement::from_bytes(&bytes[32..]));
+
+        RistrettoPoint(p1.add(&p2))
+    }
+
+    /// Hashes arbitrary input to an element: SHA-512, then `from_uniform_bytes`.
+    pub fn hash_from_bytes(input: &[u8]) -> RistrettoPoint {
+        RistrettoPoint::from_uniform_bytes(&sha512::hash(input))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let EdwardsPoint { x, y, z, t } = self.0;
+        let sqrt_m1 = FieldElement::from_bytes(&SQRT_M1);
+
+        let u1 = (z + y) * (z - y);
+        let u2 = x * y;
+        let (_, invsqrt) = sqrt_ratio_m1(&FieldElement::one(), &(u1 * u2.square()));
+        let den1 = invsqrt * u1;
+        let den2 = invsqrt * u2;
+        let z_inv = den1 * den2 * t;
+
+        let enchanted_denominator = den1 * FieldElement::from_bytes(&INVSQRT_A_MINUS_D);
+        let rotate = (t * z_inv).is_negative();
+
+        let x_rotated = select(&x, &(y * sqrt_m1), rotate);
+        let y_rotated = select(&y, &(x * sqrt_m1), rotate);
+        let den_inv = select(&den2, &enchanted_denominator, rotate);
+
+        let y = select(
+            &y_rotated,
+            &y_rotated.neg(),
+            (x_rotated * z_inv).is_negative(),
+        );
+
+        abs(&(den_inv * (z - y))).to_bytes()
+    }
+
+    pub fn add(&self, other: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(self.0.add(&other.0))
+    }
+
+    pub fn sub(&self, other: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(self.0.sub(&other.0))
+    }
+
+    pub fn mul(&self, scalar: &Scalar) -> RistrettoPoint {
+        RistrettoPoint(self.0.mul(scalar))
+    }
+
+    pub fn mul_base(scalar: &Scalar) -> RistrettoPoint {
+        RistrettoPoint(EdwardsPoint::mul_base(scalar))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == RistrettoPoint::identity()
+    }
+}
+
+// the representatives of one element differ by a point of order 4 at most
+impl PartialEq for RistrettoPoint {
+    fn eq(&self, other: &RistrettoPoint) -> bool {
+        let (a, b) = (&self.0, &other.0);
+
+        equal(&(a.x * b.y), &(a.y * b.x)) | equal(&(a.y * b.y), &(a.x * b.x))
+    }
+}
+
+impl Eq for RistrettoPoint {}
+
+impl std::fmt::Debug for RistrettoPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RistrettoPoint({})",
+            crate::encoding::hex::encode(&self.to_bytes())
+        )
+    }
+}
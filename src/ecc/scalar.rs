@@ -0,0 +1,275 @@
+// Integers modulo l, the order of the Curve25519 prime-order subgroup, for protocols built on
+// Ed25519 points such as Schnorr variants, ring signatures and FROST. Arithmetic runs in constant
+// time, and values are redacted from `Debug` since scalars are usually secret.
+
+use crate::utils::{random_bytes, redacted_debug};
+use core::ops::{Add, Mul, Neg, Sub};
+use zeroize::Zeroize;
+
+const L: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0x0000000000000000,
+    0x1000000000000000,
+];
+
+#[derive(Clone, Copy, Zeroize)]
+pub struct Scalar([u64; 4]);
+
+redacted_debug!(Scalar, "<redacted>");
+
+fn sub_with_borrow(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut output = [0u64; 4];
+    let mut borrow = 0u64;
+
+    for i in 0..4 {
+        let (x, b1) = a[i].overflowing_sub(b[i]);
+        let (x, b2) = x.overflowing_sub(borrow);
+        output[i] = x;
+        borrow = (b1 | b2) as u64;
+    }
+
+    (output, borrow)
+}
+
+fn select(a: &[u64; 4], b: &[u64; 4], choice: u64) -> [u64; 4] {
+    let mask = 0u64.wrapping_sub(choice);
+    let mut output = [0u64; 4];
+
+    for i in 0..4 {
+        output[i] = a[i] ^ (mask & (a[i] ^ b[i]));
+    }
+
+    output
+}
+
+// subtracts L once if x >= L; x must be below 2L
+fn reduce_once(x: &[u64; 4]) -> [u64; 4] {
+    let (reduced, borrow) = sub_with_borrow(x, &L);
+    select(&reduced, x, borrow)
+}
+
+// reduces a little-endian integer of arbitrary length bit by bit
+fn reduce_bytes(bytes: &[u8]) -> [u64; 4] {
+    let mut r = [0u64; 4];
+
+    for byte in bytes.iter().rev() {
+        for bit in (0..8).rev() {
+            let carry = r[3] >> 63;
+            r[3] = (r[3] << 1) | (r[2] >> 63);
+            r[2] = (r[2] << 1) | (r[1] >> 63);
+            r[1] = (r[1] << 1) | (r[0] >> 63);
+            r[0] = (r[0] << 1) | ((byte >> bit) & 1) as u64;
+
+            debug_assert_eq!(carry, 0);
+            r = reduce_once(&r);
+        }
+    }
+
+    r
+}
+
+impl Scalar {
+    pub const ZERO: Scalar = Scalar([0, 0, 0, 0]);
+    pub const ONE: Scalar = Scalar([1, 0, 0, 0]);
+
+    pub fn from_bytes_mod_order(bytes: &[u8; 32]) -> Scalar {
+        Scalar(reduce_bytes(bytes))
+    }
+
+    /// Reduces 64 bytes, such as a SHA-512 digest, with negligible bias.
+    pub fn from_bytes_mod_order_wide(bytes: &[u8; 64]) -> Scalar {
+        Scalar(reduce_bytes(bytes))
+    }
+
+    /// Returns `None` unless the bytes encode an integer below l.
+    pub fn from_canonical_bytes(bytes: &[u8; 32]) -> Option<Scalar> {
+        let mut limbs = [0u64; 4];
+
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        let (_, borrow) = sub_with_borrow(&limbs, &L);
+
+        if borrow == 1 {
+            Some(Scalar(limbs))
+        } else {
+            None
+        }
+    }
+
+    pub fn random() -> Scalar {
+        let mut bytes: [u8; 64] = random_bytes();
+        let output = Scalar::from_bytes_mod_order_wide(&bytes);
+        bytes.zeroize();
+
+        output
+    }
+
+    pub fn from_u64(x: u64) -> Scalar {
+        Scalar([x, 0, 0, 0])
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        let mut output = [0u8; 32];
+
+        for (chunk, limb) in output.chunks_exact_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+
+        output
+    }
+
+    pub fn is_zero(&self) -> bool {
+        (self.0[0] | self.0[1] | self.0[2] | self.0[3]) == 0
+    }
+
+    pub fn square(&self) -> Scalar {
+        *self * *self
+    }
+
+    /// The multiplicative inverse, or zero for zero.
+    pub fn invert(&self) -> Scalar {
+        // x^(L - 2)
+        let (exponent, _) = sub_with_borrow(&L, &[2, 0, 0, 0]);
+        let mut output = Scalar::ONE;
+
+        for i in (0..256).rev() {
+            output = output.square();
+
+            if (exponent[i / 64] >> (i % 64)) & 1 == 1 {
+                output = output * *self;
+            }
+        }
+
+        output
+    }
+
+    pub(crate) fn bit(&self, i: usize) -> u8 {
+        ((self.0[i / 64] >> (i % 64)) & 1) as u8
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Scalar) -> bool {
+        let mut diff = 0;
+
+        for i in 0..4 {
+            diff |= self.0[i] ^ other.0[i];
+        }
+
+        diff == 0
+    }
+}
+
+impl Eq for Scalar {}
+
+impl Add for Scalar {
+    type Output = Scalar;
+
+    fn add(self, rhs: Scalar) -> Scalar {
+        let mut output = [0u64; 4];
+        let mut carry = 0u64;
+
+        for ((out, a), b) in output.iter_mut().zip(self.0).zip(rhs.0) {
+            let sum = a as u128 + b as u128 + carry as u128;
+            *out = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+
+        Scalar(reduce_once(&output))
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Scalar;
+
+    fn sub(self, rhs: Scalar) -> Scalar {
+        let (difference, borrow) = sub_with_borrow(&self.0, &rhs.0);
+
+        let mut corrected = [0u64; 4];
+        let mut carry = 0u64;
+
+        for i in 0..4 {
+            let sum = difference[i] as u128 + L[i] as u128 + carry as u128;
+            corrected[i] = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+
+        Scalar(select(&difference, &corrected, borrow))
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Scalar;
+
+    fn mul(self, rhs: Scalar) -> Scalar {
+        let mut wide = [0u64; 8];
+
+        for i in 0..4 {
+            let mut carry = 0u128;
+
+            for j in 0..4 {
+                let product = self.0[i] as u128 * rhs.0[j] as u128 + wide[i + j] as u128 + carry;
+                wide[i + j] = product as u64;
+                carry = product >> 64;
+            }
+
+            wide[i + 4] = carry as u64;
+        }
+
+        let mut bytes = [0u8; 64];
+
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(wide.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+
+        let output = Scalar::from_bytes_mod_order_wide(&bytes);
+
+        wide.zeroize();
+        bytes.zeroize();
+
+        output
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar::ZERO - self
+    }
+}
+
+impl Add for &Scalar {
+    type Output = Scalar;
+
+    fn add(self, rhs: &Scalar) -> Scalar {
+        *self + *rhs
+    }
+}
+
+impl Sub for &Scalar {
+    type Output = Scalar;
+
+    fn sub(self, rhs: &Scalar) -> Scalar {
+        *self - *rhs
+    }
+}
+
+impl Mul for &Scalar {
+    type Output = Scalar;
+
+    fn mul(self, rhs: &Scalar) -> Scalar {
+        *self * *rhs
+    }
+}
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        -*self
+    }
+}
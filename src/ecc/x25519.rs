@@ -1,7 +1,12 @@
 use crate::ecc::field::FieldElement;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::ecc::InvalidKey;
+use crate::audit::{self, KeyId, Operation};
+use crate::encoding::{base64, hex, pkcs8};
+use crate::errors::Error;
+use crate::hashes::blake2b::Blake2b;
+use crate::secret::SecretString;
+use crate::utils::redacted_debug;
 
 const BASE: [u8; 32] = [
     9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -70,13 +75,15 @@ pub type PublicKey = [u8; 32];
 
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct PrivateKey {
-    key: [u8; 32],
+    pub(crate) key: [u8; 32],
 }
 
+redacted_debug!(PrivateKey, "<32 bytes, redacted>");
+
 impl PrivateKey {
-    pub fn new(key: &[u8]) -> Result<PrivateKey, InvalidKey> {
+    pub fn new(key: &[u8]) -> Result<PrivateKey, Error> {
         if key.len() != 32 {
-            return Err(InvalidKey);
+            return Err(Error::InvalidKey);
         }
 
         let mut key: [u8; 32] = key.try_into().unwrap();
@@ -84,11 +91,122 @@ impl PrivateKey {
         Ok(PrivateKey { key })
     }
 
+    pub fn from_seed(seed: &[u8]) -> PrivateKey {
+        let mut hasher = Blake2b::new_keyed(b"raycrypt x25519 seed", 32);
+        hasher.update(seed);
+
+        let mut key = hasher.digest();
+        let private = PrivateKey::new(&key).unwrap();
+        key.zeroize();
+
+        private
+    }
+
+    pub fn from_hex(key: &str) -> Result<PrivateKey, Error> {
+        let mut bytes = hex::decode(key)?;
+        let key = PrivateKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn from_base64(key: &str) -> Result<PrivateKey, Error> {
+        let mut bytes = base64::decode(key)?;
+        let key = PrivateKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn from_pem(key: &str) -> Result<PrivateKey, Error> {
+        let mut bytes = pkcs8::decode_pem(key, pkcs8::X25519)?;
+        let key = PrivateKey::new(&bytes);
+        bytes.zeroize();
+
+        key
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.key
+    }
+
+    pub fn to_hex(&self) -> SecretString {
+        SecretString::encode_hex(&self.key)
+    }
+
+    pub fn to_base64(&self) -> SecretString {
+        SecretString::encode_base64(&self.key)
+    }
+
+    pub fn to_pem(&self) -> SecretString {
+        SecretString::new(pkcs8::encode_pem(&self.key, pkcs8::X25519))
+    }
+
     pub fn public_key(&self) -> PublicKey {
         scalarmult_base(&self.key)
     }
 
     pub fn exchange(&self, public: PublicKey) -> [u8; 32] {
-        scalarmult(&self.key, &public)
+        let shared = scalarmult(&self.key, &public);
+        audit::record(
+            Operation::KeyExchange,
+            || Some(KeyId::new(&self.public_key())),
+            true,
+        );
+
+        shared
+    }
+}
+
+pub fn keygen(seed: Option<&[u8]>) -> PrivateKey {
+    match seed {
+        Some(seed) => PrivateKey::from_seed(seed),
+        None => {
+            let mut key = [0u8; 32];
+            crate::rng::fill(&mut key);
+
+            let private = PrivateKey::new(&key).unwrap();
+            key.zeroize();
+
+            private
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Keypair {
+    private: PrivateKey,
+    public: PublicKey,
+}
+
+impl Keypair {
+    pub fn new(private: PrivateKey) -> Keypair {
+        let public = private.public_key();
+
+        Keypair { private, public }
+    }
+
+    pub fn generate() -> Keypair {
+        Keypair::new(keygen(None))
+    }
+
+    pub fn from_pem(key: &str) -> Result<Keypair, Error> {
+        PrivateKey::from_pem(key).map(Keypair::new)
+    }
+
+    pub fn public(&self) -> PublicKey {
+        self.public
+    }
+
+    pub fn private(&self) -> &PrivateKey {
+        &self.private
+    }
+
+    pub fn exchange(&self, public: PublicKey) -> [u8; 32] {
+        self.private.exchange(public)
+    }
+
+    pub fn to_pem(&self) -> SecretString {
+        self.private.to_pem()
     }
 }
@@ -0,0 +1,23 @@
+pub(crate) mod base64;
+pub(crate) mod der;
+pub(crate) mod hex;
+pub(crate) mod pem;
+pub(crate) mod pkcs8;
+pub(crate) mod reader;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidEncoding;
+
+impl std::fmt::Display for InvalidEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The input is not correctly encoded.")
+    }
+}
+
+impl std::error::Error for InvalidEncoding {}
+
+// -1 when lo <= c <= hi, else 0, without branching on `c`; the hex and base64 codecs use it to
+// classify symbols in constant time
+pub(crate) fn in_range(c: i16, lo: i16, hi: i16) -> i16 {
+    ((lo - 1 - c) & (c - hi - 1)) >> 15
+}
@@ -0,0 +1,141 @@
+// Constant time in the data, like the hex codec: symbols are computed rather than looked up.
+// Only the length and the padding, which are public, change the control flow.
+
+use crate::encoding::{in_range, InvalidEncoding};
+use zeroize::Zeroize;
+
+fn symbol(sextet: u32) -> char {
+    let n = sextet as i16;
+    let mut c = n + 0x41;
+
+    c += ((25 - n) >> 8) & 6;
+    c -= ((51 - n) >> 8) & 75;
+    c -= ((61 - n) >> 8) & 15;
+    c += ((62 - n) >> 8) & 3;
+
+    c as u8 as char
+}
+
+// the value, and -1 when `c` is in the alphabet or 0 when it is not
+fn value(c: u8) -> (u32, i16) {
+    let c = c as i16;
+    let upper = in_range(c, 0x41, 0x5a);
+    let lower = in_range(c, 0x61, 0x7a);
+    let digit = in_range(c, 0x30, 0x39);
+    let plus = in_range(c, 0x2b, 0x2b);
+    let slash = in_range(c, 0x2f, 0x2f);
+
+    let value = (upper & (c - 0x41))
+        | (lower & (c - 0x47))
+        | (digit & (c + 4))
+        | (plus & 62)
+        | (slash & 63);
+
+    (value as u32, upper | lower | digit | plus | slash)
+}
+
+pub fn encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let mut bytes = [0u8; 3];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+
+        let n = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(symbol(n >> (18 - 6 * i) & 0x3f));
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+pub fn decode(data: &str) -> Result<Vec<u8>, InvalidEncoding> {
+    let data = data.as_bytes();
+
+    if !data.len().is_multiple_of(4) {
+        return Err(InvalidEncoding);
+    }
+
+    let mut output = Vec::with_capacity(data.len() / 4 * 3);
+    let mut valid = -1i16;
+
+    for (index, chunk) in data.chunks(4).enumerate() {
+        let last = index == data.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+
+        if padding > 2 || (padding > 0 && !last) {
+            output.zeroize();
+            return Err(InvalidEncoding);
+        }
+
+        let mut n = 0u32;
+
+        for &c in &chunk[..4 - padding] {
+            let (symbol, symbol_valid) = value(c);
+            n = n << 6 | symbol;
+            valid &= symbol_valid;
+        }
+
+        n <<= 6 * padding;
+
+        let mut bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+
+        // the unused bits of the final symbol must be zero
+        let unused = bytes[3 - padding..].iter().fold(0, |acc, &b| acc | b);
+        valid &= ((unused as i16) - 1) >> 15;
+
+        output.extend_from_slice(&bytes[..3 - padding]);
+        bytes.zeroize();
+    }
+
+    if valid == 0 {
+        output.zeroize();
+        return Err(InvalidEncoding);
+    }
+
+    Ok(output)
+}
+
+// the PHC string format leaves the padding off
+pub fn encode_unpadded(data: &[u8]) -> String {
+    let mut output = encode(data);
+    output.truncate(output.trim_end_matches('=').len());
+
+    output
+}
+
+pub fn decode_unpadded(data: &str) -> Result<Vec<u8>, InvalidEncoding> {
+    if data.contains('=') || data.len() % 4 == 1 {
+        return Err(InvalidEncoding);
+    }
+
+    let padding = (4 - data.len() % 4) % 4;
+    let mut padded = String::with_capacity(data.len() + padding);
+    padded.push_str(data);
+    padded.push_str(&"=".repeat(padding));
+    let decoded = decode(&padded);
+    padded.zeroize();
+
+    decoded
+}
+
+// the URL-safe alphabet without padding, as JOSE uses
+#[cfg(feature = "json")]
+pub fn encode_url(data: &[u8]) -> String {
+    encode_unpadded(data).replace('+', "-").replace('/', "_")
+}
+
+#[cfg(feature = "json")]
+pub fn decode_url(data: &str) -> Result<Vec<u8>, InvalidEncoding> {
+    if data.contains(['+', '/']) {
+        return Err(InvalidEncoding);
+    }
+
+    decode_unpadded(&data.replace('-', "+").replace('_', "/"))
+}
@@ -0,0 +1,112 @@
+use crate::encoding::InvalidEncoding;
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OID: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+
+pub const fn context(n: u8) -> u8 {
+    0xa0 | n
+}
+
+pub struct DerReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> DerReader<'a> {
+        DerReader { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    pub fn finish(&self) -> Result<(), InvalidEncoding> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidEncoding)
+        }
+    }
+
+    // returns the tag, the complete encoding, and the contents
+    pub fn read_element(&mut self) -> Result<(u8, &'a [u8], &'a [u8]), InvalidEncoding> {
+        let data = self.data;
+
+        if data.len() < 2 || data[0] & 0x1f == 0x1f {
+            return Err(InvalidEncoding);
+        }
+
+        let tag = data[0];
+        let (length, header) = match data[1] {
+            n if n < 0x80 => (n as usize, 2),
+            n @ 0x81..=0x84 => {
+                let count = (n & 0x7f) as usize;
+
+                if data.len() < 2 + count || data[2] == 0 {
+                    return Err(InvalidEncoding);
+                }
+
+                let length = data[2..2 + count]
+                    .iter()
+                    .fold(0usize, |acc, &b| acc << 8 | b as usize);
+
+                if length < 0x80 {
+                    return Err(InvalidEncoding);
+                }
+
+                (length, 2 + count)
+            }
+            _ => return Err(InvalidEncoding),
+        };
+
+        let end = header.checked_add(length).ok_or(InvalidEncoding)?;
+
+        if data.len() < end {
+            return Err(InvalidEncoding);
+        }
+
+        self.data = &data[end..];
+
+        Ok((tag, &data[..end], &data[header..end]))
+    }
+
+    pub fn read_full(&mut self, tag: u8) -> Result<&'a [u8], InvalidEncoding> {
+        match self.read_element()? {
+            (t, full, _) if t == tag => Ok(full),
+            _ => Err(InvalidEncoding),
+        }
+    }
+
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], InvalidEncoding> {
+        match self.read_element()? {
+            (t, _, contents) if t == tag => Ok(contents),
+            _ => Err(InvalidEncoding),
+        }
+    }
+
+    pub fn read_optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>, InvalidEncoding> {
+        if self.peek() == Some(tag) {
+            self.read(tag).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn read_sequence(&mut self) -> Result<DerReader<'a>, InvalidEncoding> {
+        self.read(SEQUENCE).map(DerReader::new)
+    }
+
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8], InvalidEncoding> {
+        match self.read(BIT_STRING)? {
+            [0, bits @ ..] => Ok(bits),
+            _ => Err(InvalidEncoding),
+        }
+    }
+}
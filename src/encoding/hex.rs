@@ -0,0 +1,59 @@
+// Constant time in the data: no table lookups or branches on the bytes, as these encode keys.
+
+use crate::encoding::{in_range, InvalidEncoding};
+use zeroize::Zeroize;
+
+fn symbol(nibble: u8) -> char {
+    let n = nibble as i16;
+
+    (n + 0x30 + (((9 - n) >> 8) & 0x27)) as u8 as char
+}
+
+pub fn encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len() * 2);
+
+    for byte in data {
+        output.push(symbol(byte >> 4));
+        output.push(symbol(byte & 0x0f));
+    }
+
+    output
+}
+
+// the value, and -1 when `c` is a hex digit or 0 when it is not
+fn value(c: u8) -> (u8, i16) {
+    let c = c as i16;
+    let digit = in_range(c, 0x30, 0x39);
+    let lower = in_range(c, 0x61, 0x66);
+    let upper = in_range(c, 0x41, 0x46);
+
+    let value = (digit & (c - 0x30)) | (lower & (c - 0x57)) | (upper & (c - 0x37));
+
+    (value as u8, digit | lower | upper)
+}
+
+pub fn decode(data: &str) -> Result<Vec<u8>, InvalidEncoding> {
+    let data = data.as_bytes();
+
+    if !data.len().is_multiple_of(2) {
+        return Err(InvalidEncoding);
+    }
+
+    let mut output = Vec::with_capacity(data.len() / 2);
+    let mut valid = -1i16;
+
+    for pair in data.chunks_exact(2) {
+        let (high, high_valid) = value(pair[0]);
+        let (low, low_valid) = value(pair[1]);
+
+        valid &= high_valid & low_valid;
+        output.push(high << 4 | low);
+    }
+
+    if valid == 0 {
+        output.zeroize();
+        return Err(InvalidEncoding);
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,43 @@
+use crate::encoding::{base64, InvalidEncoding};
+use zeroize::Zeroize;
+
+// buffers are sized up front and wiped after use, as PEM bodies are usually private keys
+pub fn decode(label: &str, data: &str) -> Result<Vec<u8>, InvalidEncoding> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+
+    let start = data.find(&begin).ok_or(InvalidEncoding)? + begin.len();
+    let stop = data[start..].find(&end).ok_or(InvalidEncoding)? + start;
+
+    let mut body = String::with_capacity(stop - start);
+    body.extend(
+        data[start..stop]
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace()),
+    );
+
+    let decoded = base64::decode(&body);
+    body.zeroize();
+
+    decoded
+}
+
+pub fn encode(label: &str, data: &[u8]) -> String {
+    let mut body = base64::encode(data);
+    let header = format!("-----BEGIN {}-----\n", label);
+    let footer = format!("-----END {}-----\n", label);
+
+    let mut output =
+        String::with_capacity(header.len() + body.len() + body.len().div_ceil(64) + footer.len());
+    output.push_str(&header);
+
+    for line in body.as_bytes().chunks(64) {
+        output.push_str(core::str::from_utf8(line).unwrap());
+        output.push('\n');
+    }
+
+    output.push_str(&footer);
+    body.zeroize();
+
+    output
+}
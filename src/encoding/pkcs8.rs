@@ -0,0 +1,68 @@
+use crate::encoding::der::{self, DerReader};
+use crate::encoding::{pem, InvalidEncoding};
+use zeroize::Zeroize;
+
+pub const ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+pub const X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+
+// reads a PKCS#8 private key for one of the curves with 32-byte private keys
+pub fn decode(der: &[u8], algorithm: &[u8]) -> Result<[u8; 32], InvalidEncoding> {
+    let mut outer = DerReader::new(der);
+    let mut info = outer.read_sequence()?;
+    outer.finish()?;
+
+    match info.read(der::INTEGER)? {
+        [0] | [1] => (),
+        _ => return Err(InvalidEncoding),
+    }
+
+    let mut identifier = info.read_sequence()?;
+    let oid = identifier.read(der::OID)?;
+    identifier.finish()?;
+
+    if oid != algorithm {
+        return Err(InvalidEncoding);
+    }
+
+    let mut private = DerReader::new(info.read(der::OCTET_STRING)?);
+    let key = private.read(der::OCTET_STRING)?;
+    private.finish()?;
+
+    // attributes and the (primitive, implicitly tagged) public key are allowed but unused
+    info.read_optional(der::context(0))?;
+    info.read_optional(0x81)?;
+    info.finish()?;
+
+    key.try_into().map_err(|_| InvalidEncoding)
+}
+
+pub fn decode_pem(data: &str, algorithm: &[u8]) -> Result<[u8; 32], InvalidEncoding> {
+    let mut der = pem::decode("PRIVATE KEY", data)?;
+    let key = decode(&der, algorithm);
+    der.zeroize();
+
+    key
+}
+
+pub fn encode(key: &[u8; 32], algorithm: &[u8]) -> Vec<u8> {
+    let mut info = vec![der::INTEGER, 1, 0];
+    info.extend_from_slice(&[der::SEQUENCE, algorithm.len() as u8 + 2]);
+    info.extend_from_slice(&[der::OID, algorithm.len() as u8]);
+    info.extend_from_slice(algorithm);
+    info.extend_from_slice(&[der::OCTET_STRING, 34, der::OCTET_STRING, 32]);
+    info.extend_from_slice(key);
+
+    let mut der = vec![der::SEQUENCE, info.len() as u8];
+    der.extend_from_slice(&info);
+    info.zeroize();
+
+    der
+}
+
+pub fn encode_pem(key: &[u8; 32], algorithm: &[u8]) -> String {
+    let mut der = encode(key, algorithm);
+    let pem = pem::encode("PRIVATE KEY", &der);
+    der.zeroize();
+
+    pem
+}
@@ -0,0 +1,69 @@
+// A bounds-checked cursor over untrusted bytes, shared by the binary container parsers. Every
+// read either returns the bytes asked for or `InvalidEncoding`, so parsers never index past the
+// end of their input.
+
+use crate::encoding::InvalidEncoding;
+
+#[derive(Clone)]
+pub struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader(data)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn bytes(&mut self, length: usize) -> Result<&'a [u8], InvalidEncoding> {
+        if self.0.len() < length {
+            return Err(InvalidEncoding);
+        }
+
+        let (bytes, rest) = self.0.split_at(length);
+        self.0 = rest;
+
+        Ok(bytes)
+    }
+
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N], InvalidEncoding> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    pub fn byte(&mut self) -> Result<u8, InvalidEncoding> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, InvalidEncoding> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, InvalidEncoding> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    pub fn u64(&mut self) -> Result<u64, InvalidEncoding> {
+        self.array().map(u64::from_be_bytes)
+    }
+
+    pub fn rest(&mut self) -> &'a [u8] {
+        let rest = self.0;
+        self.0 = &[];
+
+        rest
+    }
+
+    /// Fails if there are bytes left over.
+    pub fn finish(&self) -> Result<(), InvalidEncoding> {
+        if !self.0.is_empty() {
+            return Err(InvalidEncoding);
+        }
+
+        Ok(())
+    }
+}
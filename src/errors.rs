@@ -1,4 +1,32 @@
-use std::error::Error;
+use crate::aeads::archive::ArchiveError;
+use crate::aeads::backup::BackupError;
+use crate::aeads::envelope::EnvelopeError;
+use crate::aeads::ndarray::NdArrayError;
+use crate::aeads::object::ObjectError;
+use crate::aeads::rotating::RotationError;
+use crate::aeads::stream::StreamError;
+use crate::aeads::SealError;
+use crate::cbor::CborError;
+use crate::chunking::ChunkError;
+use crate::ciphers::chacha::ChaChaError;
+use crate::ecc::blind::BlindError;
+use crate::ecc::ed25519::InvalidContext;
+use crate::ecc::fingerprint::InvalidFingerprintLength;
+use crate::ecc::keyfile::KeyFileError;
+use crate::ecc::pedersen::InvalidOpening;
+use crate::ecc::points::PointError;
+use crate::ecc::rangeproof::RangeProofError;
+use crate::ecc::ring::RingError;
+use crate::encoding::InvalidEncoding;
+#[cfg(feature = "json")]
+use crate::json::JweError;
+use crate::kdfs::phc::PhcError;
+use crate::kdfs::InvalidLength;
+use crate::merkle::MerkleError;
+use crate::openpgp::OpenPgpError;
+use crate::openssl::OpensslError;
+use crate::signed_log::LogError;
+use crate::x509::CertificateError;
 use std::fmt;
 
 #[derive(Clone, Copy, PartialEq)]
@@ -24,4 +52,363 @@ impl fmt::Debug for InvalidMac {
     }
 }
 
-impl Error for InvalidMac {}
+impl std::error::Error for InvalidMac {}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct InvalidSignature;
+
+impl Eq for InvalidSignature {}
+
+impl fmt::Display for InvalidSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid signature detected.")
+    }
+}
+
+impl fmt::Debug for InvalidSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid signature detected.")
+    }
+}
+
+impl std::error::Error for InvalidSignature {}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Cancelled;
+
+impl Eq for Cancelled {}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The operation was cancelled.")
+    }
+}
+
+impl fmt::Debug for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The operation was cancelled.")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// One error type for the whole crate, for callers that only need to know what kind of failure
+/// happened. Every module error converts into it, so `?` works across modules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidKey,
+    InvalidNonce,
+    /// A MAC or AEAD tag did not verify: the data was tampered with or the key is wrong.
+    InvalidTag,
+    InvalidSignature,
+    /// The input is not correctly encoded or violates its format.
+    Format,
+    Io(std::io::ErrorKind),
+    Unsupported,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidKey => write!(f, "The key is invalid or not the one expected."),
+            Error::InvalidNonce => write!(f, "The nonce is invalid or was exhausted."),
+            Error::InvalidTag => write!(f, "{}", InvalidMac),
+            Error::InvalidSignature => write!(f, "{}", InvalidSignature),
+            Error::Format => write!(f, "The input is malformed."),
+            Error::Io(kind) => write!(f, "An I/O operation failed: {}.", kind),
+            Error::Unsupported => write!(f, "The algorithm or parameters are not supported."),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<InvalidMac> for Error {
+    fn from(_: InvalidMac) -> Self {
+        Error::InvalidTag
+    }
+}
+
+impl From<InvalidSignature> for Error {
+    fn from(_: InvalidSignature) -> Self {
+        Error::InvalidSignature
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.kind())
+    }
+}
+
+impl From<InvalidContext> for Error {
+    fn from(_: InvalidContext) -> Self {
+        Error::Unsupported
+    }
+}
+
+impl From<InvalidFingerprintLength> for Error {
+    fn from(_: InvalidFingerprintLength) -> Self {
+        Error::Unsupported
+    }
+}
+
+impl From<PointError> for Error {
+    fn from(_: PointError) -> Self {
+        Error::Format
+    }
+}
+
+// an opening is a proof about the committed values, and a wrong one fails like a signature
+impl From<InvalidOpening> for Error {
+    fn from(_: InvalidOpening) -> Self {
+        Error::InvalidSignature
+    }
+}
+
+impl From<BlindError> for Error {
+    fn from(error: BlindError) -> Self {
+        match error {
+            BlindError::InvalidCommitment | BlindError::Malformed => Error::Format,
+            BlindError::NoSession => Error::InvalidNonce,
+            BlindError::InvalidResponse => Error::InvalidSignature,
+        }
+    }
+}
+
+impl From<KeyFileError> for Error {
+    fn from(error: KeyFileError) -> Self {
+        match error {
+            KeyFileError::Io(kind) => Error::Io(kind),
+            KeyFileError::Malformed => Error::Format,
+            KeyFileError::WrongPassword => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<RangeProofError> for Error {
+    fn from(error: RangeProofError) -> Self {
+        match error {
+            RangeProofError::Malformed => Error::Format,
+            RangeProofError::Invalid => Error::InvalidSignature,
+        }
+    }
+}
+
+impl From<RingError> for Error {
+    fn from(error: RingError) -> Self {
+        match error {
+            RingError::KeyNotInRing => Error::InvalidKey,
+            RingError::Malformed => Error::Format,
+        }
+    }
+}
+
+impl From<InvalidEncoding> for Error {
+    fn from(_: InvalidEncoding) -> Self {
+        Error::Format
+    }
+}
+
+impl From<InvalidLength> for Error {
+    fn from(_: InvalidLength) -> Self {
+        Error::Unsupported
+    }
+}
+
+impl From<ChaChaError> for Error {
+    fn from(error: ChaChaError) -> Self {
+        match error {
+            ChaChaError::InvalidKeyLength => Error::InvalidKey,
+            ChaChaError::InvalidNonceLength => Error::InvalidNonce,
+        }
+    }
+}
+
+impl From<SealError> for Error {
+    fn from(error: SealError) -> Self {
+        match error {
+            SealError::Malformed => Error::Format,
+            SealError::UnknownAlgorithm => Error::Unsupported,
+            SealError::WrongKey => Error::InvalidKey,
+            SealError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<RotationError> for Error {
+    fn from(error: RotationError) -> Self {
+        match error {
+            RotationError::Malformed => Error::Format,
+            // the key of that epoch is no longer, or not yet, accepted
+            RotationError::EpochOutOfRange => Error::InvalidKey,
+            RotationError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<StreamError> for Error {
+    fn from(error: StreamError) -> Self {
+        match error {
+            StreamError::InvalidMac => Error::InvalidTag,
+            StreamError::Truncated => Error::Format,
+            StreamError::CounterOverflow => Error::InvalidNonce,
+            StreamError::InvalidChunkSize => Error::Unsupported,
+        }
+    }
+}
+
+impl From<NdArrayError> for Error {
+    fn from(error: NdArrayError) -> Self {
+        match error {
+            NdArrayError::Malformed | NdArrayError::ShapeMismatch | NdArrayError::WrongType => {
+                Error::Format
+            }
+            NdArrayError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<ChunkError> for Error {
+    fn from(error: ChunkError) -> Self {
+        match error {
+            ChunkError::InvalidParameters => Error::Unsupported,
+            ChunkError::Malformed => Error::Format,
+            ChunkError::MissingChunk => Error::Io(std::io::ErrorKind::NotFound),
+            ChunkError::CorruptChunk => Error::InvalidTag,
+            ChunkError::Io(kind) => Error::Io(kind),
+        }
+    }
+}
+
+impl From<ArchiveError> for Error {
+    fn from(error: ArchiveError) -> Self {
+        match error {
+            ArchiveError::Io(kind) => Error::Io(kind),
+            ArchiveError::NotFound => Error::Io(std::io::ErrorKind::NotFound),
+            ArchiveError::Malformed
+            | ArchiveError::DuplicateName
+            | ArchiveError::TooManyEntries => Error::Format,
+            ArchiveError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<BackupError> for Error {
+    fn from(error: BackupError) -> Self {
+        match error {
+            BackupError::Io(kind) => Error::Io(kind),
+            BackupError::NotFound => Error::Io(std::io::ErrorKind::NotFound),
+            BackupError::Malformed | BackupError::DuplicateName => Error::Format,
+            BackupError::WrongKey => Error::InvalidKey,
+            BackupError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
+
+impl<E: Into<Error>> From<EnvelopeError<E>> for Error {
+    fn from(error: EnvelopeError<E>) -> Self {
+        match error {
+            EnvelopeError::Malformed => Error::Format,
+            EnvelopeError::InvalidMac => Error::InvalidTag,
+            EnvelopeError::Provider(error) => error.into(),
+        }
+    }
+}
+
+impl<E: Into<Error>> From<ObjectError<E>> for Error {
+    fn from(error: ObjectError<E>) -> Self {
+        match error {
+            ObjectError::Malformed | ObjectError::WrongType => Error::Format,
+            ObjectError::InvalidMac => Error::InvalidTag,
+            ObjectError::UntrustedCodec => Error::Unsupported,
+            ObjectError::Codec(error) => error.into(),
+        }
+    }
+}
+
+impl From<CborError> for Error {
+    fn from(error: CborError) -> Self {
+        match error {
+            CborError::Malformed | CborError::NotDeterministic | CborError::DuplicateKey => {
+                Error::Format
+            }
+            CborError::Unsupported => Error::Unsupported,
+            CborError::InvalidSignature => Error::InvalidSignature,
+        }
+    }
+}
+
+impl From<LogError> for Error {
+    fn from(error: LogError) -> Self {
+        match error {
+            LogError::Malformed => Error::Format,
+            // either way, the log is not what its writer signed
+            LogError::InvalidCheckpoint | LogError::Tampered | LogError::Truncated => {
+                Error::InvalidSignature
+            }
+        }
+    }
+}
+
+impl From<MerkleError> for Error {
+    fn from(error: MerkleError) -> Self {
+        match error {
+            MerkleError::Malformed => Error::Format,
+            MerkleError::InvalidProof | MerkleError::InvalidSignature => Error::InvalidSignature,
+        }
+    }
+}
+
+impl From<OpensslError> for Error {
+    fn from(error: OpensslError) -> Self {
+        match error {
+            OpensslError::Malformed => Error::Format,
+            // a wrong password shows up as bad padding
+            OpensslError::BadDecrypt => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<OpenPgpError> for Error {
+    fn from(error: OpenPgpError) -> Self {
+        match error {
+            OpenPgpError::Malformed => Error::Format,
+            OpenPgpError::Unsupported => Error::Unsupported,
+            OpenPgpError::NoEncryptionKey | OpenPgpError::Revoked | OpenPgpError::NoMatchingKey => {
+                Error::InvalidKey
+            }
+            OpenPgpError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
+
+impl From<PhcError> for Error {
+    fn from(error: PhcError) -> Self {
+        match error {
+            PhcError::Malformed | PhcError::InvalidParameters(_) => Error::Format,
+            PhcError::UnsupportedAlgorithm | PhcError::UnsupportedVersion => Error::Unsupported,
+        }
+    }
+}
+
+impl From<CertificateError> for Error {
+    fn from(error: CertificateError) -> Self {
+        match error {
+            CertificateError::Malformed => Error::Format,
+            CertificateError::UnsupportedAlgorithm => Error::Unsupported,
+            CertificateError::InvalidSignature => Error::InvalidSignature,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<JweError> for Error {
+    fn from(error: JweError) -> Self {
+        match error {
+            JweError::Malformed => Error::Format,
+            JweError::Unsupported => Error::Unsupported,
+            JweError::InvalidMac => Error::InvalidTag,
+        }
+    }
+}
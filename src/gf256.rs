@@ -0,0 +1,28 @@
+// Arithmetic in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, the AES field, shared by the AES inverse
+// cipher and Shamir's secret sharing. Both work on secret bytes, so there are no table lookups
+// or secret-dependent branches.
+
+pub(crate) fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        a = (a << 1) ^ ((a >> 7).wrapping_neg() & 0x1b);
+        b >>= 1;
+    }
+
+    product
+}
+
+// a^254 = a^-1, with 0 mapping to 0
+pub(crate) fn inverse(a: u8) -> u8 {
+    let mut result = 1;
+    let mut power = a;
+
+    for _ in 0..7 {
+        power = mul(power, power);
+        result = mul(result, power);
+    }
+
+    result
+}
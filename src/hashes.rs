@@ -0,0 +1,79 @@
+pub mod blake2b;
+pub mod blake2s;
+pub mod blake3;
+pub mod poseidon;
+pub mod sha256;
+pub mod sha512;
+
+pub trait Hash: Clone + Default {
+    const BLOCK_SIZE: usize;
+    const OUTPUT_SIZE: usize;
+
+    fn update(&mut self, data: &[u8]);
+    fn finish(self) -> Vec<u8>;
+}
+
+impl Hash for sha256::Sha256 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.digest().to_vec()
+    }
+}
+
+impl Hash for sha512::Sha512 {
+    const BLOCK_SIZE: usize = 128;
+    const OUTPUT_SIZE: usize = 64;
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.digest().to_vec()
+    }
+}
+
+impl Hash for blake2b::Blake2b {
+    const BLOCK_SIZE: usize = 128;
+    const OUTPUT_SIZE: usize = 64;
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.digest()
+    }
+}
+
+impl Hash for blake2s::Blake2s {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.digest()
+    }
+}
+
+impl Hash for blake3::Blake3 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.digest().to_vec()
+    }
+}
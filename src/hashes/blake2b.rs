@@ -0,0 +1,164 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Blake2b {
+    state: [u64; 8],
+    buf: [u8; 128],
+    leftover: usize,
+    length: u128,
+    output_size: usize,
+}
+
+impl Blake2b {
+    fn compress(&mut self, block: &[u8], last: bool) {
+        let mut m = [0u64; 16];
+
+        for (word, chunk) in m.iter_mut().zip(block.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.state);
+        v[8..].copy_from_slice(&IV);
+
+        v[12] ^= self.length as u64;
+        v[13] ^= (self.length >> 64) as u64;
+
+        if last {
+            v[14] = !v[14];
+        }
+
+        for s in SIGMA {
+            g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+
+            g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+
+        for (i, word) in self.state.iter_mut().enumerate() {
+            *word ^= v[i] ^ v[i + 8];
+        }
+
+        m.zeroize();
+        v.zeroize();
+    }
+}
+
+impl Blake2b {
+    pub fn new(output_size: usize) -> Blake2b {
+        Blake2b::new_keyed(&[], output_size)
+    }
+
+    pub fn new_keyed(key: &[u8], output_size: usize) -> Blake2b {
+        assert!((1..=64).contains(&output_size) && key.len() <= 64);
+
+        let mut state = IV;
+        state[0] ^= 0x01010000 ^ (key.len() as u64) << 8 ^ output_size as u64;
+
+        let mut blake2b = Blake2b {
+            state,
+            buf: [0u8; 128],
+            leftover: 0,
+            length: 0,
+            output_size,
+        };
+
+        if !key.is_empty() {
+            blake2b.buf[..key.len()].copy_from_slice(key);
+            blake2b.leftover = 128;
+        }
+
+        blake2b
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.output_size
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.leftover == 128 {
+                self.length += 128;
+
+                let buf = self.buf;
+                self.compress(&buf, false);
+                self.leftover = 0;
+            }
+
+            let take = (128 - self.leftover).min(data.len());
+            self.buf[self.leftover..self.leftover + take].copy_from_slice(&data[..take]);
+            self.leftover += take;
+            data = &data[take..];
+        }
+    }
+
+    pub fn digest(mut self) -> Vec<u8> {
+        self.length += self.leftover as u128;
+        self.buf[self.leftover..].fill(0);
+
+        let buf = self.buf;
+        self.compress(&buf, true);
+
+        let mut output = [0u8; 64];
+
+        for (chunk, word) in output.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        output[..self.output_size].to_vec()
+    }
+}
+
+impl Default for Blake2b {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+pub fn hash(data: &[u8], output_size: usize) -> Vec<u8> {
+    let mut blake2b = Blake2b::new(output_size);
+    blake2b.update(data);
+    blake2b.digest()
+}
@@ -0,0 +1,484 @@
+use crate::cancel::CancelToken;
+use crate::parallel::install;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+// enough for 2^54 chunks, the most a 64-bit length allows
+const MAX_DEPTH: usize = 54;
+const READ_BUFFER: usize = 4 << 20;
+
+const CHUNK_START: u32 = 1;
+const CHUNK_END: u32 = 2;
+const PARENT: u32 = 4;
+const ROOT: u32 = 8;
+const KEYED_HASH: u32 = 16;
+const DERIVE_KEY_CONTEXT: u32 = 32;
+const DERIVE_KEY_MATERIAL: u32 = 64;
+
+fn g(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
+
+fn compress(
+    cv: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(cv);
+    v[8..12].copy_from_slice(&IV[..4]);
+    v[12..].copy_from_slice(&[counter as u32, (counter >> 32) as u32, block_len, flags]);
+
+    let mut m = *block;
+
+    for round in 0..7 {
+        g(&mut v, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut v, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut v, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut v, 3, 7, 11, 15, m[6], m[7]);
+
+        g(&mut v, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut v, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut v, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut v, 3, 4, 9, 14, m[14], m[15]);
+
+        if round < 6 {
+            m = PERMUTATION.map(|i| m[i]);
+        }
+    }
+
+    for i in 0..8 {
+        v[i] ^= v[i + 8];
+        v[i + 8] ^= cv[i];
+    }
+
+    m.zeroize();
+
+    v
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    core::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+// a node whose chaining value or root output has not been computed yet
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn parent(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Output {
+        let mut block = [0u32; 16];
+        block[..8].copy_from_slice(left);
+        block[8..].copy_from_slice(right);
+
+        Output {
+            cv: *key,
+            block,
+            counter: 0,
+            block_len: BLOCK_LEN as u32,
+            flags: flags | PARENT,
+        }
+    }
+
+    fn chaining_value(&self) -> [u32; 8] {
+        let mut v = compress(
+            &self.cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        );
+        let cv = words(&v.map(u32::to_le_bytes).as_flattened()[..32]);
+        v.zeroize();
+
+        cv
+    }
+
+    fn root_bytes(&self, output: &mut [u8]) {
+        for (counter, chunk) in output.chunks_mut(BLOCK_LEN).enumerate() {
+            let mut v = compress(
+                &self.cv,
+                &self.block,
+                counter as u64,
+                self.block_len,
+                self.flags | ROOT,
+            );
+
+            for (bytes, word) in chunk.chunks_mut(4).zip(v) {
+                bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+            }
+
+            v.zeroize();
+        }
+    }
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], counter: u64, flags: u32) -> ChunkState {
+        ChunkState {
+            cv: *key,
+            counter,
+            block: [0u8; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.block_len == BLOCK_LEN {
+                let mut block = words(&self.block);
+                let mut v = compress(
+                    &self.cv,
+                    &block,
+                    self.counter,
+                    BLOCK_LEN as u32,
+                    self.flags | self.start_flag(),
+                );
+
+                self.cv.copy_from_slice(&v[..8]);
+                self.blocks_compressed += 1;
+                self.block = [0u8; BLOCK_LEN];
+                self.block_len = 0;
+
+                block.zeroize();
+                v.zeroize();
+            }
+
+            let take = (BLOCK_LEN - self.block_len).min(data.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            cv: self.cv,
+            block: words(&self.block),
+            counter: self.counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Blake3 {
+    key: [u32; 8],
+    chunk: ChunkState,
+    stack: [[u32; 8]; MAX_DEPTH],
+    stack_len: usize,
+    flags: u32,
+}
+
+impl Blake3 {
+    fn with_key(key: [u32; 8], flags: u32) -> Blake3 {
+        Blake3 {
+            key,
+            chunk: ChunkState::new(&key, 0, flags),
+            stack: [[0u32; 8]; MAX_DEPTH],
+            stack_len: 0,
+            flags,
+        }
+    }
+
+    pub fn new() -> Blake3 {
+        Blake3::with_key(IV, 0)
+    }
+
+    pub fn new_keyed(key: &[u8; 32]) -> Blake3 {
+        Blake3::with_key(words(key), KEYED_HASH)
+    }
+
+    pub fn new_derive_key(context: &str) -> Blake3 {
+        let mut hasher = Blake3::with_key(IV, DERIVE_KEY_CONTEXT);
+        hasher.update(context.as_bytes());
+
+        let mut context_key = hasher.digest();
+        let derive = Blake3::with_key(words(&context_key), DERIVE_KEY_MATERIAL);
+        context_key.zeroize();
+
+        derive
+    }
+
+    // merges completed subtrees; the number of trailing zero bits in the chunk count is the
+    // number of subtrees the new chunk completes
+    fn push_chunk(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0 {
+            self.stack_len -= 1;
+            cv = Output::parent(&self.stack[self.stack_len], &cv, &self.key, self.flags)
+                .chaining_value();
+            total_chunks >>= 1;
+        }
+
+        self.stack[self.stack_len] = cv;
+        self.stack_len += 1;
+    }
+
+    fn finish_chunk(&mut self) {
+        let cv = self.chunk.output().chaining_value();
+        let total_chunks = self.chunk.counter + 1;
+
+        self.push_chunk(cv, total_chunks);
+        self.chunk = ChunkState::new(&self.key, total_chunks, self.flags);
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.chunk.len() == CHUNK_LEN {
+                self.finish_chunk();
+            }
+
+            let take = (CHUNK_LEN - self.chunk.len()).min(data.len());
+            self.chunk.update(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn update_parallel(&mut self, mut data: &[u8], parallel: bool) {
+        use rayon::prelude::*;
+
+        // bring the chunk state to a chunk boundary first
+        let take = ((CHUNK_LEN - self.chunk.len()) % CHUNK_LEN).min(data.len());
+        self.update(&data[..take]);
+        data = &data[take..];
+
+        if !parallel || data.len() <= CHUNK_LEN {
+            return self.update(data);
+        }
+
+        if self.chunk.len() == CHUNK_LEN {
+            self.finish_chunk();
+        }
+
+        // the last chunk stays in the chunk state, since it may turn out to be the root
+        let whole = (data.len() - 1) / CHUNK_LEN * CHUNK_LEN;
+        let first = self.chunk.counter;
+
+        let mut cvs: Vec<[u32; 8]> = data[..whole]
+            .par_chunks(CHUNK_LEN)
+            .with_min_len(16)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut state = ChunkState::new(&self.key, first + index as u64, self.flags);
+                state.update(chunk);
+                state.output().chaining_value()
+            })
+            .collect();
+
+        for (index, cv) in cvs.iter().enumerate() {
+            self.push_chunk(*cv, first + index as u64 + 1);
+        }
+
+        self.chunk = ChunkState::new(&self.key, first + cvs.len() as u64, self.flags);
+        self.update(&data[whole..]);
+
+        cvs.zeroize();
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn update_parallel(&mut self, data: &[u8], _parallel: bool) {
+        self.update(data);
+    }
+
+    /// Hashes whole chunks on `threads` threads of a rayon pool, or every core if `threads` is
+    /// zero. Without the `rayon` feature this is the same as `update`.
+    pub fn update_with_threads(&mut self, data: &[u8], threads: usize) {
+        install(threads, || self.update_parallel(data, threads != 1));
+    }
+
+    /// Reads `reader` to the end, hashing as in `update_with_threads`, and returns the number
+    /// of bytes read.
+    pub fn update_reader<R: Read + Send>(
+        &mut self,
+        reader: R,
+        threads: usize,
+    ) -> std::io::Result<u64> {
+        self.update_reader_with_progress(reader, threads, |_| {})
+    }
+
+    /// Like `update_reader`, calling `progress` with the bytes hashed so far after every read.
+    pub fn update_reader_with_progress<R: Read + Send, F: FnMut(u64) + Send>(
+        &mut self,
+        reader: R,
+        threads: usize,
+        mut progress: F,
+    ) -> std::io::Result<u64> {
+        self.read_all(reader, threads, &mut progress, None)
+    }
+
+    /// Like `update_reader`, failing with an `io::Error` wrapping `Cancelled` once `token` is
+    /// cancelled. The token is checked before every read.
+    pub fn update_reader_cancellable<R: Read + Send>(
+        &mut self,
+        reader: R,
+        threads: usize,
+        token: &CancelToken,
+    ) -> std::io::Result<u64> {
+        self.read_all(reader, threads, &mut |_| {}, Some(token))
+    }
+
+    fn read_all<R: Read + Send>(
+        &mut self,
+        mut reader: R,
+        threads: usize,
+        progress: &mut (dyn FnMut(u64) + Send),
+        cancel: Option<&CancelToken>,
+    ) -> std::io::Result<u64> {
+        install(threads, || {
+            let mut buffer = vec![0u8; READ_BUFFER];
+            let mut total = 0;
+
+            let result = loop {
+                if let Some(Err(cancelled)) = cancel.map(CancelToken::check) {
+                    break Err(std::io::Error::other(cancelled));
+                }
+
+                let read = match reader.read(&mut buffer) {
+                    Ok(0) => break Ok(total),
+                    Ok(read) => read,
+                    Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                    Err(error) => break Err(error),
+                };
+
+                self.update_parallel(&buffer[..read], threads != 1);
+                total += read as u64;
+                progress(total);
+            };
+
+            buffer.zeroize();
+
+            result
+        })
+    }
+
+    fn output(&self) -> Output {
+        let mut output = self.chunk.output();
+
+        for cv in self.stack[..self.stack_len].iter().rev() {
+            output = Output::parent(cv, &output.chaining_value(), &self.key, self.flags);
+        }
+
+        output
+    }
+
+    pub fn digest(self) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        self.output().root_bytes(&mut output);
+
+        output
+    }
+
+    /// Extendable output of any length; the first 32 bytes equal `digest`.
+    pub fn digest_xof(self, length: usize) -> Vec<u8> {
+        let mut output = vec![0u8; length];
+        self.output().root_bytes(&mut output);
+
+        output
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake3::new();
+    hasher.update(data);
+    hasher.digest()
+}
+
+pub fn keyed_hash(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake3::new_keyed(key);
+    hasher.update(data);
+    hasher.digest()
+}
+
+pub fn derive_key(context: &str, material: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake3::new_derive_key(context);
+    hasher.update(material);
+    hasher.digest()
+}
+
+pub fn hash_file<P: AsRef<Path>>(path: P, threads: usize) -> std::io::Result<[u8; 32]> {
+    hash_file_with_progress(path, threads, |_, _| {})
+}
+
+/// Like `hash_file`, calling `progress(hashed, file_length)` after every read.
+pub fn hash_file_with_progress<P: AsRef<Path>, F: FnMut(u64, u64) + Send>(
+    path: P,
+    threads: usize,
+    mut progress: F,
+) -> std::io::Result<[u8; 32]> {
+    let file = File::open(path)?;
+    let length = file.metadata()?.len();
+
+    let mut hasher = Blake3::new();
+    hasher.update_reader_with_progress(file, threads, |hashed| progress(hashed, length))?;
+
+    Ok(hasher.digest())
+}
+
+/// Like `hash_file`, failing with an `io::Error` wrapping `Cancelled` once `token` is cancelled.
+pub fn hash_file_cancellable<P: AsRef<Path>>(
+    path: P,
+    threads: usize,
+    token: &CancelToken,
+) -> std::io::Result<[u8; 32]> {
+    let mut hasher = Blake3::new();
+    hasher.update_reader_cancellable(File::open(path)?, threads, token)?;
+
+    Ok(hasher.digest())
+}
@@ -0,0 +1,105 @@
+// Poseidon (Grassi et al., 2019) over the ristretto255 scalar field, for hashing values that a
+// zero-knowledge circuit over the same field has to recompute cheaply. The instance has width 3
+// (rate 2, capacity 1), the S-box x^5, and 8 full and 57 partial rounds, the paper's numbers for
+// 128-bit security over a 255-bit field. There is no standard instance over this field, so the
+// round constants are hashed from a label and the MDS matrix is the Cauchy matrix
+// 1 / (i + j + 3); circuits have to use the same derivation.
+
+use crate::ecc::scalar::Scalar;
+use crate::hashes::sha512::Sha512;
+use std::sync::OnceLock;
+
+pub const WIDTH: usize = 3;
+pub const RATE: usize = 2;
+
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+const ROUNDS: usize = FULL_ROUNDS + PARTIAL_ROUNDS;
+
+struct Constants {
+    round_constants: [[Scalar; WIDTH]; ROUNDS],
+    mds: [[Scalar; WIDTH]; WIDTH],
+}
+
+fn constants() -> &'static Constants {
+    static CONSTANTS: OnceLock<Constants> = OnceLock::new();
+
+    CONSTANTS.get_or_init(|| {
+        let mut round_constants = [[Scalar::ZERO; WIDTH]; ROUNDS];
+
+        for (i, constant) in round_constants.iter_mut().flatten().enumerate() {
+            let mut hasher = Sha512::new();
+            hasher.update(b"raycrypt poseidon round constant");
+            hasher.update(&(i as u32).to_le_bytes());
+
+            *constant = Scalar::from_bytes_mod_order_wide(&hasher.digest());
+        }
+
+        let mut mds = [[Scalar::ZERO; WIDTH]; WIDTH];
+
+        for (i, row) in mds.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = Scalar::from_u64((i + j + WIDTH) as u64).invert();
+            }
+        }
+
+        Constants {
+            round_constants,
+            mds,
+        }
+    })
+}
+
+fn sbox(x: &Scalar) -> Scalar {
+    x.square().square() * *x
+}
+
+pub fn permute(state: &mut [Scalar; WIDTH]) {
+    let constants = constants();
+    let half_full = FULL_ROUNDS / 2;
+
+    for (round, round_constants) in constants.round_constants.iter().enumerate() {
+        for (x, c) in state.iter_mut().zip(round_constants) {
+            *x = *x + *c;
+        }
+
+        if round < half_full || round >= half_full + PARTIAL_ROUNDS {
+            for x in state.iter_mut() {
+                *x = sbox(x);
+            }
+        } else {
+            state[0] = sbox(&state[0]);
+        }
+
+        let mut mixed = [Scalar::ZERO; WIDTH];
+        for (output, row) in mixed.iter_mut().zip(&constants.mds) {
+            *output = row
+                .iter()
+                .zip(state.iter())
+                .fold(Scalar::ZERO, |sum, (m, x)| sum + *m * *x);
+        }
+
+        *state = mixed;
+    }
+}
+
+/// Hashes a sequence of field elements. The capacity starts out as the number of inputs, so
+/// sequences of different lengths never collide through padding.
+pub fn hash(inputs: &[Scalar]) -> Scalar {
+    let mut state = [Scalar::ZERO; WIDTH];
+    state[0] = Scalar::from_u64(inputs.len() as u64);
+
+    if inputs.is_empty() {
+        permute(&mut state);
+    }
+
+    for chunk in inputs.chunks(RATE) {
+        for (x, input) in state[1..].iter_mut().zip(chunk) {
+            *x = *x + *input;
+        }
+
+        permute(&mut state);
+    }
+
+    state[1]
+}
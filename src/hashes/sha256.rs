@@ -0,0 +1,197 @@
+pub(crate) mod backends;
+
+use crate::backend::{self, Backend};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub(crate) const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn hardware_supported() -> bool {
+    is_x86_feature_detected!("sha")
+        && is_x86_feature_detected!("ssse3")
+        && is_x86_feature_detected!("sse4.1")
+}
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn hardware_supported() -> bool {
+    std::arch::is_aarch64_feature_detected!("sha2")
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub(crate) fn hardware_supported() -> bool {
+    false
+}
+
+fn compress_soft(state: &mut [u32; 8], blocks: &[u8]) {
+    let mut w = [0u32; 64];
+
+    for block in blocks.chunks_exact(64) {
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (state, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    w.zeroize();
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buf: [u8; 64],
+    leftover: usize,
+    length: u64,
+    #[zeroize(skip)]
+    hardware: bool,
+}
+
+impl Sha256 {
+    fn compress(&mut self, blocks: &[u8]) {
+        match self.hardware {
+            #[cfg(target_arch = "x86_64")]
+            true => unsafe { backends::shani::compress(&mut self.state, blocks) },
+            #[cfg(target_arch = "aarch64")]
+            true => unsafe { backends::armsha2::compress(&mut self.state, blocks) },
+            _ => compress_soft(&mut self.state, blocks),
+        }
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Sha256 {
+        Sha256::with_hardware(backend::hardware_sha256())
+    }
+
+    /// `Backend::Scalar` selects the portable implementation; any other backend uses the SHA
+    /// extensions when the CPU has them.
+    pub fn with_backend(backend: Backend) -> Sha256 {
+        Sha256::with_hardware(backend != Backend::Scalar && hardware_supported())
+    }
+
+    fn with_hardware(hardware: bool) -> Sha256 {
+        Sha256 {
+            state: IV,
+            buf: [0u8; 64],
+            leftover: 0,
+            length: 0,
+            hardware,
+        }
+    }
+
+    pub fn is_hardware(&self) -> bool {
+        self.hardware
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        if self.leftover != 0 {
+            let take = (64 - self.leftover).min(data.len());
+            self.buf[self.leftover..self.leftover + take].copy_from_slice(&data[..take]);
+            self.leftover += take;
+            data = &data[take..];
+
+            if self.leftover < 64 {
+                return;
+            }
+
+            let buf = self.buf;
+            self.compress(&buf);
+            self.leftover = 0;
+        }
+
+        let whole = data.len() - data.len() % 64;
+        self.compress(&data[..whole]);
+
+        let remainder = &data[whole..];
+        self.buf[..remainder.len()].copy_from_slice(remainder);
+        self.leftover = remainder.len();
+    }
+
+    pub fn digest(mut self) -> [u8; 32] {
+        let bits = self.length << 3;
+
+        let mut padding = [0u8; 128];
+        padding[0] = 0x80;
+
+        let padlen = if self.leftover < 56 {
+            56 - self.leftover
+        } else {
+            120 - self.leftover
+        };
+
+        self.update(&padding[..padlen]);
+        self.update(&bits.to_be_bytes());
+
+        let mut output = [0u8; 32];
+
+        for (chunk, word) in output.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        output
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.digest()
+}
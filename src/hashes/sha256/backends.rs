@@ -0,0 +1,4 @@
+#[cfg(target_arch = "aarch64")]
+pub mod armsha2;
+#[cfg(target_arch = "x86_64")]
+pub mod shani;
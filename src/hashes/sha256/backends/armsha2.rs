@@ -0,0 +1,37 @@
+use crate::hashes::sha256::K;
+use core::arch::aarch64::*;
+
+#[target_feature(enable = "sha2")]
+pub unsafe fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    let mut abcd = vld1q_u32(state.as_ptr());
+    let mut efgh = vld1q_u32(state[4..].as_ptr());
+
+    for block in blocks.chunks_exact(64) {
+        let (abcd_saved, efgh_saved) = (abcd, efgh);
+
+        let mut w = [vdupq_n_u32(0); 4];
+
+        for (i, w) in w.iter_mut().enumerate() {
+            *w = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[16 * i..].as_ptr())));
+        }
+
+        for i in 0..16 {
+            let wk = vaddq_u32(w[i % 4], vld1q_u32(K[4 * i..].as_ptr()));
+            let abcd_previous = abcd;
+
+            abcd = vsha256hq_u32(abcd, efgh, wk);
+            efgh = vsha256h2q_u32(efgh, abcd_previous, wk);
+
+            if i < 12 {
+                let next = vsha256su0q_u32(w[i % 4], w[(i + 1) % 4]);
+                w[i % 4] = vsha256su1q_u32(next, w[(i + 2) % 4], w[(i + 3) % 4]);
+            }
+        }
+
+        abcd = vaddq_u32(abcd, abcd_saved);
+        efgh = vaddq_u32(efgh, efgh_saved);
+    }
+
+    vst1q_u32(state.as_mut_ptr(), abcd);
+    vst1q_u32(state[4..].as_mut_ptr(), efgh);
+}
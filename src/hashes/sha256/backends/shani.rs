@@ -0,0 +1,55 @@
+use crate::hashes::sha256::K;
+use core::arch::x86_64::*;
+
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+pub unsafe fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    let mask = _mm_set_epi64x(0x0c0d0e0f08090a0b, 0x0405060700010203);
+
+    // the SHA instructions keep the state as ABEF and CDGH
+    let dcba = _mm_shuffle_epi32(_mm_loadu_si128(state.as_ptr() as *const __m128i), 0xb1);
+    let efgh = _mm_shuffle_epi32(_mm_loadu_si128(state[4..].as_ptr() as *const __m128i), 0x1b);
+
+    let mut abef = _mm_alignr_epi8(dcba, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, dcba, 0xf0);
+
+    for block in blocks.chunks_exact(64) {
+        let (abef_saved, cdgh_saved) = (abef, cdgh);
+
+        let mut w = [_mm_setzero_si128(); 4];
+
+        for (i, w) in w.iter_mut().enumerate() {
+            let words = _mm_loadu_si128(block[16 * i..].as_ptr() as *const __m128i);
+            *w = _mm_shuffle_epi8(words, mask);
+        }
+
+        for i in 0..16 {
+            let k = _mm_loadu_si128(K[4 * i..].as_ptr() as *const __m128i);
+            let wk = _mm_add_epi32(w[i % 4], k);
+
+            cdgh = _mm_sha256rnds2_epu32(cdgh, abef, wk);
+            abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32(wk, 0x0e));
+
+            if i < 12 {
+                let next = _mm_sha256msg1_epu32(w[i % 4], w[(i + 1) % 4]);
+                let next = _mm_add_epi32(next, _mm_alignr_epi8(w[(i + 3) % 4], w[(i + 2) % 4], 4));
+
+                w[i % 4] = _mm_sha256msg2_epu32(next, w[(i + 3) % 4]);
+            }
+        }
+
+        abef = _mm_add_epi32(abef, abef_saved);
+        cdgh = _mm_add_epi32(cdgh, cdgh_saved);
+    }
+
+    let feba = _mm_shuffle_epi32(abef, 0x1b);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xb1);
+
+    _mm_storeu_si128(
+        state.as_mut_ptr() as *mut __m128i,
+        _mm_blend_epi16(feba, dchg, 0xf0),
+    );
+    _mm_storeu_si128(
+        state[4..].as_mut_ptr() as *mut __m128i,
+        _mm_alignr_epi8(dchg, feba, 8),
+    );
+}
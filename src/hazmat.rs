@@ -0,0 +1,107 @@
+// The shape of `cryptography.hazmat.primitives.ciphers.aead`: key in the constructor, then
+// `encrypt(nonce, data, associated_data)` with the tag appended, so the two libraries can be
+// swapped for one another in tests and benchmarks
+
+use crate::aeads;
+use crate::errors::Error;
+use crate::utils::redacted_debug;
+
+pub use crate::errors::InvalidMac as InvalidTag;
+
+fn random_key(length: usize) -> Vec<u8> {
+    let mut key = vec![0u8; length];
+    crate::rng::fill(&mut key);
+
+    key
+}
+
+pub struct ChaCha20Poly1305 {
+    aead: aeads::ChaCha20Poly1305,
+}
+
+redacted_debug!(ChaCha20Poly1305, "<32 bytes, redacted>");
+
+impl ChaCha20Poly1305 {
+    pub const KEY_SIZE: usize = aeads::ChaCha20Poly1305::KEY_SIZE;
+    pub const NONCE_SIZE: usize = aeads::ChaCha20Poly1305::NONCE_SIZE;
+    pub const TAG_SIZE: usize = aeads::ChaCha20Poly1305::TAG_SIZE;
+
+    pub fn new(key: &[u8]) -> Result<ChaCha20Poly1305, Error> {
+        if key.len() != Self::KEY_SIZE {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(ChaCha20Poly1305 {
+            aead: aeads::ChaCha20Poly1305::new(key),
+        })
+    }
+
+    pub fn generate_key() -> Vec<u8> {
+        random_key(Self::KEY_SIZE)
+    }
+
+    /// `nonce` must be 12 bytes.
+    pub fn encrypt(&self, nonce: &[u8], data: &[u8], associated_data: Option<&[u8]>) -> Vec<u8> {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+
+        self.aead
+            .encrypt(data, nonce, associated_data.unwrap_or_default())
+            .into_vec()
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: &[u8],
+        data: &[u8],
+        associated_data: Option<&[u8]>,
+    ) -> Result<Vec<u8>, InvalidTag> {
+        assert_eq!(nonce.len(), Self::NONCE_SIZE);
+
+        self.aead
+            .decrypt(data, nonce, associated_data.unwrap_or_default())
+    }
+}
+
+/// `AESGCM` in `cryptography`, limited to 12-byte nonces.
+pub struct AesGcm {
+    aead: aeads::AesGcm,
+}
+
+redacted_debug!(AesGcm, "<redacted>");
+
+impl AesGcm {
+    pub const NONCE_SIZE: usize = aeads::AesGcm::NONCE_SIZE;
+    pub const TAG_SIZE: usize = aeads::AesGcm::TAG_SIZE;
+
+    pub fn new(key: &[u8]) -> Result<AesGcm, Error> {
+        Ok(AesGcm {
+            aead: aeads::AesGcm::new(key)?,
+        })
+    }
+
+    /// `bit_length` is 128, 192 or 256.
+    pub fn generate_key(bit_length: usize) -> Vec<u8> {
+        assert!(
+            [128, 192, 256].contains(&bit_length),
+            "AES keys are 128, 192 or 256 bits"
+        );
+
+        random_key(bit_length / 8)
+    }
+
+    pub fn encrypt(&self, nonce: &[u8], data: &[u8], associated_data: Option<&[u8]>) -> Vec<u8> {
+        self.aead
+            .encrypt(data, nonce, associated_data.unwrap_or_default())
+            .into_vec()
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: &[u8],
+        data: &[u8],
+        associated_data: Option<&[u8]>,
+    ) -> Result<Vec<u8>, InvalidTag> {
+        self.aead
+            .decrypt(data, nonce, associated_data.unwrap_or_default())
+    }
+}
@@ -0,0 +1,7 @@
+mod fields;
+mod jcs;
+mod jwe;
+
+pub use fields::{FieldCipher, FieldError};
+pub use jcs::{canonicalize, sign_json, verify_json};
+pub use jwe::{decrypt_jwe, encrypt_jwe, JweAlgorithm, JweEncryption, JweError};
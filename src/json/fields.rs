@@ -0,0 +1,135 @@
+// Field-level encryption of JSON documents, for storing or logging records with only some of
+// their values hidden. Each selected value is serialized, encrypted with XChaCha20-Poly1305 and
+// replaced by a string envelope:
+//
+//     "rcf1.<key id, hex>.<base64 of nonce || ciphertext>"
+//
+// The value's JSON pointer is the associated data, so an encrypted value cannot be moved to
+// another field without failing to decrypt.
+
+use crate::aeads::{SymmetricKey, XChaCha20Poly1305};
+use crate::audit::KeyId;
+use crate::encoding::base64;
+use crate::utils::redacted_debug;
+use serde_json::Value;
+use zeroize::Zeroize;
+
+const PREFIX: &str = "rcf1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    Malformed,
+    InvalidMac,
+    UnknownKey,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldError::Malformed => write!(f, "The encrypted field is malformed."),
+            FieldError::InvalidMac => {
+                write!(f, "Invalid MAC detected. This field may be tampered with.")
+            }
+            FieldError::UnknownKey => write!(f, "The field was encrypted under another key."),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+pub struct FieldCipher {
+    aead: XChaCha20Poly1305,
+    key_id: KeyId,
+}
+
+redacted_debug!(FieldCipher, "<32 bytes, redacted>");
+
+impl FieldCipher {
+    pub fn new(key: &SymmetricKey) -> FieldCipher {
+        FieldCipher {
+            aead: XChaCha20Poly1305::new(key.as_bytes()),
+            key_id: KeyId::new(key.as_bytes()),
+        }
+    }
+
+    /// The id written into every envelope, for picking the right key after rotating keys.
+    pub fn key_id(&self) -> KeyId {
+        self.key_id
+    }
+
+    /// Encrypts `value` as the value at `path`, a JSON pointer.
+    pub fn encrypt_value(&self, value: &Value, path: &str) -> String {
+        let mut msg = value.to_string();
+        let nonce = XChaCha20Poly1305::generate_nonce();
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&self.aead.encrypt(msg.as_bytes(), &nonce, path.as_bytes()));
+        msg.zeroize();
+
+        format!(
+            "{}.{}.{}",
+            PREFIX,
+            self.key_id.to_hex(),
+            base64::encode(&sealed)
+        )
+    }
+
+    pub fn decrypt_value(&self, envelope: &str, path: &str) -> Result<Value, FieldError> {
+        let mut parts = envelope.splitn(3, '.');
+        let (Some(PREFIX), Some(key_id), Some(sealed)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(FieldError::Malformed);
+        };
+
+        if key_id != self.key_id.to_hex() {
+            return Err(FieldError::UnknownKey);
+        }
+
+        let sealed = base64::decode(sealed).map_err(|_| FieldError::Malformed)?;
+        if sealed.len() < XChaCha20Poly1305::NONCE_SIZE + XChaCha20Poly1305::TAG_SIZE {
+            return Err(FieldError::Malformed);
+        }
+
+        let (nonce, ct) = sealed.split_at(XChaCha20Poly1305::NONCE_SIZE);
+        let mut msg = self
+            .aead
+            .decrypt(ct, nonce, path.as_bytes())
+            .map_err(|_| FieldError::InvalidMac)?;
+        let value = serde_json::from_slice(&msg).map_err(|_| FieldError::Malformed);
+        msg.zeroize();
+
+        value
+    }
+
+    /// Replaces the values at `paths`, which are JSON pointers such as `/user/email`, with their
+    /// envelopes. Paths missing from the document are skipped.
+    pub fn encrypt_fields(&self, document: &mut Value, paths: &[&str]) {
+        for path in paths {
+            if let Some(value) = document.pointer_mut(path) {
+                *value = Value::String(self.encrypt_value(value, path));
+            }
+        }
+    }
+
+    /// The inverse of `encrypt_fields` with the same paths. Either every field is decrypted or,
+    /// on an error, the document is left as it was.
+    pub fn decrypt_fields(&self, document: &mut Value, paths: &[&str]) -> Result<(), FieldError> {
+        let mut decrypted = document.clone();
+
+        // in reverse, so a field encrypted inside another one is reached after its parent
+        for path in paths.iter().rev() {
+            let Some(value) = decrypted.pointer_mut(path) else {
+                continue;
+            };
+            let Value::String(envelope) = value else {
+                return Err(FieldError::Malformed);
+            };
+
+            *value = self.decrypt_value(envelope, path)?;
+        }
+
+        *document = decrypted;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+// The JSON Canonicalization Scheme of RFC 8785: no whitespace, object members sorted by the
+// UTF-16 code units of their names, strings escaped minimally and numbers written as ECMAScript
+// writes IEEE 754 doubles. Equal documents canonicalize to the same bytes in any language with
+// a JCS implementation, which makes them safe to sign.
+
+use crate::ecc::ed25519::{Signature, SigningKey, VerifyingKey};
+use crate::errors::InvalidSignature;
+use serde_json::{Number, Value};
+
+pub fn canonicalize(value: &Value) -> String {
+    let mut output = String::new();
+    write_value(&mut output, value);
+
+    output
+}
+
+/// An Ed25519 signature over the canonical form of `value`.
+pub fn sign_json(key: &SigningKey, value: &Value) -> Signature {
+    key.sign(canonicalize(value).as_bytes())
+}
+
+pub fn verify_json(
+    key: &VerifyingKey,
+    value: &Value,
+    signature: &[u8],
+) -> Result<(), InvalidSignature> {
+    key.verify(canonicalize(value).as_bytes(), signature)
+}
+
+fn write_value(output: &mut String, value: &Value) {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(true) => output.push_str("true"),
+        Value::Bool(false) => output.push_str("false"),
+        Value::Number(number) => write_number(output, number),
+        Value::String(string) => write_string(output, string),
+        Value::Array(values) => {
+            output.push('[');
+
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_value(output, value);
+            }
+
+            output.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            output.push('{');
+
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_string(output, name);
+                output.push(':');
+                write_value(output, value);
+            }
+
+            output.push('}');
+        }
+    }
+}
+
+fn write_string(output: &mut String, string: &str) {
+    output.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{c}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            c if c < ' ' => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+}
+
+// Number::toString of ECMA-262, section 6.1.6.1.20. Integers beyond 2^53 are rounded to the
+// nearest double first, as a JavaScript parser would.
+fn write_number(output: &mut String, number: &Number) {
+    let number = number.as_f64().unwrap();
+
+    if number == 0.0 {
+        output.push('0');
+        return;
+    }
+
+    if number < 0.0 {
+        output.push('-');
+    }
+
+    // Rust also finds the fewest digits that round trip, but where two are as close it does not
+    // pick the even one, so round the exact value to that many digits, which breaks ties to even
+    let shortest = format!("{:e}", number.abs());
+    let k = shortest.split_once('e').unwrap().0.replace('.', "").len() as i32;
+
+    let scientific = format!("{:.*e}", k as usize - 1, number.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let n = exponent.parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        output.push_str(&digits);
+        output.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        output.push_str(&digits[..n as usize]);
+        output.push('.');
+        output.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        output.push_str("0.");
+        output.push_str(&"0".repeat(-n as usize));
+        output.push_str(&digits);
+    } else {
+        output.push_str(&digits[..1]);
+
+        if k > 1 {
+            output.push('.');
+            output.push_str(&digits[1..]);
+        }
+
+        let sign = if n > 0 { '+' } else { '-' };
+        output.push_str(&format!("e{}{}", sign, (n - 1).abs()));
+    }
+}
@@ -0,0 +1,283 @@
+// JWE compact serialization (RFC 7516) with X25519 key agreement (RFC 8037), in the ECDH-ES and
+// ECDH-ES+A256KW modes of RFC 7518 and with AES-GCM content encryption. The sender's ephemeral
+// key travels in the "epk" header parameter, and the key is derived with the Concat KDF over
+// SHA-256, including any "apu" and "apv" parameters.
+//
+// Only what these modes need is supported: headers with "zip" or "crit" are refused.
+
+use crate::aeads::AesGcm;
+use crate::ciphers::aes::keywrap;
+use crate::ecc::x25519::{self, PrivateKey, PublicKey};
+use crate::encoding::base64;
+use crate::hashes::sha256::Sha256;
+use crate::utils::{const_time_eq, random_bytes};
+use serde_json::{json, Map, Value};
+use zeroize::Zeroize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JweError {
+    Malformed,
+    Unsupported,
+    InvalidMac,
+}
+
+impl std::fmt::Display for JweError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JweError::Malformed => write!(f, "The JWE is malformed."),
+            JweError::Unsupported => write!(f, "The JWE uses an unsupported algorithm or feature."),
+            JweError::InvalidMac => {
+                write!(f, "Invalid MAC detected. This JWE may be tampered with.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JweError {}
+
+/// The "alg" header parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JweAlgorithm {
+    /// The agreed key is the content encryption key.
+    EcdhEs,
+    /// The agreed key wraps a random content encryption key with AES Key Wrap.
+    EcdhEsA256Kw,
+}
+
+impl JweAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JweAlgorithm::EcdhEs => "ECDH-ES",
+            JweAlgorithm::EcdhEsA256Kw => "ECDH-ES+A256KW",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<JweAlgorithm> {
+        match name {
+            "ECDH-ES" => Some(JweAlgorithm::EcdhEs),
+            "ECDH-ES+A256KW" => Some(JweAlgorithm::EcdhEsA256Kw),
+            _ => None,
+        }
+    }
+}
+
+/// The "enc" header parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JweEncryption {
+    A128Gcm,
+    A256Gcm,
+}
+
+impl JweEncryption {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JweEncryption::A128Gcm => "A128GCM",
+            JweEncryption::A256Gcm => "A256GCM",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<JweEncryption> {
+        match name {
+            "A128GCM" => Some(JweEncryption::A128Gcm),
+            "A256GCM" => Some(JweEncryption::A256Gcm),
+            _ => None,
+        }
+    }
+
+    fn key_size(&self) -> usize {
+        match self {
+            JweEncryption::A128Gcm => 16,
+            JweEncryption::A256Gcm => 32,
+        }
+    }
+}
+
+// RFC 7518 section 4.6.2: one round of the Concat KDF, as no key here is over 256 bits
+fn concat_kdf(shared: &[u8], algorithm_id: &str, apu: &[u8], apv: &[u8], length: usize) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(&1u32.to_be_bytes());
+    hasher.update(shared);
+
+    for field in [algorithm_id.as_bytes(), apu, apv] {
+        hasher.update(&(field.len() as u32).to_be_bytes());
+        hasher.update(field);
+    }
+
+    hasher.update(&(length as u32 * 8).to_be_bytes());
+
+    hasher.digest()[..length].to_vec()
+}
+
+// the key derived for `alg`, which is the content encryption key for ECDH-ES
+fn agreed_key(
+    shared: &[u8],
+    alg: JweAlgorithm,
+    enc: JweEncryption,
+    apu: &[u8],
+    apv: &[u8],
+) -> Vec<u8> {
+    match alg {
+        JweAlgorithm::EcdhEs => concat_kdf(shared, enc.name(), apu, apv, enc.key_size()),
+        JweAlgorithm::EcdhEsA256Kw => concat_kdf(shared, alg.name(), apu, apv, 32),
+    }
+}
+
+/// Encrypts `plaintext` to `recipient` as a JWE in compact serialization.
+pub fn encrypt_jwe(
+    recipient: &PublicKey,
+    alg: JweAlgorithm,
+    enc: JweEncryption,
+    plaintext: &[u8],
+) -> String {
+    let ephemeral = x25519::keygen(None);
+    let mut shared = ephemeral.exchange(*recipient);
+
+    let header = json!({
+        "alg": alg.name(),
+        "enc": enc.name(),
+        "epk": {
+            "kty": "OKP",
+            "crv": "X25519",
+            "x": base64::encode_url(&ephemeral.public_key()),
+        },
+    });
+    let header = base64::encode_url(header.to_string().as_bytes());
+
+    let mut key = agreed_key(&shared, alg, enc, b"", b"");
+    shared.zeroize();
+
+    let encrypted_key = match alg {
+        JweAlgorithm::EcdhEs => Vec::new(),
+        JweAlgorithm::EcdhEsA256Kw => {
+            let mut random: [u8; 32] = random_bytes();
+            let cek = random[..enc.key_size()].to_vec();
+            random.zeroize();
+
+            let wrapped = keywrap::wrap(&key, &cek).unwrap();
+            key.zeroize();
+            key = cek;
+
+            wrapped
+        }
+    };
+
+    let iv: [u8; 12] = random_bytes();
+    let ct = AesGcm::new(&key)
+        .unwrap()
+        .encrypt(plaintext, &iv, header.as_bytes());
+    key.zeroize();
+
+    [
+        header,
+        base64::encode_url(&encrypted_key),
+        base64::encode_url(&iv),
+        base64::encode_url(ct.ciphertext()),
+        base64::encode_url(ct.tag()),
+    ]
+    .join(".")
+}
+
+fn string<'a>(header: &'a Map<String, Value>, name: &str) -> Result<Option<&'a str>, JweError> {
+    match header.get(name) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(JweError::Malformed),
+    }
+}
+
+fn bytes(header: &Map<String, Value>, name: &str) -> Result<Vec<u8>, JweError> {
+    string(header, name)?.map_or(Ok(Vec::new()), |value| {
+        base64::decode_url(value).map_err(|_| JweError::Malformed)
+    })
+}
+
+fn ephemeral_key(header: &Map<String, Value>) -> Result<PublicKey, JweError> {
+    let epk = match header.get("epk") {
+        Some(Value::Object(epk)) => epk,
+        _ => return Err(JweError::Malformed),
+    };
+
+    if string(epk, "kty")? != Some("OKP") || string(epk, "crv")? != Some("X25519") {
+        return Err(JweError::Unsupported);
+    }
+
+    bytes(epk, "x")?.try_into().map_err(|_| JweError::Malformed)
+}
+
+/// Decrypts a JWE in compact serialization addressed to `private`.
+pub fn decrypt_jwe(private: &PrivateKey, jwe: &str) -> Result<Vec<u8>, JweError> {
+    let parts: Vec<&str> = jwe.split('.').collect();
+    let [header_part, encrypted_key, iv, ct, tag] = parts[..] else {
+        return Err(JweError::Malformed);
+    };
+
+    let header = base64::decode_url(header_part).map_err(|_| JweError::Malformed)?;
+    let header = match serde_json::from_slice(&header) {
+        Ok(Value::Object(header)) => header,
+        _ => return Err(JweError::Malformed),
+    };
+
+    if header.contains_key("zip") || header.contains_key("crit") {
+        return Err(JweError::Unsupported);
+    }
+
+    let alg = string(&header, "alg")?
+        .and_then(JweAlgorithm::from_name)
+        .ok_or(JweError::Unsupported)?;
+    let enc = string(&header, "enc")?
+        .and_then(JweEncryption::from_name)
+        .ok_or(JweError::Unsupported)?;
+
+    let decode = |part: &str| base64::decode_url(part).map_err(|_| JweError::Malformed);
+    let encrypted_key = decode(encrypted_key)?;
+    let iv = decode(iv)?;
+    let ct = decode(ct)?;
+    let tag = decode(tag)?;
+
+    if iv.len() != AesGcm::NONCE_SIZE || tag.len() != AesGcm::TAG_SIZE {
+        return Err(JweError::Malformed);
+    }
+
+    let mut shared = private.exchange(ephemeral_key(&header)?);
+
+    // a low-order ephemeral key gives an all-zero secret anyone can compute
+    if const_time_eq(&shared, &[0u8; 32]) {
+        return Err(JweError::InvalidMac);
+    }
+
+    let mut key = agreed_key(
+        &shared,
+        alg,
+        enc,
+        &bytes(&header, "apu")?,
+        &bytes(&header, "apv")?,
+    );
+    shared.zeroize();
+
+    match alg {
+        JweAlgorithm::EcdhEs => {
+            if !encrypted_key.is_empty() {
+                return Err(JweError::Malformed);
+            }
+        }
+        JweAlgorithm::EcdhEsA256Kw => {
+            let cek = keywrap::unwrap(&key, &encrypted_key).map_err(|_| JweError::InvalidMac);
+            key.zeroize();
+            key = cek?;
+
+            if key.len() != enc.key_size() {
+                key.zeroize();
+                return Err(JweError::InvalidMac);
+            }
+        }
+    }
+
+    let ct = [ct, tag].concat();
+    let msg = AesGcm::new(&key)
+        .unwrap()
+        .decrypt(&ct, &iv, header_part.as_bytes())
+        .map_err(|_| JweError::InvalidMac);
+    key.zeroize();
+
+    msg
+}
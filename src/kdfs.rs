@@ -0,0 +1,18 @@
+pub mod argon2;
+pub mod fido2;
+pub mod hierarchy;
+pub mod hkdf;
+pub mod pbkdf2;
+pub mod phc;
+pub mod ratchet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidLength;
+
+impl std::fmt::Display for InvalidLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The requested output length is not supported.")
+    }
+}
+
+impl std::error::Error for InvalidLength {}
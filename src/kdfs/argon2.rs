@@ -0,0 +1,608 @@
+use crate::cancel::CancelToken;
+use crate::errors::Cancelled;
+use crate::hashes::blake2b::{self, Blake2b};
+use crate::parallel;
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+pub const VERSION: u32 = 0x13;
+const SYNC_POINTS: usize = 4;
+const ADDRESSES_PER_BLOCK: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Argon2d,
+    Argon2i,
+    Argon2id,
+}
+
+impl Variant {
+    /// The name used in PHC strings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Argon2d => "argon2d",
+            Variant::Argon2i => "argon2i",
+            Variant::Argon2id => "argon2id",
+        }
+    }
+
+    fn id(self) -> u32 {
+        match self {
+            Variant::Argon2d => 0,
+            Variant::Argon2i => 1,
+            Variant::Argon2id => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Argon2Error {
+    InvalidMemoryCost,
+    InvalidTimeCost,
+    InvalidParallelism,
+    InvalidSaltLength,
+    InvalidOutputLength,
+    Cancelled,
+}
+
+impl std::fmt::Display for Argon2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Argon2Error::InvalidMemoryCost => {
+                write!(f, "The memory cost must be at least 8 KiB per lane.")
+            }
+            Argon2Error::InvalidTimeCost => write!(f, "The time cost must be at least 1."),
+            Argon2Error::InvalidParallelism => {
+                write!(f, "The parallelism must be between 1 and 2^24 - 1.")
+            }
+            Argon2Error::InvalidSaltLength => write!(f, "The salt must be at least 8 bytes."),
+            Argon2Error::InvalidOutputLength => {
+                write!(f, "The output must be at least 4 bytes.")
+            }
+            Argon2Error::Cancelled => write!(f, "The operation was cancelled."),
+        }
+    }
+}
+
+impl std::error::Error for Argon2Error {}
+
+#[derive(Clone, Copy, Zeroize)]
+struct Block([u64; 128]);
+
+impl Block {
+    const ZERO: Block = Block([0u64; 128]);
+
+    fn from_bytes(bytes: &[u8; 1024]) -> Block {
+        Block(core::array::from_fn(|i| {
+            u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap())
+        }))
+    }
+
+    fn to_bytes(self) -> [u8; 1024] {
+        let mut bytes = [0u8; 1024];
+
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(self.0) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    fn xor(&self, other: &Block) -> Block {
+        Block(core::array::from_fn(|i| self.0[i] ^ other.0[i]))
+    }
+}
+
+// BLAKE2b's addition with a multiplication of the low halves mixed in
+fn fbla(x: u64, y: u64) -> u64 {
+    let product = (x as u32 as u64).wrapping_mul(y as u32 as u64);
+    x.wrapping_add(y).wrapping_add(product.wrapping_mul(2))
+}
+
+fn gb(v: &mut [u64; 128], a: usize, b: usize, c: usize, d: usize) {
+    v[a] = fbla(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = fbla(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = fbla(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = fbla(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn permute(v: &mut [u64; 128], i: [usize; 16]) {
+    gb(v, i[0], i[4], i[8], i[12]);
+    gb(v, i[1], i[5], i[9], i[13]);
+    gb(v, i[2], i[6], i[10], i[14]);
+    gb(v, i[3], i[7], i[11], i[15]);
+
+    gb(v, i[0], i[5], i[10], i[15]);
+    gb(v, i[1], i[6], i[11], i[12]);
+    gb(v, i[2], i[7], i[8], i[13]);
+    gb(v, i[3], i[4], i[9], i[14]);
+}
+
+fn compress(x: &Block, y: &Block) -> Block {
+    let r = x.xor(y);
+    let mut q = r;
+
+    for row in 0..8 {
+        permute(&mut q.0, core::array::from_fn(|k| 16 * row + k));
+    }
+
+    for column in 0..8 {
+        permute(
+            &mut q.0,
+            core::array::from_fn(|k| 2 * column + 16 * (k / 2) + k % 2),
+        );
+    }
+
+    q.xor(&r)
+}
+
+// H', BLAKE2b stretched to any output length
+fn variable_hash(inputs: &[&[u8]], output: &mut [u8]) {
+    let length = output.len();
+
+    let mut hasher = Blake2b::new(length.min(64));
+    hasher.update(&(length as u32).to_le_bytes());
+
+    for input in inputs {
+        hasher.update(input);
+    }
+
+    let mut v = hasher.digest();
+
+    if length <= 64 {
+        output.copy_from_slice(&v);
+        v.zeroize();
+        return;
+    }
+
+    let r = length.div_ceil(32) - 2;
+    output[..32].copy_from_slice(&v[..32]);
+
+    for i in 1..r {
+        let next = blake2b::hash(&v, 64);
+        v.zeroize();
+        v = next;
+
+        output[32 * i..32 * i + 32].copy_from_slice(&v[..32]);
+    }
+
+    let mut last = blake2b::hash(&v, length - 32 * r);
+    output[32 * r..].copy_from_slice(&last);
+
+    v.zeroize();
+    last.zeroize();
+}
+
+// the memory is kept as slices[slice][lane], one vector per segment, so that while a slice is
+// being filled every lane can own its segment and share the finished ones
+type Slice = Vec<Vec<Block>>;
+
+struct Layout {
+    variant: Variant,
+    passes: u32,
+    lanes: usize,
+    lane_length: usize,
+    segment_length: usize,
+}
+
+impl Layout {
+    fn block<'a>(
+        &self,
+        finished: (&'a [Slice], &'a [Slice]),
+        slice: usize,
+        segment: &'a [Block],
+        lane: usize,
+        column: usize,
+    ) -> &'a Block {
+        let (before, after) = finished;
+        let (index, offset) = (column / self.segment_length, column % self.segment_length);
+
+        match index.cmp(&slice) {
+            std::cmp::Ordering::Less => &before[index][lane][offset],
+            std::cmp::Ordering::Equal => &segment[offset],
+            std::cmp::Ordering::Greater => &after[index - slice - 1][lane][offset],
+        }
+    }
+
+    fn reference_column(
+        &self,
+        pass: u32,
+        slice: usize,
+        index: usize,
+        same_lane: bool,
+        random: u32,
+    ) -> usize {
+        let finished = match pass {
+            0 => slice * self.segment_length,
+            _ => self.lane_length - self.segment_length,
+        };
+
+        let area = if same_lane {
+            finished + index - 1
+        } else {
+            finished - usize::from(index == 0)
+        };
+
+        let x = (random as u64 * random as u64) >> 32;
+        let y = (area as u64 * x) >> 32;
+        let relative = area - 1 - y as usize;
+
+        let start = if pass == 0 || slice == SYNC_POINTS - 1 {
+            0
+        } else {
+            (slice + 1) * self.segment_length
+        };
+
+        (start + relative) % self.lane_length
+    }
+
+    fn fill_segment(
+        &self,
+        finished: (&[Slice], &[Slice]),
+        pass: u32,
+        slice: usize,
+        lane: usize,
+        segment: &mut [Block],
+    ) {
+        let data_independent = match self.variant {
+            Variant::Argon2d => false,
+            Variant::Argon2i => true,
+            Variant::Argon2id => pass == 0 && slice < SYNC_POINTS / 2,
+        };
+
+        let mut input = Block::ZERO;
+        let mut addresses = Block::ZERO;
+
+        input.0[..6].copy_from_slice(&[
+            pass as u64,
+            lane as u64,
+            slice as u64,
+            (self.lanes * self.lane_length) as u64,
+            self.passes as u64,
+            self.variant.id() as u64,
+        ]);
+
+        let mut next_addresses = |addresses: &mut Block| {
+            input.0[6] += 1;
+            *addresses = compress(&Block::ZERO, &compress(&Block::ZERO, &input));
+        };
+
+        // the first two blocks of every lane come from the initial hash
+        let start = if pass == 0 && slice == 0 { 2 } else { 0 };
+
+        if data_independent && start != 0 {
+            next_addresses(&mut addresses);
+        }
+
+        for index in start..self.segment_length {
+            if data_independent && index % ADDRESSES_PER_BLOCK == 0 {
+                next_addresses(&mut addresses);
+            }
+
+            let column = slice * self.segment_length + index;
+            let previous = match column {
+                0 => self.lane_length - 1,
+                _ => column - 1,
+            };
+
+            let random = if data_independent {
+                addresses.0[index % ADDRESSES_PER_BLOCK]
+            } else {
+                self.block(finished, slice, segment, lane, previous).0[0]
+            };
+
+            let reference_lane = if pass == 0 && slice == 0 {
+                lane
+            } else {
+                ((random >> 32) % self.lanes as u64) as usize
+            };
+
+            let reference_column =
+                self.reference_column(pass, slice, index, reference_lane == lane, random as u32);
+
+            let mut next = compress(
+                self.block(finished, slice, segment, lane, previous),
+                self.block(finished, slice, segment, reference_lane, reference_column),
+            );
+
+            if pass > 0 {
+                next = next.xor(&segment[index]);
+            }
+
+            segment[index] = next;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Argon2 {
+    variant: Variant,
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+    threads: usize,
+}
+
+impl Argon2 {
+    // libsodium's crypto_pwhash presets, which use one lane
+    /// 64 MiB and 2 passes, for logins and other online use.
+    pub const INTERACTIVE: Argon2 = Argon2::preset(64 * 1024, 2);
+    /// 256 MiB and 3 passes.
+    pub const MODERATE: Argon2 = Argon2::preset(256 * 1024, 3);
+    /// 1 GiB and 4 passes, for keys protecting highly sensitive data.
+    pub const SENSITIVE: Argon2 = Argon2::preset(1024 * 1024, 4);
+
+    const fn preset(memory_cost: u32, time_cost: u32) -> Argon2 {
+        Argon2 {
+            variant: Variant::Argon2id,
+            memory_cost,
+            time_cost,
+            parallelism: 1,
+            threads: 1,
+        }
+    }
+
+    /// `memory_cost` is in KiB. Lanes are filled on one thread each, up to one per core, unless
+    /// `with_threads` says otherwise.
+    pub fn new(
+        variant: Variant,
+        memory_cost: u32,
+        time_cost: u32,
+        parallelism: u32,
+    ) -> Result<Argon2, Argon2Error> {
+        if parallelism == 0 || parallelism >= 1 << 24 {
+            return Err(Argon2Error::InvalidParallelism);
+        }
+
+        if time_cost == 0 {
+            return Err(Argon2Error::InvalidTimeCost);
+        }
+
+        if (memory_cost as u64) < 8 * parallelism as u64 {
+            return Err(Argon2Error::InvalidMemoryCost);
+        }
+
+        Ok(Argon2 {
+            variant,
+            memory_cost,
+            time_cost,
+            parallelism,
+            threads: parallel::threads(parallelism as usize),
+        })
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn memory_cost(&self) -> u32 {
+        self.memory_cost
+    }
+
+    pub fn time_cost(&self) -> u32 {
+        self.time_cost
+    }
+
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+
+    /// Argon2id with the given memory and lanes, and as many passes as this machine gets through
+    /// in about `target`. One pass is timed and the rest extrapolated, so it takes about
+    /// `target / time_cost` to run.
+    pub fn calibrate(
+        target: Duration,
+        memory_cost: u32,
+        parallelism: u32,
+    ) -> Result<Argon2, Argon2Error> {
+        let single = Argon2::new(Variant::Argon2id, memory_cost, 1, parallelism)?;
+
+        let start = Instant::now();
+        single.hash(b"calibration", b"calibration", 32)?;
+        let elapsed = start.elapsed().max(Duration::from_micros(1));
+
+        let passes = (target.as_secs_f64() / elapsed.as_secs_f64()).floor();
+
+        Argon2::new(
+            Variant::Argon2id,
+            memory_cost,
+            passes.clamp(1.0, u32::MAX as f64) as u32,
+            parallelism,
+        )
+    }
+
+    /// Caps the threads filling lanes, 0 meaning one per core. The output does not depend on it.
+    pub fn with_threads(mut self, threads: usize) -> Argon2 {
+        self.threads = threads;
+        self
+    }
+
+    pub fn hash(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, Argon2Error> {
+        self.hash_with_secret(password, salt, &[], &[], length)
+    }
+
+    /// Like `hash`, calling `progress(done, total)` each time all lanes finish a slice, with
+    /// `total` being four times the time cost.
+    pub fn hash_with_progress<F: FnMut(u64, u64) + Send>(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+        length: usize,
+        mut progress: F,
+    ) -> Result<Vec<u8>, Argon2Error> {
+        self.derive([password, salt, &[], &[]], length, &mut progress, None)
+    }
+
+    /// Like `hash`, giving up with `Argon2Error::Cancelled` once `token` is cancelled. The
+    /// token is checked before every slice.
+    pub fn hash_cancellable(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+        length: usize,
+        token: &CancelToken,
+    ) -> Result<Vec<u8>, Argon2Error> {
+        self.derive(
+            [password, salt, &[], &[]],
+            length,
+            &mut |_, _| {},
+            Some(token),
+        )
+    }
+
+    pub fn hash_with_secret(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+        secret: &[u8],
+        associated_data: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, Argon2Error> {
+        self.derive(
+            [password, salt, secret, associated_data],
+            length,
+            &mut |_, _| {},
+            None,
+        )
+    }
+
+    // inputs are the password, salt, secret and associated data
+    fn derive(
+        &self,
+        inputs: [&[u8]; 4],
+        length: usize,
+        progress: &mut (dyn FnMut(u64, u64) + Send),
+        cancel: Option<&CancelToken>,
+    ) -> Result<Vec<u8>, Argon2Error> {
+        if inputs[1].len() < 8 {
+            return Err(Argon2Error::InvalidSaltLength);
+        }
+
+        if length < 4 || length > u32::MAX as usize {
+            return Err(Argon2Error::InvalidOutputLength);
+        }
+
+        let lanes = self.parallelism as usize;
+        let segment_length = self.memory_cost as usize / (SYNC_POINTS * lanes);
+
+        let layout = Layout {
+            variant: self.variant,
+            passes: self.time_cost,
+            lanes,
+            lane_length: segment_length * SYNC_POINTS,
+            segment_length,
+        };
+
+        let mut hasher = Blake2b::new(64);
+
+        for value in [
+            self.parallelism,
+            length as u32,
+            self.memory_cost,
+            self.time_cost,
+            VERSION,
+            self.variant.id(),
+        ] {
+            hasher.update(&value.to_le_bytes());
+        }
+
+        for input in inputs {
+            hasher.update(&(input.len() as u32).to_le_bytes());
+            hasher.update(input);
+        }
+
+        let mut h0 = hasher.digest();
+
+        let mut slices: [Slice; SYNC_POINTS] =
+            core::array::from_fn(|_| vec![vec![Block::ZERO; segment_length]; lanes]);
+
+        let mut bytes = [0u8; 1024];
+
+        for (lane, segment) in slices[0].iter_mut().enumerate() {
+            for (column, block) in segment[..2].iter_mut().enumerate() {
+                variable_hash(
+                    &[
+                        &h0,
+                        &(column as u32).to_le_bytes(),
+                        &(lane as u32).to_le_bytes(),
+                    ],
+                    &mut bytes,
+                );
+
+                *block = Block::from_bytes(&bytes);
+            }
+        }
+
+        let threads = self.threads;
+
+        let total = layout.passes as u64 * SYNC_POINTS as u64;
+
+        let filled = parallel::install(threads, || {
+            for pass in 0..layout.passes {
+                for slice in 0..SYNC_POINTS {
+                    if let Some(token) = cancel {
+                        token.check()?;
+                    }
+
+                    let (before, rest) = slices.split_at_mut(slice);
+                    let (current, after) = rest.split_first_mut().unwrap();
+                    let finished = (&*before, &*after);
+
+                    parallel::for_each_mut(threads, current, |lane, segment| {
+                        layout.fill_segment(finished, pass, slice, lane, segment)
+                    });
+
+                    progress(pass as u64 * SYNC_POINTS as u64 + slice as u64 + 1, total);
+                }
+            }
+
+            Ok(())
+        });
+
+        if let Err(Cancelled) = filled {
+            h0.zeroize();
+            slices.zeroize();
+
+            return Err(Argon2Error::Cancelled);
+        }
+
+        let mut last = Block::ZERO;
+
+        for segment in &slices[SYNC_POINTS - 1] {
+            last = last.xor(&segment[segment_length - 1]);
+        }
+
+        bytes = last.to_bytes();
+
+        let mut output = vec![0u8; length];
+        variable_hash(&[&bytes], &mut output);
+
+        h0.zeroize();
+        bytes.zeroize();
+        last.zeroize();
+        slices.zeroize();
+
+        Ok(output)
+    }
+}
+
+pub fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+    length: usize,
+) -> Result<Vec<u8>, Argon2Error> {
+    Argon2::new(Variant::Argon2id, memory_cost, time_cost, parallelism)?
+        .hash(password, salt, length)
+}
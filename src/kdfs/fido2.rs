@@ -0,0 +1,113 @@
+use crate::aeads::SymmetricKey;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::random_bytes;
+use zeroize::Zeroize;
+
+pub const SALT_LENGTH: usize = 32;
+pub const SECRET_LENGTH: usize = 32;
+
+const INFO: &[u8] = b"raycrypt fido2 hmac-secret";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidBinding;
+
+impl std::fmt::Display for InvalidBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The token binding is malformed.")
+    }
+}
+
+impl std::error::Error for InvalidBinding {}
+
+/// A FIDO2 authenticator that supports the CTAP2 `hmac-secret` extension. Talking to the device
+/// (USB HID, NFC, the PIN protocol) is left to the application, for example through libfido2;
+/// this only needs the extension's output for a single salt.
+pub trait Authenticator {
+    type Error;
+
+    /// Gets an assertion for `credential_id` with `hmac-secret` requested for `salt`, and returns
+    /// the decrypted output, `HMAC-SHA256(CredRandom, salt)` on the device.
+    fn hmac_secret(
+        &mut self,
+        credential_id: &[u8],
+        salt: &[u8; SALT_LENGTH],
+    ) -> Result<[u8; SECRET_LENGTH], Self::Error>;
+}
+
+/// Ties a key to a credential on a security key. The binding itself is not secret and is stored
+/// next to the data, e.g. in a file header, as `credential id length (2 bytes, big-endian) ||
+/// credential id || salt`; the key can only be derived again with the same device present.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenBinding {
+    credential_id: Vec<u8>,
+    salt: [u8; SALT_LENGTH],
+}
+
+impl TokenBinding {
+    /// A binding with a fresh random salt, so every file gets its own key.
+    pub fn generate(credential_id: &[u8]) -> TokenBinding {
+        TokenBinding::new(credential_id, random_bytes())
+    }
+
+    pub fn new(credential_id: &[u8], salt: [u8; SALT_LENGTH]) -> TokenBinding {
+        assert!(credential_id.len() <= u16::MAX as usize);
+
+        TokenBinding {
+            credential_id: credential_id.to_vec(),
+            salt,
+        }
+    }
+
+    pub fn credential_id(&self) -> &[u8] {
+        &self.credential_id
+    }
+
+    pub fn salt(&self) -> &[u8; SALT_LENGTH] {
+        &self.salt
+    }
+
+    pub fn derive_key<A: Authenticator>(
+        &self,
+        authenticator: &mut A,
+    ) -> Result<SymmetricKey, A::Error> {
+        let mut secret = authenticator.hmac_secret(&self.credential_id, &self.salt)?;
+        let hkdf = Hkdf::<Sha256>::extract(&self.salt, &secret);
+        secret.zeroize();
+
+        let mut key = hkdf.expand(INFO, SymmetricKey::KEY_SIZE).unwrap();
+        let derived = SymmetricKey::new(&key).unwrap();
+        key.zeroize();
+
+        Ok(derived)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.credential_id.len() + SALT_LENGTH);
+        bytes.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.credential_id);
+        bytes.extend_from_slice(&self.salt);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<TokenBinding, InvalidBinding> {
+        if bytes.len() < 2 {
+            return Err(InvalidBinding);
+        }
+
+        let (length, rest) = bytes.split_at(2);
+        let length = u16::from_be_bytes(length.try_into().unwrap()) as usize;
+
+        if rest.len() != length + SALT_LENGTH {
+            return Err(InvalidBinding);
+        }
+
+        let (credential_id, salt) = rest.split_at(length);
+
+        Ok(TokenBinding {
+            credential_id: credential_id.to_vec(),
+            salt: salt.try_into().unwrap(),
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use crate::aeads::SymmetricKey;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::redacted_debug;
+use std::collections::BTreeSet;
+use zeroize::Zeroize;
+
+const SALT: &[u8] = b"raycrypt key hierarchy";
+const MAX_LABEL_LENGTH: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyError {
+    InvalidLabel,
+    DuplicateLabel,
+    UnregisteredLabel,
+}
+
+impl std::fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HierarchyError::InvalidLabel => write!(
+                f,
+                "Labels are 1 to 128 characters of a-z, 0-9, '-', '_', '.' and '/'."
+            ),
+            HierarchyError::DuplicateLabel => write!(f, "The label is already registered."),
+            HierarchyError::UnregisteredLabel => {
+                write!(f, "Keys can only be derived for registered labels.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+fn validate(label: &str) -> Result<(), HierarchyError> {
+    let allowed = |c: u8| c.is_ascii_lowercase() || c.is_ascii_digit() || b"-_./".contains(&c);
+
+    if label.is_empty()
+        || label.len() > MAX_LABEL_LENGTH
+        || !label.bytes().all(allowed)
+        || label.starts_with('/')
+        || label.ends_with('/')
+    {
+        return Err(HierarchyError::InvalidLabel);
+    }
+
+    Ok(())
+}
+
+/// Purpose-labelled subkeys of a root key, each derived with HKDF-SHA256 using its label as the
+/// info string. Labels have to be registered before use and can only be registered once, so two
+/// parts of a program cannot end up sharing a key by picking the same name.
+pub struct KeyHierarchy {
+    hkdf: Hkdf<Sha256>,
+    labels: BTreeSet<String>,
+}
+
+redacted_debug!(KeyHierarchy, "<redacted>");
+
+impl KeyHierarchy {
+    pub fn new(root: &SymmetricKey) -> KeyHierarchy {
+        KeyHierarchy {
+            hkdf: Hkdf::extract(SALT, root.as_bytes()),
+            labels: BTreeSet::new(),
+        }
+    }
+
+    /// Claims a label such as `db-encryption` or `token-signing/v2`.
+    pub fn register(&mut self, label: &str) -> Result<(), HierarchyError> {
+        validate(label)?;
+
+        if !self.labels.insert(label.to_string()) {
+            return Err(HierarchyError::DuplicateLabel);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_registered(&self, label: &str) -> bool {
+        self.labels.contains(label)
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.labels.iter().map(String::as_str)
+    }
+
+    pub fn derive(&self, label: &str) -> Result<SymmetricKey, HierarchyError> {
+        if !self.labels.contains(label) {
+            return Err(HierarchyError::UnregisteredLabel);
+        }
+
+        let mut key = self.hkdf.expand(label.as_bytes(), 32).unwrap();
+        let subkey = SymmetricKey::new(&key).unwrap();
+        key.zeroize();
+
+        Ok(subkey)
+    }
+}
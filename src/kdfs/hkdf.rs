@@ -0,0 +1,80 @@
+use crate::hashes::Hash;
+use crate::kdfs::InvalidLength;
+use crate::macs::hmac::{hmac, Hmac};
+use crate::utils::redacted_debug;
+use core::marker::PhantomData;
+use zeroize::Zeroize;
+
+pub struct Hkdf<H: Hash> {
+    prk: Vec<u8>,
+    hash: PhantomData<H>,
+}
+
+redacted_debug!(Hkdf<H: Hash>, "<redacted>");
+
+impl<H: Hash> Hkdf<H> {
+    pub fn extract(salt: &[u8], ikm: &[u8]) -> Hkdf<H> {
+        let salt = if salt.is_empty() {
+            vec![0u8; H::OUTPUT_SIZE]
+        } else {
+            salt.to_vec()
+        };
+
+        Hkdf {
+            prk: hmac::<H>(&salt, ikm),
+            hash: PhantomData,
+        }
+    }
+
+    pub fn from_prk(prk: &[u8]) -> Hkdf<H> {
+        Hkdf {
+            prk: prk.to_vec(),
+            hash: PhantomData,
+        }
+    }
+
+    pub fn prk(&self) -> &[u8] {
+        &self.prk
+    }
+
+    pub fn expand(&self, info: &[u8], length: usize) -> Result<Vec<u8>, InvalidLength> {
+        if length > 255 * H::OUTPUT_SIZE {
+            return Err(InvalidLength);
+        }
+
+        let mut output = Vec::with_capacity(length);
+        let mut previous: Vec<u8> = Vec::new();
+
+        for counter in 1..=length.div_ceil(H::OUTPUT_SIZE) {
+            let mut mac = Hmac::<H>::new(&self.prk);
+            mac.update(&previous);
+            mac.update(info);
+            mac.update(&[counter as u8]);
+
+            previous.zeroize();
+            previous = mac.tag();
+
+            let take = (length - output.len()).min(H::OUTPUT_SIZE);
+            output.extend_from_slice(&previous[..take]);
+        }
+
+        previous.zeroize();
+
+        Ok(output)
+    }
+}
+
+impl<H: Hash> Drop for Hkdf<H> {
+    fn drop(&mut self) {
+        self.prk.zeroize();
+    }
+}
+
+pub fn hkdf<H: Hash>(
+    salt: &[u8],
+    ikm: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, InvalidLength> {
+    Hkdf::<H>::extract(salt, ikm).expand(info, length)
+}
@@ -0,0 +1,43 @@
+// PBKDF2 with HMAC (RFC 8018). It is only as slow as its iteration count and needs no memory, so
+// use Argon2 for new password hashes; this is here to read formats that already use it.
+
+use crate::hashes::Hash;
+use crate::macs::hmac::Hmac;
+use zeroize::Zeroize;
+
+pub fn pbkdf2<H: Hash>(password: &[u8], salt: &[u8], iterations: u32, length: usize) -> Vec<u8> {
+    assert!(iterations > 0);
+
+    let prf = Hmac::<H>::new(password);
+    let mut output = Vec::with_capacity(length.next_multiple_of(H::OUTPUT_SIZE));
+
+    for block in 1..=length.div_ceil(H::OUTPUT_SIZE) {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&(block as u32).to_be_bytes());
+
+        let mut u = mac.tag();
+        let mut t = u.clone();
+
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(&u);
+
+            let next = mac.tag();
+            u.zeroize();
+            u = next;
+
+            for (t, u) in t.iter_mut().zip(&u) {
+                *t ^= u;
+            }
+        }
+
+        output.extend_from_slice(&t);
+        u.zeroize();
+        t.zeroize();
+    }
+
+    output.truncate(length);
+
+    output
+}
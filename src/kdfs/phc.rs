@@ -0,0 +1,185 @@
+use crate::encoding::base64;
+use crate::kdfs::argon2::{Argon2, Argon2Error, Variant, VERSION};
+use crate::utils::{const_time_eq, random_bytes};
+
+pub const SALT_LENGTH: usize = 16;
+pub const HASH_LENGTH: usize = 32;
+
+/// The most memory a stored hash may ask Argon2 for, in KiB: that of `Argon2::SENSITIVE`.
+/// Anything more is rejected by `PasswordHash::parse`, so a crafted or corrupted hash cannot
+/// exhaust memory when verified.
+pub const MAX_MEMORY_COST: u32 = 1024 * 1024;
+
+/// The most lanes a stored hash may ask for. Anything more is rejected by `PasswordHash::parse`
+/// too.
+pub const MAX_PARALLELISM: u32 = 255;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhcError {
+    Malformed,
+    UnsupportedAlgorithm,
+    UnsupportedVersion,
+    InvalidParameters(Argon2Error),
+}
+
+impl std::fmt::Display for PhcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhcError::Malformed => write!(f, "The string is not a valid PHC hash."),
+            PhcError::UnsupportedAlgorithm => write!(f, "Only Argon2 PHC hashes are supported."),
+            PhcError::UnsupportedVersion => write!(f, "Only Argon2 version 19 is supported."),
+            PhcError::InvalidParameters(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PhcError {}
+
+fn variant(name: &str) -> Result<Variant, PhcError> {
+    [Variant::Argon2d, Variant::Argon2i, Variant::Argon2id]
+        .into_iter()
+        .find(|variant| variant.name() == name)
+        .ok_or(PhcError::UnsupportedAlgorithm)
+}
+
+// a decimal with no sign or leading zeros, as the PHC format requires
+fn number(field: &str, name: &str) -> Result<u32, PhcError> {
+    let value = field
+        .strip_prefix(name)
+        .and_then(|field| field.strip_prefix('='))
+        .ok_or(PhcError::Malformed)?;
+
+    if value.is_empty()
+        || !value.bytes().all(|c| c.is_ascii_digit())
+        || (value.len() > 1 && value.starts_with('0'))
+    {
+        return Err(PhcError::Malformed);
+    }
+
+    value.parse().map_err(|_| PhcError::Malformed)
+}
+
+/// An Argon2 hash in the PHC string format,
+/// `$argon2id$v=19$m=<KiB>,t=<passes>,p=<lanes>$<salt>$<hash>`, with the salt and hash in
+/// unpadded base64.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHash {
+    params: Argon2,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl PasswordHash {
+    /// Hashes `password` under a random salt.
+    pub fn generate(params: Argon2, password: &[u8]) -> Result<PasswordHash, Argon2Error> {
+        let salt: [u8; SALT_LENGTH] = random_bytes();
+        let hash = params.hash(password, &salt, HASH_LENGTH)?;
+
+        Ok(PasswordHash {
+            params,
+            salt: salt.to_vec(),
+            hash,
+        })
+    }
+
+    pub fn parse(phc: &str) -> Result<PasswordHash, PhcError> {
+        let fields: Vec<&str> = phc.split('$').collect();
+
+        let ["", algorithm, version, params, salt, hash] = fields[..] else {
+            return Err(PhcError::Malformed);
+        };
+
+        let variant = variant(algorithm)?;
+
+        if number(version, "v")? != VERSION {
+            return Err(PhcError::UnsupportedVersion);
+        }
+
+        let [m, t, p] = params.split(',').collect::<Vec<_>>()[..] else {
+            return Err(PhcError::Malformed);
+        };
+
+        let (memory_cost, time_cost, parallelism) =
+            (number(m, "m")?, number(t, "t")?, number(p, "p")?);
+
+        if memory_cost > MAX_MEMORY_COST {
+            return Err(PhcError::InvalidParameters(Argon2Error::InvalidMemoryCost));
+        }
+
+        if parallelism > MAX_PARALLELISM {
+            return Err(PhcError::InvalidParameters(Argon2Error::InvalidParallelism));
+        }
+
+        let params = Argon2::new(variant, memory_cost, time_cost, parallelism)
+            .map_err(PhcError::InvalidParameters)?;
+
+        let salt = base64::decode_unpadded(salt).map_err(|_| PhcError::Malformed)?;
+        let hash = base64::decode_unpadded(hash).map_err(|_| PhcError::Malformed)?;
+
+        if salt.len() < 8 {
+            return Err(PhcError::InvalidParameters(Argon2Error::InvalidSaltLength));
+        }
+
+        if hash.len() < 4 {
+            return Err(PhcError::InvalidParameters(
+                Argon2Error::InvalidOutputLength,
+            ));
+        }
+
+        Ok(PasswordHash { params, salt, hash })
+    }
+
+    pub fn params(&self) -> Argon2 {
+        self.params
+    }
+
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    pub fn hash(&self) -> &[u8] {
+        &self.hash
+    }
+
+    pub fn verify(&self, password: &[u8]) -> bool {
+        match self.params.hash(password, &self.salt, self.hash.len()) {
+            Ok(hash) => const_time_eq(&hash, &self.hash),
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the hash was made with different parameters than `policy`, and so should be
+    /// replaced the next time the password is available, as libsodium's
+    /// `crypto_pwhash_str_needs_rehash`.
+    pub fn needs_rehash(&self, policy: &Argon2) -> bool {
+        self.params.variant() != policy.variant()
+            || self.params.memory_cost() != policy.memory_cost()
+            || self.params.time_cost() != policy.time_cost()
+            || self.params.parallelism() != policy.parallelism()
+    }
+}
+
+impl std::fmt::Display for PasswordHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "${}$v={}$m={},t={},p={}${}${}",
+            self.params.variant().name(),
+            VERSION,
+            self.params.memory_cost(),
+            self.params.time_cost(),
+            self.params.parallelism(),
+            base64::encode_unpadded(&self.salt),
+            base64::encode_unpadded(&self.hash)
+        )
+    }
+}
+
+/// `PasswordHash::needs_rehash` on a stored string; anything that does not parse needs
+/// rehashing too.
+pub fn needs_rehash(phc: &str, policy: &Argon2) -> bool {
+    match PasswordHash::parse(phc) {
+        Ok(hash) => hash.needs_rehash(policy),
+        Err(_) => true,
+    }
+}
@@ -0,0 +1,95 @@
+// A forward-secure chain of keys for encrypting logs and other append-only records. Each step
+// splits the chain key into a record key and the next chain key with HMAC-SHA256, then wipes the
+// old chain key, so the current state reveals nothing about the keys already handed out: records
+// written before a device is seized stay unreadable. Whoever keeps the initial seed, such as an
+// auditor, can still derive every key.
+
+use crate::aeads::SymmetricKey;
+use crate::errors::Error;
+use crate::hashes::sha256::Sha256;
+use crate::macs::hmac::hmac;
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct KeyRatchet {
+    chain_key: [u8; 32],
+    index: u64,
+}
+
+redacted_debug!(KeyRatchet, "<32 bytes, redacted>");
+
+impl KeyRatchet {
+    pub const SEED_SIZE: usize = 32;
+    pub const STATE_SIZE: usize = 8 + 32;
+
+    pub fn new(seed: &[u8]) -> KeyRatchet {
+        assert_eq!(seed.len(), Self::SEED_SIZE);
+
+        KeyRatchet {
+            chain_key: seed.try_into().unwrap(),
+            index: 0,
+        }
+    }
+
+    /// The index of the key `next_key` returns next.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    fn step(&mut self) -> SymmetricKey {
+        let mut record_key = hmac::<Sha256>(&self.chain_key, &[1]);
+        let mut next = hmac::<Sha256>(&self.chain_key, &[2]);
+
+        let key = SymmetricKey::new(&record_key).unwrap();
+        self.chain_key.copy_from_slice(&next);
+        self.index = self.index.checked_add(1).expect("key ratchet exhausted");
+
+        record_key.zeroize();
+        next.zeroize();
+
+        key
+    }
+
+    /// Returns the key for the current index and moves past it for good.
+    pub fn next_key(&mut self) -> (u64, SymmetricKey) {
+        let index = self.index;
+
+        (index, self.step())
+    }
+
+    /// Moves forward to `index` and returns its key, wiping every key before it on the way. Keys
+    /// already passed are gone, so this is `None` for an index below the current one.
+    pub fn key_at(&mut self, index: u64) -> Option<SymmetricKey> {
+        if index < self.index {
+            return None;
+        }
+
+        while self.index < index {
+            self.step();
+        }
+
+        Some(self.step())
+    }
+
+    /// `index || chain key`, for persisting the ratchet between runs. Replace the stored state
+    /// every time it advances, or the old state can derive the keys handed out since.
+    pub fn to_bytes(&self) -> [u8; Self::STATE_SIZE] {
+        let mut state = [0u8; Self::STATE_SIZE];
+        state[..8].copy_from_slice(&self.index.to_be_bytes());
+        state[8..].copy_from_slice(&self.chain_key);
+
+        state
+    }
+
+    pub fn from_bytes(state: &[u8]) -> Result<KeyRatchet, Error> {
+        if state.len() != Self::STATE_SIZE {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(KeyRatchet {
+            chain_key: state[8..].try_into().unwrap(),
+            index: u64::from_be_bytes(state[..8].try_into().unwrap()),
+        })
+    }
+}
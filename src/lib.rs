@@ -1,19 +1,62 @@
+// Process-wide state. Everything else is held in values the caller owns, so several embedders
+// in one process (plugins, or interpreters each loading the library) only share the following:
+//
+// - `backend`: the backends for ChaCha20, AES, AEGIS-256 and SHA-256 are picked once, on first
+//   use, from the CPU and `RAYCRYPT_BACKEND`. Every embedder sees the same choice, and later
+//   changes to the variable are ignored.
+// - `audit`: the sink can be set once per process. It receives the key uses of every embedder,
+//   and a second `set_sink` fails with `SinkAlreadySet`.
+// - `rng`: `insecure_override` is thread-local, so it only affects the thread that installed
+//   it, whichever embedder runs there, until its guard is dropped.
+// - `parallel`: rayon pools are built on first use for each thread count and kept until exit.
+// - Read-only tables computed on first use (Poseidon constants, range proof generators), which
+//   are the same for everyone.
+
 pub mod aeads;
+pub mod audit;
+pub mod backend;
+pub mod cancel;
+pub mod cbor;
+pub mod chunking;
 pub mod ciphers;
 pub mod ecc;
+pub(crate) mod encoding;
 pub mod errors;
+pub(crate) mod gf256;
+pub mod hashes;
+pub mod hazmat;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod kdfs;
 pub mod macs;
+pub mod merkle;
+pub mod nacl;
+pub mod openpgp;
+pub mod openssl;
+pub(crate) mod parallel;
+#[cfg(all(feature = "pkcs11", unix))]
+pub mod pkcs11;
+pub mod pre;
+pub mod protocols;
+pub mod rng;
+pub mod secret;
+pub mod shamir;
+pub mod signed_log;
 pub(crate) mod utils;
+pub mod x509;
 
+pub use aeads::verify_roundtrip;
+pub use backend::{backend_info, features};
 pub use ecc::x25519::{PrivateKey, PublicKey};
+pub use errors::Error;
 pub use getrandom::getrandom;
 
 pub fn encrypt(key: Vec<u8>, msg: &[u8]) -> Vec<u8> {
     let mut nonce = [0u8; 32];
     let mut ad = [0u8; 32];
 
-    let _ = getrandom(&mut nonce);
-    let _ = getrandom(&mut ad);
+    rng::fill(&mut nonce);
+    rng::fill(&mut ad);
 
     let mut output = aeads::aegis256::encrypt::<16>(&key, msg, &nonce, &ad);
     output.append(&mut nonce.to_vec());
@@ -23,6 +66,11 @@ pub fn encrypt(key: Vec<u8>, msg: &[u8]) -> Vec<u8> {
 }
 
 pub fn decrypt(key: Vec<u8>, msg: &[u8]) -> Result<Vec<u8>, errors::InvalidMac> {
+    // an empty message encrypts to the tag, nonce and associated data alone
+    if msg.len() < 16 + 64 {
+        return Err(errors::InvalidMac);
+    }
+
     let nonce = &msg[msg.len() - 64..msg.len() - 32];
     let ad = &msg[msg.len() - 32..];
     let m = &msg[..msg.len() - 64];
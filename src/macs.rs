@@ -1 +1,6 @@
+pub mod auth;
+pub mod ghash;
+pub mod hmac;
+pub mod keyed;
 pub mod poly1305;
+pub mod polyval;
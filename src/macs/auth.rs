@@ -0,0 +1,25 @@
+use crate::errors::InvalidMac;
+use crate::hashes::sha512::Sha512;
+use crate::macs::hmac::hmac;
+use crate::utils::const_time_eq;
+
+pub const KEY_LENGTH: usize = 32;
+pub const TAG_LENGTH: usize = 32;
+
+/// libsodium's `crypto_auth`: HMAC-SHA-512 truncated to 256 bits, arguments in libsodium's
+/// order.
+pub fn crypto_auth(msg: &[u8], key: &[u8; KEY_LENGTH]) -> [u8; TAG_LENGTH] {
+    hmac::<Sha512>(key, msg)[..TAG_LENGTH].try_into().unwrap()
+}
+
+pub fn crypto_auth_verify(
+    tag: &[u8],
+    msg: &[u8],
+    key: &[u8; KEY_LENGTH],
+) -> Result<(), InvalidMac> {
+    if !const_time_eq(&crypto_auth(msg, key), tag) {
+        return Err(InvalidMac);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use crate::backend::{self, Backend};
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const KEY_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+
+// carry-less 32x32 multiply with integer multiplies, keeping a hole every four bits so
+// carries never reach the next live bit
+fn clmul32(x: u32, y: u32) -> u64 {
+    const MASKS: [u64; 4] = [0x1111_1111, 0x2222_2222, 0x4444_4444, 0x8888_8888];
+
+    let [x0, x1, x2, x3] = MASKS.map(|mask| x as u64 & mask);
+    let [y0, y1, y2, y3] = MASKS.map(|mask| y as u64 & mask);
+
+    let z0 = (x0 * y0) ^ (x1 * y3) ^ (x2 * y2) ^ (x3 * y1);
+    let z1 = (x0 * y1) ^ (x1 * y0) ^ (x2 * y3) ^ (x3 * y2);
+    let z2 = (x0 * y2) ^ (x1 * y1) ^ (x2 * y0) ^ (x3 * y3);
+    let z3 = (x0 * y3) ^ (x1 * y2) ^ (x2 * y1) ^ (x3 * y0);
+
+    (z0 & 0x1111_1111_1111_1111)
+        | (z1 & 0x2222_2222_2222_2222)
+        | (z2 & 0x4444_4444_4444_4444)
+        | (z3 & 0x8888_8888_8888_8888)
+}
+
+fn clmul64_soft(x: u64, y: u64) -> u128 {
+    let (x0, x1) = (x as u32, (x >> 32) as u32);
+    let (y0, y1) = (y as u32, (y >> 32) as u32);
+
+    let lo = clmul32(x0, y0);
+    let hi = clmul32(x1, y1);
+    let mid = clmul32(x0 ^ x1, y0 ^ y1) ^ lo ^ hi;
+
+    lo as u128 ^ ((mid as u128) << 32) ^ ((hi as u128) << 64)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq")]
+unsafe fn clmul64_hardware(x: u64, y: u64) -> u128 {
+    use core::arch::x86_64::*;
+
+    let product = _mm_clmulepi64_si128(_mm_cvtsi64_si128(x as i64), _mm_cvtsi64_si128(y as i64), 0);
+
+    let mut output = [0u8; 16];
+    _mm_storeu_si128(output.as_mut_ptr() as *mut __m128i, product);
+
+    u128::from_le_bytes(output)
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "aes")]
+unsafe fn clmul64_hardware(x: u64, y: u64) -> u128 {
+    core::arch::aarch64::vmull_p64(x, y)
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+unsafe fn clmul64_hardware(x: u64, y: u64) -> u128 {
+    clmul64_soft(x, y)
+}
+
+// field elements are kept bit-reflected, so bit i is the coefficient of x^i
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct GHash {
+    h: u128,
+    y: u128,
+    #[zeroize(skip)]
+    hardware: bool,
+}
+
+redacted_debug!(GHash, "<redacted>");
+
+impl GHash {
+    pub fn new(h: &[u8; 16]) -> GHash {
+        GHash::with_hardware(h, backend::hardware_aes())
+    }
+
+    /// `Backend::Scalar` selects the constant-time software multiply; any other backend uses
+    /// carry-less multiply instructions when the CPU has them.
+    pub fn with_backend(h: &[u8; 16], backend: Backend) -> GHash {
+        GHash::with_hardware(
+            h,
+            backend != Backend::Scalar && crate::ciphers::aes::hardware_supported(),
+        )
+    }
+
+    pub(crate) fn with_hardware(h: &[u8; 16], hardware: bool) -> GHash {
+        GHash::from_element(u128::from_be_bytes(*h).reverse_bits(), hardware)
+    }
+
+    fn clmul(&self, x: u64, y: u64) -> u128 {
+        if self.hardware {
+            unsafe { clmul64_hardware(x, y) }
+        } else {
+            clmul64_soft(x, y)
+        }
+    }
+
+    fn multiply(&self, x: u128) -> u128 {
+        let (x0, x1) = (x as u64, (x >> 64) as u64);
+        let (h0, h1) = (self.h as u64, (self.h >> 64) as u64);
+
+        let lo = self.clmul(x0, h0);
+        let hi = self.clmul(x1, h1);
+        let mid = self.clmul(x0 ^ x1, h0 ^ h1) ^ lo ^ hi;
+
+        let lo = lo ^ (mid << 64);
+        let hi = hi ^ (mid >> 64);
+
+        // reduce modulo x^128 + x^7 + x^2 + x + 1, folding the overflow twice
+        let overflow = (hi >> 127) ^ (hi >> 126) ^ (hi >> 121);
+        let folded = overflow ^ (overflow << 1) ^ (overflow << 2) ^ (overflow << 7);
+
+        lo ^ hi ^ (hi << 1) ^ (hi << 2) ^ (hi << 7) ^ folded
+    }
+
+    // POLYVAL shares this field arithmetic, handing over its own element conversions
+    pub(crate) fn from_element(h: u128, hardware: bool) -> GHash {
+        GHash { h, y: 0, hardware }
+    }
+
+    pub(crate) fn absorb(&mut self, x: u128) {
+        self.y = self.multiply(self.y ^ x);
+    }
+
+    pub(crate) fn element(&self) -> u128 {
+        self.y
+    }
+
+    /// Absorbs `data`, zero-padding a trailing partial block.
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+
+            self.absorb(u128::from_be_bytes(block).reverse_bits());
+        }
+    }
+
+    pub fn finish(self) -> [u8; 16] {
+        self.y.reverse_bits().to_be_bytes()
+    }
+}
+
+pub fn ghash(h: &[u8; 16], data: &[u8]) -> [u8; 16] {
+    let mut ghash = GHash::new(h);
+    ghash.update(data);
+    ghash.finish()
+}
@@ -0,0 +1,65 @@
+use crate::hashes::Hash;
+use crate::utils::const_time_eq;
+use crate::utils::redacted_debug;
+use zeroize::Zeroize;
+
+#[derive(Clone)]
+pub struct Hmac<H: Hash> {
+    inner: H,
+    outer: H,
+}
+
+redacted_debug!(Hmac<H: Hash>, "<redacted>");
+
+impl<H: Hash> Hmac<H> {
+    pub fn new(key: &[u8]) -> Hmac<H> {
+        let mut block = vec![0u8; H::BLOCK_SIZE];
+
+        if key.len() > H::BLOCK_SIZE {
+            let mut hasher = H::default();
+            hasher.update(key);
+            block[..H::OUTPUT_SIZE].copy_from_slice(&hasher.finish());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = H::default();
+        let mut outer = H::default();
+
+        for byte in block.iter_mut() {
+            *byte ^= 0x36;
+        }
+
+        inner.update(&block);
+
+        for byte in block.iter_mut() {
+            *byte ^= 0x36 ^ 0x5c;
+        }
+
+        outer.update(&block);
+        block.zeroize();
+
+        Hmac { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn tag(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finish());
+        outer.finish()
+    }
+
+    pub fn verify(self, other: &[u8]) -> bool {
+        let tag = self.tag();
+        const_time_eq(&tag, other)
+    }
+}
+
+pub fn hmac<H: Hash>(key: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<H>::new(key);
+    mac.update(msg);
+    mac.tag()
+}
@@ -0,0 +1,79 @@
+// Keyed hashing for code that would otherwise compute SHA-256(key || msg). That construction is
+// broken by length extension: from one tag, anyone can compute the tag of the message with more
+// bytes appended. `prefix_mac` takes the same inputs but uses HMAC-SHA-256 or keyed BLAKE2b,
+// neither of which extends. Prefix tags already stored or sent by older systems can still be
+// checked with `verify_legacy_prefix_mac`, but nothing here creates them.
+
+use crate::errors::InvalidMac;
+use crate::hashes::blake2b::{self, Blake2b};
+use crate::hashes::sha256::Sha256;
+use crate::macs::hmac::hmac;
+use crate::utils::const_time_eq;
+use zeroize::Zeroize;
+
+pub const TAG_LENGTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyedHash {
+    #[default]
+    HmacSha256,
+    /// BLAKE2b-256 in keyed mode. Keys over 64 bytes are hashed first, as HMAC does.
+    Blake2b,
+}
+
+fn blake2b_mac(key: &[u8], msg: &[u8]) -> [u8; TAG_LENGTH] {
+    let mut hashed_key = if key.len() > 64 {
+        blake2b::hash(key, 64)
+    } else {
+        key.to_vec()
+    };
+
+    let mut hasher = Blake2b::new_keyed(&hashed_key, TAG_LENGTH);
+    hasher.update(msg);
+    hashed_key.zeroize();
+
+    hasher.digest().try_into().unwrap()
+}
+
+/// A tag over `msg` under a key of any length, with HMAC-SHA-256.
+pub fn prefix_mac(key: &[u8], msg: &[u8]) -> [u8; TAG_LENGTH] {
+    prefix_mac_with(KeyedHash::default(), key, msg)
+}
+
+pub fn prefix_mac_with(algorithm: KeyedHash, key: &[u8], msg: &[u8]) -> [u8; TAG_LENGTH] {
+    match algorithm {
+        KeyedHash::HmacSha256 => hmac::<Sha256>(key, msg).try_into().unwrap(),
+        KeyedHash::Blake2b => blake2b_mac(key, msg),
+    }
+}
+
+pub fn verify_prefix_mac(key: &[u8], msg: &[u8], tag: &[u8]) -> Result<(), InvalidMac> {
+    verify_prefix_mac_with(KeyedHash::default(), key, msg, tag)
+}
+
+pub fn verify_prefix_mac_with(
+    algorithm: KeyedHash,
+    key: &[u8],
+    msg: &[u8],
+    tag: &[u8],
+) -> Result<(), InvalidMac> {
+    if !const_time_eq(&prefix_mac_with(algorithm, key, msg), tag) {
+        return Err(InvalidMac);
+    }
+
+    Ok(())
+}
+
+/// Checks a SHA-256(key || msg) tag made by a legacy system, while it is migrated. A valid tag
+/// proves less than it seems: whoever saw the tag of a prefix of `msg` could have forged it.
+pub fn verify_legacy_prefix_mac(key: &[u8], msg: &[u8], tag: &[u8]) -> Result<(), InvalidMac> {
+    let mut hasher = Sha256::new();
+    hasher.update(key);
+    hasher.update(msg);
+
+    if !const_time_eq(&hasher.digest(), tag) {
+        return Err(InvalidMac);
+    }
+
+    Ok(())
+}
@@ -1,6 +1,10 @@
+use crate::ciphers::chacha::{self, ChaCha20, ChaChaError};
 use crate::utils::*;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+pub const KEY_SIZE: usize = 32;
+pub const TAG_SIZE: usize = 16;
+
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct Poly1305 {
     r: [u32; 5],
@@ -10,6 +14,8 @@ pub struct Poly1305 {
     leftover: usize,
 }
 
+redacted_debug!(Poly1305, "<redacted>");
+
 impl Poly1305 {
     fn block(&mut self, m: &[u8], partial: bool) {
         let hibit: u32 = if partial { 0 } else { 1 << 24 };
@@ -185,3 +191,24 @@ impl Poly1305 {
         const_time_eq(&self.tag(), other)
     }
 }
+
+/// The RFC 8439 one-time Poly1305 key: the first 32 bytes of ChaCha20 keystream block 0.
+pub fn poly1305_key_gen(key: &[u8], nonce: &[u8]) -> Result<[u8; 32], ChaChaError> {
+    if key.len() != chacha::KEY_SIZE {
+        return Err(ChaChaError::InvalidKeyLength);
+    }
+
+    if nonce.len() != chacha::NONCE_SIZE {
+        return Err(ChaChaError::InvalidNonceLength);
+    }
+
+    Ok(one_time_key(&ChaCha20::new(key), nonce))
+}
+
+pub(crate) fn one_time_key(chacha: &ChaCha20, nonce: &[u8]) -> [u8; 32] {
+    let mut block = chacha.keystream(nonce, 0);
+    let key = block[..32].try_into().unwrap();
+    block.zeroize();
+
+    key
+}
@@ -0,0 +1,63 @@
+use crate::backend::{self, Backend};
+use crate::macs::ghash::GHash;
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const KEY_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+
+// POLYVAL is GHASH with byte-reversed blocks and the key multiplied by x (RFC 8452, appendix A),
+// so it runs on the GHASH arithmetic with different conversions in and out
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Polyval {
+    ghash: GHash,
+}
+
+redacted_debug!(Polyval, "<redacted>");
+
+fn element(block: &[u8; 16]) -> u128 {
+    u128::from_le_bytes(*block).reverse_bits()
+}
+
+impl Polyval {
+    pub fn new(h: &[u8; 16]) -> Polyval {
+        Polyval::with_hardware(h, backend::hardware_aes())
+    }
+
+    /// `Backend::Scalar` selects the constant-time software multiply, as for `GHash`.
+    pub fn with_backend(h: &[u8; 16], backend: Backend) -> Polyval {
+        Polyval::with_hardware(
+            h,
+            backend != Backend::Scalar && crate::ciphers::aes::hardware_supported(),
+        )
+    }
+
+    pub(crate) fn with_hardware(h: &[u8; 16], hardware: bool) -> Polyval {
+        let h = element(h);
+        let h = (h << 1) ^ ((h >> 127) * 0x87);
+
+        Polyval {
+            ghash: GHash::from_element(h, hardware),
+        }
+    }
+
+    /// Absorbs `data`, zero-padding a trailing partial block.
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+
+            self.ghash.absorb(element(&block));
+        }
+    }
+
+    pub fn finish(self) -> [u8; 16] {
+        self.ghash.element().reverse_bits().to_le_bytes()
+    }
+}
+
+pub fn polyval(h: &[u8; 16], data: &[u8]) -> [u8; 16] {
+    let mut polyval = Polyval::new(h);
+    polyval.update(data);
+    polyval.finish()
+}
@@ -0,0 +1,371 @@
+// Merkle trees as Certificate Transparency builds them (RFC 6962, section 2.1), for checking what
+// a transparency log serves. Leaves hash as SHA-256(0x00 || leaf) and nodes as
+// SHA-256(0x01 || left || right), so a leaf cannot pass for a node, and a tree of n leaves splits
+// at the largest power of two below n. An inclusion proof shows a leaf is in a tree, and a
+// consistency proof shows a tree is an append-only extension of an earlier one.
+//
+// A signed tree head is the log's signature over its size, root and a timestamp, in RFC 6962's
+// TreeHeadSignature layout, here signed with Ed25519.
+
+use crate::ecc::ed25519::{Signature, SigningKey, VerifyingKey};
+use crate::hashes::sha256::{self, Sha256};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleError {
+    Malformed,
+    /// The proof does not connect the hashes it was checked against.
+    InvalidProof,
+    InvalidSignature,
+}
+
+impl std::fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MerkleError::Malformed => write!(f, "The tree head is malformed."),
+            MerkleError::InvalidProof => write!(f, "The Merkle proof does not verify."),
+            MerkleError::InvalidSignature => {
+                write!(f, "The tree head signature does not verify.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {}
+
+pub fn leaf_hash(leaf: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&[0]);
+    hasher.update(leaf);
+
+    hasher.digest()
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&[1]);
+    hasher.update(left);
+    hasher.update(right);
+
+    hasher.digest()
+}
+
+// the largest power of two below n, for n > 1
+fn split(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+fn subtree_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    match leaves.len() {
+        0 => sha256::hash(&[]),
+        1 => leaves[0],
+        n => {
+            let k = split(n);
+            node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+fn hash_leaves<L: AsRef<[u8]>>(leaves: &[L]) -> Vec<[u8; 32]> {
+    leaves.iter().map(|leaf| leaf_hash(leaf.as_ref())).collect()
+}
+
+/// The root of the tree over `leaves`, the SHA-256 of nothing for an empty tree.
+pub fn root<L: AsRef<[u8]>>(leaves: &[L]) -> [u8; 32] {
+    subtree_root(&hash_leaves(leaves))
+}
+
+fn path(index: usize, leaves: &[[u8; 32]], proof: &mut Vec<[u8; 32]>) {
+    if leaves.len() <= 1 {
+        return;
+    }
+
+    let k = split(leaves.len());
+
+    if index < k {
+        path(index, &leaves[..k], proof);
+        proof.push(subtree_root(&leaves[k..]));
+    } else {
+        path(index - k, &leaves[k..], proof);
+        proof.push(subtree_root(&leaves[..k]));
+    }
+}
+
+/// The audit path of the leaf at `index`, from the bottom of the tree up.
+pub fn inclusion_proof<L: AsRef<[u8]>>(leaves: &[L], index: usize) -> Vec<[u8; 32]> {
+    assert!(index < leaves.len());
+
+    let mut proof = Vec::new();
+    path(index, &hash_leaves(leaves), &mut proof);
+
+    proof
+}
+
+// SUBPROOF(m, D[n], b) from RFC 6962
+fn subproof(m: usize, leaves: &[[u8; 32]], complete: bool, proof: &mut Vec<[u8; 32]>) {
+    let n = leaves.len();
+
+    if m == n {
+        if !complete {
+            proof.push(subtree_root(leaves));
+        }
+
+        return;
+    }
+
+    let k = split(n);
+
+    if m <= k {
+        subproof(m, &leaves[..k], complete, proof);
+        proof.push(subtree_root(&leaves[k..]));
+    } else {
+        subproof(m - k, &leaves[k..], false, proof);
+        proof.push(subtree_root(&leaves[..k]));
+    }
+}
+
+/// Proves that the tree over the first `old_size` leaves is a prefix of the tree over all of
+/// them. The proof between equal sizes, or from the empty tree, is empty.
+pub fn consistency_proof<L: AsRef<[u8]>>(leaves: &[L], old_size: usize) -> Vec<[u8; 32]> {
+    assert!(old_size <= leaves.len());
+
+    let mut proof = Vec::new();
+
+    if old_size > 0 {
+        subproof(old_size, &hash_leaves(leaves), true, &mut proof);
+    }
+
+    proof
+}
+
+/// Checks that `leaf_hash` is at `index` in the tree of `size` leaves with the given root
+/// (RFC 9162, section 2.1.3.2).
+pub fn verify_inclusion(
+    leaf_hash: &[u8; 32],
+    index: u64,
+    size: u64,
+    proof: &[[u8; 32]],
+    root: &[u8; 32],
+) -> Result<(), MerkleError> {
+    if index >= size {
+        return Err(MerkleError::InvalidProof);
+    }
+
+    let (mut f, mut s) = (index, size - 1);
+    let mut r = *leaf_hash;
+
+    for p in proof {
+        if s == 0 {
+            return Err(MerkleError::InvalidProof);
+        }
+
+        if f & 1 == 1 || f == s {
+            r = node_hash(p, &r);
+
+            while f & 1 == 0 && f != 0 {
+                f >>= 1;
+                s >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+
+        f >>= 1;
+        s >>= 1;
+    }
+
+    if s != 0 || r != *root {
+        return Err(MerkleError::InvalidProof);
+    }
+
+    Ok(())
+}
+
+/// Checks that the tree of `new_size` leaves with `new_root` extends the one of `old_size`
+/// leaves with `old_root` (RFC 9162, section 2.1.4.2).
+pub fn verify_consistency(
+    old_size: u64,
+    new_size: u64,
+    old_root: &[u8; 32],
+    new_root: &[u8; 32],
+    proof: &[[u8; 32]],
+) -> Result<(), MerkleError> {
+    if old_size > new_size {
+        return Err(MerkleError::InvalidProof);
+    }
+
+    // every tree extends the empty one, and a tree only extends itself
+    if old_size == 0 || old_size == new_size {
+        if !proof.is_empty() || (old_size == new_size && old_root != new_root) {
+            return Err(MerkleError::InvalidProof);
+        }
+
+        return Ok(());
+    }
+
+    // a complete old tree is a node of the new one, which the proof leaves out
+    let mut nodes = proof.iter();
+    let first = if old_size.is_power_of_two() {
+        old_root
+    } else {
+        nodes.next().ok_or(MerkleError::InvalidProof)?
+    };
+
+    let (mut f, mut s) = (old_size - 1, new_size - 1);
+
+    while f & 1 == 1 {
+        f >>= 1;
+        s >>= 1;
+    }
+
+    let (mut fr, mut sr) = (*first, *first);
+
+    for c in nodes {
+        if s == 0 {
+            return Err(MerkleError::InvalidProof);
+        }
+
+        if f & 1 == 1 || f == s {
+            fr = node_hash(c, &fr);
+            sr = node_hash(c, &sr);
+
+            while f & 1 == 0 && f != 0 {
+                f >>= 1;
+                s >>= 1;
+            }
+        } else {
+            sr = node_hash(&sr, c);
+        }
+
+        f >>= 1;
+        s >>= 1;
+    }
+
+    if s != 0 || fr != *old_root || sr != *new_root {
+        return Err(MerkleError::InvalidProof);
+    }
+
+    Ok(())
+}
+
+/// A log's signed statement of its size and root at a point in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedTreeHead {
+    /// Milliseconds since the Unix epoch, as CT uses.
+    timestamp: u64,
+    tree_size: u64,
+    root_hash: [u8; 32],
+    signature: Signature,
+}
+
+impl SignedTreeHead {
+    pub const SIZE: usize = 8 + 8 + 32 + 64;
+
+    // TreeHeadSignature: version v1, signature type tree_hash, timestamp, size and root
+    fn signed_message(timestamp: u64, tree_size: u64, root_hash: &[u8; 32]) -> Vec<u8> {
+        [
+            &[0, 1][..],
+            &timestamp.to_be_bytes(),
+            &tree_size.to_be_bytes(),
+            root_hash,
+        ]
+        .concat()
+    }
+
+    pub fn sign(
+        key: &SigningKey,
+        timestamp: u64,
+        tree_size: u64,
+        root_hash: [u8; 32],
+    ) -> SignedTreeHead {
+        let msg = SignedTreeHead::signed_message(timestamp, tree_size, &root_hash);
+
+        SignedTreeHead {
+            timestamp,
+            tree_size,
+            root_hash,
+            signature: key.sign(&msg),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<SignedTreeHead, MerkleError> {
+        if bytes.len() != SignedTreeHead::SIZE {
+            return Err(MerkleError::Malformed);
+        }
+
+        Ok(SignedTreeHead {
+            timestamp: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+            tree_size: u64::from_be_bytes(bytes[8..16].try_into().unwrap()),
+            root_hash: bytes[16..48].try_into().unwrap(),
+            signature: bytes[48..].try_into().unwrap(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; SignedTreeHead::SIZE] {
+        let mut output = [0u8; SignedTreeHead::SIZE];
+        output[..8].copy_from_slice(&self.timestamp.to_be_bytes());
+        output[8..16].copy_from_slice(&self.tree_size.to_be_bytes());
+        output[16..48].copy_from_slice(&self.root_hash);
+        output[48..].copy_from_slice(&self.signature);
+
+        output
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn tree_size(&self) -> u64 {
+        self.tree_size
+    }
+
+    pub fn root_hash(&self) -> &[u8; 32] {
+        &self.root_hash
+    }
+
+    pub fn verify(&self, public: &VerifyingKey) -> Result<(), MerkleError> {
+        let msg = SignedTreeHead::signed_message(self.timestamp, self.tree_size, &self.root_hash);
+
+        public
+            .verify(&msg, &self.signature)
+            .map_err(|_| MerkleError::InvalidSignature)
+    }
+
+    /// Checks both tree heads' signatures and that this one extends `older`. A log that signs two
+    /// heads failing this has forked its history.
+    pub fn verify_extends(
+        &self,
+        older: &SignedTreeHead,
+        proof: &[[u8; 32]],
+        public: &VerifyingKey,
+    ) -> Result<(), MerkleError> {
+        self.verify(public)?;
+        older.verify(public)?;
+
+        verify_consistency(
+            older.tree_size,
+            self.tree_size,
+            &older.root_hash,
+            &self.root_hash,
+            proof,
+        )
+    }
+
+    /// Checks the signature and that `leaf` is at `index` in this tree.
+    pub fn verify_inclusion(
+        &self,
+        leaf: &[u8],
+        index: u64,
+        proof: &[[u8; 32]],
+        public: &VerifyingKey,
+    ) -> Result<(), MerkleError> {
+        self.verify(public)?;
+
+        verify_inclusion(
+            &leaf_hash(leaf),
+            index,
+            self.tree_size,
+            proof,
+            &self.root_hash,
+        )
+    }
+}
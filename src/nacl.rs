@@ -0,0 +1,356 @@
+// Drop-in shapes for PyNaCl's SecretBox, Box, SealedBox, SigningKey and VerifyKey, producing
+// byte-for-byte the same output as libsodium
+
+use crate::ciphers::salsa::{hsalsa20, XSalsa20};
+use crate::ecc::ed25519;
+use crate::ecc::x25519::{self, PrivateKey, PublicKey};
+use crate::errors::Error;
+use crate::errors::{InvalidMac, InvalidSignature};
+use crate::hashes::blake2b::Blake2b;
+use crate::macs::poly1305::Poly1305;
+use crate::utils::{const_time_eq, random_bytes, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 24;
+pub const MACBYTES: usize = 16;
+pub const SEALBYTES: usize = 32 + MACBYTES;
+pub const SIGNATURE_SIZE: usize = 64;
+
+/// A nonce followed by a ciphertext, as PyNaCl's `EncryptedMessage`. It dereferences to the
+/// combined bytes, which is what PyNaCl code usually stores.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedMessage(Vec<u8>);
+
+impl EncryptedMessage {
+    pub fn nonce(&self) -> &[u8] {
+        &self.0[..NONCE_SIZE]
+    }
+
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.0[NONCE_SIZE..]
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl std::ops::Deref for EncryptedMessage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A signature followed by the message, as PyNaCl's `SignedMessage`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedMessage(Vec<u8>);
+
+impl SignedMessage {
+    pub fn signature(&self) -> &[u8] {
+        &self.0[..SIGNATURE_SIZE]
+    }
+
+    pub fn message(&self) -> &[u8] {
+        &self.0[SIGNATURE_SIZE..]
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl std::ops::Deref for SignedMessage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn random_nonce() -> [u8; NONCE_SIZE] {
+    random_bytes()
+}
+
+// crypto_secretbox_xsalsa20poly1305: the first 32 keystream bytes key Poly1305, the rest
+// encrypt the message, and the tag goes in front
+fn seal(key: &[u8; KEY_SIZE], msg: &[u8], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let mut padded = vec![0u8; 32 + msg.len()];
+    padded[32..].copy_from_slice(msg);
+
+    let mut stream = XSalsa20::new(key).xor(&padded, nonce);
+    padded.zeroize();
+
+    let mut poly1305 = Poly1305::new(stream[..32].try_into().unwrap());
+    poly1305.update_unpadded(&stream[32..]);
+
+    let mut output = poly1305.tag().to_vec();
+    output.extend_from_slice(&stream[32..]);
+    stream.zeroize();
+
+    output
+}
+
+fn open(key: &[u8; KEY_SIZE], ct: &[u8], nonce: &[u8; NONCE_SIZE]) -> Result<Vec<u8>, InvalidMac> {
+    if ct.len() < MACBYTES {
+        return Err(InvalidMac);
+    }
+
+    let (tag, ct) = ct.split_at(MACBYTES);
+    let xsalsa = XSalsa20::new(key);
+
+    let mut poly_key: [u8; 32] = xsalsa.keystream(nonce, 0)[..32].try_into().unwrap();
+    let mut poly1305 = Poly1305::new(poly_key);
+    poly_key.zeroize();
+
+    poly1305.update_unpadded(ct);
+
+    if !poly1305.verify(tag) {
+        return Err(InvalidMac);
+    }
+
+    let mut padded = vec![0u8; 32 + ct.len()];
+    padded[32..].copy_from_slice(ct);
+
+    let mut stream = xsalsa.xor(&padded, nonce);
+    let msg = stream[32..].to_vec();
+    stream.zeroize();
+
+    Ok(msg)
+}
+
+// PyNaCl takes the nonce from the front of the ciphertext when none is given
+fn split_nonce<'a>(
+    ciphertext: &'a [u8],
+    nonce: Option<&[u8; NONCE_SIZE]>,
+) -> Result<([u8; NONCE_SIZE], &'a [u8]), InvalidMac> {
+    match nonce {
+        Some(nonce) => Ok((*nonce, ciphertext)),
+        None if ciphertext.len() >= NONCE_SIZE => {
+            let (nonce, ciphertext) = ciphertext.split_at(NONCE_SIZE);
+            Ok((nonce.try_into().unwrap(), ciphertext))
+        }
+        None => Err(InvalidMac),
+    }
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretBox {
+    key: [u8; KEY_SIZE],
+}
+
+redacted_debug!(SecretBox, "<32 bytes, redacted>");
+
+impl SecretBox {
+    pub fn new(key: &[u8]) -> Result<SecretBox, Error> {
+        Ok(SecretBox {
+            key: key.try_into().map_err(|_| Error::InvalidKey)?,
+        })
+    }
+
+    /// Encrypts under `nonce`, or a random one if it is `None`.
+    pub fn encrypt(&self, plaintext: &[u8], nonce: Option<&[u8; NONCE_SIZE]>) -> EncryptedMessage {
+        let nonce = nonce.copied().unwrap_or_else(random_nonce);
+
+        let mut output = nonce.to_vec();
+        output.extend(seal(&self.key, plaintext, &nonce));
+
+        EncryptedMessage(output)
+    }
+
+    pub fn decrypt(
+        &self,
+        ciphertext: &[u8],
+        nonce: Option<&[u8; NONCE_SIZE]>,
+    ) -> Result<Vec<u8>, InvalidMac> {
+        let (nonce, ciphertext) = split_nonce(ciphertext, nonce)?;
+        open(&self.key, ciphertext, &nonce)
+    }
+}
+
+/// crypto_box between one party's private key and the other's public key.
+#[derive(Clone, Debug)]
+pub struct Box {
+    secret_box: SecretBox,
+}
+
+impl Box {
+    /// Fails when the public key is a low-order point, as libsodium does.
+    pub fn new(private: &PrivateKey, public: &PublicKey) -> Result<Box, Error> {
+        let mut shared = private.exchange(*public);
+
+        if const_time_eq(&shared, &[0u8; 32]) {
+            return Err(Error::InvalidKey);
+        }
+
+        let key = hsalsa20(&shared, &[0u8; 16]);
+        shared.zeroize();
+
+        Ok(Box {
+            secret_box: SecretBox { key },
+        })
+    }
+
+    pub fn shared_key(&self) -> [u8; KEY_SIZE] {
+        self.secret_box.key
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8], nonce: Option<&[u8; NONCE_SIZE]>) -> EncryptedMessage {
+        self.secret_box.encrypt(plaintext, nonce)
+    }
+
+    pub fn decrypt(
+        &self,
+        ciphertext: &[u8],
+        nonce: Option<&[u8; NONCE_SIZE]>,
+    ) -> Result<Vec<u8>, InvalidMac> {
+        self.secret_box.decrypt(ciphertext, nonce)
+    }
+}
+
+/// crypto_box_seal: anonymous encryption to a public key. Only a box built from the private
+/// key can decrypt.
+pub struct SealedBox {
+    public: PublicKey,
+    private: Option<PrivateKey>,
+}
+
+redacted_debug!(SealedBox, "<redacted>");
+
+fn seal_nonce(ephemeral: &PublicKey, recipient: &PublicKey) -> [u8; NONCE_SIZE] {
+    let mut hasher = Blake2b::new(NONCE_SIZE);
+    hasher.update(ephemeral);
+    hasher.update(recipient);
+
+    hasher.digest().try_into().unwrap()
+}
+
+impl SealedBox {
+    pub fn new(public: &PublicKey) -> SealedBox {
+        SealedBox {
+            public: *public,
+            private: None,
+        }
+    }
+
+    pub fn from_private_key(private: &PrivateKey) -> SealedBox {
+        SealedBox {
+            public: private.public_key(),
+            private: Some(PrivateKey::new(&private.to_bytes()).unwrap()),
+        }
+    }
+
+    /// Fails only when the recipient's public key is a low-order point.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let ephemeral = x25519::keygen(None);
+        let ephemeral_public = ephemeral.public_key();
+        let boxed = Box::new(&ephemeral, &self.public)?;
+
+        let mut output = ephemeral_public.to_vec();
+        output.extend(seal(
+            &boxed.shared_key(),
+            plaintext,
+            &seal_nonce(&ephemeral_public, &self.public),
+        ));
+
+        Ok(output)
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, InvalidMac> {
+        let private = self.private.as_ref().ok_or(InvalidMac)?;
+
+        if ciphertext.len() < SEALBYTES {
+            return Err(InvalidMac);
+        }
+
+        let ephemeral: PublicKey = ciphertext[..32].try_into().unwrap();
+        let boxed = Box::new(private, &ephemeral).map_err(|_| InvalidMac)?;
+
+        open(
+            &boxed.shared_key(),
+            &ciphertext[32..],
+            &seal_nonce(&ephemeral, &self.public),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyKey {
+    key: ed25519::VerifyingKey,
+}
+
+impl VerifyKey {
+    pub fn new(key: &[u8]) -> Result<VerifyKey, Error> {
+        Ok(VerifyKey {
+            key: ed25519::VerifyingKey::new(key)?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.key.to_bytes()
+    }
+
+    /// Checks `smessage` as signature followed by message, or as the bare message when the
+    /// signature is passed separately, and returns the message.
+    pub fn verify(
+        &self,
+        smessage: &[u8],
+        signature: Option<&[u8]>,
+    ) -> Result<Vec<u8>, InvalidSignature> {
+        let (signature, message) = match signature {
+            Some(signature) => (signature, smessage),
+            None if smessage.len() >= SIGNATURE_SIZE => smessage.split_at(SIGNATURE_SIZE),
+            None => return Err(InvalidSignature),
+        };
+
+        self.key.verify(message, signature)?;
+
+        Ok(message.to_vec())
+    }
+
+    pub fn to_curve25519_public_key(&self) -> PublicKey {
+        self.key.to_x25519()
+    }
+}
+
+#[derive(Debug)]
+pub struct SigningKey {
+    key: ed25519::SigningKey,
+}
+
+impl SigningKey {
+    pub fn new(seed: &[u8]) -> Result<SigningKey, Error> {
+        Ok(SigningKey {
+            key: ed25519::SigningKey::new(seed)?,
+        })
+    }
+
+    pub fn generate() -> SigningKey {
+        SigningKey {
+            key: ed25519::keygen(None),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.key.to_bytes()
+    }
+
+    pub fn verify_key(&self) -> VerifyKey {
+        VerifyKey {
+            key: self.key.verifying_key(),
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> SignedMessage {
+        let mut output = self.key.sign(message).to_vec();
+        output.extend_from_slice(message);
+
+        SignedMessage(output)
+    }
+
+    pub fn to_curve25519_private_key(&self) -> PrivateKey {
+        self.key.to_x25519()
+    }
+}
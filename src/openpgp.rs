@@ -0,0 +1,1081 @@
+// A subset of OpenPGP v6 (RFC 9580) for exchanging encrypted files. Certificates are an Ed25519
+// primary key with an X25519 encryption subkey, and messages are one literal data packet inside a
+// version 2 SEIPD packet (AES with OCB or GCM) addressed to X25519 recipients. Compression, signed
+// messages, passwords and v4 keys are not supported, and certificate expiry is left to the caller.
+
+use crate::aeads::{AesGcm, AesOcb};
+use crate::ciphers::aes::keywrap;
+use crate::ecc::ed25519::{self, SigningKey, VerifyingKey};
+use crate::ecc::x25519::{self, PrivateKey, PublicKey};
+use crate::encoding::reader::Reader;
+use crate::encoding::{base64, InvalidEncoding};
+use crate::hashes::sha256::{self, Sha256};
+use crate::hashes::sha512::Sha512;
+use crate::hashes::Hash;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{random_bytes, redacted_debug};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zeroize::Zeroize;
+
+const PKESK: u8 = 1;
+const SIGNATURE: u8 = 2;
+const SECRET_KEY: u8 = 5;
+const PUBLIC_KEY: u8 = 6;
+const SECRET_SUBKEY: u8 = 7;
+const MARKER: u8 = 10;
+const LITERAL_DATA: u8 = 11;
+const PUBLIC_SUBKEY: u8 = 14;
+const SEIPD: u8 = 18;
+const PADDING: u8 = 21;
+
+const X25519: u8 = 25;
+const ED25519: u8 = 27;
+const AES_128: u8 = 7;
+const AES_256: u8 = 9;
+const OCB: u8 = 2;
+const GCM: u8 = 3;
+const SHA256: u8 = 8;
+const SHA512: u8 = 10;
+
+const DIRECT_KEY: u8 = 0x1f;
+const SUBKEY_BINDING: u8 = 0x18;
+const KEY_REVOCATION: u8 = 0x20;
+const SUBKEY_REVOCATION: u8 = 0x28;
+
+const CREATION_TIME: u8 = 2;
+const PREFERRED_SYMMETRIC: u8 = 11;
+const PREFERRED_HASH: u8 = 21;
+const KEY_FLAGS: u8 = 27;
+const FEATURES: u8 = 30;
+const ISSUER_FINGERPRINT: u8 = 33;
+const PREFERRED_AEAD: u8 = 39;
+const CRITICAL: u8 = 0x80;
+
+// subpackets that may be marked critical; a signature with any other critical subpacket is ignored
+const KNOWN_SUBPACKETS: [u8; 12] = [2, 9, 11, 16, 21, 22, 23, 25, 27, 30, 33, 39];
+
+const CERTIFY: u8 = 0x01;
+const SIGN: u8 = 0x02;
+const ENCRYPT: u8 = 0x04 | 0x08;
+
+// chunks of 2^(10 + 6) = 64 KiB
+const CHUNK_SIZE: u8 = 10;
+const MAX_CHUNK_SIZE: u8 = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenPgpError {
+    Malformed,
+    Unsupported,
+    NoEncryptionKey,
+    Revoked,
+    NoMatchingKey,
+    InvalidMac,
+}
+
+impl std::fmt::Display for OpenPgpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenPgpError::Malformed => write!(f, "The OpenPGP data is malformed."),
+            OpenPgpError::Unsupported => {
+                write!(f, "The OpenPGP data uses an unsupported feature.")
+            }
+            OpenPgpError::NoEncryptionKey => {
+                write!(f, "The certificate has no valid encryption subkey.")
+            }
+            OpenPgpError::Revoked => write!(f, "The certificate has been revoked."),
+            OpenPgpError::NoMatchingKey => write!(f, "The message is not encrypted to this key."),
+            OpenPgpError::InvalidMac => write!(f, "The message failed to authenticate."),
+        }
+    }
+}
+
+impl std::error::Error for OpenPgpError {}
+
+impl From<InvalidEncoding> for OpenPgpError {
+    fn from(_: InvalidEncoding) -> Self {
+        OpenPgpError::Malformed
+    }
+}
+
+fn timestamp(time: SystemTime) -> u32 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs().min(u32::MAX.into()) as u32)
+}
+
+// the one, two and five byte lengths shared by packets and signature subpackets
+fn read_length(reader: &mut Reader, first: u8) -> Result<usize, OpenPgpError> {
+    match first {
+        0..=191 => Ok(first.into()),
+        192..=254 => Ok((usize::from(first - 192) << 8) + usize::from(reader.byte()?) + 192),
+        255 => Ok(reader.u32()? as usize),
+    }
+}
+
+fn write_length(output: &mut Vec<u8>, length: usize) {
+    if length < 192 {
+        output.push(length as u8);
+    } else if length < 8384 {
+        let length = length - 192;
+        output.extend_from_slice(&[(length >> 8) as u8 + 192, length as u8]);
+    } else {
+        output.push(255);
+        output.extend_from_slice(&u32::try_from(length).unwrap().to_be_bytes());
+    }
+}
+
+fn write_packet(output: &mut Vec<u8>, tag: u8, body: &[u8]) {
+    output.push(0xc0 | tag);
+    write_length(output, body.len());
+    output.extend_from_slice(body);
+}
+
+fn write_subpacket(output: &mut Vec<u8>, kind: u8, data: &[u8]) {
+    write_length(output, data.len() + 1);
+    output.push(kind);
+    output.extend_from_slice(data);
+}
+
+struct Packet {
+    tag: u8,
+    body: Vec<u8>,
+}
+
+// reads both packet header formats, joining partial body lengths into one body
+fn read_packets(data: &[u8]) -> Result<Vec<Packet>, OpenPgpError> {
+    let mut reader = Reader::new(data);
+    let mut packets = Vec::new();
+
+    while !reader.is_empty() {
+        let header = reader.byte()?;
+
+        if header & 0x80 == 0 {
+            return Err(OpenPgpError::Malformed);
+        }
+
+        let mut body = Vec::new();
+
+        let tag = if header & 0x40 != 0 {
+            loop {
+                let first = reader.byte()?;
+
+                if (224..255).contains(&first) {
+                    body.extend_from_slice(reader.bytes(1 << (first & 0x1f))?);
+                } else {
+                    let length = read_length(&mut reader, first)?;
+                    body.extend_from_slice(reader.bytes(length)?);
+                    break;
+                }
+            }
+
+            header & 0x3f
+        } else {
+            let length = match header & 0x03 {
+                0 => reader.byte()?.into(),
+                1 => reader.u16()?.into(),
+                2 => reader.u32()? as usize,
+                _ => reader.len(),
+            };
+            body.extend_from_slice(reader.bytes(length)?);
+
+            (header >> 2) & 0x0f
+        };
+
+        packets.push(Packet { tag, body });
+    }
+
+    Ok(packets)
+}
+
+// a v6 key packet; `public` is the part that is hashed and `secret` anything after it
+struct KeyPacket<'a> {
+    algorithm: u8,
+    material: &'a [u8],
+    public: &'a [u8],
+    secret: &'a [u8],
+}
+
+impl<'a> KeyPacket<'a> {
+    fn parse(body: &'a [u8]) -> Result<KeyPacket<'a>, OpenPgpError> {
+        let mut reader = Reader::new(body);
+
+        if reader.byte()? != 6 {
+            return Err(OpenPgpError::Unsupported);
+        }
+
+        reader.u32()?;
+        let algorithm = reader.byte()?;
+        let length = reader.u32()? as usize;
+        let material = reader.bytes(length)?;
+        let (public, secret) = body.split_at(body.len() - reader.len());
+
+        Ok(KeyPacket {
+            algorithm,
+            material,
+            public,
+            secret,
+        })
+    }
+
+    fn parse_public(body: &'a [u8]) -> Result<KeyPacket<'a>, OpenPgpError> {
+        let key = KeyPacket::parse(body)?;
+
+        if !key.secret.is_empty() {
+            return Err(OpenPgpError::Malformed);
+        }
+
+        Ok(key)
+    }
+}
+
+fn key_body(created: u32, algorithm: u8, key: &[u8; 32]) -> Vec<u8> {
+    let mut body = vec![6];
+    body.extend_from_slice(&created.to_be_bytes());
+    body.push(algorithm);
+    body.extend_from_slice(&32u32.to_be_bytes());
+    body.extend_from_slice(key);
+
+    body
+}
+
+// the form a key takes in fingerprints and signatures
+fn key_data(body: &[u8]) -> Vec<u8> {
+    let mut data = vec![0x9b];
+    data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    data.extend_from_slice(body);
+
+    data
+}
+
+fn fingerprint(body: &[u8]) -> [u8; 32] {
+    sha256::hash(&key_data(body))
+}
+
+fn signature_digest<H: Hash>(salt: &[u8], signed: &[u8], fields: &[u8]) -> Vec<u8> {
+    let mut hasher = H::default();
+    hasher.update(salt);
+    hasher.update(signed);
+    hasher.update(fields);
+    hasher.update(&[6, 0xff]);
+    hasher.update(&(fields.len() as u32).to_be_bytes());
+
+    hasher.finish()
+}
+
+fn sign(key: &SigningKey, kind: u8, signed: &[u8], hashed: &[u8]) -> Vec<u8> {
+    let mut body = vec![6, kind, ED25519, SHA512];
+    body.extend_from_slice(&(hashed.len() as u32).to_be_bytes());
+    body.extend_from_slice(hashed);
+
+    let salt: [u8; 32] = random_bytes();
+    let digest = signature_digest::<Sha512>(&salt, signed, &body);
+
+    body.extend_from_slice(&0u32.to_be_bytes());
+    body.extend_from_slice(&digest[..2]);
+    body.push(32);
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&key.sign(&digest));
+
+    body
+}
+
+// a v6 Ed25519 signature
+struct Signature<'a> {
+    kind: u8,
+    hash: u8,
+    fields: &'a [u8],
+    subpackets: Vec<(u8, &'a [u8])>,
+    left: &'a [u8],
+    salt: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl<'a> Signature<'a> {
+    fn parse(body: &'a [u8]) -> Result<Signature<'a>, OpenPgpError> {
+        let mut reader = Reader::new(body);
+
+        if reader.byte()? != 6 {
+            return Err(OpenPgpError::Unsupported);
+        }
+
+        let kind = reader.byte()?;
+        let algorithm = reader.byte()?;
+        let hash = reader.byte()?;
+
+        let salt_length = match (algorithm, hash) {
+            (ED25519, SHA256) => 16,
+            (ED25519, SHA512) => 32,
+            _ => return Err(OpenPgpError::Unsupported),
+        };
+
+        let length = reader.u32()? as usize;
+        let mut hashed = Reader::new(reader.bytes(length)?);
+        let fields = &body[..8 + length];
+
+        let length = reader.u32()? as usize;
+        reader.bytes(length)?;
+
+        let left = reader.bytes(2)?;
+
+        if reader.byte()? != salt_length {
+            return Err(OpenPgpError::Malformed);
+        }
+
+        let salt = reader.bytes(salt_length.into())?;
+        let signature = reader.bytes(64)?;
+        reader.finish()?;
+
+        let mut subpackets = Vec::new();
+
+        while !hashed.is_empty() {
+            let first = hashed.byte()?;
+            let length = read_length(&mut hashed, first)?;
+            let (kind, data) = hashed
+                .bytes(length)?
+                .split_first()
+                .ok_or(OpenPgpError::Malformed)?;
+
+            if kind & CRITICAL != 0 && !KNOWN_SUBPACKETS.contains(&(kind & !CRITICAL)) {
+                return Err(OpenPgpError::Unsupported);
+            }
+
+            subpackets.push((kind & !CRITICAL, data));
+        }
+
+        Ok(Signature {
+            kind,
+            hash,
+            fields,
+            subpackets,
+            left,
+            salt,
+            signature,
+        })
+    }
+
+    fn subpacket(&self, kind: u8) -> Option<&'a [u8]> {
+        self.subpackets
+            .iter()
+            .find(|(found, _)| *found == kind)
+            .map(|(_, data)| *data)
+    }
+
+    fn key_flags(&self) -> u8 {
+        self.subpacket(KEY_FLAGS)
+            .and_then(|flags| flags.first().copied())
+            .unwrap_or(0)
+    }
+
+    fn verify(&self, key: &VerifyingKey, signed: &[u8]) -> bool {
+        let digest = if self.hash == SHA512 {
+            signature_digest::<Sha512>(self.salt, signed, self.fields)
+        } else {
+            signature_digest::<Sha256>(self.salt, signed, self.fields)
+        };
+
+        self.left == &digest[..2] && key.verify(&digest, self.signature).is_ok()
+    }
+}
+
+// a self-signature of `kind` that verifies, or `None`; other signatures, including ones made with
+// algorithms this module does not support, are ignored
+fn self_signature<'a>(
+    body: &'a [u8],
+    kind: u8,
+    key: &VerifyingKey,
+    signed: &[u8],
+) -> Option<Signature<'a>> {
+    let signature = Signature::parse(body).ok()?;
+
+    if signature.kind != kind || !signature.verify(key, signed) {
+        return None;
+    }
+
+    Some(signature)
+}
+
+/// A v6 transferable public key with an X25519 encryption subkey.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    bytes: Vec<u8>,
+    fingerprint: [u8; 32],
+    verifying_key: VerifyingKey,
+    encryption_key: PublicKey,
+    encryption_fingerprint: [u8; 32],
+    gcm: bool,
+}
+
+impl Certificate {
+    /// Parses a binary certificate, checking its self-signatures. The first X25519 subkey with a
+    /// valid binding for encryption and no revocation is the one messages are encrypted to.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Certificate, OpenPgpError> {
+        let packets = read_packets(bytes)?;
+        let (first, rest) = packets.split_first().ok_or(OpenPgpError::Malformed)?;
+
+        if first.tag != PUBLIC_KEY {
+            return Err(OpenPgpError::Malformed);
+        }
+
+        let primary = KeyPacket::parse_public(&first.body)?;
+
+        if primary.algorithm != ED25519 {
+            return Err(OpenPgpError::Unsupported);
+        }
+
+        let verifying_key =
+            VerifyingKey::new(primary.material).map_err(|_| OpenPgpError::Malformed)?;
+        let primary_data = key_data(primary.public);
+
+        let mut gcm = false;
+        let mut on_primary = true;
+        let mut subkey: Option<(KeyPacket, bool, bool)> = None;
+        let mut encryption = None;
+
+        for packet in rest {
+            if packet.tag == SIGNATURE {
+                if let Some((key, bound, revoked)) = subkey.as_mut() {
+                    let signed = [&primary_data[..], &key_data(key.public)].concat();
+
+                    if let Some(signature) =
+                        self_signature(&packet.body, SUBKEY_BINDING, &verifying_key, &signed)
+                    {
+                        *bound |= signature.key_flags() & ENCRYPT != 0;
+                    }
+
+                    *revoked |=
+                        self_signature(&packet.body, SUBKEY_REVOCATION, &verifying_key, &signed)
+                            .is_some();
+                } else if on_primary {
+                    if self_signature(&packet.body, KEY_REVOCATION, &verifying_key, &primary_data)
+                        .is_some()
+                    {
+                        return Err(OpenPgpError::Revoked);
+                    }
+
+                    if let Some(signature) =
+                        self_signature(&packet.body, DIRECT_KEY, &verifying_key, &primary_data)
+                    {
+                        gcm |= signature.subpacket(PREFERRED_AEAD).is_some_and(|suites| {
+                            suites.chunks_exact(2).any(|s| s == [AES_256, GCM])
+                        });
+                    }
+                }
+
+                continue;
+            }
+
+            encryption = encryption.or(Certificate::usable(subkey.take()));
+            on_primary = false;
+
+            if packet.tag == PUBLIC_SUBKEY {
+                subkey = Some((KeyPacket::parse_public(&packet.body)?, false, false));
+            }
+        }
+
+        encryption = encryption.or(Certificate::usable(subkey.take()));
+        let (encryption_key, encryption_fingerprint) =
+            encryption.ok_or(OpenPgpError::NoEncryptionKey)?;
+
+        Ok(Certificate {
+            bytes: bytes.to_vec(),
+            fingerprint: fingerprint(primary.public),
+            verifying_key,
+            encryption_key,
+            encryption_fingerprint,
+            gcm,
+        })
+    }
+
+    fn usable(subkey: Option<(KeyPacket, bool, bool)>) -> Option<(PublicKey, [u8; 32])> {
+        let (key, bound, revoked) = subkey?;
+
+        if !bound || revoked || key.algorithm != X25519 {
+            return None;
+        }
+
+        Some((key.material.try_into().ok()?, fingerprint(key.public)))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// The fingerprint of the primary key.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        self.fingerprint
+    }
+
+    pub fn encryption_fingerprint(&self) -> [u8; 32] {
+        self.encryption_fingerprint
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.verifying_key
+    }
+}
+
+/// A v6 transferable secret key, unencrypted.
+pub struct SecretKey {
+    signing_key: SigningKey,
+    decryption_key: PrivateKey,
+    certificate: Certificate,
+}
+
+redacted_debug!(SecretKey, "<redacted>");
+
+impl SecretKey {
+    /// A new Ed25519 primary key and X25519 encryption subkey, both created at `now`. The
+    /// certificate asks for AES-256 with OCB or GCM.
+    pub fn generate(now: SystemTime) -> SecretKey {
+        let signing_key = ed25519::keygen(None);
+        let decryption_key = x25519::keygen(None);
+        let created = timestamp(now);
+
+        let primary = key_body(created, ED25519, signing_key.verifying_key().as_bytes());
+        let subkey = key_body(created, X25519, &decryption_key.public_key());
+        let issuer = [&[6][..], &fingerprint(&primary)].concat();
+
+        let mut hashed = Vec::new();
+        write_subpacket(
+            &mut hashed,
+            CREATION_TIME | CRITICAL,
+            &created.to_be_bytes(),
+        );
+        write_subpacket(&mut hashed, KEY_FLAGS | CRITICAL, &[CERTIFY | SIGN]);
+        write_subpacket(&mut hashed, FEATURES, &[0x09]);
+        write_subpacket(&mut hashed, PREFERRED_SYMMETRIC, &[AES_256, AES_128]);
+        write_subpacket(&mut hashed, PREFERRED_HASH, &[SHA512, SHA256]);
+        write_subpacket(&mut hashed, PREFERRED_AEAD, &[AES_256, OCB, AES_256, GCM]);
+        write_subpacket(&mut hashed, ISSUER_FINGERPRINT, &issuer);
+        let direct = sign(&signing_key, DIRECT_KEY, &key_data(&primary), &hashed);
+
+        let mut hashed = Vec::new();
+        write_subpacket(
+            &mut hashed,
+            CREATION_TIME | CRITICAL,
+            &created.to_be_bytes(),
+        );
+        write_subpacket(&mut hashed, KEY_FLAGS | CRITICAL, &[ENCRYPT]);
+        write_subpacket(&mut hashed, ISSUER_FINGERPRINT, &issuer);
+        let signed = [key_data(&primary), key_data(&subkey)].concat();
+        let binding = sign(&signing_key, SUBKEY_BINDING, &signed, &hashed);
+
+        let mut bytes = Vec::new();
+        write_packet(&mut bytes, PUBLIC_KEY, &primary);
+        write_packet(&mut bytes, SIGNATURE, &direct);
+        write_packet(&mut bytes, PUBLIC_SUBKEY, &subkey);
+        write_packet(&mut bytes, SIGNATURE, &binding);
+
+        SecretKey {
+            signing_key,
+            decryption_key,
+            certificate: Certificate::from_bytes(&bytes).unwrap(),
+        }
+    }
+
+    /// Parses a binary secret key. Secret keys protected with a passphrase are not supported.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey, OpenPgpError> {
+        let mut packets = read_packets(bytes)?;
+        let key = SecretKey::from_packets(&packets);
+
+        for packet in packets.iter_mut() {
+            packet.body.zeroize();
+        }
+
+        key
+    }
+
+    fn from_packets(packets: &[Packet]) -> Result<SecretKey, OpenPgpError> {
+        let mut public = Vec::new();
+        let mut secrets = Vec::new();
+
+        for packet in packets {
+            let tag = match packet.tag {
+                SECRET_KEY => PUBLIC_KEY,
+                SECRET_SUBKEY => PUBLIC_SUBKEY,
+                tag => {
+                    write_packet(&mut public, tag, &packet.body);
+                    continue;
+                }
+            };
+
+            let key = KeyPacket::parse(&packet.body)?;
+            write_packet(&mut public, tag, key.public);
+            secrets.push((fingerprint(key.public), key.secret));
+        }
+
+        let certificate = Certificate::from_bytes(&public)?;
+
+        let secret = |fingerprint: [u8; 32]| -> Result<&[u8], OpenPgpError> {
+            let (_, secret) = secrets
+                .iter()
+                .find(|(found, _)| *found == fingerprint)
+                .ok_or(OpenPgpError::NoMatchingKey)?;
+            let (usage, secret) = secret.split_first().ok_or(OpenPgpError::Malformed)?;
+
+            if *usage != 0 {
+                return Err(OpenPgpError::Unsupported);
+            }
+
+            Ok(secret)
+        };
+
+        let signing_key = SigningKey::new(secret(certificate.fingerprint)?)
+            .map_err(|_| OpenPgpError::Malformed)?;
+        let decryption_key = PrivateKey::new(secret(certificate.encryption_fingerprint)?)
+            .map_err(|_| OpenPgpError::Malformed)?;
+
+        if signing_key.verifying_key() != certificate.verifying_key
+            || decryption_key.public_key() != certificate.encryption_key
+        {
+            return Err(OpenPgpError::Malformed);
+        }
+
+        Ok(SecretKey {
+            signing_key,
+            decryption_key,
+            certificate,
+        })
+    }
+
+    /// The secret key in binary form, with the primary key and the encryption subkey as secret
+    /// key packets.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        for packet in read_packets(&self.certificate.bytes).unwrap() {
+            let (tag, mut secret) = if packet.tag == PUBLIC_KEY {
+                (SECRET_KEY, self.signing_key.to_bytes())
+            } else if packet.tag == PUBLIC_SUBKEY
+                && fingerprint(&packet.body) == self.certificate.encryption_fingerprint
+            {
+                (SECRET_SUBKEY, self.decryption_key.to_bytes())
+            } else {
+                write_packet(&mut output, packet.tag, &packet.body);
+                continue;
+            };
+
+            let mut body = [&packet.body[..], &[0], &secret].concat();
+            write_packet(&mut output, tag, &body);
+            body.zeroize();
+            secret.zeroize();
+        }
+
+        output
+    }
+
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    pub fn fingerprint(&self) -> [u8; 32] {
+        self.certificate.fingerprint
+    }
+
+    fn key_encryption_key(&self, ephemeral: &PublicKey) -> Vec<u8> {
+        let mut shared = self.decryption_key.exchange(*ephemeral);
+        let kek = key_encryption_key(&shared, ephemeral, &self.certificate.encryption_key);
+        shared.zeroize();
+
+        kek
+    }
+
+    // the session key from a PKESK addressed to this key, or `None` for other recipients
+    fn open_pkesk(&self, body: &[u8]) -> Result<Option<Vec<u8>>, OpenPgpError> {
+        let mut reader = Reader::new(body);
+
+        if reader.byte()? != 6 {
+            return Ok(None);
+        }
+
+        let length = reader.byte()?;
+        let recipient = reader.bytes(length.into())?;
+
+        if reader.byte()? != X25519 {
+            return Ok(None);
+        }
+
+        // an anonymous recipient has no fingerprint, so every key has to try it
+        if !recipient.is_empty()
+            && recipient != [&[6][..], &self.certificate.encryption_fingerprint].concat()
+        {
+            return Ok(None);
+        }
+
+        let ephemeral: PublicKey = reader.bytes(32)?.try_into().unwrap();
+        let length = reader.byte()?;
+        let wrapped = reader.bytes(length.into())?;
+        reader.finish()?;
+
+        let mut kek = self.key_encryption_key(&ephemeral);
+        let session_key = keywrap::unwrap(&kek, wrapped).ok();
+        kek.zeroize();
+
+        Ok(session_key)
+    }
+}
+
+fn key_encryption_key(shared: &[u8; 32], ephemeral: &PublicKey, recipient: &PublicKey) -> Vec<u8> {
+    let mut ikm = [&ephemeral[..], recipient, shared].concat();
+    let kek = Hkdf::<Sha256>::extract(&[], &ikm)
+        .expand(b"OpenPGP X25519", 16)
+        .unwrap();
+    ikm.zeroize();
+
+    kek
+}
+
+fn pkesk(recipient: &Certificate, session_key: &[u8]) -> Vec<u8> {
+    let ephemeral = x25519::keygen(None);
+    let ephemeral_public = ephemeral.public_key();
+
+    let mut shared = ephemeral.exchange(recipient.encryption_key);
+    let mut kek = key_encryption_key(&shared, &ephemeral_public, &recipient.encryption_key);
+    let wrapped = keywrap::wrap(&kek, session_key).unwrap();
+    shared.zeroize();
+    kek.zeroize();
+
+    let mut body = vec![6, 33, 6];
+    body.extend_from_slice(&recipient.encryption_fingerprint);
+    body.push(X25519);
+    body.extend_from_slice(&ephemeral_public);
+    body.push(wrapped.len() as u8);
+    body.extend_from_slice(&wrapped);
+
+    body
+}
+
+enum Aead {
+    Ocb(Box<AesOcb>),
+    Gcm(Box<AesGcm>),
+}
+
+// the AEAD key and nonce prefix of a SEIPDv2 packet, derived from the session key and salt
+struct MessageCipher {
+    aead: Aead,
+    iv: Vec<u8>,
+    ad: [u8; 5],
+}
+
+impl MessageCipher {
+    fn new(session_key: &[u8], header: &[u8], salt: &[u8]) -> Result<MessageCipher, OpenPgpError> {
+        let ad = [0xd2, header[0], header[1], header[2], header[3]];
+
+        let key_size = match header[1] {
+            AES_128 => 16,
+            AES_256 => 32,
+            _ => return Err(OpenPgpError::Unsupported),
+        };
+
+        let iv_size = match header[2] {
+            OCB => 15 - 8,
+            GCM => 12 - 8,
+            _ => return Err(OpenPgpError::Unsupported),
+        };
+
+        if session_key.len() != key_size || header[3] > MAX_CHUNK_SIZE {
+            return Err(OpenPgpError::Malformed);
+        }
+
+        let mut okm = Hkdf::<Sha256>::extract(salt, session_key)
+            .expand(&ad, key_size + iv_size)
+            .unwrap();
+        let (key, iv) = okm.split_at(key_size);
+
+        let aead = if header[2] == OCB {
+            Aead::Ocb(Box::new(AesOcb::new(key).unwrap()))
+        } else {
+            Aead::Gcm(Box::new(AesGcm::new(key).unwrap()))
+        };
+        let iv = iv.to_vec();
+        okm.zeroize();
+
+        Ok(MessageCipher { aead, iv, ad })
+    }
+
+    fn nonce(&self, index: u64) -> Vec<u8> {
+        [&self.iv[..], &index.to_be_bytes()].concat()
+    }
+
+    fn seal(&self, index: u64, msg: &[u8], ad: &[u8]) -> Vec<u8> {
+        match &self.aead {
+            Aead::Ocb(aead) => aead.encrypt(msg, &self.nonce(index), ad).to_vec(),
+            Aead::Gcm(aead) => aead.encrypt(msg, &self.nonce(index), ad).to_vec(),
+        }
+    }
+
+    fn open(&self, index: u64, ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, OpenPgpError> {
+        match &self.aead {
+            Aead::Ocb(aead) => aead.decrypt(ct, &self.nonce(index), ad),
+            Aead::Gcm(aead) => aead.decrypt(ct, &self.nonce(index), ad),
+        }
+        .map_err(|_| OpenPgpError::InvalidMac)
+    }
+
+    // the final tag authenticates the total length, so chunks cannot be dropped from the end
+    fn final_ad(&self, length: usize) -> Vec<u8> {
+        [&self.ad[..], &(length as u64).to_be_bytes()].concat()
+    }
+}
+
+fn seal_seipd(session_key: &[u8], aead: u8, plaintext: &[u8]) -> Vec<u8> {
+    let mut body = vec![2, AES_256, aead, CHUNK_SIZE];
+    body.extend_from_slice(&random_bytes::<32>());
+
+    let cipher = MessageCipher::new(session_key, &body[..4], &body[4..]).unwrap();
+    let mut index = 0;
+
+    for chunk in plaintext.chunks(1 << (CHUNK_SIZE + 6)) {
+        body.extend_from_slice(&cipher.seal(index, chunk, &cipher.ad));
+        index += 1;
+    }
+
+    body.extend_from_slice(&cipher.seal(index, &[], &cipher.final_ad(plaintext.len())));
+
+    body
+}
+
+fn open_seipd(session_key: &[u8], body: &[u8]) -> Result<Vec<u8>, OpenPgpError> {
+    let mut reader = Reader::new(body);
+    let header = reader.bytes(4)?;
+
+    if header[0] != 2 {
+        return Err(OpenPgpError::Unsupported);
+    }
+
+    let cipher = MessageCipher::new(session_key, header, reader.bytes(32)?)?;
+    let ciphertext = reader.rest();
+
+    if ciphertext.len() < 16 {
+        return Err(OpenPgpError::Malformed);
+    }
+
+    let (chunks, tag) = ciphertext.split_at(ciphertext.len() - 16);
+    let mut plaintext = Vec::new();
+    let mut index = 0;
+
+    for chunk in chunks.chunks((1 << (header[3] + 6)) + 16) {
+        match cipher.open(index, chunk, &cipher.ad) {
+            Ok(mut msg) => {
+                plaintext.extend_from_slice(&msg);
+                msg.zeroize();
+            }
+            Err(error) => {
+                plaintext.zeroize();
+                return Err(error);
+            }
+        }
+
+        index += 1;
+    }
+
+    if let Err(error) = cipher.open(index, tag, &cipher.final_ad(plaintext.len())) {
+        plaintext.zeroize();
+        return Err(error);
+    }
+
+    Ok(plaintext)
+}
+
+/// The contents of a literal data packet: a file's name, modification time and data.
+#[derive(Clone, PartialEq, Eq)]
+pub struct LiteralData {
+    filename: Vec<u8>,
+    modified: u32,
+    data: Vec<u8>,
+}
+
+redacted_debug!(LiteralData, "<redacted>");
+
+impl LiteralData {
+    /// `filename` is at most 255 bytes, and `modified` is kept to the second.
+    pub fn new(filename: &[u8], modified: SystemTime, data: &[u8]) -> LiteralData {
+        assert!(filename.len() <= 255);
+
+        LiteralData {
+            filename: filename.to_vec(),
+            modified: timestamp(modified),
+            data: data.to_vec(),
+        }
+    }
+
+    fn parse(body: &[u8]) -> Result<LiteralData, OpenPgpError> {
+        let mut reader = Reader::new(body);
+        reader.byte()?;
+
+        let length = reader.byte()?;
+        let filename = reader.bytes(length.into())?.to_vec();
+        let modified = reader.u32()?;
+
+        Ok(LiteralData {
+            filename,
+            modified,
+            data: reader.rest().to_vec(),
+        })
+    }
+
+    fn to_body(&self) -> Vec<u8> {
+        let mut body = vec![b'b', self.filename.len() as u8];
+        body.extend_from_slice(&self.filename);
+        body.extend_from_slice(&self.modified.to_be_bytes());
+        body.extend_from_slice(&self.data);
+
+        body
+    }
+
+    pub fn filename(&self) -> &[u8] {
+        &self.filename
+    }
+
+    pub fn modified(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.modified.into())
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Encrypts `literal` to every recipient with AES-256, using GCM if all of them ask for it and
+/// OCB, which every v6 implementation supports, otherwise.
+pub fn encrypt(recipients: &[Certificate], literal: &LiteralData) -> Vec<u8> {
+    assert!(!recipients.is_empty());
+
+    let aead = if recipients.iter().all(|recipient| recipient.gcm) {
+        GCM
+    } else {
+        OCB
+    };
+
+    let mut session_key: [u8; 32] = random_bytes();
+    let mut output = Vec::new();
+
+    for recipient in recipients {
+        write_packet(&mut output, PKESK, &pkesk(recipient, &session_key));
+    }
+
+    let mut plaintext = Vec::new();
+    write_packet(&mut plaintext, LITERAL_DATA, &literal.to_body());
+
+    write_packet(
+        &mut output,
+        SEIPD,
+        &seal_seipd(&session_key, aead, &plaintext),
+    );
+    session_key.zeroize();
+    plaintext.zeroize();
+
+    output
+}
+
+pub fn decrypt(key: &SecretKey, message: &[u8]) -> Result<LiteralData, OpenPgpError> {
+    let packets = read_packets(message)?;
+    let mut session_key = None;
+    let mut encrypted = None;
+
+    for packet in &packets {
+        match packet.tag {
+            PKESK if session_key.is_none() => session_key = key.open_pkesk(&packet.body)?,
+            PKESK | MARKER | PADDING => {}
+            SEIPD if encrypted.is_none() => encrypted = Some(&packet.body),
+            _ => return Err(OpenPgpError::Unsupported),
+        }
+    }
+
+    let encrypted = encrypted.ok_or(OpenPgpError::Malformed)?;
+    let mut session_key = session_key.ok_or(OpenPgpError::NoMatchingKey)?;
+    let plaintext = open_seipd(&session_key, encrypted);
+    session_key.zeroize();
+
+    let mut plaintext = plaintext?;
+    let packets = read_packets(&plaintext);
+    plaintext.zeroize();
+
+    let mut literal = None;
+
+    for packet in packets? {
+        match packet.tag {
+            LITERAL_DATA if literal.is_none() => literal = Some(LiteralData::parse(&packet.body)?),
+            MARKER | PADDING => {}
+            _ => return Err(OpenPgpError::Unsupported),
+        }
+    }
+
+    literal.ok_or(OpenPgpError::Malformed)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArmorKind {
+    Message,
+    PublicKey,
+    PrivateKey,
+}
+
+impl ArmorKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ArmorKind::Message => "PGP MESSAGE",
+            ArmorKind::PublicKey => "PGP PUBLIC KEY BLOCK",
+            ArmorKind::PrivateKey => "PGP PRIVATE KEY BLOCK",
+        }
+    }
+}
+
+/// ASCII armor without a checksum, as RFC 9580 recommends.
+pub fn armor(kind: ArmorKind, data: &[u8]) -> String {
+    let body = base64::encode(data);
+    let mut output = format!("-----BEGIN {}-----\n\n", kind.label());
+
+    for line in body.as_bytes().chunks(64) {
+        output.push_str(core::str::from_utf8(line).unwrap());
+        output.push('\n');
+    }
+
+    output.push_str(&format!("-----END {}-----\n", kind.label()));
+
+    output
+}
+
+/// Reads the first armored block in `text`, skipping armor headers and any checksum.
+pub fn dearmor(text: &str) -> Result<(ArmorKind, Vec<u8>), OpenPgpError> {
+    let mut lines = text.lines().map(str::trim);
+
+    let label = lines
+        .by_ref()
+        .find_map(|line| line.strip_prefix("-----BEGIN ")?.strip_suffix("-----"))
+        .ok_or(OpenPgpError::Malformed)?;
+    let kind = [
+        ArmorKind::Message,
+        ArmorKind::PublicKey,
+        ArmorKind::PrivateKey,
+    ]
+    .into_iter()
+    .find(|kind| kind.label() == label)
+    .ok_or(OpenPgpError::Unsupported)?;
+
+    let end = format!("-----END {}-----", label);
+    let mut body = String::new();
+    let mut headers = true;
+
+    for line in lines {
+        if line == end {
+            return Ok((kind, base64::decode(&body)?));
+        }
+
+        if headers && line.contains(": ") {
+            continue;
+        }
+
+        headers = false;
+
+        // the CRC-24 line is '=' and four characters, unlike padding that wrapped onto a line
+        if !(line.starts_with('=') && line.len() == 5) {
+            body.push_str(line);
+        }
+    }
+
+    Err(OpenPgpError::Malformed)
+}
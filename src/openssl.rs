@@ -0,0 +1,152 @@
+// The salted format of `openssl enc -aes-256-cbc -pbkdf2`, for reading and writing files made
+// with the OpenSSL command line. The output is "Salted__" || salt (8) || AES-256-CBC ciphertext
+// with PKCS#7 padding, where the key and IV are 48 bytes of PBKDF2-HMAC-SHA256 over the password
+// and salt. The iteration count is not stored, so it has to be the one given to `-iter`.
+//
+// Nothing authenticates the ciphertext: a wrong password is only noticed when the padding happens
+// to be invalid, and tampering goes undetected. Use it to migrate old artifacts, not for new ones.
+
+use crate::ciphers::aes::inverse::InverseAes;
+use crate::ciphers::aes::Aes;
+use crate::encoding::base64;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::pbkdf2::pbkdf2;
+use crate::utils::random_bytes;
+use zeroize::Zeroize;
+
+const MAGIC: &[u8] = b"Salted__";
+const SALT_SIZE: usize = 8;
+const HEADER_SIZE: usize = MAGIC.len() + SALT_SIZE;
+
+/// The count `-pbkdf2` uses without `-iter`.
+pub const DEFAULT_ITERATIONS: u32 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpensslError {
+    Malformed,
+    BadDecrypt,
+}
+
+impl std::fmt::Display for OpensslError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpensslError::Malformed => write!(f, "The data is not in the openssl salted format."),
+            OpensslError::BadDecrypt => {
+                write!(
+                    f,
+                    "Bad decrypt. The password or iteration count may be wrong."
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for OpensslError {}
+
+// the key followed by the IV
+fn derive(password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+    pbkdf2::<Sha256>(password, salt, iterations, 32 + 16)
+}
+
+pub fn encrypt(password: &[u8], data: &[u8], iterations: u32) -> Vec<u8> {
+    let salt: [u8; SALT_SIZE] = random_bytes();
+    let mut key_iv = derive(password, &salt, iterations);
+    let aes = Aes::new(&key_iv[..32]).unwrap();
+
+    let padding = 16 - data.len() % 16;
+    let mut output = Vec::with_capacity(HEADER_SIZE + data.len() + padding);
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(data);
+    output.resize(output.len() + padding, padding as u8);
+
+    let mut previous: [u8; 16] = key_iv[32..].try_into().unwrap();
+    key_iv.zeroize();
+
+    for chunk in output[HEADER_SIZE..].chunks_exact_mut(16) {
+        for (byte, previous) in chunk.iter_mut().zip(previous) {
+            *byte ^= previous;
+        }
+
+        previous = chunk.try_into().unwrap();
+        aes.encrypt_block(&mut previous);
+        chunk.copy_from_slice(&previous);
+    }
+
+    output
+}
+
+pub fn decrypt(password: &[u8], data: &[u8], iterations: u32) -> Result<Vec<u8>, OpensslError> {
+    if data.len() < HEADER_SIZE + 16
+        || !data.starts_with(MAGIC)
+        || !(data.len() - HEADER_SIZE).is_multiple_of(16)
+    {
+        return Err(OpensslError::Malformed);
+    }
+
+    let (salt, ct) = data[MAGIC.len()..].split_at(SALT_SIZE);
+    let mut key_iv = derive(password, salt, iterations);
+    let aes = InverseAes::new(&key_iv[..32]).unwrap();
+
+    let mut previous: [u8; 16] = key_iv[32..].try_into().unwrap();
+    key_iv.zeroize();
+
+    let mut output = Vec::with_capacity(ct.len());
+
+    for chunk in ct.chunks_exact(16) {
+        let mut block: [u8; 16] = chunk.try_into().unwrap();
+        aes.decrypt_block(&mut block);
+
+        output.extend(
+            block
+                .iter()
+                .zip(previous)
+                .map(|(byte, previous)| byte ^ previous),
+        );
+        previous = chunk.try_into().unwrap();
+        block.zeroize();
+    }
+
+    // checks every padding byte whatever the first one says
+    let padding = output[output.len() - 1];
+    let mut invalid = (padding == 0 || padding > 16) as u8;
+
+    for (i, &byte) in output[output.len() - 16..].iter().rev().enumerate() {
+        let in_padding = ((i as u8) < padding) as u8;
+        invalid |= in_padding & (byte != padding) as u8;
+    }
+
+    if invalid != 0 {
+        output.zeroize();
+        return Err(OpensslError::BadDecrypt);
+    }
+
+    output.truncate(output.len() - padding as usize);
+
+    Ok(output)
+}
+
+/// `encrypt` with the `-a` option: base64 in lines of 64 characters.
+pub fn encrypt_base64(password: &[u8], data: &[u8], iterations: u32) -> String {
+    let encoded = base64::encode(&encrypt(password, data, iterations));
+    let mut output = String::with_capacity(encoded.len() + encoded.len() / 64 + 1);
+
+    for line in encoded.as_bytes().chunks(64) {
+        output.push_str(std::str::from_utf8(line).unwrap());
+        output.push('\n');
+    }
+
+    output
+}
+
+/// `decrypt` for output of the `-a` option, in lines of any length.
+pub fn decrypt_base64(
+    password: &[u8],
+    data: &str,
+    iterations: u32,
+) -> Result<Vec<u8>, OpensslError> {
+    let joined: String = data.split_whitespace().collect();
+    let decoded = base64::decode(&joined).map_err(|_| OpensslError::Malformed)?;
+
+    decrypt(password, &decoded, iterations)
+}
@@ -0,0 +1,108 @@
+// Thread pool plumbing shared by the multi-threaded primitives. `threads` is the requested
+// thread count everywhere, with 0 meaning one per core and 1 meaning the calling thread only.
+// Requests for more threads than cores get one per core, so that parameters read from stored
+// hashes or key files cannot make us spawn an arbitrary number of threads.
+
+/// The number of threads to actually use for a request of `threads`.
+pub(crate) fn threads(threads: usize) -> usize {
+    let cores = std::thread::available_parallelism().map_or(1, |count| count.get());
+
+    match threads {
+        0 => cores,
+        threads => threads.min(cores),
+    }
+}
+
+/// Runs `f` inside a pool of `threads` threads, so that parallel work it starts uses that pool.
+/// Pools are built on first use for each thread count and kept for the life of the process.
+#[cfg(feature = "rayon")]
+pub(crate) fn install<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    use std::sync::{Arc, Mutex};
+
+    static POOLS: Mutex<Vec<(usize, Arc<rayon::ThreadPool>)>> = Mutex::new(Vec::new());
+
+    let threads = self::threads(threads);
+    if threads == 1 {
+        return f();
+    }
+
+    let pool = {
+        let mut pools = POOLS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match pools.iter().find(|(count, _)| *count == threads) {
+            Some((_, pool)) => Some(pool.clone()),
+            None => match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                Ok(pool) => {
+                    let pool = Arc::new(pool);
+                    pools.push((threads, pool.clone()));
+                    Some(pool)
+                }
+                Err(_) => None,
+            },
+        }
+    };
+
+    match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn install<T: Send>(_threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    f()
+}
+
+/// Calls `f` with the index of and a mutable reference to every item, spread over `threads`
+/// threads. With the `rayon` feature this runs on the pool set up by `install`.
+#[cfg(feature = "rayon")]
+pub(crate) fn for_each_mut<T: Send>(
+    threads: usize,
+    items: &mut [T],
+    f: impl Fn(usize, &mut T) + Sync,
+) {
+    use rayon::prelude::*;
+
+    if threads == 1 || items.len() < 2 {
+        return items
+            .iter_mut()
+            .enumerate()
+            .for_each(|(index, item)| f(index, item));
+    }
+
+    items
+        .par_iter_mut()
+        .enumerate()
+        .for_each(|(index, item)| f(index, item));
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn for_each_mut<T: Send>(
+    threads: usize,
+    items: &mut [T],
+    f: impl Fn(usize, &mut T) + Sync,
+) {
+    let threads = self::threads(threads);
+
+    if threads == 1 || items.len() < 2 {
+        return items
+            .iter_mut()
+            .enumerate()
+            .for_each(|(index, item)| f(index, item));
+    }
+
+    let per_thread = items.len().div_ceil(threads);
+    let f = &f;
+
+    std::thread::scope(|scope| {
+        for (group, chunk) in items.chunks_mut(per_thread).enumerate() {
+            scope.spawn(move || {
+                for (offset, item) in chunk.iter_mut().enumerate() {
+                    f(group * per_thread + offset, item);
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,408 @@
+// Keys held on a PKCS#11 token, reached through the token vendor's module. Only the handful of
+// Cryptoki functions needed for signing and AES-GCM key wrapping are declared, with the structure
+// layouts used on Unix platforms.
+
+use crate::aeads::envelope::KeyProvider;
+use crate::aeads::SymmetricKey;
+use crate::ecc::ed25519::Signature;
+use crate::utils::random_bytes;
+use libloading::Library;
+use std::ffi::c_void;
+use std::os::raw::c_ulong;
+use std::path::Path;
+use std::ptr::{null, null_mut};
+use zeroize::Zeroize;
+
+type Ulong = c_ulong;
+type Rv = Ulong;
+type Handle = Ulong;
+type Unused = *const c_void;
+
+const CKR_OK: Rv = 0x0;
+const CKR_CRYPTOKI_ALREADY_INITIALIZED: Rv = 0x191;
+
+const CKF_SERIAL_SESSION: Ulong = 0x4;
+const CKU_USER: Ulong = 0x1;
+
+const CKA_CLASS: Ulong = 0x0;
+const CKA_LABEL: Ulong = 0x3;
+const CKO_PRIVATE_KEY: Ulong = 0x3;
+const CKO_SECRET_KEY: Ulong = 0x4;
+
+const CKM_EDDSA: Ulong = 0x1057;
+const CKM_AES_GCM: Ulong = 0x1087;
+
+const GCM_IV_SIZE: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pkcs11Error {
+    Library,
+    KeyNotFound,
+    Malformed,
+    /// A `CKR_*` return value from the module.
+    Token(c_ulong),
+}
+
+impl std::fmt::Display for Pkcs11Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pkcs11Error::Library => write!(f, "The PKCS#11 module could not be loaded."),
+            Pkcs11Error::KeyNotFound => write!(f, "The token has no key with that label."),
+            Pkcs11Error::Malformed => write!(f, "The data has the wrong length."),
+            Pkcs11Error::Token(rv) => write!(f, "The token returned error 0x{:x}.", rv),
+        }
+    }
+}
+
+impl std::error::Error for Pkcs11Error {}
+
+fn check(rv: Rv) -> Result<(), Pkcs11Error> {
+    if rv != CKR_OK {
+        return Err(Pkcs11Error::Token(rv));
+    }
+
+    Ok(())
+}
+
+#[repr(C)]
+struct Version {
+    major: u8,
+    minor: u8,
+}
+
+#[repr(C)]
+struct Attribute {
+    kind: Ulong,
+    value: *mut c_void,
+    length: Ulong,
+}
+
+#[repr(C)]
+struct Mechanism {
+    kind: Ulong,
+    parameter: *mut c_void,
+    length: Ulong,
+}
+
+#[repr(C)]
+struct GcmParams {
+    iv: *mut u8,
+    iv_length: Ulong,
+    iv_bits: Ulong,
+    ad: *mut u8,
+    ad_length: Ulong,
+    tag_bits: Ulong,
+}
+
+// CK_FUNCTION_LIST up to C_Sign; the module owns the whole table, so the entries after it can be
+// left out
+#[repr(C)]
+struct FunctionList {
+    version: Version,
+    initialize: unsafe extern "C" fn(*mut c_void) -> Rv,
+    finalize: unsafe extern "C" fn(*mut c_void) -> Rv,
+    _info: [Unused; 10],
+    open_session: unsafe extern "C" fn(Ulong, Ulong, *mut c_void, Unused, *mut Handle) -> Rv,
+    close_session: unsafe extern "C" fn(Handle) -> Rv,
+    _sessions: [Unused; 4],
+    login: unsafe extern "C" fn(Handle, Ulong, *const u8, Ulong) -> Rv,
+    _objects: [Unused; 7],
+    find_objects_init: unsafe extern "C" fn(Handle, *mut Attribute, Ulong) -> Rv,
+    find_objects: unsafe extern "C" fn(Handle, *mut Handle, Ulong, *mut Ulong) -> Rv,
+    find_objects_final: unsafe extern "C" fn(Handle) -> Rv,
+    encrypt_init: unsafe extern "C" fn(Handle, *mut Mechanism, Handle) -> Rv,
+    encrypt: unsafe extern "C" fn(Handle, *const u8, Ulong, *mut u8, *mut Ulong) -> Rv,
+    _encrypt_parts: [Unused; 2],
+    decrypt_init: unsafe extern "C" fn(Handle, *mut Mechanism, Handle) -> Rv,
+    decrypt: unsafe extern "C" fn(Handle, *const u8, Ulong, *mut u8, *mut Ulong) -> Rv,
+    _decrypt_parts: [Unused; 2],
+    _digest: [Unused; 5],
+    sign_init: unsafe extern "C" fn(Handle, *mut Mechanism, Handle) -> Rv,
+    sign: unsafe extern "C" fn(Handle, *const u8, Ulong, *mut u8, *mut Ulong) -> Rv,
+}
+
+/// A logged-in session on a PKCS#11 token. Sessions are not safe to share between threads, so
+/// each thread should open its own.
+pub struct Token {
+    functions: *const FunctionList,
+    session: Handle,
+    finalize: bool,
+    // dropped last, after the session is closed
+    _library: Library,
+}
+
+impl std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Token({})", self.session)
+    }
+}
+
+impl Token {
+    /// Loads the PKCS#11 module at `module`, opens a session on `slot` and logs in with `pin`.
+    pub fn open(module: impl AsRef<Path>, slot: u64, pin: &[u8]) -> Result<Token, Pkcs11Error> {
+        // loading a library runs its initialisers, which is all the module path is trusted with
+        let library = unsafe { Library::new(module.as_ref()) }.map_err(|_| Pkcs11Error::Library)?;
+
+        let mut functions: *const FunctionList = null();
+
+        unsafe {
+            let get_function_list = library
+                .get::<unsafe extern "C" fn(*mut *const FunctionList) -> Rv>(b"C_GetFunctionList\0")
+                .map_err(|_| Pkcs11Error::Library)?;
+
+            check(get_function_list(&mut functions))?;
+        }
+
+        if functions.is_null() {
+            return Err(Pkcs11Error::Library);
+        }
+
+        let list = unsafe { &*functions };
+
+        // another part of the process may already be using the module, in which case it is
+        // theirs to finalize
+        let finalize = match unsafe { (list.initialize)(null_mut()) } {
+            CKR_OK => true,
+            CKR_CRYPTOKI_ALREADY_INITIALIZED => false,
+            rv => return Err(Pkcs11Error::Token(rv)),
+        };
+
+        let mut session = 0;
+        let opened = check(unsafe {
+            (list.open_session)(
+                slot as Ulong,
+                CKF_SERIAL_SESSION,
+                null_mut(),
+                null(),
+                &mut session,
+            )
+        });
+
+        if let Err(error) = opened {
+            if finalize {
+                unsafe { (list.finalize)(null_mut()) };
+            }
+
+            return Err(error);
+        }
+
+        let token = Token {
+            functions,
+            session,
+            finalize,
+            _library: library,
+        };
+
+        check(unsafe { (list.login)(session, CKU_USER, pin.as_ptr(), pin.len() as Ulong) })?;
+
+        Ok(token)
+    }
+
+    fn functions(&self) -> &FunctionList {
+        unsafe { &*self.functions }
+    }
+
+    fn find(&self, class: Ulong, label: &str) -> Result<Handle, Pkcs11Error> {
+        let functions = self.functions();
+
+        let mut class = class;
+        let mut template = [
+            Attribute {
+                kind: CKA_CLASS,
+                value: &mut class as *mut Ulong as *mut c_void,
+                length: std::mem::size_of::<Ulong>() as Ulong,
+            },
+            Attribute {
+                kind: CKA_LABEL,
+                value: label.as_ptr() as *mut c_void,
+                length: label.len() as Ulong,
+            },
+        ];
+
+        check(unsafe { (functions.find_objects_init)(self.session, template.as_mut_ptr(), 2) })?;
+
+        let mut handle = 0;
+        let mut count = 0;
+        let found =
+            check(unsafe { (functions.find_objects)(self.session, &mut handle, 1, &mut count) });
+
+        check(unsafe { (functions.find_objects_final)(self.session) })?;
+        found?;
+
+        if count == 0 {
+            return Err(Pkcs11Error::KeyNotFound);
+        }
+
+        Ok(handle)
+    }
+
+    // the usual two calls: the first asks for the output length, the second fills the buffer
+    fn output(&self, call: impl Fn(*mut u8, *mut Ulong) -> Rv) -> Result<Vec<u8>, Pkcs11Error> {
+        let mut length = 0;
+        check(call(null_mut(), &mut length))?;
+
+        let mut output = vec![0u8; length as usize];
+        check(call(output.as_mut_ptr(), &mut length))?;
+        output.truncate(length as usize);
+
+        Ok(output)
+    }
+
+    fn sign(&self, key: Handle, msg: &[u8]) -> Result<Vec<u8>, Pkcs11Error> {
+        let functions = self.functions();
+
+        let mut mechanism = Mechanism {
+            kind: CKM_EDDSA,
+            parameter: null_mut(),
+            length: 0,
+        };
+
+        check(unsafe { (functions.sign_init)(self.session, &mut mechanism, key) })?;
+
+        self.output(|output, length| unsafe {
+            (functions.sign)(
+                self.session,
+                msg.as_ptr(),
+                msg.len() as Ulong,
+                output,
+                length,
+            )
+        })
+    }
+
+    fn aes_gcm(
+        &self,
+        key: Handle,
+        iv: &[u8],
+        ad: &[u8],
+        data: &[u8],
+        encrypt: bool,
+    ) -> Result<Vec<u8>, Pkcs11Error> {
+        let functions = self.functions();
+
+        let mut params = GcmParams {
+            iv: iv.as_ptr() as *mut u8,
+            iv_length: iv.len() as Ulong,
+            iv_bits: (iv.len() * 8) as Ulong,
+            ad: ad.as_ptr() as *mut u8,
+            ad_length: ad.len() as Ulong,
+            tag_bits: 128,
+        };
+        let mut mechanism = Mechanism {
+            kind: CKM_AES_GCM,
+            parameter: &mut params as *mut GcmParams as *mut c_void,
+            length: std::mem::size_of::<GcmParams>() as Ulong,
+        };
+
+        let (init, run) = if encrypt {
+            (functions.encrypt_init, functions.encrypt)
+        } else {
+            (functions.decrypt_init, functions.decrypt)
+        };
+
+        check(unsafe { init(self.session, &mut mechanism, key) })?;
+
+        self.output(|output, length| unsafe {
+            run(
+                self.session,
+                data.as_ptr(),
+                data.len() as Ulong,
+                output,
+                length,
+            )
+        })
+    }
+
+    /// The Ed25519 private key labelled `label`.
+    pub fn signing_key(&self, label: &str) -> Result<Pkcs11SigningKey<'_>, Pkcs11Error> {
+        Ok(Pkcs11SigningKey {
+            token: self,
+            handle: self.find(CKO_PRIVATE_KEY, label)?,
+        })
+    }
+
+    /// A `KeyProvider` wrapping data keys with the AES key labelled `kek` and signing with the
+    /// Ed25519 private key labelled `signing_key`.
+    pub fn key_provider(
+        &self,
+        kek: &str,
+        signing_key: &str,
+    ) -> Result<Pkcs11KeyProvider<'_>, Pkcs11Error> {
+        Ok(Pkcs11KeyProvider {
+            token: self,
+            kek: self.find(CKO_SECRET_KEY, kek)?,
+            signing_key: self.find(CKO_PRIVATE_KEY, signing_key)?,
+        })
+    }
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        let functions = self.functions();
+
+        unsafe {
+            (functions.close_session)(self.session);
+
+            if self.finalize {
+                (functions.finalize)(null_mut());
+            }
+        }
+    }
+}
+
+/// An Ed25519 signing key that never leaves the token.
+#[derive(Debug)]
+pub struct Pkcs11SigningKey<'a> {
+    token: &'a Token,
+    handle: Handle,
+}
+
+impl Pkcs11SigningKey<'_> {
+    pub fn sign(&self, msg: &[u8]) -> Result<Signature, Pkcs11Error> {
+        self.token
+            .sign(self.handle, msg)?
+            .try_into()
+            .map_err(|_| Pkcs11Error::Malformed)
+    }
+}
+
+/// Wrapped keys are `IV || AES-GCM ciphertext` under the token's key-encryption key.
+#[derive(Debug)]
+pub struct Pkcs11KeyProvider<'a> {
+    token: &'a Token,
+    kek: Handle,
+    signing_key: Handle,
+}
+
+impl KeyProvider for Pkcs11KeyProvider<'_> {
+    type Error = Pkcs11Error;
+
+    fn wrap_key(&self, key: &SymmetricKey, ad: &[u8]) -> Result<Vec<u8>, Pkcs11Error> {
+        let iv: [u8; GCM_IV_SIZE] = random_bytes();
+
+        let mut wrapped = iv.to_vec();
+        wrapped.extend(
+            self.token
+                .aes_gcm(self.kek, &iv, ad, key.as_bytes(), true)?,
+        );
+
+        Ok(wrapped)
+    }
+
+    fn unwrap_key(&self, wrapped: &[u8], ad: &[u8]) -> Result<SymmetricKey, Pkcs11Error> {
+        if wrapped.len() < GCM_IV_SIZE {
+            return Err(Pkcs11Error::Malformed);
+        }
+
+        let (iv, ct) = wrapped.split_at(GCM_IV_SIZE);
+        let mut key = self.token.aes_gcm(self.kek, iv, ad, ct, false)?;
+        let unwrapped = SymmetricKey::new(&key).map_err(|_| Pkcs11Error::Malformed);
+        key.zeroize();
+
+        unwrapped
+    }
+
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Pkcs11Error> {
+        self.token.sign(self.signing_key, msg)
+    }
+}
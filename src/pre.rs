@@ -0,0 +1,296 @@
+// Unidirectional proxy re-encryption after Umbral (Nuñez, 2017), on the prime-order subgroup of
+// edwards25519 and without the threshold splitting: one re-encryption key, one proxy. A capsule
+// carries the KEM part of a ciphertext; the proxy transforms only the capsule, so it never sees a
+// key or a plaintext. As with Umbral, a proxy colluding with the receiver can recover the
+// delegator's secret key.
+
+use crate::aeads::XChaCha20Poly1305;
+use crate::ecc::edwards::EdwardsPoint;
+use crate::ecc::scalar::Scalar;
+use crate::hashes::sha256::Sha256;
+use crate::hashes::sha512::Sha512;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{random_bytes, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const PUBLIC_KEY_SIZE: usize = 32;
+pub const CAPSULE_SIZE: usize = 96;
+pub const FRAGMENT_SIZE: usize = 96;
+
+const HEADER_SIZE: usize = CAPSULE_SIZE + XChaCha20Poly1305::NONCE_SIZE;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreError {
+    InvalidKey,
+    Malformed,
+    InvalidCapsule,
+    InvalidMac,
+}
+
+impl std::fmt::Display for PreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreError::InvalidKey => write!(f, "The public key is not a valid curve point."),
+            PreError::Malformed => write!(f, "The ciphertext is too short to be valid."),
+            PreError::InvalidCapsule => write!(f, "The capsule does not verify."),
+            PreError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This message may be tampered with."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PreError {}
+
+fn random_scalar() -> Scalar {
+    let mut bytes: [u8; 64] = random_bytes();
+    let scalar = Scalar::from_bytes_mod_order_wide(&bytes);
+    bytes.zeroize();
+
+    scalar
+}
+
+fn hash_to_scalar(label: &[u8], points: &[&[u8; 32]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(label);
+
+    for point in points {
+        hasher.update(*point);
+    }
+
+    Scalar::from_bytes_mod_order_wide(&hasher.digest())
+}
+
+// a point from the wire, rejected unless it is a non-identity point of the prime-order subgroup
+fn point(bytes: &[u8]) -> Option<EdwardsPoint> {
+    let point = EdwardsPoint::decompress(bytes.try_into().ok()?)?;
+
+    if point == EdwardsPoint::identity() || !point.is_torsion_free() {
+        return None;
+    }
+
+    Some(point)
+}
+
+fn derive_key(shared: &EdwardsPoint, capsule: &[u8]) -> XChaCha20Poly1305 {
+    let mut shared = shared.compress();
+    let hkdf = Hkdf::<Sha256>::extract(b"raycrypt pre", &shared);
+    shared.zeroize();
+
+    let mut key = hkdf.expand(capsule, 32).unwrap();
+    let aead = XChaCha20Poly1305::new(&key);
+    key.zeroize();
+
+    aead
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey(Scalar);
+
+redacted_debug!(SecretKey, "<32 bytes, redacted>");
+
+impl SecretKey {
+    pub fn generate() -> SecretKey {
+        SecretKey(random_scalar())
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<SecretKey> {
+        let scalar = Scalar::from_canonical_bytes(bytes)?;
+
+        if scalar.is_zero() {
+            return None;
+        }
+
+        Some(SecretKey(scalar))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(EdwardsPoint::mul_base(&self.0))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PublicKey(EdwardsPoint);
+
+impl PublicKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, PreError> {
+        point(bytes).map(PublicKey).ok_or(PreError::InvalidKey)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.compress()
+    }
+}
+
+impl std::fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PublicKey({:?})", self.to_bytes())
+    }
+}
+
+impl PartialEq for PublicKey {
+    fn eq(&self, other: &PublicKey) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for PublicKey {}
+
+// (E, V, s) with E = rG, V = uG and s = u + r * H(E, V), so that anyone can check
+// sG = V + H(E, V) E without learning r or u
+struct Capsule {
+    e: EdwardsPoint,
+    v: EdwardsPoint,
+}
+
+impl Capsule {
+    // the capsule and the scalar r + u that the shared point is derived from
+    fn generate() -> (Scalar, [u8; CAPSULE_SIZE]) {
+        let r = random_scalar();
+        let u = random_scalar();
+
+        let e = EdwardsPoint::mul_base(&r);
+        let v = EdwardsPoint::mul_base(&u);
+        let (e_bytes, v_bytes) = (e.compress(), v.compress());
+        let s = u + r * hash_to_scalar(b"raycrypt pre capsule", &[&e_bytes, &v_bytes]);
+
+        let mut bytes = [0u8; CAPSULE_SIZE];
+        bytes[..32].copy_from_slice(&e_bytes);
+        bytes[32..64].copy_from_slice(&v_bytes);
+        bytes[64..].copy_from_slice(&s.to_bytes());
+
+        (r + u, bytes)
+    }
+
+    fn parse(bytes: &[u8]) -> Result<Capsule, PreError> {
+        let e = point(&bytes[..32]).ok_or(PreError::InvalidCapsule)?;
+        let v = point(&bytes[32..64]).ok_or(PreError::InvalidCapsule)?;
+        let s = Scalar::from_canonical_bytes(bytes[64..96].try_into().unwrap())
+            .ok_or(PreError::InvalidCapsule)?;
+
+        let h = hash_to_scalar(
+            b"raycrypt pre capsule",
+            &[
+                bytes[..32].try_into().unwrap(),
+                bytes[32..64].try_into().unwrap(),
+            ],
+        );
+
+        if EdwardsPoint::mul_base(&s) != v.add(&e.mul(&h)) {
+            return Err(PreError::InvalidCapsule);
+        }
+
+        Ok(Capsule { e, v })
+    }
+}
+
+/// Lets a proxy turn ciphertexts for the delegator into ciphertexts for one receiver.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ReEncryptionKey {
+    rk: Scalar,
+    #[zeroize(skip)]
+    precursor: [u8; 32],
+}
+
+redacted_debug!(ReEncryptionKey, "<redacted>");
+
+// d = H(X, B, xB), which only the delegator (knowing x) and the receiver (knowing b) can compute
+fn delegation_scalar(precursor: &[u8; 32], receiver: &PublicKey, shared: &EdwardsPoint) -> Scalar {
+    hash_to_scalar(
+        b"raycrypt pre delegation",
+        &[precursor, &receiver.to_bytes(), &shared.compress()],
+    )
+}
+
+impl ReEncryptionKey {
+    /// Made by the delegator, who hands it to the proxy.
+    pub fn generate(delegator: &SecretKey, receiver: &PublicKey) -> ReEncryptionKey {
+        let mut x = random_scalar();
+        let precursor = EdwardsPoint::mul_base(&x).compress();
+        let mut d = delegation_scalar(&precursor, receiver, &receiver.0.mul(&x));
+        let rk = delegator.0 * d.invert();
+        x.zeroize();
+        d.zeroize();
+
+        ReEncryptionKey { rk, precursor }
+    }
+}
+
+/// Encrypts to `recipient` as `capsule || nonce || ciphertext`.
+pub fn encrypt(recipient: &PublicKey, msg: &[u8], ad: &[u8]) -> Vec<u8> {
+    let (mut secret, capsule) = Capsule::generate();
+    let aead = derive_key(&recipient.0.mul(&secret), &capsule);
+    secret.zeroize();
+
+    let nonce = XChaCha20Poly1305::generate_nonce();
+
+    let mut output = capsule.to_vec();
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&aead.encrypt(msg, &nonce, &[&capsule[..], ad].concat()));
+
+    output
+}
+
+fn open(aead: XChaCha20Poly1305, ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, PreError> {
+    let (capsule, rest) = ct.split_at(CAPSULE_SIZE);
+    let (nonce, ct) = rest.split_at(XChaCha20Poly1305::NONCE_SIZE);
+
+    aead.decrypt(ct, nonce, &[capsule, ad].concat())
+        .map_err(|_| PreError::InvalidMac)
+}
+
+/// Decrypts a ciphertext made for `key` by `encrypt`.
+pub fn decrypt(key: &SecretKey, ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, PreError> {
+    if ct.len() < HEADER_SIZE + XChaCha20Poly1305::TAG_SIZE {
+        return Err(PreError::Malformed);
+    }
+
+    let capsule = Capsule::parse(&ct[..CAPSULE_SIZE])?;
+    let shared = capsule.e.add(&capsule.v).mul(&key.0);
+
+    open(derive_key(&shared, &ct[..CAPSULE_SIZE]), ct, ad)
+}
+
+/// Run by the proxy: checks the capsule and prepends a capsule fragment `E' || V' || X` for the
+/// receiver. The ciphertext itself passes through untouched.
+pub fn reencrypt(key: &ReEncryptionKey, ct: &[u8]) -> Result<Vec<u8>, PreError> {
+    if ct.len() < HEADER_SIZE + XChaCha20Poly1305::TAG_SIZE {
+        return Err(PreError::Malformed);
+    }
+
+    let capsule = Capsule::parse(&ct[..CAPSULE_SIZE])?;
+
+    let mut output = Vec::with_capacity(FRAGMENT_SIZE + ct.len());
+    output.extend_from_slice(&capsule.e.mul(&key.rk).compress());
+    output.extend_from_slice(&capsule.v.mul(&key.rk).compress());
+    output.extend_from_slice(&key.precursor);
+    output.extend_from_slice(ct);
+
+    Ok(output)
+}
+
+/// Decrypts the output of `reencrypt` with the receiver's key.
+pub fn decrypt_reencrypted(key: &SecretKey, ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, PreError> {
+    if ct.len() < FRAGMENT_SIZE + HEADER_SIZE + XChaCha20Poly1305::TAG_SIZE {
+        return Err(PreError::Malformed);
+    }
+
+    let (fragment, ct) = ct.split_at(FRAGMENT_SIZE);
+    Capsule::parse(&ct[..CAPSULE_SIZE])?;
+
+    let e = point(&fragment[..32]).ok_or(PreError::InvalidCapsule)?;
+    let v = point(&fragment[32..64]).ok_or(PreError::InvalidCapsule)?;
+    let precursor: [u8; 32] = fragment[64..].try_into().unwrap();
+    let x = point(&precursor).ok_or(PreError::InvalidCapsule)?;
+
+    let receiver = key.public_key();
+    let d = delegation_scalar(&precursor, &receiver, &x.mul(&key.0));
+    let shared = e.add(&v).mul(&d);
+
+    open(derive_key(&shared, &ct[..CAPSULE_SIZE]), ct, ad)
+}
@@ -0,0 +1,11 @@
+pub mod prekeys;
+pub mod quic;
+pub mod record;
+pub mod replay;
+pub mod sender_key;
+pub mod ssh_agent;
+pub mod ticket;
+pub mod tls13;
+pub mod transcript;
+pub mod treekem;
+pub mod wireguard;
@@ -0,0 +1,292 @@
+// The prekey bookkeeping of an X3DH-style asynchronous key agreement. A client keeps an Ed25519
+// identity, a medium-term X25519 signed prekey that it rotates on a schedule, and a batch of
+// X25519 one-time prekeys. It uploads the public halves to a directory, which hands each
+// one-time prekey out at most once and reports when the client should upload more.
+//
+// A signed prekey is signed over "raycrypt signed prekey" || id (u32, big-endian) || public key.
+
+use crate::ecc::ed25519::{Signature, SigningKey, VerifyingKey};
+use crate::ecc::x25519::{self, PrivateKey, PublicKey};
+use crate::utils::redacted_debug;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+const SIGNATURE_CONTEXT: &[u8] = b"raycrypt signed prekey";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrekeyError {
+    InvalidSignature,
+    UnknownIdentity,
+    DuplicateId,
+}
+
+impl std::fmt::Display for PrekeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrekeyError::InvalidSignature => {
+                write!(f, "The signed prekey was not signed by the identity key.")
+            }
+            PrekeyError::UnknownIdentity => {
+                write!(f, "No prekeys were published for this identity.")
+            }
+            PrekeyError::DuplicateId => write!(f, "A prekey with this id was already uploaded."),
+        }
+    }
+}
+
+impl std::error::Error for PrekeyError {}
+
+fn signed_message(id: u32, public: &PublicKey) -> Vec<u8> {
+    [SIGNATURE_CONTEXT, &id.to_be_bytes(), public].concat()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OneTimePrekey {
+    pub id: u32,
+    pub public: PublicKey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedPrekey {
+    pub id: u32,
+    pub public: PublicKey,
+    pub signature: Signature,
+    pub created: SystemTime,
+}
+
+impl SignedPrekey {
+    pub fn verify(&self, identity: &VerifyingKey) -> Result<(), PrekeyError> {
+        identity
+            .verify(&signed_message(self.id, &self.public), &self.signature)
+            .map_err(|_| PrekeyError::InvalidSignature)
+    }
+}
+
+/// What an initiator fetches to start a session. Without a one-time prekey the session still
+/// works, but loses some protection against replay of its first message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrekeyBundle {
+    pub identity: VerifyingKey,
+    pub signed_prekey: SignedPrekey,
+    pub one_time_prekey: Option<OneTimePrekey>,
+}
+
+struct SignedPrekeyPair {
+    public: SignedPrekey,
+    private: PrivateKey,
+}
+
+/// The client's private half: its identity, signed prekeys and unused one-time prekeys.
+pub struct PrekeyStore {
+    identity: SigningKey,
+    rotation_interval: Duration,
+    // the current signed prekey last, with older ones kept until `prune` for late initiators
+    signed: Vec<SignedPrekeyPair>,
+    one_time: HashMap<u32, PrivateKey>,
+    next_id: u32,
+}
+
+redacted_debug!(PrekeyStore, "<redacted>");
+
+impl PrekeyStore {
+    /// Starts with a signed prekey created at `now`, which is replaced every `rotation_interval`.
+    pub fn new(identity: SigningKey, rotation_interval: Duration, now: SystemTime) -> PrekeyStore {
+        let mut store = PrekeyStore {
+            identity,
+            rotation_interval,
+            signed: Vec::new(),
+            one_time: HashMap::new(),
+            next_id: 0,
+        };
+        store.rotate_signed_prekey(now);
+
+        store
+    }
+
+    pub fn identity(&self) -> VerifyingKey {
+        self.identity.verifying_key()
+    }
+
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        id
+    }
+
+    pub fn signed_prekey(&self) -> SignedPrekey {
+        self.signed.last().unwrap().public
+    }
+
+    /// When the current signed prekey is due to be replaced.
+    pub fn rotation_due(&self) -> SystemTime {
+        self.signed_prekey().created + self.rotation_interval
+    }
+
+    pub fn needs_rotation(&self, now: SystemTime) -> bool {
+        now >= self.rotation_due()
+    }
+
+    /// Makes a new current signed prekey, keeping the old ones until they are pruned.
+    pub fn rotate_signed_prekey(&mut self, now: SystemTime) -> SignedPrekey {
+        let id = self.next_id();
+        let private = x25519::keygen(None);
+        let public = private.public_key();
+
+        let signed = SignedPrekey {
+            id,
+            public,
+            signature: self.identity.sign(&signed_message(id, &public)),
+            created: now,
+        };
+        self.signed.push(SignedPrekeyPair {
+            public: signed,
+            private,
+        });
+
+        signed
+    }
+
+    /// Forgets signed prekeys that were replaced more than `grace` ago, so sessions started
+    /// against them can no longer be completed and their private keys are wiped.
+    pub fn prune(&mut self, now: SystemTime, grace: Duration) {
+        // each signed prekey was replaced when the one after it was created
+        let current = self.signed.len() - 1;
+        let keep_from = (0..current)
+            .find(|&i| self.signed[i + 1].public.created + grace > now)
+            .unwrap_or(current);
+
+        self.signed.drain(..keep_from);
+    }
+
+    /// Generates `count` one-time prekeys and returns their public halves for uploading.
+    pub fn generate_one_time_prekeys(&mut self, count: usize) -> Vec<OneTimePrekey> {
+        (0..count)
+            .map(|_| {
+                let id = self.next_id();
+                let private = x25519::keygen(None);
+                let public = private.public_key();
+                self.one_time.insert(id, private);
+
+                OneTimePrekey { id, public }
+            })
+            .collect()
+    }
+
+    pub fn one_time_prekeys_remaining(&self) -> usize {
+        self.one_time.len()
+    }
+
+    pub fn signed_prekey_private(&self, id: u32) -> Option<&PrivateKey> {
+        self.signed
+            .iter()
+            .find(|pair| pair.public.id == id)
+            .map(|pair| &pair.private)
+    }
+
+    /// Removes and returns a one-time prekey's private half, so it is used at most once.
+    pub fn take_one_time_prekey(&mut self, id: u32) -> Option<PrivateKey> {
+        self.one_time.remove(&id)
+    }
+}
+
+#[derive(Debug)]
+struct Published {
+    signed_prekey: SignedPrekey,
+    one_time: VecDeque<OneTimePrekey>,
+}
+
+/// The server's public half: the latest signed prekey and the unclaimed one-time prekeys of
+/// each identity.
+#[derive(Debug)]
+pub struct PrekeyDirectory {
+    identities: HashMap<VerifyingKey, Published>,
+    low_water_mark: usize,
+}
+
+impl PrekeyDirectory {
+    /// `low_water_mark` is the number of one-time prekeys below which a client is asked to
+    /// upload more.
+    pub fn new(low_water_mark: usize) -> PrekeyDirectory {
+        PrekeyDirectory {
+            identities: HashMap::new(),
+            low_water_mark,
+        }
+    }
+
+    /// Publishes or replaces an identity's signed prekey, after checking its signature.
+    pub fn publish_signed_prekey(
+        &mut self,
+        identity: VerifyingKey,
+        signed_prekey: SignedPrekey,
+    ) -> Result<(), PrekeyError> {
+        signed_prekey.verify(&identity)?;
+
+        self.identities
+            .entry(identity)
+            .and_modify(|published| published.signed_prekey = signed_prekey)
+            .or_insert_with(|| Published {
+                signed_prekey,
+                one_time: VecDeque::new(),
+            });
+
+        Ok(())
+    }
+
+    pub fn upload_one_time_prekeys(
+        &mut self,
+        identity: &VerifyingKey,
+        prekeys: &[OneTimePrekey],
+    ) -> Result<(), PrekeyError> {
+        let published = self
+            .identities
+            .get_mut(identity)
+            .ok_or(PrekeyError::UnknownIdentity)?;
+
+        for (i, prekey) in prekeys.iter().enumerate() {
+            let duplicate = published.one_time.iter().any(|other| other.id == prekey.id)
+                || prekeys[..i].iter().any(|other| other.id == prekey.id);
+
+            if duplicate {
+                return Err(PrekeyError::DuplicateId);
+            }
+        }
+
+        published.one_time.extend(prekeys);
+
+        Ok(())
+    }
+
+    /// Hands out a bundle, claiming the oldest unclaimed one-time prekey so no other initiator
+    /// gets it.
+    pub fn fetch_bundle(&mut self, identity: &VerifyingKey) -> Result<PrekeyBundle, PrekeyError> {
+        let published = self
+            .identities
+            .get_mut(identity)
+            .ok_or(PrekeyError::UnknownIdentity)?;
+
+        Ok(PrekeyBundle {
+            identity: *identity,
+            signed_prekey: published.signed_prekey,
+            one_time_prekey: published.one_time.pop_front(),
+        })
+    }
+
+    pub fn one_time_prekeys_remaining(&self, identity: &VerifyingKey) -> usize {
+        self.identities
+            .get(identity)
+            .map_or(0, |published| published.one_time.len())
+    }
+
+    /// How many one-time prekeys the client should upload to get back to `target`, or zero
+    /// while it has at least the low water mark.
+    pub fn replenish_count(&self, identity: &VerifyingKey, target: usize) -> usize {
+        let remaining = self.one_time_prekeys_remaining(identity);
+
+        if remaining >= self.low_water_mark {
+            0
+        } else {
+            target.saturating_sub(remaining)
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::ciphers::chacha::ChaCha20;
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::protocols::tls13::hkdf_expand_label;
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const INITIAL_SALT_V1: [u8; 20] = [
+    0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad,
+    0xcc, 0xbb, 0x7f, 0x0a,
+];
+
+// header protection samples 16 bytes of the packet's ciphertext
+pub const SAMPLE_LENGTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuicError {
+    /// The key is not one an AEAD of RFC 9001 uses.
+    InvalidKeyLength,
+    /// The packet is too short for its sample or packet number.
+    Malformed,
+}
+
+impl std::fmt::Display for QuicError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuicError::InvalidKeyLength => write!(f, "The QUIC key length is invalid."),
+            QuicError::Malformed => write!(f, "The QUIC packet is too short."),
+        }
+    }
+}
+
+impl std::error::Error for QuicError {}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct InitialSecrets {
+    pub client: Vec<u8>,
+    pub server: Vec<u8>,
+}
+
+redacted_debug!(InitialSecrets, "<redacted>");
+
+pub fn initial_secrets(connection_id: &[u8]) -> InitialSecrets {
+    let initial = Hkdf::<Sha256>::extract(&INITIAL_SALT_V1, connection_id);
+
+    // the labels and lengths here and in packet_keys are fixed or checked, so expanding cannot fail
+
+    InitialSecrets {
+        client: hkdf_expand_label::<Sha256>(initial.prk(), "client in", &[], 32).unwrap(),
+        server: hkdf_expand_label::<Sha256>(initial.prk(), "server in", &[], 32).unwrap(),
+    }
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct PacketKeys {
+    pub key: Vec<u8>,
+    pub iv: Vec<u8>,
+    pub hp: Vec<u8>,
+}
+
+redacted_debug!(PacketKeys, "<redacted>");
+
+/// `key_length` is 16 for AES-128-GCM and 32 for AES-256-GCM and ChaCha20-Poly1305.
+pub fn packet_keys(secret: &[u8], key_length: usize) -> Result<PacketKeys, QuicError> {
+    if key_length != 16 && key_length != 32 {
+        return Err(QuicError::InvalidKeyLength);
+    }
+
+    Ok(PacketKeys {
+        key: hkdf_expand_label::<Sha256>(secret, "quic key", &[], key_length).unwrap(),
+        iv: hkdf_expand_label::<Sha256>(secret, "quic iv", &[], 12).unwrap(),
+        hp: hkdf_expand_label::<Sha256>(secret, "quic hp", &[], key_length).unwrap(),
+    })
+}
+
+/// Only the first `SAMPLE_LENGTH` bytes of `sample` are used.
+pub fn chacha20_header_mask(hp_key: &[u8], sample: &[u8]) -> Result<[u8; 5], QuicError> {
+    if hp_key.len() != 32 {
+        return Err(QuicError::InvalidKeyLength);
+    }
+
+    if sample.len() < SAMPLE_LENGTH {
+        return Err(QuicError::Malformed);
+    }
+
+    let counter = u32::from_le_bytes(sample[..4].try_into().unwrap());
+    let keystream = ChaCha20::new(hp_key).keystream(&sample[4..SAMPLE_LENGTH], counter);
+
+    Ok(keystream[..5].try_into().unwrap())
+}
+
+fn first_byte_mask(first: u8) -> u8 {
+    if first & 0x80 != 0 {
+        0x0f
+    } else {
+        0x1f
+    }
+}
+
+// the packet number bytes, or an error if the header is too short to hold them
+fn packet_number(
+    header: &mut [u8],
+    pn_offset: usize,
+    pn_length: usize,
+) -> Result<&mut [u8], QuicError> {
+    if pn_offset == 0 {
+        return Err(QuicError::Malformed);
+    }
+
+    pn_offset
+        .checked_add(pn_length)
+        .and_then(|end| header.get_mut(pn_offset..end))
+        .ok_or(QuicError::Malformed)
+}
+
+pub fn protect_header(
+    header: &mut [u8],
+    pn_offset: usize,
+    mask: &[u8; 5],
+) -> Result<(), QuicError> {
+    let first = *header.first().ok_or(QuicError::Malformed)?;
+    let pn_length = (first & 0x03) as usize + 1;
+
+    for (byte, m) in packet_number(header, pn_offset, pn_length)?
+        .iter_mut()
+        .zip(&mask[1..])
+    {
+        *byte ^= m;
+    }
+
+    header[0] ^= mask[0] & first_byte_mask(first);
+
+    Ok(())
+}
+
+/// Returns the length of the packet number. A header too short for it is left untouched.
+pub fn unprotect_header(
+    header: &mut [u8],
+    pn_offset: usize,
+    mask: &[u8; 5],
+) -> Result<usize, QuicError> {
+    let first = *header.first().ok_or(QuicError::Malformed)?;
+    let unmasked = first ^ (mask[0] & first_byte_mask(first));
+    let pn_length = (unmasked & 0x03) as usize + 1;
+
+    for (byte, m) in packet_number(header, pn_offset, pn_length)?
+        .iter_mut()
+        .zip(&mask[1..])
+    {
+        *byte ^= m;
+    }
+
+    header[0] = unmasked;
+
+    Ok(pn_length)
+}
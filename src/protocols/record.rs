@@ -0,0 +1,308 @@
+use crate::aeads::ChaCha20Poly1305;
+use crate::hashes::sha256::Sha256;
+use crate::protocols::replay::{ReplayError, ReplayWindow};
+use crate::protocols::tls13::{next_traffic_secret, traffic_keys};
+use crate::utils::redacted_debug;
+use zeroize::Zeroize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    InvalidMac,
+    SequenceReused,
+    SequenceExhausted,
+    /// The record is further behind the newest one of its epoch than the replay window reaches.
+    TooOld,
+    StaleEpoch,
+    UnknownEpoch,
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This message may be tampered with."
+            ),
+            RecordError::SequenceReused => {
+                write!(f, "The record sequence number has already been used.")
+            }
+            RecordError::SequenceExhausted => {
+                write!(
+                    f,
+                    "The record sequence number is outside the current key epoch."
+                )
+            }
+            RecordError::TooOld => {
+                write!(
+                    f,
+                    "The record sequence number is older than the replay window."
+                )
+            }
+            RecordError::StaleEpoch => write!(f, "The record belongs to a retired key epoch."),
+            RecordError::UnknownEpoch => {
+                write!(f, "The record belongs to a key epoch that has not started.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl From<ReplayError> for RecordError {
+    fn from(error: ReplayError) -> RecordError {
+        match error {
+            ReplayError::Duplicate => RecordError::SequenceReused,
+            ReplayError::TooOld => RecordError::TooOld,
+        }
+    }
+}
+
+pub struct RecordProtection {
+    secret: Vec<u8>,
+    aead: ChaCha20Poly1305,
+    iv: [u8; 12],
+    sequence: u64,
+    epoch: u64,
+    rekey_after: u64,
+    // the sequence numbers opened so far in the current epoch
+    window: ReplayWindow,
+}
+
+redacted_debug!(RecordProtection, "<redacted>");
+
+impl RecordProtection {
+    pub fn new(secret: &[u8], rekey_after: u64) -> RecordProtection {
+        assert!(rekey_after > 0);
+
+        let (aead, iv) = Self::keys(secret);
+
+        RecordProtection {
+            secret: secret.to_vec(),
+            aead,
+            iv,
+            sequence: 0,
+            epoch: 0,
+            rekey_after,
+            window: ReplayWindow::default(),
+        }
+    }
+
+    fn keys(secret: &[u8]) -> (ChaCha20Poly1305, [u8; 12]) {
+        // fixed lengths, so this cannot fail
+        let (mut key, mut iv) = traffic_keys::<Sha256>(secret, 32, 12).unwrap();
+        let aead = ChaCha20Poly1305::new(&key);
+        let static_iv = iv[..].try_into().unwrap();
+
+        key.zeroize();
+        iv.zeroize();
+
+        (aead, static_iv)
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn nonce(&self, sequence: u64) -> [u8; 12] {
+        record_nonce(&self.iv, sequence)
+    }
+
+    fn install(&mut self, secret: Vec<u8>, aead: ChaCha20Poly1305, iv: [u8; 12]) {
+        self.secret.zeroize();
+        self.secret = secret;
+        self.aead = aead;
+        self.iv = iv;
+        self.sequence = 0;
+        self.epoch += 1;
+        self.window = ReplayWindow::new(self.window.size());
+    }
+
+    /// Moves to the next key epoch immediately, as a key update does.
+    pub fn update_keys(&mut self) {
+        let secret = next_traffic_secret::<Sha256>(&self.secret);
+        let (aead, iv) = Self::keys(&secret);
+
+        self.install(secret, aead, iv);
+    }
+
+    fn advance(&mut self, sequence: u64) {
+        self.sequence = sequence + 1;
+
+        if self.sequence == self.rekey_after {
+            self.update_keys();
+        }
+    }
+
+    pub fn seal(&mut self, msg: &[u8], ad: &[u8]) -> (u64, Vec<u8>) {
+        let sequence = self.sequence;
+        let ct = self.aead.encrypt(msg, &self.nonce(sequence), ad).into_vec();
+
+        self.advance(sequence);
+
+        (sequence, ct)
+    }
+
+    // marks a record that authenticated as seen, after the window has checked it
+    fn accept(&mut self, sequence: u64) {
+        self.window.update(sequence).unwrap();
+        self.sequence = self.sequence.max(sequence + 1);
+    }
+
+    fn open_current(
+        &mut self,
+        sequence: u64,
+        ct: &[u8],
+        ad: &[u8],
+    ) -> Result<Vec<u8>, RecordError> {
+        if sequence >= self.rekey_after {
+            return Err(RecordError::SequenceExhausted);
+        }
+
+        self.window.check(sequence)?;
+
+        let msg = self
+            .aead
+            .decrypt(ct, &self.nonce(sequence), ad)
+            .map_err(|_| RecordError::InvalidMac)?;
+
+        self.accept(sequence);
+
+        Ok(msg)
+    }
+
+    /// Opens a record of the current epoch. Records may arrive out of order, as long as they are
+    /// within the replay window of the newest one; each is only accepted once. Opening the last
+    /// record of the epoch moves to the next one, as `seal` does.
+    pub fn open(&mut self, sequence: u64, ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, RecordError> {
+        let msg = self.open_current(sequence, ct, ad)?;
+
+        if self.sequence == self.rekey_after {
+            self.update_keys();
+        }
+
+        Ok(msg)
+    }
+
+    /// `seal`, also returning the epoch the record was sealed in, for datagram protocols that
+    /// carry it alongside the sequence number.
+    pub fn seal_in_epoch(&mut self, msg: &[u8], ad: &[u8]) -> (u64, u64, Vec<u8>) {
+        let epoch = self.epoch;
+        let (sequence, ct) = self.seal(msg, ad);
+
+        (epoch, sequence, ct)
+    }
+
+    /// Opens a record tagged with its epoch. Records from the next epoch move the receiver to
+    /// it, but only once one of them authenticates; older epochs are rejected, as are epochs
+    /// further ahead. Within an epoch, records are checked against a replay window as in `open`,
+    /// and the receiver only changes epoch when the sender's records do.
+    pub fn open_in_epoch(
+        &mut self,
+        epoch: u64,
+        sequence: u64,
+        ct: &[u8],
+        ad: &[u8],
+    ) -> Result<Vec<u8>, RecordError> {
+        if epoch < self.epoch {
+            return Err(RecordError::StaleEpoch);
+        }
+
+        if epoch == self.epoch {
+            return self.open_current(sequence, ct, ad);
+        }
+
+        if epoch > self.epoch + 1 {
+            return Err(RecordError::UnknownEpoch);
+        }
+
+        if sequence >= self.rekey_after {
+            return Err(RecordError::SequenceExhausted);
+        }
+
+        let secret = next_traffic_secret::<Sha256>(&self.secret);
+        let (aead, iv) = Self::keys(&secret);
+
+        let msg = aead
+            .decrypt(ct, &record_nonce(&iv, sequence), ad)
+            .map_err(|_| RecordError::InvalidMac)?;
+
+        self.install(secret, aead, iv);
+        self.accept(sequence);
+
+        Ok(msg)
+    }
+}
+
+fn record_nonce(iv: &[u8; 12], sequence: u64) -> [u8; 12] {
+    let mut nonce = *iv;
+
+    for (n, s) in nonce[4..].iter_mut().zip(sequence.to_be_bytes()) {
+        *n ^= s;
+    }
+
+    nonce
+}
+
+impl Drop for RecordProtection {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+        self.iv.zeroize();
+    }
+}
+
+/// Nonces for TLS-style records, built as in RFC 7905: the 12-byte IV xor the 64-bit sequence
+/// number, left-padded and big-endian. Sequence numbers only ever move forward.
+#[derive(Debug)]
+pub struct NonceSequence {
+    iv: [u8; 12],
+    next: u64,
+    exhausted: bool,
+}
+
+impl NonceSequence {
+    pub fn new(iv: &[u8; 12]) -> NonceSequence {
+        NonceSequence {
+            iv: *iv,
+            next: 0,
+            exhausted: false,
+        }
+    }
+
+    pub fn nonce(&self, sequence: u64) -> [u8; 12] {
+        record_nonce(&self.iv, sequence)
+    }
+
+    fn take(&mut self, sequence: u64) -> [u8; 12] {
+        match sequence.checked_add(1) {
+            Some(next) => self.next = next,
+            None => self.exhausted = true,
+        }
+
+        self.nonce(sequence)
+    }
+
+    /// The sequence number and nonce for the next record to send.
+    pub fn next_nonce(&mut self) -> Result<(u64, [u8; 12]), RecordError> {
+        if self.exhausted {
+            return Err(RecordError::SequenceExhausted);
+        }
+
+        let sequence = self.next;
+
+        Ok((sequence, self.take(sequence)))
+    }
+
+    /// The nonce for a received record, provided its sequence number is past every one accepted
+    /// before it.
+    pub fn accept(&mut self, sequence: u64) -> Result<[u8; 12], RecordError> {
+        if self.exhausted || sequence < self.next {
+            return Err(RecordError::SequenceReused);
+        }
+
+        Ok(self.take(sequence))
+    }
+}
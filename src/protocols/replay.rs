@@ -0,0 +1,99 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    Duplicate,
+    TooOld,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Duplicate => write!(f, "The sequence number has already been seen."),
+            ReplayError::TooOld => {
+                write!(f, "The sequence number is older than the replay window.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Remembers which of the last `size` sequence numbers have been seen, so records can arrive out
+/// of order but only once. `RecordProtection` keeps one for each key epoch.
+#[derive(Clone, Copy, Debug)]
+pub struct ReplayWindow {
+    highest: Option<u64>,
+    bitmap: u128,
+    size: u64,
+}
+
+impl ReplayWindow {
+    pub fn new(size: u64) -> ReplayWindow {
+        assert!(size > 0 && size <= 128);
+
+        ReplayWindow {
+            highest: None,
+            bitmap: 0,
+            size,
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn highest(&self) -> Option<u64> {
+        self.highest
+    }
+
+    pub fn check(&self, sequence: u64) -> Result<(), ReplayError> {
+        let highest = match self.highest {
+            Some(highest) if sequence <= highest => highest,
+            _ => return Ok(()),
+        };
+
+        let offset = highest - sequence;
+
+        if offset >= self.size {
+            return Err(ReplayError::TooOld);
+        }
+
+        if self.bitmap >> offset & 1 == 1 {
+            return Err(ReplayError::Duplicate);
+        }
+
+        Ok(())
+    }
+
+    pub fn update(&mut self, sequence: u64) -> Result<(), ReplayError> {
+        self.check(sequence)?;
+
+        match self.highest {
+            Some(highest) if sequence <= highest => {
+                self.bitmap |= 1 << (highest - sequence);
+            }
+            highest => {
+                let shift = highest.map_or(128, |highest| sequence - highest);
+
+                self.bitmap = if shift >= 128 {
+                    0
+                } else {
+                    self.bitmap << shift
+                };
+                self.bitmap |= 1;
+                self.highest = Some(sequence);
+            }
+        }
+
+        if self.size < 128 {
+            self.bitmap &= (1 << self.size) - 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ReplayWindow {
+    fn default() -> ReplayWindow {
+        ReplayWindow::new(64)
+    }
+}
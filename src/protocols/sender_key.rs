@@ -0,0 +1,331 @@
+// Sender keys as in Signal's group messaging: each member keeps one symmetric chain for the
+// messages it sends and hands the chain's current state to every other member over their
+// pairwise channels. A group message is then encrypted once, whatever the group size, and each
+// message key is ratcheted forward from the chain so a leaked chain key exposes no earlier
+// message. Messages are signed with a per-chain Ed25519 key, since every member can derive the
+// message keys and so could otherwise forge them.
+
+use crate::aeads::ChaCha20Poly1305;
+use crate::ecc::ed25519::{self, SigningKey, VerifyingKey};
+use crate::hashes::sha256::Sha256;
+use crate::kdfs::hkdf::Hkdf;
+use crate::macs::hmac::hmac;
+use crate::utils::{random_bytes, redacted_debug};
+use std::collections::HashMap;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const DISTRIBUTION_LENGTH: usize = 4 + 4 + 32 + 32;
+
+/// How far ahead of the receiver's chain a message may be, and so how many skipped message keys
+/// a receiver holds at most.
+pub const MAX_SKIP: u32 = 2000;
+
+const HEADER_LENGTH: usize = 8;
+const OVERHEAD: usize = HEADER_LENGTH + ChaCha20Poly1305::TAG_SIZE + 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SenderKeyError {
+    Malformed,
+    UnknownChain,
+    InvalidSignature,
+    InvalidMac,
+    TooFarAhead,
+    DuplicateMessage,
+    ChainExhausted,
+}
+
+impl std::fmt::Display for SenderKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SenderKeyError::Malformed => write!(f, "The message is malformed."),
+            SenderKeyError::UnknownChain => {
+                write!(f, "The message was sent under a different sender key.")
+            }
+            SenderKeyError::InvalidSignature => {
+                write!(f, "The message was not signed by the sender.")
+            }
+            SenderKeyError::InvalidMac => write!(
+                f,
+                "Invalid MAC detected. This message may be tampered with."
+            ),
+            SenderKeyError::TooFarAhead => {
+                write!(f, "Too many messages from this sender were skipped.")
+            }
+            SenderKeyError::DuplicateMessage => {
+                write!(f, "The message was already received or has expired.")
+            }
+            SenderKeyError::ChainExhausted => {
+                write!(f, "The sender key has been used up and must be replaced.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SenderKeyError {}
+
+// one step of the chain: (message key, next chain key)
+fn ratchet(chain_key: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let mut message_key = hmac::<Sha256>(chain_key, &[1]);
+    let mut next = hmac::<Sha256>(chain_key, &[2]);
+
+    let output = (
+        message_key[..].try_into().unwrap(),
+        next[..].try_into().unwrap(),
+    );
+
+    message_key.zeroize();
+    next.zeroize();
+
+    output
+}
+
+fn message_aead(message_key: &[u8; 32]) -> ChaCha20Poly1305 {
+    let mut key = Hkdf::<Sha256>::extract(&[], message_key)
+        .expand(b"raycrypt sender key", ChaCha20Poly1305::KEY_SIZE)
+        .unwrap();
+    let aead = ChaCha20Poly1305::new(&key);
+    key.zeroize();
+
+    aead
+}
+
+/// The state a sender hands to each member so they can read its messages from `iteration`
+/// onwards: `chain id || iteration || chain key || signature key`, with the integers big-endian.
+/// It holds a secret, so it must only travel over a confidential pairwise channel.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SenderKeyDistribution {
+    chain_id: u32,
+    iteration: u32,
+    chain_key: [u8; 32],
+    #[zeroize(skip)]
+    verifying_key: VerifyingKey,
+}
+
+redacted_debug!(SenderKeyDistribution, "<redacted>");
+
+impl SenderKeyDistribution {
+    pub fn chain_id(&self) -> u32 {
+        self.chain_id
+    }
+
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    pub fn to_bytes(&self) -> [u8; DISTRIBUTION_LENGTH] {
+        let mut bytes = [0u8; DISTRIBUTION_LENGTH];
+        bytes[..4].copy_from_slice(&self.chain_id.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.iteration.to_be_bytes());
+        bytes[8..40].copy_from_slice(&self.chain_key);
+        bytes[40..].copy_from_slice(self.verifying_key.as_bytes());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<SenderKeyDistribution, SenderKeyError> {
+        if bytes.len() != DISTRIBUTION_LENGTH {
+            return Err(SenderKeyError::Malformed);
+        }
+
+        let verifying_key =
+            VerifyingKey::new(&bytes[40..]).map_err(|_| SenderKeyError::Malformed)?;
+
+        Ok(SenderKeyDistribution {
+            chain_id: u32::from_be_bytes(bytes[..4].try_into().unwrap()),
+            iteration: u32::from_be_bytes(bytes[4..8].try_into().unwrap()),
+            chain_key: bytes[8..40].try_into().unwrap(),
+            verifying_key,
+        })
+    }
+}
+
+/// The sending half, kept by one member for its own messages. Messages are
+/// `chain id || iteration || ciphertext || signature`. Replace it with a new one when the group
+/// loses a member, so the departed member cannot read what follows.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SenderKey {
+    chain_id: u32,
+    iteration: u32,
+    chain_key: [u8; 32],
+    signing_key: SigningKey,
+}
+
+redacted_debug!(SenderKey, "<redacted>");
+
+impl SenderKey {
+    pub fn generate() -> SenderKey {
+        SenderKey {
+            chain_id: u32::from_be_bytes(random_bytes()),
+            iteration: 0,
+            chain_key: random_bytes(),
+            signing_key: ed25519::keygen(None),
+        }
+    }
+
+    pub fn chain_id(&self) -> u32 {
+        self.chain_id
+    }
+
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// The current state of the chain. Members who receive it can read messages sent from now
+    /// on, but none sent before.
+    pub fn distribution(&self) -> SenderKeyDistribution {
+        SenderKeyDistribution {
+            chain_id: self.chain_id,
+            iteration: self.iteration,
+            chain_key: self.chain_key,
+            verifying_key: self.signing_key.verifying_key(),
+        }
+    }
+
+    /// Encrypts one message for the whole group and moves the chain forward.
+    pub fn encrypt(&mut self, msg: &[u8], ad: &[u8]) -> Result<Vec<u8>, SenderKeyError> {
+        if self.iteration == u32::MAX {
+            return Err(SenderKeyError::ChainExhausted);
+        }
+
+        let (mut message_key, next) = ratchet(&self.chain_key);
+        let aead = message_aead(&message_key);
+        message_key.zeroize();
+
+        let mut message = Vec::with_capacity(msg.len() + OVERHEAD);
+        message.extend_from_slice(&self.chain_id.to_be_bytes());
+        message.extend_from_slice(&self.iteration.to_be_bytes());
+
+        let ct = aead.encrypt(msg, &[0u8; 12], &[&message[..], ad].concat());
+        message.extend_from_slice(&ct);
+
+        let signature = self.signing_key.sign(&message);
+        message.extend_from_slice(&signature);
+
+        self.chain_key = next;
+        self.iteration += 1;
+
+        Ok(message)
+    }
+}
+
+/// The receiving half, kept by every other member for one sender's chain. Messages may arrive
+/// out of order: keys for skipped messages are kept, up to `MAX_SKIP` of them, until those
+/// messages turn up, and each is usable once.
+pub struct SenderKeyReceiver {
+    chain_id: u32,
+    iteration: u32,
+    chain_key: [u8; 32],
+    verifying_key: VerifyingKey,
+    skipped: HashMap<u32, [u8; 32]>,
+}
+
+redacted_debug!(SenderKeyReceiver, "<redacted>");
+
+impl SenderKeyReceiver {
+    pub fn new(distribution: &SenderKeyDistribution) -> SenderKeyReceiver {
+        SenderKeyReceiver {
+            chain_id: distribution.chain_id,
+            iteration: distribution.iteration,
+            chain_key: distribution.chain_key,
+            verifying_key: distribution.verifying_key,
+            skipped: HashMap::new(),
+        }
+    }
+
+    pub fn chain_id(&self) -> u32 {
+        self.chain_id
+    }
+
+    /// Checks the sender's signature, then decrypts. Nothing changes unless the message is
+    /// valid, so forged or corrupted messages cannot move the chain.
+    pub fn decrypt(&mut self, message: &[u8], ad: &[u8]) -> Result<Vec<u8>, SenderKeyError> {
+        if message.len() < OVERHEAD {
+            return Err(SenderKeyError::Malformed);
+        }
+
+        let (signed, signature) = message.split_at(message.len() - 64);
+
+        if u32::from_be_bytes(signed[..4].try_into().unwrap()) != self.chain_id {
+            return Err(SenderKeyError::UnknownChain);
+        }
+
+        self.verifying_key
+            .verify(signed, signature)
+            .map_err(|_| SenderKeyError::InvalidSignature)?;
+
+        let iteration = u32::from_be_bytes(signed[4..8].try_into().unwrap());
+        let (header, ct) = signed.split_at(HEADER_LENGTH);
+        let ad = [header, ad].concat();
+
+        if iteration < self.iteration {
+            let message_key = self
+                .skipped
+                .get(&iteration)
+                .ok_or(SenderKeyError::DuplicateMessage)?;
+            let msg = message_aead(message_key)
+                .decrypt(ct, &[0u8; 12], &ad)
+                .map_err(|_| SenderKeyError::InvalidMac)?;
+
+            if let Some(mut message_key) = self.skipped.remove(&iteration) {
+                message_key.zeroize();
+            }
+
+            return Ok(msg);
+        }
+
+        if iteration == u32::MAX || iteration - self.iteration > MAX_SKIP {
+            return Err(SenderKeyError::TooFarAhead);
+        }
+
+        // walk a copy of the chain up to the message, committing only once it decrypts
+        let mut chain_key = self.chain_key;
+        let mut skipped = Vec::with_capacity((iteration - self.iteration) as usize);
+
+        for _ in self.iteration..iteration {
+            let (message_key, next) = ratchet(&chain_key);
+            skipped.push(message_key);
+            chain_key = next;
+        }
+
+        let (mut message_key, next) = ratchet(&chain_key);
+        chain_key.zeroize();
+
+        let result = message_aead(&message_key).decrypt(ct, &[0u8; 12], &ad);
+        message_key.zeroize();
+
+        let Ok(msg) = result else {
+            skipped.zeroize();
+            return Err(SenderKeyError::InvalidMac);
+        };
+
+        for (i, message_key) in skipped.iter().enumerate() {
+            self.skipped.insert(self.iteration + i as u32, *message_key);
+        }
+
+        skipped.zeroize();
+
+        // the oldest keys go first once there are too many
+        while self.skipped.len() > MAX_SKIP as usize {
+            let oldest = *self.skipped.keys().min().unwrap();
+
+            if let Some(mut message_key) = self.skipped.remove(&oldest) {
+                message_key.zeroize();
+            }
+        }
+
+        self.chain_key = next;
+        self.iteration = iteration + 1;
+
+        Ok(msg)
+    }
+}
+
+impl Drop for SenderKeyReceiver {
+    fn drop(&mut self) {
+        self.chain_key.zeroize();
+
+        for message_key in self.skipped.values_mut() {
+            message_key.zeroize();
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use crate::ecc::ed25519::{Signature, VerifyingKey};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::errors::InvalidSignature;
+use crate::hashes::sha256;
+use std::io::{Read, Write};
+
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+pub const ED25519: &str = "ssh-ed25519";
+pub const SK_ED25519: &str = "sk-ssh-ed25519@openssh.com";
+
+// agents reply with at most this much, as OpenSSH's ssh-agent does
+const MAX_MESSAGE_LENGTH: usize = 256 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgentError {
+    NoAgent,
+    Io(std::io::ErrorKind),
+    Failure,
+    Malformed,
+}
+
+impl std::fmt::Display for AgentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgentError::NoAgent => write!(f, "SSH_AUTH_SOCK is not set."),
+            AgentError::Io(kind) => write!(f, "Could not talk to the agent: {}.", kind),
+            AgentError::Failure => write!(f, "The agent refused the request."),
+            AgentError::Malformed => write!(f, "The agent sent a malformed reply."),
+        }
+    }
+}
+
+impl std::error::Error for AgentError {}
+
+impl From<InvalidEncoding> for AgentError {
+    fn from(_: InvalidEncoding) -> AgentError {
+        AgentError::Malformed
+    }
+}
+
+impl From<std::io::Error> for AgentError {
+    fn from(error: std::io::Error) -> AgentError {
+        AgentError::Io(error.kind())
+    }
+}
+
+fn put_string(buffer: &mut Vec<u8>, data: &[u8]) {
+    buffer.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(data);
+}
+
+fn get_string<'a>(reader: &mut Reader<'a>) -> Result<&'a [u8], AgentError> {
+    let length = reader.u32()? as usize;
+
+    Ok(reader.bytes(length)?)
+}
+
+/// An Ed25519 key held by the agent, either a plain `ssh-ed25519` key or a FIDO security key
+/// (`sk-ssh-ed25519@openssh.com`) bound to an application string, usually `ssh:`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity {
+    key: VerifyingKey,
+    application: Option<String>,
+    comment: String,
+}
+
+impl Identity {
+    pub fn new(key: VerifyingKey, comment: &str) -> Identity {
+        Identity {
+            key,
+            application: None,
+            comment: comment.to_string(),
+        }
+    }
+
+    pub fn security_key(key: VerifyingKey, application: &str, comment: &str) -> Identity {
+        Identity {
+            key,
+            application: Some(application.to_string()),
+            comment: comment.to_string(),
+        }
+    }
+
+    /// Parses a public key blob in the SSH wire format. Key types other than the two Ed25519 ones
+    /// give `None`.
+    pub fn from_blob(blob: &[u8], comment: &str) -> Result<Option<Identity>, AgentError> {
+        let mut reader = Reader::new(blob);
+        let algorithm = get_string(&mut reader)?;
+
+        if algorithm != ED25519.as_bytes() && algorithm != SK_ED25519.as_bytes() {
+            return Ok(None);
+        }
+
+        let key = VerifyingKey::new(get_string(&mut reader)?).map_err(|_| AgentError::Malformed)?;
+
+        let application = if algorithm == SK_ED25519.as_bytes() {
+            let application = get_string(&mut reader)?;
+
+            Some(String::from_utf8(application.to_vec()).map_err(|_| AgentError::Malformed)?)
+        } else {
+            None
+        };
+
+        reader.finish()?;
+
+        Ok(Some(Identity {
+            key,
+            application,
+            comment: comment.to_string(),
+        }))
+    }
+
+    pub fn to_blob(&self) -> Vec<u8> {
+        let mut blob = Vec::new();
+        put_string(&mut blob, self.algorithm().as_bytes());
+        put_string(&mut blob, self.key.as_bytes());
+
+        if let Some(application) = &self.application {
+            put_string(&mut blob, application.as_bytes());
+        }
+
+        blob
+    }
+
+    pub fn algorithm(&self) -> &'static str {
+        if self.application.is_some() {
+            SK_ED25519
+        } else {
+            ED25519
+        }
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.key
+    }
+
+    pub fn application(&self) -> Option<&str> {
+        self.application.as_deref()
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    pub fn is_security_key(&self) -> bool {
+        self.application.is_some()
+    }
+
+    /// Checks a signature made by this key. Security key signatures cover the hashed application
+    /// and message along with the authenticator's flags and counter; the caller decides whether
+    /// `SshSignature::flags` shows enough user presence or verification.
+    pub fn verify(&self, msg: &[u8], signature: &SshSignature) -> Result<(), InvalidSignature> {
+        match (&self.application, signature.security_key) {
+            (None, None) => self.key.verify(msg, &signature.signature),
+            (Some(application), Some((flags, counter))) => {
+                let mut signed = Vec::with_capacity(69);
+                signed.extend_from_slice(&sha256::hash(application.as_bytes()));
+                signed.push(flags);
+                signed.extend_from_slice(&counter.to_be_bytes());
+                signed.extend_from_slice(&sha256::hash(msg));
+
+                self.key.verify(&signed, &signature.signature)
+            }
+            _ => Err(InvalidSignature),
+        }
+    }
+}
+
+/// A signature in the SSH wire format, with the authenticator flags and signature counter for
+/// security keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SshSignature {
+    signature: Signature,
+    security_key: Option<(u8, u32)>,
+}
+
+impl SshSignature {
+    pub fn new(signature: Signature) -> SshSignature {
+        SshSignature {
+            signature,
+            security_key: None,
+        }
+    }
+
+    pub fn from_security_key(signature: Signature, flags: u8, counter: u32) -> SshSignature {
+        SshSignature {
+            signature,
+            security_key: Some((flags, counter)),
+        }
+    }
+
+    pub fn from_blob(blob: &[u8]) -> Result<SshSignature, AgentError> {
+        let mut reader = Reader::new(blob);
+        let algorithm = get_string(&mut reader)?;
+        let signature = get_string(&mut reader)?
+            .try_into()
+            .map_err(|_| AgentError::Malformed)?;
+
+        let security_key = if algorithm == SK_ED25519.as_bytes() {
+            Some((reader.byte()?, reader.u32()?))
+        } else if algorithm == ED25519.as_bytes() {
+            None
+        } else {
+            return Err(AgentError::Malformed);
+        };
+
+        reader.finish()?;
+
+        Ok(SshSignature {
+            signature,
+            security_key,
+        })
+    }
+
+    pub fn to_blob(&self) -> Vec<u8> {
+        let mut blob = Vec::new();
+
+        match self.security_key {
+            None => {
+                put_string(&mut blob, ED25519.as_bytes());
+                put_string(&mut blob, &self.signature);
+            }
+            Some((flags, counter)) => {
+                put_string(&mut blob, SK_ED25519.as_bytes());
+                put_string(&mut blob, &self.signature);
+                blob.push(flags);
+                blob.extend_from_slice(&counter.to_be_bytes());
+            }
+        }
+
+        blob
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    pub fn flags(&self) -> Option<u8> {
+        self.security_key.map(|(flags, _)| flags)
+    }
+
+    pub fn counter(&self) -> Option<u32> {
+        self.security_key.map(|(_, counter)| counter)
+    }
+}
+
+/// A client for the ssh-agent protocol, so signing can be left to keys that never leave the
+/// user's agent or security key.
+#[derive(Debug)]
+pub struct AgentClient<S: Read + Write> {
+    stream: S,
+}
+
+#[cfg(unix)]
+impl AgentClient<std::os::unix::net::UnixStream> {
+    /// Connects to the agent named by `SSH_AUTH_SOCK`.
+    pub fn connect_env() -> Result<AgentClient<std::os::unix::net::UnixStream>, AgentError> {
+        let path = std::env::var_os("SSH_AUTH_SOCK").ok_or(AgentError::NoAgent)?;
+
+        AgentClient::connect(path)
+    }
+
+    pub fn connect(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<AgentClient<std::os::unix::net::UnixStream>, AgentError> {
+        Ok(AgentClient::new(std::os::unix::net::UnixStream::connect(
+            path,
+        )?))
+    }
+}
+
+impl<S: Read + Write> AgentClient<S> {
+    pub fn new(stream: S) -> AgentClient<S> {
+        AgentClient { stream }
+    }
+
+    fn request(&mut self, request: &[u8]) -> Result<Vec<u8>, AgentError> {
+        let mut message = Vec::with_capacity(request.len() + 4);
+        put_string(&mut message, request);
+        self.stream.write_all(&message)?;
+
+        let mut length = [0u8; 4];
+        self.stream.read_exact(&mut length)?;
+        let length = u32::from_be_bytes(length) as usize;
+
+        if length == 0 || length > MAX_MESSAGE_LENGTH {
+            return Err(AgentError::Malformed);
+        }
+
+        let mut response = vec![0u8; length];
+        self.stream.read_exact(&mut response)?;
+
+        if response[0] == SSH_AGENT_FAILURE {
+            return Err(AgentError::Failure);
+        }
+
+        Ok(response)
+    }
+
+    /// The Ed25519 keys the agent holds. Keys of other types are skipped.
+    pub fn identities(&mut self) -> Result<Vec<Identity>, AgentError> {
+        let response = self.request(&[SSH_AGENTC_REQUEST_IDENTITIES])?;
+
+        let mut reader = Reader::new(&response);
+
+        if reader.byte()? != SSH_AGENT_IDENTITIES_ANSWER {
+            return Err(AgentError::Malformed);
+        }
+
+        let count = reader.u32()?;
+        let mut identities = Vec::new();
+
+        for _ in 0..count {
+            let blob = get_string(&mut reader)?;
+            let comment = String::from_utf8_lossy(get_string(&mut reader)?);
+
+            if let Some(identity) = Identity::from_blob(blob, &comment)? {
+                identities.push(identity);
+            }
+        }
+
+        reader.finish()?;
+
+        Ok(identities)
+    }
+
+    /// Asks the agent to sign `data` with `identity`. Security keys may need to be touched
+    /// first, so this can block until the user does.
+    pub fn sign(&mut self, identity: &Identity, data: &[u8]) -> Result<SshSignature, AgentError> {
+        let mut request = vec![SSH_AGENTC_SIGN_REQUEST];
+        put_string(&mut request, &identity.to_blob());
+        put_string(&mut request, data);
+        request.extend_from_slice(&0u32.to_be_bytes());
+
+        let response = self.request(&request)?;
+
+        let mut reader = Reader::new(&response);
+
+        if reader.byte()? != SSH_AGENT_SIGN_RESPONSE {
+            return Err(AgentError::Malformed);
+        }
+
+        let signature = SshSignature::from_blob(get_string(&mut reader)?)?;
+        reader.finish()?;
+
+        if signature.security_key.is_some() != identity.is_security_key() {
+            return Err(AgentError::Malformed);
+        }
+
+        Ok(signature)
+    }
+}
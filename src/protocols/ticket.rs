@@ -0,0 +1,140 @@
+use crate::aeads::{SymmetricKey, XChaCha20Poly1305};
+use crate::utils::{random_bytes, redacted_debug};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const KEY_ID_LENGTH: usize = 4;
+
+const HEADER_LENGTH: usize = KEY_ID_LENGTH + 8;
+const OVERHEAD: usize = HEADER_LENGTH + XChaCha20Poly1305::NONCE_SIZE + XChaCha20Poly1305::TAG_SIZE;
+
+// the current key and the one before it, so tickets issued just before a rotation still open
+const RETAINED_KEYS: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TicketError {
+    Malformed,
+    UnknownKey,
+    InvalidMac,
+    Expired,
+}
+
+impl std::fmt::Display for TicketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketError::Malformed => write!(f, "The ticket is too short to be valid."),
+            TicketError::UnknownKey => write!(f, "The ticket was issued under a retired key."),
+            TicketError::InvalidMac => {
+                write!(f, "Invalid MAC detected. This ticket may be tampered with.")
+            }
+            TicketError::Expired => write!(f, "The ticket has expired."),
+        }
+    }
+}
+
+impl std::error::Error for TicketError {}
+
+struct TicketKey {
+    id: [u8; KEY_ID_LENGTH],
+    aead: XChaCha20Poly1305,
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Issues and opens resumption tickets the way TLS servers do. A ticket is
+/// `key id || expiry || nonce || ciphertext`, with the 4-byte key id and the big-endian expiry
+/// (seconds since the Unix epoch) authenticated as associated data.
+pub struct SessionTicket {
+    // newest first
+    keys: Vec<TicketKey>,
+    lifetime: Duration,
+}
+
+redacted_debug!(SessionTicket, "<redacted>");
+
+impl SessionTicket {
+    /// Starts with a random ticket key.
+    pub fn new(lifetime: Duration) -> SessionTicket {
+        let mut tickets = SessionTicket {
+            keys: Vec::with_capacity(RETAINED_KEYS),
+            lifetime,
+        };
+        tickets.rotate();
+
+        tickets
+    }
+
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    pub fn key_id(&self) -> [u8; KEY_ID_LENGTH] {
+        self.keys[0].id
+    }
+
+    /// Switches to a fresh random key. Tickets under the previous key can still be opened until
+    /// the next rotation.
+    pub fn rotate(&mut self) {
+        self.rotate_to(random_bytes(), &SymmetricKey::generate());
+    }
+
+    /// Switches to a given key, for servers that share ticket keys with each other.
+    pub fn rotate_to(&mut self, id: [u8; KEY_ID_LENGTH], key: &SymmetricKey) {
+        self.keys.truncate(RETAINED_KEYS - 1);
+        self.keys.insert(
+            0,
+            TicketKey {
+                id,
+                aead: XChaCha20Poly1305::new(key.as_bytes()),
+            },
+        );
+    }
+
+    pub fn seal(&self, state: &[u8], now: SystemTime) -> Vec<u8> {
+        let key = &self.keys[0];
+        let expiry = seconds(now).saturating_add(self.lifetime.as_secs());
+
+        let mut ticket = Vec::with_capacity(state.len() + OVERHEAD);
+        ticket.extend_from_slice(&key.id);
+        ticket.extend_from_slice(&expiry.to_be_bytes());
+
+        let nonce = XChaCha20Poly1305::generate_nonce();
+        let ct = key.aead.encrypt(state, &nonce, &ticket);
+
+        ticket.extend_from_slice(&nonce);
+        ticket.extend_from_slice(&ct);
+
+        ticket
+    }
+
+    pub fn open(&self, ticket: &[u8], now: SystemTime) -> Result<Vec<u8>, TicketError> {
+        if ticket.len() < OVERHEAD {
+            return Err(TicketError::Malformed);
+        }
+
+        let (header, rest) = ticket.split_at(HEADER_LENGTH);
+        let (nonce, ct) = rest.split_at(XChaCha20Poly1305::NONCE_SIZE);
+
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.id == header[..KEY_ID_LENGTH])
+            .ok_or(TicketError::UnknownKey)?;
+
+        let state = key
+            .aead
+            .decrypt(ct, nonce, header)
+            .map_err(|_| TicketError::InvalidMac)?;
+
+        let expiry = u64::from_be_bytes(header[KEY_ID_LENGTH..].try_into().unwrap());
+
+        if seconds(now) > expiry {
+            return Err(TicketError::Expired);
+        }
+
+        Ok(state)
+    }
+}
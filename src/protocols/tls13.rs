@@ -0,0 +1,152 @@
+use crate::hashes::Hash;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::redacted_debug;
+use core::marker::PhantomData;
+use zeroize::Zeroize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tls13Error {
+    /// A label, context or output length does not fit its HkdfLabel field, or is more than HKDF
+    /// can expand to.
+    InvalidLength,
+    /// The key schedule was advanced out of order.
+    WrongStage,
+}
+
+impl std::fmt::Display for Tls13Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tls13Error::InvalidLength => write!(f, "The HKDF-Expand-Label input is too long."),
+            Tls13Error::WrongStage => write!(f, "The key schedule is not at that stage."),
+        }
+    }
+}
+
+impl std::error::Error for Tls13Error {}
+
+pub fn hkdf_expand_label<H: Hash>(
+    secret: &[u8],
+    label: &str,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, Tls13Error> {
+    let label = [b"tls13 ", label.as_bytes()].concat();
+
+    let length_field = u16::try_from(length).map_err(|_| Tls13Error::InvalidLength)?;
+    let label_length = u8::try_from(label.len()).map_err(|_| Tls13Error::InvalidLength)?;
+    let context_length = u8::try_from(context.len()).map_err(|_| Tls13Error::InvalidLength)?;
+
+    let mut info = Vec::with_capacity(4 + label.len() + context.len());
+    info.extend_from_slice(&length_field.to_be_bytes());
+    info.push(label_length);
+    info.extend_from_slice(&label);
+    info.push(context_length);
+    info.extend_from_slice(context);
+
+    Hkdf::<H>::from_prk(secret)
+        .expand(&info, length)
+        .map_err(|_| Tls13Error::InvalidLength)
+}
+
+pub fn traffic_keys<H: Hash>(
+    secret: &[u8],
+    key_length: usize,
+    iv_length: usize,
+) -> Result<(Vec<u8>, Vec<u8>), Tls13Error> {
+    Ok((
+        hkdf_expand_label::<H>(secret, "key", &[], key_length)?,
+        hkdf_expand_label::<H>(secret, "iv", &[], iv_length)?,
+    ))
+}
+
+pub fn next_traffic_secret<H: Hash>(secret: &[u8]) -> Vec<u8> {
+    // a fixed label and a hash-sized output always fit
+    hkdf_expand_label::<H>(secret, "traffic upd", &[], H::OUTPUT_SIZE).unwrap()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Early,
+    Handshake,
+    Application,
+}
+
+pub struct KeySchedule<H: Hash> {
+    stage: Stage,
+    secret: Vec<u8>,
+    hash: PhantomData<H>,
+}
+
+redacted_debug!(KeySchedule<H: Hash>, "<redacted>");
+
+impl<H: Hash> KeySchedule<H> {
+    pub fn new(psk: Option<&[u8]>) -> KeySchedule<H> {
+        let zeros = vec![0u8; H::OUTPUT_SIZE];
+        let ikm = psk.unwrap_or(&zeros);
+
+        KeySchedule {
+            stage: Stage::Early,
+            secret: Hkdf::<H>::extract(&zeros, ikm).prk().to_vec(),
+            hash: PhantomData,
+        }
+    }
+
+    fn empty_hash() -> Vec<u8> {
+        H::default().finish()
+    }
+
+    fn advance(&mut self, ikm: &[u8]) {
+        // a fixed label and a hash-sized context always fit
+        let salt = self.derive_secret("derived", &Self::empty_hash()).unwrap();
+        let next = Hkdf::<H>::extract(&salt, ikm).prk().to_vec();
+
+        self.secret.zeroize();
+        self.secret = next;
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+
+    pub fn derive_secret(
+        &self,
+        label: &str,
+        transcript_hash: &[u8],
+    ) -> Result<Vec<u8>, Tls13Error> {
+        hkdf_expand_label::<H>(&self.secret, label, transcript_hash, H::OUTPUT_SIZE)
+    }
+
+    /// Mixes in the (EC)DHE shared secret. Only valid at the early stage.
+    pub fn handshake(&mut self, shared_secret: &[u8]) -> Result<(), Tls13Error> {
+        if self.stage != Stage::Early {
+            return Err(Tls13Error::WrongStage);
+        }
+
+        self.advance(shared_secret);
+        self.stage = Stage::Handshake;
+
+        Ok(())
+    }
+
+    /// Only valid at the handshake stage.
+    pub fn application(&mut self) -> Result<(), Tls13Error> {
+        if self.stage != Stage::Handshake {
+            return Err(Tls13Error::WrongStage);
+        }
+
+        self.advance(&vec![0u8; H::OUTPUT_SIZE]);
+        self.stage = Stage::Application;
+
+        Ok(())
+    }
+}
+
+impl<H: Hash> Drop for KeySchedule<H> {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
@@ -0,0 +1,46 @@
+use crate::hashes::Hash;
+
+const FORK: u64 = u64::MAX;
+
+#[derive(Clone)]
+pub struct Transcript<H: Hash> {
+    hash: H,
+}
+
+impl<H: Hash> Transcript<H> {
+    pub fn new(domain: &[u8]) -> Transcript<H> {
+        let mut transcript = Transcript { hash: H::default() };
+        transcript.absorb(b"raycrypt transcript v1", domain);
+
+        transcript
+    }
+
+    fn absorb(&mut self, label: &[u8], msg: &[u8]) {
+        self.hash.update(&(label.len() as u64).to_be_bytes());
+        self.hash.update(label);
+        self.hash.update(&(msg.len() as u64).to_be_bytes());
+        self.hash.update(msg);
+    }
+
+    pub fn append(&mut self, label: &[u8], msg: &[u8]) {
+        self.absorb(label, msg);
+    }
+
+    pub fn fork(&self, label: &[u8]) -> Transcript<H> {
+        // no label is u64::MAX bytes long, so `append` can never produce this framing
+        let mut transcript = self.clone();
+        transcript.hash.update(&FORK.to_be_bytes());
+        transcript.hash.update(&(label.len() as u64).to_be_bytes());
+        transcript.hash.update(label);
+
+        transcript
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        self.hash.clone().finish()
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.hash.finish()
+    }
+}
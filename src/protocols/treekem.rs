@@ -0,0 +1,968 @@
+// A minimal TreeKEM after MLS (RFC 9420). Members sit at the leaves of a binary tree of X25519
+// keys, and every member knows the private keys on the path from its leaf to the root. A commit
+// replaces the committer's whole path with keys derived from a chain of fresh path secrets, each
+// sealed only to the roots of the subtrees next to that path, so changing the group costs
+// O(log n) encryptions rather than one per member. The secret above the root feeds a key schedule
+// that gives every epoch its own secrets.
+//
+// What is left out: credentials and signatures (members are only known by their leaf keys),
+// unmerged leaves and parent hashes (adds and updates blank the paths above them instead),
+// proposals sent on their own (they ride inside commits), and trees that are not a power of
+// two wide.
+
+use crate::aeads::ChaCha20Poly1305;
+use crate::ecc::x25519::{self, PrivateKey, PublicKey};
+use crate::encoding::reader::Reader;
+use crate::encoding::InvalidEncoding;
+use crate::hashes::sha256::{self, Sha256};
+use crate::kdfs::hkdf::Hkdf;
+use crate::kdfs::InvalidLength;
+use crate::macs::hmac::hmac;
+use crate::utils::{const_time_eq, random_bytes, redacted_debug};
+use std::collections::BTreeMap;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const MAX_LEAVES: usize = 1 << 15;
+
+// ephemeral public key || sealed 32-byte path secret
+const SEALED_SECRET_LENGTH: usize = 32 + 32 + ChaCha20Poly1305::TAG_SIZE;
+// ephemeral public key || sealed joiner secret and path secret
+const SEALED_WELCOME_LENGTH: usize = 32 + 64 + ChaCha20Poly1305::TAG_SIZE;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeKemError {
+    Malformed,
+    WrongEpoch,
+    InvalidProposal,
+    InvalidCommit,
+    DecryptionFailed,
+    Removed,
+    NotInvited,
+    GroupFull,
+}
+
+impl std::fmt::Display for TreeKemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeKemError::Malformed => write!(f, "The message is malformed."),
+            TreeKemError::WrongEpoch => write!(f, "The commit is for a different epoch."),
+            TreeKemError::InvalidProposal => {
+                write!(f, "A proposal does not apply to the current group.")
+            }
+            TreeKemError::InvalidCommit => {
+                write!(f, "The commit does not match the group's state.")
+            }
+            TreeKemError::DecryptionFailed => write!(f, "A path secret failed to decrypt."),
+            TreeKemError::Removed => write!(f, "The commit removes this member from the group."),
+            TreeKemError::NotInvited => write!(f, "The welcome is not addressed to this key."),
+            TreeKemError::GroupFull => write!(f, "The group cannot grow any further."),
+        }
+    }
+}
+
+impl std::error::Error for TreeKemError {}
+
+impl From<InvalidEncoding> for TreeKemError {
+    fn from(_: InvalidEncoding) -> TreeKemError {
+        TreeKemError::Malformed
+    }
+}
+
+// tree arithmetic in the array layout of RFC 9420: leaf i is node 2i, and a node's level is the
+// number of trailing one bits in its index
+fn level(node: usize) -> u32 {
+    node.trailing_ones()
+}
+
+fn left(node: usize) -> usize {
+    node ^ (1 << (level(node) - 1))
+}
+
+fn right(node: usize) -> usize {
+    node ^ (3 << (level(node) - 1))
+}
+
+fn parent(node: usize) -> usize {
+    let k = level(node);
+    let b = (node >> (k + 1)) & 1;
+
+    (node | (1 << k)) ^ (b << (k + 1))
+}
+
+fn sibling(node: usize) -> usize {
+    let parent = parent(node);
+
+    if node < parent {
+        right(parent)
+    } else {
+        left(parent)
+    }
+}
+
+fn derive_secret(secret: &[u8; 32], label: &[u8]) -> [u8; 32] {
+    let mut output = Hkdf::<Sha256>::from_prk(secret)
+        .expand(&[b"raycrypt treekem ", label].concat(), 32)
+        .unwrap();
+    let derived = output[..].try_into().unwrap();
+    output.zeroize();
+
+    derived
+}
+
+fn node_key(path_secret: &[u8; 32]) -> PrivateKey {
+    let mut secret = derive_secret(path_secret, b"node");
+    let key = PrivateKey::new(&secret).unwrap();
+    secret.zeroize();
+
+    key
+}
+
+fn sealing_key(
+    shared: &[u8; 32],
+    ephemeral: &PublicKey,
+    recipient: &PublicKey,
+) -> ChaCha20Poly1305 {
+    let hkdf = Hkdf::<Sha256>::extract(&[&ephemeral[..], recipient].concat(), shared);
+
+    let mut key = hkdf.expand(b"raycrypt treekem seal", 32).unwrap();
+    let aead = ChaCha20Poly1305::new(&key);
+    key.zeroize();
+
+    aead
+}
+
+// sealed to one public key with an ephemeral X25519 exchange, as in HPKE's base mode
+fn seal_to(recipient: &PublicKey, msg: &[u8], ad: &[u8]) -> Vec<u8> {
+    let ephemeral = x25519::keygen(None);
+    let ephemeral_public = ephemeral.public_key();
+    let mut shared = ephemeral.exchange(*recipient);
+    let aead = sealing_key(&shared, &ephemeral_public, recipient);
+    shared.zeroize();
+
+    let mut sealed = ephemeral_public.to_vec();
+    sealed.extend_from_slice(&aead.encrypt(msg, &[0u8; 12], ad));
+
+    sealed
+}
+
+fn open_from(recipient: &PrivateKey, sealed: &[u8], ad: &[u8]) -> Result<Vec<u8>, TreeKemError> {
+    let ephemeral: PublicKey = sealed[..32].try_into().unwrap();
+    let mut shared = recipient.exchange(ephemeral);
+    let aead = sealing_key(&shared, &ephemeral, &recipient.public_key());
+    shared.zeroize();
+
+    aead.decrypt(&sealed[32..], &[0u8; 12], ad)
+        .map_err(|_| TreeKemError::DecryptionFailed)
+}
+
+// the public half of the tree, with `None` for blank nodes
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Tree(Vec<Option<PublicKey>>);
+
+impl Tree {
+    fn leaves(&self) -> usize {
+        self.0.len().div_ceil(2)
+    }
+
+    fn root(&self) -> usize {
+        self.leaves() - 1
+    }
+
+    fn leaf(&self, leaf: u32) -> Option<PublicKey> {
+        self.0.get(2 * leaf as usize).copied().flatten()
+    }
+
+    fn direct_path(&self, leaf: u32) -> Vec<usize> {
+        let mut node = 2 * leaf as usize;
+        let mut path = Vec::new();
+
+        while node != self.root() {
+            node = parent(node);
+            path.push(node);
+        }
+
+        path
+    }
+
+    // for each node on the direct path, its child that is not on the path
+    fn copath(&self, leaf: u32) -> Vec<usize> {
+        let mut node = 2 * leaf as usize;
+        let mut copath = Vec::new();
+
+        while node != self.root() {
+            copath.push(sibling(node));
+            node = parent(node);
+        }
+
+        copath
+    }
+
+    fn blank_path(&mut self, leaf: u32) {
+        for node in self.direct_path(leaf) {
+            self.0[node] = None;
+        }
+    }
+
+    // the fewest non-blank nodes whose keys cover every member below `node`
+    fn resolution(&self, node: usize) -> Vec<usize> {
+        if self.0[node].is_some() {
+            return vec![node];
+        }
+
+        if level(node) == 0 {
+            return Vec::new();
+        }
+
+        let mut resolution = self.resolution(left(node));
+        resolution.extend(self.resolution(right(node)));
+
+        resolution
+    }
+
+    // the leftmost free leaf, doubling the tree if there is none
+    fn add(&mut self, key: PublicKey) -> Result<u32, TreeKemError> {
+        let leaves = self.leaves();
+
+        let leaf = match (0..leaves).find(|&leaf| self.0[2 * leaf].is_none()) {
+            Some(leaf) => leaf,
+            None if 2 * leaves <= MAX_LEAVES => {
+                self.0.resize(4 * leaves - 1, None);
+                leaves
+            }
+            None => return Err(TreeKemError::GroupFull),
+        };
+
+        self.0[2 * leaf] = Some(key);
+        self.blank_path(leaf as u32);
+
+        Ok(leaf as u32)
+    }
+
+    // halves the tree while its right half is empty
+    fn truncate(&mut self) {
+        while self.leaves() > 1 {
+            let leaves = self.leaves();
+
+            if (leaves / 2..leaves).any(|leaf| self.0[2 * leaf].is_some()) {
+                break;
+            }
+
+            self.0.truncate(leaves - 1);
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = (self.leaves() as u32).to_be_bytes().to_vec();
+
+        for node in &self.0 {
+            match node {
+                Some(key) => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(key);
+                }
+                None => bytes.push(0),
+            }
+        }
+
+        bytes
+    }
+
+    fn read(reader: &mut Reader) -> Result<Tree, TreeKemError> {
+        let leaves = reader.u32()? as usize;
+
+        if !leaves.is_power_of_two() || leaves > MAX_LEAVES {
+            return Err(TreeKemError::Malformed);
+        }
+
+        let nodes = (0..2 * leaves - 1)
+            .map(|_| match reader.byte()? {
+                0 => Ok(None),
+                1 => Ok(Some(reader.array()?)),
+                _ => Err(TreeKemError::Malformed),
+            })
+            .collect::<Result<Vec<Option<PublicKey>>, TreeKemError>>()?;
+
+        Ok(Tree(nodes))
+    }
+}
+
+// binds every secret of an epoch to the group, the epoch number and the exact tree
+fn context(group_id: &[u8], epoch: u64, tree: &Tree) -> Vec<u8> {
+    let mut context = (group_id.len() as u16).to_be_bytes().to_vec();
+    context.extend_from_slice(group_id);
+    context.extend_from_slice(&epoch.to_be_bytes());
+    context.extend_from_slice(&sha256::hash(&tree.to_bytes()));
+
+    context
+}
+
+fn joiner_secret(init_secret: &[u8; 32], commit_secret: &[u8; 32]) -> [u8; 32] {
+    let hkdf = Hkdf::<Sha256>::extract(init_secret, commit_secret);
+
+    derive_secret(hkdf.prk().try_into().unwrap(), b"joiner")
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct EpochSecrets {
+    init: [u8; 32],
+    confirmation: [u8; 32],
+    exporter: [u8; 32],
+    authenticator: [u8; 32],
+}
+
+impl EpochSecrets {
+    fn derive(joiner_secret: &[u8; 32], context: &[u8]) -> EpochSecrets {
+        let hkdf = Hkdf::<Sha256>::extract(joiner_secret, context);
+        let epoch_secret: &[u8; 32] = hkdf.prk().try_into().unwrap();
+
+        EpochSecrets {
+            init: derive_secret(epoch_secret, b"init"),
+            confirmation: derive_secret(epoch_secret, b"confirm"),
+            exporter: derive_secret(epoch_secret, b"exporter"),
+            authenticator: derive_secret(epoch_secret, b"authentication"),
+        }
+    }
+
+    fn confirmation_tag(&self, body: &[u8]) -> [u8; 32] {
+        hmac::<Sha256>(&self.confirmation, body)[..]
+            .try_into()
+            .unwrap()
+    }
+}
+
+// derives the keys for `nodes` from the path secret of the first, checking each against the
+// tree, and returns the secret past the last: the commit secret when `nodes` ends at the root
+fn derive_path(
+    tree: &Tree,
+    nodes: &[usize],
+    mut secret: [u8; 32],
+    private: &mut BTreeMap<usize, PrivateKey>,
+) -> Result<[u8; 32], TreeKemError> {
+    for &node in nodes {
+        let key = node_key(&secret);
+
+        if tree.0[node] != Some(key.public_key()) {
+            secret.zeroize();
+            return Err(TreeKemError::InvalidCommit);
+        }
+
+        private.insert(node, key);
+
+        let next = derive_secret(&secret, b"path");
+        secret.zeroize();
+        secret = next;
+    }
+
+    Ok(secret)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Proposal {
+    Add(PublicKey),
+    Remove(u32),
+    /// A member's new leaf key, made with `Group::propose_update`.
+    Update(u32, PublicKey),
+}
+
+impl Proposal {
+    fn write(&self, buffer: &mut Vec<u8>) {
+        match self {
+            Proposal::Add(key) => {
+                buffer.push(1);
+                buffer.extend_from_slice(key);
+            }
+            Proposal::Remove(leaf) => {
+                buffer.push(2);
+                buffer.extend_from_slice(&leaf.to_be_bytes());
+            }
+            Proposal::Update(leaf, key) => {
+                buffer.push(3);
+                buffer.extend_from_slice(&leaf.to_be_bytes());
+                buffer.extend_from_slice(key);
+            }
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Proposal, TreeKemError> {
+        match reader.byte()? {
+            1 => Ok(Proposal::Add(reader.array()?)),
+            2 => Ok(Proposal::Remove(reader.u32()?)),
+            3 => Ok(Proposal::Update(reader.u32()?, reader.array()?)),
+            _ => Err(TreeKemError::Malformed),
+        }
+    }
+}
+
+// updates, then removes, then adds, as MLS orders them; returns the leaves of the new members
+fn apply_proposals(
+    tree: &mut Tree,
+    proposals: &[Proposal],
+    sender: u32,
+) -> Result<Vec<u32>, TreeKemError> {
+    for proposal in proposals {
+        if let Proposal::Update(leaf, key) = *proposal {
+            if leaf == sender || tree.leaf(leaf).is_none() {
+                return Err(TreeKemError::InvalidProposal);
+            }
+
+            tree.0[2 * leaf as usize] = Some(key);
+            tree.blank_path(leaf);
+        }
+    }
+
+    for proposal in proposals {
+        if let Proposal::Remove(leaf) = *proposal {
+            if leaf == sender || tree.leaf(leaf).is_none() {
+                return Err(TreeKemError::InvalidProposal);
+            }
+
+            tree.0[2 * leaf as usize] = None;
+            tree.blank_path(leaf);
+        }
+    }
+
+    tree.truncate();
+
+    proposals
+        .iter()
+        .filter_map(|proposal| match proposal {
+            Proposal::Add(key) => Some(tree.add(*key)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PathNode {
+    public_key: PublicKey,
+    // the path secret sealed to each node in the resolution of the copath node below
+    secrets: Vec<Vec<u8>>,
+}
+
+/// Moves the group to its next epoch. Every member other than the committer, and other than
+/// those it removes, applies it with `Group::process_commit`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+    epoch: u64,
+    sender: u32,
+    proposals: Vec<Proposal>,
+    leaf_key: PublicKey,
+    path: Vec<PathNode>,
+    confirmation_tag: [u8; 32],
+}
+
+impl Commit {
+    /// The epoch the commit was made in; the group moves to the one after.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn sender(&self) -> u32 {
+        self.sender
+    }
+
+    pub fn proposals(&self) -> &[Proposal] {
+        &self.proposals
+    }
+
+    // everything the confirmation tag covers
+    fn body(&self) -> Vec<u8> {
+        let mut body = self.epoch.to_be_bytes().to_vec();
+        body.extend_from_slice(&self.sender.to_be_bytes());
+        body.extend_from_slice(&(self.proposals.len() as u16).to_be_bytes());
+
+        for proposal in &self.proposals {
+            proposal.write(&mut body);
+        }
+
+        body.extend_from_slice(&self.leaf_key);
+        body.push(self.path.len() as u8);
+
+        for node in &self.path {
+            body.extend_from_slice(&node.public_key);
+            body.extend_from_slice(&(node.secrets.len() as u16).to_be_bytes());
+
+            for secret in &node.secrets {
+                body.extend_from_slice(secret);
+            }
+        }
+
+        body
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.body();
+        bytes.extend_from_slice(&self.confirmation_tag);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Commit, TreeKemError> {
+        let mut reader = Reader::new(bytes);
+
+        let epoch = reader.u64()?;
+        let sender = reader.u32()?;
+        let proposals = (0..reader.u16()?)
+            .map(|_| Proposal::read(&mut reader))
+            .collect::<Result<Vec<Proposal>, TreeKemError>>()?;
+        let leaf_key = reader.array()?;
+
+        let path = (0..reader.byte()?)
+            .map(|_| {
+                let public_key = reader.array()?;
+                let secrets = (0..reader.u16()?)
+                    .map(|_| Ok(reader.bytes(SEALED_SECRET_LENGTH)?.to_vec()))
+                    .collect::<Result<Vec<Vec<u8>>, TreeKemError>>()?;
+
+                Ok(PathNode {
+                    public_key,
+                    secrets,
+                })
+            })
+            .collect::<Result<Vec<PathNode>, TreeKemError>>()?;
+
+        let confirmation_tag = reader.array()?;
+        reader.finish()?;
+
+        Ok(Commit {
+            epoch,
+            sender,
+            proposals,
+            leaf_key,
+            path,
+            confirmation_tag,
+        })
+    }
+}
+
+/// Brings the members added by a commit into the new epoch. It carries the public tree and,
+/// for each new member, the joiner secret and one path secret sealed to its leaf key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Welcome {
+    group_id: Vec<u8>,
+    epoch: u64,
+    sender: u32,
+    tree: Tree,
+    secrets: Vec<(u32, Vec<u8>)>,
+}
+
+impl Welcome {
+    pub fn group_id(&self) -> &[u8] {
+        &self.group_id
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = (self.group_id.len() as u16).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.group_id);
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        bytes.extend_from_slice(&self.sender.to_be_bytes());
+        bytes.extend_from_slice(&self.tree.to_bytes());
+        bytes.extend_from_slice(&(self.secrets.len() as u16).to_be_bytes());
+
+        for (leaf, sealed) in &self.secrets {
+            bytes.extend_from_slice(&leaf.to_be_bytes());
+            bytes.extend_from_slice(sealed);
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Welcome, TreeKemError> {
+        let mut reader = Reader::new(bytes);
+
+        let length = reader.u16()? as usize;
+        let group_id = reader.bytes(length)?.to_vec();
+        let epoch = reader.u64()?;
+        let sender = reader.u32()?;
+        let tree = Tree::read(&mut reader)?;
+
+        let secrets = (0..reader.u16()?)
+            .map(|_| Ok((reader.u32()?, reader.bytes(SEALED_WELCOME_LENGTH)?.to_vec())))
+            .collect::<Result<Vec<(u32, Vec<u8>)>, TreeKemError>>()?;
+
+        reader.finish()?;
+
+        if tree.leaf(sender).is_none() {
+            return Err(TreeKemError::Malformed);
+        }
+
+        Ok(Welcome {
+            group_id,
+            epoch,
+            sender,
+            tree,
+            secrets,
+        })
+    }
+}
+
+/// What a prospective member publishes so it can be added: the X25519 key that becomes its leaf.
+pub struct KeyPackage(PrivateKey);
+
+redacted_debug!(KeyPackage, "<redacted>");
+
+impl KeyPackage {
+    pub fn generate() -> KeyPackage {
+        KeyPackage(x25519::keygen(None))
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.0.public_key()
+    }
+}
+
+/// One member's view of the group. All members agree on the epoch secrets as long as they
+/// process the same commits in the same order; ordering them is the delivery service's job.
+pub struct Group {
+    group_id: Vec<u8>,
+    epoch: u64,
+    leaf: u32,
+    tree: Tree,
+    // our leaf key and the keys we know on our direct path
+    private: BTreeMap<usize, PrivateKey>,
+    // leaf keys from our update proposals that have not been committed yet
+    pending: Vec<PrivateKey>,
+    secrets: EpochSecrets,
+}
+
+redacted_debug!(Group, "<redacted>");
+
+impl Group {
+    /// A group with the caller as its only member, in epoch 0.
+    pub fn create(group_id: &[u8]) -> Group {
+        assert!(group_id.len() <= u16::MAX as usize);
+
+        let leaf_key = x25519::keygen(None);
+        let tree = Tree(vec![Some(leaf_key.public_key())]);
+
+        let mut joiner: [u8; 32] = random_bytes();
+        let secrets = EpochSecrets::derive(&joiner, &context(group_id, 0, &tree));
+        joiner.zeroize();
+
+        Group {
+            group_id: group_id.to_vec(),
+            epoch: 0,
+            leaf: 0,
+            tree,
+            private: BTreeMap::from([(0, leaf_key)]),
+            pending: Vec::new(),
+            secrets,
+        }
+    }
+
+    /// Joins at the epoch a `Welcome` was made for, using the key package it was addressed to.
+    pub fn join(welcome: &Welcome, key_package: KeyPackage) -> Result<Group, TreeKemError> {
+        let public = key_package.public_key();
+        let tree = &welcome.tree;
+
+        let (leaf, sealed) = welcome
+            .secrets
+            .iter()
+            .find(|(leaf, _)| tree.leaf(*leaf) == Some(public))
+            .ok_or(TreeKemError::NotInvited)?;
+
+        if *leaf == welcome.sender {
+            return Err(TreeKemError::Malformed);
+        }
+
+        let context = context(&welcome.group_id, welcome.epoch, tree);
+        let mut plaintext = open_from(&key_package.0, sealed, &context)?;
+        let mut joiner: [u8; 32] = plaintext[..32].try_into().unwrap();
+        let path_secret: [u8; 32] = plaintext[32..].try_into().unwrap();
+        plaintext.zeroize();
+
+        // the path secret belongs to the lowest node the committer's path shares with ours
+        let path = tree.direct_path(*leaf);
+        let start = tree
+            .direct_path(welcome.sender)
+            .iter()
+            .find_map(|node| path.iter().position(|own| own == node))
+            .unwrap();
+
+        let mut private = BTreeMap::new();
+        let result = derive_path(tree, &path[start..], path_secret, &mut private);
+
+        let secrets = EpochSecrets::derive(&joiner, &context);
+        joiner.zeroize();
+        result?.zeroize();
+
+        private.insert(2 * *leaf as usize, key_package.0);
+
+        Ok(Group {
+            group_id: welcome.group_id.clone(),
+            epoch: welcome.epoch,
+            leaf: *leaf,
+            tree: tree.clone(),
+            private,
+            pending: Vec::new(),
+            secrets,
+        })
+    }
+
+    pub fn group_id(&self) -> &[u8] {
+        &self.group_id
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn leaf_index(&self) -> u32 {
+        self.leaf
+    }
+
+    /// The occupied leaves and their keys.
+    pub fn members(&self) -> Vec<(u32, PublicKey)> {
+        (0..self.tree.leaves() as u32)
+            .filter_map(|leaf| self.tree.leaf(leaf).map(|key| (leaf, key)))
+            .collect()
+    }
+
+    /// A secret every member of this epoch can derive, for keying application traffic.
+    pub fn export_secret(&self, label: &[u8], length: usize) -> Result<Vec<u8>, InvalidLength> {
+        Hkdf::<Sha256>::from_prk(&self.secrets.exporter).expand(label, length)
+    }
+
+    /// A value members can compare out of band to check they are in the same epoch.
+    pub fn epoch_authenticator(&self) -> [u8; 32] {
+        self.secrets.authenticator
+    }
+
+    /// Proposes a new leaf key for this member. Its private half is kept until a commit takes
+    /// the proposal in, and dropped when the epoch moves on without it.
+    pub fn propose_update(&mut self) -> Proposal {
+        let key = x25519::keygen(None);
+        let proposal = Proposal::Update(self.leaf, key.public_key());
+        self.pending.push(key);
+
+        proposal
+    }
+
+    fn path_ad(&self, sender: u32) -> Vec<u8> {
+        let mut ad = context(&self.group_id, self.epoch, &self.tree);
+        ad.extend_from_slice(&sender.to_be_bytes());
+
+        ad
+    }
+
+    /// Applies `proposals`, refreshes this member's path and moves to the next epoch at once,
+    /// returning the commit for the other members and a welcome if anyone was added.
+    pub fn commit(
+        &mut self,
+        proposals: &[Proposal],
+    ) -> Result<(Commit, Option<Welcome>), TreeKemError> {
+        if proposals.len() > u16::MAX as usize {
+            return Err(TreeKemError::InvalidProposal);
+        }
+
+        let mut tree = self.tree.clone();
+        let added = apply_proposals(&mut tree, proposals, self.leaf)?;
+
+        let leaf_key = x25519::keygen(None);
+        tree.0[2 * self.leaf as usize] = Some(leaf_key.public_key());
+
+        let path = tree.direct_path(self.leaf);
+        let mut private = BTreeMap::from([(2 * self.leaf as usize, leaf_key)]);
+        let mut path_secrets = Vec::with_capacity(path.len());
+        let mut secret: [u8; 32] = random_bytes();
+
+        for &node in &path {
+            let key = node_key(&secret);
+            tree.0[node] = Some(key.public_key());
+            private.insert(node, key);
+            path_secrets.push(secret);
+
+            let next = derive_secret(&secret, b"path");
+            secret.zeroize();
+            secret = next;
+        }
+
+        let ad = self.path_ad(self.leaf);
+        let nodes = path
+            .iter()
+            .zip(tree.copath(self.leaf))
+            .zip(&path_secrets)
+            .map(|((&node, copath), path_secret)| PathNode {
+                public_key: tree.0[node].unwrap(),
+                secrets: tree
+                    .resolution(copath)
+                    .iter()
+                    .map(|&recipient| seal_to(&tree.0[recipient].unwrap(), path_secret, &ad))
+                    .collect(),
+            })
+            .collect();
+
+        let epoch = self.epoch + 1;
+        let context = context(&self.group_id, epoch, &tree);
+        let mut joiner = joiner_secret(&self.secrets.init, &secret);
+        secret.zeroize();
+        let secrets = EpochSecrets::derive(&joiner, &context);
+
+        let mut commit = Commit {
+            epoch: self.epoch,
+            sender: self.leaf,
+            proposals: proposals.to_vec(),
+            leaf_key: tree.leaf(self.leaf).unwrap(),
+            path: nodes,
+            confirmation_tag: [0u8; 32],
+        };
+        commit.confirmation_tag = secrets.confirmation_tag(&commit.body());
+
+        let welcome = if added.is_empty() {
+            None
+        } else {
+            let sealed = added
+                .iter()
+                .map(|&leaf| {
+                    let own = tree.direct_path(leaf);
+                    let start = path.iter().position(|node| own.contains(node)).unwrap();
+
+                    let mut plaintext = [joiner, path_secrets[start]].concat();
+                    let sealed = seal_to(&tree.leaf(leaf).unwrap(), &plaintext, &context);
+                    plaintext.zeroize();
+
+                    (leaf, sealed)
+                })
+                .collect();
+
+            Some(Welcome {
+                group_id: self.group_id.clone(),
+                epoch,
+                sender: self.leaf,
+                tree: tree.clone(),
+                secrets: sealed,
+            })
+        };
+
+        joiner.zeroize();
+        path_secrets.zeroize();
+
+        self.epoch = epoch;
+        self.tree = tree;
+        self.private = private;
+        self.pending.clear();
+        self.secrets = secrets;
+
+        Ok((commit, welcome))
+    }
+
+    /// Applies another member's commit. Nothing changes unless the commit checks out: the
+    /// path keys must match the secrets they came from, and the confirmation tag must match
+    /// the new epoch's secrets.
+    pub fn process_commit(&mut self, commit: &Commit) -> Result<(), TreeKemError> {
+        if commit.epoch != self.epoch {
+            return Err(TreeKemError::WrongEpoch);
+        }
+
+        if commit.sender == self.leaf || self.tree.leaf(commit.sender).is_none() {
+            return Err(TreeKemError::InvalidCommit);
+        }
+
+        if commit.proposals.contains(&Proposal::Remove(self.leaf)) {
+            return Err(TreeKemError::Removed);
+        }
+
+        let mut tree = self.tree.clone();
+        apply_proposals(&mut tree, &commit.proposals, commit.sender)?;
+
+        let own = 2 * self.leaf as usize;
+
+        // our leaf key, if the commit takes in one of our updates
+        let update = commit.proposals.iter().find_map(|proposal| match proposal {
+            Proposal::Update(leaf, key) if *leaf == self.leaf => Some(*key),
+            _ => None,
+        });
+        let pending = match update {
+            Some(key) => Some(
+                self.pending
+                    .iter()
+                    .position(|pending| pending.public_key() == key)
+                    .ok_or(TreeKemError::InvalidProposal)?,
+            ),
+            None => None,
+        };
+
+        let path = tree.direct_path(commit.sender);
+
+        if commit.path.len() != path.len() {
+            return Err(TreeKemError::InvalidCommit);
+        }
+
+        tree.0[2 * commit.sender as usize] = Some(commit.leaf_key);
+
+        for (&node, path_node) in path.iter().zip(&commit.path) {
+            tree.0[node] = Some(path_node.public_key);
+        }
+
+        // the lowest node of the sender's path above us, and the resolution it was sealed to
+        let own_path = tree.direct_path(self.leaf);
+        let start = path
+            .iter()
+            .position(|node| own_path.contains(node))
+            .ok_or(TreeKemError::InvalidCommit)?;
+        let resolution = tree.resolution(tree.copath(commit.sender)[start]);
+
+        if resolution.len() != commit.path[start].secrets.len() {
+            return Err(TreeKemError::InvalidCommit);
+        }
+
+        let held = |node: usize| {
+            let key = match pending {
+                Some(pending) if node == own => &self.pending[pending],
+                _ => self.private.get(&node)?,
+            };
+
+            (tree.0[node] == Some(key.public_key())).then_some(key)
+        };
+
+        let (key, sealed) = resolution
+            .iter()
+            .zip(&commit.path[start].secrets)
+            .find_map(|(&node, sealed)| held(node).map(|key| (key, sealed)))
+            .ok_or(TreeKemError::InvalidCommit)?;
+
+        let mut plaintext = open_from(key, sealed, &self.path_ad(commit.sender))?;
+        let path_secret: [u8; 32] = plaintext[..]
+            .try_into()
+            .map_err(|_| TreeKemError::InvalidCommit)?;
+        plaintext.zeroize();
+
+        let mut new_private = BTreeMap::new();
+        let mut commit_secret = derive_path(&tree, &path[start..], path_secret, &mut new_private)?;
+
+        let epoch = self.epoch + 1;
+        let mut joiner = joiner_secret(&self.secrets.init, &commit_secret);
+        commit_secret.zeroize();
+        let secrets = EpochSecrets::derive(&joiner, &context(&self.group_id, epoch, &tree));
+        joiner.zeroize();
+
+        if !const_time_eq(
+            &secrets.confirmation_tag(&commit.body()),
+            &commit.confirmation_tag,
+        ) {
+            return Err(TreeKemError::InvalidCommit);
+        }
+
+        // keep the keys still in the tree and take in the new ones
+        let mut private = std::mem::take(&mut self.private);
+
+        if let Some(pending) = pending {
+            private.insert(own, self.pending.swap_remove(pending));
+        }
+
+        private.retain(|&node, key| tree.0.get(node) == Some(&Some(key.public_key())));
+        private.extend(new_private);
+
+        self.epoch = epoch;
+        self.tree = tree;
+        self.private = private;
+        self.pending.clear();
+        self.secrets = secrets;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,429 @@
+use crate::aeads::{ChaCha20Poly1305, XChaCha20Poly1305};
+use crate::ecc::x25519::{keygen, PrivateKey, PublicKey};
+use crate::hashes::blake2s::Blake2s;
+use crate::kdfs::hkdf::Hkdf;
+use crate::utils::{const_time_eq, redacted_debug};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const CONSTRUCTION: &[u8] = b"Noise_IKpsk2_25519_ChaChaPoly_BLAKE2s";
+pub const IDENTIFIER: &[u8] = b"WireGuard v1 zx2c4 Jason@zx2c4.com";
+pub const LABEL_MAC1: &[u8] = b"mac1----";
+pub const LABEL_COOKIE: &[u8] = b"cookie--";
+
+pub const INITIATION_LENGTH: usize = 148;
+pub const RESPONSE_LENGTH: usize = 92;
+pub const COOKIE_REPLY_LENGTH: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandshakeError {
+    InvalidMessage,
+    InvalidMac,
+    DecryptionFailed,
+    InvalidPublicKey,
+}
+
+impl std::fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandshakeError::InvalidMessage => write!(f, "The handshake message is malformed."),
+            HandshakeError::InvalidMac => write!(f, "The handshake message MAC is invalid."),
+            HandshakeError::DecryptionFailed => {
+                write!(f, "The handshake message failed to decrypt.")
+            }
+            HandshakeError::InvalidPublicKey => {
+                write!(f, "The peer sent a low-order public key.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
+fn hash(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Blake2s::new(32);
+
+    for part in parts {
+        hasher.update(part);
+    }
+
+    hasher.digest().try_into().unwrap()
+}
+
+fn mac(key: &[u8], data: &[u8]) -> [u8; 16] {
+    let mut hasher = Blake2s::new_keyed(key, 16);
+    hasher.update(data);
+
+    hasher.digest().try_into().unwrap()
+}
+
+pub fn kdf<const N: usize>(key: &[u8], input: &[u8]) -> [[u8; 32]; N] {
+    let mut okm = Hkdf::<Blake2s>::extract(key, input)
+        .expand(&[], 32 * N)
+        .unwrap();
+    let output = core::array::from_fn(|i| okm[32 * i..32 * i + 32].try_into().unwrap());
+
+    okm.zeroize();
+
+    output
+}
+
+fn nonce(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&counter.to_le_bytes());
+
+    nonce
+}
+
+fn seal(key: &[u8; 32], msg: &[u8], ad: &[u8]) -> Vec<u8> {
+    ChaCha20Poly1305::new(key)
+        .encrypt(msg, &nonce(0), ad)
+        .into_vec()
+}
+
+fn open(key: &[u8; 32], ct: &[u8], ad: &[u8]) -> Result<Vec<u8>, HandshakeError> {
+    ChaCha20Poly1305::new(key)
+        .decrypt(ct, &nonce(0), ad)
+        .map_err(|_| HandshakeError::DecryptionFailed)
+}
+
+fn dh(private: &PrivateKey, public: &[u8]) -> Result<[u8; 32], HandshakeError> {
+    let shared = private.exchange(public.try_into().unwrap());
+
+    if shared == [0u8; 32] {
+        return Err(HandshakeError::InvalidPublicKey);
+    }
+
+    Ok(shared)
+}
+
+fn initial_state(responder_public: &PublicKey) -> ([u8; 32], [u8; 32]) {
+    let chaining_key = hash(&[CONSTRUCTION]);
+    let hash = hash(&[&hash(&[&chaining_key, IDENTIFIER]), responder_public]);
+
+    (chaining_key, hash)
+}
+
+pub fn tai64n(time: SystemTime) -> [u8; 12] {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    let mut output = [0u8; 12];
+    output[..8].copy_from_slice(&(0x400000000000000a + since.as_secs()).to_be_bytes());
+    output[8..].copy_from_slice(&since.subsec_nanos().to_be_bytes());
+
+    output
+}
+
+pub fn mac1(responder_public: &PublicKey, msg: &[u8]) -> [u8; 16] {
+    mac(&hash(&[LABEL_MAC1, responder_public]), msg)
+}
+
+pub fn verify_mac1(responder_public: &PublicKey, msg: &[u8]) -> bool {
+    msg.len() >= 32
+        && const_time_eq(
+            &mac1(responder_public, &msg[..msg.len() - 32]),
+            &msg[msg.len() - 32..msg.len() - 16],
+        )
+}
+
+pub fn add_mac2(msg: &mut [u8], cookie: &[u8; 16]) {
+    let offset = msg.len() - 16;
+    let mac2 = mac(cookie, &msg[..offset]);
+
+    msg[offset..].copy_from_slice(&mac2);
+}
+
+pub fn verify_mac2(msg: &[u8], cookie: &[u8; 16]) -> bool {
+    msg.len() >= 16 && const_time_eq(&mac(cookie, &msg[..msg.len() - 16]), &msg[msg.len() - 16..])
+}
+
+pub fn cookie(secret: &[u8; 32], source: &[u8]) -> [u8; 16] {
+    mac(secret, source)
+}
+
+pub fn cookie_reply(
+    responder_public: &PublicKey,
+    receiver_index: u32,
+    nonce: &[u8; 24],
+    cookie: &[u8; 16],
+    mac1: &[u8],
+) -> [u8; COOKIE_REPLY_LENGTH] {
+    let key = hash(&[LABEL_COOKIE, responder_public]);
+
+    let mut reply = [0u8; COOKIE_REPLY_LENGTH];
+    reply[0] = 3;
+    reply[4..8].copy_from_slice(&receiver_index.to_le_bytes());
+    reply[8..32].copy_from_slice(nonce);
+    reply[32..].copy_from_slice(&XChaCha20Poly1305::new(&key).encrypt(cookie, nonce, mac1));
+
+    reply
+}
+
+pub fn open_cookie_reply(
+    responder_public: &PublicKey,
+    reply: &[u8],
+    mac1: &[u8],
+) -> Result<(u32, [u8; 16]), HandshakeError> {
+    if reply.len() != COOKIE_REPLY_LENGTH || reply[..4] != [3, 0, 0, 0] {
+        return Err(HandshakeError::InvalidMessage);
+    }
+
+    let key = hash(&[LABEL_COOKIE, responder_public]);
+    let cookie = XChaCha20Poly1305::new(&key)
+        .decrypt(&reply[32..], &reply[8..32], mac1)
+        .map_err(|_| HandshakeError::DecryptionFailed)?;
+
+    Ok((
+        u32::from_le_bytes(reply[4..8].try_into().unwrap()),
+        cookie.try_into().unwrap(),
+    ))
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct TransportKeys {
+    pub sending: [u8; 32],
+    pub receiving: [u8; 32],
+    pub local_index: u32,
+    pub remote_index: u32,
+}
+
+redacted_debug!(TransportKeys, "<redacted>");
+
+pub struct Initiator {
+    chaining_key: [u8; 32],
+    hash: [u8; 32],
+    ephemeral: PrivateKey,
+    psk: [u8; 32],
+    sender_index: u32,
+}
+
+redacted_debug!(Initiator, "<redacted>");
+
+impl Initiator {
+    pub fn new(
+        static_private: &PrivateKey,
+        responder_public: &PublicKey,
+        psk: Option<&[u8; 32]>,
+        sender_index: u32,
+        timestamp: &[u8; 12],
+    ) -> Result<(Initiator, [u8; INITIATION_LENGTH]), HandshakeError> {
+        Initiator::with_ephemeral(
+            static_private,
+            responder_public,
+            psk,
+            sender_index,
+            timestamp,
+            keygen(None),
+        )
+    }
+
+    pub fn with_ephemeral(
+        static_private: &PrivateKey,
+        responder_public: &PublicKey,
+        psk: Option<&[u8; 32]>,
+        sender_index: u32,
+        timestamp: &[u8; 12],
+        ephemeral: PrivateKey,
+    ) -> Result<(Initiator, [u8; INITIATION_LENGTH]), HandshakeError> {
+        let (chaining_key, mut h) = initial_state(responder_public);
+
+        let mut msg = [0u8; INITIATION_LENGTH];
+        msg[0] = 1;
+        msg[4..8].copy_from_slice(&sender_index.to_le_bytes());
+
+        let ephemeral_public = ephemeral.public_key();
+        let [mut chaining_key] = kdf(&chaining_key, &ephemeral_public);
+        msg[8..40].copy_from_slice(&ephemeral_public);
+        h = hash(&[&h, &ephemeral_public]);
+
+        let [ck, mut key] = kdf(&chaining_key, &dh(&ephemeral, responder_public)?);
+        chaining_key = ck;
+        msg[40..88].copy_from_slice(&seal(&key, &static_private.public_key(), &h));
+        h = hash(&[&h, &msg[40..88]]);
+
+        let [ck, k] = kdf(&chaining_key, &dh(static_private, responder_public)?);
+        (chaining_key, key) = (ck, k);
+        msg[88..116].copy_from_slice(&seal(&key, timestamp, &h));
+        h = hash(&[&h, &msg[88..116]]);
+
+        let mac1 = mac1(responder_public, &msg[..116]);
+        msg[116..132].copy_from_slice(&mac1);
+
+        key.zeroize();
+
+        Ok((
+            Initiator {
+                chaining_key,
+                hash: h,
+                ephemeral,
+                psk: psk.copied().unwrap_or([0u8; 32]),
+                sender_index,
+            },
+            msg,
+        ))
+    }
+
+    pub fn consume_response(
+        self,
+        static_private: &PrivateKey,
+        msg: &[u8],
+    ) -> Result<TransportKeys, HandshakeError> {
+        if msg.len() != RESPONSE_LENGTH
+            || msg[..4] != [2, 0, 0, 0]
+            || msg[8..12] != self.sender_index.to_le_bytes()
+        {
+            return Err(HandshakeError::InvalidMessage);
+        }
+
+        if !verify_mac1(&static_private.public_key(), msg) {
+            return Err(HandshakeError::InvalidMac);
+        }
+
+        let responder_ephemeral = &msg[12..44];
+        let [chaining_key] = kdf(&self.chaining_key, responder_ephemeral);
+        let h = hash(&[&self.hash, responder_ephemeral]);
+
+        let [chaining_key] = kdf(&chaining_key, &dh(&self.ephemeral, responder_ephemeral)?);
+        let [chaining_key] = kdf(&chaining_key, &dh(static_private, responder_ephemeral)?);
+
+        let [chaining_key, tau, mut key] = kdf(&chaining_key, &self.psk);
+        let h = hash(&[&h, &tau]);
+        open(&key, &msg[44..60], &h)?;
+        key.zeroize();
+
+        let [sending, receiving] = kdf(&chaining_key, &[]);
+
+        Ok(TransportKeys {
+            sending,
+            receiving,
+            local_index: self.sender_index,
+            remote_index: u32::from_le_bytes(msg[4..8].try_into().unwrap()),
+        })
+    }
+}
+
+impl Drop for Initiator {
+    fn drop(&mut self) {
+        self.chaining_key.zeroize();
+        self.hash.zeroize();
+        self.psk.zeroize();
+    }
+}
+
+pub struct Responder {
+    chaining_key: [u8; 32],
+    hash: [u8; 32],
+    initiator_ephemeral: PublicKey,
+    initiator_public: PublicKey,
+    timestamp: [u8; 12],
+    remote_index: u32,
+}
+
+redacted_debug!(Responder, "<redacted>");
+
+impl Responder {
+    pub fn consume_initiation(
+        static_private: &PrivateKey,
+        msg: &[u8],
+    ) -> Result<Responder, HandshakeError> {
+        if msg.len() != INITIATION_LENGTH || msg[..4] != [1, 0, 0, 0] {
+            return Err(HandshakeError::InvalidMessage);
+        }
+
+        let static_public = static_private.public_key();
+
+        if !verify_mac1(&static_public, msg) {
+            return Err(HandshakeError::InvalidMac);
+        }
+
+        let (chaining_key, h) = initial_state(&static_public);
+
+        let initiator_ephemeral: PublicKey = msg[8..40].try_into().unwrap();
+        let [chaining_key] = kdf(&chaining_key, &initiator_ephemeral);
+        let h = hash(&[&h, &initiator_ephemeral]);
+
+        let [chaining_key, mut key] =
+            kdf(&chaining_key, &dh(static_private, &initiator_ephemeral)?);
+        let initiator_public: PublicKey = open(&key, &msg[40..88], &h)?.try_into().unwrap();
+        let h = hash(&[&h, &msg[40..88]]);
+
+        let [chaining_key, k] = kdf(&chaining_key, &dh(static_private, &initiator_public)?);
+        key = k;
+        let timestamp = open(&key, &msg[88..116], &h)?.try_into().unwrap();
+        let h = hash(&[&h, &msg[88..116]]);
+
+        key.zeroize();
+
+        Ok(Responder {
+            chaining_key,
+            hash: h,
+            initiator_ephemeral,
+            initiator_public,
+            timestamp,
+            remote_index: u32::from_le_bytes(msg[4..8].try_into().unwrap()),
+        })
+    }
+
+    pub fn initiator_public(&self) -> PublicKey {
+        self.initiator_public
+    }
+
+    pub fn timestamp(&self) -> [u8; 12] {
+        self.timestamp
+    }
+
+    pub fn respond(
+        self,
+        psk: Option<&[u8; 32]>,
+        sender_index: u32,
+    ) -> Result<(TransportKeys, [u8; RESPONSE_LENGTH]), HandshakeError> {
+        self.respond_with_ephemeral(psk, sender_index, keygen(None))
+    }
+
+    pub fn respond_with_ephemeral(
+        self,
+        psk: Option<&[u8; 32]>,
+        sender_index: u32,
+        ephemeral: PrivateKey,
+    ) -> Result<(TransportKeys, [u8; RESPONSE_LENGTH]), HandshakeError> {
+        let mut msg = [0u8; RESPONSE_LENGTH];
+        msg[0] = 2;
+        msg[4..8].copy_from_slice(&sender_index.to_le_bytes());
+        msg[8..12].copy_from_slice(&self.remote_index.to_le_bytes());
+
+        let ephemeral_public = ephemeral.public_key();
+        let [chaining_key] = kdf(&self.chaining_key, &ephemeral_public);
+        msg[12..44].copy_from_slice(&ephemeral_public);
+        let h = hash(&[&self.hash, &ephemeral_public]);
+
+        let [chaining_key] = kdf(&chaining_key, &dh(&ephemeral, &self.initiator_ephemeral)?);
+        let [chaining_key] = kdf(&chaining_key, &dh(&ephemeral, &self.initiator_public)?);
+
+        let [chaining_key, tau, mut key] = kdf(&chaining_key, psk.unwrap_or(&[0u8; 32]));
+        let h = hash(&[&h, &tau]);
+        msg[44..60].copy_from_slice(&seal(&key, &[], &h));
+        key.zeroize();
+
+        let mac1 = mac1(&self.initiator_public, &msg[..60]);
+        msg[60..76].copy_from_slice(&mac1);
+
+        let [receiving, sending] = kdf(&chaining_key, &[]);
+
+        Ok((
+            TransportKeys {
+                sending,
+                receiving,
+                local_index: sender_index,
+                remote_index: self.remote_index,
+            },
+            msg,
+        ))
+    }
+}
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        self.chaining_key.zeroize();
+        self.hash.zeroize();
+    }
+}
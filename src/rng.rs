@@ -0,0 +1,129 @@
+// The source of every random byte raycrypt draws: keys, nonces, salts and share coefficients. It
+// is the operating system's generator unless a test installs another on its thread through
+// `insecure_override`, such as a `DeterministicRng` to make protocol tests reproducible, or a
+// source of its own that injects faults like repeated nonces. Overrides only apply to the thread
+// that installed them; every other thread, rayon's workers included, keeps the operating system.
+
+use crate::ciphers::chacha::ChaCha20;
+use crate::utils::redacted_debug;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub trait RandomSource {
+    fn fill(&mut self, dest: &mut [u8]);
+}
+
+/// The operating system's generator, through `getrandom`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsRng;
+
+impl RandomSource for OsRng {
+    fn fill(&mut self, dest: &mut [u8]) {
+        getrandom::getrandom(dest).unwrap();
+    }
+}
+
+/// The ChaCha20 keystream under a seed. Anyone with the seed can predict every byte, so it is
+/// only for tests.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct DeterministicRng {
+    cipher: ChaCha20,
+    block: u64,
+    buffer: [u8; 64],
+    used: usize,
+}
+
+redacted_debug!(DeterministicRng, "<32 bytes, redacted>");
+
+impl DeterministicRng {
+    pub const SEED_SIZE: usize = 32;
+
+    pub fn new(seed: &[u8]) -> DeterministicRng {
+        assert_eq!(seed.len(), Self::SEED_SIZE);
+
+        DeterministicRng {
+            cipher: ChaCha20::new(seed),
+            block: 0,
+            buffer: [0u8; 64],
+            used: 64,
+        }
+    }
+
+    /// A seed spread from a number, for tests that just need distinct streams.
+    pub fn from_u64(seed: u64) -> DeterministicRng {
+        let mut bytes = [0u8; Self::SEED_SIZE];
+        bytes[..8].copy_from_slice(&seed.to_le_bytes());
+
+        DeterministicRng::new(&bytes)
+    }
+}
+
+impl RandomSource for DeterministicRng {
+    fn fill(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            if self.used == self.buffer.len() {
+                // the high half of the block number goes in the nonce, so the stream never wraps
+                let mut nonce = [0u8; 12];
+                nonce[4..].copy_from_slice(&(self.block >> 32).to_le_bytes());
+
+                self.buffer = self.cipher.keystream(&nonce, self.block as u32);
+                self.block += 1;
+                self.used = 0;
+            }
+
+            *byte = self.buffer[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+// per thread rather than per process, so an override in one thread never leaks into another
+thread_local! {
+    static OVERRIDE: RefCell<Option<Box<dyn RandomSource>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous source of the thread when dropped.
+#[must_use = "the override ends as soon as this is dropped"]
+pub struct InsecureOverride {
+    previous: Option<Box<dyn RandomSource>>,
+    // the override belongs to the thread that installed it
+    _thread: PhantomData<*const ()>,
+}
+
+impl std::fmt::Debug for InsecureOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InsecureOverride")
+    }
+}
+
+impl Drop for InsecureOverride {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        OVERRIDE.with(|source| *source.borrow_mut() = previous);
+    }
+}
+
+/// Makes `source` supply every random byte this thread draws, keys and nonces included, until
+/// the returned guard is dropped. Keys made this way are only as secret as `source`, so this is
+/// for tests and never for production.
+pub fn insecure_override<R: RandomSource + 'static>(source: R) -> InsecureOverride {
+    let previous = OVERRIDE.with(|current| current.borrow_mut().replace(Box::new(source)));
+
+    InsecureOverride {
+        previous,
+        _thread: PhantomData,
+    }
+}
+
+/// Whether this thread's random bytes come from an override rather than the operating system.
+pub fn is_overridden() -> bool {
+    OVERRIDE.with(|source| source.borrow().is_some())
+}
+
+pub fn fill(dest: &mut [u8]) {
+    OVERRIDE.with(|source| match source.borrow_mut().as_mut() {
+        Some(source) => source.fill(dest),
+        None => OsRng.fill(dest),
+    })
+}
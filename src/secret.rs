@@ -0,0 +1,61 @@
+// Text encodings of key material. The hex and base64 codecs behind these run in constant time,
+// and every intermediate buffer is wiped, so a key written out or read back leaves no copies.
+
+use crate::encoding::{base64, hex};
+use crate::errors::Error;
+use crate::utils::redacted_debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A string holding a secret, wiped on drop and redacted from `Debug`.
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct SecretString(String);
+
+redacted_debug!(SecretString, "<redacted>");
+
+impl SecretString {
+    pub fn new(secret: String) -> SecretString {
+        SecretString(secret)
+    }
+
+    pub fn encode_hex(secret: &[u8]) -> SecretString {
+        SecretString(hex::encode(secret))
+    }
+
+    pub fn encode_base64(secret: &[u8]) -> SecretString {
+        SecretString(base64::encode(secret))
+    }
+
+    /// Decodes lowercase or uppercase hex. The caller has to wipe the bytes.
+    pub fn decode_hex(&self) -> Result<Vec<u8>, Error> {
+        Ok(hex::decode(&self.0)?)
+    }
+
+    /// Decodes padded base64. The caller has to wipe the bytes.
+    pub fn decode_base64(&self) -> Result<Vec<u8>, Error> {
+        Ok(base64::decode(&self.0)?)
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> SecretString {
+        SecretString(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> SecretString {
+        SecretString(secret.to_string())
+    }
+}
@@ -0,0 +1,282 @@
+use crate::aeads::SymmetricKey;
+use crate::encoding::hex;
+use crate::gf256::{inverse, mul};
+use crate::hashes::sha256;
+use crate::utils::{random_bytes, redacted_debug};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const KIT_VERSION: &str = "rck1";
+const KIT_ID_LENGTH: usize = 4;
+const CHECKSUM_LENGTH: usize = 4;
+const GROUP_LENGTH: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShareError {
+    InvalidThreshold,
+    Malformed,
+    ChecksumMismatch,
+    MismatchedShares,
+    DuplicateShare,
+    NotEnoughShares,
+}
+
+impl std::fmt::Display for ShareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareError::InvalidThreshold => write!(
+                f,
+                "The threshold must be between 1 and the number of shares."
+            ),
+            ShareError::Malformed => write!(f, "The share is not correctly encoded."),
+            ShareError::ChecksumMismatch => {
+                write!(f, "The share checksum does not match. It may be mistyped.")
+            }
+            ShareError::MismatchedShares => {
+                write!(f, "The shares do not come from the same secret.")
+            }
+            ShareError::DuplicateShare => write!(f, "The same share was given twice."),
+            ShareError::NotEnoughShares => write!(f, "More shares are needed to recover."),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// One point on the sharing polynomials, at x = `index`.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Share {
+    index: u8,
+    value: Vec<u8>,
+}
+
+redacted_debug!(Share, "<redacted>");
+
+impl Share {
+    pub fn new(index: u8, value: &[u8]) -> Share {
+        Share {
+            index,
+            value: value.to_vec(),
+        }
+    }
+
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+/// Shamir's secret sharing over GF(2^8): splits `secret` into `shares` shares, any `threshold`
+/// of which recover it and fewer of which reveal nothing about it.
+pub fn split(secret: &[u8], shares: u8, threshold: u8) -> Result<Vec<Share>, ShareError> {
+    if threshold == 0 || threshold > shares {
+        return Err(ShareError::InvalidThreshold);
+    }
+
+    let mut output: Vec<Share> = (1..=shares)
+        .map(|index| Share {
+            index,
+            value: Vec::with_capacity(secret.len()),
+        })
+        .collect();
+
+    let mut coefficients = vec![0u8; threshold as usize];
+
+    for &byte in secret {
+        coefficients[0] = byte;
+        crate::rng::fill(&mut coefficients[1..]);
+
+        for share in output.iter_mut() {
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0, |y, &coefficient| mul(y, share.index) ^ coefficient);
+
+            share.value.push(y);
+        }
+    }
+
+    coefficients.zeroize();
+
+    Ok(output)
+}
+
+fn check_shares(shares: &[Share]) -> Result<(), ShareError> {
+    let Some(first) = shares.first() else {
+        return Err(ShareError::NotEnoughShares);
+    };
+
+    for (i, share) in shares.iter().enumerate() {
+        if share.index == 0 || share.value.len() != first.value.len() {
+            return Err(ShareError::MismatchedShares);
+        }
+
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(ShareError::DuplicateShare);
+        }
+    }
+
+    Ok(())
+}
+
+/// Recovers the secret from at least as many shares as the threshold it was split with. Fewer
+/// shares give a wrong secret rather than an error, since the threshold is not recorded in them.
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>, ShareError> {
+    check_shares(shares)?;
+
+    let mut secret = vec![0u8; shares[0].value.len()];
+
+    for share in shares {
+        // the Lagrange basis polynomial for this share, evaluated at 0
+        let mut numerator = 1;
+        let mut denominator = 1;
+
+        for other in shares.iter().filter(|other| other.index != share.index) {
+            numerator = mul(numerator, other.index);
+            denominator = mul(denominator, other.index ^ share.index);
+        }
+
+        let basis = mul(numerator, inverse(denominator));
+
+        for (byte, &y) in secret.iter_mut().zip(&share.value) {
+            *byte ^= mul(basis, y);
+        }
+    }
+
+    Ok(secret)
+}
+
+fn checksum(body: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    sha256::hash(body)[..CHECKSUM_LENGTH].try_into().unwrap()
+}
+
+/// Splits `key` into `shares` printable recovery shares, any `threshold` of which recover it with
+/// `recover_from_shares`. Each share reads
+/// `rck1-<kit id>-<threshold>-<index>-<share in groups of 8 hex digits>-<checksum>`, where the
+/// kit id keeps shares from different kits apart and the checksum catches transcription errors.
+pub fn create_recovery_kit(
+    key: &SymmetricKey,
+    shares: u8,
+    threshold: u8,
+) -> Result<Vec<String>, ShareError> {
+    let kit_id: [u8; KIT_ID_LENGTH] = random_bytes();
+
+    let kit = split(key.as_bytes(), shares, threshold)?
+        .iter()
+        .map(|share| {
+            let mut body = kit_id.to_vec();
+            body.extend_from_slice(&[threshold, share.index]);
+            body.extend_from_slice(&share.value);
+
+            let mut value = hex::encode(&share.value);
+            let groups: Vec<&str> = value
+                .as_bytes()
+                .chunks(GROUP_LENGTH)
+                .map(|group| std::str::from_utf8(group).unwrap())
+                .collect();
+
+            let printed = format!(
+                "{}-{}-{}-{}-{}-{}",
+                KIT_VERSION,
+                hex::encode(&kit_id),
+                threshold,
+                share.index,
+                groups.join("-"),
+                hex::encode(&checksum(&body))
+            );
+
+            body.zeroize();
+            value.zeroize();
+
+            printed
+        })
+        .collect();
+
+    Ok(kit)
+}
+
+struct KitShare {
+    kit_id: Vec<u8>,
+    threshold: u8,
+    share: Share,
+}
+
+fn parse_share(printed: &str) -> Result<KitShare, ShareError> {
+    let printed = printed.trim().to_ascii_lowercase();
+    let fields: Vec<&str> = printed.split('-').collect();
+
+    let [version, kit_id, threshold, index, groups @ .., check] = &fields[..] else {
+        return Err(ShareError::Malformed);
+    };
+
+    if *version != KIT_VERSION || groups.iter().any(|group| group.len() != GROUP_LENGTH) {
+        return Err(ShareError::Malformed);
+    }
+
+    let kit_id = hex::decode(kit_id).map_err(|_| ShareError::Malformed)?;
+    let threshold: u8 = threshold.parse().map_err(|_| ShareError::Malformed)?;
+    let index: u8 = index.parse().map_err(|_| ShareError::Malformed)?;
+    let expected = hex::decode(check).map_err(|_| ShareError::Malformed)?;
+
+    let mut value = hex::decode(&groups.concat()).map_err(|_| ShareError::Malformed)?;
+
+    if kit_id.len() != KIT_ID_LENGTH || value.len() != SymmetricKey::KEY_SIZE {
+        value.zeroize();
+        return Err(ShareError::Malformed);
+    }
+
+    let mut body = kit_id.clone();
+    body.extend_from_slice(&[threshold, index]);
+    body.extend_from_slice(&value);
+
+    let valid = expected == checksum(&body);
+    body.zeroize();
+
+    let share = Share { index, value };
+
+    if !valid {
+        return Err(ShareError::ChecksumMismatch);
+    }
+
+    Ok(KitShare {
+        kit_id,
+        threshold,
+        share,
+    })
+}
+
+/// Recovers a key from printed shares made by `create_recovery_kit`. Whitespace around a share
+/// and the case of its letters do not matter.
+pub fn recover_from_shares<S: AsRef<str>>(shares: &[S]) -> Result<SymmetricKey, ShareError> {
+    let parsed = shares
+        .iter()
+        .map(|share| parse_share(share.as_ref()))
+        .collect::<Result<Vec<KitShare>, ShareError>>()?;
+
+    let Some(first) = parsed.first() else {
+        return Err(ShareError::NotEnoughShares);
+    };
+
+    if parsed
+        .iter()
+        .any(|share| share.kit_id != first.kit_id || share.threshold != first.threshold)
+    {
+        return Err(ShareError::MismatchedShares);
+    }
+
+    let shares: Vec<Share> = parsed.iter().map(|share| share.share.clone()).collect();
+
+    check_shares(&shares)?;
+
+    if shares.len() < first.threshold as usize {
+        return Err(ShareError::NotEnoughShares);
+    }
+
+    let mut secret = combine(&shares)?;
+    let key = SymmetricKey::new(&secret).unwrap();
+    secret.zeroize();
+
+    Ok(key)
+}
@@ -0,0 +1,265 @@
+// An append-only, tamper-evident log, for audit trails. Every entry's hash covers the hash of the
+// one before it, so the latest hash (the head) commits to the whole log, and every so often the
+// writer signs the head and the log size in a checkpoint. Editing, reordering or removing an
+// entry changes every later hash and no longer matches the checkpoints.
+//
+// Entries after the last checkpoint are chained but not yet signed, so they can be dropped
+// unnoticed, as can whole checkpoints along with the entries they cover. A reader catches the
+// latter by keeping a checkpoint from an earlier read and passing it in again.
+
+use crate::ecc::ed25519::{Signature, SigningKey, VerifyingKey};
+use crate::hashes::sha256::Sha256;
+use crate::utils::redacted_debug;
+
+const ENTRY_LABEL: &[u8] = b"raycrypt signed log entry";
+const CHECKPOINT_LABEL: &[u8] = b"raycrypt signed log checkpoint";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogError {
+    Malformed,
+    /// A checkpoint's signature does not verify under the log's key.
+    InvalidCheckpoint,
+    /// The entries do not hash to the head a checkpoint signed.
+    Tampered,
+    /// A checkpoint covers more entries than the log has.
+    Truncated,
+}
+
+impl std::fmt::Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogError::Malformed => write!(f, "The checkpoint is malformed."),
+            LogError::InvalidCheckpoint => write!(f, "A checkpoint signature does not verify."),
+            LogError::Tampered => write!(f, "The log does not match its checkpoints."),
+            LogError::Truncated => write!(f, "The log is shorter than its checkpoints."),
+        }
+    }
+}
+
+impl std::error::Error for LogError {}
+
+// H(index || previous head || data), with the empty log's head all zeros
+fn chain(head: &[u8; 32], index: u64, data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ENTRY_LABEL);
+    hasher.update(&index.to_be_bytes());
+    hasher.update(head);
+    hasher.update(&(data.len() as u64).to_be_bytes());
+    hasher.update(data);
+
+    hasher.digest()
+}
+
+/// A signed statement that the log's first `size` entries hash to `head`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    size: u64,
+    head: [u8; 32],
+    signature: Signature,
+}
+
+impl Checkpoint {
+    pub const SIZE: usize = 8 + 32 + 64;
+
+    fn signed_message(size: u64, head: &[u8; 32]) -> Vec<u8> {
+        [CHECKPOINT_LABEL, &size.to_be_bytes(), head].concat()
+    }
+
+    fn sign(key: &SigningKey, size: u64, head: [u8; 32]) -> Checkpoint {
+        Checkpoint {
+            size,
+            head,
+            signature: key.sign(&Checkpoint::signed_message(size, &head)),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Checkpoint, LogError> {
+        if bytes.len() != Checkpoint::SIZE {
+            return Err(LogError::Malformed);
+        }
+
+        Ok(Checkpoint {
+            size: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+            head: bytes[8..40].try_into().unwrap(),
+            signature: bytes[40..].try_into().unwrap(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; Checkpoint::SIZE] {
+        let mut output = [0u8; Checkpoint::SIZE];
+        output[..8].copy_from_slice(&self.size.to_be_bytes());
+        output[8..40].copy_from_slice(&self.head);
+        output[40..].copy_from_slice(&self.signature);
+
+        output
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn head(&self) -> &[u8; 32] {
+        &self.head
+    }
+
+    /// Checks the signature alone, without the entries.
+    pub fn verify(&self, public: &VerifyingKey) -> Result<(), LogError> {
+        public
+            .verify(
+                &Checkpoint::signed_message(self.size, &self.head),
+                &self.signature,
+            )
+            .map_err(|_| LogError::InvalidCheckpoint)
+    }
+}
+
+/// The writer's side: appends entries and signs a checkpoint every `interval` of them.
+pub struct SignedLog {
+    key: SigningKey,
+    interval: u64,
+    entries: Vec<Vec<u8>>,
+    checkpoints: Vec<Checkpoint>,
+    head: [u8; 32],
+}
+
+redacted_debug!(SignedLog, "<redacted>");
+
+impl SignedLog {
+    pub fn new(key: SigningKey, interval: u64) -> SignedLog {
+        assert!(interval > 0);
+
+        SignedLog {
+            key,
+            interval,
+            entries: Vec::new(),
+            checkpoints: Vec::new(),
+            head: [0u8; 32],
+        }
+    }
+
+    /// Picks up a stored log where it left off, after verifying it against `checkpoints`.
+    pub fn resume(
+        key: SigningKey,
+        interval: u64,
+        entries: Vec<Vec<u8>>,
+        checkpoints: Vec<Checkpoint>,
+    ) -> Result<SignedLog, LogError> {
+        assert!(interval > 0);
+
+        verify(&key.verifying_key(), &entries, &checkpoints)?;
+        let head = entries
+            .iter()
+            .enumerate()
+            .fold([0u8; 32], |head, (index, data)| {
+                chain(&head, index as u64, data)
+            });
+
+        Ok(SignedLog {
+            key,
+            interval,
+            entries,
+            checkpoints,
+            head,
+        })
+    }
+
+    /// Appends an entry, returning its index, and signs a checkpoint if one is due.
+    pub fn append(&mut self, data: &[u8]) -> u64 {
+        let index = self.entries.len() as u64;
+
+        self.head = chain(&self.head, index, data);
+        self.entries.push(data.to_vec());
+
+        if (index + 1).is_multiple_of(self.interval) {
+            self.checkpoint();
+        }
+
+        index
+    }
+
+    /// Signs the current head now, such as before closing the log, so no entry is left unsigned.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let size = self.entries.len() as u64;
+
+        if let Some(last) = self.checkpoints.last().filter(|last| last.size == size) {
+            return *last;
+        }
+
+        let checkpoint = Checkpoint::sign(&self.key, size, self.head);
+        self.checkpoints.push(checkpoint);
+
+        checkpoint
+    }
+
+    pub fn entries(&self) -> &[Vec<u8>] {
+        &self.entries
+    }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    pub fn len(&self) -> u64 {
+        self.entries.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn head(&self) -> &[u8; 32] {
+        &self.head
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+}
+
+/// Checks `entries` against every checkpoint, in any order, returning how many entries the
+/// checkpoints vouch for. Entries past that count are unsigned and could have been added by
+/// anyone, or removed without trace.
+pub fn verify<E: AsRef<[u8]>>(
+    public: &VerifyingKey,
+    entries: &[E],
+    checkpoints: &[Checkpoint],
+) -> Result<u64, LogError> {
+    for checkpoint in checkpoints {
+        checkpoint.verify(public)?;
+
+        if checkpoint.size > entries.len() as u64 {
+            return Err(LogError::Truncated);
+        }
+    }
+
+    let mut sizes: Vec<u64> = checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.size)
+        .collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    // the heads at each checkpointed size, hashing no further than the last of them
+    let mut heads = Vec::with_capacity(sizes.len());
+    let mut head = [0u8; 32];
+    let mut index = 0;
+
+    for size in &sizes {
+        while index < *size {
+            head = chain(&head, index, entries[index as usize].as_ref());
+            index += 1;
+        }
+
+        heads.push(head);
+    }
+
+    for checkpoint in checkpoints {
+        let position = sizes.binary_search(&checkpoint.size).unwrap();
+
+        if heads[position] != checkpoint.head {
+            return Err(LogError::Tampered);
+        }
+    }
+
+    Ok(sizes.last().copied().unwrap_or(0))
+}
@@ -3,6 +3,10 @@ pub(crate) fn from_le_bytes(x: &[u8]) -> u32 {
 }
 
 pub(crate) fn const_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
     let mut temp = 0;
 
     for (i, j) in a.iter().zip(b.iter()) {
@@ -11,3 +15,22 @@ pub(crate) fn const_time_eq(a: &[u8], b: &[u8]) -> bool {
 
     temp == 0
 }
+
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    crate::rng::fill(&mut bytes);
+
+    bytes
+}
+
+macro_rules! redacted_debug {
+    ($type:ident $(<$($generic:ident: $bound:path),+>)?, $contents:literal) => {
+        impl$(<$($generic: $bound),+>)? std::fmt::Debug for $type$(<$($generic),+>)? {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}({})", stringify!($type), $contents)
+            }
+        }
+    };
+}
+
+pub(crate) use redacted_debug;
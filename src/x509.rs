@@ -0,0 +1,228 @@
+// Just enough DER X.509 to check certificates from systems that sign artifacts with them: the
+// issuer, subject, serial and subject public key are extracted, and the signature can be checked
+// against an issuer's key.
+//
+// Ed25519, X25519 and ECDSA P-256/P-384 keys are all extracted, but only Ed25519 signatures can
+// be verified, so only chains signed with Ed25519 all the way up verify. ECDSA signatures fail
+// with `CertificateError::UnsupportedAlgorithm`, as there is no NIST curve arithmetic here.
+
+use crate::ecc::ed25519::VerifyingKey;
+use crate::ecc::x25519::PublicKey;
+use crate::encoding::der::{self, DerReader};
+use crate::encoding::{pem, InvalidEncoding};
+
+const ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+const X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const SECP384R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+const ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+const ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CertificateError {
+    Malformed,
+    UnsupportedAlgorithm,
+    InvalidSignature,
+}
+
+impl std::fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CertificateError::Malformed => write!(f, "The certificate is malformed."),
+            CertificateError::UnsupportedAlgorithm => {
+                write!(f, "The certificate uses an unsupported algorithm.")
+            }
+            CertificateError::InvalidSignature => {
+                write!(f, "The certificate signature is invalid.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl From<InvalidEncoding> for CertificateError {
+    fn from(_: InvalidEncoding) -> Self {
+        CertificateError::Malformed
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    Ed25519,
+    EcdsaWithSha256,
+    EcdsaWithSha384,
+}
+
+impl SignatureAlgorithm {
+    fn from_der(reader: &mut DerReader) -> Result<SignatureAlgorithm, CertificateError> {
+        let mut algorithm = reader.read_sequence()?;
+        let oid = algorithm.read(der::OID)?;
+        algorithm.finish()?;
+
+        match oid {
+            ED25519 => Ok(SignatureAlgorithm::Ed25519),
+            ECDSA_WITH_SHA256 => Ok(SignatureAlgorithm::EcdsaWithSha256),
+            ECDSA_WITH_SHA384 => Ok(SignatureAlgorithm::EcdsaWithSha384),
+            _ => Err(CertificateError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum SubjectPublicKey {
+    Ed25519(VerifyingKey),
+    X25519(PublicKey),
+    EcdsaP256([u8; 65]),
+    EcdsaP384([u8; 97]),
+}
+
+impl SubjectPublicKey {
+    pub fn from_der(der: &[u8]) -> Result<SubjectPublicKey, CertificateError> {
+        let mut reader = DerReader::new(der);
+        let key = SubjectPublicKey::read(&mut reader)?;
+        reader.finish()?;
+
+        Ok(key)
+    }
+
+    fn read(reader: &mut DerReader) -> Result<SubjectPublicKey, CertificateError> {
+        let mut info = reader.read_sequence()?;
+        let mut algorithm = info.read_sequence()?;
+        let key = info.read_bit_string()?;
+        info.finish()?;
+
+        let oid = algorithm.read(der::OID)?;
+
+        let output = match oid {
+            ED25519 => VerifyingKey::new(key)
+                .map(SubjectPublicKey::Ed25519)
+                .map_err(|_| CertificateError::Malformed)?,
+            X25519 => key
+                .try_into()
+                .map(SubjectPublicKey::X25519)
+                .map_err(|_| CertificateError::Malformed)?,
+            EC_PUBLIC_KEY => match algorithm.read(der::OID)? {
+                PRIME256V1 => key
+                    .try_into()
+                    .map(SubjectPublicKey::EcdsaP256)
+                    .map_err(|_| CertificateError::Malformed)?,
+                SECP384R1 => key
+                    .try_into()
+                    .map(SubjectPublicKey::EcdsaP384)
+                    .map_err(|_| CertificateError::Malformed)?,
+                _ => return Err(CertificateError::UnsupportedAlgorithm),
+            },
+            _ => return Err(CertificateError::UnsupportedAlgorithm),
+        };
+
+        algorithm.finish()?;
+
+        Ok(output)
+    }
+
+    /// Only Ed25519 signatures can be verified: ECDSA ones fail with `UnsupportedAlgorithm`.
+    pub fn verify(
+        &self,
+        algorithm: SignatureAlgorithm,
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CertificateError> {
+        match (self, algorithm) {
+            (SubjectPublicKey::Ed25519(key), SignatureAlgorithm::Ed25519) => key
+                .verify(msg, signature)
+                .map_err(|_| CertificateError::InvalidSignature),
+            (SubjectPublicKey::X25519(_), _) => Err(CertificateError::InvalidSignature),
+            // ECDSA keys and signatures parse, but cannot be checked
+            _ => Err(CertificateError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+pub struct Certificate {
+    tbs: Vec<u8>,
+    serial: Vec<u8>,
+    issuer: Vec<u8>,
+    subject: Vec<u8>,
+    public_key: SubjectPublicKey,
+    signature_algorithm: SignatureAlgorithm,
+    signature: Vec<u8>,
+}
+
+impl Certificate {
+    /// Parses certificates signed with Ed25519 or ECDSA, though only the Ed25519 signatures can
+    /// later be verified.
+    pub fn from_der(der: &[u8]) -> Result<Certificate, CertificateError> {
+        let mut outer = DerReader::new(der);
+        let mut certificate = outer.read_sequence()?;
+        outer.finish()?;
+
+        let tbs = certificate.read_full(der::SEQUENCE)?;
+        let signature_algorithm = SignatureAlgorithm::from_der(&mut certificate)?;
+        let signature = certificate.read_bit_string()?;
+        certificate.finish()?;
+
+        let mut reader = DerReader::new(tbs);
+        let mut fields = reader.read_sequence()?;
+
+        fields.read_optional(der::context(0))?;
+        let serial = fields.read(der::INTEGER)?;
+
+        if SignatureAlgorithm::from_der(&mut fields)? != signature_algorithm {
+            return Err(CertificateError::Malformed);
+        }
+
+        let issuer = fields.read_full(der::SEQUENCE)?;
+        fields.read(der::SEQUENCE)?;
+        let subject = fields.read_full(der::SEQUENCE)?;
+        let public_key = SubjectPublicKey::read(&mut fields)?;
+
+        Ok(Certificate {
+            tbs: tbs.to_vec(),
+            serial: serial.to_vec(),
+            issuer: issuer.to_vec(),
+            subject: subject.to_vec(),
+            public_key,
+            signature_algorithm,
+            signature: signature.to_vec(),
+        })
+    }
+
+    pub fn from_pem(data: &str) -> Result<Certificate, CertificateError> {
+        Certificate::from_der(&pem::decode("CERTIFICATE", data)?)
+    }
+
+    pub fn serial(&self) -> &[u8] {
+        &self.serial
+    }
+
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+
+    pub fn subject(&self) -> &[u8] {
+        &self.subject
+    }
+
+    pub fn public_key(&self) -> &SubjectPublicKey {
+        &self.public_key
+    }
+
+    pub fn signature_algorithm(&self) -> SignatureAlgorithm {
+        self.signature_algorithm
+    }
+
+    /// Fails with `UnsupportedAlgorithm` unless the certificate is signed with Ed25519.
+    pub fn verify_signature(&self, issuer_key: &SubjectPublicKey) -> Result<(), CertificateError> {
+        issuer_key.verify(self.signature_algorithm, &self.tbs, &self.signature)
+    }
+
+    pub fn verify_signed_by(&self, issuer: &Certificate) -> Result<(), CertificateError> {
+        if self.issuer != issuer.subject {
+            return Err(CertificateError::InvalidSignature);
+        }
+
+        self.verify_signature(&issuer.public_key)
+    }
+}
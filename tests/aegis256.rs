@@ -1,5 +1,6 @@
 use hex::decode;
-use raycrypt::aeads::aegis256::{decrypt, encrypt};
+use raycrypt::aeads::aegis256::{decrypt, decrypt_with_backend, encrypt, encrypt_with_backend};
+use raycrypt::backend::Backend;
 use serde_json::{from_str, Value};
 use std::fs;
 
@@ -43,6 +44,20 @@ fn test_aegis256_wycheproof() {
             assert_eq!(decrypted.unwrap(), pt);
         } else {
             assert_ne!(output, expected);
+            assert!(decrypt::<16>(&key, &expected, &nonce, &aad).is_err());
+        }
+
+        for backend in [Backend::Scalar, Backend::Avx2] {
+            let output = encrypt_with_backend::<16>(backend, &key, &pt, &nonce, &aad);
+            let decrypted = decrypt_with_backend::<16>(backend, &key, &expected, &nonce, &aad);
+
+            if test["result"].as_str().unwrap() == "valid" {
+                assert_eq!(output, expected);
+                assert_eq!(decrypted.unwrap(), pt);
+            } else {
+                assert_ne!(output, expected);
+                assert!(decrypted.is_err());
+            }
         }
     }
 }
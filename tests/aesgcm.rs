@@ -0,0 +1,150 @@
+use hex::decode;
+use raycrypt::aeads::AesGcm;
+use raycrypt::backend::Backend;
+use raycrypt::ciphers::aes::Aes;
+use serde_json::{from_str, Value};
+use std::fs;
+
+const BACKENDS: [Backend; 2] = [Backend::Scalar, Backend::Avx2];
+
+// FIPS 197, appendix C
+#[test]
+fn test_aes_block() {
+    let vectors = [
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "8ea2b7ca516745bfeafc49904b496089",
+        ),
+    ];
+
+    for backend in BACKENDS {
+        for (key, expected) in vectors {
+            let aes = Aes::with_backend(&decode(key).unwrap(), backend).unwrap();
+
+            let mut blocks = [decode("00112233445566778899aabbccddeeff")
+                .unwrap()
+                .try_into()
+                .unwrap(); 11];
+            aes.encrypt_blocks(&mut blocks);
+
+            for block in blocks {
+                assert_eq!(block.to_vec(), decode(expected).unwrap());
+            }
+        }
+    }
+
+    assert!(Aes::new(&[0u8; 20]).is_err());
+}
+
+#[test]
+fn test_aes_gcm_vectors() {
+    let raw = fs::read_to_string("tests/vectors/aes-gcm.json").unwrap();
+    let data: Value = from_str(&raw).unwrap();
+
+    for backend in BACKENDS {
+        for test in data["tests"].as_array().unwrap() {
+            let field = |name: &str| decode(test[name].as_str().unwrap()).unwrap();
+
+            let cipher = AesGcm::with_backend(&field("key"), backend).unwrap();
+            let expected = [field("ct"), field("tag")].concat();
+
+            let output = cipher.encrypt(&field("msg"), &field("nonce"), &field("ad"));
+
+            assert_eq!(output.ciphertext(), field("ct"));
+            assert_eq!(output.tag().to_vec(), field("tag"));
+            assert_eq!(*output, expected);
+            assert_eq!(
+                cipher.decrypt(&expected, &field("nonce"), &field("ad")),
+                Ok(field("msg"))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_aes_gcm_tampering() {
+    let cipher = AesGcm::new(&[1u8; 32]).unwrap();
+    let nonce = [2u8; 12];
+
+    let mut ct = cipher
+        .encrypt(b"attack at dawn", &nonce, b"header")
+        .into_vec();
+
+    assert!(cipher.decrypt(&ct, &nonce, b"footer").is_err());
+    assert!(cipher.decrypt(&ct[..15], &nonce, b"header").is_err());
+
+    ct[3] ^= 1;
+    assert!(cipher.decrypt(&ct, &nonce, b"header").is_err());
+
+    assert!(AesGcm::new(&[0u8; 31]).is_err());
+}
+
+#[test]
+fn test_aes_gcm_truncated_tags() {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce: Vec<u8> = (0..12).collect();
+    let cipher = AesGcm::new(&key).unwrap();
+
+    // made with Python's cryptography package
+    let ct = cipher.encrypt_with_tag_size(b"short telemetry frame", &nonce, b"radio header", 12);
+    assert_eq!(
+        hex::encode(&ct),
+        "346ab969b1c5b67ee124faeec59b014de5a4e65995fae0b131bcbd8682bce574b1"
+    );
+    assert_eq!(
+        cipher
+            .decrypt_with_tag_size(&ct, &nonce, b"radio header", 12)
+            .unwrap(),
+        b"short telemetry frame"
+    );
+
+    let full = cipher.encrypt(b"short telemetry frame", &nonce, b"radio header");
+
+    for tag_size in 12..=16 {
+        let ct = cipher.encrypt_with_tag_size(
+            b"short telemetry frame",
+            &nonce,
+            b"radio header",
+            tag_size,
+        );
+        assert_eq!(ct[..], full[..21 + tag_size]);
+        assert!(cipher
+            .decrypt_with_tag_size(&ct, &nonce, b"radio footer", tag_size)
+            .is_err());
+    }
+
+    assert!(cipher
+        .decrypt_with_tag_size(&full, &nonce, b"radio header", 12)
+        .is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_aes_gcm_tag_size_floor() {
+    AesGcm::new(&[1u8; 32])
+        .unwrap()
+        .encrypt_with_tag_size(b"", &[2u8; 12], b"", 8);
+}
+
+#[test]
+fn test_backends_agree() {
+    let msg: Vec<u8> = (0..5000).map(|i| (i * 7) as u8).collect();
+    let nonce = [9u8; 12];
+
+    let scalar = AesGcm::with_backend(&[3u8; 24], Backend::Scalar).unwrap();
+    let default = AesGcm::new(&[3u8; 24]).unwrap();
+
+    assert_eq!(scalar.backend(), "bitsliced");
+    assert_eq!(
+        scalar.encrypt(&msg, &nonce, &msg[..77]),
+        default.encrypt(&msg, &nonce, &msg[..77])
+    );
+}
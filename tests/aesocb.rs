@@ -0,0 +1,114 @@
+use hex::decode;
+use raycrypt::aeads::AesOcb;
+use raycrypt::errors::InvalidMac;
+
+// RFC 7253, appendix A, and a 15-byte nonce checked against pyca/cryptography
+#[test]
+fn test_vectors() {
+    let vectors = [
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "bbaa99887766554433221100",
+            0,
+            0,
+            "785407bfffc8ad9edcc5520ac9111ee6",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "bbaa99887766554433221101",
+            8,
+            8,
+            "6820b3657b6f615a5725bda0d3b4eb3a257c9af1f8f03009",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "bbaa99887766554433221105",
+            0,
+            16,
+            "9ffd50f147694cde9654ec6e7ce7d40adf7c3aaaa4d85a365a4e9e725d1c62f8",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "bbaa99887766554433221110",
+            40,
+            40,
+            "f6b1cfe767ccee4e3c72e608909408c86b924832c4c9ddae9f6c7069651aa65fb2a449351c9d60f06af7658756258fa4432d7841fd86e447",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "000102030405060708090a0b0c0d0e",
+            2,
+            33,
+            "67bf9416594be8e9637f1111a076eaeef769fbf3559db7b3e19ee48098fc21ed8d86635964375a859980f2a6e96d4402f7",
+        ),
+    ];
+
+    for (key, nonce, ad_length, msg_length, expected) in vectors {
+        let aes = AesOcb::new(&decode(key).unwrap()).unwrap();
+        let nonce = decode(nonce).unwrap();
+        let ad: Vec<u8> = if ad_length == 2 {
+            b"ad".to_vec()
+        } else {
+            (0..ad_length).collect()
+        };
+        let msg: Vec<u8> = (0..msg_length).collect();
+
+        let ct = aes.encrypt(&msg, &nonce, &ad);
+        assert_eq!(ct.to_vec(), decode(expected).unwrap());
+        assert_eq!(aes.decrypt(&ct, &nonce, &ad).unwrap(), msg);
+    }
+}
+
+// the iterated test of RFC 7253, appendix A, which covers every message and associated data length
+// up to 127 bytes
+#[test]
+fn test_iterated() {
+    let vectors = [
+        (16, "67e944d23256c5e0b6c61fa22fdf1ea2"),
+        (24, "f673f2c3e7174aae7bae986ca9f29e17"),
+        (32, "d90eb8e9c977c88b79dd793d7ffa161c"),
+    ];
+
+    for (key_length, expected) in vectors {
+        let mut key = vec![0u8; key_length];
+        key[key_length - 1] = 128;
+        let aes = AesOcb::new(&key).unwrap();
+
+        let nonce = |n: u64| [&[0u8; 4][..], &n.to_be_bytes()].concat();
+        let mut output = Vec::new();
+
+        for i in 0..128u64 {
+            let s = &vec![0u8; i as usize][..];
+
+            for (n, msg, ad) in [
+                (3 * i + 1, s, s),
+                (3 * i + 2, s, &[][..]),
+                (3 * i + 3, &[][..], s),
+            ] {
+                let ct = aes.encrypt(msg, &nonce(n), ad);
+                assert_eq!(aes.decrypt(&ct, &nonce(n), ad).unwrap(), msg);
+                output.extend_from_slice(&ct);
+            }
+        }
+
+        let tag = aes.encrypt(&[], &nonce(385), &output);
+        assert_eq!(tag.to_vec(), decode(expected).unwrap());
+    }
+}
+
+#[test]
+fn test_tampering() {
+    let aes = AesOcb::new(&[7u8; 32]).unwrap();
+    let nonce = AesOcb::generate_nonce();
+    let ct = aes.encrypt(b"attack at dawn, not at dusk", &nonce, b"ad");
+
+    for i in 0..ct.len() {
+        let mut tampered = ct.to_vec();
+        tampered[i] ^= 1;
+        assert_eq!(aes.decrypt(&tampered, &nonce, b"ad"), Err(InvalidMac));
+    }
+
+    assert_eq!(aes.decrypt(&ct, &nonce, b"da"), Err(InvalidMac));
+    assert_eq!(aes.decrypt(&ct[..15], &nonce, b"ad"), Err(InvalidMac));
+    assert!(AesOcb::new(&[0u8; 20]).is_err());
+}
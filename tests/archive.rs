@@ -0,0 +1,168 @@
+use raycrypt::aeads::archive::{ArchiveError, ArchiveWriter, EncryptedArchive, CHUNK_SIZE};
+use std::io::{Cursor, Read};
+
+const KEY: [u8; 32] = [7u8; 32];
+
+fn data(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i % 251) as u8).collect()
+}
+
+fn build(entries: &[(&str, &[u8], Vec<u8>)]) -> Vec<u8> {
+    let mut writer = ArchiveWriter::new(&KEY, Vec::new()).unwrap();
+
+    for (name, metadata, data) in entries {
+        assert_eq!(
+            writer.add(name, metadata, &data[..]).unwrap(),
+            data.len() as u64
+        );
+    }
+
+    writer.finish().unwrap()
+}
+
+// a reader that hands out a few bytes at a time
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let length = buf.len().min(self.0.len()).min(1000);
+        buf[..length].copy_from_slice(&self.0[..length]);
+        self.0 = &self.0[length..];
+
+        Ok(length)
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let entries = [
+        ("empty", &b""[..], Vec::new()),
+        ("small.txt", &b"mode=644"[..], b"hello".to_vec()),
+        ("exact.bin", &b""[..], data(CHUNK_SIZE)),
+        ("dir/large.bin", &b"mode=600"[..], data(2 * CHUNK_SIZE + 5)),
+    ];
+    let bytes = build(&entries);
+
+    let mut archive = EncryptedArchive::open(&KEY, Cursor::new(bytes)).unwrap();
+    let names: Vec<&str> = archive.entries().iter().map(|entry| entry.name()).collect();
+    assert_eq!(names, ["empty", "small.txt", "exact.bin", "dir/large.bin"]);
+
+    // in a different order from the one they were added in
+    for (name, metadata, data) in entries.iter().rev() {
+        let entry = archive.entry(name).unwrap();
+        assert_eq!(entry.metadata(), *metadata);
+        assert_eq!(entry.size(), data.len() as u64);
+        assert_eq!(&archive.read(name).unwrap(), data);
+    }
+
+    let mut extracted = Vec::new();
+    assert_eq!(archive.extract("small.txt", &mut extracted).unwrap(), 5);
+    assert_eq!(extracted, b"hello");
+
+    assert_eq!(archive.read("missing"), Err(ArchiveError::NotFound));
+}
+
+#[test]
+fn test_streaming_add() {
+    let large = data(3 * CHUNK_SIZE + 17);
+    let mut writer = ArchiveWriter::new(&KEY, Vec::new()).unwrap();
+    writer.add("trickled", b"", Trickle(&large)).unwrap();
+    let bytes = writer.finish().unwrap();
+
+    // the size of the data plus one tag per chunk, between the header and the index
+    assert!(bytes.len() > large.len() + 4 * 16);
+
+    let mut archive = EncryptedArchive::open(&KEY, Cursor::new(bytes)).unwrap();
+    assert_eq!(archive.read("trickled").unwrap(), large);
+}
+
+#[test]
+fn test_duplicate_names() {
+    let mut writer = ArchiveWriter::new(&KEY, Vec::new()).unwrap();
+    writer.add("a", b"", &b"first"[..]).unwrap();
+
+    assert_eq!(
+        writer.add("a", b"", &b"second"[..]),
+        Err(ArchiveError::DuplicateName)
+    );
+
+    writer.add("b", b"", &b"third"[..]).unwrap();
+    let mut archive = EncryptedArchive::open(&KEY, Cursor::new(writer.finish().unwrap())).unwrap();
+    assert_eq!(archive.read("a").unwrap(), b"first");
+    assert_eq!(archive.read("b").unwrap(), b"third");
+}
+
+#[test]
+fn test_wrong_key() {
+    let bytes = build(&[("a", b"", b"secret".to_vec())]);
+
+    assert_eq!(
+        EncryptedArchive::open(&[8u8; 32], Cursor::new(bytes)).unwrap_err(),
+        ArchiveError::InvalidMac
+    );
+}
+
+#[test]
+fn test_tampering() {
+    let bytes = build(&[
+        ("a", b"", b"first entry".to_vec()),
+        ("b", b"", b"other entry".to_vec()),
+    ]);
+
+    // the first entry's ciphertext starts after the 37-byte header
+    let mut tampered = bytes.clone();
+    tampered[40] ^= 1;
+    let mut archive = EncryptedArchive::open(&KEY, Cursor::new(tampered)).unwrap();
+    assert_eq!(archive.read("a"), Err(ArchiveError::InvalidMac));
+    assert_eq!(archive.read("b").unwrap(), b"other entry");
+
+    // entries of the same length swapped with each other
+    let mut swapped = bytes.clone();
+    let length = 11 + 16;
+    swapped[37..37 + 2 * length].rotate_left(length);
+    let mut archive = EncryptedArchive::open(&KEY, Cursor::new(swapped)).unwrap();
+    assert_eq!(archive.read("a"), Err(ArchiveError::InvalidMac));
+    assert_eq!(archive.read("b"), Err(ArchiveError::InvalidMac));
+
+    // the index
+    let mut tampered = bytes.clone();
+    let index = tampered.len() - 10;
+    tampered[index] ^= 1;
+    assert_eq!(
+        EncryptedArchive::open(&KEY, Cursor::new(tampered)).unwrap_err(),
+        ArchiveError::InvalidMac
+    );
+
+    // the salt, which the archive's key depends on
+    let mut tampered = bytes.clone();
+    tampered[5] ^= 1;
+    assert_eq!(
+        EncryptedArchive::open(&KEY, Cursor::new(tampered)).unwrap_err(),
+        ArchiveError::InvalidMac
+    );
+}
+
+#[test]
+fn test_malformed() {
+    let bytes = build(&[("a", b"", b"data".to_vec())]);
+
+    for length in [0, 10, 37, 45, bytes.len() - 1] {
+        assert!(EncryptedArchive::open(&KEY, Cursor::new(bytes[..length].to_vec())).is_err());
+    }
+
+    let mut tampered = bytes.clone();
+    tampered[0] ^= 1;
+    assert_eq!(
+        EncryptedArchive::open(&KEY, Cursor::new(tampered)).unwrap_err(),
+        ArchiveError::Malformed
+    );
+
+    // an index length that points before the header
+    let mut tampered = bytes.clone();
+    let last = tampered.len() - 2;
+    tampered[last] = 0xff;
+    assert_eq!(
+        EncryptedArchive::open(&KEY, Cursor::new(tampered)).unwrap_err(),
+        ArchiveError::Malformed
+    );
+}
@@ -0,0 +1,174 @@
+use hex::decode;
+use raycrypt::kdfs::argon2::{argon2id, Argon2, Argon2Error, Variant};
+use std::time::Duration;
+
+// RFC 9106, section 5
+#[test]
+fn test_argon2_rfc9106() {
+    let vectors = [
+        (
+            Variant::Argon2d,
+            "512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb",
+        ),
+        (
+            Variant::Argon2i,
+            "c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8",
+        ),
+        (
+            Variant::Argon2id,
+            "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659",
+        ),
+    ];
+
+    for (variant, expected) in vectors {
+        let argon2 = Argon2::new(variant, 32, 3, 4).unwrap();
+        let tag = argon2
+            .hash_with_secret(&[1u8; 32], &[2u8; 16], &[3u8; 8], &[4u8; 12], 32)
+            .unwrap();
+
+        assert_eq!(tag, decode(expected).unwrap(), "{variant:?}");
+    }
+}
+
+#[test]
+fn test_argon2_lengths() {
+    let expected = decode(
+        "3129e97c4e48114f59c1562e65960222c2ab5a5dd0bf043c6420d712a5f6048f2a5a84c85790e50c54bcf1ffe4\
+         7f962a5eae434b498dd9ac03483ee0f1e3c070",
+    )
+    .unwrap();
+
+    assert_eq!(
+        argon2id(b"password", b"somesaltsomesalt", 64, 2, 1, 64).unwrap(),
+        expected
+    );
+
+    // an output longer than one BLAKE2b digest, and memory that is not a multiple of 4p
+    let expected = decode(
+        "d30bcc0e25c9ec8e4e6aa4ead785fd1a1ecebce6a62b0b908e9703758104b19af610180774f43a8f5e67c8df9\
+         3447a552b6106791768a2f1b817fa4125ae8e8146960752cd9c36ca2e6359d7c8195b099ad67e2e97ae161e39\
+         78c3fe5e11d04534c6e407",
+    )
+    .unwrap();
+
+    let argon2 = Argon2::new(Variant::Argon2i, 50, 1, 3).unwrap();
+    assert_eq!(
+        argon2.hash(b"password", b"somesaltsomesalt", 100).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_argon2_threads() {
+    let expected =
+        decode("53da27a6a7aa59b8385042dc36da29c663b2978f8bad8e2289fda177273c374e").unwrap();
+
+    let argon2 = Argon2::new(Variant::Argon2id, 8192, 1, 8).unwrap();
+
+    for threads in [0, 1, 3, 8] {
+        let tag = argon2
+            .with_threads(threads)
+            .hash(b"password", b"somesaltsomesalt", 32)
+            .unwrap();
+
+        assert_eq!(tag, expected, "{threads}");
+    }
+}
+
+#[test]
+fn test_argon2_many_lanes() {
+    // one lane per thread would be 1024 threads, but no more than one per core are used
+    let argon2 = Argon2::new(Variant::Argon2id, 8192, 1, 1024).unwrap();
+    let tag = argon2.hash(b"password", b"somesaltsomesalt", 32).unwrap();
+
+    for _ in 0..4 {
+        assert_eq!(
+            argon2.hash(b"password", b"somesaltsomesalt", 32).unwrap(),
+            tag
+        );
+    }
+
+    let single = argon2.with_threads(1);
+    assert_eq!(
+        single.hash(b"password", b"somesaltsomesalt", 32).unwrap(),
+        tag
+    );
+}
+
+#[test]
+fn test_argon2_invalid() {
+    assert_eq!(
+        Argon2::new(Variant::Argon2id, 64, 1, 0),
+        Err(Argon2Error::InvalidParallelism)
+    );
+    assert_eq!(
+        Argon2::new(Variant::Argon2id, 64, 0, 1),
+        Err(Argon2Error::InvalidTimeCost)
+    );
+    assert_eq!(
+        Argon2::new(Variant::Argon2id, 31, 1, 4),
+        Err(Argon2Error::InvalidMemoryCost)
+    );
+
+    let argon2 = Argon2::new(Variant::Argon2id, 64, 1, 1).unwrap();
+
+    assert_eq!(
+        argon2.hash(b"password", b"short", 32),
+        Err(Argon2Error::InvalidSaltLength)
+    );
+    assert_eq!(
+        argon2.hash(b"password", b"somesaltsomesalt", 3),
+        Err(Argon2Error::InvalidOutputLength)
+    );
+}
+
+#[test]
+fn test_argon2_progress() {
+    let argon2 = Argon2::new(Variant::Argon2id, 64, 2, 2).unwrap();
+    let mut reports = Vec::new();
+
+    let tag = argon2
+        .hash_with_progress(b"password", b"somesaltsomesalt", 32, |done, total| {
+            reports.push((done, total))
+        })
+        .unwrap();
+
+    assert_eq!(
+        tag,
+        argon2.hash(b"password", b"somesaltsomesalt", 32).unwrap()
+    );
+    assert_eq!(reports, (1..=8).map(|done| (done, 8)).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_argon2_presets() {
+    // crypto_pwhash_argon2id_{OPS,MEM}LIMIT_{INTERACTIVE,MODERATE,SENSITIVE}
+    for (preset, memory, passes) in [
+        (Argon2::INTERACTIVE, 67108864, 2),
+        (Argon2::MODERATE, 268435456, 3),
+        (Argon2::SENSITIVE, 1073741824, 4),
+    ] {
+        assert_eq!(
+            preset,
+            Argon2::new(Variant::Argon2id, memory / 1024, passes, 1).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_argon2_calibrate() {
+    let params = Argon2::calibrate(Duration::from_millis(20), 256, 2).unwrap();
+
+    assert_eq!(params.variant(), Variant::Argon2id);
+    assert_eq!(params.memory_cost(), 256);
+    assert_eq!(params.parallelism(), 2);
+    assert!(params.time_cost() >= 1);
+
+    let quick = Argon2::calibrate(Duration::ZERO, 256, 2).unwrap();
+    assert_eq!(quick.time_cost(), 1);
+
+    assert_eq!(
+        Argon2::calibrate(Duration::from_millis(20), 8, 2),
+        Err(Argon2Error::InvalidMemoryCost)
+    );
+}
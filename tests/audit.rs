@@ -0,0 +1,102 @@
+use raycrypt::aeads::{AesGcm, ChaCha20Poly1305, XChaCha20Poly1305};
+use raycrypt::audit::{self, AuditEvent, KeyId, Operation, SinkAlreadySet};
+use raycrypt::ecc::{ed25519, x25519};
+use std::sync::{Mutex, Once};
+
+static EVENTS: Mutex<Vec<AuditEvent>> = Mutex::new(Vec::new());
+static INSTALL: Once = Once::new();
+
+fn install() {
+    INSTALL.call_once(|| {
+        audit::set_sink(|event: &AuditEvent| EVENTS.lock().unwrap().push(*event)).unwrap();
+    });
+}
+
+// the events for one key, as (operation, success)
+fn events(key_id: KeyId) -> Vec<(Operation, bool)> {
+    EVENTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|event| event.key_id == Some(key_id))
+        .map(|event| (event.operation, event.success))
+        .collect()
+}
+
+#[test]
+fn test_sink_set_once() {
+    install();
+
+    assert!(audit::is_enabled());
+    assert_eq!(audit::set_sink(|_: &AuditEvent| {}), Err(SinkAlreadySet));
+}
+
+#[test]
+fn test_aead_events() {
+    install();
+
+    let expected = [
+        (Operation::Encrypt, true),
+        (Operation::Decrypt, true),
+        (Operation::Decrypt, false),
+    ];
+
+    let key = [1u8; 32];
+    let cipher = ChaCha20Poly1305::new(&key);
+    let ct = cipher.encrypt(b"msg", &[0u8; 12], b"");
+    cipher.decrypt(&ct, &[0u8; 12], b"").unwrap();
+    cipher.decrypt(&ct, &[1u8; 12], b"").unwrap_err();
+    assert_eq!(events(KeyId::new(&key)), expected);
+
+    // the internal ChaCha20-Poly1305 subkey is not reported separately
+    let key = [2u8; 32];
+    let cipher = XChaCha20Poly1305::new(&key);
+    let ct = cipher.encrypt(b"msg", &[0u8; 24], b"");
+    cipher.decrypt(&ct, &[0u8; 24], b"").unwrap();
+    cipher.decrypt(&ct, &[0u8; 24], b"ad").unwrap_err();
+    assert_eq!(events(KeyId::new(&key)), expected);
+
+    let key = [3u8; 16];
+    let cipher = AesGcm::new(&key).unwrap();
+    let ct = cipher.encrypt(b"msg", &[0u8; 12], b"");
+    cipher.decrypt(&ct, &[0u8; 12], b"").unwrap();
+    cipher.decrypt(&ct[1..], &[0u8; 12], b"").unwrap_err();
+    assert_eq!(events(KeyId::new(&key)), expected);
+}
+
+#[test]
+fn test_asymmetric_events() {
+    install();
+
+    let signing = ed25519::SigningKey::from_seed(b"audit");
+    let verifying = signing.verifying_key();
+    let signature = signing.sign(b"msg");
+    verifying.verify(b"msg", &signature).unwrap();
+    verifying.verify(b"other", &signature).unwrap_err();
+
+    assert_eq!(
+        events(KeyId::new(verifying.as_bytes())),
+        [
+            (Operation::Sign, true),
+            (Operation::Verify, true),
+            (Operation::Verify, false)
+        ]
+    );
+
+    let private = x25519::PrivateKey::from_seed(b"audit");
+    private.exchange(x25519::PrivateKey::from_seed(b"peer").public_key());
+
+    assert_eq!(
+        events(KeyId::new(&private.public_key())),
+        [(Operation::KeyExchange, true)]
+    );
+}
+
+#[test]
+fn test_key_id() {
+    let id = KeyId::new(&[0u8; 32]);
+
+    assert_eq!(id.to_hex().len(), 16);
+    assert_eq!(id, KeyId::new(&[0u8; 32]));
+    assert_ne!(id, KeyId::new(&[1u8; 32]));
+}
@@ -0,0 +1,39 @@
+use hex::decode;
+use raycrypt::errors::InvalidMac;
+use raycrypt::macs::auth::{crypto_auth, crypto_auth_verify};
+
+// libsodium's test/default/auth.c
+#[test]
+fn test_crypto_auth() {
+    let mut key = [0u8; 32];
+    key[..4].copy_from_slice(b"Jefe");
+
+    let msg = b"what do ya want for nothing?";
+    let expected =
+        decode("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554").unwrap();
+
+    assert_eq!(crypto_auth(msg, &key).to_vec(), expected);
+    assert_eq!(crypto_auth_verify(&expected, msg, &key), Ok(()));
+}
+
+#[test]
+fn test_crypto_auth_verify() {
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let msg: Vec<u8> = (0..200).map(|i| i as u8).collect();
+
+    let tag = crypto_auth(&msg, &key);
+    assert_eq!(
+        tag.to_vec(),
+        decode("c0051d021d3d1b61041a6d01c75c6118ebb92bf6bfae9c2dc7026a51a16078b6").unwrap()
+    );
+
+    for index in 0..tag.len() {
+        let mut tampered = tag;
+        tampered[index] ^= 1;
+
+        assert_eq!(crypto_auth_verify(&tampered, &msg, &key), Err(InvalidMac));
+    }
+
+    assert_eq!(crypto_auth_verify(&tag[..31], &msg, &key), Err(InvalidMac));
+    assert_eq!(crypto_auth_verify(&tag, &msg[1..], &key), Err(InvalidMac));
+}
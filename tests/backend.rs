@@ -0,0 +1,89 @@
+use raycrypt::aeads::{aegis256, AesGcm};
+use raycrypt::backend::{Backend, BACKEND_ENV};
+use raycrypt::ciphers::chacha::{ChaCha20, HChaCha20};
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::{backend_info, features};
+
+#[test]
+fn test_backend_info() {
+    let info = backend_info();
+
+    assert!(info.supported.contains(&Backend::Scalar));
+    assert!(info.supported.contains(&info.chacha20));
+    assert_eq!(ChaCha20::new(&[0u8; 32]).backend(), info.chacha20);
+    assert_eq!(AesGcm::new(&[0u8; 32]).unwrap().backend(), info.aes);
+    assert_eq!(Sha256::new().is_hardware(), info.sha256 != "scalar");
+    assert_eq!(aegis256::backend(), info.aegis256);
+    assert_eq!(info.aes == "bitsliced", info.aegis256 == "bitsliced");
+
+    if let Ok(name) = std::env::var(BACKEND_ENV) {
+        assert_eq!(info.requested, Backend::from_name(&name));
+    }
+}
+
+#[test]
+fn test_backend_names() {
+    for backend in Backend::ALL {
+        assert_eq!(Backend::from_name(backend.name()), Some(backend));
+        assert_eq!(backend.to_string(), backend.name());
+    }
+
+    assert_eq!(Backend::from_name(" AVX2 "), Some(Backend::Avx2));
+    assert_eq!(Backend::from_name("sse"), Some(Backend::Sse2));
+    assert_eq!(Backend::from_name("avx1024"), None);
+}
+
+#[test]
+fn test_backends_agree() {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce = [9u8; 12];
+    let msg: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+
+    let scalar = ChaCha20::with_backend(&key, Backend::Scalar).unwrap();
+    let hscalar = HChaCha20::with_backend(&key, Backend::Scalar).unwrap();
+
+    for backend in Backend::ALL {
+        let Some(chacha) = ChaCha20::with_backend(&key, backend) else {
+            assert!(!backend.is_supported());
+            continue;
+        };
+        let hchacha = HChaCha20::with_backend(&key, backend).unwrap();
+
+        assert_eq!(chacha.encrypt(&msg, &nonce), scalar.encrypt(&msg, &nonce));
+        assert_eq!(
+            chacha.encrypt_with_counter(&msg, &nonce, u32::MAX - 2),
+            scalar.encrypt_with_counter(&msg, &nonce, u32::MAX - 2)
+        );
+        assert_eq!(chacha.keystream(&nonce, 7), scalar.keystream(&nonce, 7));
+        assert_eq!(
+            chacha.keystream_blocks(&nonce, u32::MAX),
+            scalar.keystream_blocks(&nonce, u32::MAX)
+        );
+        assert_eq!(hchacha.keystream(&[3u8; 16]), hscalar.keystream(&[3u8; 16]));
+    }
+}
+
+#[test]
+fn test_features() {
+    let features = features();
+    let info = backend_info();
+
+    assert_eq!(features.backend, info);
+    assert_eq!(features.version, env!("CARGO_PKG_VERSION"));
+    assert!(features.has("chacha20-poly1305"));
+    assert!(features.has("x25519"));
+    assert!(!features.has("rc4"));
+    assert_eq!(features.has("json"), cfg!(feature = "json"));
+    assert_eq!(features.has("jwe"), cfg!(feature = "json"));
+    assert_eq!(features.hardware_aes(), info.aes != "bitsliced");
+    assert_eq!(features.hardware_sha256(), info.sha256 != "scalar");
+
+    // hardware AES is only used where the CPU has it
+    if features.hardware_aes() {
+        assert!(features.has("aes"));
+    }
+
+    let report = features.to_string();
+    assert!(report.starts_with(&format!("raycrypt {}", features.version)));
+    assert!(report.contains(&format!("aes: {}", info.aes)));
+}
@@ -0,0 +1,172 @@
+use raycrypt::aeads::backup::{Backup, BackupError};
+use raycrypt::errors::Error;
+use raycrypt::hashes::blake3::Blake3;
+use std::io::Cursor;
+
+const KEY: [u8; 32] = [7u8; 32];
+
+fn data(label: &[u8], length: usize) -> Vec<u8> {
+    let mut hasher = Blake3::new();
+    hasher.update(label);
+
+    hasher.digest_xof(length)
+}
+
+fn restore(backup: &mut Backup<Cursor<Vec<u8>>>, name: &str) -> Vec<u8> {
+    let mut output = Vec::new();
+    let size = backup.restore(name, &mut output).unwrap();
+    assert_eq!(size, output.len() as u64);
+
+    output
+}
+
+#[test]
+fn test_sessions() {
+    let monday = data(b"monday", 1 << 20);
+    let mut tuesday = monday.clone();
+    tuesday.splice(500_000..500_000, b"an edit in the middle".iter().copied());
+
+    let mut backup = Backup::create(&KEY, Cursor::new(Vec::new())).unwrap();
+    assert_eq!(
+        backup.add_snapshot("monday", &monday[..]),
+        Ok(monday.len() as u64)
+    );
+
+    // nothing is listed before the commit
+    assert!(backup.snapshots().is_empty());
+    backup.commit().unwrap();
+    assert_eq!(backup.commits(), 1);
+    assert_eq!(backup.snapshots()[0].name(), "monday");
+    assert_eq!(backup.snapshots()[0].size(), monday.len() as u64);
+
+    let chunks = backup.chunk_count();
+    let file = backup.into_inner().into_inner();
+    let first_size = file.len();
+
+    // a second session only stores the chunks around the edit
+    let mut backup = Backup::open(&KEY, Cursor::new(file)).unwrap();
+    backup.add_snapshot("tuesday", &tuesday[..]).unwrap();
+    backup.commit().unwrap();
+
+    assert!(backup.chunk_count() - chunks <= 3);
+    let file = backup.into_inner().into_inner();
+    assert!(file.len() - first_size < 3 * 256 * 1024);
+
+    let mut backup = Backup::open(&KEY, Cursor::new(file)).unwrap();
+    assert_eq!(backup.commits(), 2);
+    assert_eq!(backup.pending_chunk_count(), 0);
+    assert_eq!(restore(&mut backup, "monday"), monday);
+    assert_eq!(restore(&mut backup, "tuesday"), tuesday);
+
+    assert_eq!(
+        backup.add_snapshot("monday", &b"again"[..]),
+        Err(BackupError::DuplicateName)
+    );
+    assert_eq!(
+        backup.restore("wednesday", Vec::new()),
+        Err(BackupError::NotFound)
+    );
+
+    // an empty snapshot has no chunks at all
+    backup.add_snapshot("empty", &[][..]).unwrap();
+    backup.commit().unwrap();
+    assert_eq!(restore(&mut backup, "empty"), b"");
+}
+
+#[test]
+fn test_resume() {
+    let data = data(b"resume", 1 << 20);
+
+    // a session that dies part way through writing a chunk
+    let mut session = Backup::create(&KEY, Cursor::new(Vec::new())).unwrap();
+    session
+        .add_snapshot("small", &b"committed before the crash"[..])
+        .unwrap();
+    session.commit().unwrap();
+    session.add_snapshot("big", &data[..]).unwrap();
+    let written = session.pending_chunk_count();
+    let mut file = session.into_inner().into_inner();
+    file.truncate(file.len() - 1000);
+
+    let mut resumed = Backup::open(&KEY, Cursor::new(file)).unwrap();
+    assert_eq!(resumed.commits(), 1);
+    assert_eq!(resumed.snapshots().len(), 1);
+    assert_eq!(resumed.pending_chunk_count(), written - 1);
+
+    // so starting the snapshot over writes just the torn chunk
+    let before = resumed.chunk_count();
+    resumed.add_snapshot("big", &data[..]).unwrap();
+    assert_eq!(resumed.chunk_count(), before + 1);
+    resumed.commit().unwrap();
+
+    let file = resumed.into_inner().into_inner();
+    let mut reopened = Backup::open(&KEY, Cursor::new(file)).unwrap();
+    assert_eq!(reopened.commits(), 2);
+    assert_eq!(reopened.pending_chunk_count(), 0);
+    assert_eq!(restore(&mut reopened, "big"), data);
+    assert_eq!(
+        restore(&mut reopened, "small"),
+        b"committed before the crash"
+    );
+}
+
+#[test]
+fn test_rejections() {
+    let data = data(b"rejections", 200_000);
+
+    let mut backup = Backup::create(&KEY, Cursor::new(Vec::new())).unwrap();
+    backup.add_snapshot("data", &data[..]).unwrap();
+    backup.commit().unwrap();
+    let file = backup.into_inner().into_inner();
+
+    assert_eq!(
+        Backup::open(&[8u8; 32], Cursor::new(file.clone())).unwrap_err(),
+        BackupError::WrongKey
+    );
+
+    assert_eq!(
+        Backup::open(&KEY, Cursor::new(Vec::new())).unwrap_err(),
+        BackupError::Malformed
+    );
+
+    let mut wrong_magic = file.clone();
+    wrong_magic[0] ^= 1;
+    assert_eq!(
+        Backup::open(&KEY, Cursor::new(wrong_magic)).unwrap_err(),
+        BackupError::Malformed
+    );
+
+    // a damaged chunk is only noticed when it is read
+    let mut damaged = file.clone();
+    damaged[69 + 29 + 100] ^= 1;
+    let mut backup = Backup::open(&KEY, Cursor::new(damaged)).unwrap();
+    assert_eq!(
+        backup.restore("data", Vec::new()),
+        Err(BackupError::InvalidMac)
+    );
+
+    // a damaged index with a later one after it is tampering, not an interruption
+    let mut backup = Backup::open(&KEY, Cursor::new(file.clone())).unwrap();
+    backup.add_snapshot("more", &b"more"[..]).unwrap();
+    backup.commit().unwrap();
+    let two_commits = backup.into_inner().into_inner();
+
+    let mut tampered = two_commits.clone();
+    let first_index = file.len() - 10;
+    tampered[first_index] ^= 1;
+    assert_eq!(
+        Backup::open(&KEY, Cursor::new(tampered)).unwrap_err(),
+        BackupError::InvalidMac
+    );
+
+    // while a damaged last index reads as the commit that never finished
+    let mut interrupted = two_commits.clone();
+    let last = interrupted.len() - 1;
+    interrupted[last] ^= 1;
+    let backup = Backup::open(&KEY, Cursor::new(interrupted)).unwrap();
+    assert_eq!(backup.commits(), 1);
+    assert_eq!(backup.pending_chunk_count(), 1);
+
+    assert_eq!(Error::from(BackupError::WrongKey), Error::InvalidKey);
+    assert_eq!(Error::from(BackupError::InvalidMac), Error::InvalidTag);
+}
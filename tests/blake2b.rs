@@ -0,0 +1,48 @@
+use hex::decode;
+use raycrypt::hashes::blake2b::{hash, Blake2b};
+
+#[test]
+fn test_blake2b_abc() {
+    let expected = decode("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923").unwrap();
+
+    assert_eq!(hash(b"abc", 64), expected);
+}
+
+#[test]
+fn test_blake2b_empty() {
+    let expected = decode("786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce").unwrap();
+
+    assert_eq!(Blake2b::default().digest(), expected);
+}
+
+#[test]
+fn test_blake2b_incremental() {
+    let expected =
+        decode("b8007121274217790e2923e0ad7027986e5a99d5531ef6ae7d294140fc81615d").unwrap();
+
+    let msg: Vec<u8> = (0..768).map(|i| i as u8).collect();
+    let mut hasher = Blake2b::new(32);
+
+    for chunk in msg.chunks(13) {
+        hasher.update(chunk);
+    }
+
+    assert_eq!(hasher.digest(), expected);
+    assert_eq!(hash(&msg, 32), expected);
+}
+
+#[test]
+fn test_blake2b_keyed() {
+    let key: Vec<u8> = (0..64).collect();
+    let msg: Vec<u8> = (0..128).collect();
+
+    let expected = decode("72065ee4dd91c2d8509fa1fc28a37c7fc9fa7d5b3f8ad3d0d7a25626b57b1b44788d4caf806290425f9890a3a2a35a905ab4b37acfd0da6e4517b2525c9651e4").unwrap();
+
+    let mut hasher = Blake2b::new_keyed(&key, 64);
+    hasher.update(&msg);
+    assert_eq!(hasher.digest(), expected);
+
+    let expected = decode("10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568").unwrap();
+
+    assert_eq!(Blake2b::new_keyed(&key, 64).digest(), expected);
+}
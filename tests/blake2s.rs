@@ -0,0 +1,51 @@
+use hex::decode;
+use raycrypt::hashes::blake2s::{hash, Blake2s};
+
+#[test]
+fn test_blake2s_abc() {
+    let expected =
+        decode("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982").unwrap();
+
+    assert_eq!(hash(b"abc", 32), expected);
+    assert_eq!(
+        {
+            let mut hasher = Blake2s::default();
+            hasher.update(b"abc");
+            hasher.digest()
+        },
+        expected
+    );
+}
+
+#[test]
+fn test_blake2s_incremental() {
+    let expected = decode("02791852856c6ef2e11dfa3027185216").unwrap();
+
+    let msg: Vec<u8> = (0..768).map(|i| i as u8).collect();
+    let mut hasher = Blake2s::new(16);
+
+    for chunk in msg.chunks(13) {
+        hasher.update(chunk);
+    }
+
+    assert_eq!(hasher.digest(), expected);
+    assert_eq!(hash(&msg, 16), expected);
+}
+
+#[test]
+fn test_blake2s_keyed() {
+    let key: Vec<u8> = (0..32).collect();
+    let msg: Vec<u8> = (0..64).collect();
+
+    let expected =
+        decode("8975b0577fd35566d750b362b0897a26c399136df07bababbde6203ff2954ed4").unwrap();
+
+    let mut hasher = Blake2s::new_keyed(&key, 32);
+    hasher.update(&msg);
+    assert_eq!(hasher.digest(), expected);
+
+    let expected =
+        decode("48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49").unwrap();
+
+    assert_eq!(Blake2s::new_keyed(&key, 32).digest(), expected);
+}
@@ -0,0 +1,156 @@
+use hex::decode;
+use raycrypt::hashes::blake3::{
+    derive_key, hash, hash_file, hash_file_with_progress, keyed_hash, Blake3,
+};
+
+const KEY: &[u8; 32] = b"whats the Elvish word for friend";
+const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+// (input length, hash, keyed hash, derived key) over the input i % 251
+const VECTORS: &[(usize, &str, &str, &str)] = &[
+    (
+        0,
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+        "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26",
+        "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d",
+    ),
+    (
+        1,
+        "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+        "6d7878dfff2f485635d39013278ae14f1454b8c0a3a2d34bc1ab38228a80c95b",
+        "b3e2e340a117a499c6cf2398a19ee0d29cca2bb7404c73063382693bf66cb06c",
+    ),
+    (
+        1023,
+        "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11",
+        "c951ecdf03288d0fcc96ee3413563d8a6d3589547f2c2fb36d9786470f1b9d6e",
+        "74a16c1c3d44368a86e1ca6df64be6a2f64cce8f09220787450722d85725dea5",
+    ),
+    (
+        1024,
+        "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+        "75c46f6f3d9eb4f55ecaaee480db732e6c2105546f1e675003687c31719c7ba4",
+        "7356cd7720d5b66b6d0697eb3177d9f8d73a4a5c5e968896eb6a689684302706",
+    ),
+    (
+        1025,
+        "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+        "357dc55de0c7e382c900fd6e320acc04146be01db6a8ce7210b7189bd664ea69",
+        "effaa245f065fbf82ac186839a249707c3bddf6d3fdda22d1b95a3c970379bcb",
+    ),
+    (
+        2048,
+        "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+        "879cf1fa2ea0e79126cb1063617a05b6ad9d0b696d0d757cf053439f60a99dd1",
+        "7b2945cb4fef70885cc5d78a87bf6f6207dd901ff239201351ffac04e1088a23",
+    ),
+    (
+        3073,
+        "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+        "68dede9bef00ba89e43f31a6825f4cf433389fedae75c04ee9f0cf16a427c95a",
+        "72613c9ec9ff7e40f8f5c173784c532ad852e827dba2bf85b2ab4b76f7079081",
+    ),
+    (
+        8193,
+        "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b",
+        "954a2a75420c8d6547e3ba5b98d963e6fa6491addc8c023189cc519821b4a1f5",
+        "af1e0346e389b17c23200270a64aa4e1ead98c61695d917de7d5b00491c9b0f1",
+    ),
+    (
+        102400,
+        "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085",
+        "1c35d1a5811083fd7119f5d5d1ba027b4d01c0c6c49fb6ff2cf75393ea5db4a7",
+        "4652cff7a3f385a6103b5c260fc1593e13c778dbe608efb092fe7ee69df6e9c6",
+    ),
+];
+
+fn input(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_blake3_vectors() {
+    for &(length, expected, keyed, derived) in VECTORS {
+        let data = input(length);
+
+        assert_eq!(hash(&data).to_vec(), decode(expected).unwrap(), "{length}");
+        assert_eq!(
+            keyed_hash(KEY, &data).to_vec(),
+            decode(keyed).unwrap(),
+            "{length}"
+        );
+        assert_eq!(
+            derive_key(CONTEXT, &data).to_vec(),
+            decode(derived).unwrap(),
+            "{length}"
+        );
+    }
+}
+
+#[test]
+fn test_blake3_incremental() {
+    for &(length, expected, _, _) in VECTORS {
+        let data = input(length);
+        let mut hasher = Blake3::new();
+
+        for chunk in data.chunks(67) {
+            hasher.update(chunk);
+        }
+
+        assert_eq!(hasher.digest().to_vec(), decode(expected).unwrap());
+    }
+}
+
+#[test]
+fn test_blake3_threads() {
+    for &(length, expected, keyed, _) in VECTORS {
+        let data = input(length);
+
+        for threads in [0, 1, 2, 4] {
+            let mut hasher = Blake3::new();
+            hasher.update_with_threads(&data, threads);
+            assert_eq!(hasher.digest().to_vec(), decode(expected).unwrap());
+
+            // start off a chunk boundary
+            let mut hasher = Blake3::new_keyed(KEY);
+            hasher.update(&data[..length.min(100)]);
+            hasher.update_with_threads(&data[length.min(100)..], threads);
+            assert_eq!(hasher.digest().to_vec(), decode(keyed).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_blake3_xof() {
+    let expected = decode(
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262e00f03e7b69af26b7faaf09f\
+         cd333050338ddfe085b8cc869ca98b206c08243a26f5487789e8f660afe6c99ef9e0c52b92e7393024a80459\
+         cf91f476f9ffdbda7001c22e159b402631f277ca96f2defdf1078282314e763699a31c5363165421cce14d",
+    )
+    .unwrap();
+
+    assert_eq!(Blake3::new().digest_xof(131), expected);
+}
+
+#[test]
+fn test_blake3_file() {
+    let data = input(5 << 20);
+    let path = std::env::temp_dir().join(format!("raycrypt-blake3-{}", std::process::id()));
+    std::fs::write(&path, &data).unwrap();
+
+    let expected = hash(&data);
+
+    for threads in [1, 4] {
+        assert_eq!(hash_file(&path, threads).unwrap(), expected);
+    }
+
+    let mut reports = Vec::new();
+    let digest = hash_file_with_progress(&path, 2, |hashed, total| reports.push((hashed, total)));
+
+    assert_eq!(digest.unwrap(), expected);
+    assert_eq!(reports, [(4 << 20, 5 << 20), (5 << 20, 5 << 20)]);
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(hash_file(&path, 1).is_err());
+}
@@ -0,0 +1,105 @@
+use raycrypt::ecc::blind::{self, BlindError, BlindSigner};
+use raycrypt::ecc::ed25519::{SigningKey, VerificationMode};
+use raycrypt::Error;
+
+fn issue(signer: &mut BlindSigner, msg: &[u8]) -> [u8; 64] {
+    let public = signer.verifying_key();
+
+    let commitment = signer.commit();
+    let (blinding, challenge) = blind::blind(&public, &commitment, msg).unwrap();
+    let response = signer.sign(&challenge).unwrap();
+
+    blinding.unblind(&response).unwrap()
+}
+
+#[test]
+fn test_issue_and_redeem() {
+    let mut signer = BlindSigner::new(SigningKey::from_seed(b"token issuer"));
+    let public = signer.verifying_key();
+
+    let token = b"token 5f2c8e";
+    let signature = issue(&mut signer, token);
+
+    // an ordinary Ed25519 signature, under every verification mode
+    assert_eq!(blind::verify(&public, token, &signature), Ok(()));
+    for mode in [
+        VerificationMode::Rfc8032,
+        VerificationMode::Strict,
+        VerificationMode::Zip215,
+    ] {
+        assert_eq!(public.verify_with_mode(token, &signature, mode), Ok(()));
+    }
+
+    assert!(blind::verify(&public, b"token 000000", &signature).is_err());
+    let other = SigningKey::from_seed(b"another issuer").verifying_key();
+    assert!(blind::verify(&other, token, &signature).is_err());
+}
+
+#[test]
+fn test_unlinkable() {
+    let mut signer = BlindSigner::new(SigningKey::from_seed(b"token issuer"));
+    let public = signer.verifying_key();
+
+    // what the signer sees of an issuance shares nothing with the signature it produces
+    let commitment = signer.commit();
+    let (blinding, challenge) = blind::blind(&public, &commitment, b"token").unwrap();
+    let response = signer.sign(&challenge).unwrap();
+    let signature = blinding.unblind(&response).unwrap();
+
+    assert_ne!(signature[..32], commitment);
+    assert_ne!(signature[32..], response);
+
+    // and blinding the same message twice gives different signatures
+    assert_ne!(issue(&mut signer, b"token"), issue(&mut signer, b"token"));
+}
+
+#[test]
+fn test_sessions() {
+    let mut signer = BlindSigner::new(SigningKey::from_seed(b"token issuer"));
+    let public = signer.verifying_key();
+
+    assert_eq!(signer.sign(&[0u8; 32]), Err(BlindError::NoSession));
+
+    // a new commitment abandons the open session
+    let abandoned = signer.commit();
+    let commitment = signer.commit();
+    let (blinding, challenge) = blind::blind(&public, &commitment, b"token").unwrap();
+    let (stale, stale_challenge) = blind::blind(&public, &abandoned, b"token").unwrap();
+
+    let response = signer.sign(&challenge).unwrap();
+    assert!(blinding.unblind(&response).is_ok());
+
+    // one response per session
+    assert_eq!(signer.sign(&stale_challenge), Err(BlindError::NoSession));
+
+    signer.commit();
+    let response = signer.sign(&stale_challenge).unwrap();
+    assert_eq!(stale.unblind(&response), Err(BlindError::InvalidResponse));
+
+    signer.commit();
+    assert_eq!(signer.sign(&[0xff; 32]), Err(BlindError::Malformed));
+    assert_eq!(Error::from(BlindError::NoSession), Error::InvalidNonce);
+}
+
+#[test]
+fn test_invalid_commitments() {
+    let public = SigningKey::from_seed(b"token issuer").verifying_key();
+
+    // the identity, a point of order 8, and a point with y = 2, which is off the curve
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let torsion: [u8; 32] =
+        hex::decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a")
+            .unwrap()
+            .try_into()
+            .unwrap();
+    let mut off_curve = [0u8; 32];
+    off_curve[0] = 2;
+
+    for commitment in [identity, torsion, off_curve] {
+        assert_eq!(
+            blind::blind(&public, &commitment, b"token").unwrap_err(),
+            BlindError::InvalidCommitment
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use raycrypt::cancel::CancelToken;
+use raycrypt::errors::Cancelled;
+use raycrypt::hashes::blake3::{hash_file_cancellable, Blake3};
+use raycrypt::kdfs::argon2::{Argon2, Argon2Error, Variant};
+use std::io::Read;
+
+#[test]
+fn test_cancel_token() {
+    let token = CancelToken::new();
+    let shared = token.clone();
+
+    assert!(!token.is_cancelled());
+    assert_eq!(token.check(), Ok(()));
+
+    std::thread::spawn(move || shared.cancel()).join().unwrap();
+
+    assert!(token.is_cancelled());
+    assert_eq!(token.check(), Err(Cancelled));
+}
+
+#[test]
+fn test_cancel_argon2() {
+    let argon2 = Argon2::new(Variant::Argon2id, 64, 2, 2).unwrap();
+    let token = CancelToken::new();
+
+    assert_eq!(
+        argon2.hash_cancellable(b"password", b"somesaltsomesalt", 32, &token),
+        argon2.hash(b"password", b"somesaltsomesalt", 32)
+    );
+
+    token.cancel();
+
+    assert_eq!(
+        argon2.hash_cancellable(b"password", b"somesaltsomesalt", 32, &token),
+        Err(Argon2Error::Cancelled)
+    );
+}
+
+// cancels its token after handing out the first read
+struct CancellingReader {
+    token: CancelToken,
+    remaining: usize,
+}
+
+impl Read for CancellingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = buf.len().min(self.remaining);
+        buf[..read].fill(7);
+
+        self.remaining -= read;
+        self.token.cancel();
+
+        Ok(read)
+    }
+}
+
+#[test]
+fn test_cancel_blake3() {
+    let token = CancelToken::new();
+    let reader = CancellingReader {
+        token: token.clone(),
+        remaining: 16 << 20,
+    };
+
+    let error = Blake3::new()
+        .update_reader_cancellable(reader, 1, &token)
+        .unwrap_err();
+
+    assert_eq!(error.get_ref().unwrap().downcast_ref(), Some(&Cancelled));
+
+    let path = std::env::temp_dir().join(format!("raycrypt-cancel-{}", std::process::id()));
+    std::fs::write(&path, b"abc").unwrap();
+
+    let result = hash_file_cancellable(&path, 1, &token);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,226 @@
+use hex::decode as hex;
+use raycrypt::cbor::{decode, encode, sign_cbor, verify_cbor, CborError, Value};
+use raycrypt::ecc::ed25519::SigningKey;
+
+fn text(s: &str) -> Value {
+    Value::from(s)
+}
+
+fn array(values: &[i64]) -> Value {
+    Value::Array(values.iter().map(|&n| Value::from(n)).collect())
+}
+
+// RFC 8949, appendix A, leaving out the examples that are not deterministically encoded
+#[test]
+fn test_vectors() {
+    let vectors = [
+        (Value::from(0u64), "00"),
+        (Value::from(1u64), "01"),
+        (Value::from(10u64), "0a"),
+        (Value::from(23u64), "17"),
+        (Value::from(24u64), "1818"),
+        (Value::from(25u64), "1819"),
+        (Value::from(100u64), "1864"),
+        (Value::from(1000u64), "1903e8"),
+        (Value::from(1000000u64), "1a000f4240"),
+        (Value::from(1000000000000u64), "1b000000e8d4a51000"),
+        (Value::from(u64::MAX), "1bffffffffffffffff"),
+        (Value::Negative(u64::MAX), "3bffffffffffffffff"),
+        (Value::from(-1i64), "20"),
+        (Value::from(-10i64), "29"),
+        (Value::from(-100i64), "3863"),
+        (Value::from(-1000i64), "3903e7"),
+        (Value::from(0.0), "f90000"),
+        (Value::from(-0.0), "f98000"),
+        (Value::from(1.0), "f93c00"),
+        (Value::from(1.1), "fb3ff199999999999a"),
+        (Value::from(1.5), "f93e00"),
+        (Value::from(65504.0), "f97bff"),
+        (Value::from(100000.0), "fa47c35000"),
+        (Value::from(3.4028234663852886e+38), "fa7f7fffff"),
+        (Value::from(1.0e+300), "fb7e37e43c8800759c"),
+        (Value::from(5.960464477539063e-8), "f90001"),
+        (Value::from(0.00006103515625), "f90400"),
+        (Value::from(-4.0), "f9c400"),
+        (Value::from(-4.1), "fbc010666666666666"),
+        (Value::from(f64::INFINITY), "f97c00"),
+        (Value::from(f64::NEG_INFINITY), "f9fc00"),
+        (Value::from(false), "f4"),
+        (Value::from(true), "f5"),
+        (Value::Null, "f6"),
+        (Value::Undefined, "f7"),
+        (
+            Value::Tag(0, Box::new(text("2013-03-21T20:04:00Z"))),
+            "c074323031332d30332d32315432303a30343a30305a",
+        ),
+        (
+            Value::Tag(1, Box::new(Value::from(1363896240u64))),
+            "c11a514b67b0",
+        ),
+        (Value::from(&b""[..]), "40"),
+        (Value::from(&[1u8, 2, 3, 4][..]), "4401020304"),
+        (text(""), "60"),
+        (text("a"), "6161"),
+        (text("IETF"), "6449455446"),
+        (text("\"\\"), "62225c"),
+        (text("\u{fc}"), "62c3bc"),
+        (text("\u{6c34}"), "63e6b0b4"),
+        (text("\u{10151}"), "64f0908591"),
+        (array(&[]), "80"),
+        (array(&[1, 2, 3]), "83010203"),
+        (
+            Value::Array(vec![Value::from(1i64), array(&[2, 3]), array(&[4, 5])]),
+            "8301820203820405",
+        ),
+        (
+            array(&(1..=25).collect::<Vec<i64>>()),
+            "98190102030405060708090a0b0c0d0e0f101112131415161718181819",
+        ),
+        (Value::Map(vec![]), "a0"),
+        (
+            Value::Map(vec![
+                (Value::from(1i64), Value::from(2i64)),
+                (Value::from(3i64), Value::from(4i64)),
+            ]),
+            "a201020304",
+        ),
+        (
+            Value::Map(vec![
+                (text("a"), Value::from(1i64)),
+                (text("b"), array(&[2, 3])),
+            ]),
+            "a26161016162820203",
+        ),
+        (
+            Value::Array(vec![text("a"), Value::Map(vec![(text("b"), text("c"))])]),
+            "826161a161626163",
+        ),
+    ];
+
+    for (value, expected) in vectors {
+        let encoded = encode(&value).unwrap();
+        assert_eq!(encoded, hex(expected).unwrap(), "{}", expected);
+        assert_eq!(decode(&encoded).unwrap(), value);
+    }
+
+    assert_eq!(
+        encode(&Value::from(f64::NAN)).unwrap(),
+        hex("f97e00").unwrap()
+    );
+    assert!(matches!(decode(&hex("f97e00").unwrap()), Ok(Value::Float(f)) if f.is_nan()));
+}
+
+// RFC 8949, section 4.2.1: map keys are sorted by their encoded bytes
+#[test]
+fn test_map_order() {
+    let keys = [
+        Value::from(-1i64),
+        text("aa"),
+        Value::Array(vec![Value::from(-1i64)]),
+        Value::from(false),
+        text("z"),
+        Value::Array(vec![Value::from(100u64)]),
+        Value::from(100u64),
+        Value::from(10u64),
+    ];
+    let map = Value::Map(keys.iter().map(|key| (key.clone(), Value::Null)).collect());
+
+    let encoded = encode(&map).unwrap();
+    assert_eq!(
+        encoded,
+        hex("a80af61864f620f6617af6626161f6811864f68120f6f4f6").unwrap()
+    );
+
+    let Value::Map(entries) = decode(&encoded).unwrap() else {
+        panic!("not a map");
+    };
+    assert_eq!(entries[0].0, Value::from(10u64));
+    assert_eq!(entries[7].0, Value::from(false));
+
+    assert_eq!(
+        encode(&Value::Map(vec![
+            (text("a"), Value::Null),
+            (text("a"), Value::Null)
+        ])),
+        Err(CborError::DuplicateKey)
+    );
+}
+
+#[test]
+fn test_strict_decoding() {
+    let vectors = [
+        // arguments and lengths longer than they need to be
+        ("1817", CborError::NotDeterministic),
+        ("190017", CborError::NotDeterministic),
+        ("1a0000ffff", CborError::NotDeterministic),
+        ("1b00000000ffffffff", CborError::NotDeterministic),
+        ("780161", CborError::NotDeterministic),
+        // indefinite lengths
+        ("5f42010243030405ff", CborError::NotDeterministic),
+        ("9fff", CborError::NotDeterministic),
+        // floats that fit a shorter form, and a NaN with a payload
+        ("fa3f800000", CborError::NotDeterministic),
+        ("fb3ff0000000000000", CborError::NotDeterministic),
+        ("fb7ff8000000000000", CborError::NotDeterministic),
+        ("f97e01", CborError::NotDeterministic),
+        // unsorted and duplicate map keys
+        ("a2616201616102", CborError::NotDeterministic),
+        ("a2616101616102", CborError::DuplicateKey),
+        // truncated data, reserved values, trailing bytes and invalid UTF-8
+        ("", CborError::Malformed),
+        ("19e8", CborError::Malformed),
+        ("62c3", CborError::Malformed),
+        ("1c", CborError::Malformed),
+        ("0000", CborError::Malformed),
+        ("62c328", CborError::Malformed),
+        ("9bffffffffffffffff", CborError::Malformed),
+        // simple values other than the booleans, null and undefined
+        ("f0", CborError::Unsupported),
+        ("f820", CborError::Unsupported),
+    ];
+
+    for (data, expected) in vectors {
+        assert_eq!(decode(&hex(data).unwrap()), Err(expected), "{}", data);
+    }
+
+    let nested = [vec![0x81u8; 100], vec![0x00]].concat();
+    assert_eq!(decode(&nested), Err(CborError::Unsupported));
+}
+
+#[test]
+fn test_sign_cbor() {
+    let key = SigningKey::new(&[5u8; 32]).unwrap();
+    let claims = Value::Map(vec![
+        (Value::from(1i64), text("device-17")),
+        (Value::from(6i64), Value::from(1700000000u64)),
+        (Value::from(-65537i64), Value::from(&[0xaau8; 16][..])),
+    ]);
+
+    let signature = sign_cbor(&key, &claims).unwrap();
+    assert_eq!(
+        verify_cbor(&key.verifying_key(), &claims, &signature),
+        Ok(())
+    );
+
+    // the same map with its entries in another order
+    let Value::Map(mut entries) = claims.clone() else {
+        unreachable!()
+    };
+    entries.reverse();
+    let reordered = Value::Map(entries);
+    assert_eq!(
+        verify_cbor(&key.verifying_key(), &reordered, &signature),
+        Ok(())
+    );
+    assert_eq!(
+        key.verifying_key()
+            .verify(&encode(&reordered).unwrap(), &signature),
+        Ok(())
+    );
+
+    let changed = Value::Map(vec![(Value::from(1i64), text("device-18"))]);
+    assert_eq!(
+        verify_cbor(&key.verifying_key(), &changed, &signature),
+        Err(CborError::InvalidSignature)
+    );
+}
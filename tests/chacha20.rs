@@ -1,6 +1,9 @@
-use raycrypt::aeads::ChaCha20Poly1305;
 use raycrypt::aeads::XChaCha20Poly1305;
-use raycrypt::ciphers::chacha::ChaCha20;
+use raycrypt::aeads::{ChaCha20Poly1305, ChaCha20Poly1305Ietf, ChaCha20Poly1305Original};
+use raycrypt::ciphers::chacha::{self, ChaCha20, KEYSTREAM_BLOCKS};
+use raycrypt::ciphers::xchacha;
+use raycrypt::hashes::sha256;
+use raycrypt::macs::poly1305;
 use serde_json::{from_str, Value};
 use std::fs;
 
@@ -81,9 +84,12 @@ fn test_chacha20_poly1305_wycheproof() {
         let output = cipher.encrypt(&pt, &nonce, &aad);
 
         if test["result"].as_str().unwrap() == "valid" {
-            assert_eq!(output, expected);
+            assert_eq!(output.ciphertext(), ciphertext);
+            assert_eq!(*output, expected);
+            assert_eq!(cipher.decrypt(&expected, &nonce, &aad), Ok(pt));
         } else {
-            assert_ne!(output, expected);
+            assert_ne!(*output, expected);
+            assert!(cipher.decrypt(&expected, &nonce, &aad).is_err());
         }
     }
 }
@@ -110,9 +116,156 @@ fn test_xchacha20_poly1305_wycheproof() {
         let output = cipher.encrypt(&pt, &nonce, &aad);
 
         if test["result"].as_str().unwrap() == "valid" {
-            assert_eq!(output, expected);
+            assert_eq!(*output, expected);
         } else {
-            assert_ne!(output, expected);
+            assert_ne!(*output, expected);
         }
     }
 }
+
+#[test]
+fn test_core_functions() {
+    use raycrypt::ciphers::chacha::{chacha20_block, hchacha20, ChaChaError};
+
+    let key: Vec<u8> = (0..32).collect();
+
+    // draft-irtf-cfrg-xchacha, section 2.2.1
+    let nonce = hex::decode("000000090000004a0000000031415927").unwrap();
+    let expected =
+        hex::decode("82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc").unwrap();
+
+    assert_eq!(hchacha20(&key, &nonce).unwrap().to_vec(), expected);
+
+    // RFC 8439, section 2.3.2
+    let nonce = hex::decode("000000090000004a00000000").unwrap();
+    let block = chacha20_block(&key, &nonce, 1).unwrap();
+
+    assert_eq!(
+        hex::encode(&block[..16]),
+        "10f1e7e4d13b5915500fdd1fa32071c4"
+    );
+
+    assert_eq!(
+        hchacha20(&key[..31], &[0u8; 16]),
+        Err(ChaChaError::InvalidKeyLength)
+    );
+    assert_eq!(
+        hchacha20(&key, &[0u8; 12]),
+        Err(ChaChaError::InvalidNonceLength)
+    );
+    assert_eq!(
+        chacha20_block(&key, &[0u8; 16], 0),
+        Err(ChaChaError::InvalidNonceLength)
+    );
+}
+
+#[test]
+fn test_keystream_blocks() {
+    let chacha = ChaCha20::new(&[0x42u8; 32]);
+    let nonce = [7u8; 12];
+
+    for counter in [0, 1, 1000, u32::MAX - 1] {
+        let blocks = chacha.keystream_blocks(&nonce, counter);
+
+        for (index, block) in blocks.chunks_exact(64).enumerate() {
+            assert_eq!(
+                block,
+                chacha.keystream(&nonce, counter.wrapping_add(index as u32))
+            );
+        }
+    }
+
+    let msg = [0u8; 64 * KEYSTREAM_BLOCKS * 3 + 17];
+    let ct = chacha.encrypt(&msg, &nonce);
+    assert_eq!(ct, chacha.encrypt_with_counter(&msg, &nonce, 1));
+    assert_eq!(ct[64..], chacha.encrypt_with_counter(&msg[64..], &nonce, 2));
+
+    for (index, block) in ct.chunks(64).enumerate() {
+        assert_eq!(
+            block,
+            &chacha.keystream(&nonce, index as u32 + 1)[..block.len()]
+        );
+    }
+}
+
+#[test]
+fn test_sizes() {
+    let msg = [5u8; 100];
+
+    let cipher = ChaCha20Poly1305::new(&[1u8; ChaCha20Poly1305::KEY_SIZE]);
+    let ct = cipher.encrypt(&msg, &[2u8; ChaCha20Poly1305::NONCE_SIZE], b"");
+    assert_eq!(ct.len(), msg.len() + ChaCha20Poly1305::TAG_SIZE);
+    assert_eq!(ct.tag().len(), ChaCha20Poly1305::TAG_SIZE);
+
+    let cipher = XChaCha20Poly1305::new(&[1u8; XChaCha20Poly1305::KEY_SIZE]);
+    let ct = cipher.encrypt(&msg, &[2u8; XChaCha20Poly1305::NONCE_SIZE], b"");
+    assert_eq!(ct.len(), msg.len() + XChaCha20Poly1305::TAG_SIZE);
+
+    assert_eq!(chacha::KEY_SIZE, ChaCha20Poly1305::KEY_SIZE);
+    assert_eq!(chacha::NONCE_SIZE, ChaCha20Poly1305::NONCE_SIZE);
+    assert_eq!(xchacha::NONCE_SIZE, XChaCha20Poly1305::NONCE_SIZE);
+    assert_eq!(poly1305::TAG_SIZE, ChaCha20Poly1305::TAG_SIZE);
+}
+
+#[test]
+fn test_xchacha20_poly1305_subkey() {
+    let cipher = XChaCha20Poly1305::new(&[3u8; 32]);
+    let mut nonce: Vec<u8> = (0..24).collect();
+
+    let (subkey, short_nonce) = cipher.derive_subkey(&nonce);
+    assert_eq!(short_nonce[..4], [0u8; 4]);
+    assert_eq!(short_nonce[4..], nonce[16..]);
+
+    let cached = ChaCha20Poly1305::new(subkey.as_bytes());
+    assert_eq!(
+        cached.encrypt(b"message", &short_nonce, b"ad"),
+        cipher.encrypt(b"message", &nonce, b"ad")
+    );
+
+    nonce[20] ^= 1;
+    let (next, _) = cipher.derive_subkey(&nonce);
+    assert_eq!(next.as_bytes(), subkey.as_bytes());
+
+    nonce[0] ^= 1;
+    let (other, _) = cipher.derive_subkey(&nonce);
+    assert_ne!(other.as_bytes(), subkey.as_bytes());
+}
+
+#[test]
+fn test_original_chacha20_poly1305() {
+    // libsodium's test vector for crypto_aead_chacha20poly1305
+    let cipher = ChaCha20Poly1305Original::new(
+        &hex::decode("4290bcb154173531f314af57f3be3b5006da371ece272afa1b5dbdd1100a1007").unwrap(),
+    );
+    let nonce = hex::decode("cd7cf67be39c794a").unwrap();
+    let ad = hex::decode("87e229d4500845a079c0").unwrap();
+    let msg = hex::decode("86d09974840bded2a5ca").unwrap();
+
+    let ct = cipher.encrypt(&msg, &nonce, &ad);
+    assert_eq!(
+        hex::encode(&ct),
+        "e3e446f7ede9a19b62a4677dabf4e3d24b876bb284753896e1d6"
+    );
+    assert_eq!(cipher.decrypt(&ct, &nonce, &ad).unwrap(), msg);
+    assert!(cipher.decrypt(&ct, &nonce, b"").is_err());
+    assert!(cipher.decrypt(&ct[..15], &nonce, &ad).is_err());
+
+    // unaligned associated data and a multi-block message, made with Python's cryptography
+    let msg = [(0..=255).collect::<Vec<u8>>().repeat(2), b"tail".to_vec()].concat();
+    let ct = cipher.encrypt(&msg, &nonce, b"seventeen bytes!!");
+    assert_eq!(ct.len(), 532);
+    assert_eq!(
+        hex::encode(sha256::hash(&ct)),
+        "aa103dfa80af0508983a850b385d5567d5e144d64bc77b67070994a264978b07"
+    );
+    assert_eq!(
+        cipher.decrypt(&ct, &nonce, b"seventeen bytes!!").unwrap(),
+        msg
+    );
+
+    // the IETF construction with the same key is a different cipher
+    let ietf = ChaCha20Poly1305Ietf::new(&[1u8; 32]);
+    let original = ChaCha20Poly1305Original::new(&[1u8; 32]);
+    let ct = ietf.encrypt(b"message", &[0u8; 12], b"");
+    assert!(original.decrypt(&ct, &[0u8; 8], b"").is_err());
+}
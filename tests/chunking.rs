@@ -0,0 +1,213 @@
+use raycrypt::chunking::{ChunkError, Chunker, Manifest};
+use raycrypt::errors::Error;
+use raycrypt::hashes::blake3::Blake3;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+fn data(length: usize) -> Vec<u8> {
+    let mut hasher = Blake3::new();
+    hasher.update(b"chunking test data");
+
+    hasher.digest_xof(length)
+}
+
+fn small() -> Chunker {
+    Chunker::new(1024, 4096, 16384).unwrap()
+}
+
+// hands out at most 1000 bytes per read, to exercise refilling
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let length = buf.len().min(self.0.len()).min(1000);
+        buf[..length].copy_from_slice(&self.0[..length]);
+        self.0 = &self.0[length..];
+
+        Ok(length)
+    }
+}
+
+#[test]
+fn test_parameters() {
+    for (min, avg, max) in [
+        (32, 4096, 16384),
+        (1024, 4000, 16384),
+        (4096, 4096, 16384),
+        (1024, 4096, 4096),
+        (1024, 1 << 30, 1 << 32),
+    ] {
+        assert_eq!(
+            Chunker::new(min, avg, max).unwrap_err(),
+            ChunkError::InvalidParameters
+        );
+    }
+
+    let chunker = Chunker::default();
+    assert_eq!(chunker.min_size(), 16 * 1024);
+    assert_eq!(chunker.avg_size(), 64 * 1024);
+    assert_eq!(chunker.max_size(), 256 * 1024);
+}
+
+#[test]
+fn test_chunks() {
+    let data = data(1 << 20);
+    let chunker = small();
+    let chunks: Vec<&[u8]> = chunker.chunks(&data).collect();
+
+    assert_eq!(chunks.concat(), data);
+
+    for chunk in &chunks[..chunks.len() - 1] {
+        assert!(
+            chunk.len() >= 1024 && chunk.len() <= 16384,
+            "{}",
+            chunk.len()
+        );
+    }
+
+    // normalized chunking keeps the sizes near the average
+    let average = data.len() / chunks.len();
+    assert!((2048..8192).contains(&average), "{}", average);
+
+    assert_eq!(
+        chunker.chunks(&data[..100]).collect::<Vec<_>>(),
+        [&data[..100]]
+    );
+    assert_eq!(chunker.chunks(&[]).count(), 0);
+}
+
+#[test]
+fn test_boundaries_follow_content() {
+    let data = data(1 << 19);
+    let chunker = small();
+
+    let mut shifted = b"a few inserted bytes".to_vec();
+    shifted.extend_from_slice(&data);
+
+    let before: HashSet<[u8; 32]> = chunker
+        .chunks(&data)
+        .map(|chunk| chunker.address(chunk))
+        .collect();
+    let after: Vec<[u8; 32]> = chunker
+        .chunks(&shifted)
+        .map(|chunk| chunker.address(chunk))
+        .collect();
+
+    let shared = after
+        .iter()
+        .filter(|address| before.contains(*address))
+        .count();
+    assert!(shared + 2 >= after.len(), "{} of {}", shared, after.len());
+}
+
+#[test]
+fn test_keyed() {
+    let data = data(1 << 18);
+    let plain = small();
+    let keyed = Chunker::keyed(&[1u8; 32], 1024, 4096, 16384).unwrap();
+    let other = Chunker::keyed(&[2u8; 32], 1024, 4096, 16384).unwrap();
+
+    let sizes =
+        |chunker: &Chunker| -> Vec<usize> { chunker.chunks(&data).map(<[u8]>::len).collect() };
+    assert_ne!(sizes(&plain), sizes(&keyed));
+    assert_ne!(sizes(&keyed), sizes(&other));
+
+    assert_eq!(
+        plain.address(b"chunk"),
+        raycrypt::hashes::blake3::hash(b"chunk")
+    );
+    assert_ne!(keyed.address(b"chunk"), plain.address(b"chunk"));
+    assert_ne!(keyed.address(b"chunk"), other.address(b"chunk"));
+}
+
+#[test]
+fn test_store_and_restore() {
+    let data = data(300_000);
+    let chunker = small();
+    let mut store = HashMap::new();
+
+    let manifest = chunker
+        .chunk_reader(Trickle(&data), |address, chunk| {
+            store.insert(*address, chunk.to_vec());
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(manifest, chunker.manifest(&data));
+    assert_eq!(manifest.total_size(), data.len() as u64);
+
+    let mut restored = Vec::new();
+    let written = chunker
+        .restore(&manifest, &mut restored, |address| {
+            store.get(address).cloned()
+        })
+        .unwrap();
+    assert_eq!(written, data.len() as u64);
+    assert_eq!(restored, data);
+
+    let first = *manifest.chunks()[0].address();
+    let mut corrupted = store.clone();
+    corrupted.get_mut(&first).unwrap()[0] ^= 1;
+    assert_eq!(
+        chunker.restore(&manifest, std::io::sink(), |address| corrupted
+            .get(address)
+            .cloned()),
+        Err(ChunkError::CorruptChunk)
+    );
+
+    store.remove(&first);
+    assert_eq!(
+        chunker.restore(&manifest, std::io::sink(), |address| store
+            .get(address)
+            .cloned()),
+        Err(ChunkError::MissingChunk)
+    );
+
+    let empty = chunker.chunk_reader(&[][..], |_, _| Ok(())).unwrap();
+    assert_eq!(empty, Manifest::default());
+
+    let failing = chunker.chunk_reader(&data[..], |_, _| {
+        Err(std::io::Error::from(std::io::ErrorKind::StorageFull))
+    });
+    assert_eq!(
+        failing,
+        Err(ChunkError::Io(std::io::ErrorKind::StorageFull))
+    );
+}
+
+#[test]
+fn test_manifest_encoding() {
+    let chunker = small();
+    let manifest = chunker.manifest(&data(100_000));
+    let bytes = manifest.to_bytes();
+
+    assert_eq!(bytes.len(), 13 + 36 * manifest.chunks().len());
+    assert_eq!(Manifest::from_bytes(&bytes), Ok(manifest.clone()));
+    assert_eq!(
+        Manifest::from_bytes(&Manifest::default().to_bytes()),
+        Ok(Manifest::default())
+    );
+
+    assert_eq!(
+        Manifest::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ChunkError::Malformed)
+    );
+
+    // a count larger than the entries that follow
+    let mut inflated = bytes.clone();
+    inflated[5..13].copy_from_slice(&u64::MAX.to_be_bytes());
+    assert_eq!(Manifest::from_bytes(&inflated), Err(ChunkError::Malformed));
+
+    let mut empty_chunk = bytes.clone();
+    empty_chunk[13 + 32..13 + 36].fill(0);
+    assert_eq!(
+        Manifest::from_bytes(&empty_chunk),
+        Err(ChunkError::Malformed)
+    );
+
+    assert_eq!(Error::from(ChunkError::CorruptChunk), Error::InvalidTag);
+    assert_eq!(
+        Error::from(ChunkError::MissingChunk),
+        Error::Io(std::io::ErrorKind::NotFound)
+    );
+}
@@ -0,0 +1,44 @@
+use raycrypt::aeads::ConvergentCipher;
+
+#[test]
+fn test_convergent_roundtrip() {
+    let cipher = ConvergentCipher::new(&[7u8; 32]);
+
+    for msg in [&b""[..], b"hello", &[42u8; 1000]] {
+        let ct = cipher.encrypt_revealing_equality(msg, b"ad");
+
+        assert_eq!(ct.len(), msg.len() + 40);
+        assert_eq!(cipher.decrypt(&ct, b"ad").unwrap(), msg);
+        assert!(cipher.decrypt(&ct, b"other").is_err());
+    }
+}
+
+#[test]
+fn test_convergent_deterministic() {
+    let cipher = ConvergentCipher::new(&[7u8; 32]);
+    let ct = cipher.encrypt_revealing_equality(b"block", b"");
+
+    assert_eq!(ct, cipher.encrypt_revealing_equality(b"block", b""));
+    assert_ne!(ct, cipher.encrypt_revealing_equality(b"block", b"ad"));
+    assert_ne!(ct, cipher.encrypt_revealing_equality(b"blocc", b""));
+
+    let other = ConvergentCipher::new(&[8u8; 32]);
+
+    assert_ne!(ct, other.encrypt_revealing_equality(b"block", b""));
+    assert!(other.decrypt(&ct, b"").is_err());
+}
+
+#[test]
+fn test_convergent_tampering() {
+    let cipher = ConvergentCipher::new(&[7u8; 32]);
+    let ct = cipher.encrypt_revealing_equality(b"some data", b"");
+
+    for index in 0..ct.len() {
+        let mut tampered = ct.clone();
+        tampered[index] ^= 1;
+
+        assert!(cipher.decrypt(&tampered, b"").is_err());
+    }
+
+    assert!(cipher.decrypt(&ct[..39], b"").is_err());
+}
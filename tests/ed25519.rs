@@ -0,0 +1,232 @@
+use hex::decode;
+use raycrypt::ecc::ed25519::{
+    InvalidContext, SigningKey, VerificationMode, VerifyingKey, MAX_CONTEXT_LENGTH,
+};
+use raycrypt::errors::InvalidSignature;
+use raycrypt::hashes::sha512::Sha512;
+use serde_json::{from_str, Value};
+use std::fs;
+
+#[test]
+fn test_rfc8032_1() {
+    let seed = decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap();
+    let public =
+        decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+    let expected = decode("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b").unwrap();
+
+    let key = SigningKey::new(&seed).unwrap();
+    let signature = key.sign(b"");
+
+    assert_eq!(key.verifying_key().to_bytes().to_vec(), public);
+    assert_eq!(signature.to_vec(), expected);
+    assert!(key.verifying_key().verify(b"", &signature).is_ok());
+}
+
+#[test]
+fn test_rfc8032_3() {
+    let seed = decode("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7").unwrap();
+    let msg = decode("af82").unwrap();
+    let expected = decode("6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a").unwrap();
+
+    let key = SigningKey::new(&seed).unwrap();
+    let signature = key.sign(&msg);
+
+    assert_eq!(signature.to_vec(), expected);
+    assert!(key.verifying_key().verify(&msg, &signature).is_ok());
+    assert!(key.verifying_key().verify(b"af83", &signature).is_err());
+}
+
+#[test]
+fn test_rfc8032_ed25519ph() {
+    let seed = decode("833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42").unwrap();
+    let public =
+        decode("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf").unwrap();
+    let expected = decode("98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406").unwrap();
+
+    let key = SigningKey::new(&seed).unwrap();
+
+    let mut prehash = Sha512::new();
+    std::io::copy(&mut &b"abc"[..], &mut prehash).unwrap();
+
+    let signature = key.sign_prehashed(prehash.clone(), b"").unwrap();
+
+    assert_eq!(key.verifying_key().to_bytes().to_vec(), public);
+    assert_eq!(signature.to_vec(), expected);
+    assert!(key
+        .verifying_key()
+        .verify_prehashed(prehash.clone(), b"", &signature)
+        .is_ok());
+    assert!(key
+        .verifying_key()
+        .verify_prehashed(prehash, b"context", &signature)
+        .is_err());
+    assert!(key.verifying_key().verify(b"abc", &signature).is_err());
+}
+
+#[test]
+fn test_ed25519ph_context_length() {
+    let key = SigningKey::from_seed(b"context");
+    let prehash = Sha512::new();
+
+    let longest = [7u8; MAX_CONTEXT_LENGTH];
+    let signature = key.sign_prehashed(prehash.clone(), &longest).unwrap();
+    assert!(key
+        .verifying_key()
+        .verify_prehashed(prehash.clone(), &longest, &signature)
+        .is_ok());
+
+    let too_long = [7u8; MAX_CONTEXT_LENGTH + 1];
+    assert_eq!(
+        key.sign_prehashed(prehash.clone(), &too_long),
+        Err(InvalidContext)
+    );
+    assert_eq!(
+        key.verifying_key()
+            .verify_prehashed(prehash, &too_long, &signature),
+        Err(InvalidSignature)
+    );
+}
+
+#[test]
+fn test_ed25519_wycheproof() {
+    let raw = fs::read_to_string("tests/vectors/ed25519.json").unwrap();
+    let data: Value = from_str(&raw).unwrap();
+
+    for group in data["testGroups"].as_array().unwrap() {
+        let public = decode(group["publicKey"]["pk"].as_str().unwrap()).unwrap();
+        let key = VerifyingKey::new(&public).unwrap();
+
+        for test in group["tests"].as_array().unwrap() {
+            let msg = decode(test["msg"].as_str().unwrap()).unwrap();
+            let sig = decode(test["sig"].as_str().unwrap()).unwrap();
+
+            let valid = test["result"].as_str().unwrap() == "valid";
+
+            assert_eq!(key.verify(&msg, &sig).is_ok(), valid);
+            assert_eq!(key.verify_strict(&msg, &sig).is_ok(), valid);
+        }
+    }
+}
+
+#[test]
+fn test_verifying_key_collections() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let keys: Vec<VerifyingKey> = (0..4u8)
+        .map(|i| SigningKey::new(&[i; 32]).unwrap().verifying_key())
+        .collect();
+
+    assert_eq!(keys[0], SigningKey::new(&[0; 32]).unwrap().verifying_key());
+    assert_ne!(keys[0], keys[1]);
+
+    let mut set: HashSet<VerifyingKey> = keys.iter().copied().collect();
+    assert_eq!(set.len(), 4);
+    assert!(!set.insert(keys[2]));
+
+    let ordered: Vec<[u8; 32]> = keys
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .iter()
+        .map(|key| key.to_bytes())
+        .collect();
+    let mut expected: Vec<[u8; 32]> = keys.iter().map(|key| key.to_bytes()).collect();
+    expected.sort();
+
+    assert_eq!(ordered, expected);
+}
+
+const MODES: [VerificationMode; 3] = [
+    VerificationMode::Rfc8032,
+    VerificationMode::Strict,
+    VerificationMode::Zip215,
+];
+
+// whether each of MODES accepts the signature
+fn accepted_by(key: &VerifyingKey, msg: &[u8], signature: &[u8]) -> [bool; 3] {
+    MODES.map(|mode| key.verify_with_mode(msg, signature, mode).is_ok())
+}
+
+#[test]
+fn test_verification_modes() {
+    let signing = SigningKey::from_seed(b"modes");
+    let key = signing.verifying_key();
+    let signature = signing.sign(b"message");
+
+    assert_eq!(accepted_by(&key, b"message", &signature), [true; 3]);
+    assert_eq!(accepted_by(&key, b"other", &signature), [false; 3]);
+
+    // S + l is rejected everywhere
+    let mut malleated = signature;
+    let mut carry = 0u16;
+    let order = decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010").unwrap();
+    for (byte, l) in malleated[32..].iter_mut().zip(order) {
+        let sum = *byte as u16 + l as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+    assert_eq!(accepted_by(&key, b"message", &malleated), [false; 3]);
+}
+
+#[test]
+fn test_small_order_points() {
+    // the identity as A and R with S = 0 verifies for every message without cofactors or checks
+    let identity =
+        decode("0100000000000000000000000000000000000000000000000000000000000000").unwrap();
+    let key = VerifyingKey::new(&identity).unwrap();
+    let signature = [identity.clone(), vec![0u8; 32]].concat();
+
+    assert_eq!(accepted_by(&key, b"any", &signature), [true, false, true]);
+
+    // the identity with the sign bit set on x = 0 is a non-canonical R
+    let mut negative_zero = identity.clone();
+    negative_zero[31] |= 0x80;
+    let signature = [negative_zero.clone(), vec![0u8; 32]].concat();
+    assert_eq!(accepted_by(&key, b"any", &signature), [false, false, true]);
+
+    // and a non-canonical A
+    assert!(VerifyingKey::new(&negative_zero).is_err());
+    let key = VerifyingKey::new_zip215(&negative_zero).unwrap();
+    assert_eq!(accepted_by(&key, b"any", &signature), [false, false, true]);
+}
+
+#[test]
+fn test_noncanonical_key() {
+    // y = p + 1, another encoding of the identity
+    let encoding =
+        decode("eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f").unwrap();
+    let identity =
+        decode("0100000000000000000000000000000000000000000000000000000000000000").unwrap();
+
+    assert!(VerifyingKey::new(&encoding).is_err());
+
+    let key = VerifyingKey::new_zip215(&encoding).unwrap();
+    let signature = [identity, vec![0u8; 32]].concat();
+    assert_eq!(key.to_bytes().to_vec(), encoding);
+    assert_eq!(accepted_by(&key, b"any", &signature), [false, false, true]);
+
+    // y = 2 is not on the curve, in any mode
+    let mut off_curve = [0u8; 32];
+    off_curve[0] = 2;
+    assert!(VerifyingKey::new_zip215(&off_curve).is_err());
+}
+
+#[test]
+fn test_cofactored_equation() {
+    // A = [a]B + T for a point T of order 2, and an odd challenge k, so [S]B - R - [k]A = T:
+    // only the cofactored equation holds
+    let key = VerifyingKey::new(
+        &decode("ee8fbc54b18d1ce487fb6033a068baa41322f15bd73e029385f0399166e38346").unwrap(),
+    )
+    .unwrap();
+    let signature = decode(
+        "40af667b63bbdacb333e4569cae3ee9cc2e4a5f1ec8a0fd00819bea34b7d5b9d\
+         39358472e1a7e40b08db58947cefef6b276cb28ef54d15f89666b1b78ed3e50d",
+    )
+    .unwrap();
+
+    assert_eq!(
+        accepted_by(&key, b"cofactor", &signature),
+        [false, false, true]
+    );
+}
@@ -0,0 +1,70 @@
+use raycrypt::ecc::elligator2::{generate, map, representative, unmap};
+use raycrypt::ecc::x25519::PrivateKey;
+
+#[test]
+fn test_map() {
+    let r: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let expected = "5f3520001c6c9936a31206afe7c7ac224e8861619bf98872444915899d95f46e";
+
+    assert_eq!(hex::encode(map(&r)), expected);
+
+    // the two most significant bits are ignored
+    let expected = "80e5132b658f7f451b2b658f7f451b2b658f7f451b2b658f7f451b2b658f7f45";
+
+    assert_eq!(hex::encode(map(&[0xff; 32])), expected);
+}
+
+#[test]
+fn test_roundtrip() {
+    let mut representable = 0;
+
+    for i in 0..64u8 {
+        let private = PrivateKey::new(&[i; 32]).unwrap();
+
+        if let Some(r) = representative(&private, i & 0xc1) {
+            representable += 1;
+
+            assert_eq!(r[31] & 0xc0, i & 0xc0);
+            assert_eq!(map(&r), private.public_key());
+            assert_eq!(unmap(&map(&r), i & 0xc1), Some(r));
+        }
+    }
+
+    // roughly half of all public keys have a representative
+    assert!(representable > 16 && representable < 48);
+}
+
+#[test]
+fn test_both_representatives() {
+    let (_, r) = generate();
+    let public = map(&r);
+
+    let first = unmap(&public, 0).unwrap();
+    let second = unmap(&public, 1).unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(map(&first), public);
+    assert_eq!(map(&second), public);
+}
+
+#[test]
+fn test_exchange() {
+    let peer = PrivateKey::new(&[42u8; 32]).unwrap();
+
+    for _ in 0..8 {
+        let (private, r) = generate();
+        let public = map(&r);
+
+        assert_eq!(peer.exchange(public), private.exchange(peer.public_key()));
+    }
+}
+
+#[test]
+fn test_unrepresentable() {
+    let mut p = [0xffu8; 32];
+    p[0] = 0xed;
+    p[31] = 0x7f;
+
+    // non-canonical encodings are rejected
+    assert_eq!(unmap(&p, 0), None);
+}
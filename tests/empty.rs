@@ -0,0 +1,143 @@
+use raycrypt::aeads::{
+    aegis256, AesGcm, AesOcb, ChaCha20Poly1305, ChaCha20Poly1305Original, XChaCha20Poly1305,
+};
+use raycrypt::{decrypt, encrypt, nacl};
+
+// each AEAD as encrypt and decrypt with a fixed key and nonce
+type Encrypt = Box<dyn Fn(&[u8], &[u8]) -> Vec<u8>>;
+type Decrypt = Box<dyn Fn(&[u8], &[u8]) -> Option<Vec<u8>>>;
+
+fn aeads() -> Vec<(&'static str, Encrypt, Decrypt)> {
+    let key = [7u8; 32];
+    let nonce = [9u8; 32];
+
+    vec![
+        (
+            "chacha20-poly1305",
+            Box::new(move |msg, ad| {
+                ChaCha20Poly1305::new(&key)
+                    .encrypt(msg, &nonce[..12], ad)
+                    .to_vec()
+            }),
+            Box::new(move |ct, ad| {
+                ChaCha20Poly1305::new(&key)
+                    .decrypt(ct, &nonce[..12], ad)
+                    .ok()
+            }),
+        ),
+        (
+            "chacha20-poly1305 original",
+            Box::new(move |msg, ad| {
+                ChaCha20Poly1305Original::new(&key)
+                    .encrypt(msg, &nonce[..8], ad)
+                    .to_vec()
+            }),
+            Box::new(move |ct, ad| {
+                ChaCha20Poly1305Original::new(&key)
+                    .decrypt(ct, &nonce[..8], ad)
+                    .ok()
+            }),
+        ),
+        (
+            "xchacha20-poly1305",
+            Box::new(move |msg, ad| {
+                XChaCha20Poly1305::new(&key)
+                    .encrypt(msg, &nonce[..24], ad)
+                    .to_vec()
+            }),
+            Box::new(move |ct, ad| {
+                XChaCha20Poly1305::new(&key)
+                    .decrypt(ct, &nonce[..24], ad)
+                    .ok()
+            }),
+        ),
+        (
+            "aes-gcm",
+            Box::new(move |msg, ad| {
+                AesGcm::new(&key)
+                    .unwrap()
+                    .encrypt(msg, &nonce[..12], ad)
+                    .to_vec()
+            }),
+            Box::new(move |ct, ad| {
+                AesGcm::new(&key)
+                    .unwrap()
+                    .decrypt(ct, &nonce[..12], ad)
+                    .ok()
+            }),
+        ),
+        (
+            "aes-ocb",
+            Box::new(move |msg, ad| {
+                AesOcb::new(&key)
+                    .unwrap()
+                    .encrypt(msg, &nonce[..12], ad)
+                    .to_vec()
+            }),
+            Box::new(move |ct, ad| {
+                AesOcb::new(&key)
+                    .unwrap()
+                    .decrypt(ct, &nonce[..12], ad)
+                    .ok()
+            }),
+        ),
+        (
+            "aegis-256",
+            Box::new(move |msg, ad| aegis256::encrypt::<16>(&key, msg, &nonce, ad)),
+            Box::new(move |ct, ad| aegis256::decrypt::<16>(&key, ct, &nonce, ad).ok()),
+        ),
+    ]
+}
+
+#[test]
+fn test_empty_plaintext_and_ad() {
+    for (name, encrypt, decrypt) in aeads() {
+        for (msg, ad) in [(&b""[..], &b""[..]), (b"", b"header"), (b"message", b"")] {
+            let ct = encrypt(msg, ad);
+            assert_eq!(ct.len(), msg.len() + 16, "{}", name);
+            assert_eq!(decrypt(&ct, ad).as_deref(), Some(msg), "{}", name);
+        }
+    }
+}
+
+#[test]
+fn test_tag_only_ciphertexts() {
+    for (name, encrypt, decrypt) in aeads() {
+        let tag = encrypt(b"", b"ad");
+
+        // the tag alone still authenticates the associated data
+        assert_eq!(decrypt(&tag, b"ad"), Some(Vec::new()), "{}", name);
+        assert_eq!(decrypt(&tag, b"AD"), None, "{}", name);
+        assert_eq!(decrypt(&tag, b""), None, "{}", name);
+
+        let mut tampered = tag.clone();
+        tampered[15] ^= 1;
+        assert_eq!(decrypt(&tampered, b"ad"), None, "{}", name);
+
+        // shorter than a tag is an error rather than a panic
+        for length in 0..16 {
+            assert_eq!(decrypt(&tag[..length], b"ad"), None, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn test_high_level_empty_message() {
+    let key = [3u8; 32].to_vec();
+    let sealed = encrypt(key.clone(), b"");
+
+    assert_eq!(sealed.len(), 16 + 64);
+    assert_eq!(decrypt(key.clone(), &sealed).unwrap(), b"");
+    assert!(decrypt(key.clone(), &sealed[1..]).is_err());
+    assert!(decrypt(key, &[]).is_err());
+}
+
+#[test]
+fn test_nacl_empty_message() {
+    let secret_box = nacl::SecretBox::new(&[5u8; 32]).unwrap();
+    let boxed = secret_box.encrypt(b"", Some(&[6u8; 24]));
+
+    assert_eq!(boxed.len(), 24 + 16);
+    assert_eq!(secret_box.decrypt(&boxed, None).unwrap(), b"");
+    assert!(secret_box.decrypt(&boxed[..39], None).is_err());
+}
@@ -0,0 +1,97 @@
+use raycrypt::aeads::envelope::{self, EnvelopeError, KeyProvider, LocalKeyProvider};
+use raycrypt::aeads::SymmetricKey;
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::errors::InvalidMac;
+
+fn provider(kek: u8) -> LocalKeyProvider {
+    LocalKeyProvider::new(
+        &SymmetricKey::new(&[kek; 32]).unwrap(),
+        SigningKey::from_seed(b"envelope"),
+    )
+}
+
+// stands in for a remote KMS that is unreachable
+struct Offline;
+
+impl KeyProvider for Offline {
+    type Error = &'static str;
+
+    fn wrap_key(&self, _: &SymmetricKey, _: &[u8]) -> Result<Vec<u8>, &'static str> {
+        Err("offline")
+    }
+
+    fn unwrap_key(&self, _: &[u8], _: &[u8]) -> Result<SymmetricKey, &'static str> {
+        Err("offline")
+    }
+
+    fn sign(&self, _: &[u8]) -> Result<Vec<u8>, &'static str> {
+        Err("offline")
+    }
+}
+
+#[test]
+fn test_envelope_roundtrip() {
+    let kms = provider(1);
+
+    for msg in [&b""[..], b"hello", &[42u8; 1000]] {
+        let sealed = envelope::seal(&kms, msg, b"ad").unwrap();
+
+        // length, nonce and key wrapped with its own nonce and tag, then the message
+        assert_eq!(sealed.len(), 2 + 72 + 24 + msg.len() + 16);
+        assert_eq!(envelope::open(&kms, &sealed, b"ad").unwrap(), msg);
+    }
+
+    assert_ne!(
+        envelope::seal(&kms, b"hello", b"").unwrap(),
+        envelope::seal(&kms, b"hello", b"").unwrap()
+    );
+}
+
+#[test]
+fn test_envelope_rejections() {
+    let kms = provider(1);
+    let sealed = envelope::seal(&kms, b"hello", b"ad").unwrap();
+
+    assert_eq!(
+        envelope::open(&kms, &sealed, b"other"),
+        Err(EnvelopeError::Provider(InvalidMac))
+    );
+    assert_eq!(
+        envelope::open(&provider(2), &sealed, b"ad"),
+        Err(EnvelopeError::Provider(InvalidMac))
+    );
+
+    let mut tampered = sealed.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        envelope::open(&kms, &tampered, b"ad"),
+        Err(EnvelopeError::InvalidMac)
+    );
+
+    assert_eq!(
+        envelope::open(&kms, &sealed[..sealed.len() - 17], b"ad"),
+        Err(EnvelopeError::Malformed)
+    );
+    assert_eq!(
+        envelope::open(&kms, &[0xff], b"ad"),
+        Err(EnvelopeError::Malformed)
+    );
+
+    assert_eq!(
+        envelope::seal(&Offline, b"hello", b""),
+        Err(EnvelopeError::Provider("offline"))
+    );
+    assert_eq!(
+        envelope::open(&Offline, &sealed, b"ad"),
+        Err(EnvelopeError::Provider("offline"))
+    );
+}
+
+#[test]
+fn test_provider_sign() {
+    let kms = provider(1);
+    let signature = kms.sign(b"manifest").unwrap();
+
+    assert!(kms.verifying_key().verify(b"manifest", &signature).is_ok());
+    assert!(kms.verifying_key().verify(b"other", &signature).is_err());
+}
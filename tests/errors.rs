@@ -0,0 +1,109 @@
+use raycrypt::aeads::{self, SealError, SymmetricKey, XChaCha20Poly1305};
+use raycrypt::ciphers::chacha::ChaChaError;
+use raycrypt::ecc::{ed25519, fingerprint};
+use raycrypt::errors::{InvalidMac, InvalidSignature};
+use raycrypt::{cbor, openssl, Error};
+
+// application code mixing modules, with one error type
+fn open_config(key: &[u8], sealed: &[u8]) -> Result<cbor::Value, Error> {
+    let key = SymmetricKey::new(key)?;
+    let plaintext = aeads::open(&key, sealed, b"config")?;
+
+    Ok(cbor::decode(&plaintext)?)
+}
+
+#[test]
+fn test_question_mark_across_modules() {
+    let key = SymmetricKey::generate();
+    let config = cbor::encode(&cbor::Value::from("debug")).unwrap();
+    let sealed = aeads::seal(&key, &config, b"config");
+
+    assert_eq!(
+        open_config(key.as_bytes(), &sealed),
+        Ok(cbor::Value::from("debug"))
+    );
+    assert_eq!(open_config(&[0u8; 16], &sealed), Err(Error::InvalidKey));
+    assert_eq!(
+        open_config(SymmetricKey::generate().as_bytes(), &sealed),
+        Err(Error::InvalidKey)
+    );
+    assert_eq!(
+        open_config(key.as_bytes(), &sealed[..10]),
+        Err(Error::Format)
+    );
+
+    let mut tampered = sealed.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    assert_eq!(
+        open_config(key.as_bytes(), &tampered),
+        Err(Error::InvalidTag)
+    );
+
+    let not_cbor = aeads::seal(&key, &[0xff], b"config");
+    assert_eq!(open_config(key.as_bytes(), &not_cbor), Err(Error::Format));
+}
+
+#[test]
+fn test_key_constructors() {
+    // key constructors return the crate error directly
+    assert_eq!(
+        SymmetricKey::new(&[0u8; 16]).unwrap_err(),
+        Error::InvalidKey
+    );
+    assert_eq!(
+        ed25519::SigningKey::new(&[0u8; 31]).unwrap_err(),
+        Error::InvalidKey
+    );
+    assert_eq!(SymmetricKey::from_hex("zz").unwrap_err(), Error::Format);
+    assert_eq!(
+        SymmetricKey::from_hex(&"00".repeat(16)).unwrap_err(),
+        Error::InvalidKey
+    );
+}
+
+#[test]
+fn test_conversions() {
+    assert_eq!(Error::from(InvalidMac), Error::InvalidTag);
+    assert_eq!(Error::from(InvalidSignature), Error::InvalidSignature);
+    assert_eq!(Error::from(SealError::UnknownAlgorithm), Error::Unsupported);
+    assert_eq!(
+        Error::from(ChaChaError::InvalidNonceLength),
+        Error::InvalidNonce
+    );
+    assert_eq!(
+        Error::from(std::io::Error::from(std::io::ErrorKind::NotFound)),
+        Error::Io(std::io::ErrorKind::NotFound)
+    );
+
+    let cipher = XChaCha20Poly1305::new(&[1u8; 32]);
+    let error = cipher.decrypt(&[0u8; 16], &[0u8; 24], b"").unwrap_err();
+    assert_eq!(Error::from(error), Error::InvalidTag);
+
+    let signing = ed25519::SigningKey::from_seed(b"errors");
+    let error = signing
+        .verifying_key()
+        .verify(b"message", &[0u8; 64])
+        .unwrap_err();
+    assert_eq!(Error::from(error), Error::InvalidSignature);
+    assert_eq!(Error::from(ed25519::InvalidContext), Error::Unsupported);
+    assert_eq!(
+        Error::from(fingerprint::InvalidFingerprintLength),
+        Error::Unsupported
+    );
+
+    let error = openssl::decrypt(b"password", b"not salted", 1).unwrap_err();
+    assert_eq!(Error::from(error), Error::Format);
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(Error::InvalidTag.to_string(), InvalidMac.to_string());
+    assert_eq!(
+        Error::Io(std::io::ErrorKind::NotFound).to_string(),
+        "An I/O operation failed: entity not found."
+    );
+
+    let boxed: Box<dyn std::error::Error> = Box::new(Error::Format);
+    assert_eq!(boxed.to_string(), "The input is malformed.");
+}
@@ -0,0 +1,88 @@
+use raycrypt::aeads::etm::EtM;
+use raycrypt::ciphers::aes::ctr::AesCtr;
+use raycrypt::ciphers::xchacha::XChaCha20;
+use raycrypt::errors::{Error, InvalidMac};
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::hashes::sha512::Sha512;
+use raycrypt::macs::hmac::Hmac;
+
+const ENCRYPTION_KEY: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+
+fn mac_key() -> Vec<u8> {
+    (32..64).collect()
+}
+
+#[test]
+fn test_aes_ctr() {
+    // NIST SP 800-38A F.5.1, first two blocks
+    let ctr = AesCtr::new(&hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap()).unwrap();
+    let iv = hex::decode("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+    let msg =
+        hex::decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51").unwrap();
+
+    assert_eq!(
+        hex::encode(ctr.xor(&msg, &iv)),
+        "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff"
+    );
+
+    // the counter wraps around at 2^128
+    let ctr = AesCtr::new(&ENCRYPTION_KEY).unwrap();
+    assert_eq!(
+        hex::encode(ctr.xor(&[0u8; 48], &[0xff; 16])),
+        "3c441f32ce07822364d7a2990e50bb13c6a13b37878f5b826f4f8162a1c8d879\
+         7346139595c0b41e497bbde365f42d0a"
+    );
+}
+
+#[test]
+fn test_aes_ctr_hmac_sha256() {
+    // made with Python's cryptography package
+    let etm = EtM::<AesCtr, Hmac<Sha256>>::new(&ENCRYPTION_KEY, &mac_key()).unwrap();
+    let nonce = hex::decode("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+    let msg = b"legacy encrypt-then-MAC record, longer than one block";
+    let expected =
+        "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff0ac2a089572b112df932ac7e436280d9dae4b92dfadf7f8\
+                    dd6c810d41cf833cabe1efc311929cbe993e0e57920fc305112b409395752356fb80a9866a8f1da\
+                    df01dcd7b7f79cefc066efaa1193cc74e17456a8189f";
+
+    let sealed = etm.encrypt(msg, &nonce, b"header");
+    assert_eq!(hex::encode(&sealed), expected);
+    assert_eq!(etm.decrypt(&sealed, b"header").unwrap(), msg);
+
+    assert_eq!(etm.decrypt(&sealed, b"Header"), Err(InvalidMac));
+    assert_eq!(etm.decrypt(&sealed[..47], b"header"), Err(InvalidMac));
+
+    let mut tampered = sealed.clone();
+    tampered[20] ^= 1;
+    assert_eq!(etm.decrypt(&tampered, b"header"), Err(InvalidMac));
+}
+
+#[test]
+fn test_truncated_tags() {
+    let etm = EtM::<XChaCha20, Hmac<Sha512>>::with_tag_size(&[7u8; 32], &mac_key(), 32).unwrap();
+    let full = EtM::<XChaCha20, Hmac<Sha512>>::new(&[7u8; 32], &mac_key()).unwrap();
+    let nonce = [9u8; 24];
+
+    let sealed = etm.encrypt(b"message", &nonce, b"");
+    let untruncated = full.encrypt(b"message", &nonce, b"");
+
+    assert_eq!(etm.tag_size(), 32);
+    assert_eq!(sealed.len(), 24 + 7 + 32);
+    assert_eq!(sealed[..], untruncated[..sealed.len()]);
+    assert_eq!(etm.decrypt(&sealed, b"").unwrap(), b"message");
+    assert_eq!(full.decrypt(&sealed, b""), Err(InvalidMac));
+}
+
+#[test]
+fn test_invalid_keys() {
+    assert!(matches!(
+        EtM::<AesCtr, Hmac<Sha256>>::new(&[0u8; 20], &mac_key()),
+        Err(Error::InvalidKey)
+    ));
+    assert!(matches!(
+        EtM::<XChaCha20, Hmac<Sha256>>::new(&[0u8; 16], &mac_key()),
+        Err(Error::InvalidKey)
+    ));
+}
@@ -0,0 +1,81 @@
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::kdfs::fido2::{Authenticator, InvalidBinding, TokenBinding};
+use raycrypt::macs::hmac::hmac;
+
+// a software stand-in for a security key holding one credential
+struct SoftToken {
+    credential_id: Vec<u8>,
+    cred_random: [u8; 32],
+}
+
+impl Authenticator for SoftToken {
+    type Error = &'static str;
+
+    fn hmac_secret(
+        &mut self,
+        credential_id: &[u8],
+        salt: &[u8; 32],
+    ) -> Result<[u8; 32], &'static str> {
+        if credential_id != self.credential_id {
+            return Err("no credentials");
+        }
+
+        Ok(hmac::<Sha256>(&self.cred_random, salt).try_into().unwrap())
+    }
+}
+
+fn token(cred_random: u8) -> SoftToken {
+    SoftToken {
+        credential_id: b"credential".to_vec(),
+        cred_random: [cred_random; 32],
+    }
+}
+
+#[test]
+fn test_derive_key() {
+    let binding = TokenBinding::new(b"credential", [1u8; 32]);
+    let key = binding.derive_key(&mut token(7)).unwrap();
+
+    assert_eq!(
+        key.as_bytes(),
+        binding.derive_key(&mut token(7)).unwrap().as_bytes()
+    );
+    assert_ne!(
+        key.as_bytes(),
+        binding.derive_key(&mut token(8)).unwrap().as_bytes()
+    );
+
+    let other_salt = TokenBinding::new(b"credential", [2u8; 32]);
+    assert_ne!(
+        key.as_bytes(),
+        other_salt.derive_key(&mut token(7)).unwrap().as_bytes()
+    );
+
+    let other_credential = TokenBinding::generate(b"elsewhere");
+    assert_eq!(
+        other_credential.derive_key(&mut token(7)).unwrap_err(),
+        "no credentials"
+    );
+
+    assert_ne!(
+        TokenBinding::generate(b"credential").salt(),
+        TokenBinding::generate(b"credential").salt()
+    );
+}
+
+#[test]
+fn test_binding_encoding() {
+    let binding = TokenBinding::new(b"credential", [1u8; 32]);
+    let bytes = binding.to_bytes();
+
+    assert_eq!(bytes.len(), 2 + 10 + 32);
+    assert_eq!(bytes[..2], [0, 10]);
+    assert_eq!(TokenBinding::from_bytes(&bytes), Ok(binding));
+
+    assert_eq!(TokenBinding::from_bytes(&bytes[..43]), Err(InvalidBinding));
+    assert_eq!(
+        TokenBinding::from_bytes(&[bytes.clone(), vec![0]].concat()),
+        Err(InvalidBinding)
+    );
+    assert_eq!(TokenBinding::from_bytes(&[0]), Err(InvalidBinding));
+}
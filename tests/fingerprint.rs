@@ -0,0 +1,81 @@
+use hex::decode;
+use raycrypt::ecc::ed25519::VerifyingKey;
+use raycrypt::ecc::fingerprint::{
+    Fingerprint, FingerprintAlgorithm, InvalidFingerprintLength, PublicKeyFingerprint,
+};
+
+fn key() -> VerifyingKey {
+    let public =
+        decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+
+    VerifyingKey::new(&public).unwrap()
+}
+
+#[test]
+fn test_renderings() {
+    let fingerprint = key()
+        .fingerprint(FingerprintAlgorithm::Blake2b, 16)
+        .unwrap();
+
+    assert_eq!(fingerprint.to_hex(), "d7108b422f25cc5edb865cc4ae184f55");
+    assert_eq!(
+        fingerprint.to_colon_hex(),
+        "d7:10:8b:42:2f:25:cc:5e:db:86:5c:c4:ae:18:4f:55"
+    );
+
+    let fingerprint = key().fingerprint(FingerprintAlgorithm::Sha256, 32).unwrap();
+
+    assert_eq!(
+        fingerprint.to_base64(),
+        "If4x36FUomFia/hUBG/SJxt77UtqvkWqWId+9H+XIbk="
+    );
+
+    let fingerprint = key().fingerprint(FingerprintAlgorithm::Sha256, 20).unwrap();
+
+    assert_eq!(
+        fingerprint.to_hex(),
+        "21fe31dfa154a261626bf854046fd2271b7bed4b"
+    );
+}
+
+#[test]
+fn test_verify_fingerprint() {
+    let key = key();
+    let x25519 = [9u8; 32];
+
+    let fingerprint = key.fingerprint(FingerprintAlgorithm::Blake2b, 32).unwrap();
+
+    assert!(key.verify_fingerprint(&fingerprint));
+    assert!(!x25519.verify_fingerprint(&fingerprint));
+    assert!(
+        x25519.verify_fingerprint(&x25519.fingerprint(FingerprintAlgorithm::Sha256, 8).unwrap())
+    );
+
+    let other = Fingerprint::new(&key.to_bytes(), FingerprintAlgorithm::Sha256, 32).unwrap();
+    assert!(key.verify_fingerprint(&other));
+    assert_ne!(fingerprint, other);
+}
+
+#[test]
+fn test_lengths() {
+    let key = key();
+
+    for (algorithm, max) in [
+        (FingerprintAlgorithm::Blake2b, 64),
+        (FingerprintAlgorithm::Sha256, 32),
+    ] {
+        assert_eq!(algorithm.max_length(), max);
+        assert_eq!(key.fingerprint(algorithm, 1).unwrap().as_bytes().len(), 1);
+        assert_eq!(
+            key.fingerprint(algorithm, max).unwrap().as_bytes().len(),
+            max
+        );
+
+        for length in [0, max + 1, usize::MAX] {
+            assert_eq!(
+                Fingerprint::new(&key.to_bytes(), algorithm, length),
+                Err(InvalidFingerprintLength)
+            );
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use hex::decode;
+use raycrypt::backend::Backend;
+use raycrypt::macs::ghash::{ghash, GHash};
+use raycrypt::macs::polyval::{polyval, Polyval};
+
+const BACKENDS: [Backend; 2] = [Backend::Scalar, Backend::Avx2];
+
+fn block(hex: &str) -> [u8; 16] {
+    decode(hex).unwrap().try_into().unwrap()
+}
+
+// McGrew and Viega, "The Galois/Counter Mode of Operation", test case 2
+#[test]
+fn test_ghash() {
+    let h = block("66e94bd4ef8a2c3b884cfa59ca342b2e");
+    let data = decode("0388dace60b6a392f328c2b971b2fe7800000000000000000000000000000080").unwrap();
+    let expected = block("f38cbb1ad69223dcc3457ae5b6b0f885");
+
+    for backend in BACKENDS {
+        let mut ghash = GHash::with_backend(&h, backend);
+        ghash.update(&data[..16]);
+        ghash.update(&data[16..]);
+
+        assert_eq!(ghash.finish(), expected);
+    }
+
+    assert_eq!(ghash(&h, &data), expected);
+}
+
+// RFC 8452, appendix A
+#[test]
+fn test_polyval() {
+    let h = block("25629347589242761d31f826ba4b757b");
+    let data = decode("4f4f95668c83dfb6401762bb2d01a262d1a24ddd2721d006bbe45f20d3c9f362").unwrap();
+    let expected = block("f7a3b47b846119fae5b7866cf5e5b77e");
+
+    for backend in BACKENDS {
+        let mut polyval = Polyval::with_backend(&h, backend);
+        polyval.update(&data[..16]);
+        polyval.update(&data[16..]);
+
+        assert_eq!(polyval.finish(), expected);
+    }
+
+    assert_eq!(polyval(&h, &data), expected);
+}
+
+#[test]
+fn test_polyval_padding() {
+    let h = block("25629347589242761d31f826ba4b757b");
+
+    let mut padded = b"partial".to_vec();
+    padded.resize(16, 0);
+
+    assert_eq!(polyval(&h, b"partial"), polyval(&h, &padded));
+    assert_eq!(polyval(&h, b""), [0u8; 16]);
+}
@@ -0,0 +1,70 @@
+use hex::decode;
+use raycrypt::hazmat::{AesGcm, ChaCha20Poly1305, InvalidTag};
+
+// generated with cryptography.hazmat.primitives.ciphers.aead
+#[test]
+fn test_hazmat_chacha20poly1305() {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce: Vec<u8> = (0..12).collect();
+    let aead = ChaCha20Poly1305::new(&key).unwrap();
+
+    let ct = aead.encrypt(&nonce, b"attack at dawn", Some(b"header"));
+    assert_eq!(
+        ct,
+        decode("e88f7c614a7c8521c3a35b92ef7332c719c91289c10afc20b4d7d24051a1").unwrap()
+    );
+    assert_eq!(
+        aead.decrypt(&nonce, &ct, Some(b"header")).unwrap(),
+        b"attack at dawn"
+    );
+    assert_eq!(aead.decrypt(&nonce, &ct, None), Err(InvalidTag));
+
+    let ct = aead.encrypt(&nonce, b"attack at dawn", None);
+    assert_eq!(
+        ct,
+        decode("e88f7c614a7c8521c3a35b92ef73af52a0bc4333b0f2169aa931fc88514e").unwrap()
+    );
+    assert_eq!(
+        aead.decrypt(&nonce, &ct, Some(b"")).unwrap(),
+        b"attack at dawn"
+    );
+
+    assert!(ChaCha20Poly1305::new(&key[..16]).is_err());
+    assert_eq!(ChaCha20Poly1305::generate_key().len(), 32);
+}
+
+#[test]
+fn test_hazmat_aesgcm() {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce: Vec<u8> = (0..12).collect();
+
+    let aead = AesGcm::new(&key[..16]).unwrap();
+    let ct = aead.encrypt(&nonce, b"attack at dawn", Some(b"header"));
+    assert_eq!(
+        ct,
+        decode("f218d3af0570d7353ff205eb41cdd97b76751000cdc7fb128e7b2482ef00").unwrap()
+    );
+    assert_eq!(
+        aead.decrypt(&nonce, &ct, Some(b"header")).unwrap(),
+        b"attack at dawn"
+    );
+
+    let aead = AesGcm::new(&key).unwrap();
+    let ct = aead.encrypt(&nonce, b"attack at dawn", None);
+    assert_eq!(
+        ct,
+        decode("2676a27aa68ee27af961f3eac6874a645e8e20a54e0ea3a3e272247f1284").unwrap()
+    );
+    assert_eq!(aead.decrypt(&nonce, &ct[1..], None), Err(InvalidTag));
+
+    for bits in [128, 192, 256] {
+        assert_eq!(AesGcm::generate_key(bits).len(), bits / 8);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_hazmat_nonce_length() {
+    let aead = ChaCha20Poly1305::new(&[0u8; 32]).unwrap();
+    aead.encrypt(&[0u8; 24], b"", None);
+}
@@ -0,0 +1,72 @@
+use raycrypt::aeads::SymmetricKey;
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::kdfs::hierarchy::{HierarchyError, KeyHierarchy};
+use raycrypt::kdfs::hkdf::hkdf;
+
+#[test]
+fn test_derive() {
+    let root = SymmetricKey::new(&[0x42; 32]).unwrap();
+    let mut keys = KeyHierarchy::new(&root);
+
+    keys.register("db-encryption").unwrap();
+    keys.register("token-signing/v2").unwrap();
+
+    let db = keys.derive("db-encryption").unwrap();
+    let expected =
+        hkdf::<Sha256>(b"raycrypt key hierarchy", &[0x42; 32], b"db-encryption", 32).unwrap();
+    assert_eq!(db.as_bytes()[..], expected);
+
+    let signing = keys.derive("token-signing/v2").unwrap();
+    assert_ne!(db.as_bytes(), signing.as_bytes());
+
+    // the same root and label always give the same key
+    let mut again = KeyHierarchy::new(&root);
+    again.register("db-encryption").unwrap();
+    assert_eq!(
+        again.derive("db-encryption").unwrap().as_bytes(),
+        db.as_bytes()
+    );
+
+    let other = KeyHierarchy::new(&SymmetricKey::new(&[0x43; 32]).unwrap());
+    assert_eq!(
+        other.derive("db-encryption").unwrap_err(),
+        HierarchyError::UnregisteredLabel
+    );
+
+    assert_eq!(
+        keys.labels().collect::<Vec<_>>(),
+        ["db-encryption", "token-signing/v2"]
+    );
+}
+
+#[test]
+fn test_registration() {
+    let mut keys = KeyHierarchy::new(&SymmetricKey::generate());
+
+    assert_eq!(
+        keys.derive("db-encryption").unwrap_err(),
+        HierarchyError::UnregisteredLabel
+    );
+
+    keys.register("db-encryption").unwrap();
+    assert!(keys.is_registered("db-encryption"));
+    assert_eq!(
+        keys.register("db-encryption"),
+        Err(HierarchyError::DuplicateLabel)
+    );
+
+    for label in [
+        "",
+        "DB",
+        "db encryption",
+        "/db",
+        "db/",
+        "db\0",
+        &"a".repeat(129),
+    ] {
+        assert_eq!(keys.register(label), Err(HierarchyError::InvalidLabel));
+    }
+
+    keys.register(&"a".repeat(128)).unwrap();
+    assert!(!keys.is_registered("db"));
+}
@@ -0,0 +1,56 @@
+use hex::decode;
+use raycrypt::hashes::sha256::{self, Sha256};
+use raycrypt::hashes::sha512::Sha512;
+use raycrypt::kdfs::hkdf::{hkdf, Hkdf};
+use raycrypt::macs::hmac::{hmac, Hmac};
+
+#[test]
+fn test_sha256() {
+    let expected =
+        decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad").unwrap();
+
+    assert_eq!(sha256::hash(b"abc").to_vec(), expected);
+}
+
+#[test]
+fn test_hmac_sha256_rfc4231() {
+    let expected =
+        decode("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843").unwrap();
+
+    assert_eq!(
+        hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?"),
+        expected
+    );
+}
+
+#[test]
+fn test_hmac_sha512_long_key() {
+    let key = [0xaa; 131];
+    let expected = decode("80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598").unwrap();
+
+    let mut mac = Hmac::<Sha512>::new(&key);
+    mac.update(b"Test Using Larger Than Block-Size Key - ");
+    mac.update(b"Hash Key First");
+
+    assert!(mac.verify(&expected));
+}
+
+#[test]
+fn test_hkdf_rfc5869_1() {
+    let ikm = [0x0b; 22];
+    let salt = decode("000102030405060708090a0b0c").unwrap();
+    let info = decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+
+    let prk = decode("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5").unwrap();
+    let okm = decode(
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+    )
+    .unwrap();
+
+    let kdf = Hkdf::<Sha256>::extract(&salt, &ikm);
+
+    assert_eq!(kdf.prk(), prk);
+    assert_eq!(kdf.expand(&info, 42).unwrap(), okm);
+    assert_eq!(hkdf::<Sha256>(&salt, &ikm, &info, 42).unwrap(), okm);
+    assert!(kdf.expand(&info, 255 * 32 + 1).is_err());
+}
@@ -0,0 +1,75 @@
+use raycrypt::aeads::{AeadDecryptor, AeadEncryptor, ChaCha20Poly1305};
+
+const KEY: [u8; 32] = [0x42; 32];
+const NONCE: [u8; 12] = [7; 12];
+
+#[test]
+fn test_matches_one_shot() {
+    let msg: Vec<u8> = (0..1000).map(|i| (i * 13) as u8).collect();
+    let ad = b"header";
+
+    let expected = ChaCha20Poly1305::new(&KEY).encrypt(&msg, &NONCE, ad);
+
+    for size in [1, 7, 15, 16, 17, 63, 64, 65, 200, 1000] {
+        let mut encryptor = AeadEncryptor::new(&KEY, &NONCE, ad);
+        let mut ct = Vec::new();
+
+        for chunk in msg.chunks(size) {
+            ct.extend(encryptor.update(chunk));
+        }
+
+        assert_eq!(ct, expected.ciphertext());
+        assert_eq!(&encryptor.finalize(), expected.tag());
+
+        let mut decryptor = AeadDecryptor::new(&KEY, &NONCE, ad);
+        let mut pt = Vec::new();
+
+        for chunk in ct.chunks(size) {
+            pt.extend(decryptor.update(chunk));
+        }
+
+        assert_eq!(pt, msg);
+        assert!(decryptor.finalize(expected.tag()).is_ok());
+    }
+}
+
+#[test]
+fn test_empty_and_uneven_chunks() {
+    let msg: Vec<u8> = (0..300).map(|i| i as u8).collect();
+    let expected = ChaCha20Poly1305::new(&KEY).encrypt(&msg, &NONCE, b"");
+
+    let mut encryptor = AeadEncryptor::new(&KEY, &NONCE, b"");
+    let mut ct = encryptor.update(b"");
+
+    for range in [0..3, 3..3, 3..100, 100..129, 129..300] {
+        ct.extend(encryptor.update(&msg[range]));
+    }
+
+    assert_eq!(ct, expected.ciphertext());
+    assert_eq!(&encryptor.finalize(), expected.tag());
+
+    let empty = ChaCha20Poly1305::new(&KEY).encrypt(b"", &NONCE, b"ad");
+    assert_eq!(
+        &AeadEncryptor::new(&KEY, &NONCE, b"ad").finalize(),
+        empty.tag()
+    );
+}
+
+#[test]
+fn test_tampering() {
+    let mut encryptor = AeadEncryptor::new(&KEY, &NONCE, b"ad");
+    let mut ct = encryptor.update(b"attack at dawn");
+    let tag = encryptor.finalize();
+
+    let mut decryptor = AeadDecryptor::new(&KEY, &NONCE, b"other");
+    decryptor.update(&ct);
+    assert!(decryptor.finalize(&tag).is_err());
+
+    ct[0] ^= 1;
+    let mut decryptor = AeadDecryptor::new(&KEY, &NONCE, b"ad");
+    decryptor.update(&ct);
+    assert!(decryptor.finalize(&tag).is_err());
+
+    let decryptor = AeadDecryptor::new(&KEY, &NONCE, b"ad");
+    assert!(decryptor.finalize(&tag[..15]).is_err());
+}
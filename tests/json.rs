@@ -0,0 +1,235 @@
+#![cfg(feature = "json")]
+
+use hex::decode;
+use raycrypt::aeads::SymmetricKey;
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::errors::InvalidSignature;
+use raycrypt::json::{canonicalize, sign_json, verify_json, FieldCipher, FieldError};
+use serde_json::{from_str, json, Value};
+
+fn record() -> Value {
+    json!({
+        "id": 42,
+        "user": {"name": "Ada", "email": "ada@example.com", "tags": ["admin", 7]},
+        "card": {"number": "4111111111111111", "expiry": null},
+        "note": "public",
+    })
+}
+
+#[test]
+fn test_round_trip() {
+    let cipher = FieldCipher::new(&SymmetricKey::generate());
+    let paths = ["/user/email", "/user/tags", "/card", "/missing"];
+
+    let mut document = record();
+    cipher.encrypt_fields(&mut document, &paths);
+
+    assert_eq!(document["id"], 42);
+    assert_eq!(document["user"]["name"], "Ada");
+    assert_eq!(document["note"], "public");
+
+    let envelope = document["user"]["email"].as_str().unwrap();
+    assert!(envelope.starts_with(&format!("rcf1.{}.", cipher.key_id().to_hex())));
+    assert!(document["card"].is_string());
+    assert!(!document.to_string().contains("4111"));
+
+    cipher.decrypt_fields(&mut document, &paths).unwrap();
+    assert_eq!(document, record());
+}
+
+#[test]
+fn test_nested_paths() {
+    let cipher = FieldCipher::new(&SymmetricKey::generate());
+    let paths = ["/user/email", "/user"];
+
+    let mut document = record();
+    cipher.encrypt_fields(&mut document, &paths);
+    assert!(document["user"].is_string());
+
+    cipher.decrypt_fields(&mut document, &paths).unwrap();
+    assert_eq!(document, record());
+}
+
+#[test]
+fn test_values() {
+    let cipher = FieldCipher::new(&SymmetricKey::generate());
+
+    for value in [
+        json!(null),
+        json!(1.5),
+        json!("text"),
+        json!([1, {"a": true}]),
+    ] {
+        let envelope = cipher.encrypt_value(&value, "/field");
+        assert_eq!(cipher.decrypt_value(&envelope, "/field").unwrap(), value);
+
+        // the same value encrypts differently every time
+        assert_ne!(cipher.encrypt_value(&value, "/field"), envelope);
+    }
+}
+
+#[test]
+fn test_errors() {
+    let cipher = FieldCipher::new(&SymmetricKey::generate());
+    let mut document = record();
+    cipher.encrypt_fields(&mut document, &["/user/email", "/card/number"]);
+    let encrypted = document.clone();
+
+    // an envelope moved to another field
+    document["user"]["email"] = encrypted["card"]["number"].clone();
+    assert_eq!(
+        cipher.decrypt_fields(&mut document, &["/user/email", "/card/number"]),
+        Err(FieldError::InvalidMac)
+    );
+    assert_eq!(document["card"], encrypted["card"]);
+
+    // a plaintext value where an envelope should be
+    let mut document = encrypted.clone();
+    document["user"]["email"] = json!("mallory@example.com");
+    assert_eq!(
+        cipher.decrypt_fields(&mut document, &["/user/email"]),
+        Err(FieldError::Malformed)
+    );
+
+    let other = FieldCipher::new(&SymmetricKey::generate());
+    let mut document = encrypted.clone();
+    assert_eq!(
+        other.decrypt_fields(&mut document, &["/user/email"]),
+        Err(FieldError::UnknownKey)
+    );
+
+    let envelope = encrypted["user"]["email"].as_str().unwrap();
+    let mut tampered = envelope.to_string();
+    tampered.replace_range(tampered.len() - 6..tampered.len() - 5, "A");
+    if tampered != envelope {
+        assert_eq!(
+            cipher.decrypt_value(&tampered, "/user/email"),
+            Err(FieldError::InvalidMac)
+        );
+    }
+
+    for bad in ["", "rcf1", "rcf2.00.AAAA", "rcf1.00", &envelope[..20]] {
+        assert!(cipher.decrypt_value(bad, "/user/email").is_err());
+    }
+    assert_eq!(
+        cipher.decrypt_value(&format!("rcf1.{}.AAAA", cipher.key_id().to_hex()), "/x"),
+        Err(FieldError::Malformed)
+    );
+}
+
+// RFC 8785, section 3.2.2
+const SAMPLE: &str = r#"{
+  "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+  "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+  "literals": [null, true, false]
+}"#;
+const CANONICAL: &str = r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#;
+
+#[test]
+fn test_canonicalize() {
+    assert_eq!(canonicalize(&from_str(SAMPLE).unwrap()), CANONICAL);
+
+    // RFC 8785, section 3.2.3: names are sorted by their UTF-16 code units
+    let sorting = r#"{
+      "€": "Euro Sign",
+      "\r": "Carriage Return",
+      "דּ": "Hebrew Letter Dalet With Dagesh",
+      "1": "One",
+      "😀": "Emoji: Grinning Face",
+      "\u0080": "Control",
+      "ö": "Latin Small Letter O With Diaeresis"
+    }"#;
+    let names: Vec<String> = canonicalize(&from_str(sorting).unwrap())
+        .split(",")
+        .map(|member| {
+            member
+                .split(':')
+                .next()
+                .unwrap()
+                .trim_start_matches('{')
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        names,
+        [
+            r#""\r""#,
+            r#""1""#,
+            "\"\u{80}\"",
+            "\"\u{f6}\"",
+            "\"\u{20ac}\"",
+            "\"\u{1f600}\"",
+            "\"\u{fb33}\""
+        ]
+    );
+
+    assert_eq!(
+        canonicalize(&json!({"b": [], "a": {"d": {}, "c": "\u{7f}\u{1f}"}})),
+        "{\"a\":{\"c\":\"\u{7f}\\u001f\",\"d\":{}},\"b\":[]}"
+    );
+}
+
+// RFC 8785, appendix B
+#[test]
+fn test_numbers() {
+    let vectors = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+
+    for (bits, expected) in vectors {
+        assert_eq!(canonicalize(&json!(f64::from_bits(bits))), expected);
+    }
+
+    assert_eq!(canonicalize(&json!(-42)), "-42");
+    assert_eq!(canonicalize(&json!(u64::MAX)), "18446744073709552000");
+}
+
+#[test]
+fn test_sign_json() {
+    let key = SigningKey::new(&(0..32).collect::<Vec<u8>>()).unwrap();
+    let document: Value = from_str(SAMPLE).unwrap();
+
+    // made with pyca/cryptography over the canonical form
+    let signature = sign_json(&key, &document);
+    assert_eq!(
+        signature.to_vec(),
+        decode("43702d74ce21fc186cb864b62e8695e0a2a61aa2c0d6d8caf8f5f92a11d231c0e37c955046f62e9737e969ba0d819c412de40bea315f4353c7dff87846adc702").unwrap()
+    );
+
+    // the same document written differently
+    let reordered: Value = from_str(&format!(" {} ", CANONICAL.replace(",", " ,\n"))).unwrap();
+    assert_eq!(
+        verify_json(&key.verifying_key(), &reordered, &signature),
+        Ok(())
+    );
+
+    let mut changed = document.clone();
+    changed["literals"][1] = json!(false);
+    assert_eq!(
+        verify_json(&key.verifying_key(), &changed, &signature),
+        Err(InvalidSignature)
+    );
+}
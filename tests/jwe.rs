@@ -0,0 +1,100 @@
+#![cfg(feature = "json")]
+
+use raycrypt::ecc::x25519::PrivateKey;
+use raycrypt::json::{decrypt_jwe, encrypt_jwe, JweAlgorithm, JweEncryption, JweError};
+
+// made with Python's cryptography package, to the key below; the first has "apu", "apv" and
+// "kid" parameters
+const ECDH_ES: &str = "eyJhbGciOiJFQ0RILUVTIiwiZW5jIjoiQTI1NkdDTSIsImFwdSI6IlFXeHBZMlUiLCJhcHYiOiJRbTlpIiwiZXBrIjp7Imt0eSI6Ik9LUCIsImNydiI6IlgyNTUxOSIsIngiOiJPS3RtUzlodmQ5Zm1hOTJhNEhrcEU2bFAyTE02RW1BQ2ZrdEd3ZlNJVEdjIn0sImtpZCI6ImJvYi0xIn0..AAECAwQFBgcICQoL.m6VRMVsoCek0y_uN3zlAT1QF.2BUsH5YP9fXX75LeecA-aA";
+const ECDH_ES_A256KW: &str = "eyJhbGciOiJFQ0RILUVTK0EyNTZLVyIsImVuYyI6IkExMjhHQ00iLCJlcGsiOnsia3R5IjoiT0tQIiwiY3J2IjoiWDI1NTE5IiwieCI6Ik9LdG1TOWh2ZDlmbWE5MmE0SGtwRTZsUDJMTTZFbUFDZmt0R3dmU0lUR2MifX0.3MQi5doNNzbnWeTlR_aEinFl_ody5cd3.AAECAwQFBgcICQoL.4_nd-nOuycGkDIpc2Zgyax8oUQ.wOirby_ze07-1Zi7GUPtZw";
+
+fn recipient() -> PrivateKey {
+    PrivateKey::new(&[0x77; 32]).unwrap()
+}
+
+#[test]
+fn test_interop() {
+    assert_eq!(
+        decrypt_jwe(&recipient(), ECDH_ES).unwrap(),
+        b"hello over ECDH-ES"
+    );
+    assert_eq!(
+        decrypt_jwe(&recipient(), ECDH_ES_A256KW).unwrap(),
+        b"hello over key wrap"
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let recipient = recipient();
+
+    for alg in [JweAlgorithm::EcdhEs, JweAlgorithm::EcdhEsA256Kw] {
+        for enc in [JweEncryption::A128Gcm, JweEncryption::A256Gcm] {
+            let jwe = encrypt_jwe(&recipient.public_key(), alg, enc, b"payload");
+            let parts: Vec<&str> = jwe.split('.').collect();
+
+            assert_eq!(parts.len(), 5);
+            assert_eq!(parts[1].is_empty(), alg == JweAlgorithm::EcdhEs);
+            assert_eq!(decrypt_jwe(&recipient, &jwe).unwrap(), b"payload");
+        }
+    }
+
+    let other = PrivateKey::new(&[0x78; 32]).unwrap();
+    let jwe = encrypt_jwe(
+        &recipient.public_key(),
+        JweAlgorithm::EcdhEsA256Kw,
+        JweEncryption::A256Gcm,
+        b"payload",
+    );
+    assert_eq!(decrypt_jwe(&other, &jwe), Err(JweError::InvalidMac));
+}
+
+#[test]
+fn test_errors() {
+    let recipient = recipient();
+    let parts: Vec<&str> = ECDH_ES.split('.').collect();
+    let with_header = |header: &str| [header, "", parts[2], parts[3], parts[4]].join(".");
+
+    let tampered = [parts[0], "", parts[2], parts[3], "3BUsH5YP9fXX75LeecA-aA"].join(".");
+    assert_eq!(
+        decrypt_jwe(&recipient, &tampered),
+        Err(JweError::InvalidMac)
+    );
+
+    // the header is authenticated, so dropping "kid" breaks the tag
+    let header = "eyJhbGciOiJFQ0RILUVTIiwiZW5jIjoiQTI1NkdDTSIsImFwdSI6IlFXeHBZMlUiLCJhcHYiOiJRbTlpIiwiZXBrIjp7Imt0eSI6Ik9LUCIsImNydiI6IlgyNTUxOSIsIngiOiJPS3RtUzlodmQ5Zm1hOTJhNEhrcEU2bFAyTE02RW1BQ2ZrdEd3ZlNJVEdjIn19";
+    assert_eq!(
+        decrypt_jwe(&recipient, &with_header(header)),
+        Err(JweError::InvalidMac)
+    );
+
+    // "zip": "DEF"
+    let header = "eyJhbGciOiJFQ0RILUVTIiwiZW5jIjoiQTI1NkdDTSIsInppcCI6IkRFRiIsImVwayI6eyJrdHkiOiJPS1AiLCJjcnYiOiJYMjU1MTkiLCJ4IjoiT0t0bVM5aHZkOWZtYTkyYTRIa3BFNmxQMkxNNkVtQUNma3RHd2ZTSVRHYyJ9fQ";
+    assert_eq!(
+        decrypt_jwe(&recipient, &with_header(header)),
+        Err(JweError::Unsupported)
+    );
+
+    // "alg": "RSA-OAEP"
+    let header = "eyJhbGciOiJSU0EtT0FFUCIsImVuYyI6IkEyNTZHQ00ifQ";
+    assert_eq!(
+        decrypt_jwe(&recipient, &with_header(header)),
+        Err(JweError::Unsupported)
+    );
+
+    // an all-zero ephemeral key, which has low order
+    let header = "eyJhbGciOiJFQ0RILUVTIiwiZW5jIjoiQTI1NkdDTSIsImVwayI6eyJrdHkiOiJPS1AiLCJjcnYiOiJYMjU1MTkiLCJ4IjoiQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQSJ9fQ";
+    assert_eq!(
+        decrypt_jwe(&recipient, &with_header(header)),
+        Err(JweError::InvalidMac)
+    );
+
+    assert_eq!(
+        decrypt_jwe(&recipient, &parts[..4].join(".")),
+        Err(JweError::Malformed)
+    );
+    assert_eq!(
+        decrypt_jwe(&recipient, &with_header("e30+")),
+        Err(JweError::Malformed)
+    );
+}
@@ -0,0 +1,75 @@
+use hex::decode;
+use raycrypt::errors::InvalidMac;
+use raycrypt::macs::keyed::{
+    prefix_mac, prefix_mac_with, verify_legacy_prefix_mac, verify_prefix_mac,
+    verify_prefix_mac_with, KeyedHash,
+};
+
+const MSG: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+// checked against Python's hmac and hashlib modules
+#[test]
+fn test_vectors() {
+    assert_eq!(
+        prefix_mac(b"key", MSG).to_vec(),
+        decode("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8").unwrap()
+    );
+    assert_eq!(
+        prefix_mac_with(KeyedHash::Blake2b, b"key", MSG).to_vec(),
+        decode("27fbd5f2cdea2c98fa372a1a3b572a2f51c06bc627e306de84663f48c8b0eb13").unwrap()
+    );
+
+    // a key longer than BLAKE2b's 64 bytes is replaced with its BLAKE2b-512 hash
+    assert_eq!(
+        prefix_mac_with(KeyedHash::Blake2b, &[b'k'; 100], MSG).to_vec(),
+        decode("04ef04d2ef721045cd62f35223be7347a801247b24dcde003dd2df532c1e58b2").unwrap()
+    );
+}
+
+#[test]
+fn test_verify() {
+    for algorithm in [KeyedHash::HmacSha256, KeyedHash::Blake2b] {
+        let tag = prefix_mac_with(algorithm, b"key", MSG);
+
+        assert_eq!(verify_prefix_mac_with(algorithm, b"key", MSG, &tag), Ok(()));
+        assert_eq!(
+            verify_prefix_mac_with(algorithm, b"other key", MSG, &tag),
+            Err(InvalidMac)
+        );
+        assert_eq!(
+            verify_prefix_mac_with(algorithm, b"key", b"other message", &tag),
+            Err(InvalidMac)
+        );
+        assert_eq!(
+            verify_prefix_mac_with(algorithm, b"key", MSG, &tag[..16]),
+            Err(InvalidMac)
+        );
+    }
+
+    let tag = prefix_mac(b"key", MSG);
+    assert_eq!(verify_prefix_mac(b"key", MSG, &tag), Ok(()));
+    assert_eq!(
+        verify_prefix_mac_with(KeyedHash::Blake2b, b"key", MSG, &tag),
+        Err(InvalidMac)
+    );
+}
+
+#[test]
+fn test_legacy_prefix_mac() {
+    // SHA-256("key" || MSG), as an older system would have computed it
+    let legacy =
+        decode("51729876100348eb46ed8c4bf39efa4037a3a2c687f864348ed69292a67ffdbc").unwrap();
+
+    assert_eq!(verify_legacy_prefix_mac(b"key", MSG, &legacy), Ok(()));
+    assert_eq!(
+        verify_legacy_prefix_mac(b"key", b"other message", &legacy),
+        Err(InvalidMac)
+    );
+
+    // the new tags are not prefix hashes, so the two cannot be confused
+    assert_eq!(verify_prefix_mac(b"key", MSG, &legacy), Err(InvalidMac));
+    assert_eq!(
+        verify_legacy_prefix_mac(b"key", MSG, &prefix_mac(b"key", MSG)),
+        Err(InvalidMac)
+    );
+}
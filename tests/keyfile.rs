@@ -0,0 +1,100 @@
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::ecc::keyfile::{self, KeyFileError, FILE_SIZE, MAX_MEMORY_COST};
+use raycrypt::errors::Error;
+use raycrypt::kdfs::argon2::{Argon2, Variant};
+
+// cheap parameters, so the tests do not spend their time in Argon2
+fn kdf() -> Argon2 {
+    Argon2::new(Variant::Argon2id, 64, 1, 1).unwrap()
+}
+
+#[test]
+fn test_seal_and_open() {
+    let key = SigningKey::new(&[7u8; 32]).unwrap();
+    let sealed = keyfile::seal(&key, b"correct horse", &kdf());
+
+    assert_eq!(sealed.len(), FILE_SIZE);
+    assert_eq!(&sealed[..5], b"RCEK\x01");
+
+    let opened = keyfile::open(&sealed, b"correct horse").unwrap();
+    assert_eq!(opened.to_bytes(), key.to_bytes());
+    assert_eq!(keyfile::public_key(&sealed), Ok(key.verifying_key()));
+
+    // the salt and nonce are fresh every time
+    assert_ne!(keyfile::seal(&key, b"correct horse", &kdf()), sealed);
+}
+
+#[test]
+fn test_rejections() {
+    let key = SigningKey::new(&[7u8; 32]).unwrap();
+    let sealed = keyfile::seal(&key, b"correct horse", &kdf());
+
+    assert_eq!(
+        keyfile::open(&sealed, b"battery staple").unwrap_err(),
+        KeyFileError::WrongPassword
+    );
+
+    // the parameters, salt, nonce and public key are all authenticated
+    for position in [8, 20, 40, 80, FILE_SIZE - 1] {
+        let mut tampered = sealed.clone();
+        tampered[position] ^= 1;
+
+        assert!(
+            keyfile::open(&tampered, b"correct horse").is_err(),
+            "{}",
+            position
+        );
+    }
+
+    let mut greedy = sealed.clone();
+    greedy[5..9].copy_from_slice(&(MAX_MEMORY_COST + 1).to_be_bytes());
+    assert_eq!(keyfile::public_key(&greedy), Err(KeyFileError::Malformed));
+
+    let mut wrong_magic = sealed.clone();
+    wrong_magic[0] = b'X';
+    assert_eq!(
+        keyfile::public_key(&wrong_magic),
+        Err(KeyFileError::Malformed)
+    );
+
+    assert_eq!(
+        keyfile::public_key(&sealed[..FILE_SIZE - 1]),
+        Err(KeyFileError::Malformed)
+    );
+    assert_eq!(
+        keyfile::public_key(&[&sealed[..], &[0]].concat()),
+        Err(KeyFileError::Malformed)
+    );
+
+    assert_eq!(Error::from(KeyFileError::WrongPassword), Error::InvalidTag);
+    assert_eq!(Error::from(KeyFileError::Malformed), Error::Format);
+}
+
+#[test]
+fn test_save_and_load() {
+    let key = SigningKey::new(&[9u8; 32]).unwrap();
+    let path = std::env::temp_dir().join(format!("raycrypt-keyfile-{}", std::process::id()));
+
+    keyfile::save(&path, &key, b"hunter2", &kdf()).unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    assert_eq!(
+        keyfile::load(&path, b"hunter2").unwrap().to_bytes(),
+        key.to_bytes()
+    );
+    assert_eq!(keyfile::load_public_key(&path), Ok(key.verifying_key()));
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        keyfile::load_public_key(&path),
+        Err(KeyFileError::Io(std::io::ErrorKind::NotFound))
+    );
+}
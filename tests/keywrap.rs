@@ -0,0 +1,64 @@
+use hex::decode;
+use raycrypt::ciphers::aes::keywrap::{unwrap, wrap};
+use raycrypt::errors::InvalidMac;
+
+// RFC 3394, section 4, and a 40-byte key checked against pyca/cryptography
+#[test]
+fn test_vectors() {
+    let vectors = [
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "00112233445566778899aabbccddeeff",
+            "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f",
+            "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+            "030a11181f262d343b424950575e656c737a81888f969da4",
+            "01c3ec40319b6534b9e44ee1a5ae7f0decc1c5c7e6063937b70354ab338d9d72",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "030a11181f262d343b424950575e656c737a81888f969da4abb2b9c0c7ced5dce3eaf1f8ff060d14",
+            "6b5c9ba022f2f4c77de16207a6606037e04f413d1c88ec7ef9c93b30c8eb7f27d5ec19b2ba163b82ab096a5c08c805c9",
+        ),
+    ];
+
+    for (kek, key, expected) in vectors {
+        let (kek, key, expected) = (
+            decode(kek).unwrap(),
+            decode(key).unwrap(),
+            decode(expected).unwrap(),
+        );
+
+        assert_eq!(wrap(&kek, &key).unwrap(), expected);
+        assert_eq!(unwrap(&kek, &expected).unwrap(), key);
+    }
+}
+
+#[test]
+fn test_tampering() {
+    let kek = [7u8; 16];
+    let wrapped = wrap(&kek, &[1u8; 32]).unwrap();
+
+    for i in 0..wrapped.len() {
+        let mut tampered = wrapped.clone();
+        tampered[i] ^= 1;
+        assert_eq!(unwrap(&kek, &tampered), Err(InvalidMac));
+    }
+
+    assert_eq!(unwrap(&[8u8; 16], &wrapped), Err(InvalidMac));
+    assert_eq!(unwrap(&kek, &wrapped[..16]), Err(InvalidMac));
+    assert_eq!(unwrap(&[7u8; 15], &wrapped), Err(InvalidMac));
+}
+
+#[test]
+fn test_invalid_lengths() {
+    assert!(wrap(&[0u8; 16], &[0u8; 8]).is_err());
+    assert!(wrap(&[0u8; 16], &[0u8; 20]).is_err());
+    assert!(wrap(&[0u8; 17], &[0u8; 16]).is_err());
+}
@@ -0,0 +1,231 @@
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::errors::Error;
+use raycrypt::merkle::{self, MerkleError, SignedTreeHead};
+
+fn decode(encoded: &str) -> [u8; 32] {
+    hex::decode(encoded).unwrap().try_into().unwrap()
+}
+
+// the leaves of the certificate-transparency reference tests
+fn leaves() -> Vec<Vec<u8>> {
+    [
+        "",
+        "00",
+        "10",
+        "2021",
+        "3031",
+        "40414243",
+        "5051525354555657",
+        "606162636465666768696a6b6c6d6e6f",
+    ]
+    .iter()
+    .map(|leaf| hex::decode(leaf).unwrap())
+    .collect()
+}
+
+#[test]
+fn test_roots() {
+    let leaves = leaves();
+    let roots = [
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+        "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+        "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+        "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+    ];
+
+    for (size, root) in roots.iter().enumerate() {
+        assert_eq!(merkle::root(&leaves[..size]), decode(root), "{}", size);
+    }
+}
+
+#[test]
+fn test_proof_vectors() {
+    let leaves = leaves();
+
+    assert_eq!(
+        merkle::inclusion_proof(&leaves, 2),
+        [
+            decode("07506a85fd9dd2f120eb694f86011e5bb4662e5c415a62917033d4a9624487e7"),
+            decode("fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125"),
+            decode("6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4"),
+        ]
+    );
+    assert_eq!(
+        merkle::consistency_proof(&leaves[..7], 3),
+        [
+            decode("0298d122906dcfc10892cb53a73992fc5b9f493ea4c9badb27b791b4127a7fe7"),
+            decode("07506a85fd9dd2f120eb694f86011e5bb4662e5c415a62917033d4a9624487e7"),
+            decode("fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125"),
+            decode("837dbb152e9b079010717e84e865da4ebc0fa198a806d59d31bf15accef22d0e"),
+        ]
+    );
+    assert_eq!(
+        merkle::consistency_proof(&leaves, 4),
+        [decode(
+            "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4"
+        )]
+    );
+}
+
+#[test]
+fn test_inclusion() {
+    let leaves: Vec<Vec<u8>> = (0..21u8).map(|i| vec![i; i as usize]).collect();
+
+    for size in 1..=leaves.len() {
+        let tree = &leaves[..size];
+        let root = merkle::root(tree);
+
+        for index in 0..size {
+            let proof = merkle::inclusion_proof(tree, index);
+            let leaf = merkle::leaf_hash(&tree[index]);
+
+            assert_eq!(
+                merkle::verify_inclusion(&leaf, index as u64, size as u64, &proof, &root),
+                Ok(())
+            );
+
+            // the wrong position or leaf; the size is only bound by the signed tree head, as a
+            // left subtree's paths are the same in the next larger tree
+            let wrong = [
+                merkle::verify_inclusion(&leaf, index as u64 + 1, size as u64, &proof, &root),
+                merkle::verify_inclusion(
+                    &merkle::leaf_hash(b"other"),
+                    index as u64,
+                    size as u64,
+                    &proof,
+                    &root,
+                ),
+            ];
+            assert!(wrong.iter().all(Result::is_err), "{} {}", size, index);
+
+            if let Some((last, rest)) = proof.split_last() {
+                assert!(
+                    merkle::verify_inclusion(&leaf, index as u64, size as u64, rest, &root)
+                        .is_err()
+                );
+
+                let extended = [&proof[..], &[*last]].concat();
+                assert!(merkle::verify_inclusion(
+                    &leaf,
+                    index as u64,
+                    size as u64,
+                    &extended,
+                    &root
+                )
+                .is_err());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_consistency() {
+    let leaves: Vec<Vec<u8>> = (0..21u8).map(|i| vec![i; 3]).collect();
+    let roots: Vec<[u8; 32]> = (0..=leaves.len())
+        .map(|size| merkle::root(&leaves[..size]))
+        .collect();
+
+    for new_size in 0..=leaves.len() {
+        for old_size in 0..=new_size {
+            let proof = merkle::consistency_proof(&leaves[..new_size], old_size);
+            let (old, new) = (old_size as u64, new_size as u64);
+
+            assert_eq!(
+                merkle::verify_consistency(old, new, &roots[old_size], &roots[new_size], &proof),
+                Ok(()),
+                "{} {}",
+                old_size,
+                new_size
+            );
+
+            if old_size == 0 || old_size == new_size {
+                continue;
+            }
+
+            // a different old tree, or the sizes swapped
+            let forked = merkle::root(&[&leaves[..old_size - 1], &[vec![0xff]]].concat());
+            assert_eq!(
+                merkle::verify_consistency(old, new, &forked, &roots[new_size], &proof),
+                Err(MerkleError::InvalidProof)
+            );
+            assert_eq!(
+                merkle::verify_consistency(new, old, &roots[new_size], &roots[old_size], &proof),
+                Err(MerkleError::InvalidProof)
+            );
+        }
+    }
+
+    assert_eq!(
+        merkle::verify_consistency(3, 3, &roots[3], &roots[4], &[]),
+        Err(MerkleError::InvalidProof)
+    );
+    assert_eq!(
+        merkle::verify_consistency(0, 3, &roots[0], &roots[3], &[roots[1]]),
+        Err(MerkleError::InvalidProof)
+    );
+}
+
+#[test]
+fn test_signed_tree_heads() {
+    let key = SigningKey::new(&[5u8; 32]).unwrap();
+    let public = key.verifying_key();
+    let leaves = leaves();
+
+    let old = SignedTreeHead::sign(&key, 1_700_000_000_000, 3, merkle::root(&leaves[..3]));
+    let new = SignedTreeHead::sign(&key, 1_700_000_060_000, 8, merkle::root(&leaves));
+
+    assert_eq!(new.tree_size(), 8);
+    assert_eq!(new.timestamp(), 1_700_000_060_000);
+    assert_eq!(SignedTreeHead::from_bytes(&new.to_bytes()), Ok(new));
+    assert_eq!(
+        SignedTreeHead::from_bytes(&new.to_bytes()[..111]),
+        Err(MerkleError::Malformed)
+    );
+
+    let proof = merkle::consistency_proof(&leaves, 3);
+    assert_eq!(new.verify_extends(&old, &proof, &public), Ok(()));
+    assert_eq!(
+        old.verify_extends(&new, &proof, &public),
+        Err(MerkleError::InvalidProof)
+    );
+
+    let inclusion = merkle::inclusion_proof(&leaves, 5);
+    assert_eq!(
+        new.verify_inclusion(&leaves[5], 5, &inclusion, &public),
+        Ok(())
+    );
+    assert_eq!(
+        new.verify_inclusion(&leaves[4], 5, &inclusion, &public),
+        Err(MerkleError::InvalidProof)
+    );
+
+    // a log that signs a different history at a later size has forked
+    let mut forked = leaves.clone();
+    forked[1] = b"rewritten".to_vec();
+    let fork = SignedTreeHead::sign(&key, 1_700_000_120_000, 8, merkle::root(&forked));
+    assert_eq!(
+        fork.verify_extends(&old, &proof, &public),
+        Err(MerkleError::InvalidProof)
+    );
+
+    let mut tampered = new.to_bytes();
+    tampered[15] ^= 1;
+    let tampered = SignedTreeHead::from_bytes(&tampered).unwrap();
+    assert_eq!(tampered.verify(&public), Err(MerkleError::InvalidSignature));
+    assert_eq!(
+        SigningKey::new(&[6u8; 32])
+            .map(|other| new.verify(&other.verifying_key()))
+            .unwrap(),
+        Err(MerkleError::InvalidSignature)
+    );
+
+    assert_eq!(
+        Error::from(MerkleError::InvalidProof),
+        Error::InvalidSignature
+    );
+}
@@ -0,0 +1,141 @@
+use hex::decode;
+use raycrypt::ecc::x25519::PrivateKey;
+use raycrypt::errors::{InvalidMac, InvalidSignature};
+use raycrypt::nacl::{Box, SealedBox, SecretBox, SigningKey, VerifyKey};
+
+// generated with the RustCrypto crypto_secretbox and crypto_box crates
+const SECRETBOX: &str = "24f29707dc8aba3c35af4bcc67dfaf384a16577a3e54bd2dce507b6a648454fede265447795649dffccc38d1e5c49d4bf4154b32ca92e023716536521ddf7400b085115e93bb8671b0eb81893b4aa08adf8dfae61f8abafa13addb4ec1034b7c0213224dab494f61c73ee11a8d6b8b99e3905d2bd97b439a0520175372af6379f6561a73e7eca52aeec572b1544b349d6725bce6570ef64feb6c51d5cce808dfbffb6d09bda972a8d9d38ef6da973768d4fcc55089fd0bf180018f52759ee0752ab9ed6196a52b6f9230cf433aa936a6267e5344c668da88";
+
+const BOX: &str = "999a10304c03921e0549cf2da52b831697192644ea6f1f228db40b4231aa93b613f7a3d2883830998d9a501039d5a47469df462ffdac69f37fc026665769273f53a0ed0410912998e8ce91d943bad07c036a5246e56ef669094edd9aacf9a8a8417fef83c755af2065d7a7d99245cc137bb74f3705391fc364da3c3affa4cbaad2bfcaf2c38c23e97eeaec027b7b6fda747baf0af11df5f18ea2a37afcab4286acdb50414d30abae9269527e3c606bb8d93c283a96b9674ab3201e784d232589b478f22f393333bf0cb8a621858f39f73351106c47f1152f";
+
+const SEALED: &str = "7684673f0dbd24dc61c7745b0e9ae94c935eb96e3656ebd7a1536649b168bf5ec00bd61144c82a8dd699bf301be59d6ed2536f331cee41a6487df37f4174";
+
+fn inputs() -> (Vec<u8>, [u8; 24], Vec<u8>) {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce: [u8; 24] = core::array::from_fn(|i| 0x40 + i as u8);
+    let msg: Vec<u8> = (0..200).collect();
+
+    (key, nonce, msg)
+}
+
+fn keys() -> (PrivateKey, PrivateKey) {
+    let alice =
+        PrivateKey::from_hex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")
+            .unwrap();
+    let bob =
+        PrivateKey::from_hex("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb")
+            .unwrap();
+
+    (alice, bob)
+}
+
+#[test]
+fn test_secretbox() {
+    let (key, nonce, msg) = inputs();
+    let secret_box = SecretBox::new(&key).unwrap();
+
+    let encrypted = secret_box.encrypt(&msg, Some(&nonce));
+    assert_eq!(encrypted.nonce(), nonce);
+    assert_eq!(encrypted.ciphertext(), decode(SECRETBOX).unwrap());
+
+    assert_eq!(secret_box.decrypt(&encrypted, None).unwrap(), msg);
+    assert_eq!(
+        secret_box
+            .decrypt(encrypted.ciphertext(), Some(&nonce))
+            .unwrap(),
+        msg
+    );
+
+    let mut tampered = encrypted.into_vec();
+    tampered[30] ^= 1;
+    assert_eq!(secret_box.decrypt(&tampered, None), Err(InvalidMac));
+    assert_eq!(secret_box.decrypt(&tampered[..39], None), Err(InvalidMac));
+
+    let random = secret_box.encrypt(b"", None);
+    assert_eq!(random.len(), 40);
+    assert_eq!(secret_box.decrypt(&random, None).unwrap(), b"");
+
+    assert!(SecretBox::new(&key[1..]).is_err());
+}
+
+#[test]
+fn test_box() {
+    let (_, nonce, msg) = inputs();
+    let (alice, bob) = keys();
+
+    let alice_box = Box::new(&alice, &bob.public_key()).unwrap();
+    let bob_box = Box::new(&bob, &alice.public_key()).unwrap();
+
+    let encrypted = alice_box.encrypt(&msg, Some(&nonce));
+    assert_eq!(encrypted.ciphertext(), decode(BOX).unwrap());
+    assert_eq!(bob_box.decrypt(&encrypted, None).unwrap(), msg);
+    assert_eq!(alice_box.shared_key(), bob_box.shared_key());
+
+    assert!(Box::new(&alice, &[0u8; 32]).is_err());
+}
+
+#[test]
+fn test_sealed_box() {
+    let (_, bob) = keys();
+
+    let opener = SealedBox::from_private_key(&bob);
+    assert_eq!(
+        opener.decrypt(&decode(SEALED).unwrap()).unwrap(),
+        b"sealed message"
+    );
+
+    let sealer = SealedBox::new(&bob.public_key());
+    let sealed = sealer.encrypt(b"hello").unwrap();
+
+    assert_eq!(sealed.len(), 5 + 48);
+    assert_eq!(opener.decrypt(&sealed).unwrap(), b"hello");
+    assert_eq!(sealer.decrypt(&sealed), Err(InvalidMac));
+    assert_ne!(sealed, sealer.encrypt(b"hello").unwrap());
+}
+
+// RFC 8032, section 7.1, test 2
+#[test]
+fn test_signing() {
+    let signing_key = SigningKey::new(
+        &decode("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb").unwrap(),
+    )
+    .unwrap();
+    let verify_key = VerifyKey::new(
+        &decode("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c").unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(signing_key.verify_key(), verify_key);
+
+    let signed = signing_key.sign(&[0x72]);
+    assert_eq!(
+        signed.signature(),
+        decode("92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00").unwrap()
+    );
+    assert_eq!(signed.message(), [0x72]);
+
+    assert_eq!(verify_key.verify(&signed, None).unwrap(), [0x72]);
+    assert_eq!(
+        verify_key
+            .verify(signed.message(), Some(signed.signature()))
+            .unwrap(),
+        [0x72]
+    );
+    assert_eq!(
+        verify_key.verify(&[0x73], Some(signed.signature())),
+        Err(InvalidSignature)
+    );
+    assert_eq!(
+        verify_key.verify(&signed[..63], None),
+        Err(InvalidSignature)
+    );
+
+    let generated = SigningKey::generate();
+    let signed = generated.sign(b"message");
+    assert!(generated.verify_key().verify(&signed, None).is_ok());
+
+    assert_eq!(
+        signing_key.to_curve25519_private_key().public_key(),
+        verify_key.to_curve25519_public_key()
+    );
+}
@@ -0,0 +1,124 @@
+use raycrypt::aeads::ndarray::{decrypt_ndarray, describe_ndarray, encrypt_ndarray, NdArrayError};
+use raycrypt::aeads::XChaCha20Poly1305;
+
+fn cipher() -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new(&[9u8; 32])
+}
+
+#[test]
+fn test_round_trip() {
+    let cipher = cipher();
+
+    // an embedding matrix spanning several batches
+    let embeddings: Vec<f32> = (0..3 * 5000).map(|i| i as f32 / 7.0).collect();
+    let sealed = encrypt_ndarray(&cipher, &embeddings, &[3, 5000]).unwrap();
+    assert_eq!(describe_ndarray(&sealed).unwrap(), ("<f4", vec![3, 5000]));
+    assert_eq!(
+        decrypt_ndarray::<f32>(&cipher, &sealed).unwrap(),
+        (embeddings, vec![3, 5000])
+    );
+
+    let values = [i64::MIN, -1, 0, 1, i64::MAX];
+    let sealed = encrypt_ndarray(&cipher, &values, &[5]).unwrap();
+    assert_eq!(
+        decrypt_ndarray::<i64>(&cipher, &sealed).unwrap(),
+        (values.to_vec(), vec![5])
+    );
+
+    // scalars and empty arrays
+    let sealed = encrypt_ndarray(&cipher, &[2.5f64], &[]).unwrap();
+    assert_eq!(
+        decrypt_ndarray::<f64>(&cipher, &sealed).unwrap(),
+        (vec![2.5], vec![])
+    );
+
+    let sealed = encrypt_ndarray::<u8>(&cipher, &[], &[4, 0]).unwrap();
+    assert_eq!(describe_ndarray(&sealed).unwrap(), ("|u1", vec![4, 0]));
+    assert_eq!(
+        decrypt_ndarray::<u8>(&cipher, &sealed).unwrap(),
+        (vec![], vec![4, 0])
+    );
+}
+
+// the bytes of a NumPy array with the same dtype
+#[test]
+fn test_layout() {
+    let cipher = cipher();
+    let sealed = encrypt_ndarray(&cipher, &[1u16, 0x0203], &[2]).unwrap();
+
+    let header = [&[1, 3][..], b"<u2", &[1], &2u64.to_be_bytes()].concat();
+    assert_eq!(&sealed[..header.len()], &header[..]);
+
+    let nonce = &sealed[header.len()..header.len() + 24];
+    let ct = &sealed[header.len() + 24..];
+    assert_eq!(cipher.decrypt(ct, nonce, &header).unwrap(), [1, 0, 3, 2]);
+}
+
+#[test]
+fn test_shape_mismatch() {
+    let cipher = cipher();
+
+    assert_eq!(
+        encrypt_ndarray(&cipher, &[1u32, 2, 3], &[2, 2]),
+        Err(NdArrayError::ShapeMismatch)
+    );
+    assert_eq!(
+        encrypt_ndarray(&cipher, &[1u32], &[usize::MAX, 2]),
+        Err(NdArrayError::ShapeMismatch)
+    );
+    assert_eq!(
+        encrypt_ndarray(&cipher, &[1u32], &[1; 33]),
+        Err(NdArrayError::ShapeMismatch)
+    );
+}
+
+#[test]
+fn test_tampering() {
+    let cipher = cipher();
+    let sealed = encrypt_ndarray(&cipher, &[1.0f32, 2.0, 3.0, 4.0], &[2, 2]).unwrap();
+
+    assert_eq!(
+        decrypt_ndarray::<i32>(&cipher, &sealed),
+        Err(NdArrayError::WrongType)
+    );
+
+    // reshaped to 1 x 4 in the header
+    let mut reshaped = sealed.clone();
+    reshaped[13] = 1;
+    reshaped[21] = 4;
+    assert_eq!(describe_ndarray(&reshaped).unwrap().1, vec![1, 4]);
+    assert_eq!(
+        decrypt_ndarray::<f32>(&cipher, &reshaped),
+        Err(NdArrayError::InvalidMac)
+    );
+
+    for i in 22..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 1;
+        assert_eq!(
+            decrypt_ndarray::<f32>(&cipher, &tampered),
+            Err(NdArrayError::InvalidMac)
+        );
+    }
+
+    let other = XChaCha20Poly1305::new(&[8u8; 32]);
+    assert_eq!(
+        decrypt_ndarray::<f32>(&other, &sealed),
+        Err(NdArrayError::InvalidMac)
+    );
+
+    for length in [0, 1, 5, 21, sealed.len() - 1] {
+        assert_eq!(
+            decrypt_ndarray::<f32>(&cipher, &sealed[..length]),
+            Err(NdArrayError::Malformed)
+        );
+    }
+
+    // a shape whose element count overflows
+    let mut huge = sealed.clone();
+    huge[6..14].copy_from_slice(&u64::MAX.to_be_bytes());
+    assert_eq!(
+        decrypt_ndarray::<f32>(&cipher, &huge),
+        Err(NdArrayError::Malformed)
+    );
+}
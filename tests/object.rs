@@ -0,0 +1,143 @@
+use raycrypt::aeads::object::{
+    open_object, open_trusted_object, seal_object, CborCodec, Codec, ObjectError,
+};
+use raycrypt::aeads::XChaCha20Poly1305;
+use raycrypt::cbor::{CborError, Value};
+
+fn cipher() -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new(&[6u8; 32])
+}
+
+// in the canonical order, which is how decoded maps come back
+fn profile() -> Value {
+    Value::Map(vec![
+        (Value::from("age"), Value::from(36u64)),
+        (Value::from("name"), Value::from("Ada")),
+    ])
+}
+
+// stands in for a format like pickle, whose decoder must not see untrusted input
+struct RawCodec;
+
+impl Codec<Vec<u8>> for RawCodec {
+    type Error = ();
+
+    fn name(&self) -> &str {
+        "raw"
+    }
+
+    fn trusted_only(&self) -> bool {
+        true
+    }
+
+    fn encode(&self, value: &Vec<u8>) -> Result<Vec<u8>, ()> {
+        Ok(value.clone())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        Ok(bytes.to_vec())
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let cipher = cipher();
+    let sealed = seal_object(&cipher, &CborCodec, &profile(), "profile/v1").unwrap();
+
+    assert_eq!(
+        open_object(&cipher, &CborCodec, &sealed, "profile/v1").unwrap(),
+        profile()
+    );
+    assert_ne!(
+        seal_object(&cipher, &CborCodec, &profile(), "profile/v1").unwrap(),
+        sealed
+    );
+}
+
+#[test]
+fn test_type_tags() {
+    let cipher = cipher();
+    let sealed = seal_object(&cipher, &CborCodec, &profile(), "profile/v1").unwrap();
+
+    assert_eq!(
+        open_object(&cipher, &CborCodec, &sealed, "profile/v2"),
+        Err(ObjectError::WrongType)
+    );
+
+    // a tag rewritten in place still fails, as it is authenticated: 1, 4, "cbor", 10, "profile/v1"
+    let mut retagged = sealed.clone();
+    retagged[16] = b'2';
+    assert_eq!(
+        open_object(&cipher, &CborCodec, &retagged, "profile/v2"),
+        Err(ObjectError::InvalidMac)
+    );
+}
+
+#[test]
+fn test_trusted_only_codecs() {
+    let cipher = cipher();
+    let sealed = seal_object(&cipher, &RawCodec, &b"data".to_vec(), "blob").unwrap();
+
+    assert_eq!(
+        open_object(&cipher, &RawCodec, &sealed, "blob"),
+        Err(ObjectError::UntrustedCodec)
+    );
+    assert_eq!(
+        open_trusted_object(&cipher, &RawCodec, &sealed, "blob").unwrap(),
+        b"data"
+    );
+
+    // an object sealed with one codec cannot be decoded with another
+    let sealed = seal_object(&cipher, &RawCodec, &b"\xa0".to_vec(), "profile/v1").unwrap();
+    assert_eq!(
+        open_object(&cipher, &CborCodec, &sealed, "profile/v1"),
+        Err(ObjectError::WrongType)
+    );
+}
+
+#[test]
+fn test_errors() {
+    let cipher = cipher();
+    let sealed = seal_object(&cipher, &CborCodec, &profile(), "profile/v1").unwrap();
+
+    let mut tampered = sealed.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    assert_eq!(
+        open_object(&cipher, &CborCodec, &tampered, "profile/v1"),
+        Err(ObjectError::InvalidMac)
+    );
+
+    assert_eq!(
+        open_object(&cipher, &CborCodec, &sealed[..30], "profile/v1"),
+        Err(ObjectError::Malformed)
+    );
+
+    let duplicate = Value::Map(vec![
+        (Value::from("a"), Value::Null),
+        (Value::from("a"), Value::Null),
+    ]);
+    assert_eq!(
+        seal_object(&cipher, &CborCodec, &duplicate, "profile/v1"),
+        Err(ObjectError::Codec(CborError::DuplicateKey))
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_codec() {
+    use raycrypt::aeads::object::JsonCodec;
+
+    let cipher = cipher();
+    let value = serde_json::json!({"event": "login", "user": 42});
+    let sealed = seal_object(&cipher, &JsonCodec, &value, "event").unwrap();
+
+    assert_eq!(
+        open_object(&cipher, &JsonCodec, &sealed, "event").unwrap(),
+        value
+    );
+    assert!(matches!(
+        open_object(&cipher, &CborCodec, &sealed, "event"),
+        Err(ObjectError::WrongType)
+    ));
+}
@@ -0,0 +1,113 @@
+use raycrypt::aeads::{
+    open, parse_header, seal, seal_with, AeadAlgorithm, ChaCha20Poly1305, SealError, SymmetricKey,
+};
+use raycrypt::audit::KeyId;
+use std::time::{Duration, SystemTime};
+
+const ALGORITHMS: [AeadAlgorithm; 3] = [
+    AeadAlgorithm::XChaCha20Poly1305,
+    AeadAlgorithm::ChaCha20Poly1305,
+    AeadAlgorithm::AesGcm,
+];
+
+#[test]
+fn test_round_trip() {
+    let key = SymmetricKey::generate();
+
+    let sealed = seal(&key, b"db password", b"");
+    assert_eq!(sealed[..2], [2, 1]);
+    assert_eq!(sealed.len(), 18 + 24 + 11 + 16);
+    assert_eq!(open(&key, &sealed, b"").unwrap(), b"db password");
+
+    for algorithm in ALGORITHMS {
+        let sealed = seal_with(algorithm, &key, b"db password", b"service=api");
+
+        assert_eq!(sealed.len(), 18 + algorithm.nonce_size() + 11 + 16);
+        assert_eq!(open(&key, &sealed, b"service=api").unwrap(), b"db password");
+        assert_eq!(
+            open(&key, &sealed, b"service=web"),
+            Err(SealError::InvalidMac)
+        );
+    }
+}
+
+#[test]
+fn test_header_is_authenticated() {
+    let key = SymmetricKey::new(&[5u8; 32]).unwrap();
+    let sealed = seal_with(AeadAlgorithm::ChaCha20Poly1305, &key, b"secret", b"");
+
+    // the same bytes without the header are a valid ChaCha20-Poly1305 ciphertext only under
+    // the header as associated data
+    let cipher = ChaCha20Poly1305::new(key.as_bytes());
+    assert_eq!(
+        cipher
+            .decrypt(&sealed[30..], &sealed[18..30], &sealed[..18])
+            .unwrap(),
+        b"secret"
+    );
+
+    let mut relabelled = sealed.clone();
+    relabelled[1] = 3;
+    assert_eq!(open(&key, &relabelled, b""), Err(SealError::InvalidMac));
+
+    let mut backdated = sealed.clone();
+    backdated[17] ^= 1;
+    assert_eq!(open(&key, &backdated, b""), Err(SealError::InvalidMac));
+}
+
+#[test]
+fn test_inspection() {
+    let key = SymmetricKey::generate();
+    let before = SystemTime::now() - Duration::from_secs(1);
+    let sealed = seal_with(AeadAlgorithm::AesGcm, &key, b"db password", b"");
+
+    let header = parse_header(&sealed).unwrap();
+    assert_eq!(header.version, 2);
+    assert_eq!(header.algorithm, AeadAlgorithm::AesGcm);
+    assert_eq!(header.key_id, Some(KeyId::new(key.as_bytes())));
+    assert!(header.created.unwrap() >= before && header.created.unwrap() <= SystemTime::now());
+    assert_eq!(header.nonce, sealed[18..30]);
+    assert_eq!(header.message_size, 11);
+
+    assert_eq!(
+        open(&SymmetricKey::generate(), &sealed, b""),
+        Err(SealError::WrongKey)
+    );
+}
+
+#[test]
+fn test_version_1() {
+    // version || algorithm || nonce || ciphertext || tag, with no key id or creation time
+    let key = SymmetricKey::new(&[5u8; 32]).unwrap();
+    let nonce = [7u8; 12];
+    let ct = ChaCha20Poly1305::new(key.as_bytes()).encrypt(b"secret", &nonce, &[1, 2]);
+    let sealed = [&[1, 2][..], &nonce, &ct].concat();
+
+    assert_eq!(open(&key, &sealed, b"").unwrap(), b"secret");
+
+    let header = parse_header(&sealed).unwrap();
+    assert_eq!(header.version, 1);
+    assert_eq!(header.key_id, None);
+    assert_eq!(header.created, None);
+    assert_eq!(header.nonce, nonce);
+}
+
+#[test]
+fn test_errors() {
+    let key = SymmetricKey::generate();
+    let sealed = seal(&key, b"secret", b"");
+
+    let mut wrong_key = sealed.clone();
+    wrong_key[5] ^= 1;
+    assert_eq!(open(&key, &wrong_key, b""), Err(SealError::WrongKey));
+    assert_eq!(open(&key, &sealed[..57], b""), Err(SealError::Malformed));
+    assert_eq!(open(&key, b"", b""), Err(SealError::Malformed));
+
+    let mut unknown = sealed.clone();
+    unknown[1] = 9;
+    assert_eq!(open(&key, &unknown, b""), Err(SealError::UnknownAlgorithm));
+
+    let mut version = sealed;
+    version[0] = 3;
+    assert_eq!(open(&key, &version, b""), Err(SealError::Malformed));
+}
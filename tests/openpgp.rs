@@ -0,0 +1,240 @@
+use hex::decode;
+use raycrypt::openpgp::{
+    armor, dearmor, decrypt, encrypt, ArmorKind, Certificate, LiteralData, OpenPgpError, SecretKey,
+};
+use serde_json::{from_str, Value};
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn vectors() -> Value {
+    let raw = fs::read_to_string("tests/vectors/openpgp.json").unwrap();
+    from_str(&raw).unwrap()
+}
+
+fn dearmored(data: &Value, name: &str) -> Vec<u8> {
+    dearmor(data[name].as_str().unwrap()).unwrap().1
+}
+
+fn literal(data: &[u8]) -> LiteralData {
+    LiteralData::new(
+        b"file.bin",
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        data,
+    )
+}
+
+// the sample v6 certificate and secret key of RFC 9580, appendix A
+#[test]
+fn test_rfc_keys() {
+    let data = vectors();
+    let certificate = Certificate::from_bytes(&dearmored(&data, "certificate")).unwrap();
+
+    assert_eq!(
+        certificate.fingerprint().to_vec(),
+        decode(data["certificateFingerprint"].as_str().unwrap()).unwrap()
+    );
+    assert_eq!(
+        certificate.encryption_fingerprint().to_vec(),
+        decode(data["encryptionFingerprint"].as_str().unwrap()).unwrap()
+    );
+
+    let key = SecretKey::from_bytes(&dearmored(&data, "secretKey")).unwrap();
+    assert_eq!(key.certificate(), &certificate);
+    assert_eq!(key.to_bytes(), dearmored(&data, "secretKey"));
+
+    assert_eq!(
+        Certificate::from_bytes(&dearmored(&data, "secretKey")),
+        Err(OpenPgpError::Malformed)
+    );
+}
+
+#[test]
+fn test_rfc_messages() {
+    let data = vectors();
+    let key = SecretKey::from_bytes(&dearmored(&data, "secretKey")).unwrap();
+
+    // RFC 9580, appendix A.8: AES-128 with OCB, followed by a padding packet
+    let message = decrypt(&key, &dearmored(&data, "ocbMessage")).unwrap();
+    assert_eq!(message.data(), b"Hello, world!");
+    assert_eq!(message.filename(), b"");
+    assert_eq!(message.modified(), UNIX_EPOCH);
+
+    // AES-256 with GCM in 64-byte chunks, made with pyca/cryptography
+    let message = decrypt(&key, &dearmored(&data, "gcmMessage")).unwrap();
+    assert_eq!(message.data(), b"interop with GCM\n".repeat(200));
+    assert_eq!(message.filename(), b"note.txt");
+    assert_eq!(
+        message.modified(),
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let key = SecretKey::generate(SystemTime::now());
+    let certificate = Certificate::from_bytes(&key.certificate().to_bytes()).unwrap();
+    assert_eq!(certificate.fingerprint(), key.fingerprint());
+
+    // either side of the 64 KiB chunk size
+    for length in [0, 1, 65535, 65536, 65537, 3 * 65536 + 100] {
+        let data: Vec<u8> = (0..length).map(|i| i as u8).collect();
+        let message = encrypt(std::slice::from_ref(&certificate), &literal(&data));
+
+        assert_eq!(decrypt(&key, &message).unwrap(), literal(&data));
+    }
+}
+
+// the RFC certificate does not ask for GCM, so this takes the OCB path
+#[test]
+fn test_ocb_round_trip() {
+    let data = vectors();
+    let key = SecretKey::from_bytes(&dearmored(&data, "secretKey")).unwrap();
+
+    let plaintext = vec![7u8; 70000];
+    let message = encrypt(&[key.certificate().clone()], &literal(&plaintext));
+    assert_eq!(decrypt(&key, &message).unwrap().into_data(), plaintext);
+}
+
+#[test]
+fn test_recipients() {
+    let alice = SecretKey::generate(SystemTime::now());
+    let bob = SecretKey::generate(SystemTime::now());
+    let eve = SecretKey::generate(SystemTime::now());
+
+    let message = encrypt(
+        &[alice.certificate().clone(), bob.certificate().clone()],
+        &literal(b"for both"),
+    );
+
+    assert_eq!(decrypt(&alice, &message).unwrap().data(), b"for both");
+    assert_eq!(decrypt(&bob, &message).unwrap().data(), b"for both");
+    assert_eq!(decrypt(&eve, &message), Err(OpenPgpError::NoMatchingKey));
+}
+
+#[test]
+fn test_secret_key_round_trip() {
+    let key = SecretKey::generate(SystemTime::now());
+    let message = encrypt(&[key.certificate().clone()], &literal(b"hello"));
+
+    let armored = armor(ArmorKind::PrivateKey, &key.to_bytes());
+    let (kind, bytes) = dearmor(&armored).unwrap();
+    assert_eq!(kind, ArmorKind::PrivateKey);
+
+    let restored = SecretKey::from_bytes(&bytes).unwrap();
+    assert_eq!(restored.certificate(), key.certificate());
+    assert_eq!(decrypt(&restored, &message).unwrap().data(), b"hello");
+
+    assert_eq!(
+        SecretKey::from_bytes(&key.certificate().to_bytes()).unwrap_err(),
+        OpenPgpError::NoMatchingKey
+    );
+}
+
+#[test]
+fn test_tampering() {
+    let key = SecretKey::generate(SystemTime::now());
+    let message = encrypt(&[key.certificate().clone()], &literal(b"attack at dawn"));
+
+    // everything after the SEIPD salt: the literal data packet, its tag and the final tag
+    let start = message.len() - (2 + 1 + 1 + 8 + 4 + 14 + 16 + 16);
+
+    for i in start..message.len() {
+        let mut tampered = message.clone();
+        tampered[i] ^= 1;
+        assert_eq!(decrypt(&key, &tampered), Err(OpenPgpError::InvalidMac));
+    }
+
+    let mut tampered = message.clone();
+    tampered[start - 1] ^= 1;
+    assert_eq!(decrypt(&key, &tampered), Err(OpenPgpError::InvalidMac));
+
+    assert_eq!(
+        decrypt(&key, &message[..message.len() - 1]),
+        Err(OpenPgpError::Malformed)
+    );
+}
+
+#[test]
+fn test_certificate_checks() {
+    let key = SecretKey::generate(SystemTime::now());
+    let bytes = key.certificate().to_bytes();
+
+    // a broken subkey binding leaves no key to encrypt to
+    let mut tampered = bytes.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    assert_eq!(
+        Certificate::from_bytes(&tampered),
+        Err(OpenPgpError::NoEncryptionKey)
+    );
+
+    // the primary key and its direct key signature alone
+    let primary = 2 + 42 + 2 + usize::from(bytes[45]);
+    assert_eq!(
+        Certificate::from_bytes(&bytes[..primary]),
+        Err(OpenPgpError::NoEncryptionKey)
+    );
+
+    assert_eq!(
+        Certificate::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(OpenPgpError::Malformed)
+    );
+    assert_eq!(Certificate::from_bytes(&[]), Err(OpenPgpError::Malformed));
+}
+
+// the same message with old-format headers and partial body lengths
+#[test]
+fn test_packet_formats() {
+    let key = SecretKey::generate(SystemTime::now());
+    let message = encrypt(&[key.certificate().clone()], &literal(&[1u8; 3000]));
+
+    let pkesk_length = usize::from(message[1]);
+    let (pkesk, seipd) = message.split_at(2 + pkesk_length);
+
+    // tag 1 with a one-byte length
+    let mut reencoded = vec![0x84, pkesk_length as u8];
+    reencoded.extend_from_slice(&pkesk[2..]);
+
+    // tag 18 in parts of 1024 and 512 bytes and a final length
+    let body = &seipd[3..];
+    reencoded.extend_from_slice(&[0xd2, 0xe0 | 10]);
+    reencoded.extend_from_slice(&body[..1024]);
+    reencoded.push(0xe0 | 9);
+    reencoded.extend_from_slice(&body[1024..1536]);
+    let rest = body.len() - 1536 - 192;
+    reencoded.extend_from_slice(&[(rest >> 8) as u8 + 192, rest as u8]);
+    reencoded.extend_from_slice(&body[1536..]);
+
+    assert_eq!(decrypt(&key, &reencoded).unwrap().data(), [1u8; 3000]);
+}
+
+#[test]
+fn test_armor() {
+    let data = b"some binary data".repeat(10);
+    let armored = armor(ArmorKind::Message, &data);
+    assert!(armored.starts_with("-----BEGIN PGP MESSAGE-----\n\n"));
+    assert_eq!(
+        dearmor(&armored).unwrap(),
+        (ArmorKind::Message, data.clone())
+    );
+
+    // headers, a checksum and surrounding text are all skipped
+    let lines: Vec<&str> = armored.lines().collect();
+    let decorated = format!(
+        "text before\n{}\nComment: a comment\nVersion: 1\n\n{}\n=njUN\n{}\ntext after\n",
+        lines[0],
+        lines[2..lines.len() - 1].join("\n"),
+        lines[lines.len() - 1]
+    );
+    assert_eq!(dearmor(&decorated).unwrap(), (ArmorKind::Message, data));
+
+    assert_eq!(
+        dearmor("-----BEGIN PGP SIGNATURE-----\n\n-----END PGP SIGNATURE-----\n"),
+        Err(OpenPgpError::Unsupported)
+    );
+    assert_eq!(
+        dearmor(&armored[..armored.len() - 10]),
+        Err(OpenPgpError::Malformed)
+    );
+    assert_eq!(dearmor("nothing here"), Err(OpenPgpError::Malformed));
+}
@@ -0,0 +1,107 @@
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::hashes::sha512::Sha512;
+use raycrypt::kdfs::pbkdf2::pbkdf2;
+use raycrypt::openssl::{self, OpensslError, DEFAULT_ITERATIONS};
+
+const MSG: &[u8] = b"Please migrate me to raycrypt.";
+
+#[test]
+fn test_pbkdf2() {
+    // RFC 7914 section 11 and the usual PBKDF2-HMAC-SHA256 vectors
+    assert_eq!(
+        hex::encode(pbkdf2::<Sha256>(b"password", b"salt", 4096, 32)),
+        "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+    );
+    assert_eq!(
+        hex::encode(pbkdf2::<Sha256>(
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            40
+        )),
+        "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9"
+    );
+    assert_eq!(
+        hex::encode(pbkdf2::<Sha512>(b"password", b"salt", 1, 64)),
+        "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252\
+         c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce"
+    );
+}
+
+#[test]
+fn test_openssl_output() {
+    // `openssl enc -aes-256-cbc -pbkdf2 -pass pass:hunter2`
+    let data = hex::decode(
+        "53616c7465645f5fa9eb385ba8ebcc0783f5b9a888de9cdfdd0970959fa34b30\
+         80c07293757f08783e9ea18787bd8fa3",
+    )
+    .unwrap();
+    assert_eq!(
+        openssl::decrypt(b"hunter2", &data, DEFAULT_ITERATIONS).unwrap(),
+        MSG
+    );
+
+    // with `-iter 1000 -a`
+    let data = "U2FsdGVkX1/BdWg8ar/sSethVo//Ian6Xbc0MMu2DfCXNlsP8/Qgn4CpR8uNFedu\n";
+    assert_eq!(
+        openssl::decrypt_base64(b"hunter2", data, 1000).unwrap(),
+        MSG
+    );
+
+    // an empty file is one block of padding
+    let data =
+        hex::decode("53616c7465645f5f3d75adf156ce970bc5470dd3b4960b6851d1e5a4ec06852f").unwrap();
+    assert_eq!(
+        openssl::decrypt(b"hunter2", &data, DEFAULT_ITERATIONS).unwrap(),
+        b""
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    for length in [0, 15, 16, 17, 100] {
+        let msg = vec![0x42; length];
+        let data = openssl::encrypt(b"hunter2", &msg, 100);
+
+        assert_eq!(&data[..8], b"Salted__");
+        assert_eq!(data.len(), 16 + (length / 16 + 1) * 16);
+        assert_eq!(openssl::decrypt(b"hunter2", &data, 100).unwrap(), msg);
+    }
+
+    let encoded = openssl::encrypt_base64(b"hunter2", &[7u8; 100], 100);
+    assert!(encoded.starts_with("U2FsdGVkX1"));
+    assert!(encoded.lines().all(|line| line.len() <= 64));
+    assert_eq!(
+        openssl::decrypt_base64(b"hunter2", &encoded, 100).unwrap(),
+        [7u8; 100]
+    );
+}
+
+#[test]
+fn test_errors() {
+    let data = openssl::encrypt(b"hunter2", MSG, 100);
+
+    assert_eq!(
+        openssl::decrypt(b"hunter2", &data[..data.len() - 1], 100),
+        Err(OpensslError::Malformed)
+    );
+    assert_eq!(
+        openssl::decrypt(b"hunter2", &data[8..], 100),
+        Err(OpensslError::Malformed)
+    );
+    assert_eq!(
+        openssl::decrypt_base64(b"hunter2", "not base64!", 100),
+        Err(OpensslError::Malformed)
+    );
+
+    // a wrong password is caught by the padding check, except by chance
+    let mut caught = 0;
+
+    for i in 0..32u8 {
+        if openssl::decrypt(&[i], &data, 100) == Err(OpensslError::BadDecrypt) {
+            caught += 1;
+        }
+    }
+
+    assert!(caught > 24);
+}
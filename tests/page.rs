@@ -0,0 +1,82 @@
+use raycrypt::aeads::page::{PageCodec, RESERVED_SIZE};
+use raycrypt::errors::InvalidMac;
+
+const KEY: [u8; 32] = [3u8; 32];
+
+#[test]
+fn test_round_trip() {
+    for page_size in [512, 4096, 65536] {
+        let mut codec = PageCodec::new(&KEY, page_size);
+        assert_eq!(codec.data_size(), page_size - RESERVED_SIZE);
+
+        let data: Vec<u8> = (0..codec.data_size()).map(|i| i as u8).collect();
+        let page = codec.encrypt_page(7, &data);
+        assert_eq!(page.len(), page_size);
+        assert_ne!(&page[..data.len()], &data[..]);
+
+        assert_eq!(codec.decrypt_page(7, &page).unwrap(), data);
+    }
+}
+
+#[test]
+fn test_generations() {
+    let mut codec = PageCodec::new(&KEY, 1024);
+    let data = vec![0u8; codec.data_size()];
+
+    // rewriting the same data to the same page gives a different page each time
+    let start = codec.generation();
+    let first = codec.encrypt_page(1, &data);
+    let second = codec.encrypt_page(1, &data);
+    assert_ne!(first, second);
+    assert_eq!(codec.generation(), start + 2);
+
+    assert_eq!(codec.decrypt_page(1, &first).unwrap(), data);
+    assert_eq!(codec.decrypt_page(1, &second).unwrap(), data);
+
+    // a reopened codec starts somewhere else, so writing before reading does not reuse nonces
+    let mut reopened = PageCodec::new(&KEY, 1024);
+    assert_ne!(reopened.generation(), start);
+    assert_ne!(reopened.encrypt_page(1, &data), first);
+
+    // reading pages moves a codec past their generations
+    let mut resumed = PageCodec::resume(&KEY, 1024, 0);
+    resumed.decrypt_page(1, &second).unwrap();
+    assert_eq!(resumed.generation(), start + 2);
+    resumed.decrypt_page(1, &first).unwrap();
+    assert_eq!(resumed.generation(), start + 2);
+
+    let mut resumed = PageCodec::resume(&KEY, 1024, u64::MAX - 1);
+    assert_eq!(resumed.decrypt_page(1, &first).unwrap(), data);
+    assert_eq!(resumed.generation(), u64::MAX - 1);
+
+    let third = resumed.encrypt_page(1, &data);
+    assert_eq!(&third[1000..1008], &(u64::MAX - 1).to_be_bytes());
+}
+
+#[test]
+fn test_tampering() {
+    let mut codec = PageCodec::new(&KEY, 512);
+    let start = codec.generation();
+    let page = codec.encrypt_page(3, &[9u8; 512 - RESERVED_SIZE]);
+
+    for i in 0..page.len() {
+        let mut tampered = page.clone();
+        tampered[i] ^= 1;
+        assert_eq!(codec.decrypt_page(3, &tampered), Err(InvalidMac));
+    }
+
+    // a page moved to another page number, read at another size or with another key
+    assert_eq!(codec.decrypt_page(4, &page), Err(InvalidMac));
+    assert_eq!(codec.decrypt_page(3, &page[..511]), Err(InvalidMac));
+    assert_eq!(
+        PageCodec::new(&KEY, 511).decrypt_page(3, &page[..511]),
+        Err(InvalidMac)
+    );
+    assert_eq!(
+        PageCodec::new(&[4u8; 32], 512).decrypt_page(3, &page),
+        Err(InvalidMac)
+    );
+
+    // failed reads leave the generation counter alone
+    assert_eq!(codec.generation(), start + 1);
+}
@@ -0,0 +1,79 @@
+// Every container parser has to reject truncated and corrupted input with an error, never a
+// panic, whatever it is given.
+
+use raycrypt::aeads::envelope::{self, LocalKeyProvider};
+use raycrypt::aeads::ndarray::{decrypt_ndarray, describe_ndarray, encrypt_ndarray};
+use raycrypt::aeads::{open, parse_header, seal, AeadAlgorithm, SymmetricKey, XChaCha20Poly1305};
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::openpgp::{self, Certificate, LiteralData, SecretKey};
+use std::time::SystemTime;
+
+// every prefix, and every single-byte corruption
+fn mutations(data: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    let prefixes = (0..data.len()).map(|length| data[..length].to_vec());
+    let corruptions = (0..data.len()).flat_map(move |i| {
+        [0x01, 0x80, 0xff].map(|flip| {
+            let mut corrupted = data.to_vec();
+            corrupted[i] ^= flip;
+            corrupted
+        })
+    });
+
+    prefixes.chain(corruptions)
+}
+
+#[test]
+fn test_sealed() {
+    let key = SymmetricKey::generate();
+    let sealed = seal(&key, b"secret", b"");
+
+    let header = parse_header(&sealed).unwrap();
+    assert_eq!(header.algorithm, AeadAlgorithm::XChaCha20Poly1305);
+    assert_eq!(header.nonce, sealed[18..42]);
+    assert_eq!(header.message_size, 6);
+
+    for data in mutations(&sealed) {
+        assert!(open(&key, &data, b"").is_err());
+        let _ = parse_header(&data);
+    }
+}
+
+#[test]
+fn test_envelope() {
+    let provider =
+        LocalKeyProvider::new(&SymmetricKey::generate(), SigningKey::from_seed(b"parsers"));
+    let sealed = envelope::seal(&provider, b"secret", b"").unwrap();
+
+    for data in mutations(&sealed) {
+        assert!(envelope::open(&provider, &data, b"").is_err());
+    }
+}
+
+#[test]
+fn test_ndarray() {
+    let cipher = XChaCha20Poly1305::new(&[1u8; 32]);
+    let sealed = encrypt_ndarray(&cipher, &[1.5f32, 2.5, 3.5, 4.5], &[2, 2]).unwrap();
+
+    for data in mutations(&sealed) {
+        assert!(decrypt_ndarray::<f32>(&cipher, &data).is_err());
+        let _ = describe_ndarray(&data);
+    }
+}
+
+#[test]
+fn test_openpgp() {
+    let key = SecretKey::generate(SystemTime::UNIX_EPOCH);
+    let certificate = key.certificate().to_bytes();
+    let message = openpgp::encrypt(
+        &[key.certificate().clone()],
+        &LiteralData::new(b"", SystemTime::UNIX_EPOCH, b"secret"),
+    );
+
+    for data in mutations(&certificate) {
+        let _ = Certificate::from_bytes(&data);
+    }
+
+    for data in mutations(&message) {
+        assert!(openpgp::decrypt(&key, &data).is_err());
+    }
+}
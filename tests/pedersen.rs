@@ -0,0 +1,95 @@
+use raycrypt::ecc::pedersen::{self, Commitment, InvalidOpening};
+use raycrypt::ecc::ristretto::RistrettoPoint;
+use raycrypt::ecc::scalar::Scalar;
+use raycrypt::Error;
+
+#[test]
+fn test_commit_and_verify() {
+    let value = Scalar::from_u64(42);
+    let blinding = Scalar::random();
+    let commitment = pedersen::commit(&value, &blinding);
+
+    assert_eq!(pedersen::verify(&commitment, &value, &blinding), Ok(()));
+    assert_eq!(
+        pedersen::verify(&commitment, &Scalar::from_u64(43), &blinding),
+        Err(InvalidOpening)
+    );
+    assert_eq!(
+        pedersen::verify(&commitment, &value, &Scalar::random()),
+        Err(InvalidOpening)
+    );
+
+    // vG + rH
+    let expected =
+        RistrettoPoint::mul_base(&value).add(&pedersen::blinding_generator().mul(&blinding));
+    assert_eq!(*commitment.as_point(), expected);
+
+    let decoded = Commitment::from_bytes(&commitment.to_bytes()).unwrap();
+    assert_eq!(decoded, commitment);
+    assert_eq!(Error::from(InvalidOpening), Error::InvalidSignature);
+}
+
+#[test]
+fn test_hiding() {
+    // the same value under different blindings gives unrelated commitments
+    let value = Scalar::from_u64(1);
+
+    assert_ne!(
+        pedersen::commit(&value, &Scalar::random()),
+        pedersen::commit(&value, &Scalar::random())
+    );
+}
+
+#[test]
+fn test_homomorphism() {
+    let (a, b) = (Scalar::from_u64(1000), Scalar::from_u64(234));
+    let (r_a, r_b) = (Scalar::random(), Scalar::random());
+
+    let sum = pedersen::commit(&a, &r_a).add(&pedersen::commit(&b, &r_b));
+    assert_eq!(pedersen::verify(&sum, &(a + b), &(r_a + r_b)), Ok(()));
+
+    let difference = pedersen::commit(&a, &r_a).sub(&pedersen::commit(&b, &r_b));
+    assert_eq!(
+        pedersen::verify(&difference, &(a - b), &(r_a - r_b)),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_vector_commitments() {
+    let values: Vec<Scalar> = (1..=4).map(Scalar::from_u64).collect();
+    let blinding = Scalar::random();
+    let commitment = pedersen::commit_vector(&values, &blinding);
+
+    assert_eq!(
+        pedersen::verify_vector(&commitment, &values, &blinding),
+        Ok(())
+    );
+
+    // order matters, and so does every entry
+    let mut swapped = values.clone();
+    swapped.swap(0, 1);
+    assert_eq!(
+        pedersen::verify_vector(&commitment, &swapped, &blinding),
+        Err(InvalidOpening)
+    );
+    assert_eq!(
+        pedersen::verify_vector(&commitment, &values[..3], &blinding),
+        Err(InvalidOpening)
+    );
+
+    // a one-entry vector is an ordinary commitment
+    assert_eq!(
+        pedersen::commit_vector(&values[..1], &blinding),
+        pedersen::commit(&values[0], &blinding)
+    );
+
+    // the generators are distinct
+    let generators: Vec<[u8; 32]> = (0..8)
+        .map(|i| pedersen::value_generator(i).to_bytes())
+        .chain([pedersen::blinding_generator().to_bytes()])
+        .collect();
+    for (i, generator) in generators.iter().enumerate() {
+        assert!(!generators[i + 1..].contains(generator));
+    }
+}
@@ -0,0 +1,136 @@
+use raycrypt::kdfs::argon2::{Argon2, Argon2Error, Variant};
+use raycrypt::kdfs::phc::{needs_rehash, PasswordHash, PhcError, MAX_MEMORY_COST, MAX_PARALLELISM};
+
+// generated with the Python cryptography package's derive_phc_encoded
+const VECTORS: [(&str, &[u8]); 3] = [
+    (
+        "$argon2id$v=19$m=64,t=2,p=2$c2FsdHNhbHRzYWx0c2FsdA$MTk3uF0ASN8bPjzWOEqSyxwROScUuU0+ShGyQuHn3pU",
+        b"password",
+    ),
+    (
+        "$argon2i$v=19$m=32,t=1,p=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        b"hunter2",
+    ),
+    (
+        "$argon2d$v=19$m=32,t=1,p=1$CAkKCwwNDg8QERIT$wSfVcJQH8N8GVIUKd7q/ZBWWv9k",
+        b"hunter2",
+    ),
+];
+
+#[test]
+fn test_vectors() {
+    for (phc, password) in VECTORS {
+        let hash = PasswordHash::parse(phc).unwrap();
+
+        assert!(hash.verify(password));
+        assert!(!hash.verify(b"wrong"));
+        assert_eq!(hash.to_string(), phc);
+    }
+
+    let hash = PasswordHash::parse(VECTORS[0].0).unwrap();
+    assert_eq!(
+        hash.params(),
+        Argon2::new(Variant::Argon2id, 64, 2, 2).unwrap()
+    );
+    assert_eq!(hash.salt(), b"saltsaltsaltsalt");
+    assert_eq!(hash.hash().len(), 32);
+}
+
+#[test]
+fn test_generate() {
+    let params = Argon2::new(Variant::Argon2id, 64, 1, 1).unwrap();
+    let hash = PasswordHash::generate(params, b"password").unwrap();
+    let parsed = PasswordHash::parse(&hash.to_string()).unwrap();
+
+    assert_eq!(parsed, hash);
+    assert!(parsed.verify(b"password"));
+    assert_ne!(PasswordHash::generate(params, b"password").unwrap(), hash);
+}
+
+#[test]
+fn test_malformed() {
+    let phc = VECTORS[1].0;
+
+    for bad in [
+        "",
+        "argon2i$v=19$m=32,t=1,p=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        "$argon2i$v=19$m=32,t=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        "$argon2i$v=19$t=1,m=32,p=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        "$argon2i$v=19$m=032,t=1,p=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        "$argon2i$v=19$m=+32,t=1,p=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        "$argon2i$v=19$m=32,t=1,p=1$CAkKCwwNDg8QERIT=$W2zbcBvECpy0Rz0Lj1R1b/LFiYo",
+        "$argon2i$v=19$m=32,t=1,p=1$CAkKCwwNDg8QERIT$W2zbcBvECpy0Rz0Lj1R1b/LFiYo$",
+    ] {
+        assert_eq!(
+            PasswordHash::parse(bad),
+            Err(PhcError::Malformed),
+            "{}",
+            bad
+        );
+    }
+
+    assert_eq!(
+        PasswordHash::parse(&phc.replace("argon2i", "scrypt")),
+        Err(PhcError::UnsupportedAlgorithm)
+    );
+    assert_eq!(
+        PasswordHash::parse(&phc.replace("v=19", "v=16")),
+        Err(PhcError::UnsupportedVersion)
+    );
+    assert_eq!(
+        PasswordHash::parse(&phc.replace("t=1", "t=0")),
+        Err(PhcError::InvalidParameters(Argon2Error::InvalidTimeCost))
+    );
+    assert_eq!(
+        PasswordHash::parse(&phc.replace("CAkKCwwNDg8QERIT", "CAkKCwwN")),
+        Err(PhcError::InvalidParameters(Argon2Error::InvalidSaltLength))
+    );
+}
+
+#[test]
+fn test_limits() {
+    let phc = VECTORS[1].0;
+    let limit = |m: u32, p: u32| {
+        PasswordHash::parse(&phc.replace("m=32,t=1,p=1", &format!("m={m},t=1,p={p}")))
+    };
+
+    assert!(limit(MAX_MEMORY_COST, MAX_PARALLELISM).is_ok());
+    assert_eq!(
+        limit(MAX_MEMORY_COST + 1, 1),
+        Err(PhcError::InvalidParameters(Argon2Error::InvalidMemoryCost))
+    );
+    assert_eq!(
+        limit(u32::MAX, 1),
+        Err(PhcError::InvalidParameters(Argon2Error::InvalidMemoryCost))
+    );
+    assert_eq!(
+        limit(MAX_MEMORY_COST, MAX_PARALLELISM + 1),
+        Err(PhcError::InvalidParameters(Argon2Error::InvalidParallelism))
+    );
+    assert_eq!(
+        limit(MAX_MEMORY_COST, (1 << 24) - 1),
+        Err(PhcError::InvalidParameters(Argon2Error::InvalidParallelism))
+    );
+}
+
+#[test]
+fn test_needs_rehash() {
+    let phc = VECTORS[0].0;
+    let current = Argon2::new(Variant::Argon2id, 64, 2, 2).unwrap();
+
+    assert!(!needs_rehash(phc, &current));
+    assert!(!needs_rehash(phc, &current.with_threads(1)));
+    assert!(needs_rehash(
+        phc,
+        &Argon2::new(Variant::Argon2id, 128, 2, 2).unwrap()
+    ));
+    assert!(needs_rehash(
+        phc,
+        &Argon2::new(Variant::Argon2id, 64, 3, 2).unwrap()
+    ));
+    assert!(needs_rehash(
+        phc,
+        &Argon2::new(Variant::Argon2i, 64, 2, 2).unwrap()
+    ));
+    assert!(needs_rehash("$2b$10$not-argon2", &current));
+}
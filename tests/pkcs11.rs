@@ -0,0 +1,22 @@
+#![cfg(all(feature = "pkcs11", unix))]
+
+use raycrypt::pkcs11::{Pkcs11Error, Token};
+
+#[test]
+fn test_missing_module() {
+    assert_eq!(
+        Token::open("/nonexistent/libpkcs11.so", 0, b"1234").unwrap_err(),
+        Pkcs11Error::Library
+    );
+}
+
+#[test]
+fn test_not_a_module() {
+    // a real library without C_GetFunctionList
+    for path in ["libc.so.6", "libm.so.6"] {
+        assert_eq!(
+            Token::open(path, 0, b"1234").unwrap_err(),
+            Pkcs11Error::Library
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use raycrypt::ecc::points::{EdwardsPoint, MontgomeryPoint, PointError};
+use raycrypt::ecc::scalar::Scalar;
+use raycrypt::ecc::x25519::Keypair;
+
+fn bytes(encoded: &str) -> [u8; 32] {
+    hex::decode(encoded).unwrap().try_into().unwrap()
+}
+
+// l, the order of the prime-order subgroup
+const ORDER: &str = "edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010";
+
+// a point of order 8
+const TORSION: &str = "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a";
+
+#[test]
+fn test_edwards_decoding() {
+    let identity = bytes("0100000000000000000000000000000000000000000000000000000000000000");
+    assert!(EdwardsPoint::from_bytes(&identity).unwrap().is_identity());
+
+    // y = p + 1, and the identity with the sign bit set on x = 0
+    let y_overflow = bytes("eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f");
+    let mut negative_zero = identity;
+    negative_zero[31] |= 0x80;
+
+    for encoding in [y_overflow, negative_zero] {
+        assert_eq!(
+            EdwardsPoint::from_bytes(&encoding),
+            Err(PointError::NonCanonical)
+        );
+        assert_eq!(
+            EdwardsPoint::from_bytes_noncanonical(&encoding),
+            Ok(EdwardsPoint::identity())
+        );
+    }
+
+    // no x satisfies the curve equation for y = 2
+    let mut off_curve = [0u8; 32];
+    off_curve[0] = 2;
+    assert_eq!(
+        EdwardsPoint::from_bytes(&off_curve),
+        Err(PointError::NotOnCurve)
+    );
+    assert_eq!(
+        EdwardsPoint::from_bytes_noncanonical(&off_curve),
+        Err(PointError::NotOnCurve)
+    );
+
+    let basepoint = EdwardsPoint::basepoint();
+    assert_eq!(
+        EdwardsPoint::from_bytes(&basepoint.to_bytes()),
+        Ok(basepoint)
+    );
+}
+
+#[test]
+fn test_edwards_predicates() {
+    let basepoint = EdwardsPoint::basepoint();
+    let torsion = EdwardsPoint::from_bytes(&bytes(TORSION)).unwrap();
+    let mixed = basepoint.add(&torsion);
+
+    assert!(basepoint.is_torsion_free());
+    assert!(!basepoint.is_small_order());
+
+    assert!(torsion.is_small_order());
+    assert!(!torsion.is_torsion_free());
+    assert!(torsion.mul_by_cofactor().is_identity());
+
+    assert!(!mixed.is_small_order());
+    assert!(!mixed.is_torsion_free());
+    assert!(mixed.mul_by_cofactor().is_torsion_free());
+
+    assert!(EdwardsPoint::identity().is_small_order());
+    assert!(EdwardsPoint::identity().is_torsion_free());
+}
+
+#[test]
+fn test_edwards_arithmetic() {
+    let basepoint = EdwardsPoint::basepoint();
+    let two = Scalar::from_u64(2);
+
+    assert_eq!(basepoint.double(), basepoint.add(&basepoint));
+    assert_eq!(EdwardsPoint::mul_base(&two), basepoint.double());
+    assert_eq!(basepoint.double().sub(&basepoint), basepoint);
+    assert!(basepoint.mul(&-Scalar::ONE).add(&basepoint).is_identity());
+
+    // l + 2 reduces to 2
+    let mut order_plus_two = bytes(ORDER);
+    order_plus_two[0] += 2;
+    let reduced = Scalar::from_bytes_mod_order(&order_plus_two);
+    assert_eq!(basepoint.mul(&reduced), basepoint.double());
+}
+
+#[test]
+fn test_montgomery_decoding() {
+    let mut nine = [0u8; 32];
+    nine[0] = 9;
+    let basepoint = MontgomeryPoint::from_bytes(&nine).unwrap();
+
+    assert!(basepoint.is_on_curve());
+    assert_eq!(EdwardsPoint::basepoint().to_montgomery(), basepoint);
+    assert_eq!(basepoint.to_edwards(false), Some(EdwardsPoint::basepoint()));
+
+    // u = 2 is on the twist
+    let mut two = [0u8; 32];
+    two[0] = 2;
+    assert_eq!(
+        MontgomeryPoint::from_bytes(&two),
+        Err(PointError::NotOnCurve)
+    );
+    assert!(!MontgomeryPoint::from_bytes_noncanonical(&two).is_on_curve());
+    assert_eq!(
+        MontgomeryPoint::from_bytes_noncanonical(&two).to_edwards(false),
+        None
+    );
+
+    // u = p + 9, and u = 9 with the top bit set, both read as 9 by X25519
+    let overflow = bytes("f6ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f");
+    let mut top_bit = nine;
+    top_bit[31] |= 0x80;
+
+    for encoding in [overflow, top_bit] {
+        assert_eq!(
+            MontgomeryPoint::from_bytes(&encoding),
+            Err(PointError::NonCanonical)
+        );
+        assert_eq!(
+            MontgomeryPoint::from_bytes_noncanonical(&encoding),
+            basepoint
+        );
+    }
+}
+
+#[test]
+fn test_montgomery_predicates() {
+    let point = MontgomeryPoint::from_bytes(&Keypair::generate().public()).unwrap();
+    assert!(point.is_torsion_free());
+    assert!(!point.is_small_order());
+
+    let torsion = EdwardsPoint::from_bytes(&bytes(TORSION)).unwrap();
+    let mixed = EdwardsPoint::basepoint().add(&torsion).to_montgomery();
+    assert!(mixed.is_on_curve());
+    assert!(!mixed.is_torsion_free());
+
+    // the small-order points on the curve, and u = -1 on the twist
+    for encoding in [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "e0eb7a7c3b41b8ae1656e3faf19fc46ada098deb9c32b1fd866205165f49b800",
+        "5f9c95bca3508c24b1d0b1559c83ef5b04445cc4581c8e86d8224eddd09f1157",
+    ] {
+        let point = MontgomeryPoint::from_bytes(&bytes(encoding)).unwrap();
+        assert!(point.is_small_order());
+        assert!(point.to_edwards(false).unwrap().is_small_order());
+    }
+
+    let minus_one = bytes("ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f");
+    let minus_one = MontgomeryPoint::from_bytes_noncanonical(&minus_one);
+    assert!(minus_one.is_small_order());
+    assert!(!minus_one.is_on_curve());
+    assert!(!minus_one.is_torsion_free());
+
+    // u = p reduces to 0
+    let p = bytes("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f");
+    assert!(MontgomeryPoint::from_bytes_noncanonical(&p).is_small_order());
+}
@@ -1,4 +1,5 @@
-use raycrypt::macs::poly1305::Poly1305;
+use raycrypt::ciphers::chacha::ChaChaError;
+use raycrypt::macs::poly1305::{poly1305_key_gen, Poly1305};
 
 #[test]
 fn test_poly1305_1() {
@@ -102,3 +103,23 @@ fn poly1305_test_4() {
 
     assert_eq!(p2.verify(&wrap_mac), true);
 }
+
+#[test]
+fn test_poly1305_key_gen() {
+    // RFC 8439, section 2.6.2
+    let key: Vec<u8> = (0x80..0xa0).collect();
+    let nonce = [0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7];
+
+    assert_eq!(
+        hex::encode(poly1305_key_gen(&key, &nonce).unwrap()),
+        "8ad5a08b905f81cc815040274ab29471a833b637e3fd0da508dbb8e2fdd1a646"
+    );
+    assert_eq!(
+        poly1305_key_gen(&key[1..], &nonce),
+        Err(ChaChaError::InvalidKeyLength)
+    );
+    assert_eq!(
+        poly1305_key_gen(&key, &nonce[1..]),
+        Err(ChaChaError::InvalidNonceLength)
+    );
+}
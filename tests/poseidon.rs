@@ -0,0 +1,54 @@
+use raycrypt::ecc::scalar::Scalar;
+use raycrypt::hashes::poseidon;
+
+fn scalar(encoded: &str) -> Scalar {
+    let bytes: [u8; 32] = hex::decode(encoded).unwrap().try_into().unwrap();
+    Scalar::from_canonical_bytes(&bytes).unwrap()
+}
+
+// generated with an independent Python implementation of the same instance
+#[test]
+fn test_permutation() {
+    let mut state = [Scalar::ZERO, Scalar::ONE, Scalar::from_u64(2)];
+    poseidon::permute(&mut state);
+
+    assert_eq!(
+        state,
+        [
+            scalar("edff2e49954382994a03da7271e9d617fe05f3e629d743e7931b726e74051b02"),
+            scalar("e332121ea07ec3f6a8b70c1d7fe8230de9b8dd8ffc15bbee679e879ccca38b06"),
+            scalar("2f0fda325622f2ffae2d863b2bcb3034c41be62569d23eb9d535ef72b46d930d"),
+        ]
+    );
+}
+
+#[test]
+fn test_hash() {
+    let inputs: Vec<Scalar> = (1..=3).map(Scalar::from_u64).collect();
+
+    for (length, expected) in [
+        "e1b7c65c37db8b07f30701cb573263659bbb1416b45d8a42ae678ac54a7cce06",
+        "7ed751c3a3d248e6c71233f562e8828fe17a959d46a3f346cb95bfe66544b10f",
+        "0ee793432d310f6ab28801387904f6ed1bafc103c9726c46f8d5cc110b9bb804",
+        "04d611657aa8184590a3b9c11659e66bea19514501a3454fe670aa2689cf5b0c",
+    ]
+    .iter()
+    .enumerate()
+    {
+        assert_eq!(poseidon::hash(&inputs[..length]), scalar(expected));
+    }
+}
+
+#[test]
+fn test_length_is_bound() {
+    // a trailing zero is not padding
+    assert_eq!(
+        poseidon::hash(&[Scalar::ZERO]),
+        scalar("03e6a012cdb9884171d8779b81084c4c659e9fba14625c577c79ad491530600b")
+    );
+    assert_ne!(poseidon::hash(&[Scalar::ZERO]), poseidon::hash(&[]));
+    assert_ne!(
+        poseidon::hash(&[Scalar::ONE]),
+        poseidon::hash(&[Scalar::ONE, Scalar::ZERO])
+    );
+}
@@ -0,0 +1,113 @@
+use raycrypt::pre::{self, PreError, PublicKey, ReEncryptionKey, SecretKey};
+
+#[test]
+fn test_direct_roundtrip() {
+    let alice = SecretKey::generate();
+    let ct = pre::encrypt(&alice.public_key(), b"hello", b"ad");
+
+    assert_eq!(ct.len(), 96 + 24 + 5 + 16);
+    assert_eq!(pre::decrypt(&alice, &ct, b"ad").unwrap(), b"hello");
+    assert_eq!(
+        pre::decrypt(&alice, &ct, b"other"),
+        Err(PreError::InvalidMac)
+    );
+    assert_eq!(
+        pre::decrypt(&SecretKey::generate(), &ct, b"ad"),
+        Err(PreError::InvalidMac)
+    );
+}
+
+#[test]
+fn test_reencryption() {
+    let alice = SecretKey::generate();
+    let bob = SecretKey::generate();
+    let rekey = ReEncryptionKey::generate(&alice, &bob.public_key());
+
+    for msg in [&b""[..], b"hello", &[42u8; 1000]] {
+        let ct = pre::encrypt(&alice.public_key(), msg, b"ad");
+        let transformed = pre::reencrypt(&rekey, &ct).unwrap();
+
+        // the proxy only adds a capsule fragment
+        assert_eq!(transformed[96..], ct);
+        assert_eq!(
+            pre::decrypt_reencrypted(&bob, &transformed, b"ad").unwrap(),
+            msg
+        );
+
+        // nobody else can use the fragment, and Bob cannot read Alice's original
+        assert_eq!(
+            pre::decrypt_reencrypted(&SecretKey::generate(), &transformed, b"ad"),
+            Err(PreError::InvalidMac)
+        );
+        assert_eq!(pre::decrypt(&bob, &ct, b"ad"), Err(PreError::InvalidMac));
+    }
+
+    // a key for a different delegator yields garbage rather than Alice's plaintext
+    let carol = SecretKey::generate();
+    let wrong = ReEncryptionKey::generate(&carol, &bob.public_key());
+    let ct = pre::encrypt(&alice.public_key(), b"hello", b"");
+    let transformed = pre::reencrypt(&wrong, &ct).unwrap();
+    assert_eq!(
+        pre::decrypt_reencrypted(&bob, &transformed, b""),
+        Err(PreError::InvalidMac)
+    );
+}
+
+#[test]
+fn test_capsule_checks() {
+    let alice = SecretKey::generate();
+    let bob = SecretKey::generate();
+    let rekey = ReEncryptionKey::generate(&alice, &bob.public_key());
+    let ct = pre::encrypt(&alice.public_key(), b"hello", b"");
+
+    // changing s breaks the capsule proof, so the proxy refuses it
+    let mut tampered = ct.clone();
+    tampered[64] ^= 1;
+    assert_eq!(
+        pre::reencrypt(&rekey, &tampered),
+        Err(PreError::InvalidCapsule)
+    );
+    assert_eq!(
+        pre::decrypt(&alice, &tampered, b""),
+        Err(PreError::InvalidCapsule)
+    );
+
+    assert_eq!(pre::reencrypt(&rekey, &ct[..100]), Err(PreError::Malformed));
+    assert_eq!(
+        pre::decrypt(&alice, &ct[..100], b""),
+        Err(PreError::Malformed)
+    );
+
+    let transformed = pre::reencrypt(&rekey, &ct).unwrap();
+    let mut fragment = transformed.clone();
+    fragment[0] ^= 1;
+    assert!(pre::decrypt_reencrypted(&bob, &fragment, b"").is_err());
+}
+
+#[test]
+fn test_keys() {
+    let alice = SecretKey::generate();
+    let public = alice.public_key();
+
+    assert_eq!(PublicKey::from_bytes(&public.to_bytes()), Ok(public));
+    assert_eq!(
+        SecretKey::from_bytes(&alice.to_bytes())
+            .unwrap()
+            .public_key(),
+        public
+    );
+    assert!(SecretKey::from_bytes(&[0u8; 32]).is_none());
+
+    // the identity and a point of order 8 are both rejected
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    assert_eq!(PublicKey::from_bytes(&identity), Err(PreError::InvalidKey));
+
+    let torsion: [u8; 32] = [
+        0xc7, 0x17, 0x6a, 0x70, 0x3d, 0x4d, 0xd8, 0x4f, 0xba, 0x3c, 0x0b, 0x76, 0x0d, 0x10, 0x67,
+        0x0f, 0x2a, 0x20, 0x53, 0xfa, 0x2c, 0x39, 0xcc, 0xc6, 0x4e, 0xc7, 0xfd, 0x77, 0x92, 0xac,
+        0x03, 0x7a,
+    ];
+    assert_eq!(PublicKey::from_bytes(&torsion), Err(PreError::InvalidKey));
+    assert_eq!(PublicKey::from_bytes(&[1u8; 31]), Err(PreError::InvalidKey));
+}
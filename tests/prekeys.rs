@@ -0,0 +1,166 @@
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::ecc::x25519;
+use raycrypt::protocols::prekeys::{PrekeyDirectory, PrekeyError, PrekeyStore};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const WEEK: Duration = Duration::from_secs(7 * 24 * 3600);
+const DAY: Duration = Duration::from_secs(24 * 3600);
+
+fn start() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+}
+
+fn store() -> PrekeyStore {
+    PrekeyStore::new(SigningKey::new(&[1u8; 32]).unwrap(), WEEK, start())
+}
+
+#[test]
+fn test_bundle_exchange() {
+    let mut bob = store();
+    let mut directory = PrekeyDirectory::new(10);
+
+    directory
+        .publish_signed_prekey(bob.identity(), bob.signed_prekey())
+        .unwrap();
+    let prekeys = bob.generate_one_time_prekeys(3);
+    directory
+        .upload_one_time_prekeys(&bob.identity(), &prekeys)
+        .unwrap();
+
+    // the initiator checks the signed prekey and uses both public keys
+    let bundle = directory.fetch_bundle(&bob.identity()).unwrap();
+    bundle.signed_prekey.verify(&bundle.identity).unwrap();
+    let one_time = bundle.one_time_prekey.unwrap();
+    assert_eq!(one_time, prekeys[0]);
+
+    let alice = x25519::keygen(None);
+    let shared = alice.exchange(bundle.signed_prekey.public);
+    assert_eq!(
+        bob.signed_prekey_private(bundle.signed_prekey.id)
+            .unwrap()
+            .exchange(alice.public_key()),
+        shared
+    );
+
+    let private = bob.take_one_time_prekey(one_time.id).unwrap();
+    assert_eq!(
+        private.exchange(alice.public_key()),
+        alice.exchange(one_time.public)
+    );
+
+    // each one-time prekey is used once, on both sides
+    assert!(bob.take_one_time_prekey(one_time.id).is_none());
+    assert_eq!(bob.one_time_prekeys_remaining(), 2);
+    assert_eq!(
+        directory
+            .fetch_bundle(&bob.identity())
+            .unwrap()
+            .one_time_prekey,
+        Some(prekeys[1])
+    );
+    assert_eq!(
+        directory
+            .fetch_bundle(&bob.identity())
+            .unwrap()
+            .one_time_prekey,
+        Some(prekeys[2])
+    );
+    assert_eq!(
+        directory
+            .fetch_bundle(&bob.identity())
+            .unwrap()
+            .one_time_prekey,
+        None
+    );
+}
+
+#[test]
+fn test_replenishing() {
+    let mut bob = store();
+    let mut directory = PrekeyDirectory::new(5);
+    directory
+        .publish_signed_prekey(bob.identity(), bob.signed_prekey())
+        .unwrap();
+
+    assert_eq!(directory.replenish_count(&bob.identity(), 20), 20);
+    let prekeys = bob.generate_one_time_prekeys(20);
+    directory
+        .upload_one_time_prekeys(&bob.identity(), &prekeys)
+        .unwrap();
+
+    for _ in 0..15 {
+        directory.fetch_bundle(&bob.identity()).unwrap();
+        assert_eq!(directory.replenish_count(&bob.identity(), 20), 0);
+    }
+
+    directory.fetch_bundle(&bob.identity()).unwrap();
+    assert_eq!(directory.one_time_prekeys_remaining(&bob.identity()), 4);
+    assert_eq!(directory.replenish_count(&bob.identity(), 20), 16);
+
+    // ids are never reused, so uploads cannot collide
+    assert_eq!(
+        directory.upload_one_time_prekeys(&bob.identity(), &prekeys[19..]),
+        Err(PrekeyError::DuplicateId)
+    );
+    let more = bob.generate_one_time_prekeys(16);
+    directory
+        .upload_one_time_prekeys(&bob.identity(), &more)
+        .unwrap();
+    assert_eq!(directory.one_time_prekeys_remaining(&bob.identity()), 20);
+}
+
+#[test]
+fn test_rotation() {
+    let mut bob = store();
+    let first = bob.signed_prekey();
+
+    assert_eq!(bob.rotation_due(), start() + WEEK);
+    assert!(!bob.needs_rotation(start() + WEEK - DAY));
+    assert!(bob.needs_rotation(start() + WEEK));
+
+    let second = bob.rotate_signed_prekey(start() + WEEK);
+    assert_ne!(second.id, first.id);
+    assert_eq!(bob.signed_prekey(), second);
+    assert_eq!(bob.rotation_due(), start() + 2 * WEEK);
+
+    // the old signed prekey stays usable for the grace period after it was replaced
+    bob.prune(start() + WEEK + DAY, 2 * DAY);
+    assert!(bob.signed_prekey_private(first.id).is_some());
+
+    bob.prune(start() + WEEK + 2 * DAY, 2 * DAY);
+    assert!(bob.signed_prekey_private(first.id).is_none());
+    assert!(bob.signed_prekey_private(second.id).is_some());
+
+    // the current one is never pruned
+    bob.prune(start() + 10 * WEEK, DAY);
+    assert_eq!(bob.signed_prekey(), second);
+}
+
+#[test]
+fn test_directory_checks() {
+    let bob = store();
+    let mallory = PrekeyStore::new(SigningKey::new(&[2u8; 32]).unwrap(), WEEK, start());
+    let mut directory = PrekeyDirectory::new(5);
+
+    assert_eq!(
+        directory.publish_signed_prekey(bob.identity(), mallory.signed_prekey()),
+        Err(PrekeyError::InvalidSignature)
+    );
+
+    let mut tampered = bob.signed_prekey();
+    tampered.public[0] ^= 1;
+    assert_eq!(
+        directory.publish_signed_prekey(bob.identity(), tampered),
+        Err(PrekeyError::InvalidSignature)
+    );
+
+    assert_eq!(
+        directory.fetch_bundle(&bob.identity()),
+        Err(PrekeyError::UnknownIdentity)
+    );
+    assert_eq!(
+        directory.upload_one_time_prekeys(&bob.identity(), &[]),
+        Err(PrekeyError::UnknownIdentity)
+    );
+    assert_eq!(directory.one_time_prekeys_remaining(&bob.identity()), 0);
+}
@@ -0,0 +1,91 @@
+use hex::decode;
+use raycrypt::protocols::quic::*;
+
+// RFC 9001, appendix A.1
+#[test]
+fn test_initial_secrets() {
+    let secrets = initial_secrets(&decode("8394c8f03e515708").unwrap());
+
+    assert_eq!(
+        secrets.client,
+        decode("c00cf151ca5be075ed0ebfb5c80323c42d6b7db67881289af4008f1f6c357aea").unwrap()
+    );
+    assert_eq!(
+        secrets.server,
+        decode("3c199828fd139efd216c155ad844cc81fb82fa8d7446fa7d78be803acdda951b").unwrap()
+    );
+
+    let keys = packet_keys(&secrets.client, 16).unwrap();
+
+    assert_eq!(
+        keys.key,
+        decode("1f369613dd76d5467730efcbe3b1a22d").unwrap()
+    );
+    assert_eq!(keys.iv, decode("fa044b2f42a3fd3b46fb255c").unwrap());
+    assert_eq!(keys.hp, decode("9f50449e04a0e810283a1e9933adedd2").unwrap());
+}
+
+// RFC 9001, appendix A.5
+#[test]
+fn test_chacha20_header_protection() {
+    let secret =
+        decode("9ac312a7f877468ebe69422748ad00a15443f18203a07d6060f688f30f21632b").unwrap();
+    let keys = packet_keys(&secret, 32).unwrap();
+
+    assert_eq!(
+        keys.hp,
+        decode("25a282b9e82f06f21f488917a4fc8f1b73573685608597d0efcb076b0ab7a7a4").unwrap()
+    );
+
+    let sample = decode("5e5cd55c41f69080575d7999c25a5bfb").unwrap();
+    let mask = chacha20_header_mask(&keys.hp, &sample).unwrap();
+
+    assert_eq!(mask.to_vec(), decode("aefefe7d03").unwrap());
+
+    let mut header = decode("4200bff4").unwrap();
+    protect_header(&mut header, 1, &mask).unwrap();
+    assert_eq!(header, decode("4cfe4189").unwrap());
+
+    assert_eq!(unprotect_header(&mut header, 1, &mask), Ok(3));
+    assert_eq!(header, decode("4200bff4").unwrap());
+}
+
+#[test]
+fn test_truncated_packets() {
+    let mask = [0xae, 0xfe, 0xfe, 0x7d, 0x03];
+
+    assert_eq!(
+        chacha20_header_mask(&[0u8; 32], &[0u8; SAMPLE_LENGTH - 1]),
+        Err(QuicError::Malformed)
+    );
+    assert_eq!(
+        chacha20_header_mask(&[0u8; 16], &[0u8; SAMPLE_LENGTH]),
+        Err(QuicError::InvalidKeyLength)
+    );
+    assert!(packet_keys(&[0u8; 32], 24).is_err());
+
+    assert_eq!(protect_header(&mut [], 1, &mask), Err(QuicError::Malformed));
+    assert_eq!(
+        unprotect_header(&mut [], 1, &mask),
+        Err(QuicError::Malformed)
+    );
+
+    // a three-byte packet number that runs past the end of the header
+    let mut header = decode("4cfe41").unwrap();
+    assert_eq!(
+        unprotect_header(&mut header, 1, &mask),
+        Err(QuicError::Malformed)
+    );
+    assert_eq!(header, decode("4cfe41").unwrap());
+
+    let mut header = decode("4200bf").unwrap();
+    assert_eq!(
+        protect_header(&mut header, 1, &mask),
+        Err(QuicError::Malformed)
+    );
+    assert_eq!(
+        protect_header(&mut header, usize::MAX, &mask),
+        Err(QuicError::Malformed)
+    );
+    assert_eq!(header, decode("4200bf").unwrap());
+}
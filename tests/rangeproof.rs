@@ -0,0 +1,81 @@
+use raycrypt::ecc::pedersen;
+use raycrypt::ecc::rangeproof::{self, RangeProof, RangeProofError, PROOF_SIZE};
+use raycrypt::ecc::scalar::Scalar;
+
+#[test]
+fn test_prove_and_verify() {
+    for value in [0, 1, 1 << 32, u64::MAX] {
+        let blinding = Scalar::random();
+        let (commitment, proof) = rangeproof::prove(value, &blinding);
+
+        assert_eq!(rangeproof::verify(&commitment, &proof), Ok(()));
+        assert_eq!(
+            commitment,
+            pedersen::commit(&Scalar::from_u64(value), &blinding)
+        );
+    }
+}
+
+#[test]
+fn test_encoding() {
+    let (commitment, proof) = rangeproof::prove(1234, &Scalar::random());
+    let bytes = proof.to_bytes();
+
+    assert_eq!(bytes.len(), PROOF_SIZE);
+    assert_eq!(PROOF_SIZE, 672);
+    assert_eq!(RangeProof::from_bytes(&bytes), Ok(proof));
+    assert_eq!(
+        rangeproof::verify(&commitment, &RangeProof::from_bytes(&bytes).unwrap()),
+        Ok(())
+    );
+
+    assert_eq!(
+        RangeProof::from_bytes(&bytes[..PROOF_SIZE - 1]),
+        Err(RangeProofError::Malformed)
+    );
+
+    // a non-canonical final scalar
+    let mut non_canonical = bytes.clone();
+    non_canonical[PROOF_SIZE - 32..].fill(0xff);
+    assert_eq!(
+        RangeProof::from_bytes(&non_canonical),
+        Err(RangeProofError::Malformed)
+    );
+}
+
+#[test]
+fn test_rejections() {
+    let blinding = Scalar::random();
+    let (commitment, proof) = rangeproof::prove(42, &blinding);
+
+    // a proof does not carry over to another commitment, even to the same value
+    let other = pedersen::commit(&Scalar::from_u64(42), &Scalar::random());
+    assert_eq!(
+        rangeproof::verify(&other, &proof),
+        Err(RangeProofError::Invalid)
+    );
+
+    // nor does a commitment to a value outside the range verify with a proof for one inside:
+    // -1 is 2^252 + ..., far above 2^64
+    let negative = pedersen::commit(&-Scalar::ONE, &blinding);
+    assert_eq!(
+        rangeproof::verify(&negative, &proof),
+        Err(RangeProofError::Invalid)
+    );
+
+    // and every byte of the proof matters
+    let bytes = proof.to_bytes();
+    for position in [0, 130, 200, 300, PROOF_SIZE - 1] {
+        let mut tampered = bytes.clone();
+        tampered[position] ^= 1;
+
+        if let Ok(tampered) = RangeProof::from_bytes(&tampered) {
+            assert_eq!(
+                rangeproof::verify(&commitment, &tampered),
+                Err(RangeProofError::Invalid),
+                "{}",
+                position
+            );
+        }
+    }
+}
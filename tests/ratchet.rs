@@ -0,0 +1,88 @@
+use raycrypt::aeads::ChaCha20Poly1305;
+use raycrypt::kdfs::ratchet::KeyRatchet;
+
+const SEED: [u8; 32] = [4u8; 32];
+
+#[test]
+fn test_next_key() {
+    let mut ratchet = KeyRatchet::new(&SEED);
+    let mut keys = Vec::new();
+
+    for i in 0..5 {
+        let (index, key) = ratchet.next_key();
+        assert_eq!(index, i);
+        assert!(!keys.contains(key.as_bytes()));
+        keys.push(*key.as_bytes());
+    }
+    assert_eq!(ratchet.index(), 5);
+
+    // the same seed derives the same chain
+    let mut auditor = KeyRatchet::new(&SEED);
+    assert_eq!(auditor.key_at(3).unwrap().as_bytes(), &keys[3]);
+    assert_eq!(auditor.index(), 4);
+    assert_eq!(auditor.next_key().1.as_bytes(), &keys[4]);
+
+    // keys that were passed cannot be derived again
+    assert!(auditor.key_at(2).is_none());
+    assert!(auditor.key_at(4).is_none());
+}
+
+#[test]
+fn test_log_encryption() {
+    let mut writer = KeyRatchet::new(&SEED);
+    let mut log = Vec::new();
+
+    for record in ["boot", "login alice", "sudo", "logout alice"] {
+        let (index, key) = writer.next_key();
+        let nonce = [0u8; 12];
+        let ct = ChaCha20Poly1305::new(key.as_bytes()).encrypt(
+            record.as_bytes(),
+            &nonce,
+            &index.to_be_bytes(),
+        );
+        log.push((index, ct.to_vec()));
+    }
+
+    // a seized writer cannot read what it wrote
+    let mut seized = KeyRatchet::from_bytes(&writer.to_bytes()).unwrap();
+    for (index, ct) in &log {
+        assert!(seized.key_at(*index).is_none());
+        let (_, later) = KeyRatchet::from_bytes(&writer.to_bytes())
+            .unwrap()
+            .next_key();
+        assert!(ChaCha20Poly1305::new(later.as_bytes())
+            .decrypt(ct, &[0u8; 12], &index.to_be_bytes())
+            .is_err());
+    }
+
+    let mut auditor = KeyRatchet::new(&SEED);
+    for ((index, ct), record) in log
+        .iter()
+        .zip(["boot", "login alice", "sudo", "logout alice"])
+    {
+        let key = auditor.key_at(*index).unwrap();
+        let msg = ChaCha20Poly1305::new(key.as_bytes())
+            .decrypt(ct, &[0u8; 12], &index.to_be_bytes())
+            .unwrap();
+        assert_eq!(msg, record.as_bytes());
+    }
+}
+
+#[test]
+fn test_state() {
+    let mut ratchet = KeyRatchet::new(&SEED);
+    ratchet.next_key();
+    ratchet.next_key();
+
+    let state = ratchet.to_bytes();
+    assert_eq!(&state[..8], &2u64.to_be_bytes());
+
+    let mut restored = KeyRatchet::from_bytes(&state).unwrap();
+    assert_eq!(restored.index(), 2);
+    assert_eq!(
+        restored.next_key().1.as_bytes(),
+        ratchet.next_key().1.as_bytes()
+    );
+
+    assert!(KeyRatchet::from_bytes(&state[..39]).is_err());
+}
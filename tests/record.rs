@@ -0,0 +1,239 @@
+use raycrypt::aeads::ChaCha20Poly1305;
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::protocols::record::{NonceSequence, RecordError, RecordProtection};
+use raycrypt::protocols::tls13::{next_traffic_secret, traffic_keys};
+
+const SECRET: [u8; 32] = [7u8; 32];
+
+#[test]
+fn test_roundtrip() {
+    let mut sender = RecordProtection::new(&SECRET, 1 << 20);
+    let mut receiver = RecordProtection::new(&SECRET, 1 << 20);
+
+    for i in 0..4u64 {
+        let (sequence, ct) = sender.seal(b"hello", b"header");
+        assert_eq!(sequence, i);
+        assert_eq!(receiver.open(sequence, &ct, b"header").unwrap(), b"hello");
+    }
+
+    let (sequence, mut ct) = sender.seal(b"hello", b"header");
+    assert_eq!(
+        receiver.open(sequence, &ct, b"other"),
+        Err(RecordError::InvalidMac)
+    );
+
+    ct[0] ^= 1;
+    assert_eq!(
+        receiver.open(sequence, &ct, b"header"),
+        Err(RecordError::InvalidMac)
+    );
+}
+
+#[test]
+fn test_nonce() {
+    let (key, iv) = traffic_keys::<Sha256>(&SECRET, 32, 12).unwrap();
+    let mut records = RecordProtection::new(&SECRET, 1 << 20);
+
+    let mut nonce = iv.clone();
+    nonce[11] ^= 1;
+    assert_eq!(records.nonce(1).to_vec(), nonce);
+
+    records.seal(b"", b"");
+    let (_, ct) = records.seal(b"record", b"");
+
+    assert_eq!(
+        ct,
+        ChaCha20Poly1305::new(&key)
+            .encrypt(b"record", &nonce, b"")
+            .into_vec()
+    );
+}
+
+#[test]
+fn test_late_records() {
+    let mut sender = RecordProtection::new(&SECRET, 1 << 20);
+    let mut receiver = RecordProtection::new(&SECRET, 1 << 20);
+
+    let (first, first_ct) = sender.seal(b"first", b"");
+    let (_, _) = sender.seal(b"lost", b"");
+    let (third, third_ct) = sender.seal(b"third", b"");
+
+    assert_eq!(receiver.open(third, &third_ct, b"").unwrap(), b"third");
+    assert_eq!(receiver.open(first, &first_ct, b"").unwrap(), b"first");
+    assert_eq!(receiver.sequence(), 3);
+
+    assert_eq!(
+        receiver.open(first, &first_ct, b""),
+        Err(RecordError::SequenceReused)
+    );
+    assert_eq!(
+        receiver.open(third, &third_ct, b""),
+        Err(RecordError::SequenceReused)
+    );
+}
+
+#[test]
+fn test_rekey() {
+    let mut sender = RecordProtection::new(&SECRET, 2);
+    let mut receiver = RecordProtection::new(&SECRET, 2);
+
+    for _ in 0..2 {
+        let (sequence, ct) = sender.seal(b"epoch zero", b"");
+        receiver.open(sequence, &ct, b"").unwrap();
+    }
+
+    assert_eq!(sender.epoch(), 1);
+    assert_eq!(receiver.epoch(), 1);
+    assert_eq!(sender.sequence(), 0);
+
+    let (sequence, ct) = sender.seal(b"epoch one", b"");
+    assert_eq!(sequence, 0);
+
+    let (key, iv) =
+        traffic_keys::<Sha256>(&next_traffic_secret::<Sha256>(&SECRET), 32, 12).unwrap();
+    assert_eq!(
+        ChaCha20Poly1305::new(&key).decrypt(&ct, &iv, b"").unwrap(),
+        b"epoch one"
+    );
+    assert_eq!(receiver.open(sequence, &ct, b"").unwrap(), b"epoch one");
+
+    assert_eq!(
+        receiver.open(2, &ct, b""),
+        Err(RecordError::SequenceExhausted)
+    );
+}
+
+#[test]
+fn test_epochs() {
+    let mut sender = RecordProtection::new(&SECRET, 1 << 20);
+    let mut receiver = RecordProtection::new(&SECRET, 1 << 20);
+
+    let (epoch, sequence, old) = sender.seal_in_epoch(b"before", b"");
+    assert_eq!((epoch, sequence), (0, 0));
+
+    sender.update_keys();
+    let (epoch, sequence, ct) = sender.seal_in_epoch(b"after", b"");
+    assert_eq!((epoch, sequence), (1, 0));
+
+    // a forged record for the next epoch must not move the receiver
+    let mut forged = ct.clone();
+    forged[0] ^= 1;
+    assert_eq!(
+        receiver.open_in_epoch(1, 0, &forged, b""),
+        Err(RecordError::InvalidMac)
+    );
+    assert_eq!(receiver.epoch(), 0);
+
+    assert_eq!(
+        receiver.open_in_epoch(2, 0, &ct, b""),
+        Err(RecordError::UnknownEpoch)
+    );
+    assert_eq!(receiver.open_in_epoch(1, 0, &ct, b"").unwrap(), b"after");
+    assert_eq!(receiver.epoch(), 1);
+
+    assert_eq!(
+        receiver.open_in_epoch(0, 0, &old, b""),
+        Err(RecordError::StaleEpoch)
+    );
+
+    let (epoch, sequence, ct) = sender.seal_in_epoch(b"again", b"");
+    assert_eq!(
+        receiver.open_in_epoch(epoch, sequence, &ct, b"").unwrap(),
+        b"again"
+    );
+}
+
+#[test]
+fn test_reordered_epochs() {
+    let mut sender = RecordProtection::new(&SECRET, 1 << 20);
+    let mut receiver = RecordProtection::new(&SECRET, 1 << 20);
+
+    let records: Vec<_> = (0..4).map(|_| sender.seal_in_epoch(b"zero", b"")).collect();
+
+    // datagrams arriving in any order within an epoch are each opened once
+    for i in [3, 0, 2, 1] {
+        let (epoch, sequence, ct) = &records[i];
+        assert_eq!(
+            receiver.open_in_epoch(*epoch, *sequence, ct, b"").unwrap(),
+            b"zero"
+        );
+    }
+
+    for (epoch, sequence, ct) in &records {
+        assert_eq!(
+            receiver.open_in_epoch(*epoch, *sequence, ct, b""),
+            Err(RecordError::SequenceReused)
+        );
+    }
+
+    sender.update_keys();
+    let first = sender.seal_in_epoch(b"one", b"");
+    let second = sender.seal_in_epoch(b"one", b"");
+    let (epoch, sequence, ct) = sender.seal_in_epoch(b"one", b"");
+
+    // the next epoch starts with a fresh window, so its records reorder the same way
+    assert_eq!(
+        receiver.open_in_epoch(epoch, sequence, &ct, b"").unwrap(),
+        b"one"
+    );
+    assert_eq!(receiver.epoch(), 1);
+
+    for (epoch, sequence, ct) in [second, first] {
+        assert_eq!(
+            receiver.open_in_epoch(epoch, sequence, &ct, b"").unwrap(),
+            b"one"
+        );
+    }
+}
+
+#[test]
+fn test_nonce_sequence() {
+    let iv = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+    let mut sender = NonceSequence::new(&iv);
+    let mut receiver = NonceSequence::new(&iv);
+    let aead = ChaCha20Poly1305::new(&SECRET);
+
+    // RFC 7905: the 12-byte IV xor the left-padded sequence number
+    let expected = [
+        1,
+        2,
+        3,
+        4,
+        5 ^ 1,
+        6 ^ 2,
+        7 ^ 3,
+        8 ^ 4,
+        9 ^ 5,
+        10 ^ 6,
+        11 ^ 7,
+        12 ^ 8,
+    ];
+    assert_eq!(sender.nonce(0x0102_0304_0506_0708), expected);
+    assert_eq!(sender.nonce(0), iv);
+
+    for i in 0..3 {
+        let (sequence, nonce) = sender.next_nonce().unwrap();
+        assert_eq!(sequence, i);
+
+        let ct = aead.encrypt(b"record", &nonce, b"");
+        let nonce = receiver.accept(sequence).unwrap();
+        assert_eq!(aead.decrypt(&ct, &nonce, b""), Ok(b"record".to_vec()));
+    }
+
+    assert_eq!(receiver.accept(1), Err(RecordError::SequenceReused));
+    assert_eq!(receiver.accept(2), Err(RecordError::SequenceReused));
+    assert!(receiver.accept(10).is_ok());
+    assert_eq!(receiver.accept(9), Err(RecordError::SequenceReused));
+
+    assert!(receiver.accept(u64::MAX).is_ok());
+    assert_eq!(receiver.accept(u64::MAX), Err(RecordError::SequenceReused));
+}
+
+#[test]
+fn test_nonce_sequence_exhaustion() {
+    let mut sequence = NonceSequence::new(&[0; 12]);
+    assert!(sequence.accept(u64::MAX - 1).is_ok());
+
+    assert_eq!(sequence.next_nonce().unwrap().0, u64::MAX);
+    assert_eq!(sequence.next_nonce(), Err(RecordError::SequenceExhausted));
+}
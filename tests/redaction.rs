@@ -0,0 +1,27 @@
+use raycrypt::aeads::ChaCha20Poly1305;
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::ecc::x25519::PrivateKey;
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::kdfs::hkdf::Hkdf;
+
+#[test]
+fn test_debug_is_redacted() {
+    let key = [0x41u8; 32];
+
+    assert_eq!(
+        format!("{:?}", PrivateKey::new(&key).unwrap()),
+        "PrivateKey(<32 bytes, redacted>)"
+    );
+    assert_eq!(
+        format!("{:?}", SigningKey::new(&key).unwrap()),
+        "SigningKey(<32 bytes, redacted>)"
+    );
+    assert_eq!(
+        format!("{:?}", ChaCha20Poly1305::new(&key)),
+        "ChaCha20Poly1305(<32 bytes, redacted>)"
+    );
+    assert_eq!(
+        format!("{:?}", Hkdf::<Sha256>::extract(b"", &key)),
+        "Hkdf(<redacted>)"
+    );
+}
@@ -0,0 +1,93 @@
+use raycrypt::protocols::record::{RecordError, RecordProtection};
+use raycrypt::protocols::replay::{ReplayError, ReplayWindow};
+
+#[test]
+fn test_in_order() {
+    let mut window = ReplayWindow::default();
+
+    assert_eq!(window.highest(), None);
+
+    for sequence in 0..200 {
+        assert!(window.update(sequence).is_ok());
+        assert_eq!(window.update(sequence), Err(ReplayError::Duplicate));
+    }
+
+    assert_eq!(window.highest(), Some(199));
+}
+
+#[test]
+fn test_out_of_order() {
+    let mut window = ReplayWindow::new(64);
+
+    assert!(window.update(100).is_ok());
+    assert!(window.update(37).is_ok());
+    assert!(window.update(99).is_ok());
+
+    assert_eq!(window.check(36), Err(ReplayError::TooOld));
+    assert_eq!(window.check(99), Err(ReplayError::Duplicate));
+    assert!(window.check(98).is_ok());
+
+    // check does not mark the sequence number as seen
+    assert!(window.check(98).is_ok());
+
+    assert!(window.update(164).is_ok());
+    assert_eq!(window.update(100), Err(ReplayError::TooOld));
+    assert!(window.update(101).is_ok());
+    assert_eq!(window.update(164), Err(ReplayError::Duplicate));
+}
+
+#[test]
+fn test_wide_window() {
+    let mut window = ReplayWindow::new(128);
+
+    assert!(window.update(1000).is_ok());
+    assert!(window.update(873).is_ok());
+    assert_eq!(window.update(872), Err(ReplayError::TooOld));
+    assert_eq!(window.update(873), Err(ReplayError::Duplicate));
+
+    assert!(window.update(u64::MAX).is_ok());
+    assert_eq!(window.update(1000), Err(ReplayError::TooOld));
+    assert_eq!(window.update(u64::MAX), Err(ReplayError::Duplicate));
+}
+
+#[test]
+fn test_record_replay() {
+    let mut sender = RecordProtection::new(&[7u8; 32], 1 << 20);
+    let mut receiver = RecordProtection::new(&[7u8; 32], 1 << 20);
+
+    let records: Vec<_> = (0..100u64)
+        .map(|i| sender.seal(&i.to_be_bytes(), b""))
+        .collect();
+
+    // reordered, with every record delivered twice: only the first copy opens
+    let mut opened = [false; 6];
+
+    for i in [5, 1, 5, 0, 4, 1, 2, 3, 0, 2, 4, 3] {
+        let (sequence, ct) = &records[i];
+        let result = receiver.open(*sequence, ct, b"");
+
+        if opened[i] {
+            assert_eq!(result, Err(RecordError::SequenceReused));
+        } else {
+            assert_eq!(result.unwrap(), (i as u64).to_be_bytes());
+            opened[i] = true;
+        }
+    }
+
+    for (sequence, ct) in &records[..6] {
+        assert_eq!(
+            receiver.open(*sequence, ct, b""),
+            Err(RecordError::SequenceReused)
+        );
+    }
+
+    // the default window spans 64 records, so 99 pushes 35 and below out of it
+    let (sequence, ct) = &records[99];
+    assert!(receiver.open(*sequence, ct, b"").is_ok());
+
+    let (sequence, ct) = &records[36];
+    assert!(receiver.open(*sequence, ct, b"").is_ok());
+
+    let (sequence, ct) = &records[35];
+    assert_eq!(receiver.open(*sequence, ct, b""), Err(RecordError::TooOld));
+}
@@ -0,0 +1,135 @@
+use raycrypt::ecc::ring::{self, LinkableSignature, PublicKey, RingError, SecretKey, Signature};
+use raycrypt::errors::InvalidSignature;
+
+fn keys(n: usize) -> (Vec<SecretKey>, Vec<PublicKey>) {
+    let secrets: Vec<SecretKey> = (0..n).map(|_| SecretKey::generate()).collect();
+    let ring = secrets.iter().map(SecretKey::public_key).collect();
+
+    (secrets, ring)
+}
+
+#[test]
+fn test_sag() {
+    let (secrets, ring) = keys(5);
+
+    // every position in the ring, including the first and the last
+    for secret in &secrets {
+        let signature = ring::sign(secret, &ring, b"leak").unwrap();
+        assert_eq!(ring::verify(&ring, b"leak", &signature), Ok(()));
+        assert_eq!(
+            ring::verify(&ring, b"another leak", &signature),
+            Err(InvalidSignature)
+        );
+
+        let decoded = Signature::from_bytes(&signature.to_bytes()).unwrap();
+        assert_eq!(ring::verify(&ring, b"leak", &decoded), Ok(()));
+    }
+
+    // a ring of one is an ordinary Schnorr signature
+    let signature = ring::sign(&secrets[0], &ring[..1], b"leak").unwrap();
+    assert_eq!(ring::verify(&ring[..1], b"leak", &signature), Ok(()));
+}
+
+#[test]
+fn test_ring_must_match() {
+    let (secrets, ring) = keys(3);
+    let signature = ring::sign(&secrets[1], &ring, b"leak").unwrap();
+
+    let mut reordered = ring.clone();
+    reordered.swap(0, 2);
+    assert_eq!(
+        ring::verify(&reordered, b"leak", &signature),
+        Err(InvalidSignature)
+    );
+    assert_eq!(
+        ring::verify(&ring[..2], b"leak", &signature),
+        Err(InvalidSignature)
+    );
+
+    let (_, others) = keys(3);
+    assert_eq!(
+        ring::verify(&others, b"leak", &signature),
+        Err(InvalidSignature)
+    );
+
+    assert_eq!(
+        ring::sign(&SecretKey::generate(), &ring, b"leak").unwrap_err(),
+        RingError::KeyNotInRing
+    );
+    assert_eq!(
+        ring::sign(&secrets[0], &[], b"leak").unwrap_err(),
+        RingError::KeyNotInRing
+    );
+}
+
+#[test]
+fn test_lsag_linking() {
+    let (secrets, ring) = keys(4);
+    let (_, other_ring) = keys(3);
+    let other_ring = [&other_ring[..], &ring[2..3]].concat();
+
+    let first = ring::sign_linkable(&secrets[2], &ring, b"vote: yes").unwrap();
+    let second = ring::sign_linkable(&secrets[2], &other_ring, b"vote: no").unwrap();
+    let third = ring::sign_linkable(&secrets[0], &ring, b"vote: yes").unwrap();
+
+    assert_eq!(ring::verify_linkable(&ring, b"vote: yes", &first), Ok(()));
+    assert_eq!(
+        ring::verify_linkable(&other_ring, b"vote: no", &second),
+        Ok(())
+    );
+    assert_eq!(ring::verify_linkable(&ring, b"vote: yes", &third), Ok(()));
+
+    assert!(first.is_linked(&second));
+    assert!(!first.is_linked(&third));
+    assert_eq!(first.key_image(), secrets[2].key_image());
+}
+
+#[test]
+fn test_lsag_key_image_is_bound() {
+    let (secrets, ring) = keys(3);
+    let signature = ring::sign_linkable(&secrets[1], &ring, b"vote").unwrap();
+
+    // swapping in another member's key image, to dodge linking, breaks the signature
+    let mut bytes = signature.to_bytes();
+    bytes[..32].copy_from_slice(&secrets[0].key_image().to_bytes());
+    let forged = LinkableSignature::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        ring::verify_linkable(&ring, b"vote", &forged),
+        Err(InvalidSignature)
+    );
+
+    let decoded = LinkableSignature::from_bytes(&signature.to_bytes()).unwrap();
+    assert_eq!(decoded, signature);
+    assert_eq!(ring::verify_linkable(&ring, b"vote", &decoded), Ok(()));
+}
+
+#[test]
+fn test_malformed_signatures() {
+    let (secrets, ring) = keys(2);
+    let bytes = ring::sign(&secrets[0], &ring, b"leak").unwrap().to_bytes();
+
+    assert_eq!(bytes.len(), 32 * 3);
+    assert_eq!(
+        Signature::from_bytes(&bytes[..40]),
+        Err(RingError::Malformed)
+    );
+    assert_eq!(
+        Signature::from_bytes(&bytes[..32]),
+        Err(RingError::Malformed)
+    );
+
+    // a response of l is not a canonical scalar
+    let mut non_canonical = bytes.clone();
+    non_canonical[64..].copy_from_slice(
+        &hex::decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010").unwrap(),
+    );
+    assert_eq!(
+        Signature::from_bytes(&non_canonical),
+        Err(RingError::Malformed)
+    );
+
+    assert_eq!(
+        LinkableSignature::from_bytes(&[0xff; 96]),
+        Err(RingError::Malformed)
+    );
+}
@@ -0,0 +1,112 @@
+use raycrypt::ecc::points::PointError;
+use raycrypt::ecc::ristretto::RistrettoPoint;
+use raycrypt::ecc::scalar::Scalar;
+
+fn bytes(encoded: &str) -> [u8; 32] {
+    hex::decode(encoded).unwrap().try_into().unwrap()
+}
+
+// RFC 9496, appendix A.1: multiples of the generator
+const MULTIPLES: [&str; 8] = [
+    "0000000000000000000000000000000000000000000000000000000000000000",
+    "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+    "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+    "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+    "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+    "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+    "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+    "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+];
+
+#[test]
+fn test_multiples() {
+    let basepoint = RistrettoPoint::basepoint();
+    let mut point = RistrettoPoint::identity();
+
+    for (i, encoded) in MULTIPLES.iter().enumerate() {
+        assert_eq!(point.to_bytes(), bytes(encoded), "{}", i);
+        assert_eq!(RistrettoPoint::from_bytes(&bytes(encoded)), Ok(point));
+        assert_eq!(RistrettoPoint::mul_base(&Scalar::from_u64(i as u64)), point);
+
+        point = point.add(&basepoint);
+    }
+
+    assert!(RistrettoPoint::identity().is_identity());
+    assert!(basepoint.mul(&-Scalar::ONE).add(&basepoint).is_identity());
+}
+
+// RFC 9496, appendix A.2
+#[test]
+fn test_invalid_encodings() {
+    for encoded in [
+        // non-canonical field elements
+        "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        // negative field elements
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+    ] {
+        assert_eq!(
+            RistrettoPoint::from_bytes(&bytes(encoded)),
+            Err(PointError::NonCanonical),
+            "{}",
+            encoded
+        );
+    }
+
+    for encoded in [
+        // non-square x^2
+        "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+        "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+        // negative xy
+        "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+        // s = -1, which gives y = 0
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+    ] {
+        assert_eq!(
+            RistrettoPoint::from_bytes(&bytes(encoded)),
+            Err(PointError::NotOnCurve),
+            "{}",
+            encoded
+        );
+    }
+}
+
+// RFC 9496, appendix A.3
+#[test]
+fn test_from_uniform_bytes() {
+    let uniform: [u8; 64] = hex::decode(concat!(
+        "5d1be09e3d0c82fc538112490e35701979d99e06ca3e2b5b54bffe8b4dc772c1",
+        "4d98b696a1bbfb5ca32c436cc61c16563790306c79eaca7705668b47dffe5bb6"
+    ))
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    assert_eq!(
+        RistrettoPoint::from_uniform_bytes(&uniform).to_bytes(),
+        bytes("3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46")
+    );
+
+    // SHA-512 of the input, then the same map
+    let point =
+        RistrettoPoint::hash_from_bytes(b"Ristretto is traditionally a short shotgun barrel");
+    assert_eq!(
+        point.to_bytes(),
+        bytes("fc92cd500bdea442e6ebf1fd607f9b1c21117ffbc9593c2bfaf77b5b3daf4f78")
+    );
+}
+
+#[test]
+fn test_equality_across_representatives() {
+    let a = RistrettoPoint::hash_from_bytes(b"a");
+    let b = RistrettoPoint::hash_from_bytes(b"b");
+
+    let sum = a.add(&b);
+    assert_eq!(RistrettoPoint::from_bytes(&sum.to_bytes()), Ok(sum));
+    assert_eq!(sum.sub(&b), a);
+    assert_ne!(a, b);
+}
@@ -0,0 +1,109 @@
+use raycrypt::aeads::XChaCha20Poly1305;
+use raycrypt::ecc::{ed25519, x25519};
+use raycrypt::rng::{self, DeterministicRng, RandomSource};
+use raycrypt::shamir;
+
+// a faulty generator that keeps returning the same bytes
+struct StuckRng(u8);
+
+impl RandomSource for StuckRng {
+    fn fill(&mut self, dest: &mut [u8]) {
+        dest.fill(self.0);
+    }
+}
+
+#[test]
+fn test_deterministic_stream() {
+    let mut a = DeterministicRng::from_u64(1);
+    let mut b = DeterministicRng::from_u64(1);
+    let mut c = DeterministicRng::from_u64(2);
+
+    let mut one_read = [0u8; 200];
+    a.fill(&mut one_read);
+
+    // reads of any size make the same stream
+    let mut pieces = [0u8; 200];
+    for chunk in pieces.chunks_mut(7) {
+        b.fill(chunk);
+    }
+    assert_eq!(one_read, pieces);
+
+    let mut other = [0u8; 200];
+    c.fill(&mut other);
+    assert_ne!(one_read, other);
+
+    // the ChaCha20 keystream under the seed
+    let mut first = [0u8; 4];
+    DeterministicRng::new(&[0u8; 32]).fill(&mut first);
+    assert_eq!(hex::encode(first), "76b8e0ad");
+}
+
+#[test]
+fn test_override_is_reproducible() {
+    let run = || {
+        let _rng = rng::insecure_override(DeterministicRng::from_u64(42));
+
+        (
+            x25519::keygen(None).public_key(),
+            ed25519::keygen(None).verifying_key(),
+            XChaCha20Poly1305::generate_nonce(),
+        )
+    };
+
+    assert_eq!(run(), run());
+
+    // the default generator is back once the guard is dropped
+    assert!(!rng::is_overridden());
+    assert_ne!(
+        XChaCha20Poly1305::generate_nonce(),
+        XChaCha20Poly1305::generate_nonce()
+    );
+}
+
+#[test]
+fn test_nested_overrides() {
+    let outer = rng::insecure_override(StuckRng(1));
+    assert!(rng::is_overridden());
+
+    {
+        let _inner = rng::insecure_override(StuckRng(2));
+        assert_eq!(XChaCha20Poly1305::generate_nonce(), [2u8; 24]);
+    }
+
+    assert_eq!(XChaCha20Poly1305::generate_nonce(), [1u8; 24]);
+    drop(outer);
+    assert!(!rng::is_overridden());
+}
+
+#[test]
+fn test_override_is_per_thread() {
+    let _rng = rng::insecure_override(StuckRng(0));
+
+    std::thread::spawn(|| {
+        assert!(!rng::is_overridden());
+        assert_ne!(
+            XChaCha20Poly1305::generate_nonce(),
+            XChaCha20Poly1305::generate_nonce()
+        );
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn test_fault_injection() {
+    let _rng = rng::insecure_override(StuckRng(9));
+
+    // a stuck generator repeats nonces, which the caller's checks can now be tested against
+    let cipher = XChaCha20Poly1305::new(&[3u8; 32]);
+    let nonce = XChaCha20Poly1305::generate_nonce();
+    assert_eq!(nonce, XChaCha20Poly1305::generate_nonce());
+    assert_eq!(
+        cipher.encrypt(b"msg", &nonce, b""),
+        cipher.encrypt(b"msg", &XChaCha20Poly1305::generate_nonce(), b"")
+    );
+
+    // with every coefficient equal, shares still recombine
+    let shares = shamir::split(b"secret", 3, 2).unwrap();
+    assert_eq!(shamir::combine(&shares[..2]).unwrap(), b"secret");
+}
@@ -0,0 +1,98 @@
+use raycrypt::aeads::rotating::{RotatingKey, RotationError};
+use raycrypt::aeads::SymmetricKey;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+fn key() -> RotatingKey {
+    RotatingKey::new(
+        &SymmetricKey::new(&[9u8; 32]).unwrap(),
+        HOUR,
+        Duration::from_secs(300),
+    )
+}
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[test]
+fn test_epochs() {
+    let key = key();
+
+    assert_eq!(key.epoch(at(0)), 0);
+    assert_eq!(key.epoch(at(3599)), 0);
+    assert_eq!(key.epoch(at(3600)), 1);
+    assert_eq!(key.epoch(UNIX_EPOCH - HOUR), 0);
+    assert_eq!(key.epoch_start(2), at(7200));
+
+    assert_ne!(key.epoch_key(1).as_bytes(), key.epoch_key(2).as_bytes());
+    assert_eq!(key.epoch_key(1).as_bytes(), key.epoch_key(1).as_bytes());
+
+    // the same master key on another schedule gives unrelated keys
+    let daily = RotatingKey::new(
+        &SymmetricKey::new(&[9u8; 32]).unwrap(),
+        24 * HOUR,
+        Duration::ZERO,
+    );
+    assert_ne!(daily.epoch_key(1).as_bytes(), key.epoch_key(1).as_bytes());
+}
+
+#[test]
+fn test_round_trip() {
+    let key = key();
+    let now = at(10 * 3600 + 1234);
+    let sealed = key.encrypt(b"cpu=0.93", b"host-7", now);
+
+    assert_eq!(RotatingKey::ciphertext_epoch(&sealed), Ok(10));
+    assert_eq!(key.decrypt(&sealed, b"host-7", now).unwrap(), b"cpu=0.93");
+    assert_eq!(
+        key.decrypt(&sealed, b"host-8", now),
+        Err(RotationError::InvalidMac)
+    );
+
+    let mut tampered = sealed.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    assert_eq!(
+        key.decrypt(&tampered, b"host-7", now),
+        Err(RotationError::InvalidMac)
+    );
+
+    // moving a ciphertext to a neighbouring epoch that is still accepted breaks the MAC
+    let mut moved = sealed.clone();
+    moved[7] = 11;
+    assert_eq!(
+        key.decrypt(&moved, b"host-7", at(11 * 3600)),
+        Err(RotationError::InvalidMac)
+    );
+
+    assert_eq!(
+        key.decrypt(&sealed[..40], b"host-7", now),
+        Err(RotationError::Malformed)
+    );
+}
+
+#[test]
+fn test_clock_skew() {
+    let key = key();
+    let sealed = key.encrypt(b"event", b"", at(11 * 3600 - 60));
+
+    // a consumer whose clock has moved into the next epoch still accepts it within the skew
+    assert!(key.decrypt(&sealed, b"", at(11 * 3600 + 240)).is_ok());
+    assert_eq!(
+        key.decrypt(&sealed, b"", at(11 * 3600 + 301)),
+        Err(RotationError::EpochOutOfRange)
+    );
+
+    // as does one whose clock is behind the producer's
+    let sealed = key.encrypt(b"event", b"", at(11 * 3600));
+    assert!(key.decrypt(&sealed, b"", at(11 * 3600 - 300)).is_ok());
+    assert_eq!(
+        key.decrypt(&sealed, b"", at(11 * 3600 - 301)),
+        Err(RotationError::EpochOutOfRange)
+    );
+
+    assert_eq!(key.accepted_epochs(at(11 * 3600 + 100)), (10, 11));
+    assert_eq!(key.accepted_epochs(at(11 * 3600 + 1800)), (11, 11));
+}
@@ -0,0 +1,40 @@
+use raycrypt::aeads::{AeadAlgorithm, RoundtripFailure};
+use raycrypt::rng::{self, DeterministicRng};
+use raycrypt::verify_roundtrip;
+
+const ALGORITHMS: [AeadAlgorithm; 3] = [
+    AeadAlgorithm::XChaCha20Poly1305,
+    AeadAlgorithm::ChaCha20Poly1305,
+    AeadAlgorithm::AesGcm,
+];
+
+#[test]
+fn test_roundtrip() {
+    for algorithm in ALGORITHMS {
+        assert_eq!(verify_roundtrip(algorithm, 40), Ok(()));
+    }
+
+    assert_eq!(verify_roundtrip(AeadAlgorithm::default(), 0), Ok(()));
+}
+
+#[test]
+fn test_reproducible() {
+    let _rng = rng::insecure_override(DeterministicRng::from_u64(469));
+
+    for algorithm in ALGORITHMS {
+        assert_eq!(verify_roundtrip(algorithm, 20), Ok(()));
+    }
+}
+
+#[test]
+fn test_failure_display() {
+    let failure = RoundtripFailure::ForgeryAccepted {
+        message_size: 17,
+        ad_size: 0,
+    };
+
+    assert_eq!(
+        failure.to_string(),
+        "A modified 17-byte message with 0 bytes of associated data was accepted."
+    );
+}
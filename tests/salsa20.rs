@@ -0,0 +1,91 @@
+use hex::decode;
+use raycrypt::ciphers::salsa::{
+    core_hsalsa20, core_salsa20, hsalsa20, salsa20_xor, salsa20_xor_ic, xsalsa20_xor,
+    xsalsa20_xor_ic, Salsa20, XSalsa20, SIGMA,
+};
+
+const SALSA20: &str = "d2508c8ac140cdacd6b42870d3c768284a84ecc15c455d761081156b94f84545ab7039737025bb5880f4ea080ac5e21f931a42f38ac4b1a85da1436d9bee979c65be9cad387f187fce90661945f24bd1fff14248860fc560d0b0dd95f79f89b6bf296bdbba6d44a49aefa98bee597086afcdf047af589716c8e7392e098529506e57a4e158ca7d8118b980e54f6035a7a04e3eaae9ecf76afb5dad44fdec93962ae4bd48659bfb97d1bb177676bf19b68e9876f18d60b7ab7261e213f89b1899937cfebf446058fc";
+
+const XSALSA20: &str = "f97e0e219bdc55e80889e238b191f70235bc6d579dc8c02169660070a060b1866a36775a1e749d0dee705b4a44a474defe067467597669ffdcec18f1c5e4bd6b94752b52aaf28043110556327dbf1460d0e5713ef3dbe611d08be1e95b2ac0eaffaddac63faa9ada338dfb6ee1236b5c2233026d8b696f41e71ec13aad4babb90370bdcb399ba37ae5c0f7b3924f839916b6fa93070c45ca0e259251b4abd47d47059cc6772ed66fcb4c71f5ecc828ff9fdb4d299d895288f9f3aed6fab71748b49ca530e99d6b91";
+
+fn inputs() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce: Vec<u8> = (0x40..0x58).collect();
+    let msg: Vec<u8> = (0..200).collect();
+
+    (key, nonce, msg)
+}
+
+#[test]
+fn test_salsa20() {
+    let (key, nonce, msg) = inputs();
+    let expected = decode(SALSA20).unwrap();
+
+    let ct = salsa20_xor_ic(&msg, &nonce[..8], 0, &key);
+    assert_eq!(ct, expected);
+    assert_eq!(salsa20_xor(&msg, &nonce[..8], &key), expected);
+    assert_eq!(salsa20_xor_ic(&ct, &nonce[..8], 0, &key), msg);
+    assert_eq!(
+        salsa20_xor_ic(&msg[64..], &nonce[..8], 1, &key),
+        expected[64..]
+    );
+
+    let keystream: Vec<u8> = msg.iter().zip(&expected).map(|(m, c)| m ^ c).collect();
+    assert_eq!(
+        Salsa20::new(&key).keystream(&nonce[..8], 2),
+        keystream[128..192]
+    );
+}
+
+#[test]
+fn test_xsalsa20() {
+    let (key, nonce, msg) = inputs();
+    let expected = decode(XSALSA20).unwrap();
+
+    let ct = xsalsa20_xor_ic(&msg, &nonce, 0, &key);
+    assert_eq!(ct, expected);
+    assert_eq!(xsalsa20_xor(&msg, &nonce, &key), expected);
+    assert_eq!(
+        xsalsa20_xor_ic(&msg[128..], &nonce, 2, &key),
+        expected[128..]
+    );
+
+    let keystream: Vec<u8> = msg.iter().zip(&expected).map(|(m, c)| m ^ c).collect();
+    assert_eq!(XSalsa20::new(&key).keystream(&nonce, 1), keystream[64..128]);
+}
+
+#[test]
+fn test_core_salsa20() {
+    let (key, nonce, msg) = inputs();
+    let expected = decode(SALSA20).unwrap();
+
+    let mut input = [0u8; 16];
+    input[..8].copy_from_slice(&nonce[..8]);
+    input[8] = 1;
+
+    let block = core_salsa20(&input, key[..].try_into().unwrap(), &SIGMA);
+    let keystream: Vec<u8> = msg[64..128]
+        .iter()
+        .zip(&expected[64..128])
+        .map(|(m, c)| m ^ c)
+        .collect();
+
+    assert_eq!(block.to_vec(), keystream);
+}
+
+// the first two keys of NaCl's crypto_box example
+#[test]
+fn test_core_hsalsa20() {
+    let shared =
+        decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742").unwrap();
+    let first = decode("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389").unwrap();
+    let second =
+        decode("dc908dda0b9344a953629b733820778880f3ceb421bb61b91cbd4c3e66256ce4").unwrap();
+    let nonce = decode("69696ee955b62b73cd62bda875fc73d6").unwrap();
+
+    let key = hsalsa20(shared[..].try_into().unwrap(), &[0u8; 16]);
+    assert_eq!(key.to_vec(), first);
+
+    let key = core_hsalsa20(nonce[..].try_into().unwrap(), &key, &SIGMA);
+    assert_eq!(key.to_vec(), second);
+}
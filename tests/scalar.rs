@@ -0,0 +1,93 @@
+use raycrypt::ecc::points::EdwardsPoint;
+use raycrypt::ecc::scalar::Scalar;
+use raycrypt::hashes::sha512::Sha512;
+
+fn scalar(encoded: &str) -> Scalar {
+    let bytes: [u8; 32] = hex::decode(encoded).unwrap().try_into().unwrap();
+    Scalar::from_canonical_bytes(&bytes).unwrap()
+}
+
+#[test]
+fn test_arithmetic() {
+    let a = Scalar::from_bytes_mod_order(&core::array::from_fn(|i| i as u8));
+    let b = Scalar::from_bytes_mod_order(&core::array::from_fn(|i| i as u8 + 100));
+
+    assert_eq!(
+        a,
+        scalar("132d0ca6e9a1f3ae316c12682d132ffa0f1112131415161718191a1b1c1d1e0f")
+    );
+    assert_eq!(
+        a + b,
+        scalar("221fcec8648fb8011556cc1ccabbcab18386888a8c8e90929496989a9c9ea002")
+    );
+    assert_eq!(
+        a - b,
+        scalar("1767542654511c0478e56010b270b42d9c9b9b9b9b9b9b9b9b9b9b9b9b9b9b0b")
+    );
+    assert_eq!(
+        a * b,
+        scalar("bc1334cdcc19aa17c0fcd79f20e95c03927119a8c0a6f869822e9f2e022da007")
+    );
+    assert_eq!(
+        a.invert(),
+        scalar("0cf17e6d77775ab76bd4f41cd2ef9ecc9ddd8242185bd685a60b49b5b3f16606")
+    );
+    assert_eq!(a * a.invert(), Scalar::ONE);
+    assert_eq!(a + -a, Scalar::ZERO);
+    assert_eq!(a * b, b * a);
+
+    assert_eq!(
+        Scalar::from_bytes_mod_order_wide(&core::array::from_fn(|i| i as u8)),
+        scalar("7a3c6282f02d37a05023b60d5428e6cc5961d4c31221937adae0b574e4d07205")
+    );
+}
+
+#[test]
+fn test_canonical_bytes() {
+    let order: [u8; 32] =
+        hex::decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010")
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+    assert_eq!(Scalar::from_canonical_bytes(&order), None);
+    assert!(Scalar::from_bytes_mod_order(&order).is_zero());
+
+    let mut below = order;
+    below[0] -= 1;
+    assert_eq!(Scalar::from_canonical_bytes(&below), Some(-Scalar::ONE));
+
+    let random = Scalar::random();
+    assert_eq!(
+        Scalar::from_canonical_bytes(&random.to_bytes()),
+        Some(random)
+    );
+    assert_ne!(random, Scalar::random());
+    assert_eq!(format!("{:?}", random), "Scalar(<redacted>)");
+}
+
+// a Schnorr signature, as a protocol built on the scalar and point types would make one
+#[test]
+fn test_schnorr() {
+    fn challenge(nonce: &EdwardsPoint, public: &EdwardsPoint, msg: &[u8]) -> Scalar {
+        let mut hasher = Sha512::new();
+        hasher.update(&nonce.to_bytes());
+        hasher.update(&public.to_bytes());
+        hasher.update(msg);
+
+        Scalar::from_bytes_mod_order_wide(&hasher.digest())
+    }
+
+    let secret = Scalar::random();
+    let public = EdwardsPoint::mul_base(&secret);
+
+    let r = Scalar::random();
+    let nonce = EdwardsPoint::mul_base(&r);
+    let s = r + challenge(&nonce, &public, b"message") * secret;
+
+    let c = challenge(&nonce, &public, b"message");
+    assert_eq!(EdwardsPoint::mul_base(&s), nonce.add(&public.mul(&c)));
+
+    let c = challenge(&nonce, &public, b"another message");
+    assert_ne!(EdwardsPoint::mul_base(&s), nonce.add(&public.mul(&c)));
+}
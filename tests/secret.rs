@@ -0,0 +1,137 @@
+use raycrypt::aeads::SymmetricKey;
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::ecc::x25519::PrivateKey;
+use raycrypt::secret::SecretString;
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[test]
+fn test_hex_codec() {
+    let every_byte: Vec<u8> = (0..=255).collect();
+    let encoded = SecretString::encode_hex(&every_byte);
+
+    assert_eq!(encoded.expose_secret(), hex::encode(&every_byte));
+    assert_eq!(encoded.decode_hex().unwrap(), every_byte);
+    assert_eq!(
+        SecretString::from(hex::encode_upper(&every_byte))
+            .decode_hex()
+            .unwrap(),
+        every_byte
+    );
+
+    // every character outside the hex digits is rejected, in either position
+    for c in 0..=255u8 {
+        let valid = c.is_ascii_hexdigit();
+        let pair = [c, b'0'];
+
+        if let Ok(pair) = std::str::from_utf8(&pair) {
+            assert_eq!(SecretString::from(pair).decode_hex().is_ok(), valid);
+            let reversed: String = pair.chars().rev().collect();
+            assert_eq!(SecretString::from(reversed).decode_hex().is_ok(), valid);
+        }
+    }
+
+    assert!(SecretString::from("abc").decode_hex().is_err());
+}
+
+#[test]
+fn test_base64_codec() {
+    // every sextet in every position, and each amount of padding
+    let data: Vec<u8> = (0..=255u8).chain((0..=255).rev()).collect();
+
+    for length in [0, 1, 2, 3, 100, data.len()] {
+        let encoded = SecretString::encode_base64(&data[..length]);
+        assert_eq!(encoded.decode_base64().unwrap(), &data[..length]);
+    }
+
+    let encoded = SecretString::encode_base64(&data);
+    for symbol in ALPHABET {
+        assert!(encoded.expose_secret().as_bytes().contains(symbol));
+    }
+
+    assert_eq!(
+        SecretString::encode_base64(b"any carnal pleas").expose_secret(),
+        "YW55IGNhcm5hbCBwbGVhcw=="
+    );
+
+    for c in 0..=127u8 {
+        let quad = [c, b'A', b'A', b'A'];
+        let quad = std::str::from_utf8(&quad).unwrap();
+
+        assert_eq!(
+            SecretString::from(quad).decode_base64().is_ok(),
+            ALPHABET.contains(&c),
+            "{:?}",
+            c as char
+        );
+    }
+
+    // non-zero bits after the end of the data
+    assert!(SecretString::from("AB==").decode_base64().is_err());
+    assert!(SecretString::from("AAB=").decode_base64().is_err());
+    assert!(SecretString::from("A=AA").decode_base64().is_err());
+}
+
+#[test]
+fn test_key_encodings() {
+    let key = SymmetricKey::generate();
+    assert_eq!(
+        SymmetricKey::from_hex(key.to_hex().expose_secret())
+            .unwrap()
+            .as_bytes(),
+        key.as_bytes()
+    );
+    assert_eq!(
+        SymmetricKey::from_base64(key.to_base64().expose_secret())
+            .unwrap()
+            .as_bytes(),
+        key.as_bytes()
+    );
+
+    let private = PrivateKey::new(&[7u8; 32]).unwrap();
+    assert_eq!(private.to_hex().expose_secret(), "07".repeat(32));
+    assert_eq!(
+        PrivateKey::from_base64(private.to_base64().expose_secret())
+            .unwrap()
+            .public_key(),
+        private.public_key()
+    );
+
+    let signing = SigningKey::from_seed(b"secret");
+    assert_eq!(
+        SigningKey::from_hex(signing.to_hex().expose_secret())
+            .unwrap()
+            .verifying_key(),
+        signing.verifying_key()
+    );
+    assert_eq!(
+        signing.to_base64().decode_base64().unwrap(),
+        signing.to_bytes()
+    );
+
+    // PEM exports of private keys are secrets too
+    let pem = signing.to_pem();
+    assert_eq!(format!("{:?}", pem), "SecretString(<redacted>)");
+    assert_eq!(
+        SigningKey::from_pem(pem.expose_secret())
+            .unwrap()
+            .verifying_key(),
+        signing.verifying_key()
+    );
+    assert_eq!(
+        PrivateKey::from_pem(private.to_pem().expose_secret())
+            .unwrap()
+            .public_key(),
+        private.public_key()
+    );
+}
+
+#[test]
+fn test_secret_string() {
+    let secret = SecretString::from("hunter2");
+
+    assert_eq!(format!("{:?}", secret), "SecretString(<redacted>)");
+    assert_eq!(secret.len(), 7);
+    assert!(!secret.is_empty());
+    assert!(SecretString::default().is_empty());
+}
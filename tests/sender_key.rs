@@ -0,0 +1,161 @@
+use raycrypt::protocols::sender_key::{
+    SenderKey, SenderKeyDistribution, SenderKeyError, SenderKeyReceiver, DISTRIBUTION_LENGTH,
+    MAX_SKIP,
+};
+
+fn receiver(sender: &SenderKey) -> SenderKeyReceiver {
+    let bytes = sender.distribution().to_bytes();
+    SenderKeyReceiver::new(&SenderKeyDistribution::from_bytes(&bytes).unwrap())
+}
+
+#[test]
+fn test_fan_out() {
+    let mut sender = SenderKey::generate();
+    let mut members: Vec<SenderKeyReceiver> = (0..3).map(|_| receiver(&sender)).collect();
+
+    for i in 0..5u8 {
+        let msg = [i; 10];
+        let message = sender.encrypt(&msg, b"group").unwrap();
+        assert_eq!(message.len(), 8 + 10 + 16 + 64);
+
+        for member in members.iter_mut() {
+            assert_eq!(member.decrypt(&message, b"group").unwrap(), msg);
+        }
+    }
+
+    assert_eq!(sender.iteration(), 5);
+}
+
+#[test]
+fn test_out_of_order() {
+    let mut sender = SenderKey::generate();
+    let mut member = receiver(&sender);
+
+    let messages: Vec<Vec<u8>> = (0..4u8)
+        .map(|i| sender.encrypt(&[i], b"").unwrap())
+        .collect();
+
+    assert_eq!(member.decrypt(&messages[2], b"").unwrap(), [2]);
+    assert_eq!(member.decrypt(&messages[0], b"").unwrap(), [0]);
+    assert_eq!(member.decrypt(&messages[3], b"").unwrap(), [3]);
+    assert_eq!(member.decrypt(&messages[1], b"").unwrap(), [1]);
+
+    for message in &messages {
+        assert_eq!(
+            member.decrypt(message, b""),
+            Err(SenderKeyError::DuplicateMessage)
+        );
+    }
+}
+
+#[test]
+fn test_late_joiner() {
+    let mut sender = SenderKey::generate();
+    let early = sender.encrypt(b"before", b"").unwrap();
+
+    let mut member = receiver(&sender);
+    let late = sender.encrypt(b"after", b"").unwrap();
+
+    assert_eq!(
+        member.decrypt(&early, b""),
+        Err(SenderKeyError::DuplicateMessage)
+    );
+    assert_eq!(member.decrypt(&late, b"").unwrap(), b"after");
+}
+
+#[test]
+fn test_too_far_ahead() {
+    let mut sender = SenderKey::generate();
+    let mut member = receiver(&sender);
+
+    for _ in 0..=MAX_SKIP {
+        sender.encrypt(b"", b"").unwrap();
+    }
+
+    let message = sender.encrypt(b"hello", b"").unwrap();
+    assert_eq!(
+        member.decrypt(&message, b""),
+        Err(SenderKeyError::TooFarAhead)
+    );
+}
+
+#[test]
+fn test_tampering_does_not_advance() {
+    let mut sender = SenderKey::generate();
+    let mut member = receiver(&sender);
+    let message = sender.encrypt(b"hello", b"ad").unwrap();
+
+    let mut forged = message.clone();
+    forged[10] ^= 1;
+    assert_eq!(
+        member.decrypt(&forged, b"ad"),
+        Err(SenderKeyError::InvalidSignature)
+    );
+    assert_eq!(
+        member.decrypt(&message, b"other"),
+        Err(SenderKeyError::InvalidMac)
+    );
+
+    assert_eq!(member.decrypt(&message, b"ad").unwrap(), b"hello");
+}
+
+#[test]
+fn test_signature_key_is_checked() {
+    let mut sender = SenderKey::generate();
+    let mut member = receiver(&sender);
+
+    // another sender key with the same chain but its own signing key
+    let mut distribution = sender.distribution().to_bytes();
+    let impostor = SenderKey::generate();
+    distribution[40..].copy_from_slice(&impostor.distribution().to_bytes()[40..]);
+    let mut other =
+        SenderKeyReceiver::new(&SenderKeyDistribution::from_bytes(&distribution).unwrap());
+
+    let message = sender.encrypt(b"hello", b"").unwrap();
+    assert_eq!(
+        other.decrypt(&message, b""),
+        Err(SenderKeyError::InvalidSignature)
+    );
+    assert_eq!(member.decrypt(&message, b"").unwrap(), b"hello");
+}
+
+#[test]
+fn test_rotation() {
+    let old = SenderKey::generate();
+    let mut member = receiver(&old);
+
+    let mut new = SenderKey::generate();
+    let mut rejoined = receiver(&new);
+    let message = new.encrypt(b"hello", b"").unwrap();
+
+    assert_eq!(
+        member.decrypt(&message, b""),
+        Err(SenderKeyError::UnknownChain)
+    );
+
+    assert_eq!(rejoined.chain_id(), new.chain_id());
+    assert_eq!(rejoined.decrypt(&message, b"").unwrap(), b"hello");
+}
+
+#[test]
+fn test_malformed() {
+    let sender = SenderKey::generate();
+    let mut member = receiver(&sender);
+
+    assert_eq!(
+        member.decrypt(&[0u8; 87], b""),
+        Err(SenderKeyError::Malformed)
+    );
+    assert_eq!(
+        SenderKeyDistribution::from_bytes(&[0u8; DISTRIBUTION_LENGTH - 1]),
+        Err(SenderKeyError::Malformed)
+    );
+
+    let distribution = sender.distribution();
+    assert_eq!(distribution.chain_id(), sender.chain_id());
+    assert_eq!(distribution.iteration(), 0);
+    assert_eq!(
+        SenderKeyDistribution::from_bytes(&distribution.to_bytes()).unwrap(),
+        distribution
+    );
+}
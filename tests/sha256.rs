@@ -0,0 +1,33 @@
+use hex::decode;
+use raycrypt::backend::Backend;
+use raycrypt::hashes::sha256::{hash, Sha256};
+
+#[test]
+fn test_sha256_abc() {
+    let expected =
+        decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad").unwrap();
+
+    assert_eq!(hash(b"abc").to_vec(), expected);
+}
+
+#[test]
+fn test_sha256_backends() {
+    let expected =
+        decode("0cd0bf930677960951dda8588edcb6b293c0c3b26ef3ba72cddff4ddfc6822c7").unwrap();
+
+    let msg: Vec<u8> = (0..10000).map(|i| (i % 251) as u8).collect();
+
+    for backend in [Backend::Scalar, Backend::Avx2] {
+        for chunk_size in [1, 7, 64, 100, 4096] {
+            let mut hasher = Sha256::with_backend(backend);
+
+            for chunk in msg.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+
+            assert_eq!(hasher.digest().to_vec(), expected);
+        }
+    }
+
+    assert!(!Sha256::with_backend(Backend::Scalar).is_hardware());
+}
@@ -0,0 +1,24 @@
+use hex::decode;
+use raycrypt::hashes::sha512::{hash, Sha512};
+
+#[test]
+fn test_sha512_abc() {
+    let expected = decode("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f").unwrap();
+
+    assert_eq!(hash(b"abc").to_vec(), expected);
+}
+
+#[test]
+fn test_sha512_incremental() {
+    let expected = decode("67ba5535a46e3f86dbfbed8cbbaf0125c76ed549ff8b0b9e03e0c88cf90fa634fa7b12b47d77b694de488ace8d9a65967dc96df599727d3292a8d9d447709c97").unwrap();
+
+    let msg = [b'a'; 1000];
+    let mut hasher = Sha512::new();
+
+    for chunk in msg.chunks(7) {
+        hasher.update(chunk);
+    }
+
+    assert_eq!(hasher.digest().to_vec(), expected);
+    assert_eq!(hash(&msg).to_vec(), expected);
+}
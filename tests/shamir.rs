@@ -0,0 +1,129 @@
+use raycrypt::aeads::SymmetricKey;
+use raycrypt::shamir::{
+    combine, create_recovery_kit, recover_from_shares, split, Share, ShareError,
+};
+
+#[test]
+fn test_split_combine() {
+    let secret = b"attack at dawn, or maybe at noon";
+    let shares = split(secret, 5, 3).unwrap();
+
+    assert_eq!(shares.len(), 5);
+    assert_eq!(
+        shares.iter().map(Share::index).collect::<Vec<_>>(),
+        [1, 2, 3, 4, 5]
+    );
+
+    for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+        let chosen: Vec<Share> = subset.iter().map(|&i| shares[i].clone()).collect();
+        assert_eq!(combine(&chosen).unwrap(), secret);
+    }
+
+    assert_eq!(combine(&shares).unwrap(), secret);
+    assert_ne!(combine(&shares[..2]).unwrap(), secret);
+
+    // a threshold of one is a plain copy
+    let copies = split(secret, 3, 1).unwrap();
+    assert!(copies.iter().all(|share| share.value() == secret));
+
+    assert_eq!(
+        split(secret, 3, 4).unwrap_err(),
+        ShareError::InvalidThreshold
+    );
+    assert_eq!(
+        split(secret, 3, 0).unwrap_err(),
+        ShareError::InvalidThreshold
+    );
+    assert_eq!(split(secret, 255, 255).unwrap().len(), 255);
+}
+
+#[test]
+fn test_combine_rejections() {
+    let shares = split(b"secret", 3, 2).unwrap();
+
+    assert_eq!(combine(&[]).unwrap_err(), ShareError::NotEnoughShares);
+    assert_eq!(
+        combine(&[shares[0].clone(), shares[0].clone()]).unwrap_err(),
+        ShareError::DuplicateShare
+    );
+    assert_eq!(
+        combine(&[shares[0].clone(), Share::new(2, b"short")]).unwrap_err(),
+        ShareError::MismatchedShares
+    );
+    assert_eq!(
+        combine(&[shares[0].clone(), Share::new(0, b"secret")]).unwrap_err(),
+        ShareError::MismatchedShares
+    );
+}
+
+#[test]
+fn test_recovery_kit() {
+    let key = SymmetricKey::generate();
+    let kit = create_recovery_kit(&key, 5, 3).unwrap();
+
+    assert_eq!(kit.len(), 5);
+
+    for (i, share) in kit.iter().enumerate() {
+        let fields: Vec<&str> = share.split('-').collect();
+
+        assert_eq!(fields.len(), 13);
+        assert_eq!(fields[0], "rck1");
+        assert_eq!(fields[2], "3");
+        assert_eq!(fields[3], (i + 1).to_string());
+        assert_eq!(fields[1], kit[0].split('-').nth(1).unwrap());
+    }
+
+    let recovered = recover_from_shares(&[&kit[4], &kit[0], &kit[2]]).unwrap();
+    assert_eq!(recovered.as_bytes(), key.as_bytes());
+
+    let shouted = format!("  {}\n", kit[1].to_uppercase());
+    let recovered = recover_from_shares(&[shouted.as_str(), &kit[3], &kit[2], &kit[0]]).unwrap();
+    assert_eq!(recovered.as_bytes(), key.as_bytes());
+}
+
+#[test]
+fn test_recovery_kit_rejections() {
+    let kit = create_recovery_kit(&SymmetricKey::generate(), 3, 2).unwrap();
+    let other = create_recovery_kit(&SymmetricKey::generate(), 3, 2).unwrap();
+
+    assert_eq!(
+        recover_from_shares(&[&kit[0]]).unwrap_err(),
+        ShareError::NotEnoughShares
+    );
+    assert_eq!(
+        recover_from_shares::<&str>(&[]).unwrap_err(),
+        ShareError::NotEnoughShares
+    );
+    assert_eq!(
+        recover_from_shares(&[&kit[0], &kit[0]]).unwrap_err(),
+        ShareError::DuplicateShare
+    );
+    assert_eq!(
+        recover_from_shares(&[&kit[0], &other[1]]).unwrap_err(),
+        ShareError::MismatchedShares
+    );
+
+    // one mistyped digit in the share value
+    let mut typo = kit[1].clone().into_bytes();
+    let position = typo.len() - 12;
+    typo[position] = if typo[position] == b'0' { b'1' } else { b'0' };
+    let typo = String::from_utf8(typo).unwrap();
+
+    assert_eq!(
+        recover_from_shares(&[&kit[0], &typo]).unwrap_err(),
+        ShareError::ChecksumMismatch
+    );
+
+    for malformed in [
+        "",
+        "rck1",
+        &kit[0][..kit[0].len() - 1],
+        &kit[0].replace("rck1", "rck2"),
+        &kit[0].replacen('-', "", 5),
+    ] {
+        assert_eq!(
+            recover_from_shares(&[malformed, &kit[1]]).unwrap_err(),
+            ShareError::Malformed
+        );
+    }
+}
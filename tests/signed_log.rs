@@ -0,0 +1,143 @@
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::errors::Error;
+use raycrypt::signed_log::{self, Checkpoint, LogError, SignedLog};
+
+fn key() -> SigningKey {
+    SigningKey::new(&[3u8; 32]).unwrap()
+}
+
+fn log(entries: usize) -> SignedLog {
+    let mut log = SignedLog::new(key(), 4);
+
+    for i in 0..entries {
+        assert_eq!(log.append(format!("entry {}", i).as_bytes()), i as u64);
+    }
+
+    log
+}
+
+#[test]
+fn test_checkpoints() {
+    let mut log = log(10);
+    let public = key().verifying_key();
+
+    assert_eq!(log.len(), 10);
+    assert_eq!(
+        log.checkpoints()
+            .iter()
+            .map(Checkpoint::size)
+            .collect::<Vec<u64>>(),
+        [4, 8]
+    );
+    assert_eq!(
+        signed_log::verify(&public, log.entries(), log.checkpoints()),
+        Ok(8)
+    );
+
+    // closing the log signs the rest, once
+    let last = log.checkpoint();
+    assert_eq!(last.size(), 10);
+    assert_eq!(last.head(), log.head());
+    assert_eq!(log.checkpoint(), last);
+    assert_eq!(log.checkpoints().len(), 3);
+    assert_eq!(
+        signed_log::verify(&public, log.entries(), log.checkpoints()),
+        Ok(10)
+    );
+
+    assert_eq!(Checkpoint::from_bytes(&last.to_bytes()), Ok(last));
+    assert_eq!(
+        Checkpoint::from_bytes(&last.to_bytes()[1..]),
+        Err(LogError::Malformed)
+    );
+
+    let empty = SignedLog::new(key(), 1);
+    assert!(empty.is_empty());
+    assert_eq!(signed_log::verify::<Vec<u8>>(&public, &[], &[]), Ok(0));
+}
+
+#[test]
+fn test_tampering() {
+    let mut log = log(12);
+    let public = key().verifying_key();
+    let checkpoints = log.checkpoints().to_vec();
+
+    let mut edited = log.entries().to_vec();
+    edited[5] = b"entry five".to_vec();
+    assert_eq!(
+        signed_log::verify(&public, &edited, &checkpoints),
+        Err(LogError::Tampered)
+    );
+
+    let mut reordered = log.entries().to_vec();
+    reordered.swap(0, 1);
+    assert_eq!(
+        signed_log::verify(&public, &reordered, &checkpoints),
+        Err(LogError::Tampered)
+    );
+
+    let mut removed = log.entries().to_vec();
+    removed.remove(2);
+    assert_eq!(
+        signed_log::verify(&public, &removed, &checkpoints),
+        Err(LogError::Truncated)
+    );
+    assert_eq!(
+        signed_log::verify(&public, &removed, &checkpoints[..2]),
+        Err(LogError::Tampered)
+    );
+
+    assert_eq!(
+        signed_log::verify(&public, &log.entries()[..11], &checkpoints),
+        Err(LogError::Truncated)
+    );
+
+    // dropping the entries and checkpoint at the end goes unnoticed, unless the reader kept the
+    // checkpoint from an earlier read
+    let kept = log.checkpoint();
+    assert_eq!(
+        signed_log::verify(&public, &log.entries()[..8], &checkpoints[..2]),
+        Ok(8)
+    );
+    assert_eq!(
+        signed_log::verify(&public, &log.entries()[..8], &[checkpoints[0], kept]),
+        Err(LogError::Truncated)
+    );
+
+    let other = SigningKey::new(&[4u8; 32]).unwrap().verifying_key();
+    assert_eq!(
+        signed_log::verify(&other, log.entries(), log.checkpoints()),
+        Err(LogError::InvalidCheckpoint)
+    );
+
+    let mut forged = checkpoints[0].to_bytes();
+    forged[7] = 5;
+    assert_eq!(
+        Checkpoint::from_bytes(&forged).unwrap().verify(&public),
+        Err(LogError::InvalidCheckpoint)
+    );
+
+    assert_eq!(Error::from(LogError::Tampered), Error::InvalidSignature);
+    assert_eq!(Error::from(LogError::Malformed), Error::Format);
+}
+
+#[test]
+fn test_resume() {
+    let log = log(6);
+
+    let mut resumed =
+        SignedLog::resume(key(), 4, log.entries().to_vec(), log.checkpoints().to_vec()).unwrap();
+    assert_eq!(resumed.head(), log.head());
+
+    resumed.append(b"entry 6");
+    resumed.append(b"entry 7");
+    assert_eq!(resumed.checkpoints().len(), 2);
+    assert_eq!(resumed.head(), self::log(8).head());
+
+    let mut edited = log.entries().to_vec();
+    edited[0].push(b'!');
+    assert_eq!(
+        SignedLog::resume(key(), 4, edited, log.checkpoints().to_vec()).unwrap_err(),
+        LogError::Tampered
+    );
+}
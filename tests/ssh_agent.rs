@@ -0,0 +1,158 @@
+#![cfg(unix)]
+
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::hashes::sha256;
+use raycrypt::protocols::ssh_agent::{AgentClient, AgentError, Identity, SshSignature};
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+
+fn string(data: &[u8]) -> Vec<u8> {
+    [&(data.len() as u32).to_be_bytes()[..], data].concat()
+}
+
+fn read_message(stream: &mut UnixStream) -> Vec<u8> {
+    let mut length = [0u8; 4];
+    stream.read_exact(&mut length).unwrap();
+
+    let mut message = vec![0u8; u32::from_be_bytes(length) as usize];
+    stream.read_exact(&mut message).unwrap();
+
+    message
+}
+
+fn write_message(stream: &mut UnixStream, message: &[u8]) {
+    stream.write_all(&string(message)).unwrap();
+}
+
+// a minimal agent holding one plain key and one security key, answering `requests` messages
+fn agent(mut stream: UnixStream, requests: usize) {
+    let key = SigningKey::from_seed(b"agent");
+    let sk = SigningKey::from_seed(b"security key");
+
+    let plain = Identity::new(key.verifying_key(), "plain");
+    let security = Identity::security_key(sk.verifying_key(), "ssh:", "yubikey");
+
+    for _ in 0..requests {
+        let request = read_message(&mut stream);
+
+        match request[0] {
+            11 => {
+                let mut answer = vec![12];
+                answer.extend_from_slice(&3u32.to_be_bytes());
+
+                // an RSA key the client cannot use
+                answer.extend(string(&[string(b"ssh-rsa"), string(&[1, 0, 1])].concat()));
+                answer.extend(string(b"rsa"));
+
+                for identity in [&plain, &security] {
+                    answer.extend(string(&identity.to_blob()));
+                    answer.extend(string(identity.comment().as_bytes()));
+                }
+
+                write_message(&mut stream, &answer);
+            }
+            13 => {
+                let blob_length = u32::from_be_bytes(request[1..5].try_into().unwrap()) as usize;
+                let blob = &request[5..5 + blob_length];
+                let data = &request[9 + blob_length..request.len() - 4];
+
+                let signature = if blob == plain.to_blob() {
+                    SshSignature::new(key.sign(data))
+                } else if blob == security.to_blob() {
+                    let signed = [
+                        &sha256::hash(b"ssh:")[..],
+                        &[0x01],
+                        &7u32.to_be_bytes(),
+                        &sha256::hash(data),
+                    ]
+                    .concat();
+
+                    SshSignature::from_security_key(sk.sign(&signed), 0x01, 7)
+                } else {
+                    write_message(&mut stream, &[5]);
+                    continue;
+                };
+
+                write_message(
+                    &mut stream,
+                    &[&[14][..], &string(&signature.to_blob())].concat(),
+                );
+            }
+            _ => write_message(&mut stream, &[5]),
+        }
+    }
+}
+
+#[test]
+fn test_agent_signing() {
+    let (client, server) = UnixStream::pair().unwrap();
+    let server = std::thread::spawn(move || agent(server, 4));
+
+    let mut agent = AgentClient::new(client);
+    let identities = agent.identities().unwrap();
+
+    assert_eq!(identities.len(), 2);
+    assert_eq!(identities[0].comment(), "plain");
+    assert!(!identities[0].is_security_key());
+    assert_eq!(identities[1].application(), Some("ssh:"));
+    assert!(identities[1].is_security_key());
+
+    let signature = agent.sign(&identities[0], b"message").unwrap();
+    assert_eq!(signature.flags(), None);
+    assert!(identities[0].verify(b"message", &signature).is_ok());
+    assert!(identities[0].verify(b"other", &signature).is_err());
+    assert!(SigningKey::from_seed(b"agent")
+        .verifying_key()
+        .verify(b"message", signature.signature())
+        .is_ok());
+
+    let signature = agent.sign(&identities[1], b"message").unwrap();
+    assert_eq!(signature.flags(), Some(0x01));
+    assert_eq!(signature.counter(), Some(7));
+    assert!(identities[1].verify(b"message", &signature).is_ok());
+    assert!(identities[1].verify(b"other", &signature).is_err());
+    assert!(identities[0].verify(b"message", &signature).is_err());
+
+    let unknown = Identity::new(SigningKey::from_seed(b"unknown").verifying_key(), "");
+    assert_eq!(
+        agent.sign(&unknown, b"message").unwrap_err(),
+        AgentError::Failure
+    );
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_blobs() {
+    let identity =
+        Identity::security_key(SigningKey::from_seed(b"blob").verifying_key(), "ssh:", "");
+    let blob = identity.to_blob();
+
+    assert_eq!(blob[..30], string(b"sk-ssh-ed25519@openssh.com"));
+    assert_eq!(Identity::from_blob(&blob, "").unwrap(), Some(identity));
+    assert_eq!(
+        Identity::from_blob(&blob[..blob.len() - 1], ""),
+        Err(AgentError::Malformed)
+    );
+    assert_eq!(
+        Identity::from_blob(&string(b"ecdsa-sha2-nistp256"), ""),
+        Ok(None)
+    );
+
+    let signature = SshSignature::from_security_key([9u8; 64], 0x05, 1);
+    assert_eq!(SshSignature::from_blob(&signature.to_blob()), Ok(signature));
+    assert_eq!(
+        SshSignature::from_blob(&SshSignature::new([9u8; 64]).to_blob()[..50]),
+        Err(AgentError::Malformed)
+    );
+}
+
+#[test]
+fn test_agent_closed() {
+    let (client, server) = UnixStream::pair().unwrap();
+    drop(server);
+
+    let mut agent = AgentClient::new(client);
+
+    assert!(matches!(agent.identities(), Err(AgentError::Io(_))));
+}
@@ -0,0 +1,138 @@
+use raycrypt::aeads::stream::{decrypt, encrypt, StreamDecryptor, StreamEncryptor, StreamError};
+use raycrypt::aeads::XChaCha20Poly1305;
+
+const KEY: [u8; 32] = [1u8; 32];
+const PREFIX: [u8; 19] = [2u8; 19];
+
+#[test]
+fn test_roundtrip() {
+    for length in [0usize, 1, 63, 64, 65, 200, 256] {
+        let msg: Vec<u8> = (0..length).map(|i| i as u8).collect();
+        let ct = encrypt(&KEY, &PREFIX, &msg, b"ad", 64).unwrap();
+
+        assert_eq!(ct.len(), msg.len() + length.div_ceil(64).max(1) * 16);
+        assert_eq!(decrypt(&KEY, &PREFIX, &ct, b"ad", 64).unwrap(), msg);
+        assert_eq!(
+            decrypt(&KEY, &PREFIX, &ct, b"other", 64),
+            Err(StreamError::InvalidMac)
+        );
+    }
+}
+
+#[test]
+fn test_nonce_layout() {
+    let mut encryptor = StreamEncryptor::new(&KEY, &PREFIX);
+    let first = encryptor.encrypt_next(b"first", b"").unwrap();
+    let second = encryptor.encrypt_last(b"second", b"").unwrap();
+
+    let aead = XChaCha20Poly1305::new(&KEY);
+    let nonce = |counter: u32, last: u8| [&PREFIX[..], &counter.to_be_bytes(), &[last]].concat();
+
+    assert_eq!(first, *aead.encrypt(b"first", &nonce(0, 0), b""));
+    assert_eq!(second, *aead.encrypt(b"second", &nonce(1, 1), b""));
+}
+
+#[test]
+fn test_truncation() {
+    let msg = [7u8; 200];
+    let ct = encrypt(&KEY, &PREFIX, &msg, b"", 64).unwrap();
+
+    assert_eq!(
+        decrypt(&KEY, &PREFIX, &ct[..160], b"", 64),
+        Err(StreamError::Truncated)
+    );
+    assert_eq!(
+        decrypt(&KEY, &PREFIX, &ct[..150], b"", 64),
+        Err(StreamError::InvalidMac)
+    );
+    assert_eq!(
+        decrypt(&KEY, &PREFIX, &[], b"", 64),
+        Err(StreamError::Truncated)
+    );
+}
+
+#[test]
+fn test_chunk_size() {
+    let ct = encrypt(&KEY, &PREFIX, b"message", b"", 64).unwrap();
+
+    assert_eq!(
+        encrypt(&KEY, &PREFIX, b"message", b"", 0),
+        Err(StreamError::InvalidChunkSize)
+    );
+    assert_eq!(
+        encrypt(&KEY, &PREFIX, b"", b"", 0),
+        Err(StreamError::InvalidChunkSize)
+    );
+    assert_eq!(
+        decrypt(&KEY, &PREFIX, &ct, b"", 0),
+        Err(StreamError::InvalidChunkSize)
+    );
+    assert_eq!(
+        decrypt(&KEY, &PREFIX, &ct, b"", usize::MAX),
+        Err(StreamError::InvalidChunkSize)
+    );
+}
+
+#[test]
+fn test_reordering() {
+    let mut encryptor = StreamEncryptor::new(&KEY, &PREFIX);
+    let first = encryptor.encrypt_next(b"first", b"").unwrap();
+    let second = encryptor.encrypt_next(b"second", b"").unwrap();
+    let last = encryptor.encrypt_last(b"", b"").unwrap();
+
+    let mut decryptor = StreamDecryptor::new(&KEY, &PREFIX);
+    assert_eq!(
+        decryptor.decrypt_next(&second, b""),
+        Err(StreamError::InvalidMac)
+    );
+    assert_eq!(decryptor.decrypt_next(&first, b"").unwrap(), b"first");
+    assert_eq!(decryptor.decrypt_next(&second, b"").unwrap(), b"second");
+    assert_eq!(decryptor.decrypt_last(&last, b"").unwrap(), b"");
+}
+
+#[test]
+fn test_rekey() {
+    let mut encryptor = StreamEncryptor::new(&KEY, &PREFIX);
+    let first = encryptor.encrypt_next(b"first", b"").unwrap();
+    encryptor.rekey();
+    let second = encryptor.encrypt_next(b"second", b"").unwrap();
+    let last = encryptor.encrypt_last(b"last", b"").unwrap();
+
+    let mut decryptor = StreamDecryptor::new(&KEY, &PREFIX);
+    assert_eq!(decryptor.decrypt_next(&first, b"").unwrap(), b"first");
+    assert_eq!(
+        decryptor.decrypt_next(&second, b""),
+        Err(StreamError::InvalidMac)
+    );
+
+    decryptor.rekey();
+    assert_eq!(decryptor.decrypt_next(&second, b"").unwrap(), b"second");
+    assert_eq!(decryptor.decrypt_last(&last, b"").unwrap(), b"last");
+}
+
+#[test]
+fn test_automatic_rekey() {
+    let mut manual = StreamEncryptor::new(&KEY, &PREFIX);
+    let mut by_messages = StreamEncryptor::new(&KEY, &PREFIX).rekey_after_messages(2);
+    let mut by_bytes = StreamEncryptor::new(&KEY, &PREFIX).rekey_after_bytes(10);
+
+    for i in 0..6 {
+        let chunk = [i; 5];
+
+        let expected = manual.encrypt_next(&chunk, b"").unwrap();
+        assert_eq!(by_messages.encrypt_next(&chunk, b"").unwrap(), expected);
+        assert_eq!(by_bytes.encrypt_next(&chunk, b"").unwrap(), expected);
+
+        if i % 2 == 1 {
+            manual.rekey();
+        }
+    }
+
+    let mut encryptor = StreamEncryptor::new(&KEY, &PREFIX).rekey_after_messages(3);
+    let mut decryptor = StreamDecryptor::new(&KEY, &PREFIX).rekey_after_messages(3);
+
+    for i in 0..10 {
+        let ct = encryptor.encrypt_next(&[i; 17], b"").unwrap();
+        assert_eq!(decryptor.decrypt_next(&ct, b"").unwrap(), [i; 17]);
+    }
+}
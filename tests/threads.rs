@@ -0,0 +1,274 @@
+use raycrypt::aeads::archive::{ArchiveError, ArchiveWriter, EncryptedArchive, Entry};
+use raycrypt::aeads::backup::{Backup, BackupError, Snapshot};
+use raycrypt::aeads::envelope::{EnvelopeError, LocalKeyProvider};
+use raycrypt::aeads::etm::EtM;
+use raycrypt::aeads::ndarray::NdArrayError;
+use raycrypt::aeads::object::{CborCodec, ObjectError};
+use raycrypt::aeads::page::PageCodec;
+use raycrypt::aeads::rotating::{RotatingKey, RotationError};
+use raycrypt::aeads::stream::{StreamDecryptor, StreamEncryptor, StreamError};
+use raycrypt::aeads::threshold::{DecryptionShare, ThresholdError};
+use raycrypt::aeads::{
+    AeadAlgorithm, AeadDecryptor, AeadEncryptor, AesGcm, AesOcb, ChaCha20Poly1305,
+    ChaCha20Poly1305Original, ConvergentCipher, RoundtripFailure, SealError, SealHeader,
+    SymmetricKey, XChaCha20Poly1305,
+};
+use raycrypt::audit::{AuditEvent, KeyId, SinkAlreadySet};
+use raycrypt::cancel::CancelToken;
+use raycrypt::cbor::{self, CborError};
+use raycrypt::chunking::{ChunkError, ChunkRef, Chunker, Manifest};
+use raycrypt::ciphers::aes::ctr::AesCtr;
+use raycrypt::ciphers::aes::Aes;
+use raycrypt::ciphers::chacha::{ChaCha20, ChaChaError, HChaCha20};
+use raycrypt::ciphers::salsa::{Salsa20, XSalsa20};
+use raycrypt::ciphers::xchacha::XChaCha20;
+use raycrypt::ecc::blind::{BlindError, BlindSigner, Blinding};
+use raycrypt::ecc::fingerprint::{Fingerprint, InvalidFingerprintLength};
+use raycrypt::ecc::keyfile::KeyFileError;
+use raycrypt::ecc::pedersen::{Commitment, InvalidOpening};
+use raycrypt::ecc::points::{EdwardsPoint, MontgomeryPoint, PointError};
+use raycrypt::ecc::rangeproof::{RangeProof, RangeProofError};
+use raycrypt::ecc::ring::{self, KeyImage, LinkableSignature, RingError};
+use raycrypt::ecc::ristretto::RistrettoPoint;
+use raycrypt::ecc::scalar::Scalar;
+use raycrypt::ecc::{ed25519, x25519};
+use raycrypt::errors::{Error, InvalidMac, InvalidSignature};
+use raycrypt::hashes::blake2b::Blake2b;
+use raycrypt::hashes::blake2s::Blake2s;
+use raycrypt::hashes::blake3::Blake3;
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::hashes::sha512::Sha512;
+use raycrypt::kdfs::argon2::{Argon2, Argon2Error};
+use raycrypt::kdfs::fido2::{InvalidBinding, TokenBinding};
+use raycrypt::kdfs::hierarchy::{HierarchyError, KeyHierarchy};
+use raycrypt::kdfs::hkdf::Hkdf;
+use raycrypt::kdfs::ratchet::KeyRatchet;
+use raycrypt::macs::ghash::GHash;
+use raycrypt::macs::hmac::Hmac;
+use raycrypt::macs::keyed::KeyedHash;
+use raycrypt::macs::poly1305::Poly1305;
+use raycrypt::macs::polyval::Polyval;
+use raycrypt::merkle::{MerkleError, SignedTreeHead};
+use raycrypt::nacl;
+use raycrypt::openpgp;
+use raycrypt::openssl::OpensslError;
+use raycrypt::pre::{self, PreError, ReEncryptionKey};
+use raycrypt::protocols::prekeys::{
+    OneTimePrekey, PrekeyBundle, PrekeyDirectory, PrekeyError, PrekeyStore, SignedPrekey,
+};
+use raycrypt::protocols::quic::{InitialSecrets, PacketKeys, QuicError};
+use raycrypt::protocols::record::{NonceSequence, RecordError, RecordProtection};
+use raycrypt::protocols::replay::ReplayWindow;
+use raycrypt::protocols::sender_key::{
+    SenderKey, SenderKeyDistribution, SenderKeyError, SenderKeyReceiver,
+};
+use raycrypt::protocols::ssh_agent::{AgentError, Identity, SshSignature};
+use raycrypt::protocols::ticket::{SessionTicket, TicketError};
+use raycrypt::protocols::tls13::{KeySchedule, Tls13Error};
+use raycrypt::protocols::transcript::Transcript;
+use raycrypt::protocols::treekem::{Commit, Group, KeyPackage, Proposal, TreeKemError, Welcome};
+use raycrypt::protocols::wireguard::{HandshakeError, Initiator, Responder, TransportKeys};
+use raycrypt::rng::{DeterministicRng, OsRng};
+use raycrypt::secret::SecretString;
+use raycrypt::shamir::{Share, ShareError};
+use raycrypt::signed_log::{Checkpoint, LogError, SignedLog};
+use raycrypt::x509::Certificate;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_public_types_are_send_sync() {
+    assert_send_sync::<AesGcm>();
+    assert_send_sync::<AesOcb>();
+    assert_send_sync::<ConvergentCipher>();
+    assert_send_sync::<Aes>();
+    assert_send_sync::<AesCtr>();
+    assert_send_sync::<EtM<AesCtr, Hmac<Sha256>>>();
+    assert_send_sync::<ChaCha20Poly1305>();
+    assert_send_sync::<ChaCha20Poly1305Original>();
+    assert_send_sync::<XChaCha20Poly1305>();
+    assert_send_sync::<SymmetricKey>();
+    assert_send_sync::<StreamEncryptor>();
+    assert_send_sync::<StreamDecryptor>();
+    assert_send_sync::<ArchiveWriter<Vec<u8>>>();
+    assert_send_sync::<EncryptedArchive<std::io::Cursor<Vec<u8>>>>();
+    assert_send_sync::<Entry>();
+    assert_send_sync::<PageCodec>();
+    assert_send_sync::<CborCodec>();
+    assert_send_sync::<PrekeyStore>();
+    assert_send_sync::<PrekeyDirectory>();
+    assert_send_sync::<PrekeyBundle>();
+    assert_send_sync::<SignedPrekey>();
+    assert_send_sync::<OneTimePrekey>();
+    assert_send_sync::<AeadEncryptor>();
+    assert_send_sync::<AeadDecryptor>();
+    assert_send_sync::<LocalKeyProvider>();
+    assert_send_sync::<ChaCha20>();
+    assert_send_sync::<HChaCha20>();
+    assert_send_sync::<XChaCha20>();
+    assert_send_sync::<Salsa20>();
+    assert_send_sync::<XSalsa20>();
+
+    assert_send_sync::<x25519::PrivateKey>();
+    assert_send_sync::<x25519::Keypair>();
+    assert_send_sync::<ed25519::SigningKey>();
+    assert_send_sync::<ed25519::VerifyingKey>();
+    assert_send_sync::<ed25519::VerificationMode>();
+    assert_send_sync::<ed25519::Keypair>();
+    assert_send_sync::<ed25519::InvalidContext>();
+    assert_send_sync::<Fingerprint>();
+    assert_send_sync::<InvalidFingerprintLength>();
+    assert_send_sync::<KeyFileError>();
+    assert_send_sync::<EdwardsPoint>();
+    assert_send_sync::<MontgomeryPoint>();
+    assert_send_sync::<PointError>();
+    assert_send_sync::<Scalar>();
+    assert_send_sync::<RistrettoPoint>();
+    assert_send_sync::<Commitment>();
+    assert_send_sync::<InvalidOpening>();
+    assert_send_sync::<ring::SecretKey>();
+    assert_send_sync::<ring::PublicKey>();
+    assert_send_sync::<ring::Signature>();
+    assert_send_sync::<LinkableSignature>();
+    assert_send_sync::<KeyImage>();
+    assert_send_sync::<RingError>();
+    assert_send_sync::<BlindSigner>();
+    assert_send_sync::<Blinding>();
+    assert_send_sync::<BlindError>();
+    assert_send_sync::<RangeProof>();
+    assert_send_sync::<RangeProofError>();
+
+    assert_send_sync::<Sha256>();
+    assert_send_sync::<Sha512>();
+    assert_send_sync::<Blake2b>();
+    assert_send_sync::<Blake2s>();
+    assert_send_sync::<Blake3>();
+    assert_send_sync::<Hmac<Sha256>>();
+    assert_send_sync::<Poly1305>();
+    assert_send_sync::<GHash>();
+    assert_send_sync::<Polyval>();
+    assert_send_sync::<Hkdf<Sha256>>();
+    assert_send_sync::<KeyRatchet>();
+    assert_send_sync::<KeyedHash>();
+    assert_send_sync::<DeterministicRng>();
+    assert_send_sync::<OsRng>();
+    assert_send_sync::<SecretString>();
+    assert_send_sync::<RotatingKey>();
+    assert_send_sync::<Argon2>();
+    assert_send_sync::<KeyHierarchy>();
+    assert_send_sync::<TokenBinding>();
+    assert_send_sync::<Share>();
+    assert_send_sync::<pre::SecretKey>();
+    assert_send_sync::<pre::PublicKey>();
+    assert_send_sync::<ReEncryptionKey>();
+    assert_send_sync::<SenderKey>();
+    assert_send_sync::<SenderKeyDistribution>();
+    assert_send_sync::<SenderKeyReceiver>();
+    assert_send_sync::<Group>();
+    assert_send_sync::<KeyPackage>();
+    assert_send_sync::<Commit>();
+    assert_send_sync::<Welcome>();
+    assert_send_sync::<Proposal>();
+    assert_send_sync::<CancelToken>();
+    assert_send_sync::<KeyId>();
+    assert_send_sync::<AuditEvent>();
+
+    assert_send_sync::<InitialSecrets>();
+    assert_send_sync::<PacketKeys>();
+    assert_send_sync::<QuicError>();
+    assert_send_sync::<RecordProtection>();
+    assert_send_sync::<NonceSequence>();
+    assert_send_sync::<ReplayWindow>();
+    assert_send_sync::<SessionTicket>();
+    assert_send_sync::<Identity>();
+    assert_send_sync::<SshSignature>();
+    assert_send_sync::<TicketError>();
+    assert_send_sync::<KeySchedule<Sha256>>();
+    assert_send_sync::<Tls13Error>();
+    assert_send_sync::<Transcript<Sha256>>();
+    assert_send_sync::<Initiator>();
+    assert_send_sync::<Responder>();
+    assert_send_sync::<TransportKeys>();
+    assert_send_sync::<Certificate>();
+    assert_send_sync::<nacl::SecretBox>();
+    assert_send_sync::<nacl::Box>();
+    assert_send_sync::<nacl::SealedBox>();
+    assert_send_sync::<nacl::SigningKey>();
+    assert_send_sync::<nacl::VerifyKey>();
+    assert_send_sync::<openpgp::Certificate>();
+    assert_send_sync::<openpgp::SecretKey>();
+    assert_send_sync::<openpgp::LiteralData>();
+    assert_send_sync::<cbor::Value>();
+
+    assert_send_sync::<InvalidMac>();
+    assert_send_sync::<InvalidSignature>();
+    assert_send_sync::<Argon2Error>();
+    assert_send_sync::<HierarchyError>();
+    assert_send_sync::<InvalidBinding>();
+    assert_send_sync::<ShareError>();
+    assert_send_sync::<MerkleError>();
+    assert_send_sync::<SignedTreeHead>();
+    assert_send_sync::<Checkpoint>();
+    assert_send_sync::<LogError>();
+    assert_send_sync::<SignedLog>();
+    assert_send_sync::<SinkAlreadySet>();
+    assert_send_sync::<PreError>();
+    assert_send_sync::<DecryptionShare>();
+    assert_send_sync::<ThresholdError>();
+    assert_send_sync::<ArchiveError>();
+    assert_send_sync::<Backup<std::fs::File>>();
+    assert_send_sync::<BackupError>();
+    assert_send_sync::<Snapshot>();
+    assert_send_sync::<CborError>();
+    assert_send_sync::<ChunkError>();
+    assert_send_sync::<ChunkRef>();
+    assert_send_sync::<Chunker>();
+    assert_send_sync::<Manifest>();
+    assert_send_sync::<NdArrayError>();
+    assert_send_sync::<RotationError>();
+    assert_send_sync::<AeadAlgorithm>();
+    assert_send_sync::<SealError>();
+    assert_send_sync::<SealHeader>();
+    assert_send_sync::<RoundtripFailure>();
+    assert_send_sync::<ObjectError<CborError>>();
+    assert_send_sync::<PrekeyError>();
+    assert_send_sync::<SenderKeyError>();
+    assert_send_sync::<TreeKemError>();
+    assert_send_sync::<AgentError>();
+    assert_send_sync::<openpgp::OpenPgpError>();
+    assert_send_sync::<OpensslError>();
+    assert_send_sync::<EnvelopeError<InvalidMac>>();
+    assert_send_sync::<Error>();
+    assert_send_sync::<ChaChaError>();
+    assert_send_sync::<StreamError>();
+    assert_send_sync::<RecordError>();
+    assert_send_sync::<HandshakeError>();
+}
+
+#[test]
+fn test_shared_across_threads() {
+    let key = ed25519::SigningKey::from_seed(b"threads");
+    let cipher = ChaCha20Poly1305::new(&[7u8; 32]);
+
+    std::thread::scope(|scope| {
+        for i in 0..8u8 {
+            let key = &key;
+            let cipher = &cipher;
+
+            scope.spawn(move || {
+                let nonce = [i; 12];
+                let ct = cipher.encrypt(&[i; 100], &nonce, b"");
+                assert_eq!(cipher.decrypt(&ct, &nonce, b"").unwrap(), [i; 100]);
+
+                let signature = key.sign(&[i]);
+                assert!(key.verifying_key().verify(&[i], &signature).is_ok());
+            });
+        }
+    });
+}
+
+#[test]
+#[should_panic]
+fn test_short_key_rejected() {
+    ChaCha20Poly1305::new(&[0u8; 16]);
+}
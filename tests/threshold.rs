@@ -0,0 +1,187 @@
+use raycrypt::aeads::threshold::{self, DecryptionShare, ThresholdError};
+use raycrypt::ecc::x25519::{self, PrivateKey, PublicKey};
+
+fn recipients(n: usize) -> (Vec<PrivateKey>, Vec<PublicKey>) {
+    let keys: Vec<PrivateKey> = (0..n).map(|_| x25519::keygen(None)).collect();
+    let public = keys.iter().map(|key| key.public_key()).collect();
+
+    (keys, public)
+}
+
+#[test]
+fn test_any_threshold_subset_opens() {
+    let (keys, public) = recipients(5);
+    let envelope = threshold::seal(&public, 3, b"hello", b"ad").unwrap();
+
+    let shares: Vec<DecryptionShare> = keys
+        .iter()
+        .map(|key| threshold::decrypt_share(&envelope, key).unwrap())
+        .collect();
+
+    for (i, share) in shares.iter().enumerate() {
+        assert_eq!(share.index(), i as u8 + 1);
+    }
+
+    for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+        let chosen: Vec<DecryptionShare> = subset.iter().map(|&i| shares[i].clone()).collect();
+        assert_eq!(
+            threshold::open(&envelope, &chosen, b"ad").unwrap(),
+            b"hello"
+        );
+    }
+
+    assert_eq!(
+        threshold::open(&envelope, &shares, b"ad").unwrap(),
+        b"hello"
+    );
+    assert_eq!(
+        threshold::open(&envelope, &shares, b"other"),
+        Err(ThresholdError::InvalidMac)
+    );
+}
+
+#[test]
+fn test_below_threshold() {
+    let (keys, public) = recipients(3);
+    let envelope = threshold::seal(&public, 2, b"hello", b"").unwrap();
+    let share = threshold::decrypt_share(&envelope, &keys[1]).unwrap();
+
+    assert_eq!(
+        threshold::open(&envelope, std::slice::from_ref(&share), b""),
+        Err(ThresholdError::NotEnoughShares)
+    );
+    assert_eq!(
+        threshold::open(&envelope, &[share.clone(), share], b""),
+        Err(ThresholdError::DuplicateShare)
+    );
+}
+
+#[test]
+fn test_one_of_one() {
+    let (keys, public) = recipients(1);
+    let envelope = threshold::seal(&public, 1, b"hello", b"").unwrap();
+    let share = threshold::decrypt_share(&envelope, &keys[0]).unwrap();
+
+    assert_eq!(threshold::open(&envelope, &[share], b"").unwrap(), b"hello");
+}
+
+#[test]
+fn test_invalid_threshold() {
+    let (_, public) = recipients(3);
+
+    assert_eq!(
+        threshold::seal(&public, 0, b"", b""),
+        Err(ThresholdError::InvalidThreshold)
+    );
+    assert_eq!(
+        threshold::seal(&public, 4, b"", b""),
+        Err(ThresholdError::InvalidThreshold)
+    );
+}
+
+#[test]
+fn test_not_a_recipient() {
+    let (_, public) = recipients(2);
+    let envelope = threshold::seal(&public, 2, b"hello", b"").unwrap();
+
+    assert_eq!(
+        threshold::decrypt_share(&envelope, &x25519::keygen(None)).unwrap_err(),
+        ThresholdError::NotARecipient
+    );
+}
+
+#[test]
+fn test_forged_share_is_caught() {
+    let (keys, public) = recipients(3);
+    let envelope = threshold::seal(&public, 2, b"hello", b"").unwrap();
+
+    let good = threshold::decrypt_share(&envelope, &keys[0]).unwrap();
+    let mut bytes = threshold::decrypt_share(&envelope, &keys[1])
+        .unwrap()
+        .to_bytes();
+    bytes[1] ^= 1;
+    let forged = DecryptionShare::from_bytes(&bytes).unwrap();
+
+    assert_eq!(
+        threshold::open(&envelope, &[good.clone(), forged], b""),
+        Err(ThresholdError::InvalidShare)
+    );
+
+    // a real share, claimed at another recipient's index
+    let mut bytes = good.to_bytes();
+    bytes[0] = 3;
+    let moved = DecryptionShare::from_bytes(&bytes).unwrap();
+
+    assert_eq!(
+        threshold::open(&envelope, &[good, moved], b""),
+        Err(ThresholdError::InvalidShare)
+    );
+}
+
+#[test]
+fn test_shares_do_not_cross_envelopes() {
+    let (keys, public) = recipients(2);
+    let first = threshold::seal(&public, 2, b"hello", b"").unwrap();
+    let second = threshold::seal(&public, 2, b"hello", b"").unwrap();
+
+    let shares = [
+        threshold::decrypt_share(&first, &keys[0]).unwrap(),
+        threshold::decrypt_share(&first, &keys[1]).unwrap(),
+    ];
+
+    assert_eq!(
+        threshold::open(&second, &shares, b""),
+        Err(ThresholdError::InvalidShare)
+    );
+}
+
+#[test]
+fn test_share_roundtrip() {
+    let (keys, public) = recipients(2);
+    let envelope = threshold::seal(&public, 2, b"hello", b"").unwrap();
+    let share = threshold::decrypt_share(&envelope, &keys[1]).unwrap();
+
+    let parsed = DecryptionShare::from_bytes(&share.to_bytes()).unwrap();
+    assert_eq!(parsed.index(), 2);
+    assert_eq!(parsed.to_bytes(), share.to_bytes());
+
+    assert_eq!(
+        DecryptionShare::from_bytes(&[0u8; 33]).unwrap_err(),
+        ThresholdError::Malformed
+    );
+    assert_eq!(
+        DecryptionShare::from_bytes(&[1u8; 32]).unwrap_err(),
+        ThresholdError::Malformed
+    );
+}
+
+#[test]
+fn test_tampered_envelope() {
+    let (keys, public) = recipients(2);
+    let envelope = threshold::seal(&public, 2, b"hello", b"").unwrap();
+
+    // raising the threshold byte changes the layout
+    let mut raised = envelope.clone();
+    raised[0] = 3;
+    assert_eq!(
+        threshold::decrypt_share(&raised, &keys[0]).unwrap_err(),
+        ThresholdError::Malformed
+    );
+
+    let mut body = envelope.clone();
+    let last = body.len() - 1;
+    body[last] ^= 1;
+    let shares = [
+        threshold::decrypt_share(&body, &keys[0]).unwrap(),
+        threshold::decrypt_share(&body, &keys[1]).unwrap(),
+    ];
+    assert_eq!(
+        threshold::open(&body, &shares, b""),
+        Err(ThresholdError::InvalidMac)
+    );
+
+    assert_eq!(
+        threshold::decrypt_share(&envelope[..40], &keys[0]).unwrap_err(),
+        ThresholdError::Malformed
+    );
+}
@@ -0,0 +1,86 @@
+use raycrypt::aeads::SymmetricKey;
+use raycrypt::protocols::ticket::{SessionTicket, TicketError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn at(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+#[test]
+fn test_roundtrip_and_expiry() {
+    let tickets = SessionTicket::new(Duration::from_secs(3600));
+    let ticket = tickets.seal(b"session state", at(1_000_000));
+
+    assert_eq!(ticket[..4], tickets.key_id());
+    assert_eq!(ticket[4..12], 1_003_600u64.to_be_bytes());
+
+    assert_eq!(
+        tickets.open(&ticket, at(1_003_600)).unwrap(),
+        b"session state"
+    );
+    assert_eq!(
+        tickets.open(&ticket, at(1_003_601)),
+        Err(TicketError::Expired)
+    );
+    assert_ne!(ticket, tickets.seal(b"session state", at(1_000_000)));
+}
+
+#[test]
+fn test_tampering() {
+    let tickets = SessionTicket::new(Duration::from_secs(60));
+    let ticket = tickets.seal(b"state", at(100));
+
+    // pushing the expiry out must break the tag
+    let mut extended = ticket.clone();
+    extended[11] ^= 0xff;
+    assert_eq!(
+        tickets.open(&extended, at(100)),
+        Err(TicketError::InvalidMac)
+    );
+
+    let mut flipped = ticket.clone();
+    *flipped.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        tickets.open(&flipped, at(100)),
+        Err(TicketError::InvalidMac)
+    );
+
+    let mut renamed = ticket.clone();
+    renamed[0] ^= 1;
+    assert_eq!(
+        tickets.open(&renamed, at(100)),
+        Err(TicketError::UnknownKey)
+    );
+
+    assert_eq!(
+        tickets.open(&ticket[..51], at(100)),
+        Err(TicketError::Malformed)
+    );
+}
+
+#[test]
+fn test_rotation() {
+    let mut tickets = SessionTicket::new(Duration::from_secs(60));
+    let first = tickets.seal(b"first", at(0));
+
+    tickets.rotate();
+    let second = tickets.seal(b"second", at(0));
+    assert_eq!(tickets.open(&first, at(0)).unwrap(), b"first");
+
+    tickets.rotate();
+    assert_eq!(tickets.open(&first, at(0)), Err(TicketError::UnknownKey));
+    assert_eq!(tickets.open(&second, at(0)).unwrap(), b"second");
+}
+
+#[test]
+fn test_shared_keys() {
+    let key = SymmetricKey::new(&[9u8; 32]).unwrap();
+
+    let mut issuer = SessionTicket::new(Duration::from_secs(60));
+    let mut other = SessionTicket::new(Duration::from_secs(60));
+    issuer.rotate_to([1, 2, 3, 4], &key);
+    other.rotate_to([1, 2, 3, 4], &key);
+
+    let ticket = issuer.seal(b"state", at(0));
+    assert_eq!(other.open(&ticket, at(30)).unwrap(), b"state");
+}
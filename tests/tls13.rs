@@ -0,0 +1,90 @@
+use hex::decode;
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::protocols::tls13::{hkdf_expand_label, traffic_keys, KeySchedule, Stage, Tls13Error};
+
+// RFC 8448, section 3 (simple 1-RTT handshake)
+#[test]
+fn test_rfc8448_key_schedule() {
+    let shared =
+        decode("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d").unwrap();
+    let transcript =
+        decode("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8").unwrap();
+
+    let mut schedule = KeySchedule::<Sha256>::new(None);
+
+    assert_eq!(
+        schedule.secret(),
+        decode("33ad0a1c607ec03b09e6cd9893680ce210adf300aa1f2660e1b22e10f170f92a").unwrap()
+    );
+
+    schedule.handshake(&shared).unwrap();
+
+    assert_eq!(schedule.stage(), Stage::Handshake);
+    assert_eq!(
+        schedule.secret(),
+        decode("1dc826e93606aa6fdc0aadc12f741b01046aa6b99f691ed221a9f0ca043fbeac").unwrap()
+    );
+    assert_eq!(
+        schedule.derive_secret("c hs traffic", &transcript).unwrap(),
+        decode("b3eddb126e067f35a780b3abf45e2d8f3b1a950738f52e9600746a0e27a55a21").unwrap()
+    );
+
+    let server = schedule.derive_secret("s hs traffic", &transcript).unwrap();
+    assert_eq!(
+        server,
+        decode("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38").unwrap()
+    );
+
+    let (key, iv) = traffic_keys::<Sha256>(&server, 16, 12).unwrap();
+    assert_eq!(key, decode("3fce516009c21727d0f2e4e86ee403bc").unwrap());
+    assert_eq!(iv, decode("5d313eb2671276ee13000b30").unwrap());
+
+    schedule.application().unwrap();
+
+    assert_eq!(
+        schedule.secret(),
+        decode("18df06843d13a08bf2a449844c5f8a478001bc4d4c627984d5a41da8d0402919").unwrap()
+    );
+}
+
+#[test]
+fn test_out_of_order() {
+    let mut schedule = KeySchedule::<Sha256>::new(None);
+    assert_eq!(schedule.application(), Err(Tls13Error::WrongStage));
+    assert_eq!(schedule.stage(), Stage::Early);
+
+    schedule.handshake(&[0u8; 32]).unwrap();
+    assert_eq!(schedule.handshake(&[0u8; 32]), Err(Tls13Error::WrongStage));
+
+    schedule.application().unwrap();
+    assert_eq!(schedule.application(), Err(Tls13Error::WrongStage));
+    assert_eq!(schedule.stage(), Stage::Application);
+}
+
+#[test]
+fn test_oversized_labels() {
+    let secret = [0u8; 32];
+
+    // "tls13 " plus 249 bytes is the longest label that fits
+    assert!(hkdf_expand_label::<Sha256>(&secret, &"a".repeat(249), &[], 32).is_ok());
+    assert_eq!(
+        hkdf_expand_label::<Sha256>(&secret, &"a".repeat(250), &[], 32),
+        Err(Tls13Error::InvalidLength)
+    );
+
+    assert!(hkdf_expand_label::<Sha256>(&secret, "key", &[0u8; 255], 32).is_ok());
+    assert_eq!(
+        hkdf_expand_label::<Sha256>(&secret, "key", &[0u8; 256], 32),
+        Err(Tls13Error::InvalidLength)
+    );
+
+    // more than HKDF can expand to, and more than the u16 length field holds
+    assert_eq!(
+        traffic_keys::<Sha256>(&secret, 255 * 32 + 1, 12),
+        Err(Tls13Error::InvalidLength)
+    );
+    assert_eq!(
+        hkdf_expand_label::<Sha256>(&secret, "key", &[], 65536),
+        Err(Tls13Error::InvalidLength)
+    );
+}
@@ -0,0 +1,63 @@
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::protocols::transcript::Transcript;
+
+fn framed(label: &[u8], msg: &[u8]) -> Vec<u8> {
+    [
+        &(label.len() as u64).to_be_bytes()[..],
+        label,
+        &(msg.len() as u64).to_be_bytes(),
+        msg,
+    ]
+    .concat()
+}
+
+#[test]
+fn test_encoding() {
+    let mut transcript = Transcript::<Sha256>::new(b"example");
+    transcript.append(b"hello", b"world");
+
+    let expected = raycrypt::hashes::sha256::hash(
+        &[
+            framed(b"raycrypt transcript v1", b"example"),
+            framed(b"hello", b"world"),
+        ]
+        .concat(),
+    );
+
+    assert_eq!(transcript.hash(), expected);
+    assert_eq!(transcript.finish(), expected);
+}
+
+#[test]
+fn test_domain_separation() {
+    let mut a = Transcript::<Sha256>::new(b"protocol a");
+    let mut b = Transcript::<Sha256>::new(b"protocol b");
+    a.append(b"msg", b"data");
+    b.append(b"msg", b"data");
+    assert_ne!(a.hash(), b.hash());
+
+    let mut a = Transcript::<Sha256>::new(b"protocol");
+    let mut b = Transcript::<Sha256>::new(b"protocol");
+    a.append(b"ab", b"c");
+    b.append(b"a", b"bc");
+    assert_ne!(a.hash(), b.hash());
+}
+
+#[test]
+fn test_fork() {
+    let mut transcript = Transcript::<Sha256>::new(b"protocol");
+    transcript.append(b"client hello", b"...");
+
+    let mut initiator = transcript.fork(b"initiator");
+    let responder = transcript.fork(b"responder");
+    assert_ne!(initiator.hash(), responder.hash());
+    assert_eq!(initiator.hash(), transcript.fork(b"initiator").hash());
+
+    let mut appended = transcript.clone();
+    appended.append(b"fork", b"initiator");
+    assert_ne!(transcript.fork(b"initiator").hash(), appended.hash());
+
+    let before = transcript.hash();
+    initiator.append(b"server hello", b"...");
+    assert_eq!(transcript.hash(), before);
+}
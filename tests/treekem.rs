@@ -0,0 +1,225 @@
+use raycrypt::protocols::treekem::{Commit, Group, KeyPackage, Proposal, TreeKemError, Welcome};
+
+fn transmit(commit: &Commit) -> Commit {
+    Commit::from_bytes(&commit.to_bytes()).unwrap()
+}
+
+fn assert_agree(groups: &[&Group]) {
+    let secret = groups[0].export_secret(b"app", 32).unwrap();
+
+    for group in groups {
+        assert_eq!(group.epoch(), groups[0].epoch());
+        assert_eq!(group.members(), groups[0].members());
+        assert_eq!(group.export_secret(b"app", 32).unwrap(), secret);
+        assert_eq!(group.epoch_authenticator(), groups[0].epoch_authenticator());
+    }
+}
+
+// adds a new member through `committer` and brings everyone else along
+fn add(committer: &mut Group, others: &mut [Group]) -> Group {
+    let key_package = KeyPackage::generate();
+    let (commit, welcome) = committer
+        .commit(&[Proposal::Add(key_package.public_key())])
+        .unwrap();
+
+    for other in others.iter_mut() {
+        other.process_commit(&transmit(&commit)).unwrap();
+    }
+
+    let welcome = Welcome::from_bytes(&welcome.unwrap().to_bytes()).unwrap();
+    Group::join(&welcome, key_package).unwrap()
+}
+
+#[test]
+fn test_two_members() {
+    let mut alice = Group::create(b"group");
+    let epoch_zero = alice.export_secret(b"app", 32).unwrap();
+
+    let bob = add(&mut alice, &mut []);
+
+    assert_eq!(alice.epoch(), 1);
+    assert_eq!(bob.leaf_index(), 1);
+    assert_eq!(bob.group_id(), b"group");
+    assert_agree(&[&alice, &bob]);
+    assert_ne!(alice.export_secret(b"app", 32).unwrap(), epoch_zero);
+    assert_ne!(
+        alice.export_secret(b"app", 32).unwrap(),
+        alice.export_secret(b"other", 32).unwrap()
+    );
+}
+
+#[test]
+fn test_growing_group() {
+    let mut alice = Group::create(b"group");
+    let mut members: Vec<Group> = Vec::new();
+
+    // five members, so the tree doubles twice
+    for _ in 0..4 {
+        let joined = add(&mut alice, &mut members);
+        members.push(joined);
+    }
+
+    let mut everyone: Vec<&Group> = members.iter().collect();
+    everyone.push(&alice);
+    assert_agree(&everyone);
+    assert_eq!(alice.members().len(), 5);
+
+    // any member can commit, not just the one who added the others
+    let (first, rest) = members.split_at_mut(1);
+    let (commit, welcome) = first[0].commit(&[]).unwrap();
+    assert!(welcome.is_none());
+
+    alice.process_commit(&transmit(&commit)).unwrap();
+
+    for member in rest.iter_mut() {
+        member.process_commit(&transmit(&commit)).unwrap();
+    }
+
+    let mut everyone: Vec<&Group> = members.iter().collect();
+    everyone.push(&alice);
+    assert_agree(&everyone);
+    assert_eq!(alice.epoch(), 5);
+}
+
+#[test]
+fn test_remove() {
+    let mut alice = Group::create(b"group");
+    let mut members = vec![add(&mut alice, &mut [])];
+    let carol = add(&mut alice, &mut members);
+    members.push(carol);
+
+    let old = alice.export_secret(b"app", 32).unwrap();
+    let (commit, _) = alice.commit(&[Proposal::Remove(2)]).unwrap();
+
+    assert_eq!(
+        members[1].process_commit(&transmit(&commit)),
+        Err(TreeKemError::Removed)
+    );
+    members[0].process_commit(&transmit(&commit)).unwrap();
+
+    assert_agree(&[&alice, &members[0]]);
+    assert_eq!(alice.members().len(), 2);
+    assert_ne!(alice.export_secret(b"app", 32).unwrap(), old);
+
+    // the removed member's leaf is free again for the next member
+    let dave = add(&mut alice, &mut members[..1]);
+    assert_eq!(dave.leaf_index(), 2);
+    assert_agree(&[&alice, &members[0], &dave]);
+}
+
+#[test]
+fn test_update() {
+    let mut alice = Group::create(b"group");
+    let mut bob = add(&mut alice, &mut []);
+    let mut carol = add(&mut alice, std::slice::from_mut(&mut bob));
+
+    let proposal = carol.propose_update();
+    let (commit, _) = alice.commit(&[proposal]).unwrap();
+
+    bob.process_commit(&transmit(&commit)).unwrap();
+    carol.process_commit(&transmit(&commit)).unwrap();
+    assert_agree(&[&alice, &bob, &carol]);
+
+    // carol's new leaf key is the one she uses from now on
+    let (commit, _) = bob.commit(&[]).unwrap();
+    alice.process_commit(&transmit(&commit)).unwrap();
+    carol.process_commit(&transmit(&commit)).unwrap();
+    assert_agree(&[&alice, &bob, &carol]);
+}
+
+#[test]
+fn test_update_for_someone_else() {
+    let mut alice = Group::create(b"group");
+    let mut bob = add(&mut alice, &mut []);
+
+    assert_eq!(
+        alice.commit(&[Proposal::Update(0, KeyPackage::generate().public_key())]),
+        Err(TreeKemError::InvalidProposal)
+    );
+
+    // an update for bob that bob never made
+    let (commit, _) = alice
+        .commit(&[Proposal::Update(1, KeyPackage::generate().public_key())])
+        .unwrap();
+    assert_eq!(
+        bob.process_commit(&commit),
+        Err(TreeKemError::InvalidProposal)
+    );
+}
+
+#[test]
+fn test_invalid_proposals() {
+    let mut alice = Group::create(b"group");
+    let _bob = add(&mut alice, &mut []);
+
+    assert_eq!(
+        alice.commit(&[Proposal::Remove(0)]),
+        Err(TreeKemError::InvalidProposal)
+    );
+    assert_eq!(
+        alice.commit(&[Proposal::Remove(5)]),
+        Err(TreeKemError::InvalidProposal)
+    );
+    assert_eq!(
+        alice.commit(&[Proposal::Remove(1), Proposal::Remove(1)]),
+        Err(TreeKemError::InvalidProposal)
+    );
+    assert_eq!(alice.epoch(), 1);
+}
+
+#[test]
+fn test_rejected_commits_change_nothing() {
+    let mut alice = Group::create(b"group");
+    let mut bob = add(&mut alice, &mut []);
+    let (commit, _) = alice.commit(&[]).unwrap();
+
+    let mut bytes = commit.to_bytes();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    assert_eq!(
+        bob.process_commit(&Commit::from_bytes(&bytes).unwrap()),
+        Err(TreeKemError::InvalidCommit)
+    );
+
+    // a path key that does not match its path secret
+    let mut bytes = commit.to_bytes();
+    bytes[8 + 4 + 2 + 32 + 1] ^= 1;
+    assert_eq!(
+        bob.process_commit(&Commit::from_bytes(&bytes).unwrap()),
+        Err(TreeKemError::InvalidCommit)
+    );
+
+    assert_eq!(bob.epoch(), 1);
+    bob.process_commit(&commit).unwrap();
+    assert_agree(&[&alice, &bob]);
+
+    assert_eq!(bob.process_commit(&commit), Err(TreeKemError::WrongEpoch));
+}
+
+#[test]
+fn test_welcome() {
+    let mut alice = Group::create(b"group");
+    let key_package = KeyPackage::generate();
+    let (_, welcome) = alice
+        .commit(&[Proposal::Add(key_package.public_key())])
+        .unwrap();
+    let welcome = welcome.unwrap();
+
+    assert_eq!(welcome.epoch(), 1);
+    assert_eq!(welcome.group_id(), b"group");
+    assert_eq!(Welcome::from_bytes(&welcome.to_bytes()).unwrap(), welcome);
+    assert_eq!(
+        Group::join(&welcome, KeyPackage::generate()).unwrap_err(),
+        TreeKemError::NotInvited
+    );
+
+    let bytes = welcome.to_bytes();
+    assert_eq!(
+        Welcome::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+        TreeKemError::Malformed
+    );
+    assert_eq!(
+        Commit::from_bytes(&[0u8; 10]).unwrap_err(),
+        TreeKemError::Malformed
+    );
+}
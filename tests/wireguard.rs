@@ -0,0 +1,137 @@
+use hex::decode;
+use raycrypt::ecc::x25519::PrivateKey;
+use raycrypt::protocols::wireguard::*;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn key(byte: u8) -> PrivateKey {
+    PrivateKey::new(&[byte; 32]).unwrap()
+}
+
+// Computed independently with hashlib and the cryptography package
+#[test]
+fn test_handshake_vector() {
+    let initiator_static = key(1);
+    let responder_static = key(2);
+    let psk = [5u8; 32];
+    let timestamp: [u8; 12] = core::array::from_fn(|i| i as u8);
+
+    let (initiator, initiation) = Initiator::with_ephemeral(
+        &initiator_static,
+        &responder_static.public_key(),
+        Some(&psk),
+        0x11223344,
+        &timestamp,
+        key(3),
+    )
+    .unwrap();
+
+    assert_eq!(initiation.to_vec(), decode("01000000443322115dfedd3b6bd47f6fa28ee15d969d5bb0ea53774d488bdaf9df1c6e0124b3ef220a26599fb2188bb723276fc173af67616c817fc32fd04d686d87ec152fac10eed2bda3bb3b6f1eded6977684082284d857fa44b0c8cd9ceeb52e34dd25b557acd8dce5f1b1c5ee8ad20db813c7e8c7a3b8f3f2f65c134ed7c58d135600000000000000000000000000000000").unwrap());
+
+    let responder = Responder::consume_initiation(&responder_static, &initiation).unwrap();
+
+    assert_eq!(responder.initiator_public(), initiator_static.public_key());
+    assert_eq!(responder.timestamp(), timestamp);
+
+    let (responder_keys, response) = responder
+        .respond_with_ephemeral(Some(&psk), 0x55667788, key(4))
+        .unwrap();
+
+    assert_eq!(response.to_vec(), decode("020000008877665544332211ac01b2209e86354fb853237b5de0f4fab13c7fcbf433a61c019369617fecf10bb286a18b3b3008561f3475e319a60dbf929738d570e7a53cfb43222927b62b7e00000000000000000000000000000000").unwrap());
+
+    let initiator_keys = initiator
+        .consume_response(&initiator_static, &response)
+        .unwrap();
+
+    assert_eq!(
+        initiator_keys.sending.to_vec(),
+        decode("a18cc809fd05b6b5f99644cf506f89dce368c2f54a9dbeb9a384f36c274317ef").unwrap()
+    );
+    assert_eq!(
+        initiator_keys.receiving.to_vec(),
+        decode("b1aa1b797d3a45da97552b8084ade493aa767f15af95854bc86557f6dacd851d").unwrap()
+    );
+    assert_eq!(initiator_keys.sending, responder_keys.receiving);
+    assert_eq!(initiator_keys.receiving, responder_keys.sending);
+    assert_eq!(initiator_keys.remote_index, 0x55667788);
+    assert_eq!(responder_keys.remote_index, 0x11223344);
+}
+
+#[test]
+fn test_handshake_rejections() {
+    let initiator_static = key(1);
+    let responder_static = key(2);
+
+    let (initiator, mut initiation) = Initiator::new(
+        &initiator_static,
+        &responder_static.public_key(),
+        Some(&[5u8; 32]),
+        1,
+        &tai64n(UNIX_EPOCH),
+    )
+    .unwrap();
+
+    assert_eq!(
+        Responder::consume_initiation(&key(9), &initiation).unwrap_err(),
+        HandshakeError::InvalidMac
+    );
+
+    let (_, response) = Responder::consume_initiation(&responder_static, &initiation)
+        .unwrap()
+        .respond(None, 2)
+        .unwrap();
+
+    assert_eq!(
+        initiator
+            .consume_response(&initiator_static, &response)
+            .unwrap_err(),
+        HandshakeError::DecryptionFailed
+    );
+
+    initiation[50] ^= 1;
+    let offset = INITIATION_LENGTH - 32;
+    let mac1 = mac1(&responder_static.public_key(), &initiation[..offset]);
+    initiation[offset..offset + 16].copy_from_slice(&mac1);
+
+    assert_eq!(
+        Responder::consume_initiation(&responder_static, &initiation).unwrap_err(),
+        HandshakeError::DecryptionFailed
+    );
+}
+
+#[test]
+fn test_tai64n() {
+    let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+
+    assert_eq!(
+        tai64n(time).to_vec(),
+        decode("400000006553f10a075bcd15").unwrap()
+    );
+    assert!(tai64n(time) < tai64n(time + Duration::from_nanos(1)));
+}
+
+#[test]
+fn test_cookies() {
+    let responder_public = key(2).public_key();
+    let (_, mut initiation) =
+        Initiator::new(&key(1), &responder_public, None, 7, &tai64n(UNIX_EPOCH)).unwrap();
+
+    assert!(verify_mac1(&responder_public, &initiation));
+
+    let cookie = cookie(&[8u8; 32], b"192.0.2.1:51820");
+    let mac1 = &initiation[INITIATION_LENGTH - 32..INITIATION_LENGTH - 16];
+    let reply = cookie_reply(&responder_public, 7, &[9u8; 24], &cookie, mac1);
+
+    assert_eq!(
+        open_cookie_reply(&responder_public, &reply, mac1).unwrap(),
+        (7, cookie)
+    );
+    assert_eq!(
+        open_cookie_reply(&responder_public, &reply, &[0u8; 16]).unwrap_err(),
+        HandshakeError::DecryptionFailed
+    );
+
+    assert!(!verify_mac2(&initiation, &cookie));
+    add_mac2(&mut initiation, &cookie);
+    assert!(verify_mac2(&initiation, &cookie));
+    assert!(verify_mac1(&responder_public, &initiation));
+}
@@ -0,0 +1,111 @@
+use hex::decode;
+use raycrypt::x509::{Certificate, CertificateError, SignatureAlgorithm, SubjectPublicKey};
+use serde_json::{from_str, Value};
+use std::fs;
+
+fn vectors() -> Value {
+    let raw = fs::read_to_string("tests/vectors/x509.json").unwrap();
+    from_str(&raw).unwrap()
+}
+
+fn certificate(data: &Value, name: &str) -> Certificate {
+    Certificate::from_pem(data[name].as_str().unwrap()).unwrap()
+}
+
+#[test]
+fn test_verify_leaf() {
+    let data = vectors();
+    let ca = certificate(&data, "ca");
+    let leaf = certificate(&data, "leaf");
+
+    assert!(ca.verify_signed_by(&ca).is_ok());
+    assert!(leaf.verify_signed_by(&ca).is_ok());
+    assert_eq!(
+        leaf.verify_signed_by(&leaf),
+        Err(CertificateError::InvalidSignature)
+    );
+
+    match leaf.public_key() {
+        SubjectPublicKey::Ed25519(key) => assert_eq!(
+            key.to_bytes().to_vec(),
+            decode(data["leafPublicKey"].as_str().unwrap()).unwrap()
+        ),
+        _ => panic!("expected an Ed25519 key"),
+    }
+}
+
+#[test]
+fn test_verify_artifact() {
+    let data = vectors();
+    let leaf = certificate(&data, "leaf");
+
+    let artifact = decode(data["artifact"].as_str().unwrap()).unwrap();
+    let signature = decode(data["artifactSignature"].as_str().unwrap()).unwrap();
+
+    let key = leaf.public_key();
+
+    assert!(key
+        .verify(SignatureAlgorithm::Ed25519, &artifact, &signature)
+        .is_ok());
+    assert!(key
+        .verify(SignatureAlgorithm::Ed25519, b"tampered", &signature)
+        .is_err());
+}
+
+#[test]
+fn test_tampered_certificate() {
+    let data = vectors();
+    let ca = certificate(&data, "ca");
+
+    let mut der = decode(data["leafDer"].as_str().unwrap()).unwrap();
+    let index = der.len() / 2;
+    der[index] ^= 1;
+
+    match Certificate::from_der(&der) {
+        Ok(leaf) => assert!(leaf.verify_signed_by(&ca).is_err()),
+        Err(e) => assert_eq!(e, CertificateError::Malformed),
+    }
+}
+
+#[test]
+fn test_ecdsa_key_extraction() {
+    let data = vectors();
+    let ca = certificate(&data, "ca");
+    let leaf = certificate(&data, "ecdsaLeaf");
+    let self_signed = certificate(&data, "ecdsaSelfSigned");
+
+    assert!(leaf.verify_signed_by(&ca).is_ok());
+
+    match leaf.public_key() {
+        SubjectPublicKey::EcdsaP256(key) => assert_eq!(
+            key.to_vec(),
+            decode(data["ecdsaPublicKey"].as_str().unwrap()).unwrap()
+        ),
+        _ => panic!("expected a P-256 key"),
+    }
+
+    assert_eq!(
+        self_signed.signature_algorithm(),
+        SignatureAlgorithm::EcdsaWithSha256
+    );
+    assert_eq!(
+        self_signed.verify_signed_by(&self_signed),
+        Err(CertificateError::UnsupportedAlgorithm)
+    );
+}
+
+#[test]
+fn test_wycheproof_public_keys() {
+    let raw = fs::read_to_string("tests/vectors/ed25519.json").unwrap();
+    let data: Value = from_str(&raw).unwrap();
+
+    for group in data["testGroups"].as_array().unwrap() {
+        let der = decode(group["publicKeyDer"].as_str().unwrap()).unwrap();
+        let pk = decode(group["publicKey"]["pk"].as_str().unwrap()).unwrap();
+
+        match SubjectPublicKey::from_der(&der).unwrap() {
+            SubjectPublicKey::Ed25519(key) => assert_eq!(key.to_bytes().to_vec(), pk),
+            _ => panic!("expected an Ed25519 key"),
+        }
+    }
+}
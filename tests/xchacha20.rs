@@ -0,0 +1,50 @@
+use hex::decode;
+use raycrypt::ciphers::xchacha::{xchacha20_xor, xchacha20_xor_ic, XChaCha20};
+
+fn inputs() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let key: Vec<u8> = (0..32).collect();
+    let nonce: Vec<u8> = (0x40..0x58).collect();
+    let msg: Vec<u8> = (0..200).collect();
+
+    (key, nonce, msg)
+}
+
+#[test]
+fn test_xor_ic() {
+    let (key, nonce, msg) = inputs();
+
+    let expected = decode("85ef3315377825c12a1c3e575e2b43702c7f98804d2559cb9c4a021f9fb108791f9626ad6c44bec266913bbe17ddf25997569be1a501ecdf95501f2293ea88e79478473394a53f51c7bdcdf5e3d12bddc2ebff97470c05cd3268a71e55597dcf7698600d31f7e27797db9e2766b7b49eeb1a0f9b1bf0c2ebce85728f6fcf322bdaaa7af19036c34f0d8a3a7450f41260665694b8bca8f2e0c83a9d0f59f8adba86d4156a1c40cd117508c831ebba67242f42a16d9d7f3557b406eda99e7ac5201f9085cd151b1077").unwrap();
+
+    let ct = xchacha20_xor_ic(&msg, &nonce, 0, &key);
+    assert_eq!(ct, expected);
+    assert_eq!(xchacha20_xor(&msg, &nonce, &key), expected);
+    assert_eq!(xchacha20_xor_ic(&ct, &nonce, 0, &key), msg);
+
+    // seeking by initial counter matches the tail of the full stream
+    assert_eq!(
+        xchacha20_xor_ic(&msg[128..], &nonce, 2, &key),
+        expected[128..]
+    );
+}
+
+#[test]
+fn test_counter_carry() {
+    let (key, nonce, msg) = inputs();
+
+    let expected = decode("bb44df175cebd27693b269327652f9ad5b2d5e70e83ab473f1f2a46dfd5a7d3f22276edf0e872c103e388ad562675b5f44165fa66954b9642d14deab1f6668153948198a4d7b93e63456b0b5bdc088732d3673529f65fa20ebeb6b586cac6deb3c95e2692381b91c09d17fc0cba916588851ba7fd1a7d4cd9206073e3e5136628d82e3ac864756c3112ed7e0a738f0d66cd20db1b390694df2929d8a3de4e2a2f9689bb1ed1fd3c143267fe30df4d1f86ba4c789ba47bc59a69133e000cd41a90359822acf1d6e8e").unwrap();
+
+    assert_eq!(
+        xchacha20_xor_ic(&msg, &nonce, u32::MAX as u64, &key),
+        expected
+    );
+
+    let cipher = XChaCha20::new(&key);
+    assert_eq!(
+        cipher.keystream(&nonce, 1 << 32).to_vec(),
+        cipher.xor_ic(&[0u8; 64], &nonce, 1 << 32)
+    );
+
+    let expected = decode("f15720b219365235bd755315ea17a0e7caa826918e8b7eb9fab61c560027a1db1114b30f4ee45543e27f192aba0dd12b58fd9d329bc7889446339afa91cbb8abc5af7355773865816a5c7e171e6b03306c3fd8c00d65198bdc0a425fdff148395fd666ed").unwrap();
+
+    assert_eq!(cipher.xor_ic(&msg[..100], &nonce, u64::MAX), expected);
+}
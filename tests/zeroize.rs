@@ -0,0 +1,48 @@
+use raycrypt::aeads::{AesGcm, ChaCha20Poly1305, ConvergentCipher, XChaCha20Poly1305};
+use raycrypt::ciphers::aes::Aes;
+use raycrypt::ciphers::chacha::{ChaCha20, HChaCha20};
+use raycrypt::ciphers::salsa::{Salsa20, XSalsa20};
+use raycrypt::ecc::ed25519::SigningKey;
+use raycrypt::ecc::x25519::PrivateKey;
+use raycrypt::hashes::blake2b::Blake2b;
+use raycrypt::hashes::blake3::Blake3;
+use raycrypt::hashes::sha256::Sha256;
+use raycrypt::hashes::sha512::Sha512;
+use raycrypt::macs::ghash::GHash;
+use raycrypt::macs::poly1305::Poly1305;
+use raycrypt::macs::polyval::Polyval;
+use raycrypt::nacl::SecretBox;
+use raycrypt::protocols::quic::{InitialSecrets, PacketKeys};
+use zeroize::ZeroizeOnDrop;
+
+fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+
+fn assert_drop<T>() {
+    assert!(std::mem::needs_drop::<T>());
+}
+
+#[test]
+fn test_secret_types_are_wiped() {
+    assert_zeroize_on_drop::<ChaCha20>();
+    assert_zeroize_on_drop::<HChaCha20>();
+    assert_zeroize_on_drop::<Salsa20>();
+    assert_zeroize_on_drop::<XSalsa20>();
+    assert_zeroize_on_drop::<Poly1305>();
+    assert_zeroize_on_drop::<Aes>();
+    assert_zeroize_on_drop::<GHash>();
+    assert_zeroize_on_drop::<Polyval>();
+    assert_zeroize_on_drop::<PrivateKey>();
+    assert_zeroize_on_drop::<SigningKey>();
+    assert_zeroize_on_drop::<Sha256>();
+    assert_zeroize_on_drop::<Sha512>();
+    assert_zeroize_on_drop::<Blake2b>();
+    assert_zeroize_on_drop::<Blake3>();
+    assert_zeroize_on_drop::<InitialSecrets>();
+    assert_zeroize_on_drop::<PacketKeys>();
+    assert_zeroize_on_drop::<ConvergentCipher>();
+    assert_zeroize_on_drop::<SecretBox>();
+
+    assert_drop::<ChaCha20Poly1305>();
+    assert_drop::<XChaCha20Poly1305>();
+    assert_drop::<AesGcm>();
+}